@@ -1,4 +1,6 @@
 import re
+import ast
+import builtins
 import readline  # Enables arrow keys for history and cursor movement in REPL
 import numpy as np
 import inspect
@@ -6,13 +8,65 @@ import math
 from pytexit import py2tex
 import os, sys
 import copy
+import itertools
+import contextlib
+import gc
+import json
+import time
 from beautifultable import BeautifulTable
 import importlib
+import importlib.util
 from functools import partial
 
 from . import bcolors
 from . import errors as err
 from . import console
+from . import completion
+from . import stats
+from . import refactor
+from . import lint
+from . import check
+from . import crash
+from . import memory
+from . import config
+from . import limits
+from . import infinity
+from . import syntax
+from . import diagnostics
+from . import features
+from . import signatures
+from . import scaffold
+from . import workspace
+from . import explanations
+from . import traces
+from . import corners
+from . import ordering
+from . import messages
+from . import watch
+from . import search
+from . import deps
+from . import pack
+from . import stamp
+from . import blame
+from . import symbolic
+from . import sensitivity
+from . import fixtures
+from . import coverage
+from . import externs
+from . import mutate
+from . import tune
+from . import sandbox
+from . import deterministic
+from . import positions
+from . import structure
+from . import tokens
+from . import grammar
+from . import incremental
+from . import fmt
+from . import montecarlo
+from . import consistency
+from . import sweep
+from . import builtin
 from .function_cache import FunctionCache
 from . import units as un
 from .errors import OneilError
@@ -26,10 +80,15 @@ def isfloat(num):
     except ValueError:
         return False
 
-FUNCTIONS = {"sin": "par_sin", "cos": "par_cos", "tan": "par_tan", "asin": "par_asin", "acos": "par_acos", "atan": "par_atan", "sinh": "par_arcsinh", "cosh": "par_cosh", "tanh": "par_tanh", "min": "par_min", "max": "par_max", "sqrt": "par_sqrt", "abs": "par_abs", "mnmx": "par_minmax", "mxmn": "par_maxmin", "log": "par_log", "log2": "par_log2", "log10": "par_log10", "ln": "par_log", "floor": "par_floor", "ceiling": "par_ceiling", "extent": "par_extent", "range": "par_range", "strip": "par_strip", "mid": "par_mid", "sign": "par_sign"}
+FUNCTIONS = {"sin": "par_sin", "cos": "par_cos", "tan": "par_tan", "asin": "par_asin", "acos": "par_acos", "atan": "par_atan", "sinh": "par_arcsinh", "cosh": "par_cosh", "tanh": "par_tanh", "min": "par_min", "max": "par_max", "sqrt": "par_sqrt", "abs": "par_abs", "mnmx": "par_minmax", "mxmn": "par_maxmin", "log": "par_log", "log2": "par_log2", "log10": "par_log10", "ln": "par_log", "floor": "par_floor", "ceiling": "par_ceiling", "extent": "par_extent", "range": "par_range", "strip": "par_strip", "mid": "par_mid", "sign": "par_sign", "linear": "par_linear", "decibels": "par_decibels", "lerp": "par_lerp", "map_range": "par_map_range"}
 
 MATH_CONSTANTS = {"pi": np.pi, "e": np.exp(1), "inf": np.inf}
 
+# The functions and constants of Oneil itself, without those that plugins (see
+# oneil.builtin) and the config file add
+BUILTIN_FUNCTIONS = tuple(FUNCTIONS)
+BUILTIN_CONSTANTS = tuple(MATH_CONSTANTS)
+
 EQUATION_OPERATORS = ["+", "-", "*", "/", "//", "%", "(", ")", "=", "<", ">", "!"]
 
 OPERATOR_OVERRIDES = {"--": "|minus|", "^": "**"}
@@ -59,6 +118,293 @@ def minus(x, y):
         return x - y
     
 
+class IncludedLine(int):
+    """
+    The line number of a line spliced in from an included fragment file. It works as the
+    line number in the fragment, but prints with the fragment's name so that errors
+    point to the right file.
+    """
+    def __new__(cls, line_no, fragment):
+        included = super().__new__(cls, line_no)
+        included.fragment = fragment
+        return included
+
+    def __str__(self):
+        return f"{int(self)} of {self.fragment}"
+
+    def __format__(self, spec):
+        return str(self) if not spec else format(int(self), spec)
+
+# Configuration flags (`--cfg <name>`) that enable `#[cfg(<name>)]` declarations.
+active_cfg = set()
+
+# Whether to evaluate nominal values only (`--nominal`): every range of numbers written
+# to a parameter, from the model or from a calculation, becomes its midpoint, so values
+# are single numbers and no ranges are propagated.
+nominal = False
+
+# The relative tolerance of `==` and `!=` between numbers while a test with a
+# `@tolerance` annotation runs, or None for exact comparisons.
+tolerance = None
+
+# Text to read instead of the contents of model files, by absolute path (see
+# read_model_lines()), for evaluating edited models without writing them to disk.
+source_overrides = {}
+
+def _split_cfg_arguments(arguments):
+    depth, current, result = 0, "", []
+    for character in arguments:
+        if character == "," and depth == 0:
+            result.append(current)
+            current = ""
+            continue
+        depth += {"(": 1, ")": -1}.get(character, 0)
+        current += character
+    return result + [current] if current.strip() else result
+
+def cfg_enabled(predicate):
+    """
+    Evaluate a cfg predicate: a flag name, or `not(...)`, `any(...)`, or `all(...)` of
+    other predicates.
+    """
+    predicate = predicate.strip()
+    match = re.search(r"^(not|any|all)\s*\((.*)\)$", predicate)
+    if match:
+        arguments = _split_cfg_arguments(match.group(2))
+        if match.group(1) == "not":
+            if len(arguments) != 1:
+                raise ValueError("not() takes exactly one predicate.")
+            return not cfg_enabled(arguments[0])
+        values = [cfg_enabled(argument) for argument in arguments]
+        return any(values) if match.group(1) == "any" else all(values)
+    if re.search(r"^\w+$", predicate):
+        return predicate in active_cfg
+    raise ValueError(f"Invalid cfg predicate \"{predicate}\".")
+
+# Annotations (`@<name>(<arguments>)`) that can be attached to declarations or models.
+ANNOTATIONS = ["deprecated", "tags", "precision", "tolerance", "mirror", "distribution", "iterate", "unit_unchecked"]
+
+def _annotation_argument(node):
+    if isinstance(node, ast.Constant):
+        return node.value
+    if isinstance(node, ast.Name):
+        return node.id
+    raise ValueError("Annotation arguments must be strings, numbers, or names.")
+
+def parse_annotation(line):
+    """
+    Parse an annotation line into its name and a tuple of arguments. Annotations attach
+    to the next parameter, test, or design value, or to the model if they are followed
+    by a blank line.
+    """
+    match = re.search(r"^@(\w+)\s*(\((.*)\))?\s*$", line)
+    if not match:
+        raise ValueError("Annotations must be of the form \"@<name>(<arguments>)\".")
+    name = match.group(1)
+    if name not in ANNOTATIONS:
+        raise ValueError(f"Unknown annotation \"@{name}\". Annotations are: {', '.join('@' + a for a in ANNOTATIONS)}.")
+
+    if name in ("precision", "tolerance"):
+        # A percentage isn't a Python expression, so the budget is parsed by itself.
+        budget = re.fullmatch(r"\s*(\d+\.?\d*(?:[eE][-+]?\d+)?)\s*(%?)\s*", match.group(3) or "")
+        if not budget or float(budget.group(1)) <= 0:
+            what = "a relative uncertainty" if name == "precision" else "a relative tolerance"
+            raise ValueError(f"\"@{name}\" takes {what} as a percentage or a fraction (e.g. \"@{name}(1%)\" or \"@{name}(0.01)\").")
+        return name, (float(budget.group(1)) / (100 if budget.group(2) else 1),)
+
+    if name == "tags":
+        # Tags can have hyphens, so they aren't Python names and are split by themselves.
+        tags = [tag.strip() for tag in (match.group(3) or "").split(",")]
+        tags = [tag[1:-1] if len(tag) > 1 and tag[0] == tag[-1] and tag[0] in "\"'" else tag for tag in tags]
+        if not all(re.fullmatch(r"[\w-]+", tag) for tag in tags):
+            raise ValueError("\"@tags\" takes one or more tags (e.g. \"@tags(mass, structures)\").")
+        return name, tuple(tags)
+
+    if name == "mirror":
+        # The Python file and constant aren't a Python expression either.
+        constant = re.fullmatch(r"\s*([\w./\\-]+\.py)::(\w+)\s*", match.group(3) or "")
+        if not constant:
+            raise ValueError("\"@mirror\" takes a Python file and a constant in it (e.g. \"@mirror(thermal.py::K_COPPER)\").")
+        return name, (constant.group(1), constant.group(2))
+
+    arguments = ()
+    if match.group(3) and match.group(3).strip():
+        try:
+            call = ast.parse(f"f({match.group(3)})", mode="eval").body
+        except Exception:
+            raise ValueError(f"Invalid arguments to \"@{name}\".")
+        if call.keywords:
+            raise ValueError(f"\"@{name}\" doesn't take keyword arguments.")
+        arguments = tuple(_annotation_argument(a) for a in call.args)
+
+    if name == "deprecated" and (len(arguments) > 1 or not all(isinstance(a, str) for a in arguments)):
+        raise ValueError("\"@deprecated\" takes at most one message string.")
+    if name == "distribution":
+        montecarlo.validate(arguments)
+    if name == "iterate" and not (len(arguments) == 1 and isinstance(arguments[0], (int, float)) and not isinstance(arguments[0], bool)):
+        raise ValueError("\"@iterate\" takes the initial guess of the parameter, in its units (e.g. \"@iterate(1000)\").")
+    if name == "unit_unchecked" and not (len(arguments) == 1 and isinstance(arguments[0], str) and arguments[0].strip()):
+        raise ValueError("\"@unit_unchecked\" takes the reason the units aren't checked, as a string.")
+    return name, arguments
+
+def annotated_tags(annotations):
+    """
+    The tags from `@tags` annotations, in the order they were given.
+    """
+    return list(annotations.get("tags", ()))
+
+def deprecation_message(annotations):
+    """
+    The message of a `@deprecated` annotation, or None if the item isn't deprecated.
+    """
+    if "deprecated" not in annotations:
+        return None
+    return annotations["deprecated"][0] if annotations["deprecated"] else ""
+
+def precision_budget(annotations):
+    """
+    The relative uncertainty allowed by a `@precision` annotation (0.01 for 1%), or None
+    if there's no budget.
+    """
+    return annotations["precision"][0] if "precision" in annotations else None
+
+def test_tolerance(annotations):
+    """
+    The relative tolerance of a test's equality comparisons from a `@tolerance`
+    annotation (0.01 for 1%), or None if they're exact.
+    """
+    return annotations["tolerance"][0] if "tolerance" in annotations else None
+
+def mirrored_constant(annotations):
+    """
+    The Python file and the name of the constant a parameter mirrors from a `@mirror`
+    annotation, or None if it doesn't mirror one.
+    """
+    return annotations["mirror"] if "mirror" in annotations else None
+
+def unit_check_justification(annotations):
+    """
+    Why a parameter's units aren't checked, from a `@unit_unchecked` annotation, or None
+    if they are.
+    """
+    return annotations["unit_unchecked"][0] if "unit_unchecked" in annotations else None
+
+def read_python_constant(directory, file, name):
+    """
+    The value of a constant in a Python file (relative to the directory of the model),
+    from the module the model imported if it's loaded, or else from the file. Raises
+    ValueError if the file or the constant can't be found.
+    """
+    path = os.path.abspath(os.path.join(directory, file))
+    if sandbox.outside_root(path):
+        raise ValueError(sandbox.refusal(file))
+    if not os.path.exists(path):
+        raise ValueError(f"{file} doesn't exist.")
+    module = next((m for m in list(sys.modules.values()) if getattr(m, "__file__", None) and os.path.abspath(m.__file__) == path), None)
+    if module is None and sandbox.no_python:
+        raise ValueError(f"{file} would have to be imported, which --no-python doesn't allow.")
+    if module is None:
+        try:
+            spec = importlib.util.spec_from_file_location(os.path.splitext(os.path.basename(path))[0], path)
+            module = importlib.util.module_from_spec(spec)
+            spec.loader.exec_module(module)
+        except Exception as e:
+            raise ValueError(f"{file} can't be imported ({type(e).__name__}: {e}).")
+    if not hasattr(module, name):
+        raise ValueError(f"{file} doesn't define {name}.")
+    return getattr(module, name)
+
+def _equal(a, b):
+    # Equality of two values of parameters, within the tolerance of the running test
+    if tolerance is not None and all(isinstance(v, (int, float)) and not isinstance(v, bool) for v in (a, b)):
+        return math.isclose(a, b, rel_tol=tolerance)
+    return a == b
+
+def relative_uncertainty(parameter):
+    """
+    The half-width of a parameter's range relative to its midpoint (0.05 for 100±5), or
+    None if its value isn't a number.
+    """
+    values = (parameter.min, parameter.max)
+    if not all(isinstance(value, (int, float)) and not isinstance(value, (bool, np.bool_)) for value in values):
+        return None
+    width, middle = (values[1] - values[0]) / 2, abs(values[1] + values[0]) / 2
+    if width == 0:
+        return 0.0
+    return width / middle if middle else np.inf
+
+def read_model_lines(file_name, trail=[]):
+    """
+    Read the lines of a model with their line numbers, splicing in the lines of fragment
+    files from `include "<fragment>"` declarations and dropping declarations disabled by
+    `#[cfg(...)]` guards. Fragment paths are relative to the file that includes them.
+    """
+    path = os.path.abspath(file_name)
+    trail = trail + [path]
+    fragment = file_name if len(trail) > 1 else None
+
+    if path in source_overrides:
+        lines = source_overrides[path].splitlines(keepends=True)
+    else:
+        with open(file_name, 'r') as f:
+            lines = f.readlines()
+
+    result = []
+    disabled = False
+    skipping = None
+    for i, line in enumerate(lines):
+        line_no = IncludedLine(i + 1, fragment) if fragment else i + 1
+        statement = bool(line.strip()) and line[0] not in (" ", "\t")
+
+        # Skip a disabled declaration with its notes and piecewise lines, or a disabled
+        # section up to the next section.
+        if skipping == "statement" and not statement:
+            if not line.strip():
+                result.append((line, line_no))
+            continue
+        if skipping == "section" and not line.startswith("section "):
+            continue
+        skipping = None
+
+        guard = re.search(r"^#\[cfg\((.*)\)\]\s*$", line)
+        if guard:
+            try:
+                disabled = disabled or not cfg_enabled(guard.group(1))
+            except ValueError as e:
+                raise SyntaxError(file_name, i + 1, line, f"Guards must be of the form \"#[cfg(<predicate>)]\". {e}")
+            result.append((line, line_no))
+            continue
+        if disabled and line.startswith("@"):
+            continue
+        if disabled and statement and line.strip()[0] != "#":
+            disabled = False
+            skipping = "section" if line.startswith("section ") else "statement"
+            continue
+
+        if line[:8] != 'include ':
+            result.append((line, line_no))
+            continue
+
+        match = re.search(r"^include\s+\"([^\"]+)\"\s*$", line)
+        if not match:
+            raise SyntaxError(file_name, i + 1, line, "Includes must be of the form \"include \"<fragment file>\"\"")
+
+        included = os.path.join(os.path.dirname(file_name), match.group(1))
+        if sandbox.outside_root(included):
+            raise ModelLoadingError(file_name, i + 1, sandbox.refusal(match.group(1)))
+        if not os.path.exists(included):
+            raise ModelLoadingError(file_name, i + 1, f"Included file \"{match.group(1)}\" does not exist.")
+        if os.path.abspath(included) in trail:
+            cycle = [os.path.relpath(p) for p in trail[trail.index(os.path.abspath(included)):]] + [os.path.relpath(included)]
+            raise ModelLoadingError(file_name, i + 1, "Include cycle: " + " -> ".join(cycle))
+
+        fragment_lines = read_model_lines(included, trail)
+        if fragment_lines and not fragment_lines[-1][0].endswith("\n"):
+            fragment_lines[-1] = (fragment_lines[-1][0] + "\n", fragment_lines[-1][1])
+        result.extend(fragment_lines)
+
+    return result
+
 def parse_file(file_name):
     file_path = os.path.abspath(file_name)
     file_dir = os.path.dirname(file_path) or os.getcwd()
@@ -71,13 +417,62 @@ def parse_file(file_name):
     design_overrides = {}
     last_line_blank = False
     section = ""
-
-    with open(file_name, 'r') as f:
-        final_line = 0
-        for i, line in enumerate(f.readlines()):
-            final_line = i
+    annotations = {}
+    model_annotations = {}
+    annotation_line = None
+
+    doc_tests = []
+    fence = None
+    # Experimental features enabled by `#feature(...)` pragmas (see oneil.features)
+    model_features = set()
+
+    final_line = 0
+    # SyntaxErrors in declarations the parser recovered from (see oneil.syntax)
+    errors = []
+    broken = False
+    for i, (line, line_no) in enumerate(read_model_lines(file_name)):
+        final_line = i
+        if broken:
+            # Skip the rest of a declaration with a SyntaxError (its notes and pieces).
+            if line == '\n' or line[0] in ('\t', ' '):
+                continue
+            broken = False
+        try:
+            if fence and line != '\n' and line[0] not in ('\t', ' '):
+                raise SyntaxError(file_name, fence[0], fence[1], "Unclosed ```oneil-test block. End it with ``` in the same notes.")
+            # Notes can have any LaTeX, but expressions in declarations and piecewise lines
+            # have to be small enough to parse.
+            if line.strip() and (line[0] not in ('\t', ' ', '#', '@') or line.strip()[0] == '{'):
+                check_expression_size(line, file_name, line_no)
+                try:
+                    line = features.operators(line, model_features)
+                except ValueError as e:
+                    raise SyntaxError(file_name, line_no, line, str(e))
             if line == '\n':
                 last_line_blank = True
+                # Annotations followed by a blank line apply to the model.
+                model_annotations.update(annotations)
+                annotations = {}
+                continue
+            elif line[0] == '@':
+                try:
+                    name, arguments = parse_annotation(line)
+                except ValueError as e:
+                    raise SyntaxError(file_name, line_no, line, str(e))
+                # Tags from several `@tags` lines add up.
+                annotations[name] = annotations.get("tags", ()) + arguments if name == "tags" else arguments
+                annotation_line = (line_no, line)
+                last_line_blank = False
+                continue
+            elif annotations and (line[0] in ('\t', ' ') or line.split()[0] in ('use', 'from', 'import', 'section') or re.search(refactor.UNIT_PATTERN, line)):
+                raise SyntaxError(file_name, annotation_line[0], annotation_line[1], "Annotations must be followed by a parameter, test, or design value, or by a blank line to apply to the model.")
+            elif features.is_pragma(line):
+                if parameters or tests or design_overrides or submodels or imports or note or section or model_annotations or isinstance(line_no, IncludedLine):
+                    raise SyntaxError(file_name, line_no, line, "Feature pragmas must come before the model's declarations, in the model's own file.")
+                try:
+                    model_features.update(features.pragma(line))
+                except ValueError as e:
+                    raise SyntaxError(file_name, line_no, line, str(e))
                 continue
             elif '#' in line and line.strip()[0] == '#':
                 last_line_blank = False
@@ -86,31 +481,51 @@ def parse_file(file_name):
                 if last_line_blank: line = "\n\n" + line
                 if line.strip() and line.strip()[0] == '{':
                     arguments = []
-                    parameter, arguments = parse_piecewise(line, parameters[-1].units, parameters[-1].id, imports, file_name.replace(".on", ""), i+1, unit_fx, pointer=parameters[-1].pointer)
+                    parameter, arguments = parse_piecewise(line, parameters[-1].units, parameters[-1].id, imports, file_name.replace(".on", ""), line_no, unit_fx, pointer=parameters[-1].pointer)
                     parameters[-1].add_piece(parameter, arguments)
+                    parameters[-1].piece_lines.append(line.strip())
+                    parameters[-1].piece_line_nos.append(line_no)
                 else:
+                    # Tests in ```oneil-test blocks of notes run like the model's tests.
+                    text = line.strip()
+                    if fence:
+                        if text == "```":
+                            fence = None
+                        elif text:
+                            check_test_syntax(text, file_name, line_no)
+                            doc_tests.append(Test(text, line_no, file_name.replace(".on", ""), section=section))
+                            if prev_line == 'param':
+                                doc_tests[-1].doc = parameters[-1].id
+                            elif prev_line == 'test':
+                                doc_tests[-1].doc = tests[-1].label()
+                            elif prev_line == 'design':
+                                doc_tests[-1].doc = list(design_overrides.keys())[-1]
+                            else:
+                                doc_tests[-1].doc = file_name.replace(".on", "")
+                    elif text.startswith("```oneil-test"):
+                        fence = (line_no, line)
                     if prev_line == 'param':
                         parameters[-1].notes.append(line.replace("\t", "", 1).replace(" "*4, "", 1))
-                        parameters[-1].note_lines.append(i+1)
+                        parameters[-1].note_lines.append(line_no)
                     elif prev_line == 'test':
                         tests[-1].notes.append(line.replace("\t", "", 1).replace(" "*4, "", 1))
-                        tests[-1].note_line_nos.append(i+1)
+                        tests[-1].note_line_nos.append(line_no)
                     elif prev_line == 'design':
                         last_key = list(design_overrides.keys())[-1]
                         design_overrides[last_key].notes.append(line.replace("\t", "", 1).replace(" "*4, "", 1))
-                        design_overrides[last_key].note_lines.append(i+1)
+                        design_overrides[last_key].note_lines.append(line_no)
                     elif prev_line == '':
                         note += line.strip()
                     else:
-                        raise ValueError("Invalid prev line type: " + line)
+                        raise SyntaxError(file_name, line_no, line, "Notes must follow the model, a parameter, a test, or a design value.")
                 last_line_blank = False
 
             elif line[:4] == 'use ':
                 try:
-                    assert(re.search(r"^use\s+\w+(\(.+=.+\))?\s+as\s+\w+\s*$", line))
+                    assert(re.search(r"^use\s+\w+\s*(\(.+=.+\))?\s+as\s+\w+\s*$", line))
                 except:
-                    raise SyntaxError(file_name, i+1, line, "Use includes must be of the form \"use <model> as <symbol>\"")
-                
+                    raise SyntaxError(file_name, line_no, line, "Use includes must be of the form \"use <model> as <symbol>\"")
+            
                 last_line_blank = False
                 include = line.replace("use", "")
                 model = include.split('as')[0].strip()
@@ -121,19 +536,22 @@ def parse_file(file_name):
                 else:
                     test_inputs = {}
 
+                if sandbox.outside_root(model + ".on"):
+                    raise ModelLoadingError(file_name, line_no, sandbox.refusal(model + ".on"))
                 if not os.path.exists(model + ".on"):
-                    raise ModelLoadingError(file_name, i + 1, f"File \"{model}.on\" does not exist.")
+                    raise ModelLoadingError(file_name, line_no, f"File \"{model}.on\" does not exist.")
                 symbol = include.split('as')[1].strip()
+                syntax.check_id(symbol, file_name, line_no, line)
 
                 if symbol in submodels.keys():
-                    raise ModelLoadingError(file_name, i + 1, f"Submodel symbol \"{symbol}\" has duplicate definitions.")
+                    raise ModelLoadingError(file_name, line_no, f"Submodel symbol \"{symbol}\" has duplicate definitions.")
 
-                submodels[symbol] = {'model': Model(model + ".on"), 'inputs': test_inputs, 'path': [model], 'line_no': i+1, 'line': line}
+                submodels[symbol] = {'model': use_submodel(model + ".on", (file_name, line_no, line)), 'inputs': test_inputs, 'path': [model], 'line_no': line_no, 'line': line}
             elif line[:5] == 'from ':
                 try:
-                    assert(re.search(r"^from\s+\w+(\.\w+)*\s+use\s+\w+(\(.+=.+\))?\s+as\s+\w+\s*$", line))
+                    assert(re.search(r"^from\s+\w+(\.\w+)*\s+use\s+\w+\s*(\(.+=.+\))?\s+as\s+\w+\s*$", line))
                 except:
-                    raise SyntaxError(file_name, i+1, line, "From includes must be of the form \"from <source> use <model> as <symbol>\"")
+                    raise SyntaxError(file_name, line_no, line, "From includes must be of the form \"from <source> use <model> as <symbol>\"")
 
                 last_line_blank = False
                 include = line.replace("from", "")
@@ -146,26 +564,32 @@ def parse_file(file_name):
                 else:
                     test_inputs = {}
 
+                if sandbox.outside_root(model + ".on"):
+                    raise ModelLoadingError(file_name, line_no, sandbox.refusal(model + ".on"))
                 if not os.path.exists(model + ".on"):
-                    raise ModelLoadingError(file_name, i + 1, f"File \"{model}.on\" does not exist.")
+                    raise ModelLoadingError(file_name, line_no, f"File \"{model}.on\" does not exist.")
 
                 path = source.split('.') + [model] if '.' in source else [source, model]
                 symbol = include.split('use')[1].split("as")[1].strip()
+                syntax.check_id(symbol, file_name, line_no, line)
 
                 if symbol in submodels.keys():
-                    raise ModelLoadingError(file_name, i + 1, f"Submodel symbol \"{symbol}\" has duplicate definitions.")
+                    raise ModelLoadingError(file_name, line_no, f"Submodel symbol \"{symbol}\" has duplicate definitions.")
 
-                submodels[symbol] = {'path': path, 'inputs': test_inputs, 'line_no': i+1, 'line': line}
+                submodels[symbol] = {'path': path, 'inputs': test_inputs, 'line_no': line_no, 'line': line}
             elif line[:7] == 'import ':
                 try:
                     assert(re.search(r"^import\s+\w+\s*$", line))
                 except:
-                    raise SyntaxError(file_name, i+1, line, "Python imports must be of the form \"import <module>\"")
-                
+                    raise SyntaxError(file_name, line_no, line, "Python imports must be of the form \"import <module>\"")
+            
                 last_line_blank = False
+                module = line.replace("import", "").strip()
+                if sandbox.no_python:
+                    imports.append(sandbox.RefusedImport(module, file_name, line_no))
+                    continue
                 if file_dir not in sys.path:
                     sys.path.append(file_dir)
-                module = line.replace("import", "").strip()
 
                 try:
                     # Reload module if already loaded to pick up changes
@@ -173,57 +597,108 @@ def parse_file(file_name):
                         imported_module = importlib.reload(sys.modules[module])
                     else:
                         imported_module = importlib.import_module(module)
-                    
+                
                     imports.append(imported_module)
-                    
+                
                     # Register with function cache and check if module changed
                     _function_cache.register_import(imported_module, root_dir=file_dir)
                 except Exception as e:
-                    raise ImportError(file_name, i+1, line, module + ".py", e)
+                    raise ImportError(file_name, line_no, line, module + ".py", e)
 
+            elif re.search(refactor.UNIT_PATTERN, line):
+                match = re.search(r"^unit\s+(\S+)\s*=\s*(.+?)\s*$", line)
+                if not match:
+                    raise SyntaxError(file_name, line_no, line, "Units must be declared in the form \"unit <symbol> = <number> <units>\".")
+                try:
+                    un.declare(match.group(1), match.group(2), file_name)
+                except ValueError as e:
+                    raise SyntaxError(file_name, line_no, line, str(e))
+                last_line_blank = False
             elif line[:8] == 'section ':
                 try:
                     assert(re.search(r"^section\s+[\w\s]*$", line))
                 except:
-                    raise SyntaxError(file_name, i+1, line, "Sections must be of the form \"section <name>\" where <name> is only word characters and whitespace.")
-                
+                    raise SyntaxError(file_name, line_no, line, "Sections must be of the form \"section <name>\" where <name> is only word characters and whitespace.")
+            
                 last_line_blank = False
                 section = line.replace("section", "").strip()
             elif line[0:4] == 'test' or line.replace(" ", "").replace("\t", "")[0:5] == '*test':
-                try:
-                    assert(re.search(r"^(\*{1,2}\s*)?test\s*(\{\w+(,\s*\w+)*\})?:.*$", line))
-                except:
-                    raise SyntaxError(file_name, i+1, line, "Tests must be of the form \"test {<input 1>, <input 2>, ... ,<input n>}: <expression>\" where {<input 1>, <input 2>, ... ,<input n>} is optional, each <input> consists of word characters only, and <expression> is a valid python expression with valid parameters and constants.")
-                
+                check_test_syntax(line, file_name, line_no)
+            
                 last_line_blank = False
-                tests.append(Test(line, i+1, file_name.replace(".on", ""), section=section))
+                tests.append(Test(line, line_no, file_name.replace(".on", ""), section=section))
+                tests[-1].annotations, annotations = annotations, {}
                 prev_line = 'test'
+            elif re.search(r"^(?:[^:=]+:\s*)?\w+\s*:=\s*\w+(\.\w+)?\s*$", line):
+                last_line_blank = False
+                if not syntax.settings["legacy_syntax"]:
+                    raise SyntaxError(file_name, line_no, line, "Aliases (\"ID := target\") aren't allowed by this project's parser settings (legacy_syntax=false). Replace the alias's uses with its target and remove it.")
+                parameter = parse_alias(line, line_no, file_name.replace(".on", ""), section)
+                syntax.check_id(parameter.id, file_name, line_no, line)
+                parameter.annotations, annotations = annotations, {}
+                parameters.append(parameter)
+                prev_line = 'param'
             elif re.search(r"^(\*{1,2}\s*)?\w+(\.\w+)?\s*=>?[^:]+(:.*)?$", line):
                 last_line_blank = False
                 unit_fx = lambda x:x
 
-                id, equation, arguments, units, unit_fx, hrunits, pointer, fallback_param = parse_body(line.split(":"), line, i+1, file_name.replace(".on", ""), imports)
+                id, equation, arguments, units, unit_fx, hrunits, pointer, fallback_param = parse_body(line.split(":"), line, line_no, file_name.replace(".on", ""), imports)
+                syntax.check_id(id, file_name, line_no, line)
                 isdiscrete = True if not pointer and isinstance(equation, str) else False
                 options = [equation] if not pointer and isinstance(equation, str) else None
-                design_overrides[id] = Parameter(equation, units, id, hr_units=hrunits, model=file_name.replace(".on", ""), line_no=i+1, line=line, name=f"{id} from {file_name}", options=options, section=section, pointer=pointer, fallback_param=fallback_param)
-                
+                design_overrides[id] = Parameter(equation, units, id, hr_units=hrunits, model=file_name.replace(".on", ""), line_no=line_no, line=line, name=f"{id} from {file_name}", options=options, section=section, pointer=pointer, fallback_param=fallback_param)
+                design_overrides[id].annotations, annotations = annotations, {}
+
                 prev_line='design'
             elif re.search(r"^[^\s]+[^:]*:\s*\w+\s*=[^:]+(:.*)?$", line):
                 last_line_blank = False
                 unit_fx = lambda x:x
-                
-                parameter, unit_fx = parse_parameter(line, i+1, file_name.replace(".on", ""), imports, section)
+            
+                parameter, unit_fx = parse_parameter(line, line_no, file_name.replace(".on", ""), imports, section)
+                syntax.check_id(parameter.id, file_name, line_no, line)
+                parameter.annotations, annotations = annotations, {}
                 parameters.append(parameter)
                 prev_line = 'param'
             else:
-                raise SyntaxError(file_name, i+1, line, "Invalid syntax.")
+                raise SyntaxError(file_name, line_no, line, "Invalid syntax.")
+        except SyntaxError as e:
+            if syntax.settings["recovery"] == "none":
+                raise
+            errors.append(e)
+            broken, annotations, fence = True, {}, None
+
+    if errors:
+        for error in errors[1:]:
+            errors[0] = errors[0].with_note(f"{line_context(error.filename, error.line_no)}: {error.message()}")
+        raise errors[0]
+
+    if fence:
+        raise SyntaxError(file_name, fence[0], fence[1], "Unclosed ```oneil-test block. End it with ``` in the same notes.")
+    tests += doc_tests
 
-        params = {p.id: p for p in parameters}
+    params = {p.id: p for p in parameters}
 
-        if not params and not tests and not design_overrides:
-            raise ModelLoadingError(file_name, final_line, "Empty model. No parameters, design values, or tests found.")
+    if not params and not tests and not design_overrides:
+        raise ModelLoadingError(file_name, final_line, "Empty model. No parameters, design values, or tests found.")
 
-        return note, params, submodels, tests, design_overrides
+    if annotations:
+        raise SyntaxError(file_name, annotation_line[0], annotation_line[1], "Annotations must be followed by a parameter, test, or design value, or by a blank line to apply to the model.")
+
+    return note, params, submodels, tests, design_overrides, model_annotations, model_features
+
+def parse_alias(line, line_number, file_name, section=""):
+    """
+    Parse an alias (`[<name>:] <ID> := <target>`), a parameter that forwards to another
+    parameter (`<ID>` or `<ID>.<symbol>`). Its units are taken from the target when the
+    model loads.
+    """
+    declaration, target = line.split(":=")
+    preamble, _, id = declaration.rpartition(":")
+    id, target = id.strip(), target.strip()
+    name = preamble.strip() or f"{id} (alias of {target})"
+    parameter = Parameter(target, {}, id, model=file_name, line_no=line_number, line=line, name=name, section=section, pointer=True)
+    parameter.alias = target
+    return parameter
 
 def parse_parameter(line, line_number, file_name, imports, section=""):
     trace = False
@@ -252,22 +727,21 @@ def parse_parameter(line, line_number, file_name, imports, section=""):
     if '(' and ')' in preamble:
         name = preamble.split('(')[0].strip()
         limits = []
-        for l in preamble.replace(" ", "").split('(')[1].split(')')[0].split(','):
+        for l in preamble.split('(')[1].split(')')[0].split(','):
+            l, limit_fx = parse_limit_units(l.strip(), units, unit_fx, file_name, line_number)
+            l = l.replace(" ", "")
             if l.replace('.','').isnumeric():
-                limits.append((unit_fx)(float(l)))
+                limits.append((limit_fx)(float(l)))
             elif l in MATH_CONSTANTS:
-                limits.append((unit_fx)(MATH_CONSTANTS[l]))
+                limits.append((limit_fx)(MATH_CONSTANTS[l]))
             elif any(character in EQUATION_OPERATORS + list(OPERATOR_OVERRIDES.keys()) for character in l):
-                try:
-                    limits.append((unit_fx)(eval(l, MATH_CONSTANTS)))
-                except ZeroDivisionError as e:
-                    raise DivideByZeroError((file_name, line_number))
+                limits.append(eval_constant(l, limit_fx, file_name, line_number))
             else:
                 raise SyntaxError(file_name, line_number, line, "Parse parameter: invalid limit: " + l)
         options = tuple(limits)
     elif '[' and ']' in preamble:
         name = preamble.split('[')[0].strip()
-        options = preamble.replace(" ", "").split('[')[1].split(']')[0].split(',')
+        options = [parse_option_units(option.strip(), units, unit_fx, file_name, line_number) for option in preamble.split('[')[1].split(']')[0].split(',')]
     else:
         name = preamble
         options = (0, np.inf)
@@ -277,6 +751,65 @@ def parse_parameter(line, line_number, file_name, imports, section=""):
 
     return Parameter(equation, units, id, hr_units=hrunits, model=file_name, line_no=line_number, line=line, name=name, options=options, arguments=arguments, trace=trace, section=section, performance=performance, pointer=pointer, fallback_param=fallback_param), unit_fx
 
+def split_limit_units(limit):
+    """
+    Split a limit into its value and the units written after it ("5 km", "5km",
+    "pi/2 rad"), or None if it doesn't have any.
+    """
+    number = r"[-+]?\d*\.?\d+(?:[eE][-+]?\d+)?"
+    unit = r"[A-Za-z$%°Δ]"
+    match = re.match(r"^(" + number + r")(?![eE][-+]?\d)\s*(" + unit + r".*)$", limit) or re.match(r"^(.+?)\s+(" + unit + r"\S*)$", limit)
+    if match and match.group(2) not in MATH_CONSTANTS:
+        return match.group(1).strip(), match.group(2).strip()
+    return limit, None
+
+def parse_limit_units(limit, units, unit_fx, file_name, line_number):
+    """
+    Check the units a limit is written with, if any, against the parameter's units.
+    Returns the limit's value and the function converting it to base units.
+    """
+    value, hrunits = split_limit_units(limit)
+    if hrunits is None:
+        return limit, unit_fx
+    try:
+        limit_units, limit_fx = un.parse(hrunits)
+    except Exception:
+        raise UnitParseError(file_name, line_number, hrunits)
+    if limit_units != units:
+        raise LimitUnitError(file_name, line_number, limit, limit_units, units)
+    return value, limit_fx
+
+def parse_option_units(option, units, unit_fx, file_name, line_number):
+    """
+    Check the units a numeric discrete option is written with ("2 m"), if any. Options
+    are compared as written, so they have to be in the parameter's own units, which are
+    then dropped.
+    """
+    value, hrunits = split_limit_units(option)
+    if hrunits is None or not isfloat(value):
+        return option.replace(" ", "")
+    try:
+        option_units, option_fx = un.parse(hrunits)
+    except Exception:
+        raise UnitParseError(file_name, line_number, hrunits)
+    if option_units != units or option_fx(1) != unit_fx(1):
+        raise LimitUnitError(file_name, line_number, option, option_units, units)
+    return value
+
+def parse_test_input(arg, value, file_name, line_number, line):
+    """
+    A test input given as a value in a use declaration (`use engine(F=5 :kN) as e`), as
+    a parameter, or None if it's a parameter ID (`F=F_max` or `F=F.t`).
+    """
+    value = value.strip()
+    if re.fullmatch(r"[A-Za-z_]\w*(\.\w+)?", value):
+        return None
+    id, equation, arguments, units, unit_fx, hrunits, pointer, _ = parse_body(f"{arg} = {value}".split(":"), line, line_number, file_name, [])
+    parameter = Parameter(equation, units, id, hr_units=hrunits, model=file_name, line_no=line_number, line=line, name=f"{id} from use")
+    if not parameter.independent:
+        raise SyntaxError(file_name, line_number, line, f"Test input {arg} must be a parameter ID or a value (e.g. \"{arg}=5 :kN\").")
+    return parameter
+
 def parse_body(body, line, line_number, file_name, imports):
     
     if '=>' in body[0]:
@@ -295,7 +828,7 @@ def parse_body(body, line, line_number, file_name, imports):
         try:
             units, unit_fx = un.parse(hrunits)
         except Exception as e:
-            raise UnitParseError(file_name, line_number, hrunits)
+            raise UnitParseError(file_name, line_number, hrunits, str(e) if isinstance(e, un.ScopeError) else None)
     elif len(body) > 2:
         raise SyntaxError(file_name, line_number, line, "Parse parameter: too many colons.")
     else: 
@@ -348,7 +881,12 @@ def parse_equation(assignment, units, id, imports, file_name, line_number, unit_
 def _parse_equation_inner(assignment, units, id, imports, file_name, line_number, unit_fx, pointer):
     """Inner parsing logic for equations (without fallback handling)."""
     
-    if assignment.strip()[0] == '{':
+    bounds = interval_literal(assignment)
+    if bounds:
+        return parse_interval(bounds, assignment, unit_fx, file_name, line_number), []
+    elif externs.name(assignment) is not None:
+        return assignment.strip(), []
+    elif assignment.strip()[0] == '{':
         equation, arguments = parse_piecewise(assignment, units, id, imports, file_name, line_number, unit_fx, pointer)
         equation = [equation]
     else:
@@ -371,20 +909,55 @@ def _parse_equation_inner(assignment, units, id, imports, file_name, line_number
             
         else:
             if '|' in assignment:
-                try:
-                    min = (unit_fx)(eval((assignment.split('|')[0]), MATH_CONSTANTS))
-                    max = (unit_fx)(eval((assignment.split('|')[1]), MATH_CONSTANTS))
-                except ZeroDivisionError as e:
-                    raise DivideByZeroError((file_name, line_number))
+                min = eval_constant(assignment.split('|')[0], unit_fx, file_name, line_number)
+                max = eval_constant(assignment.split('|')[1], unit_fx, file_name, line_number)
                 equation = (min, max)
             else:
-                try:
-                    equation = (unit_fx)(eval(assignment, MATH_CONSTANTS))
-                except ZeroDivisionError as e:
-                    raise DivideByZeroError((file_name, line_number))
+                equation = eval_constant(assignment, unit_fx, file_name, line_number)
 
     return equation, arguments
 
+class IntervalLiteral(tuple):
+    """
+    The (min, max) of a value written as an interval literal (`[2.1, 2.4]`), so it's
+    displayed the way it was written.
+    """
+
+INTERVAL_LITERAL = re.compile(r"\s*\[([^\[\],]+),([^\[\],]+)\]\s*")
+
+def interval_literal(assignment):
+    """
+    The bounds of an interval literal (`[<min>, <max>]`), as written, or None if the
+    assignment isn't one.
+    """
+    match = INTERVAL_LITERAL.fullmatch(assignment)
+    return tuple(bound.strip() for bound in match.groups()) if match else None
+
+def parse_interval(bounds, assignment, unit_fx, file_name, line_number):
+    """
+    Evaluate the bounds of an interval literal, which are numbers or expressions of
+    numbers and math constants, like single values. Equations for the min and max are
+    written with a pipe instead (`<min> | <max>`).
+    """
+    try:
+        lower, upper = (eval_constant(bound, unit_fx, file_name, line_number) for bound in bounds)
+    except (NameError, TypeError):
+        raise SyntaxError(file_name, line_number, assignment, f"The bounds of an interval literal must be numbers or math constants. Use \"{bounds[0]} | {bounds[1]}\" for equations.")
+    if lower > upper:
+        raise SyntaxError(file_name, line_number, assignment, f"The interval [{bounds[0]}, {bounds[1]}] has a min greater than its max.")
+    return IntervalLiteral((lower, upper))
+
+def eval_constant(expression, unit_fx, file_name, line_number):
+    """
+    Evaluate an expression of numbers and math constants, converted to base units.
+    """
+    try:
+        return (unit_fx)(eval(expression, dict(MATH_CONSTANTS)))
+    except ZeroDivisionError as e:
+        raise DivideByZeroError((file_name, line_number))
+    except builtins.SyntaxError as e:
+        raise SyntaxError(file_name, line_number, expression, f"Invalid expression \"{expression}\": {e.msg}.")
+
 def parse_piecewise(assignment, units, id, imports, file_name, line_number, unit_fx, pointer=False):
     eargs = []
     cargs = []
@@ -398,6 +971,173 @@ def parse_piecewise(assignment, units, id, imports, file_name, line_number, unit
     condition, cargs = _parse_equation_inner(assignment.split('if')[1].strip(), units, id, imports, file_name, line_number, unit_fx, pointer)
     return (Parameter(equation, units, id + ":eqpiece", pointer=pointer), Parameter(condition, {}, id + ":condpiece")), eargs + cargs
 
+# Functions whose result has the units of their (first) argument, and functions whose
+# result is unitless
+UNIT_PRESERVING_FUNCTIONS = ["min", "max", "abs", "mnmx", "mxmn", "floor", "ceiling", "extent", "range", "mid", "linear", "lerp"]
+UNITLESS_FUNCTIONS = ["sin", "cos", "tan", "asin", "acos", "atan", "sinh", "cosh", "tanh", "log", "log2", "log10", "ln", "sign", "strip", "decibels"]
+
+def _literal(node):
+    if isinstance(node, ast.UnaryOp) and isinstance(node.op, (ast.USub, ast.UAdd)):
+        return _literal(node.operand)
+    return isinstance(node, ast.Constant)
+
+def _combine_units(left, right, sign=1):
+    units = dict(left)
+    for unit, exponent in right.items():
+        units[unit] = units.get(unit, 0) + sign * exponent
+    return {unit: exponent for unit, exponent in units.items() if exponent != 0}
+
+def static_units(equation, lookup, logarithmic=lambda ID: False):
+    """
+    Work out the units of an equation (as parsed, e.g. "par_sqrt(l)*m") from the units
+    its parameters are declared with, without calculating it. `lookup` gives the units
+    of an ID (or `ID.submodel`), or None if they aren't known, and `logarithmic` whether
+    it's declared in dB. Returns None if the units can't be told (e.g. from a breakout
+    function). Number literals are unitless, but can be added to or compared with
+    anything, like zero. Raises UnitEvaluationError for sums and comparisons of
+    different units, and for sums of values in dB and linear values.
+    """
+    names = {new: old for old, new in FUNCTIONS.items()}
+    source = equation.replace(OPERATOR_OVERRIDES["--"], "-")
+    tree = ast.parse(source, mode="eval")
+
+    def written(*pairs):
+        # The operands of a mismatch as (text, units, the ID it names or None), to label
+        # them in the equation as written (see UnitEvaluationError.labels())
+        found = []
+        for node, units in pairs:
+            ID = node.id if isinstance(node, ast.Name) else f"{node.value.id}.{node.attr}" if isinstance(node, ast.Attribute) and isinstance(node.value, ast.Name) else None
+            found.append((ast.get_source_segment(source, node), units, ID))
+        return found
+
+    def decibels(node):
+        # Whether a node is a value in dB (see Parameter._decibels())
+        if isinstance(node, ast.Name):
+            return logarithmic(node.id)
+        elif isinstance(node, ast.Attribute) and isinstance(node.value, ast.Name):
+            return logarithmic(f"{node.value.id}.{node.attr}")
+        elif isinstance(node, ast.UnaryOp) and isinstance(node.op, ast.USub):
+            return decibels(node.operand)
+        elif isinstance(node, ast.BinOp) and isinstance(node.op, (ast.Add, ast.Sub)):
+            return decibels(node.left) and decibels(node.right)
+        return False
+
+    def units(node):
+        if isinstance(node, ast.Constant):
+            return {}
+        elif isinstance(node, ast.Name):
+            if node.id in MATH_CONSTANTS:
+                return MATH_CONSTANTS[node.id].units if isinstance(MATH_CONSTANTS[node.id], Parameter) else {}
+            return lookup(node.id)
+        elif isinstance(node, ast.Attribute) and isinstance(node.value, ast.Name):
+            return lookup(f"{node.value.id}.{node.attr}")
+        elif isinstance(node, ast.UnaryOp):
+            operand = units(node.operand)
+            if isinstance(node.op, ast.USub) and decibels(node.operand) and operand is not None:
+                return _combine_units({}, operand, -1)
+            return {} if isinstance(node.op, ast.Not) else operand
+        elif isinstance(node, ast.BoolOp):
+            for value in node.values:
+                units(value)
+            return {}
+        elif isinstance(node, ast.Compare):
+            operands = [node.left] + node.comparators
+            found = [units(operand) for operand in operands]
+            for i in range(len(operands) - 1):
+                if _literal(operands[i]) or _literal(operands[i + 1]) or None in (found[i], found[i + 1]):
+                    continue
+                if found[i] != found[i + 1]:
+                    raise UnitEvaluationError(f"Cannot compare {un.hr_units(found[i], [1, 1])} to {un.hr_units(found[i + 1], [1, 1])}.", []).with_operands("compared here", written((operands[i], found[i]), (operands[i + 1], found[i + 1])))
+            return {}
+        elif isinstance(node, ast.BinOp):
+            left, right = units(node.left), units(node.right)
+            # A number added to a value in dB is added to its linear value, as before dB
+            # arithmetic (see Parameter._decibels()).
+            if isinstance(node.op, (ast.Add, ast.Sub)) and (decibels(node.left) or decibels(node.right)) and not (_literal(node.left) or _literal(node.right)):
+                operation = 'add' if isinstance(node.op, ast.Add) else 'subtract'
+                if not (decibels(node.left) and decibels(node.right)):
+                    raise UnitEvaluationError(f"Cannot {operation} a value in dB and a linear value.", [])
+                if left is None or right is None:
+                    return None
+                if isinstance(node.op, ast.Add) and left and right:
+                    raise UnitEvaluationError(f"Cannot add two values in dB with units ({un.hr_units(left, [1, 1])} and {un.hr_units(right, [1, 1])}).", [])
+                return _combine_units(left, right, 1 if isinstance(node.op, ast.Add) else -1)
+            elif isinstance(node.op, (ast.Add, ast.Sub)):
+                if _literal(node.left) or left is None:
+                    return right
+                if _literal(node.right) or right is None:
+                    return left
+                if left != right:
+                    operation = 'add' if isinstance(node.op, ast.Add) else 'subtract'
+                    raise UnitEvaluationError(f"Cannot {operation} {un.hr_units(left, [1, 1])} and {un.hr_units(right, [1, 1])}.", []).with_operands(f"{operation}ed here", written((node.left, left), (node.right, right)))
+                return left
+            elif left is None:
+                return None
+            elif isinstance(node.op, ast.Mod):
+                return left
+            elif right is None:
+                return None
+            elif isinstance(node.op, ast.Mult):
+                return _combine_units(left, right)
+            elif isinstance(node.op, (ast.Div, ast.FloorDiv)):
+                return _combine_units(left, right, -1)
+            elif isinstance(node.op, ast.Pow):
+                if not left:
+                    return {}
+                if _literal(node.right):
+                    exponent = ast.literal_eval(node.right)
+                    return {unit: power * exponent for unit, power in left.items()}
+            return None
+        elif isinstance(node, ast.Call) and isinstance(node.func, ast.Name):
+            arguments = [units(argument) for argument in node.args]
+            name = names.get(node.func.id, node.func.id)
+            if name in ("lerp", "map_range"):
+                # Arguments that interpolate between each other must have the same units,
+                # and the fraction of lerp() must be unitless (see par_lerp()).
+                groups = [[0, 1]] if name == "lerp" else [[0, 1, 2], [3, 4]]
+                for group in groups:
+                    found = [arguments[i] for i in group if i < len(arguments) and arguments[i] is not None and not _literal(node.args[i])]
+                    if any(units != found[0] for units in found):
+                        raise UnitEvaluationError(f"Cannot interpolate between {' and '.join(sorted(set(un.hr_units(units, [1, 1]) or 'unitless' for units in found)))} ({name}()).", [])
+                if name == "lerp" and len(arguments) > 2 and arguments[2]:
+                    raise UnitEvaluationError(f"The fraction of lerp() must be unitless, not {un.hr_units(arguments[2], [1, 1])}.", [])
+                return arguments[0] if name == "lerp" else (arguments[3] if len(arguments) > 3 else None)
+            if name in UNIT_PRESERVING_FUNCTIONS and arguments:
+                return arguments[0]
+            elif name in UNITLESS_FUNCTIONS:
+                return {}
+            elif name == "sqrt" and arguments and arguments[0] is not None:
+                return {unit: power / 2 for unit, power in arguments[0].items()}
+            return None
+        return None
+
+    return units(tree.body)
+
+def parse_override(override):
+    """
+    Parse a command-line override ("x=3:mm", "x=3mm", or "x=2|4 :m") into a design
+    parameter, using the same body parser as design files.
+    """
+    if "=" not in override or not override.split("=")[0].strip():
+        raise OverrideError(override, "Overrides must be of the form \"<ID>=<value>\" or \"<ID>=<value>:<units>\".")
+
+    ID, value = [s.strip() for s in override.split("=", 1)]
+
+    # Allow the units to be attached directly to a number (e.g. "3mm", "6dB").
+    if ":" not in value:
+        number = r"[-+]?\d*\.?\d+(?:[eE][-+]?\d+)?"
+        match = re.match(r"^(" + number + r"(?:\|" + number + r")?)\s*([^\d\s|].*)$", value)
+        if match:
+            value = match.group(1) + ":" + match.group(2)
+
+    line = f"{ID} = {value}"
+    try:
+        id, equation, arguments, units, unit_fx, hrunits, pointer, fallback_param = parse_body(line.split(":"), line, None, "--set", [])
+        options = [equation] if not pointer and isinstance(equation, str) and re.fullmatch(r"\w+", equation) else None
+        return Parameter(equation, units, id, hr_units=hrunits, model="--set", line=line, name=f"{id} from --set", options=options, pointer=pointer)
+    except OneilError as e:
+        raise OverrideError(override, e.message())
+
 def convert_functions(assignment, imports, file_name, line_number):
     arguments = []
     if isfloat(assignment):
@@ -422,10 +1162,14 @@ def convert_functions(assignment, imports, file_name, line_number):
     arguments = arg_str.split(",")
     
     for i in imports:
-        if func in i.__dict__.keys():
+        if not isinstance(i, sandbox.RefusedImport) and func in i.__dict__.keys():
             equation = i.__dict__[func]
             break
-            
+
+    refused = [i for i in imports if isinstance(i, sandbox.RefusedImport)]
+    if not equation and refused:
+        # The function may come from an import that wasn't run (see oneil.sandbox).
+        equation = sandbox.refused_function(func, refused)
     if not equation:
         raise SyntaxError(file_name, line_number, assignment, "Parse parameter: invalid function: " + func)
 
@@ -506,6 +1250,71 @@ def par_strip(val):
     else:
         raise TypeError("Input to strip() must be of type Parameter.")
 
+@err.add_trace
+def par_linear(val):
+    # Values in dB are kept linear, so they're already their linear values, and a number
+    # of dB is converted (linear(20) is 100).
+    if isinstance(val, (int, float)):
+        val = Parameter(val, {}, str(val))
+    if isinstance(val, Parameter):
+        if val.logarithmic:
+            return Parameter((val.min, val.max), val.units, "linear({})".format(val.id))
+        if val.units != {}:
+            raise UnitEvaluationError(f"Input to linear() must be in dB or a unitless number of dB, not {un.hr_units(val.units)}.", [val])
+        return Parameter((deterministic.rounded(10**(val.min/10)), deterministic.rounded(10**(val.max/10))), {}, "linear({})".format(val.id))
+    else:
+        raise TypeError("Input to linear() must be of type Parameter, int, or float.")
+
+@err.add_trace
+def par_decibels(val):
+    # The linear value of a unitless value, in dB or not, as a number of dB (decibels(100)
+    # is 20).
+    if isinstance(val, (int, float)):
+        val = Parameter(val, {}, str(val))
+    if isinstance(val, Parameter):
+        if val.units != {}:
+            raise UnitEvaluationError(f"Input to decibels() must be unitless, not {un.hr_units(val.units)}.", [val])
+        if val.min <= 0:
+            raise ParameterError("Input to decibels() must be >0 (par_decibels).", val)
+        return Parameter((10*deterministic.log10(val.min), 10*deterministic.log10(val.max)), {}, "decibels({})".format(val.id))
+    else:
+        raise TypeError("Input to decibels() must be of type Parameter, int, or float.")
+
+def _interpolation_units(function, groups, fractions=()):
+    # The units of each group of arguments of an interpolation, which must match within
+    # the group (a number is unitless), and checks that the fractions are unitless.
+    found = []
+    for group in groups:
+        units = [value.units if isinstance(value, Parameter) else {} for value in group]
+        if any(u != units[0] for u in units):
+            raise UnitEvaluationError(f"Cannot interpolate between {' and '.join(sorted(set(un.hr_units(u, [1, 1]) or 'unitless' for u in units)))} ({function}).", [v for v in group if isinstance(v, Parameter)])
+        found.append(units[0])
+    for value in fractions:
+        if isinstance(value, Parameter) and value.units:
+            raise UnitEvaluationError(f"The fraction of {function} must be unitless, not {un.hr_units(value.units, [1, 1])}.", [value])
+    return found
+
+def _interpolate(function, name, units, values):
+    # Interpolation is linear in each argument between the design points, so the extremes
+    # of the result are at the ends of the arguments' ranges.
+    ends = [(value.min, value.max) if isinstance(value, Parameter) else (value, value) for value in values]
+    results = [function(*corner) for corner in itertools.product(*ends)]
+    return Parameter((min(results), max(results)), units, name)
+
+@err.add_trace
+def par_lerp(a, b, t):
+    units, = _interpolation_units("lerp()", [(a, b)], [t])
+    return _interpolate(lambda a, b, t: a + (b - a)*t, f"lerp({', '.join(str(getattr(v, 'name', v)) for v in (a, b, t))})", units, (a, b, t))
+
+@err.add_trace
+def par_map_range(x, in_min, in_max, out_min, out_max):
+    _, units = _interpolation_units("map_range()", [(x, in_min, in_max), (out_min, out_max)])
+    def map_range(x, in_min, in_max, out_min, out_max):
+        if in_max == in_min:
+            raise DivideByZeroError(None)
+        return out_min + (x - in_min)*(out_max - out_min)/(in_max - in_min)
+    return _interpolate(map_range, f"map_range({', '.join(str(getattr(v, 'name', v)) for v in (x, in_min, in_max, out_min, out_max))})", units, (x, in_min, in_max, out_min, out_max))
+
 @err.add_trace
 def par_min(val1, val2=None):
     if not val2:
@@ -569,10 +1378,10 @@ def par_sin(val):
     if isinstance(val, Parameter):
         if val.units != {}:
             raise UnitEvaluationError("Input to sine must be unitless.", [val])
-        results = [np.sin(val.min), np.sin(val.max)]
-        return Parameter((min(results), max(results)), {}, "sin({})".format(val.id))
+        results = [deterministic.sin(val.min), deterministic.sin(val.max)]
+        return Parameter(interval(results), {}, "sin({})".format(val.id))
     elif isinstance(val, (int, float)):
-        return Parameter((np.sin(val), np.sin(val)), {}, "sin({})".format(val))
+        return Parameter((deterministic.sin(val), deterministic.sin(val)), {}, "sin({})".format(val))
     else:
         raise TypeError("Input to sin() must be of type Parameter, int, or float.")
 
@@ -581,10 +1390,10 @@ def par_cos(val):
     if isinstance(val, Parameter):
         if val.units != {}:
             raise UnitEvaluationError("Input to cosine must be unitless.", [val])
-        results = [np.cos(val.min), np.cos(val.max)]
-        return Parameter((min(results), max(results)), {}, "cos({})".format(val.id))
+        results = [deterministic.cos(val.min), deterministic.cos(val.max)]
+        return Parameter(interval(results), {}, "cos({})".format(val.id))
     elif isinstance(val, (int, float)):
-        return Parameter((np.cos(val), np.cos(val)), {}, "cos({})".format(val))
+        return Parameter((deterministic.cos(val), deterministic.cos(val)), {}, "cos({})".format(val))
     else:
         raise TypeError("Input to cos() must be of type Parameter, int, or float.")
 
@@ -593,10 +1402,10 @@ def par_tan(val):
     if isinstance(val, Parameter):
         if val.units != {}:
             raise UnitEvaluationError("Input to tangent must be unitless.", [val])
-        results = [np.tan(val.min), np.tan(val.max)]
-        return Parameter((min(results), max(results)), {}, "tan({})".format(val.id))
+        results = [deterministic.tan(val.min), deterministic.tan(val.max)]
+        return Parameter(interval(results), {}, "tan({})".format(val.id))
     elif isinstance(val, (int, float)):
-        return Parameter((np.tan(val), np.tan(val)), {}, "tan({})".format(val))
+        return Parameter((deterministic.tan(val), deterministic.tan(val)), {}, "tan({})".format(val))
     else:
         raise TypeError("Input to tan() must be of type Parameter, int, or float.")
 
@@ -607,12 +1416,12 @@ def apar_sin(val):
             raise UnitEvaluationError("Input to arcsine must be unitless.", [val])
         if not -1 <= val.min <= 1 or not -1 <= val.max <= 1:
             raise ParameterError("Input to arcsine must be between -1 and 1 (apar_sin).", val)
-        results = [np.arcsin(val.min), np.arcsin(val.max)]
-        return Parameter((min(results), max(results)), {}, "asin({})".format(val.id))
+        results = [deterministic.arcsin(val.min), deterministic.arcsin(val.max)]
+        return Parameter(interval(results), {}, "asin({})".format(val.id))
     elif isinstance(val, (int, float)):
         if not -1 <= val <= 1:
             raise ParameterError("Input to arcsine must be between -1 and 1 (apar_sin).", val)
-        return Parameter((np.arcsin(val), np.arcsin(val)), {}, "asin({})".format(val))
+        return Parameter((deterministic.arcsin(val), deterministic.arcsin(val)), {}, "asin({})".format(val))
     else:
         raise TypeError("Input to asin() must be of type Parameter, int, or float.")
 
@@ -623,12 +1432,12 @@ def apar_cos(val):
             raise UnitEvaluationError("Input to arccosine must be unitless.", [val])
         if not -1 <= val.min <= 1 or not -1 <= val.max <= 1:
             raise ParameterError("Input to arccosine must be between -1 and 1 (apar_cos).", val)
-        results = [np.arccos(val.min), np.arccos(val.max)]
-        return Parameter((min(results), max(results)), {}, "acos({})".format(val.id))
+        results = [deterministic.arccos(val.min), deterministic.arccos(val.max)]
+        return Parameter(interval(results), {}, "acos({})".format(val.id))
     elif isinstance(val, (int, float)):
         if not -1 <= val <= 1:
             raise ParameterError("Input to arccosine must be between -1 and 1 (apar_cos).", val)
-        return Parameter((np.arccos(val), np.arccos(val)), {}, "acos({})".format(val))
+        return Parameter((deterministic.arccos(val), deterministic.arccos(val)), {}, "acos({})".format(val))
     else:
         raise TypeError("Input to acos() must be of type Parameter, int, or float.")
 
@@ -637,10 +1446,10 @@ def apar_tan(val):
     if isinstance(val, Parameter):
         if val.units != {}:
             raise UnitEvaluationError("Input to arctangent must be unitless.", [val])
-        results = [np.arctan(val.min), np.arctan(val.max)]
-        return Parameter((min(results), max(results)), {}, "atan({})".format(val.id))
+        results = [deterministic.arctan(val.min), deterministic.arctan(val.max)]
+        return Parameter(interval(results), {}, "atan({})".format(val.id))
     elif isinstance(val, (int, float)):
-        return Parameter((np.arctan(val), np.arctan(val)), {}, "atan({})".format(val))
+        return Parameter((deterministic.arctan(val), deterministic.arctan(val)), {}, "atan({})".format(val))
     else:
         raise TypeError("Input to atan() must be of type Parameter, int, or float.")
 
@@ -675,12 +1484,12 @@ def par_abs(val):
 def par_log(val):
     if isinstance(val, Parameter):
         # ERR option ETC
-        if np.log(val.min) < np.log(val.max):
-            return Parameter((np.log(val.min), np.log(val.max)), {}, "|{}|".format(val.id))
+        if deterministic.log(val.min) < deterministic.log(val.max):
+            return Parameter((deterministic.log(val.min), deterministic.log(val.max)), {}, "|{}|".format(val.id))
         else:
-            return Parameter((np.log(val.max), np.log(val.min)), {}, "|{}|".format(val.id))
+            return Parameter((deterministic.log(val.max), deterministic.log(val.min)), {}, "|{}|".format(val.id))
     elif isinstance(val, (int, float)):
-        return Parameter((np.log(val), np.log(val)), {}, "|{}|".format(val))
+        return Parameter((deterministic.log(val), deterministic.log(val)), {}, "|{}|".format(val))
     else:
         raise TypeError("Input to log() must be of type Parameter, int, or float.")
 
@@ -688,12 +1497,12 @@ def par_log(val):
 def par_log10(val):
     if isinstance(val, Parameter):
         # ERR option ETC
-        if np.log10(val.min) < np.log10(val.max):
-            return Parameter((np.log10(val.min), np.log10(val.max)), {}, "|{}|".format(val.id))
+        if deterministic.log10(val.min) < deterministic.log10(val.max):
+            return Parameter((deterministic.log10(val.min), deterministic.log10(val.max)), {}, "|{}|".format(val.id))
         else:
-            return Parameter((np.log10(val.max), np.log10(val.min)), {}, "|{}|".format(val.id))
+            return Parameter((deterministic.log10(val.max), deterministic.log10(val.min)), {}, "|{}|".format(val.id))
     elif isinstance(val, (int, float)):
-        return Parameter((np.log10(val), np.log10(val)), {}, id="|{}|".format(val))
+        return Parameter((deterministic.log10(val), deterministic.log10(val)), {}, id="|{}|".format(val))
     else:
         raise TypeError("Input to log10() must be of type Parameter, int, or float.")
     
@@ -701,12 +1510,12 @@ def par_log10(val):
 def par_log2(val):
     if isinstance(val, Parameter):
         # ERR option ETC
-        if np.log2(val.min) < np.log2(val.max):
-            return Parameter((np.log2(val.min), np.log2(val.max)), {}, "|{}|".format(val.id))
+        if deterministic.log2(val.min) < deterministic.log2(val.max):
+            return Parameter((deterministic.log2(val.min), deterministic.log2(val.max)), {}, "|{}|".format(val.id))
         else:
-            return Parameter((np.log2(val.max), np.log2(val.min)), {}, "|{}|".format(val.id))
+            return Parameter((deterministic.log2(val.max), deterministic.log2(val.min)), {}, "|{}|".format(val.id))
     elif isinstance(val, (int, float)):
-        return Parameter((np.log2(val), np.log2(val)), {}, id="|{}|".format(val))
+        return Parameter((deterministic.log2(val), deterministic.log2(val)), {}, id="|{}|".format(val))
     else:
         raise TypeError("Input to log2() must be of type Parameter, int, or float.")
 
@@ -730,6 +1539,15 @@ def par_ceiling(val):
     else:
         raise TypeError("Input to ceiling() must be of type Parameter, int, or float.")
 
+def line_context(filename, line_no):
+    return messages.text("context-line", "in {file} (line {line})", file=filename, line=line_no)
+
+def file_context(filename):
+    return messages.text("context-file", "in {file}", file=filename)
+
+def parameter_context(parameter):
+    return messages.text("context-parameter", "in {label} ({id}) from line {line} in model {model}", label=parameter.name, id=parameter.id, line=parameter.line_no, model=parameter.model)
+
 class DesignError(OneilError):
     def __init__(self, filenames: list[str]):
         self.filenames = filenames
@@ -742,22 +1560,110 @@ class DesignError(OneilError):
         
     def message(self):
         files_str = ", ".join(self.filenames)
-        return f"Can't find design files: [{files_str}]"
+        return messages.error_text(self.kind(), f"Can't find design files: [{files_str}]", files=files_str)
 
 class UnitParseError(OneilError):
-    def __init__(self, filename: str, line_no: int, hrunits: str):
+    def __init__(self, filename: str, line_no: int, hrunits: str, reason: str = None):
         self.filename = filename
         self.line_no = line_no
         self.hrunits = hrunits
+        if reason:
+            self.notes_ = [reason]
         
     def kind(self) -> str:
         return "UnitParseError"
         
     def context(self) -> str | None:
-        return f"in {self.filename} (line {self.line_no})"
+        return line_context(self.filename, self.line_no)
         
     def message(self) -> str:
-        return f"Failed to parse units '{self.hrunits}'"
+        return messages.error_text(self.kind(), f"Failed to parse units '{self.hrunits}'", units=self.hrunits)
+
+class LimitUnitError(OneilError):
+    def __init__(self, filename: str, line_no: int, limit: str, limit_units: dict, units: dict):
+        # A limit written with units that don't match its parameter's
+        self.filename = filename
+        self.line_no = line_no
+        self.limit = limit
+        self.limit_units = limit_units
+        self.units = units
+
+    def kind(self) -> str:
+        return "LimitUnitError"
+
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+
+    def message(self) -> str:
+        limit_units = un.hr_units(self.limit_units) or "unitless"
+        units = un.hr_units(self.units) or "unitless"
+        return messages.error_text(self.kind(), f"The limit \"{self.limit}\" is in {limit_units}, but the parameter is in {units}.", limit=self.limit, limit_units=limit_units, units=units)
+
+class SubmodelInputUnitError(OneilError):
+    def __init__(self, filename: str, line_no: int, line: str, arg: str, value: str, units: dict, expected: dict, submodel: str):
+        # A test input in a use declaration (`use engine(F=5 :kN) as e`) with units that
+        # don't match those of the submodel parameter it stands in for
+        self.filename = filename
+        self.line_no = line_no
+        self.arg = arg
+        self.value = value
+        self.units = units
+        self.expected = expected
+        self.submodel = submodel
+        self.notes_ = [line.strip()]
+
+    def kind(self) -> str:
+        return "SubmodelInputUnitError"
+
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+
+    def message(self) -> str:
+        units = un.hr_units(self.units) or "unitless"
+        expected = un.hr_units(self.expected) or "unitless"
+        return messages.error_text(self.kind(), f"The test input {self.arg}={self.value} is in {units}, but {self.arg} in {self.submodel} is in {expected}.", arg=self.arg, value=self.value, units=units, submodel=self.submodel, expected=expected)
+
+class FunctionUnitError(OneilError):
+    def __init__(self, parameter, function: str, signature: str, problems: list[str], invalid=False):
+        # A parameter calling an imported function whose unit signature (see
+        # oneil.signatures) it doesn't match, or whose signature is invalid
+        self.parameter = parameter
+        self.function = function
+        self.signature = signature
+        self.invalid = invalid
+        self.notes_ = problems
+
+    def kind(self) -> str:
+        return "FunctionUnitError"
+
+    def context(self) -> str | None:
+        return parameter_context(self.parameter)
+
+    def message(self) -> str:
+        if self.invalid:
+            return messages.error_text(self.kind(), f"The unit signature of {self.function}() (\"{self.signature}\") is invalid.", variant="invalid", function=self.function, signature=self.signature)
+        return messages.error_text(self.kind(), f"The call of {self.function}() doesn't match its unit signature ({self.signature}).", function=self.function, signature=self.signature)
+
+def _shown_units(units):
+    return f"in {un.hr_units(units, [1, 1])}" if units else "unitless"
+
+def _written_pattern(text):
+    """
+    A pattern for part of an equation as parsed (e.g. "par_sqrt(m)+a**2") that finds it
+    as written in a model file ("sqrt(m) + a^2"), where spaces are dropped, builtin
+    functions are renamed, and operators are replaced (see OPERATOR_OVERRIDES).
+    """
+    pattern = []
+    for token in re.findall(r"\*\*|\w+|\S", text):
+        if token in FUNCTIONS.values():
+            pattern.append("(?:" + "|".join(re.escape(name) for name, renamed in FUNCTIONS.items() if renamed == token) + ")")
+        elif token == "**":
+            pattern.append(r"(?:\*\*|\^)")
+        elif token == "-":
+            pattern.append("--?")
+        else:
+            pattern.append(re.escape(token))
+    return r"\s*".join(pattern)
 
 class UnitEvaluationError(OneilError):
     def __init__(self, message: str, vals: list):
@@ -776,16 +1682,72 @@ class UnitEvaluationError(OneilError):
     def with_context(self, context):
         self.context_ = context
         return self
-        
+
+    def with_operands(self, label, operands):
+        # The operation the units don't match in (see static_units()): the label of its
+        # operator and its operands as (text as parsed, units, the ID it names or None)
+        self.operation = (label, operands)
+        return self
+
+    def with_declarations(self, declaration):
+        # Finds the parameter an ID is declared by (see Model._declaration()), to label
+        # the units of the operands' declarations
+        self.declaration = declaration
+        return self
+
+    def labels(self) -> list[dict]:
+        """
+        The spans of the operation the units don't match in (its operator, its operands
+        and their units, and the units their parameters are declared with), as dicts
+        with the file, line, 1-based columns (`end_column` is exclusive), message, and
+        whether the span is the primary one (see oneil.render). Empty if the operation
+        isn't known or can't be found in the equation as written.
+        """
+        if not hasattr(self, "operation") or not isinstance(self.context_, Parameter):
+            return []
+        label, operands = self.operation
+        where = console.location(self)
+        if where["start_column"] is None:
+            return []
+        line = positions.source_line(where["file"], where["line"])
+        start = positions.index_of(line, where["end_column"] - 1)
+        spans = []
+        for text, _, _ in operands:
+            match = re.compile(_written_pattern(text)).search(line, start)
+            if not match:
+                return []
+            spans.append((match.start(), match.end()))
+            start = match.end()
+
+        span = lambda start, end, message, primary=False: {"file": where["file"], "line": where["line"], "start_column": start + 1, "end_column": end + 1, "message": message, "primary": primary}
+        # The operator is what's between the operands, but for spaces and brackets
+        operator = re.compile(r"[^\s()]+").search(line, spans[0][1], spans[1][0])
+        if not operator:
+            return []
+        found = [span(operator.start(), operator.end(), label, True)]
+        found += [span(start, end, _shown_units(units)) for (start, end), (_, units, _) in zip(spans, operands)]
+        for _, units, ID in operands:
+            parameter = self.declaration(ID) if ID and hasattr(self, "declaration") else None
+            if parameter is None or parameter.line_no is None:
+                continue
+            source = parameter.source(parameter.model if parameter.model.endswith(".on") else parameter.model + ".on")
+            declared = positions.source_line(source["file"], source["line"])
+            match = re.search(r":\s*([^\s:]+)\s*$", declared) if units else None
+            if match:
+                source.update({"start_column": match.start(1) + 1, "end_column": match.end(1) + 1})
+            if source.get("start_column") is not None:
+                found.append(source | {"message": f"{ID} is declared {'in ' + match.group(1) if match else _shown_units(units)}", "primary": False})
+        return sorted(found, key=lambda label: (label["file"] != where["file"], label["file"], label["line"], label["start_column"]))
+
     def kind(self) -> str:
         return "UnitEvaluationError"
         
     def context(self) -> str | None:
         if self.context_:
             if isinstance(self.context_, Parameter):
-                return f"in '{self.context_.name}' ({self.context_.id}, line {self.context_.line_no}, model '{self.context_.model}')"
+                return messages.text("context-unit-parameter", "in '{label}' ({id}, line {line}, model '{model}')", label=self.context_.name, id=self.context_.id, line=self.context_.line_no, model=self.context_.model)
             elif isinstance(self.context_, Model):
-                return f"in model '{self.context_.name}'"
+                return messages.text("context-unit-model", "in model '{model}'", model=self.context_.name)
             else:
                 raise TypeError(f"Invalid context type: {type(self.context_)}")
         else:
@@ -795,6 +1757,28 @@ class UnitEvaluationError(OneilError):
         message = self.message_
         return message
 
+class PiecewiseUnitError(OneilError):
+    def __init__(self, parameter, branches: list[tuple[int, str, str]], filename: str):
+        # The piecewise parameter and its branches with mismatched units as (line number,
+        # branch as written, what doesn't match)
+        self.parameter = parameter
+        self.branches = branches
+        self.filename = filename
+        self.notes_ = [f"line {line_no}: {text}: {problem}" for line_no, text, problem in branches]
+
+    def kind(self) -> str:
+        return "PiecewiseUnitError"
+
+    def context(self) -> str | None:
+        return parameter_context(self.parameter)
+
+    def message(self) -> str:
+        units = un.hr_units(self.parameter.units, [1, 1])
+        return messages.error_text(self.kind(), f"Branches of a piecewise equation don't match its units ({units}).", units=units)
+
+    def related(self) -> list[dict]:
+        return [{"file": getattr(line_no, "fragment", self.filename), "line": int(line_no), "message": problem} for line_no, _, problem in self.branches]
+
 class ParameterError(OneilError):
     def __init__(self, message, parameter):
         self.parameter = parameter
@@ -810,12 +1794,16 @@ class ParameterError(OneilError):
 
     def context(self) -> str | None:
         if self.parameter:
-            return f"in {self.parameter.name} ({self.parameter.id}) from line {self.parameter.line_no} in model {self.parameter.model}"
+            return parameter_context(self.parameter)
         return None
     
     def message(self) -> str:
         return self.message_
 
+class LimitError(ParameterError):
+    def kind(self) -> str:
+        return "LimitError"
+
 class DivideByZeroError(OneilError):
     def __init__(self, parameter_or_location):
         if isinstance(parameter_or_location, Parameter):
@@ -830,17 +1818,17 @@ class DivideByZeroError(OneilError):
         
     def context(self) -> str | None:
         if self.parameter != None:
-            return f"in {self.parameter.name} ({self.parameter.id}) from line {self.parameter.line_no} in model {self.parameter.model}"
+            return parameter_context(self.parameter)
         elif self.location != None:
-            return f"in {self.location[0]} (line {self.location[1]})"
+            return line_context(self.location[0], self.location[1])
         else:
             return None
         
     def message(self) -> str:
         if self.parameter != None:
-            return f"Cannot divide by zero ({self.parameter.id} is 0)"
+            return messages.error_text(self.kind(), f"Cannot divide by zero ({self.parameter.id} is 0)", "parameter", id=self.parameter.id)
         else:
-            return f"Cannot divide by zero"
+            return messages.error_text(self.kind(), f"Cannot divide by zero")
 
 class SyntaxError(OneilError):
     def __init__(self, filename: str, line_no: int, line: str, message: str):
@@ -853,7 +1841,7 @@ class SyntaxError(OneilError):
         return "SyntaxError"
         
     def context(self) -> str | None:
-        return f"in {self.filename} (line {self.line_no})"
+        return line_context(self.filename, self.line_no)
         
     def message(self) -> str:
         return self.message_
@@ -868,70 +1856,456 @@ class IDError(OneilError):
         return "IDError"
         
     def context(self) -> str | None:
-        return f"in model {self.model.name} (ID: {self.ID})"
+        return messages.text("context-id", "in model {model} (ID: {id})", model=self.model.name, id=self.ID)
+        
+    def message(self) -> str:
+        return f"{self.message_}"
+
+
+class ImportError(OneilError):
+    def __init__(self, filename, line_no, line, imprt, error):
+        self.filename = filename
+        self.line_no = line_no
+        self.imprt = imprt
+        self.error = error
+        
+    def kind(self) -> str:
+        return "ImportError"
+        
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
         
     def message(self) -> str:
-        return f"{self.message_}"
+        return messages.error_text(self.kind(), f"Failed to import '{self.imprt}': {self.error}. Does the import run by itself?", module=self.imprt, error=self.error)
+
+class ModelLoadingError(OneilError):
+    def __init__(self, filename: str, line_no: int, message: str):
+        self.filename = filename
+        self.line_no = line_no
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "ModelLoadingError"
+        
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+        
+    def message(self) -> str:
+        return self.message_
+
+class CircularDependencyError(OneilError):
+    def __init__(self, cycle: list[tuple[str, int, str]]):
+        # The use declarations in the cycle as (file, line number, line), each using the
+        # model declared in the next, and the last using the first
+        self.cycle = cycle
+        self.notes_ = [f"{line_context(file, line_no)}: {line.strip()}" for file, line_no, line in cycle]
+
+    def kind(self) -> str:
+        return "CircularDependencyError"
+
+    def context(self) -> str | None:
+        return line_context(self.cycle[0][0], self.cycle[0][1])
+
+    def message(self) -> str:
+        cycle = " -> ".join([file for file, _, _ in self.cycle] + [self.cycle[0][0]])
+        return messages.error_text(self.kind(), f"Models use each other in a cycle: {cycle}", cycle=cycle)
+
+    def related(self) -> list[dict]:
+        return [{"file": file, "line": line_no, "message": line.strip()} for file, line_no, line in self.cycle]
+
+class ModelNotFoundError(OneilError):
+    def __init__(self, filename: str):
+        self.filename = filename
+
+    def kind(self) -> str:
+        return "ModelNotFoundError"
+
+    def context(self) -> str | None:
+        return None
+
+    def message(self) -> str:
+        return messages.error_text(self.kind(), f"Model {self.filename} not found.", file=self.filename)
+
+class ModelError(OneilError):
+    def __init__(self, filename: str, source: list[str] = [], message: str = ""):
+        self.filename = filename
+        self.source = source
+        
+    def kind(self) -> str:
+        return "ModelError"
+        
+    def context(self) -> str | None:
+        return file_context(self.filename)
+        
+    def message(self) -> str:
+        return messages.error_text(self.kind(), f"Submodel not found (source: {' -> '.join(self.source)})", source=' -> '.join(self.source))
+
+class ImportedFunctionError(OneilError):
+    def __init__(self, parameter, error):
+        self.parameter = parameter
+        self.error = error
+
+    def kind(self) -> str:
+        return "ImportedFunctionError"
+        
+    def context(self) -> str | None:
+        return line_context(self.parameter.equation, self.parameter.line_no)
+        
+    def message(self) -> str:
+        return f"{self.error}"
+
+class OverrideError(OneilError):
+    def __init__(self, override: str, message: str):
+        self.override = override
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "OverrideError"
+
+    def context(self) -> str | None:
+        return messages.text("context-override", "in --set {override}", override=self.override)
+
+    def message(self) -> str:
+        return self.message_
+
+class ExternError(OneilError):
+    def __init__(self, model, parameters, message=None):
+        self.model = model
+        self.parameters = parameters
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "ExternError"
+
+    def context(self) -> str | None:
+        return file_context(self.model.name + ".on")
+
+    def message(self) -> str:
+        if self.message_ is not None:
+            return self.message_
+        return "No value was given for " + ", ".join(f"{parameter.id} (extern \"{parameter.extern}\")" for parameter in self.parameters) + "."
+
+    def notes(self) -> list[str]:
+        if self.message_ is not None:
+            return super().notes()
+        return super().notes() + [f"Give the values with --input-json <file>, or with {', '.join(externs.ENV_PREFIX + parameter.extern.upper() for parameter in self.parameters)} in the environment."]
+
+class EvaluationLimitError(OneilError):
+    MESSAGES = {
+        "time": "Evaluation took longer than the time limit ({limit} s).",
+        "depth": "The chain of dependencies is deeper than the depth limit ({limit} parameters).",
+    }
+    FLAGS = {"time": "--max-time", "depth": "--max-depth"}
+
+    def __init__(self, limit: str, value, parameter=None):
+        self.limit = limit
+        self.value = value
+        self.parameter = parameter
+        self.notes_ = [f"Raise the limit with {self.FLAGS[limit]} or \"{limit}\" in the [limits] section of the config file."]
+
+    def kind(self) -> str:
+        return "EvaluationLimitError"
+
+    def context(self) -> str | None:
+        return parameter_context(self.parameter) if self.parameter else None
+
+    def message(self) -> str:
+        return messages.error_text(self.kind(), self.MESSAGES[self.limit].format(limit=self.value), self.limit, limit=self.value)
+
+class ConfigError(OneilError):
+    def __init__(self, filename: str, message: str):
+        self.filename = filename
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "ConfigError"
+
+    def context(self) -> str | None:
+        return file_context(self.filename)
+
+    def message(self) -> str:
+        return self.message_
+
+class RefactorError(OneilError):
+    def __init__(self, filename: str, message: str):
+        self.filename = filename
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "RefactorError"
+
+    def context(self) -> str | None:
+        return file_context(self.filename)
+
+    def message(self) -> str:
+        return self.message_
+
+class PackError(OneilError):
+    def __init__(self, filename: str, message: str):
+        self.filename = filename
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "PackError"
+
+    def context(self) -> str | None:
+        return file_context(self.filename)
+
+    def message(self) -> str:
+        return self.message_
+
+class SweepError(OneilError):
+    def __init__(self, spec: str, message: str):
+        self.spec = spec
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "SweepError"
+
+    def context(self) -> str | None:
+        return messages.text("context-sweep", "in sweep {spec}", spec=self.spec)
+
+    def message(self) -> str:
+        return self.message_
+
+class ScaffoldError(OneilError):
+    def __init__(self, template: str, message: str):
+        self.template = template
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "ScaffoldError"
+
+    def context(self) -> str | None:
+        return messages.text("context-template", "in template {template}", template=self.template)
+
+    def message(self) -> str:
+        return self.message_
+
+class VerifyError(OneilError):
+    def __init__(self, filename: str, message: str):
+        self.filename = filename
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "VerifyError"
+
+    def context(self) -> str | None:
+        return file_context(self.filename)
+
+    def message(self) -> str:
+        return self.message_
+
+class ConsistencyError(OneilError):
+    def __init__(self, filename: str, problems: list[dict]):
+        # Inconsistencies between the resolved model and the evaluator (see oneil.consistency),
+        # each with its file, line, and message
+        self.filename = filename
+        self.problems = problems
+        self.notes_ = [f"{line_context(problem['file'], problem['line'])}: {problem['message']}" for problem in problems]
+
+    def kind(self) -> str:
+        return "ConsistencyError"
+
+    def context(self) -> str | None:
+        return file_context(self.filename)
+
+    def message(self) -> str:
+        return messages.error_text(self.kind(), f"The resolved model has {len(self.problems)} inconsistencies with the evaluator.", count=len(self.problems))
+
+# Warnings are reported with console.print_warning() rather than raised.
+class DeprecationWarning(OneilError):
+    severity = "warning"
+
+    def __init__(self, filename: str, line_no: int, message: str):
+        self.filename = filename
+        self.line_no = line_no
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "DeprecationWarning"
+
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+
+    def message(self) -> str:
+        return self.message_
+
+class PrecisionWarning(OneilError):
+    severity = "warning"
+
+    def __init__(self, filename: str, line_no: int, message: str):
+        self.filename = filename
+        self.line_no = line_no
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "PrecisionWarning"
+
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+
+    def message(self) -> str:
+        return self.message_
+
+class MirrorWarning(OneilError):
+    severity = "warning"
+
+    def __init__(self, filename: str, line_no: int, message: str):
+        self.filename = filename
+        self.line_no = line_no
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "MirrorWarning"
+
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+
+    def message(self) -> str:
+        return self.message_
+
+class EmptyIntervalWarning(OneilError):
+    severity = "warning"
+
+    def __init__(self, filename: str, line_no: int, message: str):
+        self.filename = filename
+        self.line_no = line_no
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "EmptyIntervalWarning"
+
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+
+    def message(self) -> str:
+        return self.message_
+
+class InfinityWarning(OneilError):
+    severity = "warning"
+
+    def __init__(self, filename: str, line_no: int, message: str):
+        self.filename = filename
+        self.line_no = line_no
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "InfinityWarning"
+
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+
+    def message(self) -> str:
+        return self.message_
+
+class FeatureWarning(OneilError):
+    severity = "warning"
+
+    def __init__(self, filename: str, line_no: int, message: str):
+        self.filename = filename
+        self.line_no = line_no
+        self.message_ = message
+
+    def kind(self) -> str:
+        return "FeatureWarning"
+
+    def context(self) -> str | None:
+        return line_context(self.filename, self.line_no)
+
+    def message(self) -> str:
+        return self.message_
 
+class UnusedImportWarning(OneilError):
+    severity = "warning"
 
-class ImportError(OneilError):
-    def __init__(self, filename, line_no, line, imprt, error):
+    def __init__(self, filename: str, line_no: int, message: str):
         self.filename = filename
         self.line_no = line_no
-        self.imprt = imprt
-        self.error = error
-        
+        self.message_ = message
+
     def kind(self) -> str:
-        return "ImportError"
-        
+        return "UnusedImportWarning"
+
     def context(self) -> str | None:
-        return f"in {self.filename} (line {self.line_no})"
-        
+        return line_context(self.filename, self.line_no)
+
     def message(self) -> str:
-        return f"Failed to import '{self.imprt}': {self.error}. Does the import run by itself?"
+        return self.message_
+
+class ShadowedBuiltinWarning(OneilError):
+    severity = "warning"
 
-class ModelLoadingError(OneilError):
     def __init__(self, filename: str, line_no: int, message: str):
         self.filename = filename
         self.line_no = line_no
         self.message_ = message
 
     def kind(self) -> str:
-        return "ModelLoadingError"
-        
+        return "ShadowedBuiltinWarning"
+
     def context(self) -> str | None:
-        return f"in {self.filename} (line {self.line_no})"
-        
+        return line_context(self.filename, self.line_no)
+
     def message(self) -> str:
         return self.message_
 
-class ModelError(OneilError):
-    def __init__(self, filename: str, source: list[str] = [], message: str = ""):
+class LintWarning(OneilError):
+    def __init__(self, filename: str, line_no: int, lint: str, message: str, severity: str = "warning", fix: str | None = None):
         self.filename = filename
-        self.source = source
-        
+        self.line_no = line_no
+        self.lint = lint
+        self.message_ = message
+        # "note" for low-severity lints like spelling
+        self.severity = severity
+        # The line that replaces line_no, if the lint can be fixed automatically
+        # (`oneil lint --fix`)
+        self.fix = fix
+
     def kind(self) -> str:
-        return "ModelError"
-        
+        return "LintNote" if self.severity == "note" else "LintWarning"
+
     def context(self) -> str | None:
-        return f"in {self.filename}"
-        
+        return line_context(self.filename, self.line_no)
+
     def message(self) -> str:
-        return f"Submodel not found (source: {' -> '.join(self.source)})"
+        return f"{self.message_} [{self.lint}]"
 
-class ImportedFunctionError(OneilError):
-    def __init__(self, parameter, error):
-        self.parameter = parameter
-        self.error = error
+# Limits on the size of an expression, well below where Python's parser and compiler
+# (and Oneil's own recursive helpers) run out of stack on deeply nested or very long
+# expressions.
+MAX_EXPRESSION_NESTING = 100
+MAX_EXPRESSION_OPERATORS = 500
 
-    def kind(self) -> str:
-        return "ImportedFunctionError"
-        
-    def context(self) -> str | None:
-        return f"in {self.parameter.equation} (line {self.parameter.line_no})"
-        
-    def message(self) -> str:
-        return f"{self.error}"
+def check_expression_size(line, file_name, line_no):
+    """
+    Raise a SyntaxError if a line's brackets are nested too deeply or it has too many
+    operators to parse safely.
+    """
+    depth = max_depth = 0
+    for character in line:
+        if character in "([{":
+            depth += 1
+            max_depth = max(max_depth, depth)
+        elif character in ")]}":
+            depth = max(depth - 1, 0)
+    if max_depth > MAX_EXPRESSION_NESTING:
+        raise SyntaxError(file_name, line_no, line, f"Expressions can't be nested more than {MAX_EXPRESSION_NESTING} levels deep (found {max_depth}).")
+
+    operators = len(re.findall(r"\*\*|//|==|!=|[<>]=?|[-+*/%]|\b(?:and|or|not)\b", line))
+    if operators > MAX_EXPRESSION_OPERATORS:
+        raise SyntaxError(file_name, line_no, line, f"Expressions can't have more than {MAX_EXPRESSION_OPERATORS} operators (found {operators}). Split the expression into intermediate parameters.")
+
+def check_test_syntax(line, file_name, line_no):
+    if not re.search(r"^(\*{1,2}\s*)?test\s*(\"[^\"]*\"\s*)?(\{\w+(,\s*\w+)*\})?:.*$", line):
+        raise SyntaxError(file_name, line_no, line, "Tests must be of the form \"test \"<name>\" {<input 1>, <input 2>, ... ,<input n>}: <expression>\" where the name and {<input 1>, <input 2>, ... ,<input n>} are optional, each <input> consists of word characters only, and <expression> is a valid python expression with valid parameters and constants.")
+
+def test_colon(line):
+    """
+    The index of the colon that starts a test's expression, after the test's name.
+    """
+    name = re.search(r'^(?:\*{1,2}\s*)?test\s*"[^"]*"', line)
+    return line.index(":", name.end() if name else 0)
 
 class Test:
     def __init__(self, line, line_no, model, section=""):
@@ -940,6 +2314,11 @@ class Test:
         self.notes = []
         self.note_line_nos = []
         self.section = section
+        self.annotations = {}
+        self.passed = None
+        # For tests from ```oneil-test blocks, whose notes have it: the parameter or
+        # design value's ID, the test's label, or the model's name
+        self.doc = None
 
         # Parse the line
         if line[0] == '*':
@@ -949,27 +2328,40 @@ class Test:
             self.trace = False
             self.line = line
         
-        if '{' in line.split(':')[0]:
+        # The optional name (`test "name": ...`) can contain colons, so split after it.
+        colon = test_colon(line)
+        name = re.search(r'^(?:\*{1,2}\s*)?test\s*"([^"]*)"', line)
+        self.name = name.group(1).strip() if name else None
+        preamble = line[name.end():colon] if name else line[:colon]
+
+        if '{' in preamble:
             try:
-                self.refs = [l.strip() for l in line.split(':')[0].split('{')[1].split('}')[0].split(',')]
+                self.refs = [l.strip() for l in preamble.split('{')[1].split('}')[0].split(',')]
             except:
                 raise SyntaxError(model, line_no, line, "Invalid syntax for test references.")
         else:
             self.refs = []
 
-        self.expression = line.split(':')[1].strip()
+        self.expression = line[colon + 1:].split(':')[0].strip()
 
         if not self.expression:
             raise SyntaxError(model, line_no, line, "Empty test expression.")
 
         for old, new in FUNCTIONS.items():
             if "." + old not in self.expression:
-                self.expression = self.expression.replace(old + "(", new + "(")
+                self.expression = re.sub(r"(?<!\w)" + re.escape(old) + r"\(", new + "(", self.expression)
         for old, new in OPERATOR_OVERRIDES.items():
             self.expression = self.expression.replace(old, new)
 
         self.args = [x for x in re.findall(r"\b(?!\d+)(?<!')(?<!\")\b\w+\.?\w*\b(?!')(?!\")\b", self.expression) if x not in FUNCTIONS.values() and x not in BOOLEAN_OPERATORS]
 
+    def label(self):
+        """
+        How the test is referred to in results and diagnostics: its name, or its expression
+        if it doesn't have one.
+        """
+        return f'"{self.name}"' if self.name else self.expression
+
 
 class Parameter:
     def __init__(self, equation, units, id, hr_units="", model="", line_no=None, line="", name=None, options=None, performance=False, trace=False, section="", arguments=[], pointer=False, fallback_param=None):
@@ -995,9 +2387,35 @@ class Parameter:
         self.pointer = pointer
         self.piecewise = True if isinstance(equation, list) else False
         self.minmax_equation = False
+        # Whether the parameter's value was written as an interval literal (`[2.1, 2.4]`)
+        self.interval = isinstance(equation, IntervalLiteral)
         self.hr_units = hr_units
+        # Whether the parameter is an absolute temperature or a temperature difference
+        # ("absolute" or "difference"), or None if it's neither or that isn't known
+        self.temperature = un.temperature(hr_units)
+        # Whether the parameter is in dB, so sums and differences with it are done in dB
+        self.logarithmic = un.logarithmic(hr_units)
         self.used_fallback = False  # Track if fallback was used for this parameter
+        # Where the parameter's value became an empty interval, if it is one (see
+        # Model._empty_origin())
+        self.empty = None
+        # Where the parameter's value became infinite from finite inputs, if it did (see
+        # Model._apply_infinity_policy())
+        self.unbounded = None
+        # Which ends of its inputs' ranges give its min and max, if they're tracked (see
+        # oneil.corners)
+        self.corners = None
+        # How many iterations the cycle through the parameter took, if it's iterated
+        # (see oneil.ordering)
+        self.iterations = None
+        # The error that kept the parameter from being calculated, in a model loaded with
+        # `partial` (see Model._fail())
+        self.failure = None
         
+        # The name of the external input that gives the parameter's value, if it's bound
+        # to one (see oneil.externs)
+        self.extern = None
+
         # Fallback Parameter (used if primary callable fails)
         # This is a complete Parameter that gets calculated using normal flow
         self.fallback_param = fallback_param
@@ -1010,6 +2428,13 @@ class Parameter:
         # note
         self.notes = []
         self.note_lines = []
+        self.annotations = {}
+        # The branches of a piecewise equation after the first, as written, and their lines
+        self.piece_lines = []
+        self.piece_line_nos = []
+
+        # The parameter this one forwards to, if it's an alias (`ID := target`)
+        self.alias = None
 
         # options
         if options:
@@ -1041,7 +2466,13 @@ class Parameter:
             self.independent = True
             self.pointer = False
         elif isinstance(equation, str):
-            if any(character in EQUATION_OPERATORS + list(OPERATOR_OVERRIDES.keys()) for character in equation):
+            if externs.name(equation) is not None:
+                # Bound to an external input, which gives its value when the model is
+                # loaded (see Model._bind_externs())
+                self.extern = externs.name(equation)
+                self.independent = True
+                self.pointer = False
+            elif any(character in EQUATION_OPERATORS + list(OPERATOR_OVERRIDES.keys()) for character in equation):
                 # Find parameter names including "." imports (in equation order)
                 self.args = [x for x in re.findall(r"(?!\d+)\w+\.?\w*", re.sub('[\'|\"].*[\'|\"]','',equation)) if x not in FUNCTIONS]
 
@@ -1050,7 +2481,7 @@ class Parameter:
 
                 for old, new in FUNCTIONS.items():
                     if "." + old not in equation:
-                        equation = equation.replace(old + "(", new + "(")
+                        equation = re.sub(r"(?<!\w)" + re.escape(old) + r"\(", new + "(", equation)
                 for old, new in OPERATOR_OVERRIDES.items():
                     equation = equation.replace(old, new)
 
@@ -1092,7 +2523,7 @@ class Parameter:
         if not piece_args:
             raise ParameterError("Piecewise parameters must be dependent on another parameter.", self)
         else:
-            self.args = list(set(self.args + piece_args))
+            self.args = list(dict.fromkeys(self.args + piece_args))
 
     def assign(self, value):
         if value is not None:
@@ -1108,11 +2539,17 @@ class Parameter:
                     value.equation = None
                     value.isdiscrete = True
                 else:
-                    raise ParameterError(f"Parameter was given a value that is not among its options: {value.options}", self)
+                    raise LimitError(f"Parameter was given a value that is not among its options: {value.options}", self)
                 
             if value.min is not None and value.max is not None:
                 if value.units != self.units:
                     raise UnitEvaluationError(f"Input or calculated units ({value.units}) do not match the required units: ({self.units}).", [self, value]).with_context(self)
+                if self.temperature and value.temperature and value.temperature != self.temperature:
+                    if value.temperature == "difference":
+                        message = f"The calculated value is a temperature difference, but the parameter is an absolute temperature ({self.hr_units}). Give it units of temperature difference (Δ{self.hr_units.lstrip('Δ')}) or K."
+                    else:
+                        message = f"The calculated value is an absolute temperature, but the parameter is a temperature difference ({self.hr_units}). Give it units of absolute temperature ({self.hr_units.lstrip('Δ')}) or K."
+                    raise UnitEvaluationError(message, [self, value]).with_context(self)
                 self.min = value.min
                 self.max = value.max
             elif not value.independent:
@@ -1130,34 +2567,51 @@ class Parameter:
             
             if value.model: self.model = value.model
 
-            self.line_no = {'model line': self.line_no, 'design line': value.line_no}
-            self.line = {'model line': self.line, 'design line': value.line}
-            self.notes = value.notes
-            self.note_lines = value.note_lines
-            self.section = value.section
+            # Keep the model line from the first write, so recalculating doesn't nest it.
+            self.line_no = {'model line': self.line_no['model line'] if isinstance(self.line_no, dict) else self.line_no, 'design line': value.line_no}
+            self.line = {'model line': self.line['model line'] if isinstance(self.line, dict) else self.line, 'design line': value.line}
+            # Calculated values have no notes and aren't in a section, so they keep the
+            # parameter's.
+            if value.notes:
+                self.notes = value.notes
+                self.note_lines = value.note_lines
+            self.section = value.section or self.section
             self.isdiscrete = value.isdiscrete
             self.trace = value.trace
         elif isinstance(value, tuple):
             if self.isdiscrete:
                 raise ParameterError("Multiple discrete values aren't supported.", self)
+            if nominal and all(isinstance(v, (int, float)) and not isinstance(v, (bool, np.bool_)) for v in value):
+                value = ((value[0] + value[1]) / 2,) * 2
             self.write_one(value[0], "min")
             self.write_one(value[1], "max")
         else:
             self.write_one(value, "minmax")
 
-        if self.min and self.max:
+        if is_empty(self):
+            # An empty interval has no values to check against the limits. See
+            # Model.empty_report() for where it came from.
+            _note_empty(self)
+            return
+        if infinity.is_infinite(self):
+            _note_infinite(self)
+
+        # Interval literals are checked even if a bound is 0 (`[0, 2.4]`).
+        if (self.min and self.max) or (self.interval and self.min is not None and self.max is not None):
             if self.min > self.max:
                 raise ParameterError("Parameter min is greater than Parameter max.", self)
 
-            if self.options and self.min and self.max:
+            if self.options:
                 if self.isdiscrete:
                     if not (self.min in self.options and self.max in self.options):
-                        raise ParameterError(f"Parameter was given a value that is not among its options: {self.options}", self)
+                        raise LimitError(f"Parameter was given a value that is not among its options: {self.options}", self)
                 else:
                     if not self.options[1] >= self.options[0]:
                         raise ParameterError("Minimum limit > maximum limit.", self)
                     if not (self.min >= self.options[0] and self.max <= self.options[1]):
-                        raise ParameterError(f"Values out of bounds [{un.hr_vals_and_units(self.options,self.units,self.hr_units)}]. Revise values or limits.", self)
+                        if self.interval:
+                            raise LimitError(f"The interval {self.human_readable()} is out of bounds [{un.hr_vals_and_units(self.options,self.units,self.hr_units)}]. Revise the interval or limits.", self)
+                        raise LimitError(f"Values out of bounds [{un.hr_vals_and_units(self.options,self.units,self.hr_units)}]. Revise values or limits.", self)
 
     def write_one(self, value, minmax):
 
@@ -1177,7 +2631,7 @@ class Parameter:
                 self.max = bool(value)
         elif isinstance(value, str):
             if value not in self.options:
-                raise ParameterError(f"Parameter was assigned an option that is not among its options: {self.options}", self)
+                raise LimitError(f"Parameter was assigned an option that is not among its options: {self.options}", self)
             
             if minmax == "minmax":
                 self.min = self.max = value
@@ -1289,29 +2743,135 @@ class Parameter:
         if self.isdiscrete:
             return f"{self.min} | {self.max}"
         else:
-            if not pref and self.hr_units is not None:
+            if not pref and self.hr_units is not None and un.UNIT_STYLE != "base":
                 pref = self.hr_units
             if self.min is not None and self.max is not None:
                 if isinstance(self.min, str):
                     return self.min if self.min == self.max else f"{self.min} | {self.max}"
                 else:
-                    return un.hr_vals_and_units((self.min,self.max), self.units, pref, sigfigs)
+                    return un.hr_vals_and_units((self.min,self.max), self.units, pref, sigfigs, interval=self.interval)
             else:
                 return "None"
 
+    def result(self):
+        """
+        The parameter's name, section, value, and units for exported results. Values are in
+        the parameter's display units, and are None if the parameter hasn't been calculated.
+        """
+        result = {"name": self.name, "section": self.section or None, "tags": annotated_tags(self.annotations)}
+        if self.min is None or self.max is None:
+            result.update({"min": None, "max": None, "units": self.hr_units or None})
+        elif self.isdiscrete or isinstance(self.min, str):
+            result.update({"min": self.min, "max": self.max, "units": None})
+        else:
+            (result["min"], result["max"]), result["units"] = un.hr_values((self.min, self.max), self.units, self.hr_units or None)
+            result["units"] = result["units"] or None
+        return result
+
+    def limits(self):
+        """
+        The parameter's limits and its margins to them (the distance from its min to the
+        lower limit and from its max to the upper limit) in its display units, and whether
+        it's within them. Infinite limits and their margins are None. Discrete parameters
+        have their options as limits and no margins.
+        """
+        if not self.options or self.min is None or self.max is None:
+            return {"limits": None, "margins": None, "within_limits": None}
+        if self.isdiscrete or isinstance(self.min, str):
+            return {"limits": list(self.options), "margins": None, "within_limits": self.min in self.options and self.max in self.options}
+
+        result = self.result()
+        (lower, upper), _ = un.hr_values(tuple(self.options), self.units, result["units"])
+        finite = lambda limit: limit if np.isfinite(limit) else None
+        return {
+            "limits": {"min": finite(lower), "max": finite(upper)},
+            "margins": {
+                "lower": result["min"] - lower if np.isfinite(lower) else None,
+                "upper": upper - result["max"] if np.isfinite(upper) else None,
+            },
+            "within_limits": bool(self.options[0] <= self.min and self.max <= self.options[1]),
+        }
+
+    def source(self, filename, encoding="utf-32"):
+        """
+        Where the parameter is defined: the file (the model file given, or the fragment it
+        was included from), the line, and the 1-based columns of its ID in that line
+        (`end_column` is exclusive), counted in the given encoding (see oneil.positions).
+        """
+        line_no = self.line_no['model line'] if isinstance(self.line_no, dict) else self.line_no
+        line = self.line['model line'] if isinstance(self.line, dict) else self.line
+        if isinstance(line_no, IncludedLine):
+            filename = os.path.relpath(line_no.fragment)
+        source = {"file": filename, "line": int(line_no) if line_no is not None else None}
+
+        # Columns are found in the line as written, since the parsed line has lost its
+        # `$` or `*` prefix.
+        if line_no is not None:
+            line = positions.source_line(filename, int(line_no)) or line
+        match = re.search(r"(?<![\w.])" + re.escape(self.id) + r"\s*(:=|=>|=)", line or "")
+        if match:
+            source.update({
+                "start_column": positions.column_of(line, match.start(), encoding) + 1,
+                "end_column": positions.column_of(line, match.start() + len(self.id), encoding) + 1,
+            })
+        return source
+
     def copy(self):
         return Parameter((self.min, self.max), self.units, "copy of " + self.name, model=self.model, line_no=self.line_no, line=self.line)
 
     def __str__(self):
         return self.human_readable(4)
 
+    def _temperature_of(self, other, subtract):
+        """
+        Whether the sum or difference of two temperatures is an absolute temperature or a
+        temperature difference (see Parameter.temperature): the difference of two absolute
+        temperatures is a difference, and an absolute temperature plus or minus a
+        difference is absolute. Adding two absolute temperatures, or subtracting one from
+        a difference, is a UnitEvaluationError.
+        """
+        kinds = (self.temperature, other.temperature)
+        if kinds == ("absolute", "absolute"):
+            if not subtract:
+                raise UnitEvaluationError(f"Cannot add two absolute temperatures ({self.id} and {other.id}). Subtract them for a temperature difference, or give one units of temperature difference (e.g. Δ°C).", [self, other])
+            return "difference"
+        if kinds == ("difference", "absolute") and subtract:
+            raise UnitEvaluationError(f"Cannot subtract an absolute temperature ({other.id}) from a temperature difference ({self.id}).", [self, other])
+        if "absolute" in kinds:
+            return "absolute"
+        return "difference" if kinds == ("difference", "difference") else None
+
+    def _decibels(self, other, subtract, standard=False):
+        """
+        The sum or difference of parameters in dB, which is the product or quotient of
+        their linear values (which is how they're kept). A parameter in dB and a linear one
+        can't be added or subtracted, and neither can two in dB with units, like two powers
+        in dBmW, since that would multiply the powers. Numbers are still added to the
+        linear value of a parameter in dB.
+        """
+        if not (self.logarithmic and other.logarithmic):
+            logarithmic, linear = (self, other) if self.logarithmic else (other, self)
+            raise UnitEvaluationError(f"Cannot {'subtract' if subtract else 'add'} {logarithmic.id}, in dB, and {linear.id}, which is linear. Give {linear.id} units in dB, or use linear({logarithmic.id}) for linear math.", [self, other])
+        if not subtract and self.units and other.units:
+            raise UnitEvaluationError(f"Cannot add {self.id} and {other.id}, which are both in dB with units, since that would multiply them. Use linear({self.id}) + linear({other.id}) to add their linear values.", [self, other])
+        if subtract:
+            result = self // other if standard else self / other
+        else:
+            result = self * other
+        result.logarithmic = True
+        return result
+
     # "+" Addition, left-hand, all cases 
     @err.add_trace
     def __add__(self, other):
+        if isinstance(other, Parameter) and (self.logarithmic or other.logarithmic):
+            return self._decibels(other, subtract=False)
         if isinstance(other, Parameter):
             if self.units != other.units:
                 raise UnitEvaluationError(f"Cannot add {un.hr_units(other.units)} to {un.hr_units(self.units)}.", [self, other])
-            return Parameter((self.min + other.min, self.max + other.max), self.units, "({}) + ({})".format(self.id, other.id))
+            result = Parameter((self.min + other.min, self.max + other.max), self.units, "({}) + ({})".format(self.id, other.id))
+            result.temperature = self._temperature_of(other, subtract=False)
+            return result
         elif self.units == {}:
             return Parameter((self.min + other, self.max + other), {}, "({}) + ({})".format(self.id, str(other)))
         else:
@@ -1320,12 +2880,16 @@ class Parameter:
     # "-" Subtraction, left-hand, extreme
     @err.add_trace
     def __sub__(self, other):
+        if isinstance(other, Parameter) and (self.logarithmic or other.logarithmic):
+            return self._decibels(other, subtract=True)
         if isinstance(other, Parameter):
             if self.units != other.units:
                 raise UnitEvaluationError(f"Cannot subtract {un.hr_units(other.units)} from {un.hr_units(self.units)}.", [self, other])
             if self.id == other.id and self.model == other.model: 
                 return Parameter(0, {}, "({}) - ({})".format(self.id, other.id))
-            return Parameter((self.min - other.max, self.max - other.min), self.units, "({}) - ({})".format(self.id, other.id))
+            result = Parameter((self.min - other.max, self.max - other.min), self.units, "({}) - ({})".format(self.id, other.id))
+            result.temperature = self._temperature_of(other, subtract=True)
+            return result
         elif self.units == {}:
             return Parameter((self.min - other, self.max - other), {}, "({}) - ({})".format(self.id, str(other)))
         else:
@@ -1334,14 +2898,18 @@ class Parameter:
     # "--" Subtraction, left-hand, standard
     @err.add_trace
     def _minus(self, other):
+        if isinstance(other, Parameter) and (self.logarithmic or other.logarithmic):
+            return self._decibels(other, subtract=True, standard=True)
         if isinstance(other, Parameter):
             if self.units != other.units:
                 raise UnitEvaluationError(f"Cannot subtract {un.hr_units(other.units)} from {un.hr_units(self.units)}.", [self, other])
             results = [self.min - other.min, self.max - other.max]
-            return Parameter((min(results), max(results)), self.units, "({}) -- ({})".format(self.id, other.id))
+            result = Parameter(interval(results), self.units, "({}) -- ({})".format(self.id, other.id))
+            result.temperature = self._temperature_of(other, subtract=True)
+            return result
         elif self.units == {}:
             results = [self.min - other, self.max - other]
-            return Parameter((min(results), max(results)), {}, "({}) -- ({})".format(self.id, str(other)))
+            return Parameter(interval(results), {}, "({}) -- ({})".format(self.id, str(other)))
         else:
             raise UnitEvaluationError(f"Cannot subtract a unitless number from {un.hr_units(self.units)}.", [self, other])
 
@@ -1358,10 +2926,10 @@ class Parameter:
                 else:
                     new_units[k] = v
             results = [self.min * other.min, self.max * other.max]
-            return Parameter((min(results), max(results)), new_units, "({}) * ({})".format(self.id, other.id))
+            return Parameter(interval(results), new_units, "({}) * ({})".format(self.id, other.id))
         elif isinstance(other, (int, float)):
             results = [self.min * other, self.max * other]
-            return Parameter((min(results), max(results)), self.units, "({}) * ({})".format(self.id, str(other)))
+            return Parameter(interval(results), self.units, "({}) * ({})".format(self.id, str(other)))
         else:
             TypeError("Multiplication must be between two Parameters or a Parameter and a number.")
 
@@ -1384,10 +2952,10 @@ class Parameter:
             except FloatingPointError:
                 raise DivideByZeroError(other)
             else:
-                return Parameter((min(results), max(results)), new_units, "({}) / ({})".format(self.id, other.id))
+                return Parameter(interval(results), new_units, "({}) / ({})".format(self.id, other.id))
         elif isinstance(other, (int, float)):
             results = [self.min / other, self.max / other]
-            return Parameter((min(results), max(results)), self.units, "({}) / ({})".format(self.id, str(other)))
+            return Parameter(interval(results), self.units, "({}) / ({})".format(self.id, str(other)))
         else:
             raise TypeError("Division must be between two Parameters or a Parameter and a number.")
 
@@ -1408,10 +2976,10 @@ class Parameter:
             except FloatingPointError:
                 raise DivideByZeroError(other)
             else:
-                return Parameter((min(results), max(results)), new_units, "({}) // ({})".format(self.id, other.id))
+                return Parameter(interval(results), new_units, "({}) // ({})".format(self.id, other.id))
         elif isinstance(other, (int, float)):
             results = [self.min / other, self.max / other]
-            return Parameter((min(results), max(results)), self.units, "({}) // ({})".format(self.id, str(other)))
+            return Parameter(interval(results), self.units, "({}) // ({})".format(self.id, str(other)))
         else:
             raise TypeError("Division must be between two Parameters or a Parameter and a number.")
 
@@ -1422,12 +2990,12 @@ class Parameter:
             if self.min != self.max or other.units != {}:
                 raise UnitEvaluationError(f"Exponent must be a single unitless Parameter or number.", [self, other])
             new_units = {k: v * other.min for k, v in self.units.items()}
-            results = [self.min**other.min, self.max**other.max, self.min**other.max, self.max**other.min]
-            return Parameter((min(results), max(results)), new_units, "({})**({})".format(self.id, other.id))
+            results = [deterministic.rounded(result) for result in (self.min**other.min, self.max**other.max, self.min**other.max, self.max**other.min)]
+            return Parameter(interval(results), new_units, "({})**({})".format(self.id, other.id))
         elif isinstance(other, (int, float)):
             new_units = {k: v * other for k, v in self.units.items()}
-            results = [self.min**other, self.max**other]
-            return Parameter((min(results), max(results)), new_units, "({})**({})".format(self.id, str(other)))
+            results = [deterministic.rounded(self.min**other), deterministic.rounded(self.max**other)]
+            return Parameter(interval(results), new_units, "({})**({})".format(self.id, str(other)))
         else:
             raise TypeError("Exponent must be a single unitless Parameter or number.")
 
@@ -1469,7 +3037,7 @@ class Parameter:
     def __rmul__(self, other):
         if isinstance(other, (int, float)):
             results = [self.min * other, self.max * other]
-            return Parameter((min(results), max(results)), self.units, "({})({})".format(str(other), self.id))
+            return Parameter(interval(results), self.units, "({})({})".format(str(other), self.id))
         else:
             raise TypeError("Multiplication must be between a Parameter and a number.")
 
@@ -1497,13 +3065,18 @@ class Parameter:
         if isinstance(other, (int, float)):
             if self.units != {}:
                 raise UnitEvaluationError(f"Exponent must be a single unitless Parameter or number.", [self, other])
-            return Parameter((other**self.min, other**self.max), {}, "({})**({})".format(str(other), self.id))
+            return Parameter((deterministic.rounded(other**self.min), deterministic.rounded(other**self.max)), {}, "({})**({})".format(str(other), self.id))
         else:
             raise TypeError("Exponentiation must be between a Parameter and a number.")
 
     # "-" Unary operator
     @err.add_trace
     def __neg__(self):
+        if self.logarithmic:
+            # Negating a value in dB inverts its linear value
+            result = 1 / self
+            result.logarithmic = True
+            return result
         return Parameter((-self.max, -self.min), self.units, "-({})".format(self.id))
 
     # "<" Less than, left-hand, all cases
@@ -1569,12 +3142,12 @@ class Parameter:
             if self.units != other.units:
                 message = f"Cannot compare {un.hr_units(self.units)} to {un.hr_units(other.units)} (equal)."
                 raise UnitEvaluationError(message, [self, other])
-            return self.min == other.min and self.max == other.max
+            return _equal(self.min, other.min) and _equal(self.max, other.max)
         elif isinstance(other, (int, float)):
             if other != 0 and self.units != {}:
                 message = f"Cannot compare {un.hr_units(self.units)} to a unitless number (equal)."
                 raise UnitEvaluationError(message, [self, other])
-            return self.min == other and self.max == other
+            return _equal(self.min, other) and _equal(self.max, other)
         elif isinstance(other, str):
             return self.min == other
         elif isinstance(other, bool):
@@ -1589,12 +3162,12 @@ class Parameter:
             if self.units != other.units:
                 message = f"Cannot compare {un.hr_units(self.units)} to {un.hr_units(other.units)} (not equal)."
                 raise UnitEvaluationError(message, [self, other])
-            return self.min != other.min and self.max != other.max
+            return not _equal(self.min, other.min) and not _equal(self.max, other.max)
         elif isinstance(other, (int, float)):
             if other != 0 and self.units != {}:
                 message = f"Cannot compare {un.hr_units(self.units)} to a unitless number (not equal)."
                 raise UnitEvaluationError(message, [self, other])
-            return self.min != other and self.max != other
+            return not _equal(self.min, other) and not _equal(self.max, other)
         elif isinstance(other, str):
             return self.min == other
         else:
@@ -1730,32 +3303,167 @@ class Parameter:
 
 _function_cache = FunctionCache(lambda: Parameter)
 
+# Submodels resolved while evaluating a batch of models (see `shared_submodels`), by path.
+_shared_submodels = None
+
+# The models being parsed, outermost first, as [path, the use declaration being loaded,
+# whether it's loaded with `partial`]
+_loading = []
+
+# The first operation (the ID of its result, like "(a) -- (b)") that produced an empty
+# interval while the current parameter was calculated, or None
+_first_empty = None
+
+# Why an operation produced an empty interval, by the operator in its result's ID
+EMPTY_REASONS = [
+    (r"\)\s*(-|--|\|minus\|)\s*\(", "subtracting infinite bounds of the same sign (inf - inf)"),
+    (r"\)\s*\+\s*\(", "adding infinite bounds of opposite signs (inf + -inf)"),
+    (r"\)\s*\*\s*\(", "multiplying a zero bound by an infinite one (0 * inf)"),
+    (r"\)\s*//?\s*\(", "dividing zero by zero or an infinite bound by another (inf / inf)"),
+]
+
+def is_empty(parameter):
+    """
+    Whether a parameter's value is an empty interval: a bound isn't a number (NaN), so
+    there are no values between them.
+    """
+    return any(isinstance(value, float) and math.isnan(value) for value in (parameter.min, parameter.max))
+
+def interval(results):
+    """
+    The smallest interval containing the results of an operation on the bounds of its
+    operands, or an empty interval (NaN bounds) if a result isn't a number. (min() and
+    max() would silently drop a NaN, depending on where it is in the results.)
+    """
+    if any(isinstance(result, float) and math.isnan(result) for result in results):
+        return (math.nan, math.nan)
+    return (min(results), max(results))
+
+def empty_reason(expression):
+    """
+    Why the operation with the given result ID produced an empty interval.
+    """
+    for pattern, reason in EMPTY_REASONS:
+        if re.search(pattern, expression):
+            return reason
+    return "a result that isn't a number (NaN)"
+
+def _note_empty(parameter):
+    global _first_empty
+    if _first_empty is None:
+        _first_empty = parameter.id
+
+# The first operation that produced an infinite bound while the current parameter was
+# calculated, or None (see oneil.infinity)
+_first_infinite = None
+
+def infinite_reason(expression):
+    """
+    Why the operation with the given result ID produced an infinite bound.
+    """
+    if re.search(r"\)\s*//?\s*\(", expression):
+        return "dividing by a range that reaches zero"
+    return "a result too large for a float (overflow)"
+
+def _note_infinite(parameter):
+    global _first_infinite
+    if _first_infinite is None:
+        _first_infinite = parameter.id
+
+def use_submodel(filename, use):
+    """
+    Load the submodel a use declaration (file, line number, line) names, raising a
+    CircularDependencyError if it's one of the models being loaded.
+    """
+    path = os.path.abspath(filename)
+    paths = [loading[0] for loading in _loading]
+    if path in paths:
+        raise CircularDependencyError([loading[1] for loading in _loading[paths.index(path):-1]] + [use])
+    if _loading:
+        _loading[-1][1] = use
+    # The submodels of a model loaded with `partial` are loaded with it too
+    return load_submodel(filename, partial=bool(_loading) and _loading[-1][2])
+
+def load_submodel(filename, partial=False):
+    if _shared_submodels is None:
+        return Model(filename, partial=partial)
+
+    path = os.path.abspath(filename)
+    if path not in _shared_submodels:
+        _shared_submodels[path] = Model(filename, partial=partial)
+    return _shared_submodels[path]
+
+@contextlib.contextmanager
+def shared_submodels():
+    """
+    Load and calculate each submodel only once while the context is active. Only use this
+    when no designs or overrides are applied, since those write into the shared submodels.
+    """
+    global _shared_submodels
+    previous, _shared_submodels = _shared_submodels, {}
+    try:
+        yield _shared_submodels
+    finally:
+        _shared_submodels = previous
+
 
 class Model:
-    def __init__(self, model_filename, design_filename=None):
+    def __init__(self, model_filename, design_filename=None, partial=False):
         self.model_filename = os.path.abspath(model_filename)
+        # With `partial`, errors in resolving, unit checking, and evaluating parameters and
+        # tests are collected (see errors()) rather than raised, and only the parameters
+        # and tests they affect are left out (see _fail())
+        self.partial = partial
+        self.diagnostics = []
+        self._load_diagnostics = 0
         _function_cache.set_cache_file(self.model_filename)
         
-        self.note, self.parameters, self.submodels, self.tests, _ = parse_file(model_filename)
+        _loading.append([self.model_filename, None, partial])
+        try:
+            # The units the model declares, which its designs can use too
+            with un.scope() as self.units:
+                self.note, self.parameters, self.submodels, self.tests, _, self.annotations, self.features = parse_file(model_filename)
+        finally:
+            _loading.pop()
 
         self.name = model_filename.replace(".on", "")
         self.design = "default"
         self.constants = MATH_CONSTANTS
         self.calculated = False
+        # Parameters over their `@precision` budget (see precision_report())
+        self.over_budget = []
+        # Parameters whose values differ from the Python constants they `@mirror` (see
+        # mirror_report())
+        self.mirror_drift = []
+        # Empty intervals, by where they came from (see empty_report())
+        self.empty_intervals = []
+        # Values that became infinite from finite inputs (see infinity_report())
+        self.unbounded = []
         self.defaults = []
         self.fail_count = 0
         self.test_count = 0
+        self.overrides = []
+        self.design_files = []
+
+        self._resolve_aliases()
         
         if design_filename:
             self.overwrite(design_filename)
+        self._bind_externs()
 
         try:
             self._check_namespace()
         except OneilError as e:
             raise e.with_note(f"In namespace check for {self.name}")
 
+        self.deprecated_uses = self._check_deprecations()
+        for use in self.deprecated_uses:
+            console.print_warning(use["warning"])
+        for warning in self._check_features() + self._check_imports() + self._check_shadowing():
+            console.print_warning(warning)
+
         for key, param in self.parameters.items():
-            if param.pointer:
+            if param.pointer and not param.alias:
                 if param.piecewise:
                     for eq, cond in param.equation:
                         if eq.pointer:
@@ -1779,7 +3487,186 @@ class Model:
                             param.args.append(param.equation)
                         else:
                             raise ParameterError("Parameter " + param.id + " (line " + str(param.line_no + 1) + ") in " + param.model + " has a string, non-equation assignment (" + param.equation + ") that is not in the model and has no options defined. If it's supposed to be a case, specify options. If it's supposed to be assigned to another value, make sure that value is also defined.", param)
-        
+
+        self._check_piecewise_units()
+        self._check_function_units()
+        self._check_test_inputs()
+        if consistency.enabled():
+            consistency.verify(self)
+
+        # Find every unit error before evaluating, so the parameters with one are left out
+        # rather than found one at a time
+        if partial:
+            for error in check.unit_errors(self):
+                if isinstance(error.context_, Parameter) and error.context_.failure is None:
+                    self._fail([error.context_], error)
+        # The errors found while loading, which stay when the model is evaluated again
+        self._load_diagnostics = len(self.diagnostics)
+
+    def _bind_externs(self):
+        """
+        Write the values of the parameters bound to external inputs (`extern("<name>")`)
+        from --input-json or the environment (see oneil.externs). The parameters without
+        a value are listed in one ExternError.
+        """
+        missing = []
+        for parameter in self.parameters.values():
+            if parameter.extern is None or parameter.min is not None:
+                continue
+            value = externs.lookup(parameter.extern)
+            if value is None:
+                missing.append(parameter)
+                continue
+            try:
+                written = parse_override(f"{parameter.id}={externs.text(value, parameter.hr_units)}")
+                if not written.independent or (not written.isdiscrete and written.units != parameter.units):
+                    raise ValueError(f"Must be a value in units that match {un.hr_units(parameter.units, [1, 1]) or 'unitless'}, not {externs.text(value, parameter.hr_units)}.")
+                parameter.write(written.min if parameter.isdiscrete or written.isdiscrete else (written.min, written.max))
+            except (ValueError, OverrideError) as e:
+                parameter.min = parameter.max = None
+                message = e.message() if isinstance(e, OverrideError) else str(e)
+                self._fail([parameter], ExternError(self, [parameter], f"Invalid value for {parameter.id} (extern \"{parameter.extern}\"): {message}"))
+            except OneilError as e:
+                parameter.min = parameter.max = None
+                self._fail([parameter], e.with_note(f"In extern \"{parameter.extern}\""))
+        if missing:
+            self._fail(missing, ExternError(self, missing))
+
+    def _fail(self, parameters, error):
+        """
+        Record an error that keeps parameters (or a test, with none) from being calculated
+        in a model loaded with `partial`, so the rest of the model is still evaluated.
+        Raises the error otherwise.
+        """
+        if not self.partial:
+            raise error
+        if not any(error is found for found in self.diagnostics):
+            self.diagnostics.append(error)
+        for parameter in parameters:
+            parameter.failure = error
+
+    def errors(self, trail=None):
+        """
+        The errors collected in this model and its submodels (see `partial`), each once.
+        """
+        trail = set() if trail is None else trail
+        if id(self) in trail:
+            return []
+        trail.add(id(self))
+
+        found = list(self.diagnostics)
+        for entry in self.submodels.values():
+            if 'model' in entry:
+                found += [error for error in entry['model'].errors(trail) if not any(error is other for other in found)]
+        return found
+
+    def failed(self):
+        """
+        The parameters that couldn't be calculated, by ID, with the errors that kept them
+        from it.
+        """
+        return {ID: parameter.failure for ID, parameter in self.parameters.items() if parameter.failure is not None}
+
+    def _check_test_inputs(self):
+        """
+        Check the units of the test inputs given to submodels in use declarations against
+        the units of the submodel parameters they stand in for, so that a mismatch is
+        reported at the declaration rather than in the submodel's tests.
+        """
+        for symbol, entry in self.submodels.items():
+            if not entry['inputs']:
+                continue
+            try:
+                submodel = entry.get('model') or self._retrieve_model(entry['path'])
+            except OneilError:
+                continue
+            filename = os.path.basename(self.model_filename)
+            for arg, value in list(entry['inputs'].items()):
+                if arg not in submodel.parameters:
+                    continue
+                given = parse_test_input(arg, value, filename, entry['line_no'], entry['line'])
+                units = given.units if given else self._declared_units(value.strip())
+                expected = submodel.parameters[arg].units
+                if units is not None and units != expected:
+                    self._fail([], SubmodelInputUnitError(filename, entry['line_no'], entry['line'], arg, value.strip(), units, expected, submodel.name))
+                    # The submodel's tests that need the input are skipped
+                    del entry['inputs'][arg]
+
+    def _declared_units(self, ID):
+        """
+        The units a parameter (or `ID.submodel`) is declared with, or None if it isn't
+        found.
+        """
+        parameter = self._declaration(ID)
+        return parameter.units if parameter else None
+
+    def _declaration(self, ID):
+        """
+        The parameter an ID (or `ID.submodel`) refers to, or None if it isn't found.
+        """
+        parameters = self.parameters
+        if "." in ID:
+            ID, symbol = ID.split(".", 1)
+            submodel = self.submodels.get(symbol, {}).get("model")
+            parameters = submodel.parameters if submodel else {}
+        return parameters.get(ID)
+
+    def _declared_logarithmic(self, ID):
+        """
+        Whether a parameter (or `ID.submodel`) is declared in dB.
+        """
+        parameters = self.parameters
+        if "." in ID:
+            ID, symbol = ID.split(".", 1)
+            submodel = self.submodels.get(symbol, {}).get("model")
+            parameters = submodel.parameters if submodel else {}
+        return ID in parameters and parameters[ID].logarithmic
+
+    def _check_piecewise_units(self):
+        """
+        Check that every branch of each piecewise equation has the parameter's units, and
+        that the comparisons in the conditions compare like units, without calculating
+        them (only the branch that applies is calculated).
+        """
+        for param in self.parameters.values():
+            if not param.piecewise or unit_check_justification(param.annotations) is not None:
+                continue
+
+            first = param.line.split("=", 1)[-1].rsplit(":", 1)[0].strip() if param.line else ""
+            lines = [(param.line_no, first)] + list(zip(param.piece_line_nos, param.piece_lines))
+            mismatched = []
+            for (equation, condition), (line_no, text) in zip(param.equation, lines):
+                try:
+                    if isinstance(condition.equation, str):
+                        static_units(condition.equation, self._declared_units, self._declared_logarithmic)
+                    if isinstance(equation.equation, str) and not equation.independent and not equation.minmax_equation:
+                        units = static_units(equation.equation, self._declared_units, self._declared_logarithmic)
+                        if units is not None and units != param.units:
+                            mismatched.append((line_no, text, f"{un.hr_units(units, [1, 1]) or 'unitless'}, not {un.hr_units(param.units, [1, 1]) or 'unitless'}"))
+                except UnitEvaluationError as e:
+                    mismatched.append((line_no, text, e.message()))
+
+            if mismatched:
+                self._fail([param], PiecewiseUnitError(param, mismatched, self.model_filename))
+
+    def _check_function_units(self):
+        """
+        Check the parameters that call imported Python functions against the functions'
+        unit signatures (see oneil.signatures), without calling them. Functions without a
+        signature aren't checked until they run.
+        """
+        for param in self.parameters.values():
+            if not param.callable or unit_check_justification(param.annotations) is not None:
+                continue
+            function = param.equation
+            try:
+                problems = signatures.check(function, param.args, param.units, self._declared_units)
+            except ValueError as e:
+                self._fail([param], FunctionUnitError(param, function.__name__, signatures.declared(function), [str(e)], invalid=True))
+                continue
+            if problems:
+                self._fail([param], FunctionUnitError(param, function.__name__, signatures.declared(function), problems))
+
     # Checks that all of the arguments to each parameter are defined
     def _check_namespace(self, verbose=False):
         undefined = {}
@@ -1807,8 +3694,282 @@ class Model:
             error = IDError(self, f"{undefined.keys()}", self.name.capitalize() + " has undefined arguments")
             for value in undefined.values():
                 error = error.with_note(value)
+            if self.partial:
+                users = [param for param in self.parameters.values() if any(arg in undefined for arg in param.args)]
+                self._fail(users, error.with_note(f"In namespace check for {self.name}"))
+                return
             raise error
 
+    # Give each alias the units of the parameter it forwards to.
+    def _resolve_aliases(self):
+        def resolve(parameter, trail):
+            if parameter.id in trail:
+                raise ParameterError("Circular alias: " + " := ".join(trail + [parameter.id]) + ".", parameter)
+            if '.' in parameter.alias:
+                ID, symbol = parameter.alias.split('.')
+                if symbol not in self.submodels:
+                    raise IDError(self, parameter.alias, f"Alias {parameter.id} refers to an undefined submodel \"{symbol}\".")
+                target = self._retrieve_model(self.submodels[symbol]['path']).parameters.get(ID)
+            else:
+                target = self.parameters.get(parameter.alias)
+            if target is None:
+                raise IDError(self, parameter.alias, f"Alias {parameter.id} refers to an undefined parameter.")
+            if target.alias and '.' not in parameter.alias:
+                resolve(target, trail + [parameter.id])
+            parameter.units, parameter.hr_units = target.units, target.hr_units
+
+        for parameter in self.parameters.values():
+            if parameter.alias:
+                resolve(parameter, [])
+
+    # Find references to deprecated parameters and uses of deprecated submodels.
+    def _check_deprecations(self):
+        uses = []
+
+        def deprecated(label, line_no, user, annotations):
+            message = deprecation_message(annotations)
+            if message is None:
+                return
+            text = f"{label} is deprecated" + (f": {message}" if message else ".")
+            uses.append({
+                "item": label,
+                "message": message,
+                "user": user,
+                "warning": DeprecationWarning(self.name + ".on", line_no, text).with_note(f"Used by {user}"),
+            })
+
+        for symbol, entry in self.submodels.items():
+            try:
+                submodel = self._retrieve_model(entry['path'])
+            except ModelError:
+                continue
+            deprecated(f"Model {submodel.name}", entry['line_no'], f"{self.name} (as {symbol})", submodel.annotations)
+
+        users = [(p, p.id) for p in self.parameters.values()] + [(t, f"test \"{t.expression}\"") for t in self.tests]
+        for user, user_label in users:
+            for arg in dict.fromkeys(user.args):
+                if '.' in arg:
+                    ID, symbol = arg.split('.', 1)
+                    if symbol not in self.submodels:
+                        continue
+                    try:
+                        target = self._retrieve_model(self.submodels[symbol]['path']).parameters.get(ID)
+                    except ModelError:
+                        continue
+                else:
+                    target = self.parameters.get(arg)
+                if target is not None and target is not user:
+                    deprecated(arg, user.line_no, f"{user_label} in {self.name}", target.annotations)
+
+        return uses
+
+    # Submodels with experimental features (see oneil.features) this model doesn't enable.
+    def _check_features(self):
+        warnings = []
+        for symbol, entry in self.submodels.items():
+            try:
+                submodel = self._retrieve_model(entry['path'])
+            except ModelError:
+                continue
+            experimental = sorted(submodel.features - self.features)
+            if experimental:
+                message = f"Model {submodel.name} (as {symbol}) uses experimental features ({', '.join(experimental)}), whose syntax can change between versions."
+                warnings.append(FeatureWarning(self.name + ".on", entry['line_no'], message).with_note(f"Add \"#feature({', '.join(experimental)})\" to {self.name}.on to acknowledge them."))
+        return warnings
+
+    # The Python functions the model's parameters call, including in piecewise branches,
+    # min and max equations, and fallbacks.
+    def _called_functions(self):
+        functions = []
+        for param in self.parameters.values():
+            parts = [param, param.fallback_param]
+            if param.piecewise:
+                parts += [piece for pieces in param.equation for piece in pieces]
+            elif param.minmax_equation:
+                parts += list(param.equation)
+            functions += [part.equation for part in parts if isinstance(part, Parameter) and part.callable]
+        return functions
+
+    # Python modules the model imports that none of its parameters call.
+    def _check_imports(self):
+        if sandbox.no_python:
+            return []
+        called = self._called_functions()
+        warnings = []
+        for line, line_no in read_model_lines(self.model_filename):
+            match = re.search(r"^import\s+(\w+)\s*$", line)
+            module = sys.modules.get(match.group(1)) if match else None
+            if module is None or any(getattr(module, getattr(function, "__name__", ""), None) is function for function in called):
+                continue
+            warnings.append(UnusedImportWarning(self.name + ".on", line_no, f"{match.group(1)} is imported, but no parameter calls its functions.").with_note("Remove the import if nothing needs it."))
+        return warnings
+
+    # Parameters named like builtin functions or math constants.
+    def _check_shadowing(self):
+        warnings = []
+        for param in self.parameters.values():
+            if param.id in FUNCTIONS:
+                message = f"{param.id} has the name of the builtin function {param.id}(), so equations can't use it as a parameter."
+            elif param.id in MATH_CONSTANTS:
+                message = f"{param.id} has the name of a builtin constant, so equations that use {param.id} get the constant, not the parameter."
+            else:
+                continue
+            warnings.append(ShadowedBuiltinWarning(self.name + ".on", param.line_no, message).with_note(f"Rename {param.id} so it can't be confused with the builtin."))
+        return warnings
+
+    # Deprecated items that are still used in this model and its submodels.
+    def deprecations(self, trail=None):
+        trail = set() if trail is None else trail
+        if id(self) in trail:
+            return []
+        trail.add(id(self))
+
+        uses = list(self.deprecated_uses)
+        for entry in self.submodels.values():
+            if 'model' in entry:
+                uses.extend(entry['model'].deprecations(trail))
+        return uses
+
+    def unchecked_units(self):
+        """
+        The parameters of the model and its submodels marked `@unit_unchecked`, each with
+        the model it's in and the reason its units aren't checked.
+        """
+        return [{"id": parameter.id, "model": model.name, "reason": unit_check_justification(parameter.annotations)} for model, parameter in self._annotated_parameters("unit_unchecked")]
+
+    # Parameters of this model and its submodels with an annotation (e.g. "precision"),
+    # or all of them if the annotation is None.
+    def _annotated_parameters(self, annotation, trail=None):
+        trail = set() if trail is None else trail
+        if id(self) in trail:
+            return []
+        trail.add(id(self))
+
+        annotated = [(self, parameter) for parameter in self.parameters.values() if annotation is None or annotation in parameter.annotations]
+        for entry in self.submodels.values():
+            if 'model' in entry:
+                annotated.extend(entry['model']._annotated_parameters(annotation, trail))
+        return annotated
+
+    # The independent parameters with a range of values that a parameter depends on,
+    # directly or through other parameters, with the models they're in.
+    def _uncertain_inputs(self, parameter, found=None, seen=None):
+        found = {} if found is None else found
+        seen = set() if seen is None else seen
+        for arg in parameter.args:
+            if '.' in arg:
+                ID, symbol = arg.split('.', 1)
+                if symbol not in self.submodels:
+                    continue
+                model = self._retrieve_model(self.submodels[symbol]['path'])
+            else:
+                ID, model = arg, self
+            source = model.parameters.get(ID)
+            if source is None or id(source) in seen:
+                continue
+            seen.add(id(source))
+            if source.independent:
+                if relative_uncertainty(source):
+                    found[id(source)] = (model, source)
+            else:
+                model._uncertain_inputs(source, found, seen)
+        return list(found.values())
+
+    def precision_report(self):
+        """
+        The parameters of the model and its submodels whose relative uncertainty (the
+        half-width of their range over its midpoint) is over their `@precision` budget,
+        each with the model it's in, its uncertainty and budget, the inputs that
+        contribute most to it, and a PrecisionWarning. An input's share is how much of
+        the parameter's range goes away when the input is fixed at its midpoint.
+        """
+        over = []
+        for model, parameter in self._annotated_parameters("precision"):
+            uncertainty = relative_uncertainty(parameter)
+            if uncertainty is not None and uncertainty > precision_budget(parameter.annotations):
+                over.append({"model": model, "parameter": parameter, "uncertainty": uncertainty, "budget": precision_budget(parameter.annotations)})
+        if not over:
+            return []
+
+        # Fix each input at its midpoint in turn and see how much narrower the ranges get.
+        widths = {id(entry["parameter"]): entry["parameter"].max - entry["parameter"].min for entry in over}
+        inputs = {}
+        for entry in over:
+            for model, source in entry["model"]._uncertain_inputs(entry["parameter"]):
+                inputs.setdefault(id(source), (model, source))
+        shares = {}
+        for model, source in inputs.values():
+            original = (source.min, source.max)
+            source.min = source.max = (original[0] + original[1]) / 2
+            try:
+                self._reset_recursively()
+                self._calculate_models_recursively(quiet=True)
+                for entry in over:
+                    parameter = entry["parameter"]
+                    if widths[id(parameter)] and relative_uncertainty(parameter) is not None:
+                        shares[(id(parameter), id(source))] = 1 - (parameter.max - parameter.min) / widths[id(parameter)]
+            except OneilError:
+                pass
+            finally:
+                source.min, source.max = original
+        self._reset_recursively()
+        self._calculate_models_recursively(quiet=True)
+
+        report = []
+        for entry in over:
+            model, parameter = entry["model"], entry["parameter"]
+            contributors = []
+            for source_model, source in inputs.values():
+                share = shares.get((id(parameter), id(source)))
+                if share is not None and share > 0:
+                    contributors.append({"input": source.id if source_model is model else f"{source.id} in {source_model.name}", "share": share})
+            contributors.sort(key=lambda contributor: -contributor["share"])
+
+            line_no = parameter.line_no['model line'] if isinstance(parameter.line_no, dict) else parameter.line_no
+            warning = PrecisionWarning(model.name + ".on", line_no, f"{parameter.name} ({parameter.id}) is uncertain by ±{100 * entry['uncertainty']:.3g}%, over its precision budget of ±{100 * entry['budget']:.3g}%.")
+            if contributors:
+                warning.with_note("Mostly from " + ", ".join(f"{c['input']} ({100 * c['share']:.0f}%)" for c in contributors[:3]))
+            report.append({"id": parameter.id, "model": model.name, "uncertainty": entry["uncertainty"], "budget": entry["budget"], "contributors": contributors, "warning": warning})
+        return report
+
+    def mirror_report(self):
+        """
+        The parameters of the model and its submodels that don't match the Python
+        constants they `@mirror`, each with the model it's in, the file and constant, the
+        constant's value (or None if it can't be read), and a MirrorWarning. Constants are
+        compared with the parameters' values in base units, and can be a number or a
+        (min, max) pair.
+        """
+        drift = []
+        for model, parameter in self._annotated_parameters("mirror"):
+            file, name = mirrored_constant(parameter.annotations)
+            line_no = parameter.line_no['model line'] if isinstance(parameter.line_no, dict) else parameter.line_no
+            try:
+                value = read_python_constant(os.path.dirname(model.model_filename), file, name)
+            except ValueError as e:
+                warning = MirrorWarning(model.name + ".on", line_no, f"{parameter.name} ({parameter.id}) mirrors {file}::{name}, which can't be read: {e}")
+                drift.append({"id": parameter.id, "model": model.name, "file": file, "constant": name, "value": None, "warning": warning})
+                continue
+
+            number = lambda v: isinstance(v, (int, float, np.integer, np.floating)) and not isinstance(v, (bool, np.bool_))
+            extremes = tuple(value) if isinstance(value, (tuple, list)) and len(value) == 2 else (value, value)
+            if all(number(v) for v in extremes + (parameter.min, parameter.max)):
+                if math.isclose(parameter.min, extremes[0], rel_tol=1e-9) and math.isclose(parameter.max, extremes[1], rel_tol=1e-9):
+                    continue
+            elif (parameter.min, parameter.max) == extremes:
+                continue
+
+            shown = lambda pair: f"{pair[0]:g}" if pair[0] == pair[1] else f"{pair[0]:g}|{pair[1]:g}"
+            units = (" " + un._build_compound_unit_str(parameter.units)) if parameter.units else ""
+            try:
+                values = (shown((parameter.min, parameter.max)), shown(extremes))
+            except (TypeError, ValueError):
+                values = (str(parameter.human_readable()), repr(value))
+            warning = MirrorWarning(model.name + ".on", line_no, f"{parameter.name} ({parameter.id}) is {values[0]}{units}, but {file}::{name} is {values[1]}{units}.")
+            warning.with_note("The two have drifted apart. Update one of them, or remove the @mirror annotation.")
+            drift.append({"id": parameter.id, "model": model.name, "file": file, "constant": name, "value": value, "warning": warning})
+        return drift
+
     # Recursively report all submodule paramaters with the same ID
     def _check_namespace_recursively(self, submodel, arg, param, trail=[]):
         if arg in submodel.parameters:
@@ -1833,15 +3994,18 @@ class Model:
         if isinstance(design_files, str):
             if not os.path.exists(design_files):
                 raise DesignError([design_files])
-            _, design_params, _, tests, design = parse_file(design_files)
+            with un.scope(self.units):
+                _, design_params, _, tests, design, _, _ = parse_file(design_files)
         elif isinstance(design_files, list):
             missing_files = [file for file in design_files if not os.path.exists(file)]
             if len(missing_files) > 0:
                 raise DesignError(missing_files)
-            _, design_params, _, tests, design = parse_file(design_files[0])
+            with un.scope(self.units):
+                _, design_params, _, tests, design, _, _ = parse_file(design_files[0])
             if len(design_files) > 1:
                 for design_file in design_files[1:]:
-                    _, overdesign_params, _, overtests, overdesign = parse_file(design_file)
+                    with un.scope(self.units):
+                        _, overdesign_params, _, overtests, overdesign, _, _ = parse_file(design_file)
                     for ID, parameter in overdesign.items():
                         design[ID] = parameter
                     for ID, parameter in overdesign_params.items():
@@ -1850,7 +4014,26 @@ class Model:
                     tests.extend(overtests)
         else:
             raise TypeError("Design file must be a string or list of strings.")
-                
+        self.design_files += [os.path.abspath(file) for file in ([design_files] if isinstance(design_files, str) else design_files)]
+        self.apply_design(design, design_files)
+
+        self.tests.extend(tests)
+
+        design_files.reverse()
+        design_files = [file.strip(".on") for file in design_files]
+        new_design = "@".join(design_files)
+        if self.design != new_design:
+            self.design = new_design + "@" + self.design if self.design != "default" else new_design
+
+        self._reset_recursively()
+        self.build()
+
+    def apply_design(self, design, source):
+        """
+        Write design values (by ID, or `ID.submodel` for submodels' parameters) to the
+        model's parameters, without recalculating. The source (design files or a fixture)
+        is named in errors.
+        """
         # Rewrite parameters with appended submodel paths.
         for ID, parameter in design.items():
             if "." in ID:
@@ -1862,25 +4045,60 @@ class Model:
             try:
                 self._rewrite_parameter(ID, parameter, path)
             except ModelError as e:
-                raise e.with_note(f"Couldn't find {submodel} in path {'.'.join(path)} while overwriting {ID} in {design_files} (line {self.submodels[submodel]['line_no']})")
+                raise e.with_note(f"Couldn't find {submodel} in path {'.'.join(path)} while overwriting {ID} in {source} (line {self.submodels[submodel]['line_no']})")
             except ParameterError as e:
-                raise e.with_note(f"In parameter {ID} in {design_files}")
+                raise e.with_note(f"In parameter {ID} in {source}")
 
         self.defaults.append(list[set(self.parameters).difference(design)])
         
         for ID, parameter in design.items():
             self.parameters[ID] = parameter
 
-        self.tests.extend(tests)
+    # Apply command-line overrides (e.g. "x=3:mm" or "x.sub=2") on top of the current design,
+    # and evaluate the model again unless `build` is False.
+    def apply_overrides(self, overrides, quiet=False, build=True):
+        if not overrides:
+            return
 
-        design_files.reverse()
-        design_files = [file.strip(".on") for file in design_files]
-        new_design = "@".join(design_files)
-        if self.design != new_design:
-            self.design = new_design + "@" + self.design if self.design != "default" else new_design
+        for override in overrides:
+            value = parse_override(override)
+
+            # The submodel symbols follow the ID from the innermost out, as in equations
+            # (x.e.d is x in e, a submodel of d).
+            ID, *symbols = value.id.split(".")
+            model = self
+            for submodel in reversed(symbols):
+                if submodel not in model.submodels:
+                    raise OverrideError(override, f"Submodel \"{submodel}\" not found in model {model.name}.")
+                model = model._retrieve_model(model.submodels[submodel]['path'])
+
+            if ID not in model.parameters:
+                raise OverrideError(override, f"Parameter \"{ID}\" not found in model {model.name}.")
+            parameter = model.parameters[ID]
+
+            try:
+                if value.independent:
+                    if not value.isdiscrete and value.units != parameter.units:
+                        raise UnitEvaluationError(f"Input units ({un.hr_units(value.units)}) do not match the required units: ({un.hr_units(parameter.units)}).", [parameter, value]).with_context(parameter)
+                    parameter.write(value.min if parameter.isdiscrete or value.isdiscrete else (value.min, value.max))
+                else:
+                    parameter.write(value)
+            except OneilError as e:
+                raise e.with_note(f"In --set {override}")
+
+            # Overridden values replace the parameter's equation entirely.
+            if value.independent:
+                parameter.independent = True
+                parameter.equation = None
+                parameter.args = []
+                parameter.callable = parameter.piecewise = parameter.minmax_equation = parameter.pointer = False
+                parameter.fallback_param = None
+
+            self.overrides.append(override)
 
         self._reset_recursively()
-        self.build()
+        if build:
+            self.build(quiet=quiet)
 
     def _param2latex(self, param_ID):
         # Replace parameter functions with their normal function names
@@ -1958,7 +4176,8 @@ class Model:
         return snippet
 
     def test_snippet(self, ID, test):
-        snippet = "\n\\subsubsection{" + ID.replace("_", "~").title() + "}"
+        title = test.name.replace("_", "\\_") if test.name else ID.replace("_", "~").title()
+        snippet = "\n\\subsubsection{" + title + "}"
         snippet += "\\label{sssec:" + ID.replace("_", "-") + "}\n"
         snippet += "\\begin{equation}\n"
         snippet += "\t\\label{eq:" + ID.replace("_", "-") + "}\n"
@@ -2059,21 +4278,209 @@ class Model:
             elif isinstance(entry, Test):
                 document += self.test_snippet(ID, entry)
 
+        deprecations = self.deprecations()
+        if deprecations:
+            document += "\n\n\\subsection{Deprecated Items}"
+            document += "\\label{ssec:deprecated-items}\n\n\\begin{itemize}\n"
+            for use in deprecations:
+                item = use['item'].replace("_", "\\_")
+                user = use['user'].replace("_", "\\_")
+                message = use['message'].replace("_", "\\_")
+                document += f"    \\item \\texttt{{{item}}} (used by {user})" + (f": {message}" if message else "") + "\n"
+            document += "\\end{itemize}\n"
+
+        unchecked = self.unchecked_units()
+        if unchecked:
+            document += "\n\n\\subsection{Unchecked Units}"
+            document += "\\label{ssec:unchecked-units}\n\n\\begin{itemize}\n"
+            for entry in unchecked:
+                item = entry['id'].replace("_", "\\_")
+                model = entry['model'].replace("_", "\\_")
+                reason = entry['reason'].replace("_", "\\_")
+                document += f"    \\item \\texttt{{{item}}}" + (f" in {model}" if entry['model'] != self.name else "") + f": {reason}\n"
+            document += "\\end{itemize}\n"
+
         if "\\cite" in document:
             document += "\n\\printbibliography\\end{document}"
         else:
-            document += "\n\\end{document}"
-        
-        with open("export.tex", "w") as export:
-            # Write the output file.
-            export.write(document)
+            document += "\n\\end{document}"
+        
+        with open("export.tex", "w") as export:
+            # Write the output file, with the reproducibility stamp as a comment.
+            export.write(stamp.REPORT_PREFIX + json.dumps(stamp.stamp(self)) + "\n")
+            export.write(document)
+
+        # Compile the output file using pdflatex => biblatex => pdflatex 2X.
+        for i in (1, 2):
+            os.system("pdflatex -synctex=1 -interaction=nonstopmode export.tex")
+            os.system("biber export")
+            os.system("pdflatex -synctex=1 -interaction=nonstopmode export.tex")
+        os.system("rm export.aux export.log export.bbl export.blg export.bcf export.run.xml export.synctex.gz")
+
+    def labels(self):
+        """
+        An index from each parameter label (its name, lowercased) to the IDs of the
+        parameters with that label, in declaration order.
+        """
+        index = {}
+        for ID, parameter in self.parameters.items():
+            index.setdefault(parameter.name.lower(), []).append(ID)
+        return index
+
+    def lookup(self, name):
+        """
+        The ID of the parameter with the given ID or label (ignoring case). Raises IDError
+        if there's no such parameter or more than one parameter has the label.
+        """
+        if name in self.parameters:
+            return name
+        IDs = self.labels().get(name.strip().lower(), [])
+        if len(IDs) > 1:
+            raise IDError(self, name, f"More than one parameter is labeled \"{name}\" ({', '.join(IDs)}). Use an ID instead.")
+        if not IDs:
+            raise IDError(self, name, "Could not find a parameter with this ID or label in the model.")
+        return IDs[0]
+
+    def explain(self, name, sigfigs=4):
+        """
+        Print where a parameter (given by ID or label) is defined, its value and limits,
+        what it depends on, what uses it, and its notes.
+        """
+        ID = self.lookup(name)
+        parameter = self.parameters[ID]
+        line_no = parameter.line_no['model line'] if isinstance(parameter.line_no, dict) else parameter.line_no
+        line = parameter.line['model line'] if isinstance(parameter.line, dict) else parameter.line
+
+        print(f"{parameter.name} ({ID}), line {line_no} of {parameter.model}" + (f", section \"{parameter.section}\"" if parameter.section else ""))
+        print("    " + line.strip())
+        print("    Value: " + parameter.human_readable(sigfigs))
+        if parameter.options and not parameter.isdiscrete and not isinstance(parameter.min, str):
+            print("    Limits: " + un.hr_vals_and_units(tuple(parameter.options), parameter.units, parameter.hr_units or None, sigfigs))
+        elif parameter.options:
+            print("    Options: " + ", ".join(str(option) for option in parameter.options))
+        if parameter.args:
+            print("    Depends on: " + ", ".join(dict.fromkeys(parameter.args)))
+        users = [other for other, p in self.parameters.items() if ID in p.args and other != ID]
+        users += [f"test {test.label()}" for test in self.tests if ID in test.args]
+        if users:
+            print("    Used by: " + ", ".join(users))
+        if parameter.iterations:
+            cycle = sorted(ordering.cycle(self.parameters, ID))
+            print(f"    Iterated: converged in {parameter.iterations} iteration(s), with " + ", ".join(cycle))
+        corners.print_corners(self, ID)
+        for note in parameter.notes:
+            print("    " + note.strip())
+
+    def piecewise_series(self, ID, input_ID=None, points=50, domain=None):
+        """
+        Sample a piecewise parameter across an independent parameter its conditions use
+        (the first one by default), for plotting it. The domain (in base units) defaults
+        to the thresholds in the conditions and the input's value, widened by half and
+        kept within the input's limits; discrete inputs are sampled at each option.
+
+        Returns the input and parameter IDs and display units, the branches, the samples
+        (x and y in display units, with the active branch, or None where no branch applies
+        or the model fails), and the switches between branches. The x of each switch is
+        found by bisection, and its jump is the change in y across it (0 if the function
+        is continuous there).
+        """
+        ID = self.lookup(ID)
+        parameter = self.parameters[ID]
+        if not parameter.piecewise:
+            raise IDError(self, ID, f"{ID} isn't a piecewise parameter.")
+        inputs = [arg for piece in parameter.equation for arg in piece[1].args if arg in self.parameters and self.parameters[arg].independent]
+        input_ID = input_ID or next(iter(inputs), None)
+        if input_ID not in self.parameters or not self.parameters[input_ID].independent:
+            raise IDError(self, input_ID or ID, f"Sampling {ID} needs an independent parameter of the model as the input.")
+        source = self.parameters[input_ID]
+        discrete = source.isdiscrete or isinstance(source.min, str)
+        original = (source.min, source.max)
+
+        def evaluate(x):
+            source.min = source.max = x
+            self._reset_recursively()
+            try:
+                self._calculate_models_recursively(quiet=True)
+                branch = next((i for i, piece in enumerate(parameter.equation) if piece[1].min and piece[1].max), None)
+                return {"x": source.result()["min"], "y": parameter.result()["min"] if branch is not None else None, "branch": branch}
+            except OneilError:
+                return {"x": source.result()["min"], "y": None, "branch": None}
+
+        if discrete:
+            xs = list(source.options)
+        elif domain:
+            xs = list(np.linspace(domain[0], domain[1], points))
+        else:
+            # Thresholds: numbers and the other parameters compared in the conditions
+            numbers = [float(n) for piece in parameter.equation for n in re.findall(r"(?<![\w.])\d+\.?\d*(?:[eE][-+]?\d+)?", str(piece[1].equation))]
+            numbers += [value for piece in parameter.equation for arg in piece[1].args if arg != input_ID and arg in self.parameters
+                        for value in (self.parameters[arg].min, self.parameters[arg].max) if isinstance(value, (int, float))]
+            start, end = min(numbers + [source.min]), max(numbers + [source.max])
+            margin = (end - start) / 2 or abs(start) or 1
+            lower, upper = source.options if source.options else (-np.inf, np.inf)
+            xs = list(np.linspace(max(start - margin, lower), min(end + margin, upper), points))
 
-        # Compile the output file using pdflatex => biblatex => pdflatex 2X.
-        for i in (1, 2):
-            os.system("pdflatex -synctex=1 -interaction=nonstopmode export.tex")
-            os.system("biber export")
-            os.system("pdflatex -synctex=1 -interaction=nonstopmode export.tex")
-        os.system("rm export.aux export.log export.bbl export.blg export.bcf export.run.xml export.synctex.gz")
+        try:
+            samples = [evaluate(x) for x in xs]
+            switches = []
+            for i in range(len(xs) - 1):
+                before, after = samples[i], samples[i + 1]
+                if before["branch"] == after["branch"] or None in (before["branch"], after["branch"]):
+                    continue
+                if discrete:
+                    switches.append({"x": after["x"], "from": before["branch"], "to": after["branch"], "jump": None})
+                    continue
+                lower, upper = xs[i], xs[i + 1]
+                for _ in range(40):
+                    middle = (lower + upper) / 2
+                    if evaluate(middle)["branch"] == before["branch"]:
+                        lower = middle
+                    else:
+                        upper = middle
+                left, right = evaluate(lower), evaluate(upper)
+                jump = right["y"] - left["y"] if None not in (left["y"], right["y"]) else None
+                switches.append({"x": right["x"], "from": before["branch"], "to": after["branch"], "jump": jump})
+        finally:
+            source.min, source.max = original
+            self._reset_recursively()
+            self._calculate_models_recursively(quiet=True)
+
+        return {
+            "id": ID,
+            "input": input_ID,
+            "units": {"x": source.result()["units"], "y": parameter.result()["units"]},
+            "branches": [{"equation": str(piece[0].equation if piece[0].equation is not None else piece[0].min), "condition": str(piece[1].equation)} for piece in parameter.equation],
+            "samples": samples,
+            "switches": switches,
+        }
+
+    def print_piecewise(self, ID, input_ID=None, points=50, sigfigs=4):
+        """
+        Print the samples of a piecewise parameter (see piecewise_series()) and where it
+        switches between branches, flagging jumps.
+        """
+        series = self.piecewise_series(ID, input_ID, points)
+        x_units = f" ({series['units']['x']})" if series["units"]["x"] else ""
+        y_units = f" ({series['units']['y']})" if series["units"]["y"] else ""
+        number = lambda value: "-" if value is None else (f"{value:.{sigfigs}g}" if isinstance(value, float) else str(value))
+
+        table = BeautifulTable()
+        table.columns.header = [series["input"] + x_units, series["id"] + y_units, "Branch"]
+        for sample in series["samples"]:
+            branch = "-" if sample["branch"] is None else f"{sample['branch'] + 1}: {series['branches'][sample['branch']]['condition']}"
+            table.append_row([number(sample["x"]), number(sample["y"]), branch])
+        print(table)
+
+        values = [sample["y"] for sample in series["samples"] if isinstance(sample["y"], (int, float))]
+        scale = max([abs(value) for value in values] + [1e-300])
+        for switch in series["switches"]:
+            at = f"At {series['input']} = {number(switch['x'])}{(' ' + series['units']['x']) if series['units']['x'] else ''}: branch {switch['from'] + 1} -> {switch['to'] + 1}"
+            if switch["jump"] is None:
+                print(at)
+            elif abs(switch["jump"]) > 1e-6 * scale:
+                print(f"{at}, {bcolors.YELLOW}jumps by {number(switch['jump'])}{(' ' + series['units']['y']) if series['units']['y'] else ''} (discontinuous){bcolors.ENDC}")
+            else:
+                print(f"{at} (continuous)")
 
     def dependents(self, search_IDs):
         for search_ID in search_IDs:
@@ -2088,8 +4495,15 @@ class Model:
             else: 
                 print(search_ID + " has no dependents.")
 
-    def _reset(self):
+    def _reset(self, IDs=None):
+        # Clear the values of the dependent parameters (or of those of them in IDs), so
+        # they're calculated again, and the errors from calculating them.
+        del self.diagnostics[self._load_diagnostics:]
         for ID, parameter in self.parameters.items():
+            if IDs is not None and ID not in IDs:
+                continue
+            if not any(parameter.failure is error for error in self.diagnostics):
+                parameter.failure = None
             if not parameter.independent: 
                 parameter.min = parameter.max = None
                 if parameter.piecewise:
@@ -2113,13 +4527,40 @@ class Model:
                 entry['model']._reset_recursively()
 
     def build(self, quiet=False):
-        # Calculate imports
-        self._calculate_models_recursively(quiet)
+        with limits.evaluation():
+            # Calculate imports
+            with memory.phase("calculate", self.name):
+                self._calculate_models_recursively(quiet)
+
+            # Run tests
+            with memory.phase("test", self.name):
+                self.test(verbose=False)
+
+        # With `partial`, every error found while loading and evaluating is reported
+        # together, before the results of the rest of the model
+        if self.partial:
+            for error in self.errors():
+                console.print_error(error)
+        if self.fail_count:
+            console.record_exit(console.EXIT_TEST_FAILURE)
+
+        self.over_budget = self.precision_report()
+        for entry in self.over_budget:
+            console.print_warning(entry["warning"])
+
+        self.mirror_drift = self.mirror_report()
+        for entry in self.mirror_drift:
+            console.print_warning(entry["warning"])
+
+        self.empty_intervals = self.empty_report()
+        for entry in self.empty_intervals:
+            console.print_warning(entry["warning"])
 
-        # Run tests
-        self.test(verbose=False)
+        self.unbounded = self.infinity_report()
+        for entry in self.unbounded:
+            console.print_warning(entry["warning"])
 
-        if not quiet: self.summarize()
+        if not quiet and not console.quiet: self.summarize(sigfigs=config.display["sigfigs"])
 
         self.calculated = True
 
@@ -2141,6 +4582,8 @@ class Model:
             _function_cache.end_run(success=success)
 
     def eval(self, expression):
+        check_expression_size(expression, self.name + ".on", None)
+
         # Make a dict of calculation parameters from the submodels
         submodel_parameters = {}
         result = None
@@ -2186,6 +4629,7 @@ class Model:
             raise ParameterError("Eval failed.", expression)
 
     def _test_recursively(self, log, path=[], test_inputs={}, trail=[], verbose=True):
+        global tolerance
         fails = 0
         tests = 0
             
@@ -2194,10 +4638,12 @@ class Model:
             tests += 1
             test_params = {}
             run_expression = test.expression
+            # How names in run_expression are written in the model, for traces
+            names = {new: old for old, new in reversed(FUNCTIONS.items())}
 
             # Only run tests with inputs if inputs were found
             if not any(ref not in test_inputs for ref in test.refs):
-                if verbose: print("Test (" + self.name + "): " + run_expression)
+                if verbose: print("Test (" + self.name + (f", notes of {test.doc}" if test.doc else "") + "): " + (f'"{test.name}": ' if test.name else "") + run_expression)
                 for i, arg in enumerate(test.args):
                     if "." in arg:
                         result, prefixed_ID = self.retrieve_parameter_from_submodel(arg)
@@ -2205,9 +4651,10 @@ class Model:
                         if isinstance(result, Parameter):
                             test_params[prefixed_ID] = result
                         else:
-                            raise TypeError("Invalid result type: " + str(type(result)))
+                            raise IDError(self, arg, f"{arg} is not a parameter.")
 
                         run_expression = run_expression.replace(arg, prefixed_ID)
+                        names[prefixed_ID] = arg
                     elif arg in test_inputs:
                         test_params[arg] = test_inputs[arg]
                     elif arg in FUNCTIONS.values() or any([arg==v for v in OPERATOR_OVERRIDES.values()]) or arg in self.constants or arg in BOOLEAN_OPERATORS:
@@ -2219,6 +4666,15 @@ class Model:
 
                 test_params = test_params | self.constants
 
+                # With `partial`, tests that use parameters that couldn't be calculated are
+                # skipped
+                if any(isinstance(value, Parameter) and value.failure is not None for value in test_params.values()):
+                    fails += 1
+                    test.passed = None
+                    if verbose:
+                        print("\tResult: " + bcolors.FAIL + "skipped" + bcolors.ENDC)
+                    continue
+
                 if test.trace:
                     print("Breakpoint for test: " + test.expression)
                     
@@ -2227,22 +4683,43 @@ class Model:
                     breakpoint()
                     eval(run_expression, globals(), test_params)
 
+                tolerance = test_tolerance(test.annotations)
+                error = None
                 try:
                     calculation = eval(run_expression, globals(), test_params)
                 except UnitEvaluationError as e:
-                    raise e.with_context(self)
+                    error = e.with_context(self)
                 except ZeroDivisionError as e:
-                    raise DivideByZeroError((run_expression.file_name, run_expression.line_number))
+                    error = DivideByZeroError((run_expression.file_name, run_expression.line_number))
                 except OneilError as e:
-                    raise e
+                    error = e
                 except Exception as e:
-                    raise ImportedFunctionError(self, e)
+                    error = ImportedFunctionError(self, e)
+                finally:
+                    tolerance = None
+                if error is not None:
+                    # With `partial`, a test that can't be evaluated is skipped
+                    self._fail([], error)
+                    fails += 1
+                    test.passed = None
+                    if verbose:
+                        print("\tResult: " + bcolors.FAIL + "skipped" + bcolors.ENDC)
+                    continue
 
+                test.passed = bool(calculation)
                 if isinstance(calculation, (bool, np.bool_)):
                     result = bcolors.OKGREEN + "pass" + bcolors.ENDC if calculation else bcolors.FAIL + "fail" + bcolors.ENDC
 
                 if verbose: print("\tResult: " + str(result))
 
+                if verbose and traces.traced(test.passed):
+                    tolerance = test_tolerance(test.annotations)
+                    try:
+                        for line in traces.trace(run_expression, globals(), test_params, names):
+                            print("\t" + line)
+                    finally:
+                        tolerance = None
+
                 if not calculation:
                     fails += 1
                     if verbose:
@@ -2255,8 +4732,9 @@ class Model:
                                 print("\t" + v.__repr__())
             else:
                 fails += 1
+                test.passed = None
                 if verbose:
-                    print("Test (" + self.name + "): " + test.expression + " (" + bcolors.FAIL + "skipped" + bcolors.ENDC + ")")
+                    print("Test (" + self.name + "): " + test.label() + " (" + bcolors.FAIL + "skipped" + bcolors.ENDC + ")")
 
         # Initiate testing of this model's submodels
         for submodel_ID in {k:v for (k,v) in self.submodels.items() if 'model' in v and v['model'].name not in log}:
@@ -2267,6 +4745,8 @@ class Model:
             # Prepare test inputs for the submodel
             if submodel_entry['inputs']:
                 for arg, inp in submodel_entry['inputs'].items():                 
+                    if isinstance(inp, str) and parse_test_input(arg, inp, self.name, submodel_entry['line_no'], submodel_entry['line']):
+                        inp = parse_test_input(arg, inp, self.name, submodel_entry['line_no'], submodel_entry['line'])
                     if isinstance(inp, Parameter):
                         test_inputs[arg] = inp
                     elif isinstance(inp, str):
@@ -2276,13 +4756,13 @@ class Model:
                             if isinstance(result, Parameter):
                                 test_inputs[arg] = result
                             else:
-                                raise TypeError("Invalid result type: " + str(type(result)))
+                                raise IDError(self, inp, f"Test input {inp} for submodel {submodel_ID} is not a parameter.")
                         elif inp in self.parameters:
                             test_inputs[arg] = self.parameters[inp]
                         else:
                             raise ParameterError(f"Test input {inp} for submodel {submodel_ID} not found in {self.name}.", inp)
                     else:
-                        raise TypeError("Invalid test input type: " + str(type(inp)))
+                        raise IDError(self, arg, f"Test input {arg} for submodel {submodel_ID} must be a parameter or parameter ID.")
                 
                     if '.' in arg:
                         arg_ID, source = arg.split('.')
@@ -2344,12 +4824,143 @@ class Model:
         users = [param for ID, param in self.parameters.items() if parameter_ID in param.args]
         for p in users: print(p.id)
 
-    def all(self, sigfigs=4):
-        # Sort the parameter keys alphabetically and wrap in a list
-        parameter_keys = list(self.parameters.keys())
+    def all(self, sigfigs=4, sections=False, tag=None):
+        parameter_keys = [ID for ID, parameter in self.parameters.items() if tag is None or tag in annotated_tags(parameter.annotations)]
+        if not parameter_keys:
+            print(f"No parameters tagged {tag}." if tag else "No parameters.")
+            return
+        if sections:
+            self.print_sections(parameter_keys, sigfigs=sigfigs, verbose=True)
+            return
+
+        # Sort the parameter keys alphabetically
         parameter_keys.sort()
         self.tree(parameter_keys, sigfigs=sigfigs, levels=0, verbose=True, turtles=False)
 
+    def sections(self):
+        """
+        The IDs of the model's parameters grouped by section, in declaration order.
+        Parameters before the first section are under "".
+        """
+        sections = {}
+        for ID, parameter in self.parameters.items():
+            sections.setdefault(parameter.section, []).append(ID)
+        return sections
+
+    def print_sections(self, parameter_IDs, sigfigs=4, verbose=False):
+        """
+        Print the given parameters grouped by section, each section with its test results.
+        """
+        for section, IDs in self.sections().items():
+            IDs = [ID for ID in IDs if ID in parameter_IDs]
+            tests = [test for test in self.tests if test.section == section]
+            if not IDs and not tests:
+                continue
+            header = bcolors.BOLD + (section or "(no section)") + bcolors.ENDC
+            if tests:
+                passed = len([test for test in tests if test.passed])
+                status = bcolors.OKGREEN + "PASS" + bcolors.ENDC if passed == len(tests) else bcolors.FAIL + "FAIL" + bcolors.ENDC
+                header += f" (tests: {passed}/{len(tests)} {status})"
+            print(header)
+            if IDs:
+                self.tree(IDs, sigfigs=sigfigs, levels=0, verbose=verbose, turtles=False)
+
+    # Results as plain data (for JSON), with each parameter's limits and optionally where
+    # it's defined.
+    def results(self, source=False, trail=None, encoding="utf-32"):
+        trail = set() if trail is None else trail
+        trail.add(id(self))
+        filename = os.path.relpath(self.model_filename)
+
+        parameters = {}
+        for ID, parameter in self.parameters.items():
+            parameters[ID] = parameter.result() | parameter.limits()
+            if parameter.corners is not None:
+                parameters[ID]["corners"] = parameter.corners
+            if parameter.iterations:
+                parameters[ID]["iterations"] = parameter.iterations
+            if source:
+                parameters[ID]["source"] = parameter.source(filename, encoding)
+                parameters[ID]["node"] = structure.node_id(parameter, self.name)
+
+        submodels = {}
+        for symbol, entry in self.submodels.items():
+            if 'model' in entry and id(entry['model']) not in trail:
+                submodels[symbol] = entry['model'].results(source, trail, encoding)
+
+        results = {
+            "model": self.name,
+            "design": self.design,
+            "parameters": parameters,
+            "tests": {
+                "passed": self.test_count - self.fail_count,
+                "total": self.test_count,
+                "results": [{"name": test.name, "expression": test.expression, "line": int(test.line_no), "passed": test.passed} for test in self.tests],
+            },
+            "submodels": submodels,
+        }
+        if source:
+            results["source"] = {"file": filename}
+            for test, result in zip(self.tests, results["tests"]["results"]):
+                result["node"] = structure.node_id(test, self.name)
+        return results
+
+    def performance_parameters(self, path=(), trail=None):
+        """
+        The performance parameters (`$`) of the model and its submodels, for dashboards.
+        Each has its result and limits (see Parameter.limits()), the tests of its model that
+        use it, and whether it passed: within its limits with every one of those tests
+        passing. Submodel parameters have the path of submodel symbols as their model.
+        """
+        trail = set() if trail is None else trail
+        trail.add(id(self))
+
+        performance = []
+        for ID, parameter in self.parameters.items():
+            if not parameter.performance:
+                continue
+            entry = {"model": ".".join(path) or self.name, "id": ID} | parameter.result() | parameter.limits()
+            entry["tests"] = [{"name": test.name, "expression": test.expression, "line": int(test.line_no), "passed": test.passed} for test in self.tests if ID in test.args]
+            entry["passed"] = entry["within_limits"] is not False and all(test["passed"] for test in entry["tests"])
+            performance.append(entry)
+
+        for symbol, entry in self.submodels.items():
+            if 'model' in entry and id(entry['model']) not in trail:
+                performance += entry['model'].performance_parameters(path + (symbol,), trail)
+        return performance
+
+    def tagged_parameters(self, tag=None, path=(), trail=None):
+        """
+        The parameters of the model and its submodels with the given tag (or with any tag),
+        for budgets that span sections and models. Each has its model (the path of submodel
+        symbols for submodel parameters), ID, and result.
+        """
+        trail = set() if trail is None else trail
+        trail.add(id(self))
+
+        tagged = []
+        for ID, parameter in self.parameters.items():
+            parameter_tags = annotated_tags(parameter.annotations)
+            if parameter_tags and (tag is None or tag in parameter_tags):
+                tagged.append({"model": ".".join(path) or self.name, "id": ID} | parameter.result())
+
+        for symbol, entry in self.submodels.items():
+            if 'model' in entry and id(entry['model']) not in trail:
+                tagged += entry['model'].tagged_parameters(tag, path + (symbol,), trail)
+        return tagged
+
+    def export_json(self, filename=None, source=False):
+        results = self.results(source)
+        if filename == "-":
+            results["reproducibility"] = stamp.stamp(self)
+            print(json.dumps(results, indent=2))
+            return
+        filename = filename or self.name + ".json"
+        results["reproducibility"] = stamp.stamp(self, os.path.dirname(os.path.abspath(filename)))
+        with open(filename, "w") as f:
+            json.dump(results, f, indent=2)
+        print(f"Exported results to {filename}.")
+
     def independent(self, indent=0):
         print(f"{' ' * indent}{self.name}:")
         for param in self.parameters.values():
@@ -2363,7 +4974,7 @@ class Model:
                     if param.independent:
                         param.long_print(indent=indent+4)
 
-    def summarize(self, sigfigs=4, verbose=False):
+    def summarize(self, sigfigs=4, verbose=False, sections=False):
         usage = _function_cache.usage_summary()
         print("-" * 80)
         print(bcolors.OKBLUE + "Model: " + self.name + bcolors.ENDC)
@@ -2372,6 +4983,12 @@ class Model:
         + " (" + str(len([p for ID, p in self.parameters.items() if p.independent])) + " independent, " 
         + str(len([p for ID, p in self.parameters.items() if not p.independent])) + " dependent, "
         + str(len(self.constants)) + " constants)")
+        if active_cfg:
+            print(bcolors.ORANGE + "Config: " + ", ".join(sorted(active_cfg)) + bcolors.ENDC)
+        if self.overrides:
+            print(bcolors.ORANGE + "Overrides: " + ", ".join(self.overrides) + bcolors.ENDC)
+        if nominal:
+            print(bcolors.ORANGE + "Values: nominal (midpoints of ranges)" + bcolors.ENDC)
         print(f"Tests: {self.test_count - self.fail_count}/{self.test_count}", end="")
         if self.fail_count:
             print(" (" + bcolors.BOLD + bcolors.FAIL + "FAIL" + bcolors.ENDC + ")")
@@ -2381,10 +4998,46 @@ class Model:
             print("Caches used: " + ", ".join(usage["cache_models"]))
         else:
             print("Caches used: none")
+        deprecations = self.deprecations()
+        if deprecations:
+            print(bcolors.YELLOW + "Deprecated items in use:" + bcolors.ENDC)
+            for use in deprecations:
+                print(f"  {use['item']} (used by {use['user']})" + (f": {use['message']}" if use['message'] else ""))
+        if self.over_budget:
+            print(bcolors.YELLOW + "Over precision budget:" + bcolors.ENDC)
+            for entry in self.over_budget:
+                print(f"  {entry['id']}" + (f" in {entry['model']}" if entry['model'] != self.name else "") + f": ±{100 * entry['uncertainty']:.3g}% (budget ±{100 * entry['budget']:.3g}%)")
+        if self.empty_intervals:
+            print(bcolors.YELLOW + "Empty intervals:" + bcolors.ENDC)
+            for entry in self.empty_intervals:
+                print(f"  {', '.join(entry['affected'])}: from {entry['expression']}" + (f" in {entry['model']}" if entry['model'] != self.name else ""))
+        if self.unbounded:
+            print(bcolors.YELLOW + "Infinite from finite inputs:" + bcolors.ENDC)
+            for entry in self.unbounded:
+                print(f"  {', '.join(entry['affected'])}: from {entry['expression']}" + (f" in {entry['model']}" if entry['model'] != self.name else "") + (f" (saturated at ±{entry['saturated']:g})" if entry['saturated'] is not None else ""))
+        if self.mirror_drift:
+            print(bcolors.YELLOW + "Drifted from mirrored Python constants:" + bcolors.ENDC)
+            for entry in self.mirror_drift:
+                print(f"  {entry['id']}" + (f" in {entry['model']}" if entry['model'] != self.name else "") + f": {entry['file']}::{entry['constant']}")
+        unchecked = self.unchecked_units()
+        if unchecked:
+            print(bcolors.YELLOW + "Units not checked:" + bcolors.ENDC)
+            for entry in unchecked:
+                print(f"  {entry['id']}" + (f" in {entry['model']}" if entry['model'] != self.name else "") + f": {entry['reason']}")
+        failed = self.failed()
+        if failed:
+            print(bcolors.YELLOW + "Not calculated (see the errors above):" + bcolors.ENDC)
+            for ID, error in failed.items():
+                origin = getattr(error, "parameter", None) or getattr(error, "context_", None)
+                origin = origin.id if isinstance(origin, Parameter) and origin.id != ID else None
+                print(f"  {ID}: {error.kind()}" + (f" (from {origin})" if origin else ""))
         print("-" * 80)
 
-        summary_parameters = list[self.parameters.keys()] if verbose else [k for k, v in self.parameters.items() if v.performance]
-        self.tree(summary_parameters, sigfigs=sigfigs, verbose=verbose, levels=0, turtles=False)
+        summary_parameters = list(self.parameters.keys()) if verbose else [k for k, v in self.parameters.items() if v.performance and k not in failed]
+        if sections:
+            self.print_sections(summary_parameters, sigfigs=sigfigs, verbose=verbose)
+        else:
+            self.tree(summary_parameters, sigfigs=sigfigs, verbose=verbose, levels=0, turtles=False)
 
     def tree(self, parameter_IDs=[], indent=0, sigfigs=4, levels=3, verbose=False, up=False, turtles=True):
         if isinstance(parameter_IDs, str):
@@ -2417,6 +5070,10 @@ class Model:
                 else:
                     parameter.short_print(sigfigs, indent=indent * 4, verbose=verbose, submodel_id=submodel_id, level=indent)
 
+                # An iterated cycle (see oneil.ordering) ends where it comes back around.
+                if parameter.iterations and (f"{parameter.id}.{submodel_id}" if submodel_id else parameter.id) in trail:
+                    continue
+
                 # For dependent parameters, continue the recursion
                 if parameter.equation:
                     arg_params = []
@@ -2487,102 +5144,295 @@ class Model:
     def _calculate_parameters_recursively(self, parameters, trail=[]):
         for parameter in parameters.values():
             if isinstance(parameter, Parameter) and any([parameter.min is None, parameter.max is None]):
-                submodel_parameters = {}
+                try:
+                    if parameter.failure is not None:
+                        raise parameter.failure
+                    if ordering.iterated(parameter):
+                        self._iterate_parameter(parameter, trail)
+                    else:
+                        self._calculate_parameter(parameter, trail)
+                except EvaluationLimitError:
+                    # Running out of time or depth stops the whole evaluation
+                    raise
+                except OneilError as e:
+                    # With `partial`, the parameter and those that use it are left out,
+                    # and the rest of the model is still calculated
+                    if self.parameters.get(parameter.id) is not parameter:
+                        raise
+                    self._fail([parameter], e)
+                    if trail:
+                        raise
+
+    def _calculate_parameter(self, parameter, trail):
+        global _first_empty, _first_infinite
+        submodel_parameters = {}
 
-                # Update the trail for recursive errors.
-                new_trail = copy.copy(trail)
+        # Update the trail for recursive errors.
+        new_trail = copy.copy(trail)
 
-                if new_trail:
-                    if new_trail[-1] != parameter.id:
-                        new_trail.append(parameter.id)
-                else:
-                    new_trail = [parameter.id]
+        if new_trail:
+            if new_trail[-1] != parameter.id:
+                new_trail.append(parameter.id)
+        else:
+            new_trail = [parameter.id]
+        limits.check(parameter, new_trail)
 
-                # Initialize variables that may not be set in all branches
-                expression = None
-                calc_args = []
-                
-                # If parameter has subparameters (piecewise, minmax), calculate them, else, calculate this parameter directly.
-                if parameter.piecewise:
-                    piece_equations = {}
-                    piece_conditions = {}
-                    true_equations = {}
-                    
-                    if len(parameter.equation) < 2:
-                        raise ParameterError(f"Piecewise function requires at least two pieces.", parameter)
-                    
-                    for i, piece in enumerate(parameter.equation):
-                        piece_equations.update({piece[0].id + str(i): piece[0]})
-                        piece_conditions.update({piece[1].id + str(i): piece[1]})
-                    self._calculate_parameters_recursively(piece_conditions, new_trail)
+        # Initialize variables that may not be set in all branches
+        expression = None
+        calc_args = []
+        
+        # If parameter has subparameters (piecewise, minmax), calculate them, else, calculate this parameter directly.
+        if parameter.piecewise:
+            piece_equations = {}
+            piece_conditions = {}
+            true_equations = {}
+            
+            if len(parameter.equation) < 2:
+                raise ParameterError(f"Piecewise function requires at least two pieces.", parameter)
+            
+            for i, piece in enumerate(parameter.equation):
+                piece_equations.update({piece[0].id + str(i): piece[0]})
+                piece_conditions.update({piece[1].id + str(i): piece[1]})
+            self._share_unit_check(parameter, list(piece_equations.values()) + list(piece_conditions.values()))
+            self._calculate_parameters_recursively(piece_conditions, new_trail)
 
-                    for cond in piece_conditions:
-                        if piece_conditions[cond].min and piece_conditions[cond].max:
-                            true_equations.update({cond.replace("cond", "eq"): piece_equations[cond.replace("cond", "eq")]})
-                    self._calculate_parameters_recursively(true_equations, new_trail)
+            for cond in piece_conditions:
+                if piece_conditions[cond].min and piece_conditions[cond].max:
+                    true_equations.update({cond.replace("cond", "eq"): piece_equations[cond.replace("cond", "eq")]})
+            self._calculate_parameters_recursively(true_equations, new_trail)
 
-                elif parameter.minmax_equation:
-                    minmax_equation_parameters = {}
-                    for i, eq in enumerate(parameter.equation):
-                        minmax_equation_parameters.update({eq.id + str(i): eq})
-                    self._calculate_parameters_recursively(minmax_equation_parameters, new_trail)
-                else:
-                    if not parameter.args:
-                        raise ParameterError("Parameter has no args nor a set value.", parameter)
+        elif parameter.minmax_equation:
+            minmax_equation_parameters = {}
+            for i, eq in enumerate(parameter.equation):
+                minmax_equation_parameters.update({eq.id + str(i): eq})
+            self._share_unit_check(parameter, minmax_equation_parameters.values())
+            self._calculate_parameters_recursively(minmax_equation_parameters, new_trail)
+        else:
+            if not parameter.args:
+                raise ParameterError("Parameter has no args nor a set value.", parameter)
 
-                    # Calculate any argument parameters that haven't been calculated yet
-                    arg_parameters = {arg: self.parameters[arg] for arg in [x for x in parameter.args if x in self.parameters]}
+            # Calculate any argument parameters that haven't been calculated yet
+            arg_parameters = {arg: self.parameters[arg] for arg in [x for x in parameter.args if x in self.parameters]}
 
-                    if not all([True if all([parameter.min, parameter.max]) else False for arg, parameter in arg_parameters.items()]):
-                        calc_args = {k: self.parameters[k] for k in [x for x in parameter.args if x in self.parameters] if "." not in k}
-                        
-                        if parameter.id in trail:
-                            raise ParameterError("Circular dependency found in path: " + "=>".join(trail) + ".", parameter)
-                        
-                        self._calculate_parameters_recursively(calc_args, new_trail)
+            if not all([True if all([parameter.min, parameter.max]) else False for arg, parameter in arg_parameters.items()]):
+                calc_args = {k: self.parameters[k] for k in [x for x in parameter.args if x in self.parameters] if "." not in k}
+                
+                if parameter.id in trail:
+                    error = ParameterError("Circular dependency found in path: " + "=>".join(trail) + ".", parameter)
+                    hint = ordering.hint(self.parameters, trail)
+                    raise error.with_note(hint) if hint else error
+                
+                self._calculate_parameters_recursively(calc_args, new_trail)
 
-                    # Make a dict of calculation parameters from the submodels
-                    calc_args = []
-                    for i, arg in enumerate(parameter.args):
-                        if "." in arg:
-                            result, prefixed_ID = self.retrieve_parameter_from_submodel(arg)
+            # Make a dict of calculation parameters from the submodels
+            calc_args = []
+            for i, arg in enumerate(parameter.args):
+                if "." in arg:
+                    result, prefixed_ID = self.retrieve_parameter_from_submodel(arg)
 
-                            if isinstance(result, Parameter):
-                                submodel_parameters[prefixed_ID] = result
-                            else:
-                                raise TypeError("Invalid result type: " + str(type(result)))    
-                            
-                            calc_args.append(prefixed_ID)                        
-                        else:
-                            calc_args.append(arg)
+                    if isinstance(result, Parameter) and result.failure is not None:
+                        raise result.failure
+                    if isinstance(result, Parameter):
+                        submodel_parameters[prefixed_ID] = result
+                    else:
+                        raise IDError(self, arg, f"{arg} from {parameter.id} is not a parameter.")
+                    
+                    calc_args.append(prefixed_ID)                        
+                else:
+                    calc_args.append(arg)
 
-                    # Substitute submodel params in the expression
-                    # This has to be done sorted or longer params could get accidentally replaced by a shorter params that match the beginning of the longer param
-                    expression = parameter.equation
-                    sorted_parameter_args = [arg for arg in parameter.args]
-                    sorted_parameter_args.sort(key=len, reverse=True)
-                    for i, arg in enumerate(sorted_parameter_args):
-                        if "." in arg:
-                            result, prefixed_ID = self.retrieve_parameter_from_submodel(arg)
+            # Substitute submodel params in the expression
+            # This has to be done sorted or longer params could get accidentally replaced by a shorter params that match the beginning of the longer param
+            expression = parameter.equation
+            sorted_parameter_args = [arg for arg in parameter.args]
+            sorted_parameter_args.sort(key=len, reverse=True)
+            for i, arg in enumerate(sorted_parameter_args):
+                if "." in arg:
+                    result, prefixed_ID = self.retrieve_parameter_from_submodel(arg)
 
-                            if not parameter.callable:
-                                expression = re.sub(r"(?<!\w)" + re.escape(arg), re.escape(prefixed_ID), expression)
+                    if not parameter.callable:
+                        expression = re.sub(r"(?<!\w)" + re.escape(arg), re.escape(prefixed_ID), expression)
 
-                # all_params = self.parameters | submodel_parameters | self.constants
+        # all_params = self.parameters | submodel_parameters | self.constants
 
-                # Calculate the parameter
-                calculation = None
-                try:
-                    calculation = self._compute_parameter_value(
-                        parameter, expression, submodel_parameters, calc_args
-                    )
-                except (ImportedFunctionError, Exception) as e:
-                    # If primary calculation failed and we have a fallback, try it
-                    if parameter.fallback_param is not None:
-                        calculation = self._compute_fallback(parameter, submodel_parameters, e, new_trail)
-                    else:
-                        raise
-                parameter.assign(calculation)
+        # Calculate the parameter
+        _first_empty = _first_infinite = None
+        calculation = None
+        try:
+            calculation = self._compute_parameter_value(
+                parameter, expression, submodel_parameters, calc_args
+            )
+        except EvaluationLimitError as e:
+            e.parameter = e.parameter or parameter
+            raise
+        except (ImportedFunctionError, Exception) as e:
+            # If primary calculation failed and we have a fallback, try it
+            if parameter.fallback_param is not None:
+                calculation = self._compute_fallback(parameter, submodel_parameters, e, new_trail)
+            else:
+                raise
+        parameter.corners = None
+        if corners.enabled and expression is not None and not parameter.callable and not parameter.pointer and not parameter.used_fallback:
+            parameter.corners = corners.track(parameter, expression, globals(), self._scope(parameter, submodel_parameters, calc_args), calc_args, calculation)
+        calculation = self._apply_infinity_policy(parameter, calculation)
+        parameter.assign(calculation)
+        parameter.empty = self._empty_origin(parameter, calculation) if is_empty(parameter) else None
+
+    @staticmethod
+    def _share_unit_check(parameter, parts):
+        # The branches and conditions of a piecewise equation, and the ends of a min/max
+        # equation, aren't unit checked if the parameter isn't
+        if unit_check_justification(parameter.annotations) is not None:
+            for part in parts:
+                part.annotations["unit_unchecked"] = parameter.annotations["unit_unchecked"]
+
+    def _iterate_parameter(self, parameter, trail):
+        """
+        Calculate a parameter marked `@iterate` by iterating the cycle it's in until its
+        value settles (see oneil.ordering). A parameter that isn't in a cycle is calculated
+        once.
+        """
+        members = ordering.cycle(self.parameters, parameter.id) if self.parameters.get(parameter.id) is parameter else set()
+        if not members:
+            self._calculate_parameter(parameter, trail)
+            return
+        guess = parameter.annotations["iterate"][0]
+        guess = un.parse(parameter.hr_units)[1](guess) if parameter.hr_units else guess
+        previous = (guess, guess)
+        for iteration in range(1, ordering.settings["max_iterations"] + 1):
+            # Calculate the rest of the cycle from the previous value, then the parameter
+            # from the rest of the cycle.
+            self._reset(members)
+            parameter.min, parameter.max = previous
+            self._calculate_parameters_recursively({ID: self.parameters[ID] for ID in members}, trail + [parameter.id])
+            parameter.min = parameter.max = None
+            self._calculate_parameter(parameter, trail)
+            value = (parameter.min, parameter.max)
+            if not ordering.finite(value):
+                raise ParameterError(f"The cycle through {parameter.id} diverged: its value isn't a finite number after {iteration} iteration(s).", parameter)
+            change = ordering.change(previous, value)
+            if change < ordering.settings["tolerance"]:
+                parameter.iterations = iteration
+                return
+            previous = ordering.step(previous, value)
+        raise ParameterError(f"The cycle through {parameter.id} didn't converge in {iteration} iterations: its value still changed by {100 * change:.3g}% in the last one.", parameter)
+
+    def _empty_origin(self, parameter, calculation):
+        """
+        Where an empty parameter's emptiness came from: an input that's empty, the branch
+        of a piecewise parameter that's empty, or else the first operation in its own
+        equation that produced an empty interval. Inputs that inherit an empty interval
+        share its origin, so the origin records the first parameter that had it.
+        """
+        for arg in parameter.args:
+            try:
+                source = self.retrieve_parameter_from_submodel(arg)[0] if '.' in arg else self.parameters.get(arg)
+            except OneilError:
+                continue
+            if isinstance(source, Parameter) and source.empty:
+                return source.empty
+        if isinstance(calculation, Parameter) and calculation.empty:
+            return calculation.empty
+
+        expression = _first_empty or parameter.id
+        line_no = parameter.line_no['model line'] if isinstance(parameter.line_no, dict) else parameter.line_no
+        return {"id": parameter.id.split(":")[0], "model": self.name, "line": line_no, "expression": expression, "reason": empty_reason(expression)}
+
+    def _apply_infinity_policy(self, parameter, calculation):
+        """
+        Apply the infinity policy (see oneil.infinity) to a parameter's calculated value if
+        it has an infinite bound that its inputs don't: raise a ParameterError ("fail"),
+        record where it came from in parameter.unbounded ("warn"), or record it and clamp
+        the infinite bounds ("saturate"). Returns the value to assign.
+        """
+        parameter.unbounded = None
+        policy = infinity.settings["policy"]
+        if policy == "allow" or not isinstance(calculation, Parameter) or is_empty(calculation) or not infinity.is_infinite(calculation):
+            return calculation
+        if calculation.unbounded:
+            parameter.unbounded = calculation.unbounded
+            return calculation
+        if isinstance(parameter.equation, str) and re.search(r"(?<![\w.])inf(?!\w)", parameter.equation):
+            return calculation
+        for arg in parameter.args:
+            try:
+                source = self.retrieve_parameter_from_submodel(arg)[0] if '.' in arg else self.parameters.get(arg)
+            except OneilError:
+                continue
+            if isinstance(source, Parameter) and infinity.is_infinite(source):
+                # Infinite because an input is: by design, or reported where it started.
+                parameter.unbounded = source.unbounded
+                return calculation
+
+        expression = _first_infinite or parameter.id
+        if policy == "fail":
+            raise ParameterError(f"{expression} is infinite though its inputs are finite, from {infinite_reason(expression)}. Revise the equation or its inputs, or set the infinity policy.", parameter)
+
+        line_no = parameter.line_no['model line'] if isinstance(parameter.line_no, dict) else parameter.line_no
+        parameter.unbounded = {"id": parameter.id.split(":")[0], "model": self.name, "line": line_no, "expression": expression, "reason": infinite_reason(expression), "saturated": None}
+        if policy == "saturate":
+            parameter.unbounded["saturated"] = infinity.bound(calculation.units)
+            calculation = copy.copy(calculation)
+            calculation.min = infinity.saturate(calculation.min, calculation.units)
+            calculation.max = infinity.saturate(calculation.max, calculation.units)
+        return calculation
+
+    def infinity_report(self):
+        """
+        The values in the model and its submodels that became infinite from finite inputs
+        (see oneil.infinity), grouped by where they came from: for each origin, the
+        parameter and model it was produced in, the operation that produced it and why,
+        the bound it was saturated at (in base units, or None), the parameters it made
+        infinite, the tests that use them, and an InfinityWarning.
+        """
+        origins = {}
+        for model, parameter in self._annotated_parameters(None):
+            if parameter.unbounded:
+                origin = origins.setdefault(id(parameter.unbounded), {"origin": parameter.unbounded, "affected": [], "tests": []})
+                origin["affected"].append(parameter.id if model is self else f"{parameter.id} in {model.name}")
+                for test in model.tests:
+                    label = test.label() if model is self else f"{test.label()} in {model.name}"
+                    if parameter.id in test.args and label not in origin["tests"]:
+                        origin["tests"].append(label)
+
+        report = []
+        for entry in origins.values():
+            origin = entry["origin"]
+            if origin["saturated"] is None:
+                message = f"{origin['id']} is infinite: {origin['expression']} has an infinite bound though its inputs are finite, from {origin['reason']}."
+            else:
+                message = f"{origin['id']} was infinite and is saturated at ±{origin['saturated']:g} (base units): {origin['expression']} has an infinite bound though its inputs are finite, from {origin['reason']}."
+            warning = InfinityWarning(origin["model"] + ".on", origin["line"], message)
+            downstream = [ID for ID in entry["affected"] if ID != origin["id"]]
+            if downstream:
+                warning.with_note(("Also saturated because of it: " if origin["saturated"] is not None else "Also infinite because of it: ") + ", ".join(downstream))
+            if entry["tests"]:
+                warning.with_note(("Tests that use the saturated values: " if origin["saturated"] is not None else "Tests that compare against infinity: ") + ", ".join(entry["tests"]))
+            report.append({"id": origin["id"], "model": origin["model"], "expression": origin["expression"], "reason": origin["reason"], "saturated": origin["saturated"], "affected": entry["affected"], "tests": entry["tests"], "warning": warning})
+        return report
+
+    def empty_report(self):
+        """
+        The empty intervals in the model and its submodels, grouped by where they came
+        from: for each origin, the parameter and model it was first produced in, the
+        operation that produced it and why, the parameters it made empty (as `ID` or
+        `ID in <model>`), and an EmptyIntervalWarning.
+        """
+        origins = {}
+        for model, parameter in self._annotated_parameters(None):
+            if parameter.empty and is_empty(parameter):
+                origin = origins.setdefault(id(parameter.empty), {"origin": parameter.empty, "affected": []})
+                origin["affected"].append(parameter.id if model is self else f"{parameter.id} in {model.name}")
+
+        report = []
+        for entry in origins.values():
+            origin = entry["origin"]
+            warning = EmptyIntervalWarning(origin["model"] + ".on", origin["line"], f"{origin['id']} is an empty interval: {origin['expression']} has bounds that aren't numbers, from {origin['reason']}.")
+            downstream = [ID for ID in entry["affected"] if ID != origin["id"]]
+            if downstream:
+                warning.with_note("Also empty because of it: " + ", ".join(downstream))
+            report.append({"id": origin["id"], "model": origin["model"], "expression": origin["expression"], "reason": origin["reason"], "affected": entry["affected"], "warning": warning})
+        return report
 
     def _compute_parameter_value(self, parameter, expression, submodel_parameters, calc_args):
         """
@@ -2604,8 +5454,22 @@ class Model:
         else:
             if parameter.pointer:
                 return (self.parameters | submodel_parameters | self.constants)[expression]
-            else:
-                return parameter.calculate(expression, globals(), self.parameters | submodel_parameters | self.constants, calc_args)
+            result = parameter.calculate(expression, globals(), self._scope(parameter, submodel_parameters, calc_args), calc_args)
+            if unit_check_justification(parameter.annotations) is not None and isinstance(result, Parameter):
+                # The result of an equation that isn't unit checked takes the parameter's units
+                result = Parameter((result.min, result.max), parameter.units, result.name)
+            return result
+
+    def _scope(self, parameter, submodel_parameters, calc_args):
+        """
+        The values a parameter is calculated from. A parameter marked `@unit_unchecked`
+        gets its inputs without units, as if each were in strip(), as numbers in base SI
+        units.
+        """
+        scope = self.parameters | submodel_parameters | self.constants
+        if unit_check_justification(parameter.annotations) is None:
+            return scope
+        return scope | {ID: par_strip(scope[ID]) for ID in calc_args if isinstance(scope.get(ID), Parameter)}
 
     def _compute_fallback(self, parameter, submodel_parameters, original_error, trail):
         """
@@ -2656,6 +5520,8 @@ class Model:
             raise ParameterError(f"Both primary and fallback calculations failed. Fallback error: {e}", parameter)
 
     def retrieve_parameter_from_submodel(self, ID):
+        if ID.count(".") != 1:
+            raise IDError(self, ID, "Submodel parameters must be of the form \"<ID>.<submodel symbol>\".")
         parameter_ID, submodel_ID = ID.split(".")
         path = []
         prefixed_ID = ""
@@ -2671,21 +5537,21 @@ class Model:
     
     # Recursively retrieve a parameter from a submodel or submodel of a submodel, etc.
     def _retrieve_parameter_recursively(self, parameter_ID, path, trail=[]):
-        new_trail = copy.copy(trail).append(self.name)
+        new_trail = trail + [self.name]
 
         if path:
             submodel_name = path.pop(0)
             submodel = [model['model'] for k, model in self.submodels.items() if 'model' in model and model['model'].name == submodel_name]
             if submodel:
                 submodel = submodel[0]
-                result = submodel._retrieve_parameter_recursively(parameter_ID, path, trail)
+                result = submodel._retrieve_parameter_recursively(parameter_ID, path, new_trail)
             else:
-                raise IDError(submodel, parameter_ID, f"Submodel name \"{submodel_name}\" not found while retrieving parameter ID \"{parameter_ID}\" from path ({', '.join(new_trail.append(submodel_name).append(path))}).")
+                raise IDError(self, parameter_ID, f"Submodel name \"{submodel_name}\" not found while retrieving parameter ID \"{parameter_ID}\" from path ({', '.join(new_trail + [submodel_name] + path)}).")
         else:
             if parameter_ID in self.parameters:
                 result = self.parameters[parameter_ID]
             else:
-                raise IDError(self, parameter_ID, f"Parameter ID \"{parameter_ID}\" not found in path ({self.name + (new_trail or '')}).")
+                raise IDError(self, parameter_ID, f"Parameter ID \"{parameter_ID}\" not found in path ({', '.join(new_trail)}).")
         
         return result
 
@@ -2748,14 +5614,14 @@ def handler(model: Model, inpt: str) -> Model:
         opts[arg.split("=")[0]] = arg_value
     
     # Handle flag-style arguments (e.g., "verbose" without "=")
-    flag_args = ["verbose", "turtles", "up"]
+    flag_args = ["verbose", "turtles", "up", "sections"]
     for flag in flag_args:
         if flag in args:
             args.remove(flag)
             opts[flag] = True
 
     # Options that must be integers (numeric opts are parsed as float above)
-    int_opts = ["sigfigs", "levels", "indent"]
+    int_opts = ["sigfigs", "levels", "indent", "points", "samples", "seed"]
     for k in int_opts:
         if k in opts:
             try:
@@ -2769,18 +5635,57 @@ def handler(model: Model, inpt: str) -> Model:
     cleaned_inpt = " ".join([cmd] + args).strip()
 
     # Print precision for parameter/expression branches below.
-    sigfigs = opts.get("sigfigs", 4)
+    sigfigs = opts.setdefault("sigfigs", config.display["sigfigs"])
 
     try:
         if cmd == "tree":
-            model.tree(args, **opts)
+            model.tree(args, **{k: v for k, v in opts.items() if k != "sections"})
         elif cmd == "summarize":
-            summarize_opts = {k: v for k, v in opts.items() if k in ("sigfigs", "verbose")}
+            summarize_opts = {k: v for k, v in opts.items() if k in ("sigfigs", "verbose", "sections")}
             model.summarize(**summarize_opts)
         elif cmd == "all":
-            model.all(sigfigs=sigfigs)
+            model.all(sigfigs=sigfigs, sections=opts.get("sections", False), tag=opts.get("tag"))
         elif cmd == "dependents":
             model.dependents(args)
+        elif cmd == "explain":
+            # Use the raw words, since labels can contain words that are flags elsewhere.
+            name = " ".join(word for word in inpt.strip().split(" ")[1:] if not word.startswith("sigfigs=")).strip("\"'")
+            if not name:
+                print("Usage: explain <ID or label>")
+                return model
+            # An error code (e.g. E0104) that isn't also a parameter explains the code
+            if re.fullmatch(r"[EW]\d{4}", name) and name not in model.parameters:
+                explanations.print_explanation(name)
+                return model
+            model.explain(name, sigfigs=sigfigs)
+        elif cmd == "piecewise":
+            names = [arg for arg in args if arg != "json"]
+            if not names or len(names) > 2:
+                print("Usage: piecewise <ID> [<input ID>] [points=N] [json]")
+                return model
+            input_ID = names[1] if len(names) > 1 else None
+            if "json" in args:
+                print(json.dumps(model.piecewise_series(names[0], input_ID, opts.get("points", 50)), indent=2, default=float))
+            else:
+                model.print_piecewise(names[0], input_ID, opts.get("points", 20), sigfigs=sigfigs)
+        elif cmd == "symbolic":
+            names = [arg for arg in args if arg != "latex"]
+            if not names:
+                print("Usage: symbolic <ID> [<input ID>...] [latex]")
+                return model
+            symbolic.print_closed_form(model, names[0], names[1:], latex="latex" in args)
+        elif cmd == "sensitivity":
+            sensitivity.print_sensitivities(model, args or None, sigfigs=sigfigs)
+        elif cmd == "sweep":
+            specs = [arg for arg in opt_list if arg.split("=")[0] != "sigfigs"]
+            if not specs:
+                print("Usage: sweep <ID>=<start>..<stop>/<step>[:<units>] [...] [outputs]")
+            else:
+                sweep.print_sweep(sweep.sweep(model, specs, args or None), sigfigs=sigfigs)
+        elif cmd == "montecarlo":
+            report = montecarlo.simulate(model, opts.get("samples", 1000), opts.get("seed"))
+            IDs = [(model.name, model.lookup(ID)) for ID in args] if args else None
+            montecarlo.print_simulation(report, IDs, sigfigs=sigfigs)
         elif cmd == "independent":
             model.independent()
         elif cmd == "design":
@@ -2793,13 +5698,21 @@ def handler(model: Model, inpt: str) -> Model:
             args = [arg if "." in arg else arg + ".on" for arg in args]
             model.overwrite(args)
         elif cmd == "test":
-            model.test()
+            fail_count, _ = model.test()
+            if fail_count:
+                console.record_exit(console.EXIT_TEST_FAILURE)
         elif cmd == "export":
-            model.export_pdf(args)
+            if args and args[0] == "json":
+                # export json [<file>] [source]
+                source = "source" in args[1:]
+                files = [arg for arg in args[1:] if arg != "source"]
+                model.export_json(files[0] if files else None, source=source)
+            else:
+                model.export_pdf(args)
         elif cmd == "load":
             model_name, model_designs, commands = parse_args(args)
 
-            model = loader(model_name, model_designs, capture_errors=False)
+            model = loader(model_name, model_designs, capture_errors=False, partial=True)
 
             for command in commands:
                 print("(" + bcolors.OKBLUE + model.name + bcolors.ENDC + ") >>> " + command)
@@ -2815,9 +5728,9 @@ def handler(model: Model, inpt: str) -> Model:
             else:
                 _function_cache.set_verbose(False)
             if model.design == "default":
-                model = loader(model.name, [], capture_errors=False)
+                model = loader(model.name, [], capture_errors=False, partial=True)
             else:
-                model = loader(model.name, [model.design], capture_errors=False)
+                model = loader(model.name, [model.design], capture_errors=False, partial=True)
         elif cmd == "cache":
             if args and args[0] == "clear":
                 _function_cache.clear()
@@ -2837,12 +5750,19 @@ def handler(model: Model, inpt: str) -> Model:
             return model
         elif cmd == "units":
             print(un.print_all())
+        elif cmd == "builtins":
+            kinds = [arg.removesuffix("s") for arg in args] or ["function", "constant"]
+            unknown = [arg for arg, kind in zip(args, kinds) if kind not in builtin.KINDS]
+            if unknown:
+                print(f"Unknown kind of builtin: {', '.join(unknown)} (functions, constants, or units).")
+            else:
+                builtin.print_builtins(builtin.default(), kinds)
         elif cmd == "quit":
-            sys.exit()
+            sys.exit(console.exit_code)
         elif cmd == "quit()":
-            sys.exit()
+            sys.exit(console.exit_code)
         elif cmd == "exit":
-            sys.exit()
+            sys.exit(console.exit_code)
         elif ":" in cleaned_inpt:
             param_expr = cleaned_inpt.split(":")[0].strip()
             requested_units = cleaned_inpt.split(":")[1].strip()
@@ -2881,13 +5801,17 @@ def handler(model: Model, inpt: str) -> Model:
             if isinstance(result, Parameter):
                 result.hprint(sigfigs=sigfigs)
             else:
-                raise TypeError("Invalid result type: " + str(type(result)))
+                raise IDError(model, cleaned_inpt, f"{cleaned_inpt} is not a parameter.")
         else:
             print(f"Command {cleaned_inpt} not found. Type 'help' for a list of commands.")
             return model
     except OneilError as err:
         console.print_error(err)
         return model
+    except Exception as err:
+        # A bug in Oneil: report it and keep the session going.
+        crash.report(err, inpt.strip())
+        return model
     
     return model
             
@@ -2899,18 +5823,22 @@ Commands:
         Options:
             verbose     Show parameter names (e.g., U: 0.2 -- "Uptime")
             levels=N    Limit tree depth to N levels (default: 3)
-            sigfigs=N   Significant figures for printed values (default: 4)
+            sigfigs=N   Significant figures for printed values (default: 4, or display.sigfigs in the config)
 
     summarize [options]
         Print a summary of the model.
         Options:
             verbose     Include all parameters, not just performance parameters
-            sigfigs=N   Significant figures for printed values (default: 4)
+            sections    Group the parameters by section, with each section's tests
+            sigfigs=N   Significant figures for printed values (default: 4, or display.sigfigs in the config)
 
     all [options]
         Print all parameters.
         Options:
-            sigfigs=N   Significant figures for printed values (default: 4)
+            sections    Group the parameters by section (in declaration order), with
+                        each section's tests
+            tag=TAG     Only print the parameters tagged with TAG (see @tags)
+            sigfigs=N   Significant figures for printed values (default: 4, or display.sigfigs in the config)
 
     [param]
     [param.submodel]
@@ -2919,11 +5847,46 @@ Commands:
         Print a parameter, submodel parameter, or evaluated expression.
         Use 'param:units' to display in alternative units (e.g., 'P:W').
         Options:
-            sigfigs=N   Significant figures for printed values (default: 4)
+            sigfigs=N   Significant figures for printed values (default: 4, or display.sigfigs in the config)
 
     dependents [param 1] [param 2] ... [param n]
         Print all parameters that depend on the specified parameters.
 
+    explain [param or label]
+        Print where a parameter is defined, its value and limits, what it depends on,
+        what uses it, and its notes. The parameter can be given by its ID or its label
+        (e.g. 'explain Rotation rate'). Given an error code instead (e.g. 'explain
+        E0104'), it prints the code's cause, an example, and how to fix it.
+
+    piecewise [param] [input] [points=N] [json]
+        Sample a piecewise parameter across an independent parameter its conditions use
+        (the first one by default) and print the values with the active branch, and
+        where it switches branches, flagging jumps. With 'json', print the samples as
+        JSON for plotting.
+
+    symbolic [param] [inputs] [latex]
+        Print the parameter's closed-form expression: its equation with the equations
+        it depends on substituted in, in terms of the given inputs (or of the
+        independent parameters it depends on), in base units. With 'latex', also print
+        it as LaTeX.
+
+    sensitivity [params]
+        Print the exact derivatives of the parameters (the performance parameters by
+        default) with respect to the inputs they depend on, at the inputs' nominal
+        values, with the elasticity of each, warning about piecewise boundaries.
+
+    sweep [ID=start..stop/step] [ID=a,b,c] ... [outputs]
+        Evaluate the model at every combination of the values of the inputs (in their
+        display units, or in units given after a colon, e.g. m=2..6/1:kg) and print the
+        outputs (the performance parameters by default) and failed tests at each point.
+        Only the parameters that depend on the inputs are recalculated.
+
+    montecarlo [params] [samples=N] [seed=S]
+        Evaluate the model with N samples (1000 by default) of its inputs, drawn from
+        their @distribution annotations, and print the interval, mean, standard
+        deviation, and percentiles of the parameters (the performance parameters by
+        default), and how often the tests that didn't always pass passed.
+
     independent
         Print all independent parameters.
 
@@ -2933,6 +5896,11 @@ Commands:
     test
         Run all tests on the model and any loaded designs.
 
+    export json [file] [source]
+        Export the results of the model and its submodels to a JSON file (default:
+        <model>.json, or "-" to print them). With 'source', each parameter includes the
+        file, line, and columns where it's defined.
+
     export [param 1] [param 2] ... [param n]
         Export the entire model to a PDF file or just the specified parameters.
 
@@ -2958,14 +5926,21 @@ Commands:
     units
         Print all units supported in Oneil.
 
+    builtins [functions] [constants] [units]
+        Print the builtin functions and constants (or the given kinds), including
+        those added by plugins, with how they're called and what they are.
+
     quit
     quit()
     exit
         Exit the program.
 """
 
-def loader(inp: str, designs: list[str], capture_errors: bool = True, quiet: bool = False) -> Model:
+def loader(inp: str, designs: list[str], capture_errors: bool = True, quiet: bool = False, overrides: list[str] | None = None, partial: bool = False) -> Model:
+    # With `partial`, the model is evaluated around the errors in it, which are reported
+    # together (see Model)
     model = None
+    quiet = quiet or console.quiet
     _function_cache.reset_usage_summary()
 
     while not model:
@@ -2975,7 +5950,7 @@ def loader(inp: str, designs: list[str], capture_errors: bool = True, quiet: boo
                 inp = ""
                 continue
             if inp == "quit" or inp == "quit()" or inp == "exit":
-                sys.exit()
+                sys.exit(console.exit_code)
             if "." not in inp: 
                 inp += ".on"
             else:
@@ -2984,10 +5959,12 @@ def loader(inp: str, designs: list[str], capture_errors: bool = True, quiet: boo
                     inp = ""
                     continue
             if os.path.exists(inp):
+                crash.current_model = os.path.abspath(inp)
                 if not quiet:
                     print("Loading model " + inp + "...")
                 try:
-                    model = Model(inp)
+                    with memory.phase("parse and resolve", inp):
+                        model = Model(inp, partial=partial)
                     model.build(quiet=quiet)
                 except OneilError as err:
                     if not capture_errors:
@@ -2997,16 +5974,29 @@ def loader(inp: str, designs: list[str], capture_errors: bool = True, quiet: boo
                         model = None
                         inp = ""
             else:
+                if not capture_errors:
+                    raise ModelNotFoundError(inp)
                 print("Model " + inp + " not found.")
                 inp = ""
                 continue
         else:
+            console.summarize_errors()
+            completion.complete_models()
             inp = input("Enter a model: ")
 
     # Load designs
     for design in designs:
         handler(model, "design " + design)
 
+    # Apply command-line overrides on top of the designs
+    if overrides:
+        try:
+            model.apply_overrides(overrides, quiet=quiet)
+        except OneilError as err:
+            if not capture_errors:
+                raise err
+            console.print_error(err)
+
     return model
 
 loader_help = """"
@@ -3026,17 +6016,68 @@ loader_help = """"
     You are in the loader. To access other commands, you need to load a model. For more information, see the README.
 """
 
+def load_config(reloaded=False):
+    """
+    Load the user config (display settings and extra constants). Invalid settings are
+    reported as warnings and the rest are still applied.
+    """
+    try:
+        config.load(MATH_CONSTANTS)
+    except ConfigError as e:
+        console.print_warning(e)
+    if reloaded and not console.quiet:
+        print(f"Reloaded config from {config.path()}")
+
 def interpreter(model):
+    # The model (and design) whose IDs are completed, which changes with load, reload,
+    # and design
+    completing = None
     while True:
+        if completing is None or completing[0] is not model or completing[1] != model.design:
+            completion.complete_repl(model)
+            completing = (model, model.design)
+        if config.changed():
+            load_config(reloaded=True)
         if model.design == "default":
             model = handler(model, input(f"({bcolors.OKBLUE}{model.name}{bcolors.ENDC}) >>> "))
         else:
             model = handler(model, input(f"({bcolors.ORANGE}{model.design}@{bcolors.ENDC}{bcolors.OKBLUE}{model.name}{bcolors.ENDC}) >>> "))
+        console.summarize_errors()
 
 def debugger(model):
     print("Enterring debug mode. Type 'quit' to exit.")
     while True:
         model = handler(model, input(f"{bcolors.FAIL}debugger{bcolors.ENDC} ({bcolors.OKBLUE}{model.name}{bcolors.ENDC}) >>> "))
+        console.summarize_errors()
+
+def extract_flag(args: list[str], flag: str) -> tuple[list[str], bool]:
+    """
+    Remove a boolean flag from the args, returning the remaining args and whether it was given.
+    """
+    remaining = [arg for arg in args if arg != flag]
+    return remaining, len(remaining) != len(args)
+
+def extract_option(args: list[str], flag: str) -> tuple[list[str], list[str]]:
+    """
+    Remove every occurrence of a repeatable option (`--flag value` or `--flag=value`)
+    from the args, returning the remaining args and the collected values.
+    """
+    remaining = []
+    values = []
+    args = iter(args)
+    for arg in args:
+        if arg == flag:
+            value = next(args, None)
+            if value is None:
+                print(f"{bcolors.error('ERROR')} Missing value for {flag}.")
+                sys.exit(1)
+            values.append(value)
+        elif arg.startswith(flag + "="):
+            values.append(arg[len(flag) + 1:])
+        else:
+            remaining.append(arg)
+
+    return remaining, values
 
 def parse_args(args: list[str]) -> tuple[str, list[str], list[str]]:
     # if there are no arguments, then the user needs to be prompted for details
@@ -3068,38 +6109,687 @@ def perform_regression_test(model_file):
     handler(model, "test")
 
     return
-    
+
+def _used_models(files):
+    """
+    The model files used by the given files, found from their `use` and `from` lines
+    without loading them.
+    """
+    used = set()
+    for file in files:
+        directory = os.path.dirname(file)
+        try:
+            lines = read_model_lines(file)
+        except (OSError, OneilError):
+            continue
+        for line, _ in lines:
+            match = re.search(r"^(?:from\s+\S+\s+)?use\s+(\w+)", line)
+            if match:
+                used.add(os.path.abspath(os.path.join(directory, match.group(1) + ".on")))
+    return used
+
+def _summary(model, output="table", samples=0, seed=None):
+    summary = {"model": model.name, "parameters": len(model.parameters), "tests": model.test_count, "failed": model.fail_count, "performance": model.performance_parameters(), "tagged": model.tagged_parameters()}
+    if output == "json":
+        summary["results"] = model.results()
+    if samples:
+        with stats.working_directory(os.path.dirname(model.model_filename)):
+            summary["monte_carlo"] = montecarlo.simulate(model, samples, seed)
+    return summary
+
+def _number(value, default="-"):
+    if value is None:
+        return default
+    return f"{value:.4g}" if isinstance(value, float) else str(value)
+
+def print_results_json(results):
+    """
+    Print the results of the evaluated models as one JSON document, for scripts and CI:
+    each model's file, whether it passed, how long it took, and its results (see
+    Model.results()) with its performance and tagged parameters, or the error that
+    stopped it. Errors still set the exit code.
+    """
+    models = []
+    for file, summary, elapsed, err in sorted(results, key=lambda r: r[0]):
+        entry = {"file": file, "passed": not err and not summary["failed"], "time": elapsed}
+        if err:
+            console.record_exit(console.exit_code_for(err))
+            entry["error"] = console.error_json(err)
+        else:
+            entry |= {key: summary[key] for key in ("results", "performance", "tagged", "monte_carlo") if key in summary}
+        models.append(entry)
+    print(json.dumps({"models": models}, indent=2, default=float))
+
+def print_performance(results):
+    """
+    Print the performance parameters of the evaluated models with their limits, the
+    smallest margin to a limit, and whether they passed.
+    """
+    table = BeautifulTable()
+    table.columns.header = ["Model", "Parameter", "Value", "Limits", "Margin", "Result"]
+    for file, summary, _, _ in sorted(results, key=lambda r: r[0]):
+        for entry in (summary or {}).get("performance", []):
+            units = " " + entry["units"] if entry["units"] else ""
+            value = _number(entry["min"]) if entry["min"] == entry["max"] else f"{_number(entry['min'])}|{_number(entry['max'])}"
+            limits, margin = "-", "-"
+            if isinstance(entry["limits"], dict):
+                limits = f"[{_number(entry['limits']['min'], '-inf')}, {_number(entry['limits']['max'], 'inf')}]"
+                margins = [m for m in entry["margins"].values() if m is not None]
+                margin = _number(min(margins)) + units if margins else "-"
+            elif entry["limits"]:
+                limits = "[" + ", ".join(str(option) for option in entry["limits"]) + "]"
+            model = file if entry["model"] == summary["model"] else f"{file} ({entry['model']})"
+            status = bcolors.OKGREEN + "PASS" + bcolors.ENDC if entry["passed"] else bcolors.FAIL + "FAIL" + bcolors.ENDC
+            table.append_row([model, f"{entry['name']} ({entry['id']})", value + units, limits, margin, status])
+
+    if len(table.rows):
+        print(table)
+    else:
+        print("No performance parameters found.")
+
+def print_tagged(results, tags):
+    """
+    Print the parameters of the evaluated models with any of the given tags.
+    """
+    table = BeautifulTable()
+    table.columns.header = ["Model", "Parameter", "Value", "Tags"]
+    for file, summary, _, _ in sorted(results, key=lambda r: r[0]):
+        for entry in (summary or {}).get("tagged", []):
+            if not any(tag in entry["tags"] for tag in tags):
+                continue
+            value = _number(entry["min"]) if entry["min"] == entry["max"] else f"{_number(entry['min'])}|{_number(entry['max'])}"
+            model = file if entry["model"] == summary["model"] else f"{file} ({entry['model']})"
+            table.append_row([model, f"{entry['name']} ({entry['id']})", value + (" " + entry["units"] if entry["units"] else ""), ", ".join(entry["tags"])])
+
+    if len(table.rows):
+        print(table)
+    else:
+        print(f"No parameters tagged {' or '.join(tags)} found.")
+
+def model_overrides(overrides: list[str], files: list[str]) -> dict[str, list[str]]:
+    """
+    Sort overrides for many models ("craft.m=5:kg" or "craft.r.w=2:m") by the model files
+    they name. Each override applies to every file with that name (without ".on").
+    """
+    by_file = {}
+    for override in overrides:
+        if "." not in override.split("=")[0]:
+            raise OverrideError(override, "Overrides of oneil eval must name the model: \"<model>.<ID>=<value>\".")
+        name, rest = override.split(".", 1)
+        matches = [file for file in files if os.path.splitext(os.path.basename(file))[0] == name.strip()]
+        if not matches:
+            raise OverrideError(override, f"Model \"{name.strip()}\" isn't one of the models being evaluated.")
+        for file in matches:
+            by_file.setdefault(file, []).append(rest)
+    return by_file
+
+def evaluate_all(paths: list[str], entries_only: bool = True, stream: bool = False, performance: bool = False, tags: list[str] = [], output: str = "table", overrides: list[str] = [], samples: int = 0, seed: int | None = None):
+    """
+    Load and evaluate many models in one process, sharing their resolved submodels, and
+    print a summary table. Directories are searched for .on files; unless `entries_only`
+    is False, only the entry models (those not used by another model) in them are
+    evaluated. Design files and fixtures (see oneil.fixtures) are skipped.
+
+    With `stream`, each model is loaded, evaluated, and released before the next one
+    (without sharing submodels), so only one model is in memory at a time. With
+    `performance`, the performance parameters of each model are printed after the table,
+    and with `tags`, the parameters with any of the tags. With `output` "json", the
+    results of each model (see print_results_json()) are printed instead.
+
+    `overrides` (see model_overrides()) are applied to the models they name, which are
+    evaluated even if other models use them. Without `stream`, the overridden model is
+    shared, so the models that use it see the overrides too: every override is applied
+    before any model is evaluated, so the results don't depend on the order of the files.
+
+    With `samples`, each model is also evaluated with that many samples of its inputs
+    (see oneil.montecarlo), and the statistics of its performance parameters are printed
+    after the table (or included as "monte_carlo" in the JSON results).
+    """
+    named = [path for path in paths if not os.path.isdir(path)]
+    results = []
+    overridden = model_overrides(overrides, fixtures.models(paths)) if overrides else {}
+    named += list(overridden)
+    if stream:
+        files = fixtures.models(paths)
+        used = _used_models(files) if entries_only else set()
+        for file in files:
+            if entries_only and os.path.abspath(file) in used and file not in named:
+                continue
+            directory, name = os.path.split(file)
+            start = time.perf_counter()
+            try:
+                with stats.working_directory(directory):
+                    with memory.phase("parse and resolve", file):
+                        model = Model(name)
+                    if not model.parameters and not model.tests:
+                        continue
+                    if file in overridden:
+                        model.apply_overrides(overridden[file], quiet=True)
+                    else:
+                        model.build(quiet=True)
+                results.append((file, _summary(model, output, samples, seed), time.perf_counter() - start, None))
+            except OneilError as err:
+                results.append((file, None, time.perf_counter() - start, err))
+            model = None
+            gc.collect()
+    else:
+        with shared_submodels():
+            models = {}
+            for file in fixtures.models(paths):
+                directory, name = os.path.split(file)
+                start = time.perf_counter()
+                try:
+                    with stats.working_directory(directory), memory.phase("parse and resolve", file):
+                        models[file] = (load_submodel(name), time.perf_counter() - start)
+                except OneilError as err:
+                    results.append((file, None, time.perf_counter() - start, err))
+
+            used = {entry['model'].model_filename for model, _ in models.values() for entry in model.submodels.values() if 'model' in entry}
+            failed = {}
+            for file in overridden:
+                if file in models:
+                    try:
+                        with stats.working_directory(os.path.dirname(file)):
+                            models[file][0].apply_overrides(overridden[file], quiet=True, build=False)
+                    except OneilError as err:
+                        failed[file] = err
+
+            for file, (model, load_time) in models.items():
+                if not model.parameters and not model.tests:
+                    continue
+                if entries_only and model.model_filename in used and file not in named:
+                    continue
+                if file in failed:
+                    results.append((file, None, load_time, failed[file]))
+                    continue
+
+                start = time.perf_counter()
+                try:
+                    with stats.working_directory(os.path.dirname(file)):
+                        model.build(quiet=True)
+                    results.append((file, _summary(model, output, samples, seed), load_time + time.perf_counter() - start, None))
+                except OneilError as err:
+                    results.append((file, None, load_time + time.perf_counter() - start, err))
+
+    if output == "json":
+        print_results_json(results)
+        memory.report()
+        return results
+
+    table = BeautifulTable()
+    table.columns.header = ["Model", "Parameters", "Tests", "Result", "Time (s)"]
+    for file, summary, elapsed, err in sorted(results, key=lambda r: r[0]):
+        if err:
+            table.append_row([file, "-", "-", bcolors.FAIL + err.kind() + bcolors.ENDC, f"{elapsed:.3f}"])
+        else:
+            status = bcolors.FAIL + "FAIL" + bcolors.ENDC if summary["failed"] else bcolors.OKGREEN + "PASS" + bcolors.ENDC
+            table.append_row([file, summary["parameters"], f"{summary['tests'] - summary['failed']}/{summary['tests']}", status, f"{elapsed:.3f}"])
+
+    if results:
+        print(table)
+    else:
+        print("No models found.")
+
+    failed = [r for r in results if r[3] or r[1]["failed"]]
+    if stream:
+        print(f"Evaluated {len(results)} model(s) one at a time: {len(results) - len(failed)} passed, {len(failed)} failed.")
+    else:
+        print(f"Evaluated {len(results)} model(s) sharing {len(used)} submodel(s): {len(results) - len(failed)} passed, {len(failed)} failed.")
+
+    if performance and results:
+        print_performance(results)
+    if tags and results:
+        print_tagged(results, tags)
+    if samples:
+        for file, summary, _, _ in sorted(results, key=lambda r: r[0]):
+            if summary:
+                print(file)
+                montecarlo.print_simulation(summary["monte_carlo"])
+
+    console.print_error_groups([(file, err) for file, _, _, err in sorted(results, key=lambda r: r[0]) if err], lambda file: f"While evaluating {file}")
+
+    memory.report()
+    return results
+
+def _manifest_lines(manifest: str) -> list[str]:
+    if not os.path.exists(manifest):
+        print(f"{bcolors.error('ERROR')} {messages.text('manifest-not-found', 'Manifest {file} not found.', file=manifest)}")
+        sys.exit(console.EXIT_USAGE)
+
+    with open(manifest, "r") as f:
+        lines = [line.split("#")[0].strip() for line in f.readlines()]
+    return [line for line in lines if line]
+
+def read_manifest(manifest: str) -> list[str]:
+    """
+    Read the model paths listed in a manifest file (one per line, relative to the manifest,
+    with "#" comments). Glossary lines ("glossary: <words>"), parser settings
+    ("parser: <settings>"), and templates ("template: <name> <file>") are skipped.
+    """
+    directory = os.path.dirname(manifest)
+    return [os.path.join(directory, line) for line in _manifest_lines(manifest) if not line.startswith(("glossary:", syntax.PREFIX, scaffold.PREFIX, diagnostics.PREFIX))]
+
+def read_glossary(manifest: str) -> set[str]:
+    """
+    Read the project-specific words for the spelling lint from the glossary lines of a
+    manifest file ("glossary: <words separated by spaces or commas>").
+    """
+    words = set()
+    for line in _manifest_lines(manifest):
+        if line.startswith("glossary:"):
+            words.update(word for word in re.split(r"[\s,]+", line[len("glossary:"):]) if word)
+    return words
+
+def read_parser_settings(manifests: list[str]):
+    """
+    Apply the parser settings of manifest files ("parser: <name>=<value> ...", see
+    oneil.syntax) and their diagnostics policy ("diagnostics: <category>=<level> ...", see
+    oneil.diagnostics). Invalid settings are reported as warnings and the rest are still
+    applied.
+    """
+    for manifest in manifests:
+        for settings in (syntax, diagnostics):
+            try:
+                settings.load(manifest, _manifest_lines(manifest))
+            except ConfigError as e:
+                console.print_warning(e)
+
+def parse_output_flags(args: list[str]) -> list[str]:
+    """
+    Apply the output flags (--quiet, --error-format, --deny-warnings, and the -A, -W, and
+    -D diagnostics flags) shared by all subcommands.
+    """
+    args, console.quiet = extract_flag(args, "--quiet")
+    args, console.deny_warnings = extract_flag(args, "--deny-warnings")
+    errors = []
+    args = diagnostics.extract_flags(args, errors)
+    if errors:
+        print(f"{bcolors.error('ERROR')} {errors[0]}")
+        sys.exit(console.EXIT_USAGE)
+    args, error_formats = extract_option(args, "--error-format")
+    if error_formats:
+        if error_formats[-1] not in console.ERROR_FORMATS:
+            print(f"{bcolors.error('ERROR')} Invalid error format \"{error_formats[-1]}\". Must be one of: {', '.join(console.ERROR_FORMATS)}.")
+            sys.exit(console.EXIT_USAGE)
+        console.error_format = error_formats[-1]
+
+    return args
+
 def main(args=sys.argv[1:]):
+    global nominal
+    crash.command_line = list(args)
     try:
+        args = parse_output_flags(args)
+        args, max_times = extract_option(args, "--max-time")
+        args, max_depths = extract_option(args, "--max-depth")
+        args, max_errors = extract_option(args, "--max-errors")
+        try:
+            limits.overrides.update({"time": float(max_times[-1])} if max_times else {})
+            limits.overrides.update({"depth": int(max_depths[-1])} if max_depths else {})
+            console.max_errors_flag = int(max_errors[-1]) if max_errors else None
+        except ValueError:
+            print("Usage: --max-time takes a number of seconds, and --max-depth and --max-errors take an integer")
+            sys.exit(console.EXIT_USAGE)
+        args, deterministic.enabled = extract_flag(args, "--deterministic")
+        args, sandbox.no_python = extract_flag(args, "--no-python")
+        args, sandbox.no_fs_outside_root = extract_flag(args, "--no-fs-outside-root")
+        args, policies = extract_option(args, "--infinity")
+        if policies:
+            if policies[-1] not in infinity.POLICIES:
+                print(f"{bcolors.error('ERROR')} Invalid infinity policy \"{policies[-1]}\". Must be one of: {', '.join(infinity.POLICIES)}.")
+                sys.exit(console.EXIT_USAGE)
+            infinity.overrides["policy"] = policies[-1]
+        args, trace_levels = extract_option(args, "--trace")
+        if trace_levels:
+            if trace_levels[-1] not in traces.LEVELS:
+                print(f"{bcolors.error('ERROR')} Invalid trace level \"{trace_levels[-1]}\". Must be one of: {', '.join(traces.LEVELS)}.")
+                sys.exit(console.EXIT_USAGE)
+            traces.override = trace_levels[-1]
+        args, orders = extract_option(args, "--order")
+        if orders:
+            if orders[-1] not in ordering.ORDERS:
+                print(f"{bcolors.error('ERROR')} Invalid evaluation order \"{orders[-1]}\". Must be one of: {', '.join(ordering.ORDERS)}.")
+                sys.exit(console.EXIT_USAGE)
+            ordering.overrides["order"] = orders[-1]
+        load_config()
+        args, cfg = extract_option(args, "--cfg")
+        active_cfg.update(cfg)
+        args, nominal = extract_flag(args, "--nominal")
+        args, corners.enabled = extract_flag(args, "--corners")
+        args, input_files = extract_option(args, "--input-json")
+        try:
+            for file in input_files:
+                externs.load_json(file)
+        except ValueError as e:
+            print(f"{bcolors.error('ERROR')} {e}")
+            sys.exit(console.EXIT_USAGE)
+        args, depfiles = extract_option(args, "--depfile")
+        args, depfile_targets = extract_option(args, "--depfile-target")
+        deps.depfile = depfiles[-1] if depfiles else None
+        deps.depfile_target = depfile_targets[-1] if depfile_targets else None
+        args, profile_memory = extract_flag(args, "--profile-memory")
+        if profile_memory:
+            memory.enable()
+
         # if the first argument is "regression-test", then we need to perform a regression test
         # the second argument is the model file to test
-        if args[0] == "regression-test":
+        if args and args[0] == "regression-test":
             if len(args) != 2:
                 print("Usage: oneil regression-test <model_file>")
                 sys.exit(1)
             
             perform_regression_test(args[1])
+            sys.exit(console.exit_code)
+        elif args and args[0] == "completions":
+            if len(args) != 2 or args[1] not in completion.SHELLS:
+                print(f"Usage: oneil completions <{'|'.join(completion.SHELLS)}>")
+                sys.exit(1)
+
+            print(completion.script(args[1]), end="")
+            return
+        elif args and args[0] == "stats":
+            paths = args[1:]
+            thresholds = {}
+            for flag in ["parameters", "nesting", "fan-in", "fan-out", "external"]:
+                paths, values = extract_option(paths, "--max-" + flag)
+                if values:
+                    try:
+                        thresholds[flag.replace("-", "_")] = int(values[-1])
+                    except ValueError:
+                        print(f"Usage: --max-{flag} takes an integer (got \"{values[-1]}\")")
+                        sys.exit(1)
+
+            stats.report(pack.resolve(paths), thresholds)
+            return
+        elif args and args[0] == "eval":
+            paths, all_models = extract_flag(args[1:], "--all")
+            paths, stream = extract_flag(paths, "--stream")
+            paths, performance = extract_flag(paths, "--performance")
+            paths, tags = extract_option(paths, "--tag")
+            paths, manifests = extract_option(paths, "--manifest")
+            paths, outputs = extract_option(paths, "--output")
+            paths, overrides = extract_option(paths, "--set")
+            paths, samples = extract_option(paths, "--samples")
+            paths, seeds = extract_option(paths, "--seed")
+            read_parser_settings(manifests)
+            for manifest in manifests:
+                paths += read_manifest(manifest)
+            usage = (not paths and not all_models) or (outputs and outputs[-1] not in ("table", "json"))
+            if usage or not all(value.isdigit() for value in samples + seeds):
+                print("Usage: oneil eval [--all] [--stream] [--performance] [--tag <tag>] [--manifest <file>] [--output table|json] [--set <model>.<ID>=<value>] [--samples <N>] [--seed <seed>] <model files or directories>")
+                sys.exit(console.EXIT_USAGE)
+
+            try:
+                results = evaluate_all(pack.resolve(paths) or ["."], stream=stream, performance=performance, tags=tags, output=outputs[-1] if outputs else "table", overrides=overrides,
+                                       samples=int(samples[-1]) if samples else 0, seed=int(seeds[-1]) if seeds else None)
+            except OverrideError as err:
+                console.print_error(err)
+                sys.exit(console.exit_code)
+            deps.write_depfile([(os.path.basename(file), [], os.path.dirname(file)) for file, _, _, _ in results])
+            sys.exit(console.exit_code)
+        elif args and args[0] == "watch":
+            args, overrides = extract_option(args[1:], "--set")
+            args, thresholds = extract_option(args, "--threshold")
+            args, intervals = extract_option(args, "--interval")
+            if len(args) != 1:
+                print("Usage: oneil watch [--threshold <relative change, e.g. 1%>] [--interval <seconds>] [--set <ID>=<value>] <model>")
+                sys.exit(console.EXIT_USAGE)
+            try:
+                threshold = watch.parse_threshold(thresholds[-1]) if thresholds else 0.0
+                interval = float(intervals[-1]) if intervals else 0.5
+            except ValueError:
+                print("Usage: --threshold takes a number or a percentage (e.g. 0.01 or 1%), and --interval takes a number of seconds")
+                sys.exit(console.EXIT_USAGE)
+
+            inp, designs, _ = parse_args(args)
+            watch.run(inp, designs, overrides, threshold, interval)
+        elif args and args[0] == "tokens":
+            paths, manifests = extract_option(args[1:], "--manifest")
+            if len(paths) != 1:
+                print("Usage: oneil tokens [--manifest <file>] <model.on>")
+                sys.exit(console.EXIT_USAGE)
+            read_parser_settings(manifests)
+            tokens.run(paths[0])
+            return
+        elif args and args[0] == "grammar":
+            if len(args) != 2:
+                print("Usage: oneil grammar <directory>")
+                sys.exit(console.EXIT_USAGE)
+            grammar.run(args[1])
+            return
+        elif args and args[0] == "deps":
+            if len(args) != 2:
+                print("Usage: oneil deps [<design>@]<model>")
+                sys.exit(console.EXIT_USAGE)
+            inp, designs, _ = parse_args(args[1:])
+            if pack.is_pack(inp):
+                inp, designs = pack.unpack(inp)
+            deps.run(inp, designs)
+            deps.write_depfile([(inp, designs, None)])
+            sys.exit(console.exit_code)
+        elif args and args[0] == "pack":
+            args, outputs = extract_option(args[1:], "-o")
+            if len(args) != 1 or pack.is_pack(args[0]):
+                print("Usage: oneil pack [<design>@]<model> [-o <file.onpack>]")
+                sys.exit(console.EXIT_USAGE)
+            inp, designs, _ = parse_args(args)
+            output = outputs[-1] if outputs else os.path.basename(inp).replace(".on", "") + pack.EXTENSION
+            manifest = pack.pack(inp, designs, output)
+            if not console.quiet:
+                print(messages.text("pack-done", "Packed {count} file(s) into {file}.", count=len(manifest["files"]), file=output))
+            sys.exit(console.exit_code)
+        elif args and args[0] == "blame":
+            if len(args) < 3:
+                print("Usage: oneil blame <model> <ID or label>")
+                sys.exit(console.EXIT_USAGE)
+            blame.run(args[1], " ".join(args[2:]))
+            sys.exit(console.exit_code)
+        elif args and args[0] == "symbolic":
+            words = [arg for arg in args[1:] if arg != "--latex"]
+            if len(words) < 2:
+                print("Usage: oneil symbolic <model> <ID> [<input ID>...] [--latex]")
+                sys.exit(console.EXIT_USAGE)
+            symbolic.run(words[0], words[1], words[2:], latex="--latex" in args)
+            sys.exit(console.exit_code)
+        elif args and args[0] == "test":
+            paths, manifests = extract_option(args[1:], "--manifest")
+            if len(paths) > 1 or not (paths or manifests):
+                print("Usage: oneil test [--manifest <file>] [[<design>@]<model>]")
+                sys.exit(console.EXIT_USAGE)
+            read_parser_settings(manifests)
+            inp, designs, _ = parse_args(paths)
+            if manifests:
+                fixtures.run(inp, designs, manifests)
+                sys.exit(console.exit_code)
+            model = loader(inp, designs, capture_errors=False, quiet=True)
+            fail_count, test_count = model.test()
+            print(f"Tests: {test_count - fail_count}/{test_count}", end="")
+            if fail_count:
+                print(" (" + bcolors.BOLD + bcolors.FAIL + "FAIL" + bcolors.ENDC + ")")
+                console.record_exit(console.EXIT_TEST_FAILURE)
+            else:
+                print(" (" + bcolors.OKGREEN + "PASS" + bcolors.ENDC + ")")
+            sys.exit(console.exit_code)
+        elif args and args[0] == "coverage":
+            paths, html_files = extract_option(args[1:], "--html")
+            if len(paths) != 1:
+                print("Usage: oneil coverage [--html <file>] [<design>@]<model>")
+                sys.exit(console.EXIT_USAGE)
+            inp, designs, _ = parse_args(paths)
+            coverage.run(inp, designs, html_files[-1] if html_files else None)
+            sys.exit(console.exit_code)
+        elif args and args[0] == "mutate":
+            if len(args) != 2:
+                print("Usage: oneil mutate [<design>@]<model>")
+                sys.exit(console.EXIT_USAGE)
+            inp, designs, _ = parse_args(args[1:])
+            mutate.run(inp, designs)
+            sys.exit(console.exit_code)
+        elif args and args[0] == "tune":
+            paths, manifests = extract_option(args[1:], "--manifest")
+            if len(paths) != 1:
+                print("Usage: oneil tune [--manifest <file>] [<design>@]<model>")
+                sys.exit(console.EXIT_USAGE)
+            read_parser_settings(manifests)
+            inp, designs, _ = parse_args(paths)
+            tune.run(inp, designs)
+            sys.exit(console.exit_code)
+        elif args and args[0] == "dev":
+            if len(args) != 3 or args[1] != "verify":
+                print("Usage: oneil dev verify <model>")
+                sys.exit(console.EXIT_USAGE)
+            try:
+                if consistency.run(args[2]):
+                    sys.exit(console.EXIT_INTERNAL_ERROR)
+            except OneilError as err:
+                console.print_error(err)
+                sys.exit(console.exit_code)
+            return
+        elif args and args[0] == "init":
+            if len(args) > 2:
+                print("Usage: oneil init [<directory>]")
+                sys.exit(console.EXIT_USAGE)
+            workspace.run(args[1] if len(args) == 2 else ".")
+            return
+        elif args and args[0] == "explain-error":
+            words, markdown = extract_flag(args[1:], "--markdown")
+            if markdown and not words:
+                print(explanations.markdown())
+                return
+            if len(words) != 1:
+                print("Usage: oneil explain-error <code>, or oneil explain-error --markdown")
+                sys.exit(console.EXIT_USAGE)
+            if not explanations.print_explanation(words[0]):
+                sys.exit(console.EXIT_USAGE)
+            return
+        elif args and args[0] == "new":
+            words, manifests = extract_option(args[1:], "--manifest")
+            words, outputs = extract_option(words, "-o")
+            words, list_templates = extract_flag(words, "--list")
+            if list_templates and not words:
+                scaffold.print_templates(manifests)
+                return
+            if len(words) != 2:
+                print("Usage: oneil new [--manifest <file>] [-o <file.on>] <template> <name>, or oneil new [--manifest <file>] --list")
+                sys.exit(console.EXIT_USAGE)
+            path = scaffold.new(words[0], words[1], outputs[-1] if outputs else None, manifests)
+            if not console.quiet:
+                print(messages.text("new-done", "Created {file} from the {template} template.", file=path, template=words[0]))
+            sys.exit(console.exit_code)
+        elif args and args[0] == "sweep":
+            paths, specs = extract_option(args[1:], "--vary")
+            paths, output_files = extract_option(paths, "-o")
+            if not paths or not specs:
+                print("Usage: oneil sweep [<design>@]<model> --vary <ID>=<start>..<stop>/<step>[:<units>] [--vary ...] [-o <file.csv>] [<output ID>...]")
+                sys.exit(console.EXIT_USAGE)
+            inp, designs, _ = parse_args(paths[:1])
+            sweep.run(inp, designs, specs, paths[1:] or None, output_files[-1] if output_files else None)
+            sys.exit(console.exit_code)
+        elif args and args[0] == "sensitivity":
+            if len(args) < 2:
+                print("Usage: oneil sensitivity <model> [<ID>...]")
+                sys.exit(console.EXIT_USAGE)
+            sensitivity.run(args[1], args[2:])
+            sys.exit(console.exit_code)
+        elif args and args[0] == "verify":
+            if len(args) != 2:
+                print("Usage: oneil verify <results.json or export.tex>")
+                sys.exit(console.EXIT_USAGE)
+            stamp.verify(args[1])
+            sys.exit(console.exit_code)
+        elif args and args[0] == "search":
+            if len(args) < 2:
+                print("Usage: oneil search <text> [model files or directories]")
+                sys.exit(console.EXIT_USAGE)
+            search.run(args[1], pack.resolve(args[2:]))
+            sys.exit(console.exit_code)
+        elif args and args[0] == "lint":
+            paths, spell_check = extract_flag(args[1:], "--spelling")
+            paths, fix = extract_flag(paths, "--fix")
+            paths, manifests = extract_option(paths, "--manifest")
+            glossary = set()
+            read_parser_settings(manifests)
+            for manifest in manifests:
+                paths += read_manifest(manifest)
+                glossary |= read_glossary(manifest)
+            lint.run(pack.resolve(paths), spell_check, glossary, fix)
+            sys.exit(console.exit_code)
+        elif args and args[0] == "check":
+            paths, manifests = extract_option(args[1:], "--manifest")
+            paths, since = extract_option(paths, "--since")
+            read_parser_settings(manifests)
+            for manifest in manifests:
+                paths += read_manifest(manifest)
+            check.run(pack.resolve(paths), since[-1] if since else None)
+            sys.exit(console.exit_code)
+        elif args and args[0] == "fmt":
+            paths, check_only = extract_flag(args[1:], "--check")
+            fmt.run(paths, check_only)
+            sys.exit(console.exit_code)
+        elif args and args[0] == "refactor":
+            try:
+                if not refactor.run(args[1:]):
+                    sys.exit(console.EXIT_USAGE)
+            except OneilError as err:
+                console.print_error(err)
+                sys.exit(console.exit_code)
+            return
+        elif args and args[0] == "__complete":
+            # Called by the shell completion scripts with the words being completed.
+            for candidate in completion.candidates(args[1:]):
+                print(candidate)
             return
         else:
-            console.print_welcome_message()
+            if not console.quiet:
+                console.print_welcome_message()
 
-            # parse the files and commands
+            # parse the files, overrides, and commands
+            args, overrides = extract_option(args, "--set")
             inp, designs, commands = parse_args(args)
-
-            # load the model
-            model = loader(inp, designs)
+            if pack.is_pack(inp):
+                # Submodels are found relative to the working directory, so work in the
+                # extracted archive, with the designs it was packed with applied first.
+                entry, packed_designs = pack.unpack(inp)
+                os.chdir(os.path.dirname(entry))
+                inp = os.path.basename(entry)
+                designs = [os.path.relpath(design) for design in packed_designs] + designs
+            if inp:
+                deps.write_depfile([(inp, designs, None)])
+
+            # load the model (when commands are scripted, exit on errors instead of prompting)
+            try:
+                model = loader(inp, designs, capture_errors=not commands, overrides=overrides, partial=True)
+            except OneilError as err:
+                console.print_error(err)
+                sys.exit(console.exit_code)
+            memory.report()
 
             # Handle commands after the first as cli commands.
             for command in commands:
-                print("(" + bcolors.OKBLUE + model.name + bcolors.ENDC + ") >>> " + command)
+                if not console.quiet:
+                    print("(" + bcolors.OKBLUE + model.name + bcolors.ENDC + ") >>> " + command)
                 model = handler(model, command)
+                console.summarize_errors()
 
             if len(args) > 2:
-                quit() 
+                sys.exit(console.exit_code)
 
             # run the interpeter on the model
             interpreter(model)
     except KeyboardInterrupt:
         # Handle when the user presses Ctrl+C
         print(f"\n\n{bcolors.ITALIC}Quitting...{bcolors.ENDC}")
-        sys.exit(0)
\ No newline at end of file
+        sys.exit(0)
+    except EOFError:
+        # Input ended (Ctrl+D, or a closed pipe)
+        print(f"\n{bcolors.ITALIC}Quitting...{bcolors.ENDC}")
+        sys.exit(console.exit_code)
+    except OneilError as err:
+        # An error in the command's inputs (e.g. a modified archive), not a crash
+        console.print_error(err)
+        sys.exit(console.exit_code)
+    except Exception as err:
+        crash.report(err)
+        sys.exit(console.exit_code)
+    finally:
+        console.summarize_errors()
\ No newline at end of file
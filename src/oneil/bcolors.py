@@ -40,3 +40,10 @@ def error(msg: str):
     """
 
     return f"{FAIL}{BOLD}{msg}{ENDC}"
+
+def warning(msg: str):
+    """
+    Wrap the message in yellow
+    """
+
+    return f"{YELLOW}{BOLD}{msg}{ENDC}"
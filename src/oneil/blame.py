@@ -0,0 +1,122 @@
+"""
+Where a parameter's definition came from (`oneil blame`), from git: who last changed each
+line of it, and the recent commits that changed its expression, printed after the
+parameter's explanation so a changed value or margin can be traced to the change that
+caused it.
+
+This needs git and a model in a git repository. Parameters from included fragments are
+blamed in the fragment.
+"""
+
+import datetime
+import os
+import subprocess
+
+from . import bcolors
+from . import console
+from . import messages
+from .stats import working_directory
+
+# How many of the commits that changed the parameter to show
+HISTORY = 5
+
+def _git(args, directory):
+    result = subprocess.run(["git"] + args, cwd=directory or ".", capture_output=True, text=True)
+    if result.returncode != 0:
+        raise OSError(result.stderr.strip() or f"git {args[0]} failed")
+    return result.stdout
+
+def span(parameter, filename):
+    """
+    The file and first and last lines (1-based) of a parameter's definition: its
+    declaration and, for piecewise parameters, the lines of its branches.
+    """
+    source = parameter.source(filename)
+    first = last = source["line"]
+    with open(source["file"], "r") as f:
+        lines = f.read().splitlines()
+    while last < len(lines) and lines[last].strip().startswith("{"):
+        last += 1
+    return source["file"], first, last
+
+def blame(file, first, last):
+    """
+    The commit that last changed each line of the span, as dicts with the line, commit
+    (None for uncommitted changes), author, date, and summary.
+    """
+    directory, name = os.path.split(file)
+    output = _git(["blame", "--porcelain", "-L", f"{first},{last}", "--", name], directory)
+
+    # Each line starts with "<hash> <original line> <final line>", followed by the
+    # commit's details the first time it appears.
+    commits = {}
+    lines = []
+    current = None
+    for line in output.splitlines():
+        words = line.split(" ")
+        if len(words) >= 3 and len(words[0]) == 40 and words[1].isdigit():
+            current = commits.setdefault(words[0], {"commit": None if set(words[0]) == {"0"} else words[0][:7]})
+            lines.append((words[0], int(words[2])))
+        elif line.startswith("author "):
+            current["author"] = line[len("author "):]
+        elif line.startswith("author-time "):
+            current["date"] = datetime.date.fromtimestamp(int(line.split(" ")[1])).isoformat()
+        elif line.startswith("summary "):
+            current["summary"] = line[len("summary "):]
+    return [dict(commits[commit], line=line_no) for commit, line_no in lines]
+
+def history(file, first, last, count=HISTORY):
+    """
+    The most recent commits that changed the span, newest first, each with its commit,
+    author, date, summary, and the removed and added lines.
+    """
+    directory, name = os.path.split(file)
+    output = _git(["log", "-L", f"{first},{last}:{name}", "-n", str(count), "--date=short", "--format=%x00%h%x09%an%x09%ad%x09%s"], directory)
+
+    changes = []
+    for block in output.split("\x00")[1:]:
+        header, _, diff = block.partition("\n")
+        commit, author, date, summary = (header.split("\t") + ["", "", "", ""])[:4]
+        removed = [line[1:] for line in diff.splitlines() if line.startswith("-") and not line.startswith("---")]
+        added = [line[1:] for line in diff.splitlines() if line.startswith("+") and not line.startswith("+++")]
+        changes.append({"commit": commit, "author": author, "date": date, "summary": summary, "removed": removed, "added": added})
+    return changes
+
+def run(inp, name):
+    """
+    Explain the parameter (by ID or label) of the model and print its git history.
+    Returns whether the history could be read.
+    """
+    from . import Model
+
+    directory, model_file = os.path.split(inp if inp.endswith(".on") else inp + ".on")
+    with working_directory(directory or "."):
+        model = Model(model_file)
+        model.build(quiet=True)
+        ID = model.lookup(name)
+        model.explain(ID)
+        file, first, last = span(model.parameters[ID], model_file)
+
+        try:
+            lines = blame(file, first, last)
+            changes = history(file, first, last)
+        except OSError as e:
+            print(f"{bcolors.error('ERROR')} {messages.text('blame-no-git', 'No git history for {file}: {error}', file=file, error=e)}")
+            console.record_exit(console.EXIT_USAGE)
+            return False
+
+    print(messages.text("blame-lines", "Last changed:"))
+    for entry in lines:
+        if entry["commit"] is None:
+            print(f"    {entry['line']:>4}  {bcolors.YELLOW}{messages.text('blame-uncommitted', 'not committed yet')}{bcolors.ENDC}")
+        else:
+            print(f"    {entry['line']:>4}  {bcolors.OKBLUE}{entry['commit']}{bcolors.ENDC}  {entry['date']}  {entry['author']}  {entry['summary']}")
+
+    print(messages.text("blame-history", "Recent changes to {file}, lines {first}-{last}:", file=file, first=first, last=last))
+    for change in changes:
+        print(f"    {bcolors.OKBLUE}{change['commit']}{bcolors.ENDC} {change['date']} {change['author']}: {change['summary']}")
+        for line in change["removed"]:
+            print(f"        {bcolors.FAIL}- {line.strip()}{bcolors.ENDC}")
+        for line in change["added"]:
+            print(f"        {bcolors.OKGREEN}+ {line.strip()}{bcolors.ENDC}")
+    return True
@@ -0,0 +1,245 @@
+"""
+The builtin functions, constants, and units models can use, described in one place for
+the interpreter (`builtins`), editor completions (`oneil/builtins` in oneil.tune), and
+plugins that add their own.
+
+default() describes the builtins Oneil ships with. A plugin builds a Builtins with the
+ones it adds and registers them before loading models, which can then use them like the
+shipped ones:
+
+    from oneil import builtin
+
+    builtin.register(builtin.Builtins.builder()
+        .with_function("sinc", sinc, ["x"], "The normalized sinc of x.")
+        .with_constant("g_0", 9.80665, "m/s^2", "Standard gravity.")
+        .with_unit("ft", {"m": 1}, 0.3048, "International foot."))
+
+Functions are called like breakout functions, with the parameters (or numbers) they're
+given, and return a Parameter or a number. Constants are a number, with units if given.
+Units are a dict of base units and the multiplier to base units.
+"""
+
+KINDS = ["function", "constant", "unit"]
+
+# The parameters and descriptions of the builtin functions (see oneil.FUNCTIONS)
+FUNCTION_DOCS = {
+    "sin": (["x"], "The sine of an angle in radians."),
+    "cos": (["x"], "The cosine of an angle in radians."),
+    "tan": (["x"], "The tangent of an angle in radians."),
+    "asin": (["x"], "The arcsine of x, in radians."),
+    "acos": (["x"], "The arccosine of x, in radians."),
+    "atan": (["x"], "The arctangent of x, in radians."),
+    "sinh": (["x"], "The hyperbolic sine of x."),
+    "cosh": (["x"], "The hyperbolic cosine of x."),
+    "tanh": (["x"], "The hyperbolic tangent of x."),
+    "min": (["a", "b?"], "The smaller of a and b, or the min of a's range."),
+    "max": (["a", "b?"], "The larger of a and b, or the max of a's range."),
+    "sqrt": (["x"], "The square root of x."),
+    "abs": (["x"], "The absolute value of x."),
+    "mnmx": (["a", "b"], "The range from the smallest to the largest extreme of a and b."),
+    "mxmn": (["a", "b"], "The range from the largest to the smallest extreme of a and b."),
+    "log": (["x"], "The natural logarithm of x."),
+    "log2": (["x"], "The base-2 logarithm of x."),
+    "log10": (["x"], "The base-10 logarithm of x."),
+    "ln": (["x"], "The natural logarithm of x."),
+    "floor": (["x"], "x rounded down to a whole number."),
+    "ceiling": (["x"], "x rounded up to a whole number."),
+    "extent": (["a", "b?"], "The largest absolute value of a and b, or of a's range."),
+    "range": (["x"], "The width of x's range (its max minus its min)."),
+    "strip": (["x"], "x without its units."),
+    "mid": (["x"], "The midpoint of x's range."),
+    "sign": (["x"], "-1, 0, or 1 for the sign of x."),
+    "linear": (["x"], "The linear value of x, a value in dB or a unitless number of dB (linear(20) is 100)."),
+    "decibels": (["x"], "A unitless linear value x as a number of dB (decibels(100) is 20)."),
+    "lerp": (["a", "b", "t"], "The value a fraction t (unitless) of the way from a to b, which have the same units."),
+    "map_range": (["x", "in_min", "in_max", "out_min", "out_max"], "x mapped linearly from in_min..in_max to out_min..out_max, in the units of out_min and out_max."),
+}
+
+CONSTANT_DOCS = {
+    "pi": "The ratio of a circle's circumference to its diameter.",
+    "e": "Euler's number, the base of the natural logarithm.",
+    "inf": "Infinity, for unbounded limits and ranges.",
+}
+
+class Builtin:
+    """
+    A builtin function, constant, or unit, with what it's called with (for functions) and
+    a description.
+    """
+    def __init__(self, name, kind, value, parameters=None, units=None, doc=""):
+        self.name = name
+        self.kind = kind
+        self.value = value
+        self.parameters = parameters
+        self.units = units
+        self.doc = doc
+
+    def signature(self):
+        """
+        How the builtin is written: `name(a, b?)` for functions (`?` for optional
+        parameters), `name :units` for constants with units, and the name otherwise.
+        """
+        if self.kind == "function":
+            return f"{self.name}({', '.join(self.parameters)})"
+        if self.kind == "constant" and self.units:
+            return f"{self.name} :{self.units}"
+        return self.name
+
+    def describe(self):
+        """
+        The builtin as a dict, for JSON.
+        """
+        return {"name": self.name, "kind": self.kind, "signature": self.signature(), "doc": self.doc}
+
+class Builtins:
+    """
+    A set of builtins, by kind and name. Build one with builder() and the with_ methods,
+    which return the set so they can be chained.
+    """
+    def __init__(self):
+        self.entries = {kind: {} for kind in KINDS}
+
+    @classmethod
+    def builder(cls):
+        return cls()
+
+    def _add(self, entry):
+        if entry.name in self.entries[entry.kind]:
+            raise ValueError(f"The {entry.kind} \"{entry.name}\" is already defined.")
+        self.entries[entry.kind][entry.name] = entry
+        return self
+
+    def with_function(self, name, implementation, parameters=("x",), doc=""):
+        if not name.isidentifier():
+            raise ValueError(f"\"{name}\" isn't a valid function name.")
+        if not callable(implementation):
+            raise ValueError(f"The function \"{name}\" must be callable.")
+        return self._add(Builtin(name, "function", implementation, parameters=list(parameters), doc=doc))
+
+    def with_constant(self, name, value, units=None, doc=""):
+        if not name.isidentifier():
+            raise ValueError(f"\"{name}\" isn't a valid constant name.")
+        return self._add(Builtin(name, "constant", value, units=units, doc=doc))
+
+    def with_unit(self, symbol, base, multiplier=1, doc=""):
+        from . import units as un
+
+        if any(unit not in un.BASE_UNITS for unit in base):
+            raise ValueError(f"The unit \"{symbol}\" must be defined in base units ({', '.join(un.BASE_UNITS)}).")
+        return self._add(Builtin(symbol, "unit", (dict(base), multiplier), doc=doc))
+
+    def names(self, kind=None):
+        """
+        The names of the builtins (of one kind), sorted.
+        """
+        kinds = [kind] if kind else KINDS
+        return sorted(name for k in kinds for name in self.entries[k])
+
+    def lookup(self, name, kind=None):
+        """
+        The builtin with the name (of the kind, or functions first, then constants and
+        units), or None.
+        """
+        for k in [kind] if kind else KINDS:
+            if name in self.entries[k]:
+                return self.entries[k][name]
+        return None
+
+    def signature(self, name, kind=None):
+        """
+        The signature of the builtin with the name (see Builtin.signature()), or None.
+        """
+        entry = self.lookup(name, kind)
+        return entry.signature() if entry else None
+
+    def describe(self, kind=None):
+        """
+        Every builtin (of one kind) as a dict (see Builtin.describe()), sorted by kind and
+        name.
+        """
+        kinds = [kind] if kind else KINDS
+        return [self.entries[k][name].describe() for k in kinds for name in sorted(self.entries[k])]
+
+# Builtins added with register(), by kind and name
+registered = Builtins()
+
+def default():
+    """
+    The builtins models can use: Oneil's functions, constants (with those from the
+    config file), and units, and those registered by plugins.
+    """
+    import sys
+    from . import FUNCTIONS, MATH_CONSTANTS, Parameter
+    from . import units as un
+
+    evaluator = sys.modules[__package__]
+    builtins = Builtins.builder()
+    for name, implementation in FUNCTIONS.items():
+        # Some names are reserved for functions the evaluator doesn't implement yet.
+        if not callable(getattr(evaluator, implementation, None)):
+            continue
+        parameters, doc = FUNCTION_DOCS.get(name, (["x"], ""))
+        entry = registered.lookup(name, "function")
+        builtins.with_function(name, getattr(evaluator, implementation), entry.parameters if entry else parameters, entry.doc if entry else doc)
+    for name, value in MATH_CONSTANTS.items():
+        entry = registered.lookup(name, "constant")
+        if entry:
+            builtins.with_constant(name, value, entry.units, entry.doc)
+        elif isinstance(value, Parameter):
+            builtins.with_constant(name, value, un.hr_units(value.units) or None, "From the config file.")
+        else:
+            builtins.with_constant(name, value, None, CONSTANT_DOCS.get(name, ""))
+    for symbol, (base, multiplier, *details) in un.SYMBOLS.items():
+        if symbol:
+            entry = registered.lookup(symbol, "unit")
+            names = ", ".join(name if isinstance(name, str) else name[0] for name in details[0].get("alt", [])) if details else ""
+            builtins.with_unit(symbol, base, multiplier, entry.doc if entry else names)
+    return builtins
+
+def register(builtins):
+    """
+    Make the builtins of a plugin available to models loaded afterwards. A name that's
+    already a builtin of the same kind is a ValueError.
+    """
+    from . import FUNCTIONS, MATH_CONSTANTS, parse_override
+    from . import units as un
+    import sys
+
+    evaluator = sys.modules[__package__]
+    for entry in builtins.entries["function"].values():
+        if entry.name in FUNCTIONS:
+            raise ValueError(f"The function \"{entry.name}\" is already a builtin.")
+    for entry in builtins.entries["constant"].values():
+        if entry.name in MATH_CONSTANTS:
+            raise ValueError(f"The constant \"{entry.name}\" is already a builtin.")
+    for entry in builtins.entries["unit"].values():
+        if entry.name in un.SYMBOLS or entry.name in un.LINEAR_UNITS:
+            raise ValueError(f"The unit \"{entry.name}\" is already a builtin.")
+
+    for entry in builtins.entries["function"].values():
+        # Equations call builtins by their name in the evaluator (see oneil.FUNCTIONS).
+        setattr(evaluator, "plugin_" + entry.name, entry.value)
+        FUNCTIONS[entry.name] = "plugin_" + entry.name
+    for entry in builtins.entries["constant"].values():
+        value = parse_override(f"{entry.name}={entry.value}:{entry.units}") if entry.units else entry.value
+        if entry.units:
+            value.model = "plugin"
+            value.name = f"{entry.name} from plugin"
+        MATH_CONSTANTS[entry.name] = value
+    for entry in builtins.entries["unit"].values():
+        un.SYMBOLS[entry.name] = un.LINEAR_UNITS[entry.name] = entry.value + ({"alt": []},)
+    for kind in KINDS:
+        registered.entries[kind].update(builtins.entries[kind])
+
+def print_builtins(builtins, kinds=("function", "constant")):
+    """
+    Print the builtins of the kinds with their signatures and descriptions.
+    """
+    from . import bcolors
+
+    for kind in kinds:
+        entries = builtins.describe(kind)
+        print(bcolors.BOLD + kind.capitalize() + "s" + bcolors.ENDC)
+        width = max((len(entry["signature"]) for entry in entries), default=0)
+        for entry in entries:
+            print(f"    {entry['signature']:<{width}}  {entry['doc']}".rstrip())
@@ -0,0 +1,241 @@
+"""
+Static checks of the models in a workspace (`oneil check`), for CI and pre-commit
+hooks: every model file under the given directories, whether or not another model uses
+it, is parsed, its references resolved (submodels, aliases, and the IDs its equations
+use), and its units checked, without evaluating anything. validate() returns the
+results of each file for scripts, and run() prints the errors and the broken files.
+
+With `--since <git ref>`, only the model files that changed since the ref (committed or
+not, and new files git doesn't track yet) are checked, along with the models that use
+them, directly or not, through submodels, included fragments, or Python modules (see
+oneil.deps), so a pre-commit hook stays fast in a large repository.
+
+Loading a model already checks the units of piecewise equations, of calls to functions
+with unit signatures, and of test inputs (see Model). The check adds the units of every
+other equation and test, worked out from the units parameters are declared with (see
+static_units()), so a mismatch that evaluation would only find at the parameter it
+reaches first is reported for every parameter at once. Equations whose units can't be
+told without running them (e.g. calls to Python functions without a signature) are
+left for evaluation, and parameters marked `@unit_unchecked` aren't checked. A
+UnitCache keeps the units worked out for each equation, so checking a model again after
+an edit only works out the ones the edit could change.
+"""
+
+import ast
+import collections
+import os
+
+from . import bcolors
+from . import console
+from . import messages
+from .blame import _git
+from .stats import working_directory, workspace_files
+
+def _equations(parameter):
+    # The equations of a parameter that can be checked statically
+    from . import unit_check_justification
+
+    if parameter.independent or parameter.callable or parameter.piecewise or parameter.alias or parameter.pointer:
+        return []
+    if unit_check_justification(parameter.annotations) is not None:
+        return []
+    if parameter.minmax_equation:
+        return [part.equation for part in parameter.equation if isinstance(part.equation, str) and not part.pointer]
+    return [parameter.equation] if isinstance(parameter.equation, str) else []
+
+def unit_errors(model, cache=None):
+    """
+    The unit errors in a model's own equations and tests, as UnitEvaluationErrors, all
+    of them rather than only the first. With a UnitCache, the units of the equations and
+    tests that haven't changed since the cache last saw them are reused.
+    """
+    from . import UnitEvaluationError, static_units, _shown_units
+
+    def units_of(name, expression):
+        if cache is None:
+            return static_units(expression, model._declared_units, model._declared_logarithmic)
+        return cache.units(model, name, expression)
+
+    errors = []
+    for parameter in model.parameters.values():
+        for equation in _equations(parameter):
+            try:
+                units = units_of(parameter.id, equation)
+            except UnitEvaluationError as e:
+                errors.append(e.with_context(parameter).with_declarations(model._declaration))
+                continue
+            if units is not None and units != parameter.units and not parameter.logarithmic:
+                errors.append(UnitEvaluationError(f"{parameter.id} is declared {_shown_units(parameter.units)}, but its equation is {_shown_units(units)}.", []).with_context(parameter))
+    for test in model.tests:
+        try:
+            units_of(f"test {test.label()}", test.expression)
+        except UnitEvaluationError as e:
+            errors.append(e.with_context(model).with_note(f"In test {test.label()} (line {test.line_no})"))
+    return errors
+
+def _uses(expression):
+    # The IDs (and `ID.submodel`s) an equation or test as parsed uses, or None if it
+    # doesn't parse
+    try:
+        tree = ast.parse(expression, mode="eval")
+    except SyntaxError:
+        return None
+    found = set()
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Attribute) and isinstance(node.value, ast.Name):
+            found.add(f"{node.value.id}.{node.attr}")
+        elif isinstance(node, ast.Name):
+            found.add(node.id)
+    return found
+
+def _sorted(units):
+    # Units as a comparable list, or None if they aren't known
+    return None if units is None else sorted(units.items())
+
+class UnitCache:
+    """
+    The units of each equation and test of the models checked so far, with what they
+    were worked out from: the expression and the declared units of the IDs it uses.
+    Checking a model again after an edit (see unit_errors()) only works out the units of
+    the equations and tests whose expression changed, or which use an ID whose units did,
+    e.g. on each save in an editor (`oneil/checkUnits` in oneil.tune). Expressions with
+    unit errors are always checked again, so their errors are new each time.
+    """
+    def __init__(self):
+        # (model file, parameter ID or test label, expression) -> (IDs used with their
+        # units, units)
+        self.entries = {}
+        # The parameters and tests whose units were worked out by the last check, as
+        # (model file, parameter ID or test label)
+        self.checked = []
+
+    def units(self, model, name, expression):
+        from . import static_units
+
+        key = (model.model_filename, name, expression)
+        uses = _uses(expression)
+        declared = None if uses is None else sorted((ID, _sorted(model._declared_units(ID)), model._declared_logarithmic(ID)) for ID in uses)
+        if declared is not None and key in self.entries and self.entries[key][0] == declared:
+            return self.entries[key][1]
+        if (model.model_filename, name) not in self.checked:
+            self.checked.append((model.model_filename, name))
+        units = static_units(expression, model._declared_units, model._declared_logarithmic)
+        if declared is not None:
+            self.entries[key] = (declared, units)
+        return units
+
+    def unit_errors(self, model):
+        """
+        unit_errors() of a model, working out only the units that may have changed since
+        the last check. Sets `checked`.
+        """
+        self.checked = []
+        return unit_errors(model, self)
+
+# The result of checking a model file: the error that kept it from loading, if any, and
+# the unit errors in it
+Result = collections.namedtuple("Result", ["file", "load_error", "unit_errors"])
+
+def _models(model, seen):
+    # The model and the submodels it uses, each once
+    if model.model_filename in seen:
+        return []
+    seen.add(model.model_filename)
+    found = [model]
+    for entry in model.submodels.values():
+        if entry.get("model"):
+            found += _models(entry["model"], seen)
+    return found
+
+def changed(since, directory="."):
+    """
+    The files changed since a git ref, committed or not, and the files git doesn't track
+    yet, as real paths. Raises OSError if git can't tell (e.g. outside a repository, or
+    for an unknown ref).
+    """
+    top = _git(["rev-parse", "--show-toplevel"], directory).strip()
+    names = _git(["diff", "--name-only", since, "--"], directory).splitlines()
+    names += _git(["ls-files", "--others", "--exclude-standard", "--full-name"], directory).splitlines()
+    return {os.path.realpath(os.path.join(top, name)) for name in names if name}
+
+def affected(files, changes):
+    """
+    The model files (of files) that are among the changed files, or that would load one
+    of them (see oneil.deps), including changed files that were deleted.
+    """
+    from . import deps
+
+    found = []
+    for file in files:
+        directory, name = os.path.split(file)
+        loaded, missing = deps.dependencies(name, directory=directory or ".")
+        if {os.path.realpath(entry["file"]) for entry in loaded + missing} & changes:
+            found.append(file)
+    return found
+
+def validate(paths, since=None):
+    """
+    Check every model file under the given files and directories (parse, resolve, and
+    check units, without evaluating), including files no other model uses, and the
+    submodels they use. With `since` (a git ref), only the files affected by the
+    changes since it are checked (see affected()). Returns a Result for each file, in
+    order, with the files as paths relative to the working directory. Unit errors are
+    under the file of the model they're in, so an error in a submodel is reported once.
+    Raises OSError if git can't find the changes.
+    """
+    from . import Model, OneilError
+
+    files = workspace_files(paths)
+    if since is not None:
+        files = affected(files, changed(since))
+    results = {}
+    seen = set()
+    for file in files:
+        directory, name = os.path.split(file)
+        try:
+            with working_directory(directory):
+                model = Model(name)
+        except OneilError as e:
+            results[os.path.relpath(file)] = Result(os.path.relpath(file), e, [])
+            continue
+        results.setdefault(os.path.relpath(file), Result(os.path.relpath(file), None, []))
+        for checked in _models(model, seen):
+            key = os.path.relpath(checked.model_filename)
+            results.setdefault(key, Result(key, None, []))
+            results[key].unit_errors.extend(unit_errors(checked))
+    return list(results.values())
+
+def run(paths, since=None):
+    """
+    Check each model file under the given files and directories (see validate()), print
+    every error found, and a summary of the broken files. Returns the results.
+    """
+    try:
+        results = validate(paths, since)
+    except OSError as e:
+        message = messages.text("check-no-git", "Can't find the changes since {ref}: {error}", ref=since, error=e)
+        print(f"{bcolors.error('ERROR')} {message}")
+        console.record_exit(console.EXIT_USAGE)
+        return []
+    failures = [(result.file, result.load_error) for result in results if result.load_error]
+    console.print_error_groups(failures, lambda file: messages.text("while-checking", "While checking {file}", file=file))
+    for result in results:
+        for error in result.unit_errors:
+            console.print_error(error)
+
+    broken = [result for result in results if result.load_error or result.unit_errors]
+    if not console.quiet:
+        if not results and since is not None:
+            print(messages.text("check-unchanged", "No models changed since {ref}.", ref=since))
+        elif not results:
+            print(messages.text("no-models", "No models found."))
+        elif broken:
+            print(messages.text("check-broken", "Broken files:"))
+            for result in broken:
+                reason = messages.text("check-load-failed", "doesn't load") if result.load_error else messages.text("check-unit-errors", "{count} unit error(s)", count=len(result.unit_errors))
+                print(f"    {result.file}: {reason}")
+            print(bcolors.FAIL + messages.text("check-found", "{broken} of {models} file(s) broken.", broken=len(broken), models=len(results)) + bcolors.ENDC)
+        else:
+            print(bcolors.OKGREEN + messages.text("check-clean", "No errors in {models} file(s).", models=len(results)) + bcolors.ENDC)
+
+    return results
@@ -0,0 +1,212 @@
+"""
+Tab completion for the Oneil CLI, both in the shell (`oneil completions <shell>`)
+and inside the interpreter.
+"""
+
+import contextlib
+import glob
+import io
+import os
+import readline
+
+SUBCOMMANDS = ["regression-test", "completions", "stats", "lint", "check", "refactor", "eval", "watch", "search", "deps", "pack", "verify", "blame", "symbolic", "sensitivity", "sweep", "init", "new", "explain-error", "test", "coverage", "mutate", "tune", "tokens", "grammar", "fmt", "dev"]
+
+SHELLS = ["bash", "zsh", "fish"]
+
+OPTIONS = ["--set", "--cfg", "--profile-memory", "--depfile", "--depfile-target", "--max-time", "--max-depth", "--max-errors", "--infinity", "--trace", "--no-python", "--no-fs-outside-root", "--deterministic", "--nominal", "--corners", "--input-json", "--order", "--deny-warnings", "-A", "-W", "-D"]
+
+REPL_COMMANDS = ["tree", "summarize", "all", "dependents", "explain", "piecewise", "symbolic", "sensitivity", "sweep", "montecarlo", "independent", "design", "test", "export", "load", "reload", "cache", "help", "units", "builtins", "quit", "exit"]
+
+SCRIPTS = {
+    "bash": """# Oneil bash completion. Add to ~/.bashrc:
+#     eval "$(oneil completions bash)"
+_oneil_complete() {
+    local IFS=$'\\n'
+    COMPREPLY=( $(oneil __complete "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null) )
+}
+complete -o default -o nospace -F _oneil_complete oneil
+""",
+    "zsh": """# Oneil zsh completion. Add to ~/.zshrc (after compinit):
+#     eval "$(oneil completions zsh)"
+_oneil() {
+    local -a candidates
+    candidates=("${(@f)$(oneil __complete "${(@)words[2,CURRENT]}" 2>/dev/null)}")
+    compadd -S '' -a candidates
+}
+compdef _oneil oneil
+""",
+    "fish": """# Oneil fish completion. Save to ~/.config/fish/completions/oneil.fish:
+#     oneil completions fish > ~/.config/fish/completions/oneil.fish
+function __oneil_complete
+    set -l tokens (commandline -opc) (commandline -ct)
+    oneil __complete $tokens[2..-1] 2>/dev/null
+end
+complete -c oneil -f -a '(__oneil_complete)'
+""",
+}
+
+def script(shell):
+    if shell not in SCRIPTS:
+        raise ValueError(f"Unsupported shell \"{shell}\". Supported shells: {', '.join(SHELLS)}.")
+    return SCRIPTS[shell]
+
+def model_files(prefix):
+    """
+    Find .on files (and directories that may contain them) starting with the prefix.
+    Design chains (`design@model`) complete the part after the last "@".
+    """
+    head, _, prefix = prefix.rpartition("@")
+    head = head + "@" if head else ""
+
+    matches = []
+    for path in sorted(glob.glob(os.path.expanduser(prefix) + "*")):
+        if os.path.isdir(path):
+            matches.append(head + path + os.sep)
+        elif path.endswith((".on", ".onpack")):
+            matches.append(head + path)
+
+    return matches
+
+def model_ids(model):
+    """
+    List the parameter IDs of a model, including the `ID.submodel` IDs of its submodels.
+    """
+    ids = list(model.parameters.keys())
+    for symbol, entry in model.submodels.items():
+        try:
+            submodel = model._retrieve_model(entry['path'])
+        except Exception:
+            continue
+        ids.extend(f"{ID}.{symbol}" for ID in submodel.parameters)
+
+    return ids
+
+def file_ids(model_file):
+    """
+    Load a model file (with its submodels, but without evaluating it) and list its IDs,
+    or none if it can't be loaded.
+    """
+    from . import Model
+
+    model_file = model_file.rpartition("@")[2]
+    if not model_file.endswith(".on"):
+        model_file += ".on"
+    if not os.path.exists(model_file):
+        return []
+
+    try:
+        # Loading can print (e.g. from imported Python modules), which would corrupt the completions.
+        with contextlib.redirect_stdout(io.StringIO()):
+            return model_ids(Model(model_file))
+    except Exception:
+        return []
+
+def candidates(words):
+    """
+    Return the completions for the CLI words following `oneil`, where the last word is
+    the (possibly empty) word being completed.
+    """
+    if not words:
+        words = [""]
+    current = words[-1]
+    previous = words[-2] if len(words) > 1 else None
+
+    if len(words) == 1:
+        options = SUBCOMMANDS + OPTIONS + model_files(current)
+    elif previous in ("-A", "-W", "-D"):
+        from .diagnostics import categories
+        options = categories()
+    elif words[0] == "completions":
+        options = SHELLS if len(words) == 2 else []
+    elif words[0] == "blame":
+        options = model_files(current) if len(words) == 2 else file_ids(words[1])
+    elif words[0] == "symbolic":
+        options = model_files(current) if len(words) == 2 else ["--latex"] + file_ids(words[1])
+    elif words[0] == "sensitivity":
+        options = model_files(current) if len(words) == 2 else file_ids(words[1])
+    elif words[0] == "test":
+        options = ["--manifest"] + model_files(current)
+    elif words[0] == "coverage":
+        options = ["--html"] + model_files(current)
+    elif words[0] in ("regression-test", "deps", "mutate"):
+        options = model_files(current) if len(words) == 2 else []
+    elif words[0] in ("tune", "tokens"):
+        options = ["--manifest"] + model_files(current)
+    elif words[0] in ("grammar", "init"):
+        options = [path + os.sep for path in sorted(glob.glob(current + "*")) if os.path.isdir(path)] if len(words) == 2 else []
+    elif words[0] == "pack":
+        options = ["-o"] + model_files(current) if len(words) == 2 else []
+    elif words[0] == "stats":
+        options = model_files(current)
+    elif words[0] == "dev":
+        options = ["verify"] if len(words) == 2 else model_files(current)
+    elif words[0] == "fmt":
+        options = ["--check"] + model_files(current)
+    elif words[0] == "check":
+        options = ["--manifest", "--since"] + model_files(current)
+    elif words[0] == "lint":
+        options = ["--spelling", "--fix", "--manifest"] + model_files(current)
+    elif words[0] == "search":
+        options = model_files(current) if len(words) > 2 else []
+    elif words[0] == "eval" and previous == "--output":
+        options = ["table", "json"]
+    elif words[0] == "eval":
+        options = ["--all", "--stream", "--performance", "--tag", "--manifest", "--output", "--set", "--samples", "--seed"] + model_files(current)
+    elif words[0] == "explain-error":
+        from .messages import CODES
+        options = ["--markdown"] + sorted(CODES.values()) if len(words) == 2 else []
+    elif words[0] == "new":
+        from .scaffold import TEMPLATES
+        options = ["--manifest", "-o", "--list"] + sorted(TEMPLATES)
+    elif words[0] == "sweep" and previous == "--vary":
+        options = [ID + "=" for ID in file_ids(words[1])]
+    elif words[0] == "sweep":
+        options = ["--vary", "-o"] + (model_files(current) if len(words) == 2 else file_ids(words[1]))
+    elif words[0] == "watch":
+        options = ["--threshold", "--interval", "--set"] + model_files(current)
+    elif words[0] == "refactor":
+        from .refactor import COMMANDS
+        options = COMMANDS if len(words) == 2 else model_files(current)
+    elif current.startswith("-"):
+        options = OPTIONS
+    elif previous == "--set":
+        options = [ID + "=" for ID in file_ids(words[0])]
+    else:
+        options = REPL_COMMANDS + file_ids(words[0])
+
+    return [option for option in options if option.startswith(current)]
+
+def _readline_completer(options_for):
+    def completer(text, state):
+        matches = [option for option in options_for(text) if option.startswith(text)]
+        return matches[state] if state < len(matches) else None
+    return completer
+
+def _enable_readline_completion():
+    if "libedit" in (readline.__doc__ or ""):
+        readline.parse_and_bind("bind ^I rl_complete")
+    else:
+        readline.parse_and_bind("tab: complete")
+    # Keep "." and "@" inside words so that "ID.submodel" and "design@model" complete as a whole.
+    readline.set_completer_delims(" \t\n=")
+
+def complete_models():
+    """
+    Complete model file paths at the loader prompt.
+    """
+    _enable_readline_completion()
+    readline.set_completer(_readline_completer(model_files))
+
+def complete_repl(model):
+    """
+    Complete interpreter commands, the IDs of the loaded model, and the builtin functions
+    and constants. Listing the IDs walks the submodels, so the interpreter only calls this
+    when the model changes.
+    """
+    from . import builtin
+
+    _enable_readline_completion()
+    ids = model_ids(model)
+    builtins = builtin.default()
+    names = [name + "(" for name in builtins.names("function")] + builtins.names("constant")
+    readline.set_completer(_readline_completer(lambda text: REPL_COMMANDS + ids + names + model_files(text)))
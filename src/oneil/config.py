@@ -0,0 +1,221 @@
+"""
+User configuration (`~/.config/oneil/config.toml`) for display settings and extra
+builtin constants.
+
+The file is read at startup and again whenever it changes during an interpreter
+session. For example:
+
+    [display]
+    sigfigs = 6              # significant figures for printed values
+    notation = "scientific"  # or "general" (the default)
+    units = "base"           # or "derived" (the default), e.g. "kg m/s^2" instead of "N"
+    language = "en"          # the message catalog (ONEIL_LANG overrides it)
+    max_errors = 20          # errors printed per command before summarizing (0 for all)
+    trace = "failures"       # trace the conditions of failing tests (see oneil.traces)
+
+    [colors]
+    enabled = true
+    error = "red"
+    warning = "yellow"
+
+    [constants]
+    g_0 = "9.80665 :m/s^2"
+    c = 299792458            # unitless numbers don't need a unit
+
+    [limits]
+    time = 60                # seconds for evaluating a model (see oneil.limits)
+
+    [infinity]
+    policy = "warn"          # infinite values from finite inputs (see oneil.infinity)
+
+    [evaluation]
+    order = "fixpoint"       # iterate cycles marked @iterate (see oneil.ordering)
+"""
+
+import os
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+from . import bcolors
+from . import console
+from . import infinity
+from . import limits
+from . import messages
+from . import ordering
+from . import traces
+from . import units as un
+
+COLORS = {
+    "red": '\033[91m',
+    "green": '\033[92m',
+    "yellow": '\033[93m',
+    "blue": '\033[94m',
+    "magenta": '\033[95m',
+    "cyan": '\033[96m',
+    "orange": '\033[38;5;208m',
+}
+
+# The bcolors attributes each configurable color sets.
+COLOR_KEYS = {
+    "error": "FAIL",
+    "warning": "YELLOW",
+    "pass": "OKGREEN",
+    "model": "OKBLUE",
+    "design": "ORANGE",
+}
+
+NOTATIONS = {"general": "g", "scientific": "e"}
+UNIT_STYLES = ["derived", "base"]
+
+DEFAULT_DISPLAY = {"sigfigs": 4, "notation": "general", "units": "derived", "language": messages.DEFAULT_LANGUAGE, "max_errors": 0, "trace": "off"}
+
+# The current display settings
+display = dict(DEFAULT_DISPLAY)
+
+_default_colors = {name: getattr(bcolors, name) for name in dir(bcolors) if name.isupper() and isinstance(getattr(bcolors, name), str)}
+_default_level_colors = list(bcolors.LEVEL_COLORS)
+_constants = set()
+_loaded_mtime = None
+
+def path():
+    if os.environ.get("ONEIL_CONFIG"):
+        return os.environ["ONEIL_CONFIG"]
+    config_home = os.environ.get("XDG_CONFIG_HOME") or os.path.join(os.path.expanduser("~"), ".config")
+    return os.path.join(config_home, "oneil", "config.toml")
+
+def _mtime():
+    try:
+        return os.path.getmtime(path())
+    except OSError:
+        return None
+
+def _reset(constants):
+    display.clear()
+    display.update(DEFAULT_DISPLAY)
+    for name, value in _default_colors.items():
+        setattr(bcolors, name, value)
+    bcolors.LEVEL_COLORS[:] = _default_level_colors
+    un.NUMBER_FORMAT = "g"
+    un.UNIT_STYLE = "derived"
+    messages.language = os.environ.get("ONEIL_LANG") or messages.DEFAULT_LANGUAGE
+    limits.reset()
+    infinity.reset()
+    ordering.reset()
+    _apply_max_errors()
+    for name in _constants:
+        constants.pop(name, None)
+    _constants.clear()
+
+def _apply_display(settings, errors):
+    for key, value in settings.items():
+        if key not in DEFAULT_DISPLAY:
+            errors.append(f"Unknown display setting \"{key}\".")
+        elif key == "sigfigs" and not (isinstance(value, int) and value > 0):
+            errors.append("display.sigfigs must be a positive integer.")
+        elif key == "notation" and value not in NOTATIONS:
+            errors.append(f"display.notation must be one of: {', '.join(NOTATIONS)}.")
+        elif key == "units" and value not in UNIT_STYLES:
+            errors.append(f"display.units must be one of: {', '.join(UNIT_STYLES)}.")
+        elif key == "language" and not (isinstance(value, str) and messages.available(value)):
+            errors.append(f"No message catalog for display.language \"{value}\".")
+        elif key == "max_errors" and not (isinstance(value, int) and not isinstance(value, bool) and value >= 0):
+            errors.append("display.max_errors must be a non-negative integer (0 for no limit).")
+        elif key == "trace" and value not in traces.LEVELS:
+            errors.append(f"display.trace must be one of: {', '.join(traces.LEVELS)}.")
+        else:
+            display[key] = value
+    un.NUMBER_FORMAT = NOTATIONS[display["notation"]]
+    un.UNIT_STYLE = display["units"]
+    messages.language = os.environ.get("ONEIL_LANG") or display["language"]
+    _apply_max_errors()
+
+def _apply_max_errors():
+    console.max_errors = display["max_errors"] if console.max_errors_flag is None else console.max_errors_flag
+
+def _apply_colors(settings, errors):
+    if settings.get("enabled", True) is False:
+        for name in _default_colors:
+            if name != "ENDC":
+                setattr(bcolors, name, "")
+        bcolors.ENDC = ""
+        bcolors.LEVEL_COLORS[:] = [""] * len(_default_level_colors)
+        return
+    for key, value in settings.items():
+        if key == "enabled":
+            continue
+        if key not in COLOR_KEYS:
+            errors.append(f"Unknown color \"{key}\". Colors are: {', '.join(COLOR_KEYS)}.")
+        elif value not in COLORS:
+            errors.append(f"colors.{key} must be one of: {', '.join(COLORS)}.")
+        else:
+            setattr(bcolors, COLOR_KEYS[key], COLORS[value])
+
+def _apply_constants(settings, constants, errors):
+    from . import parse_override, OneilError
+
+    for name, value in settings.items():
+        if name in constants:
+            errors.append(f"Constant \"{name}\" is already a builtin constant.")
+            continue
+        if isinstance(value, bool) or not isinstance(value, (int, float, str)):
+            errors.append(f"Constant \"{name}\" must be a number or a \"<value> :<unit>\" string.")
+            continue
+        if isinstance(value, str):
+            try:
+                value = parse_override(f"{name}={value}")
+            except OneilError as e:
+                errors.append(f"Constant \"{name}\": {e.message()}")
+                continue
+            if not value.independent:
+                errors.append(f"Constant \"{name}\" must be a value, not an equation.")
+                continue
+            value.model = "config"
+            value.name = f"{name} from config"
+        constants[name] = value
+        _constants.add(name)
+
+def load(constants):
+    """
+    Read the config file (if there is one) and apply it, adding its constants to the
+    given builtin constants. Raises ConfigError for invalid settings after applying the
+    valid ones.
+    """
+    from . import ConfigError
+
+    global _loaded_mtime
+    _reset(constants)
+    _loaded_mtime = _mtime()
+    if _loaded_mtime is None:
+        return
+
+    try:
+        with open(path(), "rb") as f:
+            settings = tomllib.load(f)
+    except (OSError, tomllib.TOMLDecodeError) as e:
+        raise ConfigError(path(), str(e))
+
+    errors = []
+    for section in settings:
+        if section not in ("display", "colors", "constants", "limits", "infinity", "evaluation"):
+            errors.append(f"Unknown section \"[{section}]\".")
+    _apply_display(settings.get("display", {}), errors)
+    _apply_colors(settings.get("colors", {}), errors)
+    _apply_constants(settings.get("constants", {}), constants, errors)
+    limits.apply(settings.get("limits", {}), errors)
+    infinity.apply(settings.get("infinity", {}), errors)
+    ordering.apply(settings.get("evaluation", {}), errors)
+
+    if errors:
+        error = ConfigError(path(), "Some settings were ignored.")
+        for message in errors:
+            error = error.with_note(message)
+        raise error
+
+def changed():
+    """
+    Whether the config file was created, changed, or removed since it was loaded.
+    """
+    return _mtime() != _loaded_mtime
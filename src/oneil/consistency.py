@@ -0,0 +1,154 @@
+"""
+Self-consistency checks between the resolved model and the evaluator (`oneil dev verify`),
+for catching internal inconsistencies with a clear message rather than a crash partway
+through an evaluation.
+
+Resolving a model gives each parameter and test the IDs it uses (its args). The
+evaluator looks them up among the model's parameters, the parameters of its submodels
+(as `ID.submodel`), and the math constants, and calls the functions in equations from
+the oneil module (the builtins, renamed `par_*`) or imported Python modules. Without
+evaluating anything, this pass checks that:
+
+* every ID used by a parameter, a piecewise branch or condition, a min/max equation, a
+  fallback, or a test resolves, and every submodel symbol is a loaded submodel
+* every function an equation or test calls is one the evaluator can call
+* breakout parameters have a Python function, and pointers name a parameter
+* the submodels given test inputs in use declarations are loaded, and the parameters
+  passed as test inputs exist
+
+With ONEIL_DEBUG set, every model is checked when it's loaded, and the first model with
+problems raises a ConsistencyError.
+"""
+
+import builtins
+import keyword
+import os
+import re
+import sys
+
+from . import bcolors
+from .stats import working_directory
+
+# A name called like a function in an equation or test (keywords like `not (a < b)` are
+# left out afterwards)
+CALL = re.compile(r"(?<![\w.'\"])([A-Za-z_]\w*)\s*\(")
+
+def enabled():
+    """
+    Whether models are checked when they're loaded (ONEIL_DEBUG is set).
+    """
+    return bool(os.environ.get("ONEIL_DEBUG"))
+
+def _resolves(model, arg):
+    from . import OneilError
+
+    if "." not in arg:
+        return arg in model.parameters or arg in model.constants
+    ID, symbol = arg.split(".", 1)
+    if symbol not in model.submodels:
+        return False
+    try:
+        submodel = model.submodels[symbol].get("model") or model._retrieve_model(model.submodels[symbol]["path"])
+    except OneilError:
+        return False
+    return ID in submodel.parameters
+
+def _unknown_calls(expression):
+    evaluator = sys.modules[__package__]
+    calls = [name for name in CALL.findall(expression) if not keyword.iskeyword(name)]
+    return [name for name in calls if not callable(getattr(evaluator, name, None)) and not hasattr(builtins, name)]
+
+def _equation_problems(model, parameter, what):
+    # The problems of one equation (a Parameter, or a branch, condition, side of a min/max
+    # equation, or fallback of one): its args, the functions it calls, and its kind.
+    problems = []
+    for arg in parameter.args:
+        if not _resolves(model, arg):
+            problems.append(f"{what} uses {arg}, which doesn't resolve to a parameter.")
+    if parameter.independent:
+        return problems
+    if parameter.callable:
+        if not callable(parameter.equation):
+            problems.append(f"{what} is a breakout parameter without a Python function to call.")
+    elif parameter.pointer:
+        if not isinstance(parameter.equation, str) or not _resolves(model, parameter.equation):
+            problems.append(f"{what} points to {parameter.equation}, which doesn't resolve to a parameter.")
+    elif parameter.piecewise:
+        for i, (equation, condition) in enumerate(parameter.equation):
+            problems += _equation_problems(model, equation, f"Branch {i + 1} of {what}")
+            problems += _equation_problems(model, condition, f"The condition of branch {i + 1} of {what}")
+    elif parameter.minmax_equation:
+        for side, equation in zip(("min", "max"), parameter.equation):
+            problems += _equation_problems(model, equation, f"The {side} equation of {what}")
+    elif isinstance(parameter.equation, str):
+        problems += [f"{what} calls {name}(), which isn't a function the evaluator knows." for name in _unknown_calls(parameter.equation)]
+    else:
+        problems.append(f"{what} has no equation to evaluate.")
+    return problems
+
+def problems(model, trail=None):
+    """
+    The inconsistencies in a model and its submodels, each with the model's file, the
+    line, and a message.
+    """
+    from . import OneilError, parse_test_input
+
+    trail = set() if trail is None else trail
+    if id(model) in trail:
+        return []
+    trail.add(id(model))
+    file = os.path.basename(model.model_filename)
+
+    found = []
+    def add(line, messages):
+        found.extend({"file": file, "line": line if not isinstance(line, dict) else line["model line"], "message": message} for message in messages)
+
+    for ID, parameter in model.parameters.items():
+        what = f"{parameter.name} ({ID})"
+        add(parameter.line_no, _equation_problems(model, parameter, what))
+        if parameter.fallback_param is not None:
+            add(parameter.line_no, _equation_problems(model, parameter.fallback_param, f"The fallback of {what}"))
+
+    for test in model.tests:
+        what = f"Test {test.label()}"
+        add(test.line_no, [f"{what} uses {arg}, which doesn't resolve to a parameter or test input." for arg in test.args if arg not in test.refs and not _resolves(model, arg)])
+        add(test.line_no, [f"{what} calls {name}(), which isn't a function the evaluator knows." for name in _unknown_calls(test.expression)])
+
+    for symbol, entry in model.submodels.items():
+        # Submodels reached with `from` are only looked up when a parameter uses them.
+        if entry["inputs"]:
+            try:
+                model._retrieve_model(entry["path"])
+            except OneilError as e:
+                add(entry["line_no"], [f"Submodel {symbol} is given test inputs but isn't loaded ({e.message()})."])
+        for arg, value in entry["inputs"].items():
+            if isinstance(value, str) and parse_test_input(arg, value, file, entry["line_no"], entry["line"]) is None and not _resolves(model, value.strip()):
+                add(entry["line_no"], [f"The test input {arg}={value.strip()} for {symbol} doesn't resolve to a parameter."])
+        if "model" in entry:
+            found += problems(entry["model"], trail)
+    return found
+
+def verify(model):
+    """
+    Raise a ConsistencyError if the model or its submodels have inconsistencies.
+    """
+    from . import ConsistencyError
+
+    found = problems(model)
+    if found:
+        raise ConsistencyError(os.path.basename(model.model_filename), found)
+
+def run(inp):
+    """
+    Load a model and print its inconsistencies. Returns them.
+    """
+    from . import Model
+
+    directory, model_file = os.path.split(inp if inp.endswith(".on") else inp + ".on")
+    with working_directory(directory or "."):
+        found = problems(Model(model_file))
+    for problem in found:
+        print(f"{bcolors.error('ERROR')} {problem['file']} (line {problem['line']}): {problem['message']}")
+    if not found:
+        print(bcolors.OKGREEN + "The resolved model is consistent with the evaluator." + bcolors.ENDC)
+    return found
@@ -6,18 +6,274 @@ except ImportError:
     def get_version(package_name):
         return get_distribution(package_name).version
 
+import json
+import os
+import sys
+
 from . import bcolors
+from . import render
 
 __version__ = get_version("oneil")
 
+# Exit codes let scripts and CI branch on the type of failure.
+EXIT_SUCCESS = 0
+EXIT_USAGE = 1
+EXIT_PARSE_ERROR = 2
+EXIT_RESOLUTION_ERROR = 3
+EXIT_UNIT_ERROR = 4
+EXIT_TEST_FAILURE = 5
+EXIT_LIMIT_VIOLATION = 6
+EXIT_EVALUATION_ERROR = 7
+EXIT_INTERNAL_ERROR = 8
+EXIT_WARNINGS = 9
+
+EXIT_CODES = {
+    "SyntaxError": EXIT_PARSE_ERROR,
+    "UnitParseError": EXIT_PARSE_ERROR,
+    "ImportError": EXIT_PARSE_ERROR,
+    "ModelLoadingError": EXIT_RESOLUTION_ERROR,
+    "ModelError": EXIT_RESOLUTION_ERROR,
+    "ModelNotFoundError": EXIT_RESOLUTION_ERROR,
+    "IDError": EXIT_RESOLUTION_ERROR,
+    "DesignError": EXIT_RESOLUTION_ERROR,
+    "OverrideError": EXIT_RESOLUTION_ERROR,
+    "CircularDependencyError": EXIT_RESOLUTION_ERROR,
+    "ExternError": EXIT_RESOLUTION_ERROR,
+    "RefactorError": EXIT_USAGE,
+    "PackError": EXIT_USAGE,
+    "VerifyError": EXIT_USAGE,
+    "SweepError": EXIT_USAGE,
+    "ScaffoldError": EXIT_USAGE,
+    "UnitEvaluationError": EXIT_UNIT_ERROR,
+    "PiecewiseUnitError": EXIT_UNIT_ERROR,
+    "LimitUnitError": EXIT_UNIT_ERROR,
+    "SubmodelInputUnitError": EXIT_UNIT_ERROR,
+    "FunctionUnitError": EXIT_UNIT_ERROR,
+    "LimitError": EXIT_LIMIT_VIOLATION,
+    "ConsistencyError": EXIT_INTERNAL_ERROR,
+}
+
+ERROR_FORMATS = ["human", "json"]
+
+# Output settings, set from the command-line flags.
+error_format = "human"
+quiet = False
+
+# Whether warnings fail the command (--deny-warnings), for CI
+deny_warnings = False
+
+# The most errors to print for one command before summarizing the rest (0 for no
+# limit), from display.max_errors or --max-errors, which takes precedence
+max_errors = 0
+max_errors_flag = None
+
+# The errors printed and left out since the last summary
+errors_printed = 0
+errors_suppressed = 0
+
+# The exit code of the first failure, used when the CLI exits.
+exit_code = EXIT_SUCCESS
+
+def exit_code_for(error):
+    return EXIT_CODES.get(error.kind(), EXIT_EVALUATION_ERROR)
+
+def record_exit(code):
+    global exit_code
+    if exit_code == EXIT_SUCCESS:
+        exit_code = code
+
 def print_welcome_message():
     print("Oneil " + __version__)
     print("Type 'help' for a list of commands or see the README for more information.")
     print("-"*80)
 
 def print_error(error):
+    global errors_printed, errors_suppressed
+    record_exit(exit_code_for(error))
+
+    if max_errors and errors_printed >= max_errors:
+        errors_suppressed += 1
+        return
+    errors_printed += 1
+
+    if error_format == "json":
+        print_error_json(error)
+        return
+
+    # Errors that involve several places in the models show each one (see oneil.render)
+    snippet = ''.join(f"\n{line}" for line in render.snippet(error.labels())) if hasattr(error, "labels") else ""
     notes = ''.join(list(map(lambda note: f"\n  - {note}", error.notes())))
     if error.context() == None:
-        print(f"{bcolors.error(error.kind())}: {error.message()}{notes}")
+        print(f"{bcolors.error(_kind(error))}: {error.message()}{snippet}{notes}")
     else:
-        print(f"{bcolors.error(error.kind())} {error.context()}: {error.message()}{notes}")
+        print(f"{bcolors.error(_kind(error))} {error.context()}: {error.message()}{snippet}{notes}")
+
+def _is_source(file, error):
+    # Whether the error is in the model file itself (rather than in a model it uses)
+    source = getattr(error, "filename", None)
+    name = lambda path: os.path.splitext(os.path.basename(str(path)))[0]
+    return source is not None and name(source) == name(file)
+
+def group_errors(failures):
+    """
+    Group (file, error) pairs from many models by the error, so a broken submodel used by
+    several models is one group. Returns (error, files) pairs, the groups with the most
+    models first, and in each group the model with the error itself (if it's one of them)
+    first.
+    """
+    groups = {}
+    for file, error in failures:
+        key = (error.kind(), error.context(), error.message())
+        groups.setdefault(key, (error, []))[1].append(file)
+    grouped = [(error, sorted(files, key=lambda file: not _is_source(file, error))) for error, files in groups.values()]
+    return sorted(grouped, key=lambda group: -len(group[1]))
+
+def print_error_groups(failures, while_text):
+    """
+    Print the errors from many models (see group_errors()) once each, noting the first
+    model with while_text(file) and how many other models failed because of it.
+    """
+    from . import messages
+
+    for error, files in group_errors(failures):
+        error = error.with_note(while_text(files[0]))
+        if len(files) > 1:
+            error = error.with_note(messages.text("error-also-in", "Also caused {count} other model(s) to fail: {files}", count=len(files) - 1, files=", ".join(files[1:])))
+        print_error(error)
+
+def summarize_errors():
+    """
+    Print how many errors were left out ("...and 137 more errors") since the last
+    summary, and start counting again. Called when a command or run finishes.
+    """
+    global errors_printed, errors_suppressed
+    from . import messages
+
+    if errors_suppressed:
+        message = messages.text("more-errors", "...and {count} more error(s) (see --max-errors).", count=errors_suppressed)
+        if error_format == "json":
+            print(json.dumps({"kind": "ErrorSummary", "message": message, "suppressed": errors_suppressed}), file=sys.stderr)
+        else:
+            print(f"{bcolors.ITALIC}{message}{bcolors.ENDC}")
+    errors_printed = 0
+    errors_suppressed = 0
+
+def _kind(error):
+    # The kind with its stable code, e.g. "SyntaxError[E0001]"
+    return f"{error.kind()}[{error.code()}]" if error.code() else error.kind()
+
+def print_warning(warning):
+    """
+    Print a warning (a OneilError that doesn't stop the model from loading), unless the
+    diagnostics policy allows it (see oneil.diagnostics). Warnings don't change the exit
+    code unless they're denied, in which case they're printed as errors.
+    """
+    from . import diagnostics
+
+    level = diagnostics.level(warning)
+    if level == "allow":
+        return
+    severity = _severity(warning)
+    notes = warning.notes()
+    if level == "deny":
+        record_exit(EXIT_WARNINGS)
+        severity = "error"
+        notes = notes + [f"The diagnostics policy denies {diagnostics.category(warning)}."]
+    color = bcolors.error if severity == "error" else bcolors.warning
+
+    if error_format == "json":
+        print(json.dumps({
+            "kind": warning.kind(),
+            "code": warning.code(),
+            "code_description": _code_description(warning),
+            "context": warning.context(),
+            "message": warning.message(),
+            "notes": notes,
+            "severity": severity,
+        } | location(warning)), file=sys.stderr)
+        return
+
+    notes = ''.join(list(map(lambda note: f"\n  - {note}", notes)))
+    if warning.context() == None:
+        print(f"{color(_kind(warning))}: {warning.message()}{notes}")
+    else:
+        print(f"{color(_kind(warning))} {warning.context()}: {warning.message()}{notes}")
+
+def _severity(warning):
+    # Errors that are printed as warnings (like invalid config settings) are warnings
+    severity = getattr(warning, "severity", "warning")
+    return "warning" if severity == "error" else severity
+
+def _code_description(error):
+    """
+    The link to the long-form explanation of an error's code (see oneil.explanations),
+    or None for errors without a code.
+    """
+    from . import explanations
+
+    href = explanations.url(error.code())
+    return {"href": href} if href else None
+
+NO_LOCATION = {"file": None, "line": None, "start_column": None, "end_column": None}
+
+def location(error, encoding="utf-32"):
+    """
+    Where an error or warning is, for editors and CI annotations: its file, its 1-based
+    line, and the 1-based columns it spans in that line (`end_column` is exclusive),
+    counted in the given encoding (see oneil.positions). Errors about a parameter span
+    its ID, and errors about a line span the line's text. Parts that aren't known are
+    None.
+    """
+    from . import positions
+
+    parameter = getattr(error, "parameter", None) or getattr(error, "context_", None)
+    if hasattr(parameter, "source") and parameter.model and parameter.line_no is not None and not parameter.model.startswith("-"):
+        return NO_LOCATION | parameter.source(parameter.model if parameter.model.endswith(".on") else parameter.model + ".on", encoding)
+    model = parameter if hasattr(parameter, "model_filename") else getattr(error, "model", None)
+    if hasattr(model, "model_filename"):
+        return NO_LOCATION | {"file": os.path.relpath(model.model_filename)}
+
+    filename, line_no = getattr(error, "filename", None), getattr(error, "line_no", None)
+    if isinstance(getattr(error, "location", None), tuple):
+        filename, line_no = error.location
+    if not isinstance(filename, str):
+        return dict(NO_LOCATION)
+    if hasattr(line_no, "fragment"):
+        filename = os.path.relpath(line_no.fragment)
+    elif not filename.endswith(".on"):
+        filename += ".on"
+    if not isinstance(line_no, int) or line_no < 1:
+        return NO_LOCATION | {"file": filename}
+    result = NO_LOCATION | {"file": filename, "line": int(line_no)}
+    line = positions.source_line(filename, int(line_no))
+    if line.strip():
+        start = len(line) - len(line.lstrip())
+        result["start_column"] = positions.column_of(line, start, encoding) + 1
+        result["end_column"] = positions.column_of(line, len(line.rstrip()), encoding) + 1
+    return result
+
+def error_json(error):
+    """
+    An error as plain data, for JSON output.
+    """
+    output = {
+        "kind": error.kind(),
+        "code": error.code(),
+        "code_description": _code_description(error),
+        "severity": "error",
+        "context": error.context(),
+        "message": error.message(),
+        "notes": error.notes(),
+        "exit_code": exit_code_for(error),
+    } | location(error)
+    # Other locations involved in the error (e.g. each use declaration in a cycle), for
+    # editors to link to
+    if hasattr(error, "related"):
+        output["related"] = error.related()
+    # The places an error underlines in a snippet, with their labels (see oneil.render)
+    if hasattr(error, "labels") and error.labels():
+        output["labels"] = error.labels()
+    return output
+
+def print_error_json(error):
+    print(json.dumps(error_json(error)), file=sys.stderr)
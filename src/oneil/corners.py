@@ -0,0 +1,113 @@
+"""
+Which endpoints of a parameter's inputs give its min and its max (`--corners`), so
+engineers can see which worst-case stackup drives a bound.
+
+Extrema math takes each input at whichever end of its range makes the result smallest or
+largest, so each bound of a parameter comes from a corner of its inputs' ranges: for
+`m_total = m_dry + m_prop - m_vent`, the min is from m_dry and m_prop at their min and
+m_vent at its max. With tracking on, each calculated parameter's equation is evaluated
+again at the corners of its inputs with ranges to find the corner that gives each bound.
+A bound that no corner gives (like the peak of a function inside an input's range) is
+inside the inputs' ranges and has no corner.
+
+`explain` prints the corners of a parameter, with the stackup of independent inputs
+behind them, and exported results have them as "corners". Parameters calculated by
+Python functions, piecewise parameters, and parameters with more than MAX_INPUTS inputs
+with ranges aren't tracked.
+"""
+
+import copy
+import itertools
+import math
+
+# Whether to track the corners of calculated parameters (`--corners`)
+enabled = False
+
+# The most inputs with ranges a parameter can have to be tracked (2^n evaluations)
+MAX_INPUTS = 10
+
+BOUNDS = ["min", "max"]
+
+def _bounds(value):
+    from . import Parameter
+
+    if isinstance(value, Parameter):
+        return value.min, value.max
+    if isinstance(value, tuple):
+        return value
+    return value, value
+
+def _ranged(value):
+    from . import Parameter
+
+    return (isinstance(value, Parameter) and isinstance(value.min, (int, float)) and isinstance(value.max, (int, float))
+            and not isinstance(value.min, bool) and value.min != value.max)
+
+def track(parameter, expression, glob, scope, calc_args, calculation):
+    """
+    The corners that give a calculated parameter's bounds, as {"min": corner, "max":
+    corner}, where a corner maps each input with a range (by ID, as in the equation) to
+    the end of its range it's at, "min" or "max", or is None if no corner gives that
+    bound. Returns None if the parameter can't be tracked or has no inputs with ranges.
+    `scope` and `calc_args` are what the parameter was calculated with.
+    """
+    inputs = [(ID, key) for ID, key in zip(parameter.args, calc_args) if _ranged(scope.get(key))]
+    if not inputs or len(inputs) > MAX_INPUTS:
+        return None
+    lower, upper = _bounds(calculation)
+    if not all(isinstance(bound, (int, float)) for bound in (lower, upper)):
+        return None
+
+    corners = {"min": None, "max": None}
+    for ends in itertools.product(BOUNDS, repeat=len(inputs)):
+        local = dict(scope)
+        for (_, key), end in zip(inputs, ends):
+            local[key] = copy.copy(scope[key])
+            local[key].min = local[key].max = getattr(scope[key], end)
+        try:
+            value = _bounds(parameter.calculate(expression, glob, local, calc_args))
+        except Exception:
+            continue
+        corner = {ID: end for (ID, _), end in zip(inputs, ends)}
+        if corners["min"] is None and math.isclose(min(value), lower, rel_tol=1e-9):
+            corners["min"] = corner
+        if corners["max"] is None and math.isclose(max(value), upper, rel_tol=1e-9):
+            corners["max"] = corner
+    return corners
+
+def stackup(model, ID, bound):
+    """
+    The independent inputs (and inputs from submodels) behind a bound of a parameter,
+    following the corners of the parameters between them, as {ID: end}. Inputs whose
+    corners aren't known are included as they are, and an input that's needed at both
+    ends by different paths (which extrema math allows) is at "both". Returns None if
+    the parameter's corner for the bound isn't known.
+    """
+    corners = model.parameters[ID].corners
+    if not corners or corners[bound] is None:
+        return None
+    result = {}
+    for input_ID, end in corners[bound].items():
+        deeper = stackup(model, input_ID, end) if input_ID in model.parameters else None
+        for leaf, leaf_end in (deeper if deeper is not None else {input_ID: end}).items():
+            result[leaf] = leaf_end if result.get(leaf, leaf_end) == leaf_end else "both"
+    return result
+
+def describe(corner):
+    return ", ".join(f"{ID} at both ends" if end == "both" else f"{ID} at its {end}" for ID, end in corner.items())
+
+def print_corners(model, ID, indent="    "):
+    """
+    Print the corners of a parameter's bounds and the stackups behind them, for `explain`.
+    """
+    corners = model.parameters[ID].corners
+    if not corners:
+        return
+    for bound in BOUNDS:
+        if corners[bound] is None:
+            print(f"{indent}{bound.capitalize()} from: inside the ranges of its inputs, not at their ends")
+            continue
+        print(f"{indent}{bound.capitalize()} from: {describe(corners[bound])}")
+        inputs = stackup(model, ID, bound)
+        if inputs != corners[bound]:
+            print(f"{indent}    Stackup: {describe(inputs)}")
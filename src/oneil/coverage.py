@@ -0,0 +1,235 @@
+"""
+Coverage of a model's tests (`oneil coverage`): which parameters and piecewise branches
+the tests exercise, and which limits no test checks.
+
+A test exercises the parameters its expression uses and everything they depend on, in
+the model and its submodels. For a piecewise parameter, that's the parameters its
+conditions use and the equation of the branch that applies with the model's values,
+which is the branch the test exercises. Tests skipped for missing inputs exercise
+nothing. A parameter's explicit limits (`Name(min, max): ...`) are checked if a test
+exercises the parameter; otherwise a value could leave them without any test noticing
+which input moved it there.
+
+Results are grouped by model file, since a submodel used more than once is covered by
+the tests that exercise any of its uses.
+"""
+
+import html
+import os
+
+import numpy as np
+from beautifultable import BeautifulTable
+
+from . import bcolors
+
+def _models(model, found=None):
+    # Every model instance in the tree, including each use of a shared submodel once
+    found = found if found is not None else {}
+    if id(model) not in found:
+        found[id(model)] = model
+        for entry in model.submodels.values():
+            if 'model' in entry:
+                _models(entry['model'], found)
+    return found
+
+def _active_branch(parameter):
+    return next((i for i, piece in enumerate(parameter.equation) if piece[1].min and piece[1].max), None)
+
+def _has_limits(parameter):
+    return isinstance(parameter.options, tuple) and tuple(parameter.options) != (0, np.inf)
+
+def _line(parameter):
+    # The file and line the parameter is declared on (not the line of a design value)
+    line_no = parameter.line_no['model line'] if isinstance(parameter.line_no, dict) else parameter.line_no
+    return getattr(line_no, "fragment", None), int(line_no) if line_no is not None else None
+
+def _exercise(scope, IDs, exercised, branches):
+    """
+    Mark the parameters (IDs or `ID.symbol`s of the scope model) and everything they
+    depend on as exercised, by (model file, ID), and the branches of their piecewise
+    parameters that apply, by (model file, ID, index).
+    """
+    for ID in IDs:
+        if "." in ID:
+            ID, symbol = ID.split(".", 1)
+            if symbol not in scope.submodels:
+                continue
+            _exercise(scope._retrieve_model(scope.submodels[symbol]['path']), [ID], exercised, branches)
+            continue
+        if ID not in scope.parameters or (scope.model_filename, ID) in exercised:
+            continue
+        exercised.add((scope.model_filename, ID))
+
+        parameter = scope.parameters[ID]
+        if parameter.piecewise and not parameter.independent:
+            branch = _active_branch(parameter)
+            args = [arg for piece in parameter.equation for arg in piece[1].args]
+            if branch is not None:
+                branches.add((scope.model_filename, ID, branch))
+                args += parameter.equation[branch][0].args
+            if parameter.fallback_param:
+                args += parameter.fallback_param.args
+        else:
+            args = parameter.args
+        _exercise(scope, list(dict.fromkeys(args)), exercised, branches)
+
+def coverage(model):
+    """
+    The coverage of a model's tests (after they've run), for each model file in the
+    model's tree: its parameters (whether the tests exercise each one), the branches of
+    its piecewise parameters (whether the tests exercise each one), and its parameters
+    with explicit limits that no test checks. Each entry has the line it's declared on,
+    and the file if it's in an included fragment.
+    """
+    models = _models(model)
+    exercised, branches = set(), set()
+    for scope in models.values():
+        for test in scope.tests:
+            if test.passed is not None:
+                _exercise(scope, test.args, exercised, branches)
+
+    files = {}
+    for scope in models.values():
+        report = files.setdefault(scope.model_filename, {"file": scope.model_filename, "name": scope.name, "parameters": {}, "branches": {}})
+        for ID, parameter in scope.parameters.items():
+            fragment, line = _line(parameter)
+            entry = report["parameters"].setdefault(ID, {"id": ID, "name": parameter.name, "fragment": fragment, "line": line, "covered": False, "limits": _has_limits(parameter)})
+            entry["covered"] = entry["covered"] or (scope.model_filename, ID) in exercised
+            if parameter.piecewise and not parameter.independent:
+                for i, piece in enumerate(parameter.equation):
+                    branch = report["branches"].setdefault((ID, i), {"id": ID, "branch": i, "condition": str(piece[1].equation), "fragment": fragment, "line": line, "covered": False})
+                    branch["covered"] = branch["covered"] or (scope.model_filename, ID, i) in branches
+
+    result = []
+    for report in files.values():
+        parameters = list(report["parameters"].values())
+        result.append({
+            "file": report["file"],
+            "name": report["name"],
+            "parameters": parameters,
+            "branches": list(report["branches"].values()),
+            "unchecked_limits": [parameter for parameter in parameters if parameter["limits"] and not parameter["covered"]],
+        })
+    return result
+
+def _fraction(covered, total):
+    if not total:
+        return "-"
+    return f"{covered}/{total} ({100 * covered / total:.0f}%)"
+
+def print_coverage(model):
+    """
+    Print the coverage of a model's tests (see coverage()) as a table of its model
+    files, followed by what isn't covered.
+    """
+    report = coverage(model)
+    table = BeautifulTable()
+    table.columns.header = ["Model", "Parameters", "Branches", "Unchecked limits"]
+    for entry in report:
+        parameters, branches = entry["parameters"], entry["branches"]
+        table.append_row([
+            os.path.relpath(entry["file"]),
+            _fraction(sum(p["covered"] for p in parameters), len(parameters)),
+            _fraction(sum(b["covered"] for b in branches), len(branches)),
+            len(entry["unchecked_limits"]),
+        ])
+    print(table)
+
+    for entry in report:
+        name = os.path.relpath(entry["file"])
+        uncovered = [p["id"] for p in entry["parameters"] if not p["covered"]]
+        if uncovered:
+            print(f"{bcolors.YELLOW}{name}: not exercised by any test: {', '.join(uncovered)}{bcolors.ENDC}")
+        for branch in entry["branches"]:
+            if not branch["covered"]:
+                print(f"{bcolors.YELLOW}{name}: branch {branch['branch'] + 1} of {branch['id']} ({branch['condition']}) not exercised by any test{bcolors.ENDC}")
+        if entry["unchecked_limits"]:
+            print(f"{bcolors.YELLOW}{name}: limits never checked by a test: {', '.join(p['id'] for p in entry['unchecked_limits'])}{bcolors.ENDC}")
+    return report
+
+def _branch_lines(lines, line):
+    """
+    The lines (1-based) of the branches of a piecewise parameter declared on the given
+    line: the first branch is on the declaration if it has a "{", and each other branch
+    is on an indented line after it starting with "{".
+    """
+    found = [line] if "{" in lines[line - 1].split("=", 1)[-1] else []
+    for i in range(line, len(lines)):
+        text = lines[i]
+        if text.strip() and text[0] not in (" ", "\t"):
+            break
+        if text.strip().startswith("{"):
+            found.append(i + 1)
+    return found
+
+HTML_STYLE = """body { font-family: sans-serif; }
+pre { line-height: 1.4; }
+.covered { background: #ddf4dd; }
+.uncovered { background: #f8d7d7; }
+.partial { background: #fcefc7; }
+.line-number { color: #888; display: inline-block; width: 4em; }
+.note { color: #a15c00; font-style: italic; }"""
+
+def html_report(model):
+    """
+    The coverage of a model's tests (see coverage()) as an HTML page with the source of
+    each model file, its lines annotated with whether the parameters and branches
+    declared on them are exercised and whether their limits are checked.
+    """
+    sections = []
+    for entry in coverage(model):
+        # Annotations by (file, line): the CSS class and a note
+        marks = {}
+        sources = {}
+        for parameter in entry["parameters"]:
+            path = parameter["fragment"] or entry["file"]
+            state = "covered" if parameter["covered"] else "uncovered"
+            note = "" if parameter["covered"] else "not exercised by any test" + ("; limits never checked" if parameter["limits"] else "")
+            marks[(path, parameter["line"])] = (state, note)
+        for branch in entry["branches"]:
+            path = branch["fragment"] or entry["file"]
+            if path not in sources:
+                with open(path, "r") as f:
+                    sources[path] = f.readlines()
+            lines = _branch_lines(sources[path], branch["line"])
+            if branch["branch"] >= len(lines):
+                continue
+            at = (path, lines[branch["branch"]])
+            state = "covered" if branch["covered"] else "uncovered"
+            if at in marks and marks[at][0] != state:
+                state = "partial"
+            note = "" if branch["covered"] else f"branch {branch['branch'] + 1} not exercised"
+            marks[at] = (state, "; ".join(n for n in (marks.get(at, ("", ""))[1], note) if n))
+
+        for path in dict.fromkeys([entry["file"]] + [p for p, _ in marks]):
+            if path not in sources:
+                with open(path, "r") as f:
+                    sources[path] = f.readlines()
+            rows = []
+            for i, text in enumerate(sources[path]):
+                state, note = marks.get((path, i + 1), (None, ""))
+                row = f'<span class="line-number">{i + 1}</span>{html.escape(text.rstrip())}'
+                if note:
+                    row += f'  <span class="note">{html.escape(note)}</span>'
+                rows.append(f'<span class="{state}">{row}</span>' if state else row)
+            parameters = entry["parameters"]
+            summary = f"Parameters: {_fraction(sum(p['covered'] for p in parameters), len(parameters))}, branches: {_fraction(sum(b['covered'] for b in entry['branches']), len(entry['branches']))}"
+            sections.append(f"<h2>{html.escape(os.path.relpath(path))}</h2>\n<p>{html.escape(summary)}</p>\n<pre>\n" + "\n".join(rows) + "\n</pre>")
+
+    title = f"Test coverage of {html.escape(model.name)}"
+    return f"<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{HTML_STYLE}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n" + "\n".join(sections) + "\n</body>\n</html>\n"
+
+def run(inp, designs=(), html_file=None):
+    """
+    Load a model (with the designs), run its tests, and print their coverage, writing
+    the annotated HTML report to html_file if given.
+    """
+    from . import loader
+
+    model = loader(inp, list(designs), capture_errors=False, quiet=True)
+    report = print_coverage(model)
+    if html_file:
+        with open(html_file, "w") as f:
+            f.write(html_report(model))
+        print(f"Wrote the coverage report to {html_file}.")
+    return report
@@ -0,0 +1,87 @@
+"""
+Crash reports for unexpected errors (bugs in Oneil rather than in a model).
+
+Instead of a raw traceback, the CLI writes a crash report with everything needed to
+reproduce the crash and prints a short message pointing to it. Set ONEIL_BACKTRACE=1
+to also print the traceback.
+"""
+
+import datetime
+import json
+import os
+import platform
+import sys
+import tempfile
+import traceback
+
+from . import bcolors
+from . import console
+from . import messages
+
+ISSUES_URL = "https://github.com/careweather/oneil/issues"
+
+# The model being loaded or explored, included in crash reports.
+current_model = None
+
+def crash_directory():
+    return os.environ.get("ONEIL_CRASH_DIR") or tempfile.gettempdir()
+
+# The command line the CLI was started with, included in crash reports.
+command_line = None
+
+def bundle(error, repl_command=None):
+    """
+    The text of a crash report: the version, command, model, environment, and traceback.
+    """
+    command = command_line if command_line is not None else sys.argv[1:]
+    lines = [
+        "Oneil crash report",
+        f"Version: {console.__version__}",
+        f"Time: {datetime.datetime.now().isoformat(timespec='seconds')}",
+        f"Command: {' '.join(['oneil'] + command)}",
+        f"Working directory: {os.getcwd()}",
+        f"Model: {current_model or '(none)'}",
+        f"Interpreter command: {repl_command}" if repl_command else None,
+        f"Python: {sys.version.split()[0]} ({platform.platform()})",
+        "",
+        "".join(traceback.format_exception(type(error), error, error.__traceback__)),
+    ]
+    return "\n".join(line for line in lines if line is not None)
+
+def report(error, repl_command=None):
+    """
+    Write a crash report for an unexpected exception (in an interpreter command, if
+    given) and print where it was written. Returns the path of the report, or None if
+    it couldn't be written.
+    """
+    console.record_exit(console.EXIT_INTERNAL_ERROR)
+    text = bundle(error, repl_command)
+
+    path = os.path.join(crash_directory(), f"oneil-crash-{datetime.datetime.now().strftime('%Y%m%d-%H%M%S')}-{os.getpid()}.txt")
+    try:
+        with open(path, "w") as f:
+            f.write(text)
+    except OSError:
+        path = None
+
+    if os.environ.get("ONEIL_BACKTRACE"):
+        traceback.print_exception(type(error), error, error.__traceback__)
+
+    crashed = messages.text("crash", "Oneil crashed ({error}).", error=f"{type(error).__name__}: {error}")
+    report = messages.text("crash-report", "A crash report was written to {file}", file=path)
+    if console.error_format == "json":
+        print(json.dumps({
+            "kind": "InternalError",
+            "code": messages.code("InternalError"),
+            "context": None,
+            "message": crashed,
+            "notes": [report] if path else [],
+            "exit_code": console.EXIT_INTERNAL_ERROR,
+        }), file=sys.stderr)
+        return path
+
+    print(f"{bcolors.error('InternalError[' + messages.code('InternalError') + ']')}: {crashed} {messages.text('crash-bug', 'This is a bug in Oneil, not in your model.')}", file=sys.stderr)
+    if path:
+        print(f"  - {report}", file=sys.stderr)
+    print(f"  - {messages.text('crash-issue', 'Please report it at {url} with the crash report attached.', url=ISSUES_URL)}", file=sys.stderr)
+    return path
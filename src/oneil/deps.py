@@ -0,0 +1,181 @@
+"""
+The files a model would load (`oneil deps`), found without resolving its parameters
+or tests.
+
+Follows `use` and `from ... use` declarations, included fragments, Python imports
+(and the local modules those import), and design files, recording for each file the
+chain of files that led to it. Build systems can use the list as the input set of an
+evaluation, or read it from a Makefile/ninja depfile written with `--depfile`.
+"""
+
+import ast
+import json
+import os
+import re
+
+from . import bcolors
+from . import console
+from . import messages
+from .stats import working_directory
+
+# The depfile to write (from --depfile) and its target (from --depfile-target, or the
+# depfile's path without ".d")
+depfile = None
+depfile_target = None
+
+def _on_file(name):
+    return name if name.endswith(".on") else name + ".on"
+
+def _entry(path, kind, chain, start):
+    return {"file": os.path.relpath(path, start), "kind": kind, "chain": [os.path.relpath(file, start) for file in chain]}
+
+def _local_module(directory, module):
+    for path in (os.path.join(directory, module + ".py"), os.path.join(directory, module, "__init__.py")):
+        if os.path.exists(path):
+            return os.path.abspath(path)
+    return None
+
+def _python_imports(path):
+    """
+    The local modules imported by a Python file (modules installed elsewhere aren't
+    part of the model).
+    """
+    try:
+        with open(path, "r") as f:
+            tree = ast.parse(f.read(), path)
+    except (OSError, ValueError, SyntaxError):
+        return []
+
+    directory = os.path.dirname(path)
+    modules = []
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Import):
+            modules += [alias.name.split(".")[0] for alias in node.names]
+        elif isinstance(node, ast.ImportFrom) and node.module:
+            modules.append(node.module.split(".")[0])
+        elif isinstance(node, ast.ImportFrom):
+            modules += [alias.name for alias in node.names]
+    return [found for found in (_local_module(directory, module) for module in modules) if found]
+
+def _model_dependencies(path):
+    """
+    The models, fragments, and Python modules used directly by a model file, as
+    (path, kind, fragment) tuples, where fragment is the included fragment the
+    declaration is in, if any.
+    """
+    from . import read_model_lines
+
+    found = []
+    for line, line_no in read_model_lines(path):
+        fragment = getattr(line_no, "fragment", None)
+        fragment = os.path.abspath(fragment) if fragment else None
+        if fragment:
+            found.append((fragment, "fragment", None))
+        use = re.search(r"^(?:from\s+\S+\s+)?use\s+(\w+)", line)
+        if use:
+            # Submodels are found relative to the working directory, like when loading.
+            found.append((os.path.abspath(use.group(1) + ".on"), "model", fragment))
+        module = re.search(r"^import\s+(\w+)\s*$", line)
+        if module:
+            local = _local_module(os.path.dirname(path), module.group(1))
+            if local:
+                found.append((local, "python", fragment))
+    return list(dict.fromkeys(found))
+
+def dependencies(inp, designs=[], directory=None):
+    """
+    The files loading the model (with the given designs) would read, in the order
+    they're found. Returns the files (each with its kind and the chain of files from
+    the model or design that led to it) and the files that don't exist.
+
+    Submodels are found relative to the working directory, or to `directory` for
+    models loaded from their own directory (like `oneil eval` does). Paths are relative
+    to the working directory either way.
+    """
+    start = os.getcwd()
+    if directory:
+        with working_directory(directory):
+            return _dependencies(inp, designs, start)
+    return _dependencies(inp, designs, start)
+
+def _dependencies(inp, designs, start):
+    from . import OneilError
+
+    queue = [(os.path.abspath(_on_file(inp)), "model", [])]
+    queue += [(os.path.abspath(_on_file(design)), "design", []) for design in designs]
+    files = {}
+    missing = []
+    while queue:
+        path, kind, chain = queue.pop(0)
+        chain = chain + [path]
+        if path in files:
+            continue
+        if not os.path.exists(path):
+            missing.append(_entry(path, kind, chain, start))
+            continue
+        files[path] = _entry(path, kind, chain, start)
+
+        if kind == "python":
+            queue += [(module, "python", chain) for module in _python_imports(path)]
+        elif kind in ("model", "design"):
+            try:
+                queue += [(found, found_kind, chain + [fragment] if fragment else chain) for found, found_kind, fragment in _model_dependencies(path)]
+            except OneilError as e:
+                console.print_error(e)
+
+    return list(files.values()), missing
+
+def run(inp, designs=[]):
+    """
+    Print the files the model would load, with how each one was reached, or the same
+    as JSON with `--error-format json`. Returns the number of missing files.
+    """
+    files, missing = dependencies(inp, designs)
+
+    if console.error_format == "json":
+        print(json.dumps({"files": files, "missing": missing}))
+    else:
+        for entry in files:
+            via = f" {bcolors.ITALIC}(via {' -> '.join(entry['chain'][:-1])}){bcolors.ENDC}" if len(entry["chain"]) > 1 else ""
+            print(f"{entry['file']} [{entry['kind']}]{via}")
+        for entry in missing:
+            if len(entry["chain"]) > 1:
+                message = messages.text("deps-missing", "{file} not found (via {chain}).", file=entry["file"], chain=" -> ".join(entry["chain"][:-1]))
+            else:
+                message = messages.error_text("ModelNotFoundError", f"Model {entry['file']} not found.", file=entry["file"])
+            print(f"{bcolors.error('ERROR')} {message}")
+
+    if missing:
+        console.record_exit(console.EXIT_RESOLUTION_ERROR)
+    return len(missing)
+
+def _escape(path):
+    # Make (and ninja) treat spaces, "#", and "$" specially in depfiles.
+    return path.replace("$", "$$").replace("#", "\\#").replace(" ", "\\ ")
+
+def depfile_text(target, files):
+    """
+    A Makefile/ninja depfile saying that the target depends on the files.
+    """
+    return f"{_escape(target)}:" + "".join(f" \\\n  {_escape(file)}" for file in files) + "\n"
+
+def write_depfile(models):
+    """
+    Write the depfile from --depfile (if it was given) for everything the models read,
+    given as (model, designs, directory) tuples (see `dependencies()`). Missing files are
+    left out, since there's nothing for a build system to track until they exist.
+    """
+    if not depfile:
+        return
+    files = []
+    for inp, designs, directory in models:
+        found, _ = dependencies(inp, designs, directory)
+        files += [entry["file"] for entry in found if entry["file"] not in files]
+    target = depfile_target or (depfile[:-2] if depfile.endswith(".d") else depfile)
+    try:
+        with open(depfile, "w") as f:
+            f.write(depfile_text(target, files))
+    except OSError as e:
+        message = messages.text("depfile-error", "Can't write depfile {file}: {error}", file=depfile, error=e.strerror)
+        print(f"{bcolors.error('ERROR')} {message}")
+        console.record_exit(console.EXIT_USAGE)
@@ -0,0 +1,61 @@
+"""
+Deterministic evaluation (`--deterministic`), so baselines made on one OS or CPU compare
+cleanly in CI on another.
+
+The same model can give results that differ in their last bits on different machines.
+This profile handles each cause:
+
+* Vectorized math: NumPy picks SIMD kernels for the CPU it runs on, some with fused
+  multiply-add, so `sin()` or `log()` can differ between machines with the same
+  NumPy. Deterministic mode evaluates the builtin functions on Python floats with the
+  `math` module instead. Python's own float arithmetic (`+`, `-`, `*`, `/`) is IEEE 754
+  and never fused, and `sqrt()`, `floor()`, and `ceiling()` are exact everywhere.
+* libm: `math` still calls the platform's C math library, which can differ by an ulp
+  between glibc, macOS, and Windows, and so can `**` with a fractional exponent. Their
+  results are rounded to DIGITS significant digits, so those differences disappear
+  unless a value falls within an ulp of a rounding boundary.
+* Evaluation order: parameters are calculated in the order they're declared and their
+  arguments in the order they appear, never in set order (which changes with
+  PYTHONHASHSEED). This holds in every mode.
+
+Domain errors (e.g. `log(0)`) are FloatingPointErrors as they are with NumPy.
+"""
+
+import math
+
+import numpy as np
+
+enabled = False
+
+# Significant digits kept from libm results in deterministic mode
+DIGITS = 12
+
+def rounded(value):
+    """
+    A libm result rounded to DIGITS significant digits in deterministic mode. Decimal
+    formatting is correctly rounded in Python on every platform.
+    """
+    if not enabled or not isinstance(value, float) or not math.isfinite(value):
+        return value
+    return float(f"{value:.{DIGITS - 1}e}")
+
+def _function(name, numpy_function, math_function):
+    def evaluate(value):
+        if not enabled:
+            return numpy_function(value)
+        try:
+            return rounded(math_function(float(value)))
+        except ValueError:
+            raise FloatingPointError(f"invalid value encountered in {name}")
+    evaluate.__name__ = name
+    return evaluate
+
+sin = _function("sin", np.sin, math.sin)
+cos = _function("cos", np.cos, math.cos)
+tan = _function("tan", np.tan, math.tan)
+arcsin = _function("arcsin", np.arcsin, math.asin)
+arccos = _function("arccos", np.arccos, math.acos)
+arctan = _function("arctan", np.arctan, math.atan)
+log = _function("log", np.log, math.log)
+log10 = _function("log10", np.log10, math.log10)
+log2 = _function("log2", np.log2, math.log2)
@@ -0,0 +1,149 @@
+"""
+The diagnostics policy: whether each category of warning (and note) is allowed
+(not printed), warned about (printed, the default), or denied (printed as an error that
+fails the command with exit code 9), so a team can make its models stricter one category
+at a time.
+
+The categories are the warnings of the parser, resolver, and unit checker (see
+CATEGORIES) and the lints of `oneil lint` (see oneil.lint.LINTS). `warnings` stands for
+every category of warning that isn't set itself, but not for notes.
+
+The policy comes from the `diagnostics:` lines of a project's manifest (see
+oneil.read_manifest), as `category=level` pairs, and from the `-A`, `-W`, and `-D`
+flags (allow, warn, and deny), which take precedence:
+
+    diagnostics: warnings=deny spelling=warn alias=allow
+
+    $ oneil -D unused-import -A deprecated check models/
+
+A later setting of a category takes precedence over an earlier one. `--deny-warnings`
+is the same as `-D warnings`, but it doesn't override `warnings` set by the manifest or
+the other flags.
+"""
+
+import re
+
+LEVELS = ["allow", "warn", "deny"]
+
+FLAGS = {"-A": "allow", "-W": "warn", "-D": "deny"}
+
+PREFIX = "diagnostics:"
+
+# The categories of the warnings that aren't lints, by the kind of warning
+CATEGORIES = {
+    "deprecated": "DeprecationWarning",
+    "precision": "PrecisionWarning",
+    "mirror": "MirrorWarning",
+    "empty-interval": "EmptyIntervalWarning",
+    "infinity": "InfinityWarning",
+    "feature": "FeatureWarning",
+    "unused-import": "UnusedImportWarning",
+    "shadowed-builtin": "ShadowedBuiltinWarning",
+}
+
+GROUP = "warnings"
+
+# The levels from the manifest, and those set by flags, which take precedence
+policy = {}
+overrides = {}
+
+def reset():
+    policy.clear()
+    overrides.clear()
+
+def categories():
+    from .lint import LINTS
+
+    return [GROUP] + list(CATEGORIES) + list(LINTS)
+
+def category(warning):
+    """
+    The category of a warning, or None for diagnostics that the policy doesn't cover
+    (like invalid config settings).
+    """
+    if hasattr(warning, "lint"):
+        return warning.lint
+    return next((name for name, kind in CATEGORIES.items() if kind == warning.kind()), None)
+
+def level(warning):
+    """
+    Whether a warning is allowed, warned about, or denied ("allow", "warn", or "deny").
+    """
+    from . import console
+
+    name = category(warning)
+    if name is None:
+        return "warn"
+    for settings in (overrides, policy):
+        if name in settings:
+            return settings[name]
+    if getattr(warning, "severity", "warning") == "warning":
+        for settings in (overrides, policy):
+            if GROUP in settings:
+                return settings[GROUP]
+        if console.deny_warnings:
+            return "deny"
+    return "warn"
+
+def _set(settings, name, value, errors):
+    if name not in categories():
+        errors.append(f"Unknown diagnostic category \"{name}\". Categories are: {', '.join(categories())}.")
+    elif value not in LEVELS:
+        errors.append(f"The level of {name} must be one of: {', '.join(LEVELS)}.")
+    else:
+        settings[name] = value
+
+def extract_flags(args, errors):
+    """
+    Remove the `-A`, `-W`, and `-D` flags (`-D category` or `-Dcategory`) from the args
+    and set the categories they name, adding a message to errors for each invalid one.
+    Returns the remaining args.
+    """
+    remaining = []
+    args = iter(args)
+    for arg in args:
+        flag, name = arg[:2], arg[2:]
+        if flag not in FLAGS:
+            remaining.append(arg)
+            continue
+        if not name:
+            name = next(args, None)
+        if name is None:
+            errors.append(f"Missing category for {flag}.")
+            continue
+        _set(overrides, name, FLAGS[flag], errors)
+    return remaining
+
+def from_manifest(line, errors):
+    """
+    The levels of a `diagnostics:` line, in order, adding a message to errors for each
+    pair that isn't `category=level`.
+    """
+    pairs = []
+    for pair in re.split(r"[\s,]+", line[len(PREFIX):].strip()):
+        if not pair:
+            continue
+        name, equals, value = pair.partition("=")
+        if not equals or not name or not value:
+            errors.append(f"Diagnostics must be category=level pairs, not \"{pair}\".")
+            continue
+        pairs.append((name, value))
+    return pairs
+
+def load(manifest, lines):
+    """
+    Apply the levels of a manifest's `diagnostics:` lines (the later ones take
+    precedence). Raises ConfigError for invalid levels after applying the valid ones.
+    """
+    from . import ConfigError
+
+    errors = []
+    for line in lines:
+        if line.startswith(PREFIX):
+            for name, value in from_manifest(line, errors):
+                _set(policy, name, value, errors)
+    if errors:
+        error = ConfigError(manifest, "Some diagnostics were ignored.")
+        for message in errors:
+            error = error.with_note(message)
+        raise error
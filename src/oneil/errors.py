@@ -1,4 +1,10 @@
+# How serious a diagnostic is: errors stop the command, warnings flag things that work
+# but are probably mistakes, and notes are hints
+SEVERITIES = ["error", "warning", "note"]
+
 class OneilError(Exception):
+    severity = "error"
+
     def kind(self) -> str:
         raise NotImplementedError("Subclasses must implement this method")
     
@@ -8,6 +14,10 @@ class OneilError(Exception):
     def message(self) -> str:
         raise NotImplementedError("Subclasses must implement this method")
 
+    def code(self) -> str | None:
+        from .messages import code
+        return code(self.kind())
+
     def notes(self) -> list[str]:
         if hasattr(self, "notes_"):
             return self.notes_
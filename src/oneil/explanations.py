@@ -0,0 +1,108 @@
+"""
+Long-form explanations of the stable error codes (`oneil explain-error E0001`): what
+causes each error or warning, an example that produces it, and how to fix it.
+
+The explanations are kept as data in explanations.toml beside this module, and
+docs/errors.md is generated from them (`oneil explain-error --markdown`), so each code
+has a page anchor that JSON diagnostics and the tuning protocol link to as its code
+description (see url()).
+"""
+
+import os
+import re
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+# Where docs/errors.md is published, for links to an explanation
+DOCS_URL = "https://github.com/careweather/oneil/blob/main/docs/errors.md"
+
+FIELDS = ["title", "cause", "example", "fix"]
+
+_explanations = None
+
+def explanations():
+    """
+    The explanations, by code.
+    """
+    global _explanations
+    if _explanations is None:
+        with open(os.path.join(os.path.dirname(__file__), "explanations.toml"), "rb") as f:
+            _explanations = tomllib.load(f)
+    return _explanations
+
+def normalize(code):
+    """
+    The code for a code in any case (`e0001`) or for a kind of error (`SyntaxError`),
+    or None if it's neither.
+    """
+    from . import messages
+
+    if re.fullmatch(r"[EeWw]\d{4}", code):
+        return code.upper()
+    return messages.code(code)
+
+def explain(code):
+    """
+    The explanation of a code (see normalize()) as a dict with its code, kind, title,
+    cause, example, fix, and url, or None if there's none.
+    """
+    from . import messages
+
+    code = normalize(code)
+    if code not in explanations():
+        return None
+    kind = next((kind for kind, value in messages.CODES.items() if value == code), None)
+    return {"code": code, "kind": kind, **{field: explanations()[code][field].strip() for field in FIELDS}, "url": url(code)}
+
+def url(code):
+    """
+    The link to a code's explanation, or None for errors without a code.
+    """
+    return f"{DOCS_URL}#{code.lower()}" if code else None
+
+def print_explanation(code):
+    from . import bcolors
+
+    entry = explain(code)
+    if entry is None:
+        print(f"{bcolors.error('ERROR')} No explanation for \"{code}\". Codes look like E0001 (errors) or W0001 (warnings).")
+        return False
+    print(f"{bcolors.BOLD}{entry['code']}: {entry['title']}{bcolors.ENDC} ({entry['kind']})")
+    print()
+    print(entry["cause"])
+    print()
+    print(bcolors.BOLD + "Example" + bcolors.ENDC)
+    for line in entry["example"].splitlines():
+        print("    " + line)
+    print()
+    print(bcolors.BOLD + "Fix" + bcolors.ENDC)
+    print(entry["fix"])
+    return True
+
+def markdown():
+    """
+    Every explanation as a Markdown document, for docs/errors.md.
+    """
+    lines = ["# Error Codes", "", "Generated from `src/oneil/explanations.toml` with `oneil explain-error --markdown`. Don't edit it by hand.", ""]
+    for code in sorted(explanations(), key=lambda code: (code[0] != "E", code)):
+        entry = explain(code)
+        lines += [
+            f"## {code}",
+            "",
+            f"**{entry['title']}** (`{entry['kind']}`)",
+            "",
+            entry["cause"],
+            "",
+            "Example:",
+            "",
+            "```",
+            *entry["example"].splitlines(),
+            "```",
+            "",
+            "Fix: " + entry["fix"],
+            "",
+        ]
+    return "\n".join(lines)
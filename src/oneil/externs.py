@@ -0,0 +1,83 @@
+"""
+External inputs: parameters bound to data given at evaluation time rather than written
+in the model, for values that come from other tools or pipelines:
+
+    Launch mass: m_launch = extern("launch_mass") :kg
+
+Their values come from `--input-json <file>`, a JSON object of values by name, or from
+environment variables named `ONEIL_` and the name in upper case (`ONEIL_LAUNCH_MASS`),
+and the JSON file takes precedence. A value is a number or a `[min, max]` pair (or a
+string of one) in the parameter's units as written in the model, a string with units
+(`"2.4 Mg"`, `"2300|2500 :kg"`) that are converted to them, or one of a discrete
+parameter's options.
+
+Every parameter that's bound to an external input without a value is listed in one
+ExternError when the model is loaded, and so are values in the wrong units.
+"""
+
+import json
+import os
+import re
+
+PATTERN = re.compile(r"^\s*extern\(\s*([\"'])(\w+)\1\s*\)\s*$")
+
+NUMBER = r"[-+]?(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?"
+
+ENV_PREFIX = "ONEIL_"
+
+# The values from --input-json files, by name (the later files take precedence)
+values = {}
+
+def name(equation):
+    """
+    The name of the external input an equation (`extern("launch_mass")`) binds its
+    parameter to, or None if it isn't one.
+    """
+    match = PATTERN.match(equation) if isinstance(equation, str) else None
+    return match.group(2) if match else None
+
+def load_json(path):
+    """
+    Add the values of a JSON file of external inputs. Raises ValueError if it can't be
+    read or isn't an object.
+    """
+    try:
+        with open(path, "r") as f:
+            data = json.load(f)
+    except OSError as e:
+        raise ValueError(f"Can't read the inputs in {path}: {e.strerror}.")
+    except json.JSONDecodeError as e:
+        raise ValueError(f"The inputs in {path} aren't valid JSON: {e.msg} (line {e.lineno}).")
+    if not isinstance(data, dict):
+        raise ValueError(f"The inputs in {path} must be a JSON object of values by name.")
+    values.update(data)
+
+def lookup(name):
+    """
+    The value given for an external input, from --input-json or the environment, or None
+    if it wasn't given.
+    """
+    if name in values:
+        return values[name]
+    return os.environ.get(ENV_PREFIX + name.upper())
+
+def _number(value):
+    return isinstance(value, (int, float)) and not isinstance(value, bool)
+
+def text(value, hr_units):
+    """
+    A value as it would be written in a model (`2300|2500 :kg`), in the parameter's units
+    if it's a number. Raises ValueError for values that aren't numbers, pairs, or strings.
+    """
+    if _number(value):
+        written = str(value)
+    elif isinstance(value, list) and len(value) == 2 and all(_number(end) for end in value):
+        written = f"{value[0]}|{value[1]}"
+    elif isinstance(value, str) and re.fullmatch(rf"\s*{NUMBER}\s*(\|\s*{NUMBER}\s*)?", value):
+        # Environment variables are strings, so numbers without units are read like numbers
+        written = value.strip()
+    elif isinstance(value, str) and value.strip():
+        return value.strip()
+    else:
+        raise ValueError(f"Must be a number, a [min, max] pair, or a string, not {json.dumps(value)}.")
+    return f"{written} :{hr_units}" if hr_units else written
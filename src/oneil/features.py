@@ -0,0 +1,93 @@
+"""
+Experimental language features, which a model opts into with a `#feature(...)` pragma
+so new syntax can ship experimentally without changing how other models parse:
+
+    #feature(unicode_operators)
+
+    Width: w = 2 :m
+    Height: h = 3 :m
+    Area: A = w × h :m^2
+
+Pragmas come before anything but comments and blank lines, and enable the features in
+their file (with the fragments it includes), not in its submodels or designs. Using an
+experimental syntax without enabling it is a SyntaxError that names the feature. A
+model that uses a submodel with features the model doesn't enable itself gets a
+FeatureWarning, since the submodel's syntax can still change.
+
+The features are:
+
+    unicode_operators  ×, ·, ÷, and − (U+2212) in equations, piecewise branches, and
+                       tests, for *, *, /, and -
+"""
+
+import re
+
+FEATURES = {
+    "unicode_operators": "×, ·, ÷, and − in equations, piecewise branches, and tests.",
+}
+
+# The operators of unicode_operators and the operators they stand for. Each is one
+# character, so columns in the line don't change.
+OPERATORS = {"×": "*", "·": "*", "÷": "/", "−": "-"}
+
+PRAGMA = re.compile(r"^#feature\((.*)\)\s*$")
+
+def is_pragma(line):
+    return line.startswith("#feature")
+
+def pragma(line):
+    """
+    The features a `#feature(<name>, ...)` line enables. Raises ValueError for a
+    malformed pragma or an unknown feature.
+    """
+    match = PRAGMA.match(line)
+    if not match:
+        raise ValueError("Feature pragmas must be of the form \"#feature(<name>, ...)\".")
+    names = [name.strip() for name in match.group(1).split(",")]
+    for name in names:
+        if name not in FEATURES:
+            raise ValueError(f"Unknown feature \"{name}\". Features are: {', '.join(FEATURES)}.")
+    return names
+
+def enabled(text):
+    """
+    The features enabled by the pragmas of a model's text, ignoring invalid ones.
+    """
+    found = set()
+    for line in text.splitlines():
+        if is_pragma(line):
+            try:
+                found.update(pragma(line))
+            except ValueError:
+                pass
+    return found
+
+def expression_start(line):
+    """
+    Where the expressions of a declaration start: after the test's name, after the "="
+    of a parameter or design value, or at the start of a piecewise branch.
+    """
+    from . import test_colon
+
+    stripped = line.lstrip(" \t*")
+    if stripped.startswith("{"):
+        return 0
+    if stripped.startswith("test"):
+        try:
+            return test_colon(line) + 1
+        except ValueError:
+            return len(line)
+    return line.find("=") + 1 if "=" in line else len(line)
+
+def operators(line, features):
+    """
+    The line with the experimental operators in its expressions replaced by the ones
+    they stand for. Raises ValueError if it has one and unicode_operators isn't enabled.
+    """
+    start = expression_start(line)
+    found = [char for char in line[start:] if char in OPERATORS]
+    if not found:
+        return line
+    if "unicode_operators" not in features:
+        raise ValueError(f"The operator \"{found[0]}\" is experimental. Enable it with \"#feature(unicode_operators)\" at the top of the model.")
+    return line[:start] + "".join(OPERATORS.get(char, char) for char in line[start:])
@@ -0,0 +1,107 @@
+"""
+Fixture models (`oneil test --manifest`), for regression suites of model libraries.
+
+The .on files in the `tests/` directory beside a manifest are fixtures: each uses the
+models under test (`use craft as c`, found beside the manifest, not in `tests/`), sets
+their inputs with design values (`m.c = 12 :kg`), and checks their outputs with tests.
+Golden values are checked with `==` in a test with a relative `@tolerance`:
+
+    Expected acceleration: a_gold = 0.8333 :m/s^2
+
+    @tolerance(0.1%)
+    test "golden acceleration": a.c == a_gold
+
+`oneil test --manifest` runs the tests of the models the manifest lists and of its
+fixtures. `oneil eval` skips the fixtures of the directories it searches.
+"""
+
+import os
+
+from beautifultable import BeautifulTable
+
+from . import bcolors
+from . import console
+from .stats import working_directory, workspace_files
+
+DIRECTORY = "tests"
+
+def discover(manifest):
+    """
+    The fixtures of a manifest: the .on files in the tests directory beside it.
+    """
+    directory = os.path.join(os.path.dirname(manifest), DIRECTORY)
+    return workspace_files([directory]) if os.path.isdir(directory) else []
+
+def models(paths):
+    """
+    The .on files in the given files and directories (see stats.workspace_files()),
+    without the fixtures in the tests directories of the directories.
+    """
+    files = []
+    for path in paths or ["."]:
+        for file in workspace_files([path]):
+            if os.path.isdir(path) and DIRECTORY in os.path.relpath(file, path).split(os.sep)[:-1]:
+                continue
+            files.append(file)
+    return files
+
+def load(file, root):
+    """
+    Load a fixture from the root directory (where the models it uses are), apply its
+    design values to them, and evaluate it, which runs its tests.
+    """
+    from . import Model, parse_file
+
+    with working_directory(root):
+        name = os.path.relpath(file, root)
+        model = Model(name)
+        design = parse_file(name)[4]
+        if design:
+            model.apply_design(design, name)
+        model.build(quiet=True)
+    return model
+
+def run(model=None, designs=(), manifests=()):
+    """
+    Run and print the tests of a model (with the designs), of the models the manifests
+    list, and of the manifests' fixtures, then print a summary of each file. Returns a
+    list of (file, passed, total), with None counts for files that couldn't be
+    evaluated.
+    """
+    from . import OneilError, loader, read_manifest
+
+    # Each file with the directory to load it from and its designs (None for fixtures)
+    suite = [(model, None, designs)] if model else []
+    for manifest in manifests:
+        suite += [(file, os.path.dirname(file) or ".", ()) for file in read_manifest(manifest)]
+        suite += [(fixture, os.path.dirname(manifest) or ".", None) for fixture in discover(manifest)]
+
+    results = []
+    for file, root, file_designs in suite:
+        try:
+            if file_designs is None:
+                evaluated = load(file, root)
+            else:
+                with working_directory(root or "."):
+                    evaluated = loader(os.path.relpath(file, root) if root else file, list(file_designs), capture_errors=False, quiet=True)
+            failed, total = evaluated.test()
+        except OneilError as err:
+            console.print_error(err)
+            results.append((file, None, None))
+            continue
+        if failed:
+            console.record_exit(console.EXIT_TEST_FAILURE)
+        results.append((file, total - failed, total))
+
+    table = BeautifulTable()
+    table.columns.header = ["File", "Tests", "Result"]
+    for file, passed, total in results:
+        if total is None:
+            table.append_row([file, "-", bcolors.FAIL + "ERROR" + bcolors.ENDC])
+        else:
+            table.append_row([file, f"{passed}/{total}", bcolors.OKGREEN + "PASS" + bcolors.ENDC if passed == total else bcolors.FAIL + "FAIL" + bcolors.ENDC])
+    if len(table.rows):
+        print(table)
+    else:
+        print("No models or fixtures to test.")
+    return results
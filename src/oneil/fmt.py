@@ -0,0 +1,262 @@
+"""
+Formatting model files the same way (`oneil fmt`).
+
+Declarations are rewritten from their tokens (see oneil.tokens) with:
+
+* one space around `+`, `-`, `--`, comparisons, `?`, and `if`, `and`, `or`, and `not`,
+  and none around `*`, `/`, `//`, `%`, `**`, and `^`, after a unary minus, or inside
+  brackets, and a space after commas
+* `Label (limits) [options]: ID = equation :units`, with the `=` of consecutive
+  parameter and design lines aligned, and piecewise branches aligned under the first
+* at most one blank line in a row, no trailing whitespace, and a blank line before each
+  `section` (unless it follows a comment or annotation, which may apply to it)
+
+Comments, annotations, notes, the model's header, and use, from, import, and include
+lines are kept as written. A line is only rewritten if it has the same tokens
+afterwards, so lines the parser wouldn't accept are kept too.
+"""
+
+import difflib
+
+from . import bcolors
+from . import console
+from .refactor import kind
+from .stats import workspace_files
+from .tokens import _expression, _line
+
+# Operators written with a space on each side. The others are written without spaces.
+SPACED_OPERATORS = ["+", "-", "--", "==", "!=", "<", ">", "<=", ">="]
+
+OPENING = ["(", "[", "{"]
+CLOSING = [")", "]", "}"]
+
+class _Written:
+    """
+    A token as written, and whether it's a unary `+` or `-`.
+    """
+    def __init__(self, token, unary):
+        self.kind = token.kind
+        self.text = token.text
+        self.unary = unary
+
+def _unary(previous, token):
+    if token.kind != "operator" or token.text not in ("+", "-"):
+        return False
+    return previous is None or previous.kind in ("operator", "keyword") or previous.text in OPENING + [",", "|", "?", "="]
+
+def _space(previous, token, unary):
+    """
+    The space to write between two tokens of an expression.
+    """
+    if token.text in CLOSING + [",", "|", "."] or previous.text in OPENING + ["|", "."]:
+        return ""
+    if previous.text == ",":
+        return " "
+    if token.text == "(" and previous.kind == "function":
+        return ""
+    if previous.unary:
+        return ""
+    if unary:
+        return " " if previous.kind == "keyword" or previous.text in SPACED_OPERATORS + ["?"] else ""
+    if token.kind == "operator":
+        return " " if token.text in SPACED_OPERATORS else ""
+    if previous.kind == "operator":
+        return " " if previous.text in SPACED_OPERATORS else ""
+    return " "
+
+def expression(tokens):
+    """
+    Write the tokens of an expression with canonical spacing.
+    """
+    text = ""
+    previous = None
+    for token in tokens:
+        unary = _unary(previous, token)
+        space = _space(previous, token, unary) if previous is not None else ""
+        # Two minus signs next to each other would be read as the `--` operator.
+        if not space and text.endswith("-") and token.text.startswith("-"):
+            space = " "
+        text += space + token.text
+        previous = _Written(token, unary)
+    return text
+
+def _split(tokens, text):
+    """
+    The tokens before the first punctuation token with the text, and after it.
+    """
+    for i, token in enumerate(tokens):
+        if token.kind == "punctuation" and token.text == text:
+            return tokens[:i], tokens[i + 1:]
+    return tokens, None
+
+def _equation(tokens):
+    """
+    `= equation :units` (or `=> ID`), from the tokens after the ID.
+    """
+    assign = tokens[0].text
+    body, units = _split(tokens[1:], ":")
+    text = f"{assign} {expression(body)}"
+    if units is not None:
+        text += " :" + "".join(token.text for token in units)
+    return text
+
+def _prefix(tokens):
+    prefix = []
+    while tokens and tokens[0].kind == "operator" and tokens[0].text in ("$", "*", "**"):
+        prefix.append(tokens[0].text)
+        tokens = tokens[1:]
+    return "".join(operator + " " for operator in prefix), tokens
+
+def _parameter(tokens):
+    """
+    The part of a parameter line before the `=`, and the rest.
+    """
+    prefix, tokens = _prefix(tokens)
+    preamble, rest = _split(tokens, ":")
+    label = preamble[0].text if preamble and preamble[0].kind == "label" else ""
+    bounds = expression(preamble[1:] if label else preamble)
+    head = prefix + label + (" " + bounds if bounds else "") + ": " + rest[0].text
+    return head, _equation(rest[1:])
+
+def _design(tokens):
+    prefix, tokens = _prefix(tokens)
+    target, rest = [], tokens
+    while rest and (rest[0].kind in ("definition", "submodel") or rest[0].text == "."):
+        target.append(rest[0].text)
+        rest = rest[1:]
+    return prefix + "".join(target), _equation(rest)
+
+def _alias(tokens):
+    head, rest = _split(tokens, ":=")
+    label, ID = _split(head, ":")
+    text = f"{label[0].text}: {ID[0].text}" if ID is not None else head[0].text
+    return f"{text} := {expression(rest)}"
+
+def _test(tokens):
+    prefix, tokens = _prefix(tokens)
+    inputs, rest = _split(tokens[1:], ":")
+    inputs = expression(inputs)
+    return f"{prefix}test{' ' + inputs if inputs else ''}: {expression(rest)}"
+
+def _same(line, formatted, statement):
+    def found(text):
+        try:
+            return [(token.kind, token.text) for token in _line(text, 1, statement)]
+        except ValueError:
+            return None
+    return found(line) is not None and found(line) == found(formatted)
+
+def _declaration(line):
+    """
+    Format one top-level line. Returns the text before the `=` and from the `=` on for
+    lines to align, or the whole line as the first part and None.
+    """
+    line_kind = kind(line)
+    tokens = list(_line(line, 1, line_kind)) if line_kind in ("parameter", "design", "alias", "test") else []
+    if not tokens or any(token.kind == "error" for token in tokens):
+        return line, None
+    try:
+        if line_kind == "parameter":
+            head, rest = _parameter(tokens)
+        elif line_kind == "design":
+            head, rest = _design(tokens)
+        elif line_kind == "alias":
+            head, rest = _alias(tokens), None
+        else:
+            head, rest = _test(tokens), None
+    except (IndexError, TypeError):
+        return line, None
+    if not _same(line, head + (" " + rest if rest is not None else ""), line_kind):
+        return line, None
+    return head, rest
+
+def _blocks(lines):
+    """
+    Group lines into blocks of a top-level line (or the model's header) and its indented
+    lines, each with whether a blank line came before it. Runs of blank lines become
+    one, and blank lines within a block (between paragraphs of notes) are kept.
+    """
+    blocks = []
+    blank = False
+    for line in lines:
+        line = line.rstrip()
+        if not line:
+            blank = bool(blocks)
+        elif line[0] in (" ", "\t") and blocks:
+            if blank:
+                blocks[-1][1].append("")
+            blocks[-1][1].append(line)
+            blank = False
+        else:
+            blocks.append((blank, [line]))
+            blank = False
+    return blocks
+
+def format_text(text):
+    """
+    Format a model file's text.
+    """
+    blocks = _blocks(text.splitlines())
+    formatted = [_declaration(lines[0]) for _, lines in blocks]
+
+    # Consecutive lines to align, broken by blank lines and lines that aren't aligned
+    runs = [[]]
+    for i, (blank, _) in enumerate(blocks):
+        if blank or formatted[i][1] is None:
+            runs.append([])
+        if formatted[i][1] is not None:
+            runs[-1].append(i)
+    widths = {i: max(len(formatted[j][0]) for j in run) for run in runs for i in run}
+
+    out = []
+    previous = None
+    for i, (blank, lines) in enumerate(blocks):
+        head, rest = formatted[i]
+        first = head if rest is None else head.ljust(widths[i]) + " " + rest
+        line_kind = kind(lines[0])
+        section = line_kind == "section" and previous not in (None, "comment", "annotation")
+        if out and (blank or section):
+            out.append("")
+        out.append(first)
+        for line in lines[1:]:
+            if line.strip().startswith("{") and line_kind == "parameter" and rest is not None:
+                branch = expression(list(_expression(line, 1, 0)))
+                branch_line = " " * (widths[i] + 3) + branch
+                out.append(branch_line if _same(line, branch_line, "parameter") else line)
+            else:
+                out.append(line)
+        previous = line_kind
+    return "\n".join(out) + "\n" if out else ""
+
+def run(paths, check=False):
+    """
+    Format each model file in place, or with `check`, print the files that aren't
+    formatted with a diff and fail. Returns the files that were (or would be) changed.
+    """
+    changed = []
+    for file in workspace_files(paths):
+        with open(file, "r", encoding="utf-8") as f:
+            text = f.read()
+        formatted = format_text(text)
+        if formatted == text:
+            continue
+        changed.append(file)
+        if check:
+            diff = difflib.unified_diff(text.splitlines(keepends=True), formatted.splitlines(keepends=True), file, file)
+            print("".join(diff), end="")
+        else:
+            with open(file, "w", encoding="utf-8") as f:
+                f.write(formatted)
+
+    if check and changed:
+        console.record_exit(console.EXIT_USAGE)
+    if not console.quiet:
+        from . import messages
+
+        if check and changed:
+            print(bcolors.YELLOW + messages.text("fmt-unformatted", "{count} file(s) aren't formatted.", count=len(changed)) + bcolors.ENDC)
+        elif changed:
+            print(bcolors.OKGREEN + messages.text("fmt-done", "Formatted {count} file(s).", count=len(changed)) + bcolors.ENDC)
+        else:
+            print(bcolors.OKGREEN + messages.text("fmt-clean", "All files are formatted.") + bcolors.ENDC)
+    return changed
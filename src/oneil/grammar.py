@@ -0,0 +1,270 @@
+"""
+A tree-sitter grammar for Oneil, generated from the parser's definitions (`oneil grammar`)
+so that editor highlighting and folding (Neovim, Helix, GitHub) don't drift from the
+language.
+
+The line kinds and their keywords come from oneil.refactor (which mirrors
+`parse_file`), and the operators, builtin functions, and constants from the parser (only
+Oneil's own, so the grammar doesn't depend on plugins or the config file).
+Node names follow the token kinds of oneil.tokens, and highlights.scm captures them the
+way `oneil tokens` classifies them. The generated files are checked in under
+tree-sitter/, and test/test_grammar.py fails when they're out of date.
+"""
+
+import json
+import os
+
+from .refactor import DECLARATIONS
+
+HEADER = "// Generated by `oneil grammar` from the Oneil parser. Don't edit it by hand: change the\n// parser and run `oneil grammar tree-sitter` to regenerate it.\n"
+
+# Binary operators from loosest to tightest, as Python evaluates the equations.
+# `--` is standard subtraction (`|minus|`) and `^` is `**`.
+BINARY = [
+    ("or", "left", ["or"]),
+    ("and", "left", ["and"]),
+    ("comparison", "left", ["<", ">", "<=", ">=", "==", "!="]),
+    ("minus", "left", ["--"]),
+    ("additive", "left", ["+", "-"]),
+    ("multiplicative", "left", ["*", "/", "//", "%"]),
+    ("power", "right", ["**", "^"]),
+]
+# Prefix operators bind just tighter than the binary level they follow.
+UNARY = {"not": "and", "-": "multiplicative"}
+
+def _js(value):
+    return json.dumps(value)
+
+def _choice(strings):
+    return "choice(" + ", ".join(_js(s) for s in strings) + ")"
+
+def _keyword(word, space=True):
+    # Declarations are only recognized with a space after the keyword, as in the parser
+    # (`use ` but not `user`), while tests only need `test`.
+    pattern = f"seq({_js(word)}, /[ \\t]/)" if space else _js(word)
+    return f"token(prec(1, {pattern}))"
+
+def grammar_js():
+    from . import BUILTIN_FUNCTIONS, BUILTIN_CONSTANTS, OPERATOR_OVERRIDES, EQUATION_OPERATORS, BOOLEAN_OPERATORS
+
+    operators = {op for _, _, ops in BINARY for op in ops} | set(UNARY)
+    # Every operator the parser knows must have a precedence here.
+    known = set(BOOLEAN_OPERATORS) | set(OPERATOR_OVERRIDES) | {op for op in EQUATION_OPERATORS if op not in "()=!"}
+    missing = known - operators
+    if missing:
+        raise ValueError(f"No precedence for operators: {', '.join(sorted(missing))}")
+
+    # Levels are spaced by two to leave room for the prefix operators between them.
+    levels = {name: 2 * (i + 1) for i, (name, _, _) in enumerate(BINARY)}
+    binary = []
+    for name, associativity, ops in BINARY:
+        binary.append(f"      ...[{', '.join(_js(op) for op in ops)}].map(op => prec.{associativity}({levels[name]}, seq(field('left', $._expression), field('operator', op), field('right', $._expression)))),")
+    unary = [f"      prec({levels[level] + 1}, seq(field('operator', {_js(op)}), field('operand', $._expression))),"
+             for op, level in UNARY.items()]
+
+    keywords = {word: _keyword(word) for word in DECLARATIONS}
+    rules = f"""{HEADER}
+module.exports = grammar({{
+  name: 'oneil',
+
+  extras: $ => [/[ \\t]/],
+
+  word: $ => $.identifier,
+
+  conflicts: $ => [[$.parameter]],
+
+  rules: {{
+    source_file: $ => seq(repeat($._item), optional($._statement)),
+
+    _item: $ => choice(seq($._statement, $._newline), $.notes, $._newline),
+
+    _newline: $ => /\\r?\\n/,
+
+    _statement: $ => choice(
+      $.comment,
+      $.attribute,
+      $.annotation,
+      $.use_declaration,
+      $.from_declaration,
+      $.import_declaration,
+      $.include_declaration,
+      $.section,
+      $.unit_declaration,
+      $.test,
+      $.alias,
+      $.design,
+      $.parameter,
+    ),
+
+    // Indented lines that aren't piecewise branches
+    notes: $ => prec.right(repeat1(seq($.note, $._newline))),
+    note: $ => token(seq(/[ \\t]+/, /[^\\s{{#][^\\n]*/)),
+
+    comment: $ => token(prec(1, seq('#', /[^\\n]*/))),
+    attribute: $ => token(prec(2, seq('#[cfg', /[^\\n]*/))),
+
+    annotation: $ => seq(field('name', alias(token(prec(1, /@\\w*/)), $.annotation_name)), optional(field('arguments', alias(/[^\\s][^\\n]*/, $.text)))),
+
+    use_declaration: $ => seq(alias({keywords["use"]}, 'use'), field('model', $.module), optional($.submodel_inputs), 'as', field('symbol', $.submodel)),
+    from_declaration: $ => seq(alias({keywords["from"]}, 'from'), field('source', $.module), alias({keywords["use"]}, 'use'), field('model', $.module), optional($.submodel_inputs), 'as', field('symbol', $.submodel)),
+    import_declaration: $ => seq(alias({keywords["import"]}, 'import'), field('module', $.module)),
+    include_declaration: $ => seq(alias({keywords["include"]}, 'include'), field('path', $.string)),
+    section: $ => seq(alias({keywords["section"]}, 'section'), field('name', alias(/[^\\s][^\\n]*/, $.label))),
+    unit_declaration: $ => seq(alias({keywords["unit"]}, 'unit'), field('symbol', alias($.identifier, $.definition)), '=', field('value', $.number), optional(field('units', $.units))),
+
+    module: $ => /\\w+(\\.\\w+)*/,
+    submodel: $ => /\\w+/,
+    submodel_inputs: $ => seq('(', sep1(seq(field('input', $.identifier), '=', field('value', $._expression)), ','), ')'),
+
+    test: $ => seq(
+      optional($.trace),
+      alias({_keyword("test", space=False)}, 'test'),
+      optional(field('name', $.string)),
+      optional(seq('{{', sep1(field('input', $.identifier), ','), '}}')),
+      ':',
+      field('expression', $._expression),
+    ),
+
+    alias: $ => seq(
+      optional(seq(field('label', $.label), ':')),
+      field('id', alias($.label, $.definition)),
+      ':=',
+      field('target', $._expression),
+    ),
+
+    design: $ => seq(
+      optional($.trace),
+      field('id', alias($.label, $.definition)),
+      choice('=', '=>'),
+      field('value', $._value),
+      optional($._units),
+    ),
+
+    parameter: $ => seq(
+      optional($.performance),
+      optional($.trace),
+      field('label', $.label),
+      optional(choice($.limits, $.options)),
+      ':',
+      field('id', $.definition),
+      choice('=', '=>'),
+      field('value', $._value),
+      optional(seq('?', field('fallback', $._value))),
+      optional($._units),
+      repeat(seq($._newline, $.piecewise_branch)),
+    ),
+
+    performance: $ => '$',
+    trace: $ => choice('*', '**'),
+    label: $ => /[^\\s#@:=$*(\\[{{][^:=\\n(\\[]*/,
+    definition: $ => $.identifier,
+    limits: $ => seq('(', field('min', $._expression), ',', field('max', $._expression), ')'),
+    options: $ => seq('[', sep1($.identifier, ','), ']'),
+
+    _units: $ => seq(':', field('units', $.units)),
+    units: $ => /[^\\s][^\\n]*/,
+
+    _value: $ => choice($.interval, $.interval_literal, $.piecewise_branch, $._expression),
+    interval: $ => seq(field('min', $._expression), '|', field('max', $._expression)),
+    interval_literal: $ => seq('[', field('min', $._expression), ',', field('max', $._expression), ']'),
+    piecewise_branch: $ => seq('{{', field('value', $._expression), 'if', field('condition', $._expression)),
+
+    _expression: $ => choice(
+      $.number,
+      $.string,
+      $.constant,
+      $.identifier,
+      $.submodel_reference,
+      $.call,
+      $.parenthesized,
+      $.unary,
+      $.binary,
+    ),
+
+    parenthesized: $ => seq('(', $._expression, ')'),
+    call: $ => prec({2 * len(BINARY) + 2}, seq(field('function', alias($.identifier, $.function)), '(', optional(sep1($._expression, ',')), ')')),
+    submodel_reference: $ => prec({2 * len(BINARY) + 2}, seq(field('id', $.identifier), '.', field('symbol', alias($.identifier, $.submodel)))),
+
+    unary: $ => choice(
+{chr(10).join(unary)}
+    ),
+
+    binary: $ => choice(
+{chr(10).join(binary)}
+    ),
+
+    // Builtin functions: {", ".join(sorted(BUILTIN_FUNCTIONS))}
+    constant: $ => {_choice(sorted(BUILTIN_CONSTANTS))},
+    number: $ => /(\\d+\\.?\\d*|\\.\\d+)([eE][-+]?\\d+)?/,
+    string: $ => choice(/'[^'\\n]*'/, /"[^"\\n]*"/),
+    identifier: $ => /[A-Za-z_]\\w*/,
+  }},
+}});
+
+function sep1(rule, separator) {{
+  return seq(rule, repeat(seq(separator, rule)));
+}}
+"""
+    return rules
+
+def highlights_scm():
+    from . import BUILTIN_FUNCTIONS
+
+    keywords = DECLARATIONS + ["test", "as", "if", "and", "or", "not"]
+    operators = [op for _, _, ops in BINARY for op in ops if op.isascii() and not op.isalpha()]
+    return f"""; {HEADER[3:].replace(chr(10) + '// ', chr(10) + '; ')}
+(comment) @comment
+(attribute) @attribute
+(annotation_name) @attribute
+(text) @string.special
+(note) @comment.documentation
+
+[{" ".join(_js(word) for word in keywords)}] @keyword
+
+(label) @string
+(definition (identifier) @variable)
+(identifier) @variable
+(submodel) @module
+(module) @module
+((function (identifier) @function.builtin)
+ (#any-of? @function.builtin {" ".join(_js(name) for name in sorted(BUILTIN_FUNCTIONS))}))
+(function (identifier) @function)
+(constant) @constant.builtin
+(number) @number
+(string) @string
+(units) @type
+
+[{" ".join(_js(op) for op in operators)}] @operator
+(performance) @operator
+(trace) @operator
+[":" "=" ":=" "=>" "(" ")" "[" "]" "{{" "}}" "," "|" "?" "."] @punctuation.delimiter
+"""
+
+def folds_scm():
+    return f"""; {HEADER[3:].replace(chr(10) + '// ', chr(10) + '; ')}
+(notes) @fold
+(parameter) @fold
+"""
+
+FILES = {
+    "grammar.js": grammar_js,
+    os.path.join("queries", "highlights.scm"): highlights_scm,
+    os.path.join("queries", "folds.scm"): folds_scm,
+}
+
+def generate():
+    """
+    The generated files, by path relative to the grammar's directory.
+    """
+    return {path: generator() for path, generator in FILES.items()}
+
+def run(directory):
+    """
+    Write the grammar and its queries to a directory.
+    """
+    for path, text in generate().items():
+        target = os.path.join(directory, path)
+        os.makedirs(os.path.dirname(target), exist_ok=True)
+        with open(target, "w") as f:
+            f.write(text)
+        print(f"Wrote {target}")
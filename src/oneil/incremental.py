@@ -0,0 +1,116 @@
+"""
+Incremental reparsing of a model file as it's edited, for editors that send changes
+rather than the whole file (`oneil/didChange` in oneil.tune).
+
+A Document keeps the file's declarations: each top-level line with the indented lines
+(notes and piecewise branches) and blank lines after it, and the lines before the first
+one (the model's header). Each declaration is kept with its tokens (see oneil.tokens).
+An edit re-tokenizes only the declarations it touches, plus the one before it, since
+indenting a line moves it into that declaration. The declarations after the edit are
+kept, with their line numbers shifted. The tokens are always the same as
+tokenize(document.text).
+
+Changes are LSP's `TextDocumentContentChangeEvent`s: {"range": {"start": {"line": 0,
+"character": 4}, "end": {...}}, "text": "..."} with 0-based lines and columns in the
+document's position encoding, or {"text": "..."} to replace the whole file.
+"""
+
+import collections
+
+from . import positions
+from .tokens import tokenize
+
+Declaration = collections.namedtuple("Declaration", ["start", "end", "tokens"])
+
+def _top_level(line):
+    return bool(line.strip()) and line[0] not in (" ", "\t")
+
+def _declarations(lines, start, end):
+    """
+    Split lines[start:end] (0-based) into declarations, with their tokens. `start` has to
+    be the first line of a declaration and `end` the last line of one, plus one.
+    """
+    found = []
+    first = start
+    for i in range(start + 1, end + 1):
+        if i == end or _top_level(lines[i]):
+            text = "\n".join(lines[first:i])
+            # Tokens are numbered from the declaration's first line.
+            found.append(Declaration(first, i, [token._replace(line=token.line + first) for token in tokenize(text)]))
+            first = i
+    return found
+
+def _shift(declaration, delta):
+    return Declaration(declaration.start + delta, declaration.end + delta, [token._replace(line=token.line + delta) for token in declaration.tokens])
+
+class Document:
+    def __init__(self, text, encoding="utf-32"):
+        self.text = text
+        self.encoding = encoding
+        self.lines = text.splitlines()
+        self.declarations = _declarations(self.lines, 0, len(self.lines)) if self.lines else []
+        # The declarations re-tokenized by the last change
+        self.changed = list(self.declarations)
+
+    def tokens(self):
+        return [token for declaration in self.declarations for token in declaration.tokens]
+
+    def _index(self, line):
+        """
+        The index of the declaration containing a 0-based line (the last one past the end).
+        """
+        for i, declaration in enumerate(self.declarations):
+            if declaration.start <= line < declaration.end:
+                return i
+        return len(self.declarations) - 1
+
+    def _edit(self, change):
+        """
+        Apply one change to the text. Returns the 0-based lines it replaced in the old text
+        (first and last, inclusive) and how many lines it added or removed.
+        """
+        if "range" not in change:
+            old_lines = len(self.lines)
+            self.text = change["text"]
+            return 0, max(old_lines - 1, 0), len(self.text.splitlines()) - old_lines
+
+        start, end = change["range"]["start"], change["range"]["end"]
+        begin = positions.offset(self.text, start["line"] + 1, start["character"], self.encoding)
+        finish = positions.offset(self.text, end["line"] + 1, end["character"], self.encoding)
+        removed = self.text[begin:finish]
+        self.text = self.text[:begin] + change["text"] + self.text[finish:]
+        delta = change["text"].count("\n") - removed.count("\n")
+        return start["line"], end["line"], delta
+
+    def change(self, changes):
+        """
+        Apply changes in order, re-tokenizing the declarations they touch. Returns the
+        declarations that were re-tokenized.
+        """
+        fresh = set()
+        for change in changes:
+            first, last, delta = self._edit(change)
+            self.lines = self.text.splitlines()
+            if not self.declarations or "range" not in change:
+                self.declarations = _declarations(self.lines, 0, len(self.lines)) if self.lines else []
+                fresh = {id(declaration) for declaration in self.declarations}
+                continue
+
+            # From the declaration before the edit (the edited line may now be indented)
+            # to the end of the declaration the edit ends in
+            before = self._index(max(first - 1, 0))
+            after = self._index(last)
+            start = self.declarations[before].start
+            end = len(self.lines) if after == len(self.declarations) - 1 else self.declarations[after].end + delta
+            region = _declarations(self.lines, start, end) if start < end else []
+
+            kept = []
+            for declaration in self.declarations[after + 1:]:
+                shifted = _shift(declaration, delta) if delta else declaration
+                if id(declaration) in fresh:
+                    fresh.add(id(shifted))
+                kept.append(shifted)
+            self.declarations = self.declarations[:before] + region + kept
+            fresh.update(id(declaration) for declaration in region)
+        self.changed = [declaration for declaration in self.declarations if id(declaration) in fresh]
+        return self.changed
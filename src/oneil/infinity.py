@@ -0,0 +1,86 @@
+"""
+What to do when a calculation produces an infinite bound from finite inputs, e.g.
+dividing by a range that reaches zero (`1/(0|2)`) or overflowing a float. Limits with
+an infinite maximum and tests like `x > 0` still pass against ±inf, so the result can be
+meaningless without anything saying so.
+
+The policy comes from the `[infinity]` section of the config file and the `--infinity`
+flag:
+
+    [infinity]
+    policy = "warn"      # "allow" (the default), "warn", "fail", or "saturate"
+    bound = 1e30         # where "saturate" clamps infinite bounds, in base units
+
+    [infinity.bounds]    # bounds for values with particular units, in those units
+    "m/s" = 299792458
+
+With "warn" and "saturate", each infinite value is reported with an InfinityWarning at
+the parameter that produced it. With "fail", it's a ParameterError. Parameters that are
+infinite because an input is (`1|inf`, or `inf` in their equation) are left alone.
+"""
+
+import math
+
+from . import units as un
+
+POLICIES = ["allow", "warn", "fail", "saturate"]
+
+DEFAULT_SETTINGS = {"policy": "allow", "bound": 1e30}
+
+# The current settings, and those set by flags, which take precedence over the config
+settings = dict(DEFAULT_SETTINGS)
+overrides = {}
+
+# Saturation bounds for particular units, as (base units, bound in base units)
+unit_bounds = []
+
+def is_infinite(parameter):
+    return any(isinstance(value, float) and math.isinf(value) for value in (parameter.min, parameter.max))
+
+def bound(units):
+    """
+    The magnitude infinite bounds of a value with the given (base) units saturate at.
+    """
+    return next((value for base, value in unit_bounds if base == units), settings["bound"])
+
+def saturate(value, units):
+    if not (isinstance(value, float) and math.isinf(value)):
+        return value
+    return math.copysign(bound(units), value)
+
+def reset():
+    settings.clear()
+    settings.update(DEFAULT_SETTINGS | overrides)
+    unit_bounds.clear()
+
+def _positive(value):
+    return isinstance(value, (int, float)) and not isinstance(value, bool) and 0 < value < math.inf
+
+def apply(config, errors):
+    """
+    Apply infinity settings (from the config file), adding a message to errors for each
+    invalid one.
+    """
+    for key, value in config.items():
+        if key == "bounds":
+            if not isinstance(value, dict):
+                errors.append("infinity.bounds must be a table of units and bounds.")
+                continue
+            for unit, magnitude in value.items():
+                try:
+                    units, unit_fx = un.parse(unit)
+                except (ValueError, KeyError, IndexError):
+                    errors.append(f"infinity.bounds: unknown unit \"{unit}\".")
+                    continue
+                if not _positive(magnitude):
+                    errors.append(f"infinity.bounds.\"{unit}\" must be a positive number.")
+                    continue
+                unit_bounds.append((units, unit_fx(magnitude)))
+        elif key not in DEFAULT_SETTINGS:
+            errors.append(f"Unknown infinity setting \"{key}\". Settings are: {', '.join(DEFAULT_SETTINGS)}, bounds.")
+        elif key == "policy" and value not in POLICIES:
+            errors.append(f"infinity.policy must be one of: {', '.join(POLICIES)}.")
+        elif key == "bound" and not _positive(value):
+            errors.append("infinity.bound must be a positive number.")
+        elif key not in overrides:
+            settings[key] = value
@@ -0,0 +1,90 @@
+"""
+Limits on evaluation, so a pathological model (a runaway Python function or a very
+deep chain of dependencies) fails with an EvaluationLimitError instead of hanging a
+CI job or crashing.
+
+The limits come from the `[limits]` section of the config file and the `--max-time`
+and `--max-depth` flags:
+
+    [limits]
+    time = 60      # seconds for evaluating a model (including its submodels and tests)
+    depth = 250    # parameters in a chain of dependencies
+"""
+
+import contextlib
+import signal
+import threading
+import time
+
+DEFAULT_LIMITS = {"time": None, "depth": 250}
+
+# The current limits, and those set by flags, which take precedence over the config
+limits = dict(DEFAULT_LIMITS)
+overrides = {}
+
+# When the current evaluation has to finish, or None without a time limit
+_deadline = None
+
+def _timed_out(parameter=None):
+    from . import EvaluationLimitError
+
+    return EvaluationLimitError("time", limits["time"], parameter)
+
+def _alarm(signum, frame):
+    raise _timed_out()
+
+@contextlib.contextmanager
+def evaluation():
+    """
+    Apply the time limit to the evaluation in the block. Nested evaluations (e.g. of
+    submodels) share the outermost deadline. Python functions that don't return are
+    interrupted with an alarm where signals are available (the main thread on Unix).
+    """
+    global _deadline
+    if _deadline is not None or not limits["time"]:
+        yield
+        return
+
+    _deadline = time.monotonic() + limits["time"]
+    alarm = hasattr(signal, "setitimer") and threading.current_thread() is threading.main_thread()
+    if alarm:
+        previous = signal.signal(signal.SIGALRM, _alarm)
+        signal.setitimer(signal.ITIMER_REAL, limits["time"])
+    try:
+        yield
+    finally:
+        _deadline = None
+        if alarm:
+            signal.setitimer(signal.ITIMER_REAL, 0)
+            signal.signal(signal.SIGALRM, previous)
+
+def check(parameter, trail):
+    """
+    Raise an EvaluationLimitError if the evaluation ran out of time or the chain of
+    dependencies leading to the parameter is too deep.
+    """
+    from . import EvaluationLimitError
+
+    if _deadline is not None and time.monotonic() > _deadline:
+        raise _timed_out(parameter)
+    if limits["depth"] and len(trail) > limits["depth"]:
+        raise EvaluationLimitError("depth", limits["depth"], parameter).with_note("Dependency chain: " + " => ".join(trail[:3] + ["..."] + trail[-3:]))
+
+def reset():
+    limits.clear()
+    limits.update(DEFAULT_LIMITS | overrides)
+
+def apply(settings, errors):
+    """
+    Apply limit settings (from the config file or flags), adding a message to errors
+    for each invalid one.
+    """
+    for key, value in settings.items():
+        if key not in DEFAULT_LIMITS:
+            errors.append(f"Unknown limit \"{key}\". Limits are: {', '.join(DEFAULT_LIMITS)}.")
+        elif key == "time" and not (isinstance(value, (int, float)) and not isinstance(value, bool) and value > 0):
+            errors.append("limits.time must be a positive number of seconds.")
+        elif key == "depth" and not (isinstance(value, int) and not isinstance(value, bool) and value > 0):
+            errors.append("limits.depth must be a positive integer.")
+        elif key not in overrides:
+            limits[key] = value
@@ -0,0 +1,356 @@
+"""
+Lints for the models in a workspace (`oneil lint`).
+
+Lints flag things that load and evaluate correctly but should be cleaned up. They
+are reported as warnings and don't change the exit code unless the diagnostics policy
+denies them (see oneil.diagnostics); models that fail to load are reported as errors.
+"""
+
+import os
+import re
+
+from . import bcolors
+from . import console
+from . import diagnostics
+from . import messages
+from .stats import working_directory, workspace_files
+
+# The lints and what they flag.
+LINTS = {
+    "alias": "Aliases (`ID := target`) left over from renames, which should eventually be removed.",
+    "duplicate-label": "Parameters in one model with the same label, which are confusing in reports.",
+    "unit-name": "Units written with their names (`seconds`, `newton-meters`) rather than their symbols (`s`, `N*m`).",
+    "magnitude": "Values so large or small that they're probably missing a unit prefix (`2400000000 :Hz` for `2.4 :GHz`).",
+    "spelling": "Likely typos in labels, notes, and test names, which end up in reports (only with --spelling).",
+    "unit-unchecked": "Parameters marked `@unit_unchecked`, with the reason their units aren't checked, so they get reviewed.",
+}
+
+def _users(model, ID):
+    users = [p.id for p in model.parameters.values() if ID in p.args and p.id != ID]
+    users += [f"test {t.label()}" for t in model.tests if ID in t.args]
+    return users
+
+def aliases(model):
+    from . import LintWarning
+
+    for parameter in model.parameters.values():
+        if not parameter.alias or parameter.model != model.name:
+            continue
+        warning = LintWarning(model.name + ".on", parameter.line_no, "alias", f"{parameter.id} is an alias of {parameter.alias}. Replace its uses with {parameter.alias} and remove it.")
+        users = _users(model, parameter.id)
+        if users:
+            warning = warning.with_note(f"Used by {', '.join(users)}")
+        yield warning
+
+def duplicate_labels(model):
+    from . import LintWarning
+
+    for label, IDs in model.labels().items():
+        parameters = [model.parameters[ID] for ID in IDs if model.parameters[ID].model == model.name and not model.parameters[ID].alias]
+        for parameter in parameters[1:]:
+            first = parameters[0]
+            warning = LintWarning(model.name + ".on", parameter.line_no, "duplicate-label", f"{parameter.id} has the same label as {first.id} (\"{first.name}\"). Give it a distinct label.")
+            yield warning.with_note(f"{first.id} is defined on line {first.line_no}")
+
+# Values from SMALL to LARGE (in the units they're written in) are left alone.
+SMALL = 1e-3
+LARGE = 1e6
+NUMBER = r"[-+]?(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?"
+# A parameter with a literal value and simple units, split into its parts.
+LITERAL_PATTERN = re.compile(rf"^(?P<preamble>[^:(]*)(?:\((?P<limits>[^)]*)\))?(?P<assignment>\s*:\s*\w+\s*=\s*)(?P<value>{NUMBER}(?:\s*\|\s*{NUMBER})?)(?P<colon>\s*:\s*)(?P<units>[^\s:]+)(?P<end>\s*)$")
+
+def _prefixed(units):
+    """
+    The SI prefix and unit of simple units (`GHz` is `G` and `Hz`), or None.
+    """
+    from . import units as un
+
+    core = un.SI_UNITS | un.BASE_UNITS
+    if units in core and units in un.SI_MULTIPLES:
+        return "", units
+    for prefix in un.SI_PREFIXES:
+        if prefix and units.startswith(prefix) and units[len(prefix):] in core and units in un.SI_MULTIPLES:
+            return prefix, units[len(prefix):]
+    return None
+
+def _best_prefix(value, unit):
+    """
+    The prefix that puts a value (in the unprefixed unit) between 1 and 1000, or as
+    close as the unit's prefixes allow.
+    """
+    from . import units as un
+
+    prefixes = sorted((p for p in un.SI_PREFIXES if p + unit in un.SI_MULTIPLES), key=lambda p: un.SI_PREFIXES[p][1])
+    fitting = [p for p in prefixes if un.SI_PREFIXES[p][1] <= value]
+    return fitting[-1] if fitting else prefixes[0]
+
+def _number(value):
+    return f"{value:.12g}"
+
+def _rescale(text, factor):
+    return re.sub(NUMBER, lambda match: _number(float(match.group()) * factor), text)
+
+def magnitudes(model):
+    from . import LintWarning, read_model_lines
+    from . import units as un
+
+    filename = model.name + ".on"
+    try:
+        lines = {(line_no, getattr(line_no, "fragment", None)): line for line, line_no in read_model_lines(filename)}
+    except OSError:
+        lines = {}
+    for parameter in model.parameters.values():
+        if parameter.model != model.name or not parameter.independent or parameter.isdiscrete or not parameter.hr_units:
+            continue
+        match = LITERAL_PATTERN.match(lines.get((parameter.line_no, getattr(parameter.line_no, "fragment", None)), "").rstrip("\n"))
+        prefixed = _prefixed(parameter.hr_units)
+        if not match or not prefixed or match.group("units") != parameter.hr_units:
+            continue
+        values = [abs(float(value)) for value in re.findall(NUMBER, match.group("value"))]
+        written = max(values)
+        if written == 0 or SMALL <= written < LARGE:
+            continue
+        prefix, unit = prefixed
+        best = _best_prefix(written * un.SI_PREFIXES[prefix][1], unit)
+        if best == prefix:
+            continue
+        factor = un.SI_PREFIXES[prefix][1] / un.SI_PREFIXES[best][1]
+        value = _rescale(match.group("value"), factor)
+        warning = LintWarning(filename, parameter.line_no, "magnitude", f"{parameter.id} is {match.group('value')} :{parameter.hr_units}. Write it as {value} :{best + unit}.")
+
+        # Limits are in the same units, so they're rescaled too. Limits that aren't
+        # numbers can't be, so those parameters aren't fixed automatically, and neither
+        # are parameters in included fragments.
+        limits = match.group("limits")
+        if hasattr(parameter.line_no, "fragment"):
+            yield warning
+            continue
+        if limits is not None and not all(re.fullmatch(rf"\s*(?:{NUMBER}|[-+]?inf)\s*", limit) for limit in limits.split(",")):
+            yield warning.with_note("Its limits aren't numbers, so rescale them by hand")
+            continue
+        warning.fix = (match.group("preamble") + ("" if limits is None else f"({_rescale(limits, factor)})") + match.group("assignment")
+                       + value + match.group("colon") + best + unit + match.group("end"))
+        yield warning
+
+def unit_names(model):
+    from . import LintWarning, read_model_lines
+    from . import units as un
+    from .refactor import _units_span
+
+    filename = model.name + ".on"
+    try:
+        lines = {line_no: line for line, line_no in read_model_lines(filename) if not hasattr(line_no, "fragment")}
+    except OSError:
+        lines = {}
+    for parameter in model.parameters.values():
+        if parameter.model != model.name or not parameter.hr_units:
+            continue
+        symbols = un.canonical(parameter.hr_units)
+        if symbols == parameter.hr_units:
+            continue
+        warning = LintWarning(filename, parameter.line_no, "unit-name", f"The units of {parameter.id} are written as \"{parameter.hr_units}\". Write them as \"{symbols}\".")
+        line = lines.get(parameter.line_no, "").rstrip("\n")
+        span = _units_span(line) if line else None
+        if span and line[span[0]:span[1]] == parameter.hr_units:
+            warning.fix = line[:span[0]] + symbols + line[span[1]:]
+        yield warning
+
+def unchecked_units(model):
+    from . import LintWarning, unit_check_justification
+
+    for parameter in model.parameters.values():
+        reason = unit_check_justification(parameter.annotations)
+        if reason is None or parameter.model != model.name:
+            continue
+        yield LintWarning(model.name + ".on", parameter.line_no, "unit-unchecked", f"The units of {parameter.id} aren't checked: {reason.rstrip('.')}.", severity="note")
+
+_dictionary = None
+
+def dictionary():
+    """
+    The known words: the bundled word list, plus the system word list if there is one.
+    """
+    global _dictionary
+    if _dictionary is None:
+        _dictionary = set()
+        for path in (os.path.join(os.path.dirname(__file__), "words.txt"), "/usr/share/dict/words"):
+            try:
+                with open(path, "r", encoding="utf-8", errors="ignore") as f:
+                    _dictionary.update(word.lower() for line in f for word in line.split("#")[0].split())
+            except OSError:
+                pass
+    return _dictionary
+
+# Endings removed (and what replaces them) to find a word's stem in the dictionary.
+SUFFIXES = [("ies", "y"), ("es", ""), ("s", ""), ("ied", "y"), ("ed", ""), ("ed", "e"), ("ing", ""), ("ing", "e"), ("ly", ""), ("ally", ""), ("er", ""), ("er", "e"), ("ers", ""), ("ion", "e"), ("ions", "e"), ("al", ""), ("ity", ""), ("ness", ""), ("ment", "")]
+PREFIXES = ["un", "re", "pre", "sub", "non", "over", "under", "multi", "inter"]
+
+def _known(word, known):
+    if word in known:
+        return True
+    stems = [word[len(prefix):] for prefix in PREFIXES if word.startswith(prefix)] + [word]
+    for stem in stems:
+        if stem in known:
+            return True
+        for suffix, replacement in SUFFIXES:
+            if stem.endswith(suffix) and stem[:-len(suffix)] + replacement in known:
+                return True
+    return False
+
+def _one_edit(a, b):
+    """
+    Whether a and b differ by one inserted, deleted, replaced, or swapped letter.
+    """
+    if abs(len(a) - len(b)) > 1 or a == b:
+        return False
+    if len(a) == len(b):
+        diffs = [i for i in range(len(a)) if a[i] != b[i]]
+        return len(diffs) == 1 or (len(diffs) == 2 and diffs[1] == diffs[0] + 1 and a[diffs[0]] == b[diffs[1]] and a[diffs[1]] == b[diffs[0]])
+    short, long = sorted((a, b), key=len)
+    return any(long[:i] + long[i + 1:] == short for i in range(len(long)))
+
+# Math, LaTeX commands, and URLs aren't spell-checked.
+IGNORED = re.compile(r"\$[^$]*\$|\\[a-zA-Z]+|https?://\S+|`[^`]*`")
+WORD = re.compile(r"[A-Za-z]+(?:'[a-z]+)?")
+
+def misspellings(text, known):
+    """
+    The likely typos in a text: words that aren't known, but are one edit away from a
+    known word, with that word as the suggestion. Unknown words that aren't close to a
+    known word (names, jargon, acronyms) aren't reported.
+    """
+    found = []
+    for match in WORD.finditer(IGNORED.sub(" ", text)):
+        word = match.group()
+        if word.endswith("'s"):
+            word = word[:-2]
+        # Short words, acronyms, and camelCase identifiers are skipped.
+        if len(word) < 4 or not (word.islower() or word[1:].islower()):
+            continue
+        lower = word.lower()
+        if _known(lower, known):
+            continue
+        suggestions = sorted(w for w in known if len(w) >= 4 and _one_edit(lower, w))
+        if suggestions:
+            found.append((word, suggestions[0]))
+    return found
+
+def _unit_words():
+    from . import units as un
+
+    words = set()
+    for unit, (_, _, details) in (un.STANDARD_UNITS | un.DIMENSIONLESS_UNITS).items():
+        words.add(unit.lower())
+        for alt in details.get("alt", []):
+            for name in (alt if isinstance(alt, tuple) else (alt,)):
+                words.update(WORD.findall(name.lower()))
+    return words
+
+def spelling(model, glossary=()):
+    from . import LintWarning, read_model_lines
+
+    # Parameter IDs, submodel symbols, and unit names are words too.
+    known = dictionary() | _unit_words() | {word.lower() for word in glossary} | {name.lower() for name in model.parameters} | {name.lower() for name in model.submodels}
+    filename = model.name + ".on"
+
+    texts = []
+    try:
+        for line, line_no in read_model_lines(filename):
+            if line.strip() and line[0] not in (" ", "\t", "#"):
+                break
+            texts.append((line_no, "the model note", line))
+    except OSError:
+        pass
+    for parameter in model.parameters.values():
+        if parameter.model != model.name:
+            continue
+        texts.append((parameter.line_no, f"the label of {parameter.id}", parameter.name))
+        texts += [(line_no, f"a note of {parameter.id}", note) for note, line_no in zip(parameter.notes, parameter.note_lines)]
+    for test in model.tests:
+        if test.name:
+            texts.append((test.line_no, "a test name", test.name))
+        texts += [(line_no, f"a note of test {test.label()}", note) for note, line_no in zip(test.notes, test.note_line_nos)]
+
+    for line_no, where, text in texts:
+        for word, suggestion in misspellings(text, known):
+            yield LintWarning(filename, line_no, "spelling", f"\"{word}\" in {where} may be misspelled. Did you mean \"{suggestion}\"?", severity="note")
+
+CHECKS = {
+    "alias": aliases,
+    "duplicate-label": duplicate_labels,
+    "unit-name": unit_names,
+    "magnitude": magnitudes,
+    "unit-unchecked": unchecked_units,
+}
+
+def lint_model(model, spell_check=False, glossary=()):
+    """
+    Run every lint on a loaded model and return the warnings. The spelling lint only
+    runs if `spell_check` is set, with the given glossary of extra words.
+    """
+    warnings = [warning for check in CHECKS.values() for warning in check(model)]
+    if spell_check:
+        warnings += spelling(model, glossary)
+    return warnings
+
+def apply_fixes(file, warnings):
+    """
+    Replace the lines of a model file that have automatic fixes. Returns the warnings
+    that were fixed.
+    """
+    fixed = [warning for warning in warnings if warning.fix is not None]
+    if not fixed:
+        return []
+    with open(file, "r") as f:
+        lines = f.readlines()
+    for warning in fixed:
+        lines[warning.line_no - 1] = warning.fix + "\n"
+    with open(file, "w") as f:
+        f.writelines(lines)
+    return fixed
+
+def run(paths, spell_check=False, glossary=(), fix=False):
+    """
+    Load each model in the workspace (parse only, no evaluation) and print its lint
+    warnings, or with `fix`, fix the ones that can be fixed automatically and print the
+    rest. Returns the number of warnings printed, including notes.
+    """
+    from . import Model, OneilError
+
+    count = 0
+    notes = 0
+    fixes = 0
+    failures = []
+    files = workspace_files(paths)
+    for file in files:
+        directory, name = os.path.split(file)
+        try:
+            with working_directory(directory):
+                warnings = lint_model(Model(name), spell_check, glossary)
+        except OneilError as e:
+            failures.append((file, e))
+            continue
+        if fix:
+            fixed = apply_fixes(file, warnings)
+            warnings = [warning for warning in warnings if warning not in fixed]
+            fixes += len(fixed)
+        warnings = [warning for warning in warnings if diagnostics.level(warning) != "allow"]
+        for warning in warnings:
+            console.print_warning(warning)
+        count += len(warnings)
+        notes += len([warning for warning in warnings if warning.severity == "note"])
+    console.print_error_groups(failures, lambda file: messages.text("while-linting", "While linting {file}", file=file))
+
+    if not console.quiet:
+        if fixes:
+            print(bcolors.OKGREEN + messages.text("lint-fixed", "Fixed {count} warning(s).", count=fixes) + bcolors.ENDC)
+        if not files:
+            print(messages.text("no-models", "No models found."))
+        elif count:
+            found = [messages.text("lint-warnings", "{count} warning(s)", count=count - notes)] if count - notes else []
+            found += [messages.text("lint-notes", "{count} note(s)", count=notes)] if notes else []
+            print(bcolors.YELLOW + messages.text("lint-found", "{found} in {models} model(s).", found=messages.text("and", " and ").join(found), models=len(files)) + bcolors.ENDC)
+        else:
+            print(bcolors.OKGREEN + messages.text("lint-clean", "No warnings in {models} model(s).", models=len(files)) + bcolors.ENDC)
+
+    return count
@@ -0,0 +1,61 @@
+"""
+Peak memory reporting per phase (`--profile-memory`).
+
+Each phase (parsing and resolving a model, calculating it, and running its tests)
+records the peak memory allocated while it ran and the memory still held when it
+finished. Only the outermost phase is recorded, so the phases of submodels count
+toward the model that loads them.
+"""
+
+import contextlib
+import tracemalloc
+
+from beautifultable import BeautifulTable
+
+# Set from the --profile-memory flag.
+enabled = False
+
+# (phase, model, peak bytes, retained bytes) for each recorded phase
+phases = []
+
+_depth = 0
+
+def enable():
+    global enabled
+    enabled = True
+    if not tracemalloc.is_tracing():
+        tracemalloc.start()
+
+@contextlib.contextmanager
+def phase(name, model):
+    global _depth
+    if not enabled or _depth:
+        yield
+        return
+
+    _depth += 1
+    tracemalloc.reset_peak()
+    try:
+        yield
+    finally:
+        _depth -= 1
+        current, peak = tracemalloc.get_traced_memory()
+        phases.append((name, model, peak, current))
+
+def _megabytes(size):
+    return f"{size / 1e6:.2f}"
+
+def report():
+    """
+    Print the recorded phases and clear them.
+    """
+    if not enabled or not phases:
+        return
+
+    table = BeautifulTable()
+    table.columns.header = ["Phase", "Model", "Peak (MB)", "Retained (MB)"]
+    for name, model, peak, current in phases:
+        table.append_row([name, model, _megabytes(peak), _megabytes(current)])
+    print(table)
+    print(f"Peak memory: {_megabytes(max(peak for _, _, peak, _ in phases))} MB")
+    phases.clear()
@@ -0,0 +1,141 @@
+"""
+Stable error codes and the message catalogs used to localize diagnostics and CLI
+output.
+
+Every kind of error and warning has a code (e.g. E0001 for SyntaxError) that won't
+change between versions, so scripts and translations can refer to it. Messages are
+looked up by ID in the catalog for the current language, `locale/<language>.toml` in
+the package or in the user's config directory (e.g. `~/.config/oneil/locale/de.toml`),
+falling back to the English catalog and then to the English text at the call site.
+
+    [codes]
+    E0103 = "Modell {file} nicht gefunden."
+    E0303-parameter = "Division durch Null ({id} ist 0)"
+
+    [messages]
+    no-models = "Keine Modelle gefunden."
+"""
+
+import os
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+# The code of each kind of error and warning. Codes are grouped like the exit codes:
+# E00xx parse errors, E01xx resolution errors, E02xx unit errors, E03xx evaluation
+# errors, E04xx CLI errors, E08xx internal errors, and Wxxxx warnings. Never reuse or
+# renumber a code.
+CODES = {
+    "SyntaxError": "E0001",
+    "UnitParseError": "E0002",
+    "ImportError": "E0003",
+    "ModelLoadingError": "E0101",
+    "ModelError": "E0102",
+    "ModelNotFoundError": "E0103",
+    "IDError": "E0104",
+    "DesignError": "E0105",
+    "OverrideError": "E0106",
+    "CircularDependencyError": "E0107",
+    "ExternError": "E0108",
+    "UnitEvaluationError": "E0201",
+    "PiecewiseUnitError": "E0202",
+    "LimitUnitError": "E0203",
+    "SubmodelInputUnitError": "E0204",
+    "FunctionUnitError": "E0205",
+    "ParameterError": "E0301",
+    "LimitError": "E0302",
+    "DivideByZeroError": "E0303",
+    "ImportedFunctionError": "E0304",
+    "EvaluationLimitError": "E0305",
+    "ConfigError": "E0401",
+    "RefactorError": "E0402",
+    "PackError": "E0403",
+    "VerifyError": "E0404",
+    "SweepError": "E0405",
+    "ScaffoldError": "E0406",
+    "InternalError": "E0801",
+    "ConsistencyError": "E0802",
+    "DeprecationWarning": "W0001",
+    "LintWarning": "W0002",
+    "LintNote": "W0003",
+    "PrecisionWarning": "W0004",
+    "MirrorWarning": "W0005",
+    "EmptyIntervalWarning": "W0006",
+    "InfinityWarning": "W0007",
+    "FeatureWarning": "W0008",
+    "UnusedImportWarning": "W0009",
+    "ShadowedBuiltinWarning": "W0010",
+}
+
+DEFAULT_LANGUAGE = "en"
+
+# The current language, from ONEIL_LANG or the config file's display.language
+language = os.environ.get("ONEIL_LANG") or DEFAULT_LANGUAGE
+
+_catalogs = {}
+
+def code(kind):
+    """
+    The stable code of a kind of error or warning, or None for unknown kinds.
+    """
+    return CODES.get(kind)
+
+def _catalog_paths(lang):
+    from .config import path as config_path
+
+    yield os.path.join(os.path.dirname(config_path()), "locale", f"{lang}.toml")
+    yield os.path.join(os.path.dirname(__file__), "locale", f"{lang}.toml")
+
+def catalog(lang):
+    """
+    The messages of a language's catalog, with the error code templates under "codes"
+    and the other messages under "messages". Missing catalogs are empty.
+    """
+    if lang not in _catalogs:
+        _catalogs[lang] = {"codes": {}, "messages": {}}
+        for path in _catalog_paths(lang):
+            if os.path.exists(path):
+                with open(path, "rb") as f:
+                    entries = tomllib.load(f)
+                _catalogs[lang] = {"codes": entries.get("codes", {}), "messages": entries.get("messages", {})}
+                break
+    return _catalogs[lang]
+
+def available(lang):
+    return any(os.path.exists(path) for path in _catalog_paths(lang))
+
+def _lookup(section, ID):
+    for lang in (language, DEFAULT_LANGUAGE):
+        template = catalog(lang)[section].get(ID)
+        if template is not None:
+            return template
+    return None
+
+def text(ID, default=None, **fields):
+    """
+    The message with the given ID in the current language, formatted with the fields.
+    Falls back to the English catalog and then to `default`.
+    """
+    template = _lookup("messages", ID)
+    if template is None:
+        template = default if default is not None else ID
+    try:
+        return template.format(**fields)
+    except (KeyError, IndexError, ValueError):
+        return (default or ID).format(**fields)
+
+def error_text(kind, default, variant=None, **fields):
+    """
+    The message of an error or warning in the current language, looked up by its code
+    (and variant, e.g. "E0303-parameter", for kinds with more than one message).
+    """
+    ID = code(kind) and (f"{code(kind)}-{variant}" if variant else code(kind))
+    template = _lookup("codes", ID) if ID else None
+    if template is None:
+        return default
+    try:
+        return template.format(**fields)
+    except (KeyError, IndexError, ValueError):
+        return default
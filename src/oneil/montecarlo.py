@@ -0,0 +1,231 @@
+"""
+Monte Carlo uncertainty propagation (`oneil eval --samples`, `montecarlo`), for
+probabilistic results alongside the worst-case intervals.
+
+Each sample draws one value for every independent parameter with a range of numbers,
+from the distribution given by its `@distribution` annotation (uniform over the range by
+default), and evaluates the model and its tests with those values. The ranges are the
+distributions' bounds, so no setting needs units:
+
+* `@distribution(uniform)`: every value in the range is equally likely.
+* `@distribution(normal)` or `@distribution(normal, <k>)`: normal, centered on the
+  range, which is ±k standard deviations (3 by default). Values outside the parameter's
+  limits are drawn again, so the distribution is truncated at the limits.
+* `@distribution(triangular)` or `@distribution(triangular, <f>)`: triangular over the
+  range, peaking at the fraction f of the way from its min to its max (0.5 by default).
+
+Samples where the model fails (e.g. a calculated value out of its limits) are counted
+and left out of the statistics. Inputs with an infinite bound aren't sampled.
+"""
+
+import math
+import random
+
+import numpy as np
+from beautifultable import BeautifulTable
+
+from . import bcolors
+from . import deterministic
+
+DISTRIBUTIONS = ["uniform", "normal", "triangular"]
+
+# The percentiles reported for each parameter
+PERCENTILES = [5, 50, 95]
+
+# How many times a normal sample outside the parameter's limits is drawn again before
+# it's clipped to them
+REDRAWS = 100
+
+def validate(arguments):
+    """
+    Check the arguments of a `@distribution` annotation, raising a ValueError if they're
+    invalid.
+    """
+    usage = "\"@distribution\" takes uniform, normal, or triangular, and optionally a number (e.g. \"@distribution(normal, 2)\")."
+    if not arguments or arguments[0] not in DISTRIBUTIONS or len(arguments) > 2:
+        raise ValueError(usage)
+    if len(arguments) == 2:
+        argument = arguments[1]
+        if arguments[0] == "uniform" or not isinstance(argument, (int, float)) or isinstance(argument, bool):
+            raise ValueError(usage)
+        if arguments[0] == "normal" and argument <= 0:
+            raise ValueError("The range of a normal distribution must span a positive number of standard deviations.")
+        if arguments[0] == "triangular" and not 0 <= argument <= 1:
+            raise ValueError("The peak of a triangular distribution must be a fraction of the way through its range, from 0 to 1.")
+
+def distribution(annotations):
+    """
+    The distribution of a parameter from its `@distribution` annotation, as its name and
+    argument (the standard deviations in the range, or the fraction at the peak).
+    """
+    arguments = annotations.get("distribution", ("uniform",))
+    defaults = {"uniform": None, "normal": 3, "triangular": 0.5}
+    return arguments[0], arguments[1] if len(arguments) > 1 else defaults[arguments[0]]
+
+def draw(parameter, low, high, generator):
+    """
+    Draw a value for a parameter with the range (low, high) in base units.
+    """
+    name, argument = distribution(parameter.annotations)
+    if name == "triangular":
+        return generator.triangular(low, high, low + argument * (high - low))
+    if name == "uniform":
+        return generator.uniform(low, high)
+
+    lower, upper = parameter.options if isinstance(parameter.options, tuple) else (-math.inf, math.inf)
+    mean, deviation = (low + high) / 2, (high - low) / 2 / argument
+    for _ in range(REDRAWS):
+        value = generator.gauss(mean, deviation)
+        if lower <= value <= upper:
+            return value
+    return min(max(value, lower), upper)
+
+def percentile(values, q):
+    """
+    The q-th percentile of sorted values, interpolating between the nearest two.
+    """
+    position = (len(values) - 1) * q / 100
+    below = math.floor(position)
+    above = min(below + 1, len(values) - 1)
+    return values[below] + (values[above] - values[below]) * (position - below)
+
+def _models(model, path=(), trail=None):
+    # The model and its submodels, each with its path of submodel symbols
+    trail = set() if trail is None else trail
+    trail.add(id(model))
+    found = [(path, model)]
+    for symbol, entry in model.submodels.items():
+        if 'model' in entry and id(entry['model']) not in trail:
+            found += _models(entry['model'], path + (symbol,), trail)
+    return found
+
+def _number(value):
+    return isinstance(value, (int, float)) and not isinstance(value, (bool, np.bool_))
+
+def _inputs(models):
+    # The independent parameters with a finite range of numbers, with their paths
+    inputs = []
+    for path, model in models:
+        for parameter in model.parameters.values():
+            values = (parameter.min, parameter.max)
+            if parameter.independent and all(_number(v) and math.isfinite(v) for v in values) and values[0] < values[1]:
+                inputs.append((path, parameter))
+    return inputs
+
+def simulate(model, samples=1000, seed=None):
+    """
+    Evaluate a model (already built) with samples of its inputs and those of its
+    submodels. Returns the number of samples and of those that failed, the inputs (named
+    `ID` or `ID.submodel`) with their distributions, and for each parameter with a
+    number value, its interval result (min and max) with the mean, standard deviation,
+    and PERCENTILES of its samples, all in its display units, and for each test, the
+    fraction of the samples in which it passed. Parameters and tests have the path of
+    submodel symbols as their model. The model is recalculated with its own values
+    afterwards.
+
+    Without a seed, samples are random, except with `--deterministic`, where the seed
+    is 0.
+    """
+    from . import OneilError
+
+    if seed is None and deterministic.enabled:
+        seed = 0
+    generator = random.Random(seed)
+    models = _models(model)
+    inputs = _inputs(models)
+    original = [(parameter.min, parameter.max) for _, parameter in inputs]
+
+    intervals = {}
+    for path, submodel in models:
+        for ID, parameter in submodel.parameters.items():
+            result = parameter.result()
+            if _number(result["min"]) and _number(result["max"]):
+                intervals[(path, ID)] = (parameter, result)
+    values = {key: [] for key in intervals}
+    passes = {(path, i): 0 for path, submodel in models for i in range(len(submodel.tests))}
+
+    failed = 0
+    try:
+        for _ in range(samples):
+            for (_, parameter), (low, high) in zip(inputs, original):
+                parameter.min = parameter.max = draw(parameter, low, high, generator)
+            model._reset_recursively()
+            try:
+                model._calculate_models_recursively(quiet=True)
+                model.test(verbose=False)
+            except OneilError:
+                failed += 1
+                continue
+            for path, submodel in models:
+                for ID, parameter in submodel.parameters.items():
+                    if (path, ID) in values:
+                        result = parameter.result()
+                        values[(path, ID)].append((result["min"] + result["max"]) / 2)
+                for i, test in enumerate(submodel.tests):
+                    passes[(path, i)] += bool(test.passed)
+    finally:
+        for (_, parameter), (low, high) in zip(inputs, original):
+            parameter.min, parameter.max = low, high
+        model._reset_recursively()
+        model._calculate_models_recursively(quiet=True)
+        model.test(verbose=False)
+
+    evaluated = samples - failed
+    parameters = []
+    for (path, ID), (parameter, result) in intervals.items():
+        entry = {"model": ".".join(path) or model.name, "id": ID, "name": result["name"], "units": result["units"], "performance": parameter.performance, "min": result["min"], "max": result["max"]}
+        found = sorted(values[(path, ID)])
+        if found:
+            mean = sum(found) / len(found)
+            entry["mean"] = mean
+            entry["std"] = math.sqrt(sum((value - mean) ** 2 for value in found) / len(found))
+            entry["percentiles"] = {str(q): percentile(found, q) for q in PERCENTILES}
+        else:
+            entry.update({"mean": None, "std": None, "percentiles": {str(q): None for q in PERCENTILES}})
+        parameters.append(entry)
+
+    tests = []
+    for path, submodel in models:
+        for i, test in enumerate(submodel.tests):
+            probability = passes[(path, i)] / evaluated if evaluated else None
+            tests.append({"model": ".".join(path) or model.name, "name": test.name, "expression": test.expression, "probability": probability})
+
+    return {
+        "samples": samples,
+        "failed": failed,
+        "seed": seed,
+        "inputs": [{"input": ".".join((parameter.id,) + path[::-1]), "distribution": distribution(parameter.annotations)[0]} for path, parameter in inputs],
+        "parameters": parameters,
+        "tests": tests,
+    }
+
+def _value(value, sigfigs):
+    return "-" if value is None else f"{value:.{sigfigs}g}"
+
+def print_simulation(report, IDs=None, sigfigs=4):
+    """
+    Print the results of simulate() for the given parameters (as (model, ID) pairs, or the
+    performance parameters by default) as a table, and the tests that didn't pass in
+    every sample with how often they passed.
+    """
+    table = BeautifulTable()
+    table.columns.header = ["Model", "Parameter", "Interval", "Mean", "Std"] + [f"P{q}" for q in PERCENTILES]
+    for entry in report["parameters"]:
+        if (entry["model"], entry["id"]) not in IDs if IDs is not None else not entry["performance"]:
+            continue
+        units = " " + entry["units"] if entry["units"] else ""
+        interval = _value(entry["min"], sigfigs) if entry["min"] == entry["max"] else f"{_value(entry['min'], sigfigs)}|{_value(entry['max'], sigfigs)}"
+        percentiles = [_value(entry["percentiles"][str(q)], sigfigs) for q in PERCENTILES]
+        table.append_row([entry["model"], f"{entry['name']} ({entry['id']})", interval + units, _value(entry["mean"], sigfigs) + units, _value(entry["std"], sigfigs) + units] + percentiles)
+
+    seed = f", seed {report['seed']}" if report["seed"] is not None else ""
+    print(f"Monte Carlo: {report['samples']} sample(s) of {len(report['inputs'])} input(s){seed}.")
+    if len(table.rows):
+        print(table)
+    else:
+        print("No performance parameters. Give the parameters to find the statistics of.")
+    for test in report["tests"]:
+        if test["probability"] is not None and test["probability"] < 1:
+            print(f"{bcolors.YELLOW}Test {test['name'] or test['expression']} ({test['model']}) passed in {100 * test['probability']:.3g}% of samples.{bcolors.ENDC}")
+    if report["failed"]:
+        print(f"{bcolors.YELLOW}{report['failed']} sample(s) failed to evaluate and were left out.{bcolors.ENDC}")
@@ -0,0 +1,170 @@
+"""
+Mutation testing of a model's tests (`oneil mutate`, experimental), for measuring how
+strong they are.
+
+Each mutant is the model with one small change to one of its equations: an operator
+swapped (`+` and `-`, `*` and `/`, `<` and `<=`, `>` and `>=`, `==` and `!=`, `min` and
+`max`) or a number perturbed by 10%. A mutant is caught if one of the tests fails with
+it, or if the model can't be evaluated with it (e.g. a value leaves its limits).
+Mutants that survive point at equations whose mistakes no test would notice.
+
+Only equations of the model file are mutated (parameters with a number as their value
+are inputs, not equations). Mutants are read from `source_overrides`, so the model file
+is never written.
+"""
+
+import contextlib
+import io
+import os
+import re
+
+from beautifultable import BeautifulTable
+
+from . import bcolors
+from . import console
+
+# How much numbers are perturbed, relative to their value
+PERTURBATION = 0.1
+
+SWAPS = {"+": "-", "-": "+", "*": "/", "/": "*", "<": "<=", "<=": "<", ">": ">=", ">=": ">", "==": "!=", "!=": "==", "min(": "max(", "max(": "min("}
+
+TOKEN = re.compile(r"<=|>=|==|!=|\*\*|//|--|[-+*/<>]|(?<![\w.])(?:min|max)\(|(?<![\w.])\d+\.?\d*(?:[eE][-+]?\d+)?")
+
+def _perturb(number):
+    value = float(number)
+    return f"{value * (1 + PERTURBATION):.6g}" if value else "1"
+
+def _equation_span(line):
+    """
+    The columns (start, end) of the equation in a parameter declaration or a piecewise
+    line, or None if the line has no equation to mutate.
+    """
+    if re.search(r"^\s+\{", line):
+        return line.index("{") + 1, len(line.rstrip("\n"))
+    if not line.strip() or line[0] in (" ", "\t", "#", "@") or line.split()[0] in ("use", "from", "import", "section", "include", "test") or ":=" in line:
+        return None
+    if not re.search(r"^[^\s]+[^:]*:\s*\w+\s*=[^:]+(:.*)?$", line):
+        return None
+    body = line.index(":") + 1
+    start = line.index("=", body) + 1
+    if line[start:start + 1] == ">":
+        start += 1
+    end = line.find(":", start)
+    end = len(line.rstrip("\n")) if end == -1 else end
+    # Numbers alone are inputs, not equations.
+    from . import MATH_CONSTANTS
+    names = [name for name in re.findall(r"[A-Za-z_]\w*", line[start:end]) if name not in MATH_CONSTANTS and not re.fullmatch(r"[eE]\d*", name)]
+    return (start, end) if names else None
+
+def mutations(lines):
+    """
+    The mutations of a model's lines, as (line number, column, original, replacement),
+    with 1-based line numbers and 0-based columns.
+    """
+    found = []
+    for i, line in enumerate(lines):
+        span = _equation_span(line)
+        if not span:
+            continue
+        start, end = span
+        # Don't mutate inside strings (the options of discrete parameters).
+        masked = re.sub(r"'[^']*'|\"[^\"]*\"", lambda m: " " * len(m.group(0)), line[start:end])
+        for match in TOKEN.finditer(masked):
+            token, column = match.group(0), start + match.start()
+            if token[0].isdigit():
+                found.append((i + 1, column, token, _perturb(token)))
+            elif token in SWAPS:
+                # Only binary + and -: after an operand, not a sign.
+                before = masked[:match.start()].rstrip()
+                if token in ("+", "-") and not (before and (before[-1].isalnum() or before[-1] in "_)")):
+                    continue
+                found.append((i + 1, column, token, SWAPS[token]))
+    return found
+
+def _failed_tests(model):
+    from .coverage import _models
+
+    return [test.label() for scope in _models(model).values() for test in scope.tests if test.passed is False]
+
+def _evaluate(inp, designs, text):
+    # The failed tests of the model evaluated with its file's text, or the error that
+    # stopped it, without printing or changing the exit code.
+    from . import OneilError, loader, source_overrides
+
+    path = os.path.abspath(inp)
+    exit_code = console.exit_code
+    source_overrides[path] = text
+    try:
+        with contextlib.redirect_stdout(io.StringIO()):
+            model = loader(inp, list(designs), capture_errors=False, quiet=True)
+        return _failed_tests(model), None
+    except OneilError as e:
+        return None, e
+    finally:
+        del source_overrides[path]
+        console.exit_code = exit_code
+
+def mutate(inp, designs=()):
+    """
+    Evaluate the mutants of a model file (see mutations()) with the designs. Returns a
+    list of mutants, each with its line, column, original and replacement text, and
+    result: "killed" (with the tests that failed), "error" (with the error's kind), or
+    "survived".
+
+    Raises ValueError if the model's tests don't all pass, since then they can't tell
+    mutants apart.
+    """
+    inp = inp if inp.endswith(".on") else inp + ".on"
+    with open(inp, "r") as f:
+        lines = f.readlines()
+    failed, error = _evaluate(inp, designs, "".join(lines))
+    if error:
+        raise error
+    if failed:
+        raise ValueError(f"The model's tests must pass before mutating it (failed: {', '.join(failed)}).")
+
+    mutants = []
+    for line_no, column, original, replacement in mutations(lines):
+        line = lines[line_no - 1]
+        mutated = lines[:line_no - 1] + [line[:column] + replacement + line[column + len(original):]] + lines[line_no:]
+        failed, error = _evaluate(inp, designs, "".join(mutated))
+        mutant = {"line": line_no, "column": column + 1, "original": original, "replacement": replacement}
+        if error:
+            mutant.update({"result": "error", "error": error.kind()})
+        elif failed:
+            mutant.update({"result": "killed", "tests": failed})
+        else:
+            mutant.update({"result": "survived"})
+        mutants.append(mutant)
+    return mutants
+
+def run(inp, designs=()):
+    """
+    Mutate a model and print which mutants its tests caught, with its mutation score
+    (the fraction of mutants caught).
+    """
+    try:
+        mutants = mutate(inp, designs)
+    except ValueError as e:
+        print(f"{bcolors.error('ERROR')} {e}")
+        console.record_exit(console.EXIT_TEST_FAILURE)
+        return []
+    if not mutants:
+        print("No equations to mutate.")
+        return mutants
+
+    table = BeautifulTable()
+    table.columns.header = ["Line", "Mutation", "Result"]
+    for mutant in mutants:
+        if mutant["result"] == "killed":
+            result = bcolors.OKGREEN + "killed by " + ", ".join(mutant["tests"]) + bcolors.ENDC
+        elif mutant["result"] == "error":
+            result = bcolors.OKGREEN + "caught (" + mutant["error"] + ")" + bcolors.ENDC
+        else:
+            result = bcolors.YELLOW + "survived" + bcolors.ENDC
+        table.append_row([f"{mutant['line']}:{mutant['column']}", f"{mutant['original']} -> {mutant['replacement']}", result])
+    print(table)
+
+    caught = sum(mutant["result"] != "survived" for mutant in mutants)
+    print(f"Mutation score: {caught}/{len(mutants)} ({100 * caught / len(mutants):.0f}%) of the mutants were caught.")
+    return mutants
@@ -0,0 +1,109 @@
+"""
+How parameters that depend on each other in a cycle are evaluated. With the "strict"
+order (the default), parameters are evaluated after the parameters they use, so a cycle
+is a ParameterError. With the "fixpoint" order, a cycle that's declared by marking one
+of its parameters with `@iterate(<initial guess>)` is iterated until its values settle,
+for benign algebraic loops like iterative sizing:
+
+    @iterate(1000)
+    Total mass: m_total = m_payload + m_structure :kg
+    Structure mass: m_structure = 0.25*m_total :kg
+
+The iterated parameter starts at its initial guess (in its units), the rest of the cycle
+is calculated from it, and its equation gives its next value, moved `damping` of the way
+from the previous one. Iteration stops when each bound changes by less than `tolerance`
+(relative), or fails with a ParameterError after `max_iterations`.
+
+The order and the iteration settings come from the `[evaluation]` section of the config
+file and the `--order` flag:
+
+    [evaluation]
+    order = "fixpoint"       # "strict" (the default) or "fixpoint"
+    damping = 0.5            # the fraction of each step taken (default: 1, no damping)
+    tolerance = 1e-9         # relative change in each bound to stop at (the default)
+    max_iterations = 100     # iterations before failing (the default)
+"""
+
+import math
+
+ORDERS = ["strict", "fixpoint"]
+
+DEFAULT_SETTINGS = {"order": "strict", "damping": 1.0, "tolerance": 1e-9, "max_iterations": 100}
+
+# The current settings, and those set by flags, which take precedence over the config
+settings = dict(DEFAULT_SETTINGS)
+overrides = {}
+
+def reset():
+    settings.clear()
+    settings.update(DEFAULT_SETTINGS | overrides)
+
+def apply(config, errors):
+    """
+    Apply evaluation settings (from the config file), adding a message to errors for
+    each invalid one.
+    """
+    for key, value in config.items():
+        number = isinstance(value, (int, float)) and not isinstance(value, bool)
+        if key not in DEFAULT_SETTINGS:
+            errors.append(f"Unknown evaluation setting \"{key}\". Settings are: {', '.join(DEFAULT_SETTINGS)}.")
+        elif key == "order" and value not in ORDERS:
+            errors.append(f"evaluation.order must be one of: {', '.join(ORDERS)}.")
+        elif key == "damping" and not (number and 0 < value <= 1):
+            errors.append("evaluation.damping must be a number greater than 0 and at most 1.")
+        elif key == "tolerance" and not (number and 0 < value < 1):
+            errors.append("evaluation.tolerance must be a number between 0 and 1.")
+        elif key == "max_iterations" and not (isinstance(value, int) and not isinstance(value, bool) and value > 0):
+            errors.append("evaluation.max_iterations must be a positive integer.")
+        elif key not in overrides:
+            settings[key] = value
+
+def iterated(parameter):
+    """
+    Whether a parameter is iterated as the declared parameter of a cycle.
+    """
+    return settings["order"] == "fixpoint" and "iterate" in getattr(parameter, "annotations", {})
+
+def cycle(parameters, ID):
+    """
+    The IDs of the parameters (of one model) in a cycle with a parameter: those it uses,
+    directly or not, that use it in turn.
+    """
+    def reached(start):
+        found, stack = set(), list(parameters[start].args)
+        while stack:
+            arg = stack.pop()
+            if arg in parameters and arg not in found:
+                found.add(arg)
+                stack.extend(parameters[arg].args)
+        return found
+
+    return {other for other in reached(ID) if other != ID and ID in reached(other)}
+
+def step(previous, value):
+    """
+    The next value of an iterated parameter (as (min, max)) from its previous value and
+    what its equation gave, with damping.
+    """
+    return tuple(a + settings["damping"] * (b - a) for a, b in zip(previous, value))
+
+def change(previous, value):
+    """
+    The largest relative change between two values (as (min, max)).
+    """
+    return max(abs(b - a) / max(abs(a), abs(b)) if a != b else 0 for a, b in zip(previous, value))
+
+def hint(parameters, trail):
+    """
+    How to iterate a cycle through the parameters in trail instead of rejecting it, or
+    None.
+    """
+    marked = [ID for ID in trail if "iterate" in getattr(parameters.get(ID), "annotations", {})]
+    if marked and settings["order"] != "fixpoint":
+        return f"{marked[0]} is marked @iterate. Evaluate with `--order fixpoint` (or order = \"fixpoint\" in the [evaluation] section of the config file) to iterate the cycle."
+    if not marked and settings["order"] == "fixpoint":
+        return "Mark a parameter of the cycle with @iterate(<initial guess>) to iterate the cycle."
+    return None
+
+def finite(value):
+    return all(isinstance(bound, (int, float)) and not isinstance(bound, bool) and math.isfinite(bound) for bound in value)
@@ -0,0 +1,120 @@
+"""
+Self-contained archives of a model (`oneil pack`), for sharing the exact state of a
+design with reviewers.
+
+An archive (`.onpack`) is a zip file with the entry model, the designs it was packed
+with, and every file they load (see oneil.deps), under their paths relative to the
+directory that holds all of them. Its manifest, `onpack.json`, names the entry model and
+designs and has the SHA-256 hash of each file:
+
+    {
+      "format": 1,
+      "oneil": "0.9.0",
+      "entry": "models/craft.on",
+      "designs": ["designs/flight.on"],
+      "files": {"models/craft.on": {"kind": "model", "sha256": "..."}, ...}
+    }
+
+Commands given an archive instead of a model extract it to a temporary directory and
+check the hashes first, so a modified archive is an error rather than a different
+result.
+"""
+
+import atexit
+import hashlib
+import json
+import os
+import shutil
+import tempfile
+import zipfile
+
+from . import console
+from . import deps
+
+EXTENSION = ".onpack"
+MANIFEST = "onpack.json"
+FORMAT = 1
+
+# The archives extracted in this process, by path, as (entry model, designs)
+_unpacked = {}
+
+def is_pack(path):
+    return path.endswith(EXTENSION)
+
+def file_hash(path):
+    """
+    The SHA-256 hash of a file, as hex.
+    """
+    digest = hashlib.sha256()
+    with open(path, "rb") as f:
+        for block in iter(lambda: f.read(1 << 16), b""):
+            digest.update(block)
+    return digest.hexdigest()
+
+def pack(inp, designs, output):
+    """
+    Write an archive of the model (with the given designs) and everything it loads to
+    output. Returns the manifest. Raises a PackError if a file is missing.
+    """
+    from . import PackError
+
+    files, missing = deps.dependencies(inp, designs)
+    if missing:
+        raise PackError(output, f"Can't pack {inp}: {missing[0]['file']} not found.")
+
+    paths = {os.path.abspath(entry["file"]): entry["kind"] for entry in files}
+    root = os.path.commonpath([os.path.dirname(path) for path in paths])
+    name = lambda path: os.path.relpath(os.path.abspath(path), root).replace(os.sep, "/")
+
+    manifest = {
+        "format": FORMAT,
+        "oneil": console.__version__,
+        "entry": name(deps._on_file(inp)),
+        "designs": [name(deps._on_file(design)) for design in designs],
+        "files": {name(path): {"kind": kind, "sha256": file_hash(path)} for path, kind in paths.items()},
+    }
+    with zipfile.ZipFile(output, "w", zipfile.ZIP_DEFLATED) as archive:
+        archive.writestr(MANIFEST, json.dumps(manifest, indent=2))
+        for path in paths:
+            archive.write(path, name(path))
+    return manifest
+
+def unpack(path):
+    """
+    Extract an archive to a temporary directory (removed when Oneil exits) and check its
+    hashes. Returns the paths of the entry model and designs. Raises a PackError if the
+    archive can't be read or was modified.
+    """
+    from . import PackError
+
+    if path in _unpacked:
+        return _unpacked[path]
+    if not os.path.exists(path):
+        raise PackError(path, "Archive not found.")
+
+    directory = tempfile.mkdtemp(prefix="oneil-pack-")
+    atexit.register(shutil.rmtree, directory, True)
+    try:
+        with zipfile.ZipFile(path) as archive:
+            manifest = json.loads(archive.read(MANIFEST))
+            for name in manifest["files"]:
+                # Only extract files inside the directory (no absolute paths or "..").
+                target = os.path.normpath(os.path.join(directory, name))
+                if os.path.isabs(name) or not target.startswith(directory + os.sep):
+                    raise PackError(path, f"Invalid path in archive: {name}")
+                archive.extract(name, directory)
+    except (OSError, KeyError, ValueError, zipfile.BadZipFile) as e:
+        raise PackError(path, f"Can't read archive: {e}")
+
+    for name, entry in manifest["files"].items():
+        if file_hash(os.path.join(directory, name)) != entry["sha256"]:
+            raise PackError(path, f"{name} doesn't match its hash in the archive. The archive was modified after it was packed.")
+
+    _unpacked[path] = (os.path.join(directory, manifest["entry"]), [os.path.join(directory, design) for design in manifest["designs"]])
+    return _unpacked[path]
+
+def resolve(paths):
+    """
+    Replace the archives in a list of model paths with their extracted entry models.
+    """
+    return [unpack(path)[0] if is_pack(path) else path for path in paths]
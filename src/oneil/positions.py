@@ -0,0 +1,94 @@
+"""
+Positions in model files, and the columns different tools count them in.
+
+Oneil reports columns in characters (Unicode code points), 1-based, like the lines.
+Editors speaking LSP count UTF-16 code units by default, so a character outside the
+Basic Multilingual Plane (most emoji, some math symbols) counts as two, and tools that
+work on bytes count UTF-8, where anything beyond ASCII counts as two to four. A label
+like "Δv budget" puts every column after it off by one in UTF-8 if the counts are
+mixed up, so columns are converted here rather than by each caller.
+
+Encodings are named as in LSP's `positionEncoding`: "utf-8", "utf-16", or "utf-32"
+(code points, the default).
+"""
+
+import linecache
+import os
+
+ENCODINGS = ["utf-8", "utf-16", "utf-32"]
+
+def _width(character, encoding):
+    code = ord(character)
+    if encoding == "utf-16":
+        return 2 if code > 0xFFFF else 1
+    if encoding == "utf-8":
+        return 1 if code < 0x80 else 2 if code < 0x800 else 3 if code < 0x10000 else 4
+    return 1
+
+def _check(encoding):
+    if encoding not in ENCODINGS:
+        raise ValueError(f"Unknown position encoding \"{encoding}\" (expected one of {', '.join(ENCODINGS)}).")
+
+def column_of(line, index, encoding="utf-32"):
+    """
+    The column of the character at `index` in a line (0-based, in code units of the
+    encoding).
+    """
+    _check(encoding)
+    if encoding == "utf-32":
+        return index
+    return sum(_width(character, encoding) for character in line[:index])
+
+def index_of(line, column, encoding="utf-32"):
+    """
+    The index of the character at a column (0-based, in code units of the encoding).
+    A column in the middle of a character is that character, and one past the end of
+    the line is the end of the line.
+    """
+    _check(encoding)
+    if encoding == "utf-32":
+        return min(column, len(line))
+    units = 0
+    for i, character in enumerate(line):
+        units += _width(character, encoding)
+        if units > column:
+            return i
+    return len(line)
+
+def convert(line, column, source, target):
+    """
+    Convert a 0-based column from one encoding to another.
+    """
+    return column_of(line, index_of(line, column, source), target)
+
+def offset(text, line_no, column, encoding="utf-32"):
+    """
+    The index in a text of a 1-based line and 0-based column.
+    """
+    lines = text.splitlines(keepends=True)
+    start = sum(len(line) for line in lines[:line_no - 1])
+    line = lines[line_no - 1].rstrip("\r\n") if line_no - 1 < len(lines) else ""
+    return start + index_of(line, column, encoding)
+
+def line_and_column(text, index, encoding="utf-32"):
+    """
+    The 1-based line and 0-based column of an index in a text.
+    """
+    start = text.rfind("\n", 0, index) + 1
+    end = text.find("\n", start)
+    line = text[start:end if end != -1 else len(text)].rstrip("\r")
+    return text.count("\n", 0, index) + 1, column_of(line, index - start, encoding)
+
+def source_line(path, line_no):
+    """
+    A line of a model file as it is on disk (or in the editor, see
+    `oneil.source_overrides`), without its line ending. Empty if it can't be read.
+    """
+    from . import source_overrides
+
+    absolute = os.path.abspath(path)
+    if absolute in source_overrides:
+        lines = source_overrides[absolute].splitlines()
+        return lines[line_no - 1] if 0 < line_no <= len(lines) else ""
+    linecache.checkcache(absolute)
+    return linecache.getline(absolute, line_no).rstrip("\r\n")
@@ -0,0 +1,744 @@
+"""
+Refactorings that restructure models without changing their results (`oneil refactor`).
+
+The refactorings edit the model files line by line so that notes, comments, and
+formatting are kept. After editing, every changed model is reloaded and evaluated; if
+any of them fails to load or the values of its parameters changed, all of the files
+are restored.
+"""
+
+import contextlib
+import io
+import os
+import re
+
+from . import bcolors, console
+from .stats import working_directory
+
+COMMANDS = ["extract-section", "inline", "canonical-units"]
+
+USAGE = {
+    "extract-section": "oneil refactor extract-section <model.on> <section> <new_model.on>",
+    "inline": "oneil refactor inline <model.on> <submodel symbol>",
+    "canonical-units": "oneil refactor canonical-units [model files or directories]",
+}
+
+# Keywords that start a declaration line (followed by a space)
+DECLARATIONS = ["use", "from", "import", "include", "section", "unit"]
+
+DESIGN_PATTERN = r"^(\*{1,2}\s*)?\w+(\.\w+)?\s*=>?[^:]+(:.*)?$"
+PARAMETER_PATTERN = r"^[^\s]+[^:]*:\s*\w+\s*=[^:]+(:.*)?$"
+ALIAS_PATTERN = r"^(?:[^:=]+:\s*)?\w+\s*:=\s*\w+(\.\w+)?\s*$"
+# Unit declarations (`unit furlong = 201.168 m`), but not parameters labeled "unit ..."
+UNIT_PATTERN = r"^unit\s+\S+\s*="
+
+def kind(line):
+    """
+    Classify the first line of a block the same way `parse_file` does.
+    """
+    if not line.strip() or line[0] in (" ", "\t"):
+        return "header"
+    if line.strip()[0] == "#":
+        return "comment"
+    if line[0] == "@":
+        return "annotation"
+    for keyword in DECLARATIONS:
+        if line.startswith(keyword + " ") and (keyword != "unit" or re.search(UNIT_PATTERN, line)):
+            return keyword
+    if line[0:4] == "test" or line.replace(" ", "").replace("\t", "")[0:5] == "*test":
+        return "test"
+    if re.search(ALIAS_PATTERN, line):
+        return "alias"
+    if re.search(DESIGN_PATTERN, line):
+        return "design"
+    if re.search(PARAMETER_PATTERN, line):
+        return "parameter"
+    return "unknown"
+
+def blocks(lines):
+    """
+    Group the lines of a model file into blocks: a statement followed by its notes,
+    piecewise continuation lines, and the blank lines after it.
+    """
+    result = []
+    for line in lines:
+        if result and (not line.strip() or line[0] in (" ", "\t")):
+            result[-1].append(line)
+        else:
+            result.append([line])
+    return result
+
+def block_id(block):
+    """
+    The parameter ID defined by a parameter, alias, or design block.
+    """
+    line = block[0].lstrip("$*").strip()
+    if kind(block[0]) == "alias":
+        return line.split(":=")[0].split(":")[-1].strip()
+    body = line.split(":")[1] if kind(block[0]) == "parameter" else line.split(":")[0]
+    return body.split("=")[0].strip()
+
+def _reference_pattern(references):
+    alternatives = "|".join(re.escape(r) for r in sorted(references, key=len, reverse=True))
+    # A reference isn't part of a longer ID, a submodel accessor, or a Python function name.
+    return re.compile(r"(?<![\w.])(" + alternatives + r")(?![\w.])(?!\s*\()")
+
+def rewrite_expression(text, renames):
+    """
+    Rename the references (`ID` or `ID.symbol`) in an expression, leaving quoted
+    strings (discrete values in piecewise conditions) alone.
+    """
+    if not renames:
+        return text
+    pattern = _reference_pattern(renames)
+    parts = re.split(r"('[^']*'|\"[^\"]*\")", text)
+    return "".join(part if i % 2 else pattern.sub(lambda m: renames[m.group(1)], part) for i, part in enumerate(parts))
+
+def _rewrite_assignment(line, start, renames):
+    equals = line.index("=", start)
+    assignment = equals + 2 if line[equals:equals + 2] == "=>" else equals + 1
+    end = line.find(":", assignment)
+    if end == -1:
+        end = len(line.rstrip("\n"))
+    return line[:assignment] + rewrite_expression(line[assignment:end], renames) + line[end:]
+
+def rewrite_block(block, renames):
+    """
+    Rename references in the equations and tests of a block, including the tests in
+    ```oneil-test blocks of its notes. Names, other notes, and units are left alone.
+    """
+    from . import test_colon
+
+    first = block[0]
+    block_kind = kind(first)
+    if block_kind == "parameter":
+        first = _rewrite_assignment(first, first.index(":") + 1, renames)
+    elif block_kind == "design":
+        first = _rewrite_assignment(first, 0, renames)
+        ID = block_id(block)
+        if ID in renames:
+            first = first.replace(ID, renames[ID], 1)
+    elif block_kind == "alias":
+        target = first.index(":=") + 2
+        first = first[:target] + rewrite_expression(first[target:], renames)
+    elif block_kind == "test":
+        colon = test_colon(first) + 1
+        first = first[:colon] + rewrite_expression(first[colon:], renames)
+    elif block_kind in ("use", "from") and "(" in first:
+        # Test inputs passed to the submodel (`use model(input=ID) as symbol`)
+        start, end = first.index("("), first.index(")")
+        inputs = [i.split("=", 1) for i in first[start + 1:end].split(",")]
+        first = first[:start + 1] + ",".join(k + "=" + rewrite_expression(v, renames) for k, v in inputs) + first[end:]
+
+    rest = []
+    fence = first.strip().startswith("```oneil-test")
+    for line in block[1:]:
+        text = line.strip()
+        if fence:
+            # Tests in ```oneil-test blocks of notes
+            if text == "```":
+                fence = False
+            elif text:
+                indent = line[:len(line) - len(line.lstrip())]
+                colon = test_colon(text) + 1
+                line = indent + text[:colon] + rewrite_expression(text[colon:], renames) + line[len(line.rstrip()):]
+        elif text.startswith("```oneil-test"):
+            fence = True
+        elif block_kind == "parameter" and text.startswith("{"):
+            colon = line.find(":")
+            colon = len(line.rstrip("\n")) if colon == -1 else colon
+            line = rewrite_expression(line[:colon], renames) + line[colon:]
+        rest.append(line)
+
+    return [first] + rest
+
+def rename_definition(block, renames):
+    """
+    Rename the ID defined by a parameter or alias block.
+    """
+    if kind(block[0]) not in ("parameter", "alias") or block_id(block) not in renames:
+        return block
+    first = block[0]
+    equals = first.index(":=") if kind(first) == "alias" else first.index("=", first.index(":") + 1)
+    colon = first.rfind(":", 0, equals) + 1
+    ID = block_id(block)
+    return [first[:colon] + first[colon:equals].replace(ID, renames[ID], 1) + first[equals:]] + block[1:]
+
+def _read(file):
+    with open(file, "r") as f:
+        return f.readlines()
+
+def _write(file, lines):
+    with open(file, "w") as f:
+        f.writelines(lines)
+
+def _insert_use(file_blocks, line):
+    """
+    Insert a `use` or `from` line after the existing includes (or before the first statement).
+    """
+    includes = [i for i, b in enumerate(file_blocks) if kind(b[0]) in ("use", "from", "import")]
+    if includes:
+        index = includes[-1] + 1
+        if not file_blocks[index - 1][-1].strip():
+            # Keep the blank line that separates the includes from the rest of the model.
+            blanks = []
+            while file_blocks[index - 1] and not file_blocks[index - 1][-1].strip():
+                blanks.insert(0, file_blocks[index - 1].pop())
+            file_blocks.insert(index, [line] + blanks)
+        else:
+            file_blocks.insert(index, [line])
+    else:
+        index = next((i for i, b in enumerate(file_blocks) if kind(b[0]) not in ("header", "comment")), len(file_blocks))
+        file_blocks.insert(index, [line, "\n"])
+
+def _include_pattern(model_name):
+    """
+    Match `use <model> as S` and `from <source> use <model> as S`, capturing the
+    source path and the symbol.
+    """
+    return re.compile(r"^(?:from\s+(?P<source>\w+(?:\.\w+)*)\s+)?use\s+" + re.escape(model_name) + r"(?:\(.*\))?\s+as\s+(?P<symbol>\w+)\s*$")
+
+def _symbols(file_blocks):
+    symbols = set()
+    for b in file_blocks:
+        if kind(b[0]) in ("use", "from"):
+            symbols.add(b[0].split("as")[-1].strip())
+    return symbols
+
+def _is_design(file_blocks):
+    kinds = [kind(b[0]) for b in file_blocks]
+    return "design" in kinds and all(k in ("design", "comment", "annotation", "header") for k in kinds)
+
+class Refactoring:
+    """
+    A set of file edits that are applied together and reverted together.
+    """
+    def __init__(self, directory):
+        self.directory = directory
+        self.originals = {}
+        self.edits = {}
+        self.created = []
+        self.changes = []
+
+    def path(self, file):
+        return os.path.join(self.directory, file)
+
+    def read(self, file):
+        return _read(self.path(file))
+
+    def write(self, file, lines, description):
+        path = self.path(file)
+        if os.path.exists(path):
+            self.originals.setdefault(path, _read(path))
+        else:
+            self.created.append(path)
+        _write(path, lines)
+        self.edits[path] = lines
+        self.changes.append(f"{file}: {description}")
+
+    def revert(self):
+        for path, lines in self.originals.items():
+            _write(path, lines)
+        for path in self.created:
+            os.remove(path)
+
+    def verify(self, files):
+        """
+        Reload and evaluate the changed models, reverting every edit if one of them fails
+        or if a parameter it still defines has a different value. The values before the
+        edits come from evaluating the original files, which are put back meanwhile.
+        Models that couldn't be evaluated before are only reloaded.
+        """
+        from . import OneilError, RefactorError
+
+        self.revert()
+        try:
+            before = {}
+            for file in files:
+                if self.path(file) in self.originals:
+                    try:
+                        before[file] = _values(self.path(file))
+                    except OneilError:
+                        pass
+        finally:
+            for path, lines in self.edits.items():
+                _write(path, lines)
+
+        for file in files:
+            directory, name = os.path.split(self.path(file))
+            try:
+                if file not in before:
+                    _load(directory, name)
+                    continue
+                after = _values(self.path(file))
+            except OneilError as e:
+                self.revert()
+                raise e.with_note(f"The refactoring broke {file}, so all of the changes were reverted.")
+
+            changed = [ID for ID, (value, _) in before[file].items() if ID in after and after[ID][0] != value]
+            if changed:
+                self.revert()
+                error = RefactorError(file, f"The refactoring changed the results of {file}, so all of the changes were reverted.")
+                for ID in changed:
+                    error = error.with_note(f"{ID} was {before[file][ID][1]} and is now {after[ID][1]}")
+                raise error
+
+    def report(self):
+        for change in self.changes:
+            print(bcolors.OKGREEN + "  ~ " + bcolors.ENDC + change)
+
+def _resolve(model_file):
+    from . import RefactorError
+
+    if not model_file.endswith(".on"):
+        model_file += ".on"
+    if not os.path.exists(model_file):
+        raise RefactorError(model_file, f"Model {model_file} not found.")
+
+    directory, name = os.path.split(model_file)
+    return directory or ".", name
+
+def _load(directory, name):
+    from . import Model
+
+    with working_directory(directory):
+        return Model(name)
+
+def _values(path):
+    """
+    Evaluate a model without printing anything or changing the exit code, and return the
+    values of its parameters by ID: their min, max, and SI units, and how they're shown.
+    """
+    directory, name = os.path.split(path)
+    exit_code = console.exit_code
+    try:
+        with contextlib.redirect_stdout(io.StringIO()):
+            model = _load(directory, name)
+            model.build(quiet=True)
+    finally:
+        console.exit_code = exit_code
+    return {ID: ((p.min, p.max, p.units), p.human_readable()) for ID, p in model.parameters.items()}
+
+def _sibling_models(directory, exclude):
+    return [f for f in sorted(os.listdir(directory)) if f.endswith(".on") and f not in exclude]
+
+def _update_dependents(refactoring, model_name, moved, target, exclude):
+    """
+    Update the models that reach the moved IDs through a submodel symbol for
+    `model_name` so that they reach them through its new submodel `target` instead.
+    """
+    pattern = _include_pattern(model_name)
+    changed = []
+    for file in _sibling_models(refactoring.directory, exclude):
+        file_blocks = blocks(refactoring.read(file))
+        includes = [m for m in (pattern.search(b[0]) for b in file_blocks) if m]
+        text = "".join(line for b in file_blocks for line in b)
+
+        symbols = _symbols(file_blocks)
+        renames = {}
+        new_uses = []
+        for include in includes:
+            symbol = include.group("symbol")
+            referenced = [ID for ID in moved if _reference_pattern([f"{ID}.{symbol}"]).search(text)]
+            if not referenced:
+                continue
+
+            new_symbol = target if target not in symbols else f"{symbol}_{target}"
+            suffix = 2
+            while new_symbol in symbols:
+                new_symbol, suffix = f"{symbol}_{target}{suffix}", suffix + 1
+            symbols.add(new_symbol)
+
+            path = (include.group("source") + "." if include.group("source") else "") + model_name
+            new_uses.append(f"from {path} use {target} as {new_symbol}\n")
+            renames.update({f"{ID}.{symbol}": f"{ID}.{new_symbol}" for ID in referenced})
+
+        if not renames:
+            continue
+
+        file_blocks = [rewrite_block(b, renames) for b in file_blocks]
+        for line in new_uses:
+            _insert_use(file_blocks, line)
+        refactoring.write(file, [line for b in file_blocks for line in b], f"updated {len(renames)} reference(s)")
+        changed.append(file)
+
+    return changed
+
+def _update_designs(refactoring, parameter_ids, renames, exclude):
+    """
+    Rewrite design files for the refactored model (designs that only override its IDs).
+    """
+    for file in _sibling_models(refactoring.directory, exclude):
+        file_blocks = blocks(refactoring.read(file))
+        if not _is_design(file_blocks):
+            continue
+        design_ids = [block_id(b) for b in file_blocks if kind(b[0]) == "design"]
+        if not any(ID in renames for ID in design_ids) or not all(ID in parameter_ids for ID in design_ids):
+            continue
+
+        file_blocks = [rewrite_block(b, renames) for b in file_blocks]
+        refactoring.write(file, [line for b in file_blocks for line in b], "updated design overrides")
+
+def extract_section(model_file, section, new_file):
+    """
+    Move the parameters of a section into a new model, used by the original model.
+    """
+    from . import RefactorError, MATH_CONSTANTS
+
+    directory, name = _resolve(model_file)
+    new_name = os.path.basename(new_file if new_file.endswith(".on") else new_file + ".on")
+    stem = new_name[:-3]
+    if not re.search(r"^\w+$", stem):
+        raise RefactorError(new_name, "Model names must only contain word characters.")
+    if os.path.dirname(new_file) and os.path.abspath(os.path.dirname(new_file)) != os.path.abspath(directory):
+        raise RefactorError(new_name, f"The new model must be in the same directory as {name} so that it can be used.")
+    if os.path.exists(os.path.join(directory, new_name)):
+        raise RefactorError(new_name, f"{new_name} already exists.")
+
+    model = _load(directory, name)
+    if stem in model.submodels:
+        raise RefactorError(name, f"{name} already has a submodel \"{stem}\". Choose another name for the new model.")
+
+    moved = {ID for ID, p in model.parameters.items() if p.section == section}
+    if not moved:
+        raise RefactorError(name, f"Section \"{section}\" has no parameters.")
+
+    # The new model can't see the parameters left behind.
+    outside = {}
+    for ID in moved:
+        for arg in model.parameters[ID].args:
+            if "." not in arg and arg not in moved and arg not in MATH_CONSTANTS:
+                outside.setdefault(arg, []).append(ID)
+    if outside:
+        error = RefactorError(name, f"Parameters in section \"{section}\" depend on parameters outside of it.")
+        for arg, IDs in outside.items():
+            error = error.with_note(f"{arg} is used by {', '.join(sorted(IDs))}")
+        raise error
+
+    lines = _read(os.path.join(directory, name))
+    file_blocks = blocks(lines)
+    symbol_lines = {symbol: entry['line_no'] for symbol, entry in model.submodels.items()}
+
+    # Find the blocks of the section.
+    start = next((i for i, b in enumerate(file_blocks) if kind(b[0]) == "section" and b[0].replace("section", "").strip() == section), None)
+    if start is None:
+        raise RefactorError(name, f"Section \"{section}\" isn't in {name} (it may come from an included file), so it can't be extracted.")
+    end = next((i for i in range(start + 1, len(file_blocks)) if kind(file_blocks[i][0]) == "section"), len(file_blocks))
+
+    line_nos = [1]
+    for b in file_blocks:
+        line_nos.append(line_nos[-1] + len(b))
+
+    kept, extracted = [], [file_blocks[start]]
+    needed_symbols = set()
+    uses_python = False
+    for i in range(start + 1, end):
+        b = file_blocks[i]
+        block_kind = kind(b[0])
+        if block_kind in ("parameter", "alias"):
+            parameter = model.parameters[block_id(b)]
+            needed_symbols.update(arg.split(".")[1] for arg in parameter.args if "." in arg)
+            uses_python = uses_python or parameter.callable or (parameter.piecewise and any(eq.callable for eq, _ in parameter.equation))
+            extracted.append(b)
+        elif block_kind == "test":
+            test = next(t for t in model.tests if t.line_no == line_nos[i])
+            local_args = [arg for arg in test.args if "." not in arg and arg not in MATH_CONSTANTS]
+            if all(arg in moved for arg in local_args):
+                needed_symbols.update(arg.split(".")[1] for arg in test.args if "." in arg)
+                extracted.append(b)
+            else:
+                kept.append(b)
+        else:
+            extracted.append(b)
+
+    # Includes the new model needs: the submodels it references, the models their
+    # `from` paths start at, and the Python imports if it calls Python functions.
+    include_lines = set()
+    for symbol in needed_symbols:
+        entry = model.submodels[symbol]
+        include_lines.add(entry['line_no'])
+        if len(entry['path']) > 1:
+            root = next((e for e in model.submodels.values() if e['path'] == entry['path'][:1]), None)
+            if root:
+                include_lines.add(root['line_no'])
+    if uses_python:
+        include_lines.update(i + 1 for i, line in enumerate(lines) if kind(line) == "import")
+
+    new_lines = [lines[i - 1] for i in sorted(include_lines)]
+    if new_lines:
+        new_lines.append("\n")
+    new_lines += [line for b in extracted for line in b]
+    while not new_lines[-1].strip():
+        new_lines.pop()
+
+    # Rewrite the original model to use the new one.
+    renames = {ID: f"{ID}.{stem}" for ID in moved}
+    parent_blocks = [rewrite_block(b, renames) for b in file_blocks[:start] + kept + file_blocks[end:]]
+    _insert_use(parent_blocks, f"use {stem} as {stem}\n")
+
+    refactoring = Refactoring(directory)
+    refactoring.write(new_name, new_lines, f"created with {len(moved)} parameter(s) from section \"{section}\"")
+    refactoring.write(name, [line for b in parent_blocks for line in b], f"moved section \"{section}\" to {new_name}")
+
+    model_name = name[:-3]
+    changed = _update_dependents(refactoring, model_name, moved, stem, [name, new_name])
+    _update_designs(refactoring, set(model.parameters), renames, [name, new_name])
+
+    refactoring.verify([new_name, name] + changed)
+    return refactoring
+
+def _unique(ID, taken):
+    candidate, suffix = ID, 2
+    while candidate in taken:
+        candidate, suffix = f"{ID}{suffix}", suffix + 1
+    return candidate
+
+def _drop(file_blocks, block):
+    """
+    Drop a block, keeping the blank lines after it so that the spacing stays the same.
+    """
+    blank = [line for line in block[1:] if not line.strip()]
+    if file_blocks and blank:
+        file_blocks[-1] = file_blocks[-1] + blank
+
+def _without_from_prefix(line, prefix):
+    """
+    Shorten the source path of a `from` line by removing the model `prefix` (the part
+    of the path that ends with the inlined submodel).
+    """
+    match = re.search(r"^from\s+(?P<source>\w+(?:\.\w+)*)(?P<rest>\s+use\s.*)$", line, re.DOTALL)
+    if not match:
+        return line
+    source = match.group("source").split(".")
+    if source[:len(prefix)] != prefix:
+        return line
+    remaining = source[:len(prefix) - 1] + source[len(prefix):]
+    if not remaining:
+        return match.group("rest").lstrip()
+    return "from " + ".".join(remaining) + match.group("rest")
+
+def inline_submodel(model_file, symbol):
+    """
+    Pull the parameters of a submodel into the model that uses it, the inverse of
+    extract_section. IDs that collide with the model's own are renamed to `ID_symbol`.
+    """
+    from . import RefactorError, test_colon
+
+    directory, name = _resolve(model_file)
+    model = _load(directory, name)
+    if symbol not in model.submodels:
+        raise RefactorError(name, f"{name} has no submodel \"{symbol}\".")
+
+    entry = model.submodels[symbol]
+    sub_name = entry['path'][-1] + ".on"
+    submodel = _load(directory, sub_name)
+    sub_lines = _read(os.path.join(directory, sub_name))
+    sub_blocks = blocks(sub_lines)
+    parent_blocks = blocks(_read(os.path.join(directory, name)))
+
+    # Rename the submodel IDs that collide with the model's own.
+    taken = set(model.parameters) | set(submodel.parameters)
+    id_renames = {}
+    for ID in submodel.parameters:
+        if ID in model.parameters:
+            id_renames[ID] = _unique(f"{ID}_{symbol}", taken)
+            taken.add(id_renames[ID])
+
+    # Merge the submodel's includes into the model, reusing the model's symbols for the
+    # same submodels.
+    parent_symbols = {s: e['path'] for s, e in model.submodels.items() if s != symbol}
+    parent_imports = [b[0].strip() for b in parent_blocks if kind(b[0]) == "import"]
+    sub_renames = dict(id_renames)
+    new_includes = []
+    for sub_symbol, sub_entry in submodel.submodels.items():
+        line = sub_entry['line']
+        same = [s for s, path in parent_symbols.items() if path == sub_entry['path']]
+        if same:
+            new_symbol = same[0]
+        else:
+            new_symbol = sub_symbol
+            if new_symbol in parent_symbols:
+                new_symbol = _unique(f"{sub_symbol}_{symbol}", set(parent_symbols) | {symbol})
+                line = re.sub(r"\bas\s+" + sub_symbol + r"\s*$", f"as {new_symbol}", line.rstrip("\n")) + "\n"
+            parent_symbols[new_symbol] = sub_entry['path']
+            new_includes.append(line if line.endswith("\n") else line + "\n")
+        if new_symbol != sub_symbol:
+            for p in list(submodel.parameters.values()) + list(submodel.tests):
+                sub_renames.update({arg: arg.split(".")[0] + "." + new_symbol for arg in p.args if arg.endswith("." + sub_symbol)})
+    new_includes += [b[0] for b in sub_blocks if kind(b[0]) == "import" and b[0].strip() not in parent_imports]
+
+    # Test inputs passed to the submodel become part of its tests.
+    inputs = {k: v.strip() if re.search(r"^\w+(\.\w+)?$", v.strip()) else "(" + v.strip() + ")" for k, v in entry['inputs'].items()}
+
+    line_no = 1
+    inlined = []
+    for b in sub_blocks:
+        block_line_no, line_no = line_no, line_no + len(b)
+        block_kind = kind(b[0])
+        if block_kind in ("use", "from", "import", "include", "header"):
+            # Notes on the submodel itself would attach to the model's last parameter,
+            # so keep them as comments.
+            notes = b if block_kind == "header" else b[1:]
+            if any(line.strip() for line in notes):
+                inlined.append(["# " + line.strip() + "\n" if line.strip() else line for line in notes])
+            continue
+        b = rename_definition(rewrite_block(b, sub_renames), id_renames)
+        if block_kind == "test" and inputs:
+            refs = next(t.refs for t in submodel.tests if t.line_no == block_line_no)
+            if refs and all(ref in inputs for ref in refs):
+                colon = test_colon(b[0])
+                b = [b[0][:colon].split("{")[0].rstrip() + " " + rewrite_expression(b[0][colon:], inputs)] + b[1:]
+        inlined.append(b)
+
+    # Keep the inlined parameters out of the model's last section.
+    first = next((b for b in inlined if kind(b[0]) not in ("header", "comment")), None)
+    if any(kind(b[0]) == "section" for b in parent_blocks) and (first is None or kind(first[0]) != "section"):
+        inlined.insert(0, [f"section {entry['path'][-1]}\n"])
+
+    # Rewrite the model: drop the include, reach the submodel IDs directly, and shorten
+    # `from` paths that went through the submodel.
+    parent_renames = {f"{ID}.{symbol}": id_renames.get(ID, ID) for ID in submodel.parameters}
+    updated = []
+    for b in parent_blocks:
+        if kind(b[0]) in ("use", "from") and b[0].split("as")[-1].strip() == symbol:
+            _drop(updated, b)
+            continue
+        if kind(b[0]) == "from":
+            b = [_without_from_prefix(b[0], entry['path'])] + b[1:]
+        updated.append(rewrite_block(b, parent_renames))
+    for line in new_includes:
+        _insert_use(updated, line)
+    if updated and updated[-1][-1].strip():
+        updated[-1] = updated[-1] + ["\n"]
+    updated += inlined
+
+    refactoring = Refactoring(directory)
+    refactoring.write(name, [line for b in updated for line in b], f"inlined {len(submodel.parameters)} parameter(s) from {sub_name}" + (f" ({len(id_renames)} renamed)" if id_renames else ""))
+
+    # Models that reached the submodel through this model (`from model use submodel as s`)
+    # now reach its IDs through their own symbol for the model.
+    model_name = name[:-3]
+    sub_stem = entry['path'][-1]
+    changed = []
+    for file in _sibling_models(directory, [name, sub_name]):
+        file_blocks = blocks(refactoring.read(file))
+        symbols = _symbols(file_blocks)
+
+        # The file's symbols for the model, by the source path they were used from.
+        model_symbols = {}
+        for b in file_blocks:
+            match = _include_pattern(model_name).search(b[0])
+            if match:
+                model_symbols[match.group("source") or ""] = match.group("symbol")
+
+        renames, new_uses, rewritten = {}, [], []
+        for b in file_blocks:
+            match = _include_pattern(sub_stem).search(b[0])
+            source = (match.group("source") or "") if match else ""
+            if match and source.split(".")[-1] == model_name:
+                prefix = source.rpartition(".")[0]
+                if prefix not in model_symbols:
+                    model_symbols[prefix] = _unique(model_name, symbols)
+                    symbols.add(model_symbols[prefix])
+                    new_uses.append((f"from {prefix} use " if prefix else "use ") + f"{model_name} as {model_symbols[prefix]}\n")
+                renames.update({f"{ID}.{match.group('symbol')}": f"{id_renames.get(ID, ID)}.{model_symbols[prefix]}" for ID in submodel.parameters})
+                _drop(rewritten, b)
+                continue
+            if kind(b[0]) == "from":
+                for prefix in list(model_symbols):
+                    b = [_without_from_prefix(b[0], (prefix.split(".") if prefix else []) + [model_name, sub_stem])] + b[1:]
+            rewritten.append(b)
+
+        text = "".join(line for b in rewritten for line in b)
+        renames = {old: new for old, new in renames.items() if _reference_pattern([old]).search(text)}
+        if not renames and rewritten == file_blocks:
+            continue
+
+        rewritten = [rewrite_block(b, renames) for b in rewritten]
+        for line in new_uses:
+            _insert_use(rewritten, line)
+        refactoring.write(file, [line for b in rewritten for line in b], f"updated {len(renames)} reference(s)")
+        changed.append(file)
+
+    _update_designs(refactoring, set(model.parameters) | set(parent_renames), parent_renames, [name, sub_name])
+
+    refactoring.verify([name] + changed)
+    return refactoring
+
+def _units_span(line):
+    """
+    Where the units of a parameter or design line are (after the colon that follows
+    the assignment), or None if it has none.
+    """
+    block_kind = kind(line)
+    if block_kind == "parameter":
+        start = line.index("=", line.index(":") + 1)
+    elif block_kind == "design":
+        start = line.index("=")
+    else:
+        return None
+    colon = line.find(":", start)
+    if colon == -1:
+        return None
+    end = len(line.rstrip())
+    while colon + 1 < end and line[colon + 1] in " \t":
+        colon += 1
+    return colon + 1, end
+
+def canonical_units(paths):
+    """
+    Replace natural language unit names (`sec`, `meters/second`) with the symbols they
+    stand for (`s`, `m/s`) in every model of a workspace. Only the units of parameter
+    and design lines change, so formatting, notes, and results stay the same.
+    """
+    from . import units as un
+    from .stats import workspace_files
+
+    refactoring = Refactoring(".")
+    changed = []
+    for file in workspace_files(paths):
+        lines = refactoring.read(file)
+        count = 0
+        for i, line in enumerate(lines):
+            span = _units_span(line) if line.strip() and line[0] not in (" ", "\t") else None
+            if span is None:
+                continue
+            start, end = span
+            units = un.canonical(line[start:end])
+            if units != line[start:end]:
+                lines[i] = line[:start] + units + line[end:]
+                count += 1
+        if count:
+            refactoring.write(file, lines, f"{count} unit name(s) replaced")
+            changed.append(file)
+
+    refactoring.verify(changed)
+    return refactoring
+
+def run(args):
+    """
+    Run a refactoring from the command-line arguments following `oneil refactor`.
+    """
+    if not args or args[0] not in COMMANDS:
+        print("Usage:")
+        for usage in USAGE.values():
+            print("  " + usage)
+        return False
+
+    command, args = args[0], args[1:]
+    if command == "extract-section":
+        if len(args) != 3:
+            print("Usage: " + USAGE[command])
+            return False
+        refactoring = extract_section(*args)
+    elif command == "inline":
+        if len(args) != 2:
+            print("Usage: " + USAGE[command])
+            return False
+        refactoring = inline_submodel(*args)
+    elif command == "canonical-units":
+        refactoring = canonical_units(args)
+
+    print(f"Refactored ({command}):")
+    refactoring.report()
+    return True
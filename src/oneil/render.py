@@ -0,0 +1,51 @@
+"""
+Source snippets for errors that involve several places in the models, with each place
+underlined and labeled, so one error can show an operator, both of its operands, and the
+units they're declared with:
+
+     --> engine.on
+      |
+    1 | Mass: m = 3 :kg
+      |              -- m is declared in kg
+    4 | Impulse: J = F*t_b + m :N*s
+      |              ----- in (kg m)/s
+      |                    ^ added here
+      |                      - in kg
+
+Errors with labels (see UnitEvaluationError.labels()) are printed with a snippet after
+their message. The primary label, where the error is, is underlined with `^` and the
+others with `-`. Labels are grouped by file, and by line in each file.
+"""
+
+from . import bcolors
+from . import positions
+
+def _underline(line, label):
+    # The spaces up to a label (keeping tabs so the marks line up) and its marks
+    start = label["start_column"] - 1
+    end = max(label["end_column"] - 1, start + 1)
+    padding = "".join(character if character == "\t" else " " for character in line[:start])
+    mark, color = ("^", bcolors.FAIL) if label["primary"] else ("-", bcolors.OKBLUE)
+    return f"{padding}{color}{mark * (end - start)} {label['message']}{bcolors.ENDC}"
+
+def snippet(labels):
+    """
+    The lines of a snippet of the model files showing the labels (dicts with the file,
+    the line, the 1-based columns, with `end_column` exclusive, the message, and whether
+    the label is primary), in the order they're given.
+    """
+    if not labels:
+        return []
+    width = len(str(max(label["line"] for label in labels)))
+    gutter = f"{bcolors.OKBLUE}{' ' * width} |{bcolors.ENDC}"
+    lines = []
+    files = list(dict.fromkeys(label["file"] for label in labels))
+    for file in files:
+        lines += [f"{' ' * width}{bcolors.OKBLUE}--> {bcolors.ENDC}{file}", gutter]
+        in_file = [label for label in labels if label["file"] == file]
+        for line_no in sorted(set(label["line"] for label in in_file)):
+            line = positions.source_line(file, line_no)
+            lines.append(f"{bcolors.OKBLUE}{line_no:>{width}} |{bcolors.ENDC} {line}")
+            for label in sorted((label for label in in_file if label["line"] == line_no), key=lambda label: label["start_column"]):
+                lines.append(f"{gutter} {_underline(line, label)}")
+    return lines
@@ -0,0 +1,59 @@
+"""
+Security review mode for models from third parties (`--no-python` and
+`--no-fs-outside-root`), so they can be parsed, checked, and partially evaluated without
+running their Python or reading files they shouldn't.
+
+With `no_python`, `import` declarations don't run their modules. The model still
+parses: each function the model calls from them is replaced by one that raises a
+PermissionError, so the parameters that use it fail with an ImportedFunctionError (or
+use their fallback) when they're evaluated. `@mirror` doesn't import the Python files it
+names either.
+
+With `no_fs_outside_root`, models can only reach files inside the workspace root (the
+working directory, where submodels are found): `use`, `from`, `include`, and `@mirror`
+paths that resolve outside it, including through symlinks, are refused.
+"""
+
+import os
+
+no_python = False
+no_fs_outside_root = False
+
+class RefusedImport:
+    """
+    A Python import declaration that wasn't run, in place of its module in a model's
+    imports.
+    """
+    def __init__(self, module, file, line_no):
+        self.module = module
+        self.file = file
+        self.line_no = line_no
+
+def refused_function(name, refused):
+    """
+    A stand-in for a function from imports that weren't run, which raises a
+    PermissionError when it's called.
+    """
+    modules = ", ".join(f"{i.module}.py" for i in refused)
+    def refused_call(*args, **kwargs):
+        raise PermissionError(f"{name}() would run Python from {modules}, which --no-python doesn't allow.")
+    refused_call.__name__ = refused_call.__qualname__ = name
+    return refused_call
+
+def root():
+    return os.path.realpath(os.getcwd())
+
+def outside_root(path):
+    """
+    Whether a file is refused because it's outside the workspace root.
+    """
+    if not no_fs_outside_root:
+        return False
+    try:
+        return os.path.commonpath([root(), os.path.realpath(path)]) != root()
+    except ValueError:
+        # On another drive
+        return True
+
+def refusal(reference):
+    return f"\"{reference}\" is outside the workspace root ({root()}), which --no-fs-outside-root doesn't allow."
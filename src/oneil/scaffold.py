@@ -0,0 +1,155 @@
+"""
+New models from templates (`oneil new <template> <name>`), so the models of a team's
+library start with the same structure: a header note, model annotations, standard
+sections, and an example parameter and test to replace.
+
+Oneil has a few templates built in (TEMPLATES). A project adds its own, or replaces
+the built-in ones, with `template:` lines in its manifest, naming the template and its
+file (relative to the manifest):
+
+    template: component templates/component.on
+    template: radio templates/radio.on
+
+Templates are model files with placeholders, which are replaced when the model is made:
+
+    {{name}}      the new model's name (`thermal_radiator`)
+    {{title}}     the name as a title (`Thermal radiator`)
+    {{template}}  the template's name (`component`)
+    {{date}}      today's date (`2026-10-15`)
+"""
+
+import datetime
+import os
+import re
+
+PREFIX = "template:"
+
+TEMPLATES = {
+    "component": ("A part of a system, with its inputs, outputs, and tests.", """\
+    {{title}}: describe what this {{template}} models, its assumptions, and its sources.
+    Created {{date}}.
+
+@tags({{template}})
+
+section Inputs
+Mass: m = 1 :kg
+    The mass of this {{template}}.
+Mass margin: f_m = 0.2
+    The margin held for growth of the mass.
+
+section Outputs
+Mass with margin: m_max = m*(1 + f_m) :kg
+    The mass to carry in budgets.
+
+test "the margin adds mass": m_max > m
+"""),
+    "system": ("A system made of component models, with its budgets.", """\
+    {{title}}: describe what this {{template}} does and the components it's made of.
+    Created {{date}}.
+
+@tags({{template}})
+
+# use <component> as <symbol>
+
+section Budgets
+Mass allocation: m_alloc = 10 :kg
+    The mass allocated to this {{template}}.
+Mass: m = 8 :kg
+    Replace with the sum of the components' masses (e.g. m_max.c1 + m_max.c2).
+
+test "mass within allocation": m <= m_alloc
+"""),
+    "design": ("Design values for a model, for `oneil <design>@<model>`.", """\
+# {{title}}: design values, created {{date}}.
+# Give each value a reason in a note.
+
+m = 1 :kg
+    Replace with the design values of the model's independent parameters.
+"""),
+}
+
+PLACEHOLDER = re.compile(r"\{\{\s*(\w*)\s*\}\}")
+
+def _title(name):
+    words = name.replace("_", " ").strip()
+    return words[:1].upper() + words[1:]
+
+def read_templates(manifests):
+    """
+    The templates in the `template:` lines of manifest files, by name, as (description,
+    path) pairs.
+    """
+    from . import ConfigError, _manifest_lines
+
+    templates = {}
+    for manifest in manifests:
+        for line in _manifest_lines(manifest):
+            if not line.startswith(PREFIX):
+                continue
+            words = line[len(PREFIX):].split(None, 1)
+            if len(words) != 2:
+                raise ConfigError(manifest, "Template lines must be of the form \"template: <name> <file>\".")
+            name, path = words
+            templates[name] = (f"From {path}.", os.path.join(os.path.dirname(manifest), path))
+    return templates
+
+def templates(manifests=()):
+    """
+    The templates available, by name, as (description, text) pairs: the built-in ones and
+    those of the manifests, which take precedence.
+    """
+    from . import ScaffoldError
+
+    found = dict(TEMPLATES)
+    for name, (description, path) in read_templates(manifests).items():
+        if not os.path.exists(path):
+            raise ScaffoldError(name, f"Template file {path} not found.")
+        with open(path, "r") as f:
+            found[name] = (description, f.read())
+    return found
+
+def render(text, template, name, date=None):
+    """
+    A template's text with its placeholders replaced. Unknown placeholders are a
+    ScaffoldError.
+    """
+    from . import ScaffoldError
+
+    values = {
+        "name": name,
+        "title": _title(name),
+        "template": template,
+        "date": (date or datetime.date.today()).isoformat(),
+    }
+
+    def replace(match):
+        if match.group(1) not in values:
+            raise ScaffoldError(template, f"Unknown placeholder \"{match.group()}\". Placeholders are: {', '.join('{{' + key + '}}' for key in values)}.")
+        return values[match.group(1)]
+
+    return PLACEHOLDER.sub(replace, text)
+
+def new(template, name, output=None, manifests=()):
+    """
+    Write a new model (output, or `<name>.on`) from a template and return its path.
+    """
+    from . import ScaffoldError
+
+    if not re.fullmatch(r"\w+", name):
+        raise ScaffoldError(template, f"\"{name}\" isn't a valid model name. Use letters, digits, and underscores, so other models can use it.")
+    available = templates(manifests)
+    if template not in available:
+        raise ScaffoldError(template, f"Unknown template. Templates are: {', '.join(sorted(available))}.")
+    output = output or name + ".on"
+    if os.path.exists(output):
+        raise ScaffoldError(template, f"{output} already exists.")
+    text = render(available[template][1], template, name)
+    with open(output, "w") as f:
+        f.write(text)
+    return output
+
+def print_templates(manifests=()):
+    available = templates(manifests)
+    width = max(len(name) for name in available)
+    for name in sorted(available):
+        print(f"{name:<{width}}  {available[name][0]}")
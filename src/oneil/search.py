@@ -0,0 +1,123 @@
+"""
+Search over the models in a workspace (`oneil search`).
+
+Searches the IDs, labels, and notes of parameters, the names and notes of tests, and
+model notes, reporting each hit with its file, line, and section. Parameters found by
+ID also list where other models use them through a submodel (`ID.symbol`).
+"""
+
+import os
+
+from . import bcolors
+from . import console
+from . import messages
+from .stats import working_directory, workspace_files
+
+def _matches(query, text):
+    return bool(text) and query.lower() in text.lower()
+
+def _hit(file, line, section, kind, text):
+    # Lines from included fragments are reported in the fragment.
+    if getattr(line, "fragment", None):
+        file = os.path.normpath(os.path.join(os.path.dirname(file), line.fragment))
+    return {"file": file, "line": int(line) if line is not None else None, "section": section or None, "kind": kind, "text": text.strip()}
+
+def _model_note_hits(query, file, name):
+    from . import read_model_lines
+
+    hits = []
+    for line, line_no in read_model_lines(name):
+        if line.strip() and line[0] not in (" ", "\t", "#"):
+            break
+        if _matches(query, line):
+            hits.append(_hit(file, line_no, None, "model note", line))
+    return hits
+
+def _model_hits(query, file, model):
+    hits = []
+    name = os.path.basename(file)
+    hits += _model_note_hits(query, file, name)
+
+    for ID, parameter in model.parameters.items():
+        if parameter.model != model.name:
+            continue
+        line_no = parameter.line_no['model line'] if isinstance(parameter.line_no, dict) else parameter.line_no
+        if _matches(query, ID):
+            hits.append(_hit(file, line_no, parameter.section, "id", f"{ID} ({parameter.name})") | {"id": ID, "model": os.path.abspath(file)})
+        elif _matches(query, parameter.name):
+            hits.append(_hit(file, line_no, parameter.section, "label", f"{parameter.name} ({ID})"))
+        for note, note_line in zip(parameter.notes, parameter.note_lines):
+            if _matches(query, note):
+                hits.append(_hit(file, note_line, parameter.section, "note", f"{ID}: {note}"))
+
+    for test in model.tests:
+        if _matches(query, test.name):
+            hits.append(_hit(file, test.line_no, test.section, "test", f"test {test.label()}"))
+        for note, note_line in zip(test.notes, test.note_line_nos):
+            if _matches(query, note):
+                hits.append(_hit(file, note_line, test.section, "note", f"test {test.label()}: {note}"))
+
+    return hits
+
+def _references(hits, models):
+    """
+    Where the parameters found by ID are used by other models, through their submodels.
+    """
+    defined = {(hit["model"], hit["id"]) for hit in hits if hit["kind"] == "id"}
+    references = []
+    for file, model in models.items():
+        for symbol, entry in model.submodels.items():
+            try:
+                submodel = entry.get('model') or model._retrieve_model(entry['path'])
+            except Exception:
+                continue
+            for ID in [ID for path, ID in defined if path == submodel.model_filename]:
+                reference = f"{ID}.{symbol}"
+                for user_ID, parameter in model.parameters.items():
+                    if reference in parameter.args and parameter.model == model.name:
+                        line_no = parameter.line_no['model line'] if isinstance(parameter.line_no, dict) else parameter.line_no
+                        references.append(_hit(file, line_no, parameter.section, "reference", f"{user_ID} uses {reference}"))
+                for test in model.tests:
+                    if reference in test.args:
+                        references.append(_hit(file, test.line_no, test.section, "reference", f"test {test.label()} uses {reference}"))
+    return references
+
+def search(query, paths):
+    """
+    Search the models in the workspace (parse only, no evaluation). Returns the hits
+    (each with its file, line, section, kind, and text) and the errors of models that
+    couldn't be loaded.
+    """
+    from . import Model, OneilError
+
+    models = {}
+    errors = []
+    hits = []
+    for file in workspace_files(paths):
+        directory, name = os.path.split(file)
+        try:
+            with working_directory(directory):
+                models[file] = Model(name)
+                hits += _model_hits(query, file, models[file])
+        except OneilError as e:
+            errors.append(e.with_note(messages.text("while-searching", "While searching {file}", file=file)))
+
+    return hits + _references(hits, models), errors
+
+def run(query, paths):
+    """
+    Print the hits for the query, like grep: `file:line [section] kind: text`. Returns
+    the number of hits.
+    """
+    hits, errors = search(query, paths)
+    for error in errors:
+        console.print_error(error)
+
+    for hit in sorted(hits, key=lambda h: (h["file"], h["line"] or 0)):
+        location = f"{hit['file']}:{hit['line']}" if hit["line"] is not None else hit["file"]
+        section = f" [{hit['section']}]" if hit["section"] else ""
+        print(f"{bcolors.OKBLUE}{location}{bcolors.ENDC}{section} {hit['kind']}: {hit['text']}")
+
+    if not console.quiet and not hits:
+        print(messages.text("search-no-matches", "No matches for \"{query}\".", query=query))
+    return len(hits)
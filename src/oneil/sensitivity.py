@@ -0,0 +1,348 @@
+"""
+Sensitivities of parameters to their inputs (`oneil sensitivity`), for finding which
+inputs drive a performance parameter.
+
+The derivatives are exact: the parameters' equations are evaluated with dual numbers
+(forward-mode automatic differentiation), which carry each value's derivatives with
+respect to every input through the same equations Oneil evaluates, at the nominal values
+of the inputs (the midpoints of their ranges). Piecewise parameters are differentiated
+through the branch that applies, with a warning when an input is at the boundary between
+branches, where the derivative is one-sided. Breakout functions can't be differentiated,
+so parameters that depend on one have no sensitivities.
+
+Derivatives are in base units. The elasticity of a parameter y to an input x,
+(dy/dx)*(x/y), is the relative change in y for a relative change in x, so it compares
+inputs with different units.
+"""
+
+import math
+import os
+import re
+
+from beautifultable import BeautifulTable
+
+from . import bcolors
+from .stats import working_directory
+from .symbolic import OPAQUE, _units
+
+# How close (relative to their size) two compared values have to be to be at a boundary
+BOUNDARY_TOLERANCE = 1e-9
+
+class NotDifferentiable(Exception):
+    pass
+
+class Dual:
+    """
+    A value with its derivatives with respect to the inputs (by name).
+    """
+    __slots__ = ("value", "derivatives")
+
+    def __init__(self, value, derivatives=None):
+        self.value = value
+        self.derivatives = derivatives or {}
+
+    def _chain(self, value, slope):
+        # f(self), where slope is f'(self.value)
+        return Dual(value, {k: slope * d for k, d in self.derivatives.items()})
+
+    def _combine(self, other, value, d_self, d_other):
+        # f(self, other), where d_self and d_other are its partial derivatives
+        derivatives = {k: d_self * d for k, d in self.derivatives.items()}
+        for k, d in other.derivatives.items():
+            derivatives[k] = derivatives.get(k, 0) + d_other * d
+        return Dual(value, derivatives)
+
+    def __add__(self, other):
+        other = lift(other)
+        return self._combine(other, self.value + other.value, 1, 1)
+
+    def __sub__(self, other):
+        other = lift(other)
+        return self._combine(other, self.value - other.value, 1, -1)
+
+    def __mul__(self, other):
+        other = lift(other)
+        return self._combine(other, self.value * other.value, other.value, self.value)
+
+    def __truediv__(self, other):
+        other = lift(other)
+        if other.value == 0:
+            raise ZeroDivisionError("division by zero")
+        return self._combine(other, self.value / other.value, 1 / other.value, -self.value / other.value ** 2)
+
+    def __pow__(self, other):
+        other = lift(other)
+        value = self.value ** other.value
+        d_self = other.value * self.value ** (other.value - 1) if other.value != 0 else 0
+        d_other = value * math.log(self.value) if other.derivatives and self.value > 0 else 0
+        return self._combine(other, value, d_self, d_other)
+
+    __radd__ = __add__
+    __rmul__ = __mul__
+    # `//` is division with standard (not extreme) math, which is the same for one value.
+    __floordiv__ = __truediv__
+
+    def __rsub__(self, other):
+        return lift(other) - self
+
+    def __rtruediv__(self, other):
+        return lift(other) / self
+
+    __rfloordiv__ = __rtruediv__
+
+    def __rpow__(self, other):
+        return lift(other) ** self
+
+    def __neg__(self):
+        return self._chain(-self.value, -1)
+
+    def __pos__(self):
+        return self
+
+    def __abs__(self):
+        return self._chain(abs(self.value), 1 if self.value >= 0 else -1)
+
+    def _compare(self, other, compare):
+        if isinstance(other, str):
+            # Discrete values are strings, which a number never equals.
+            return compare is _ne if compare in (_eq, _ne) else NotImplemented
+        other = lift(other)
+        if abs(self.value - other.value) <= BOUNDARY_TOLERANCE * max(1, abs(self.value), abs(other.value)):
+            boundaries.append((self, other))
+        return compare(self.value, other.value)
+
+    def __lt__(self, other):
+        return self._compare(other, lambda a, b: a < b)
+
+    def __le__(self, other):
+        return self._compare(other, lambda a, b: a <= b)
+
+    def __gt__(self, other):
+        return self._compare(other, lambda a, b: a > b)
+
+    def __ge__(self, other):
+        return self._compare(other, lambda a, b: a >= b)
+
+    def __eq__(self, other):
+        return self._compare(other, _eq)
+
+    def __ne__(self, other):
+        return self._compare(other, _ne)
+
+    __hash__ = None
+
+    def __repr__(self):
+        return f"Dual({self.value}, {self.derivatives})"
+
+def _eq(a, b):
+    return a == b
+
+def _ne(a, b):
+    return a != b
+
+# The comparisons of values that were equal (within BOUNDARY_TOLERANCE) since the list
+# was last cleared, for finding piecewise conditions at a boundary
+boundaries = []
+
+def lift(value):
+    return value if isinstance(value, Dual) else Dual(value)
+
+def _function(f, slope):
+    # A function of one value, with its derivative
+    def dual(x):
+        x = lift(x)
+        return x._chain(f(x.value), slope(x.value))
+    return dual
+
+def _pick(choose):
+    # min() and max(): of one value, the value itself; of two, the one chosen
+    def dual(a, b=None):
+        if b is None:
+            return a
+        a, b = lift(a), lift(b)
+        smaller = a < b  # Records a tie as a boundary.
+        return (a if smaller else b) if choose is min else (b if smaller else a)
+    return dual
+
+def _midpoint(a, b):
+    return (lift(a) + lift(b)) / 2
+
+# Dual versions of the functions equations can use (see oneil.FUNCTIONS)
+FUNCTIONS = {
+    "sin": _function(math.sin, math.cos),
+    "cos": _function(math.cos, lambda x: -math.sin(x)),
+    "tan": _function(math.tan, lambda x: 1 / math.cos(x) ** 2),
+    "asin": _function(math.asin, lambda x: 1 / math.sqrt(1 - x ** 2)),
+    "acos": _function(math.acos, lambda x: -1 / math.sqrt(1 - x ** 2)),
+    "atan": _function(math.atan, lambda x: 1 / (1 + x ** 2)),
+    "sinh": _function(math.sinh, math.cosh),
+    "cosh": _function(math.cosh, math.sinh),
+    "tanh": _function(math.tanh, lambda x: 1 / math.cosh(x) ** 2),
+    "sqrt": _function(math.sqrt, lambda x: 0.5 / math.sqrt(x) if x else math.inf),
+    "abs": abs,
+    "log": _function(math.log, lambda x: 1 / x),
+    "ln": _function(math.log, lambda x: 1 / x),
+    "log2": _function(math.log2, lambda x: 1 / (x * math.log(2))),
+    "log10": _function(math.log10, lambda x: 1 / (x * math.log(10))),
+    "floor": _function(math.floor, lambda x: 0),
+    "ceiling": _function(math.ceil, lambda x: 0),
+    "sign": _function(lambda x: (x > 0) - (x < 0), lambda x: 0),
+    "min": _pick(min),
+    "max": _pick(max),
+    "extent": lambda a, b=None: abs(a) if b is None else _pick(max)(abs(a), abs(b)),
+    # A single value has no range, and is its own midpoint.
+    "range": lambda x: Dual(0),
+    "mid": lambda x: x,
+    "strip": lambda x: x,
+    "lerp": lambda a, b, t: a + (b - a)*t,
+    "map_range": lambda x, in_min, in_max, out_min, out_max: out_min + (x - in_min)*(out_max - out_min)/(in_max - in_min),
+    "mnmx": _midpoint,
+    "mxmn": _midpoint,
+}
+
+def sensitivities(model, IDs=None):
+    """
+    The sensitivities of parameters (by ID or label; the performance parameters by
+    default) to the independent parameters they depend on, at the inputs' nominal
+    values. For each parameter: its ID, nominal value and base units, the inputs (named
+    like `ID` or `ID.submodel`) with the derivative, its base units, and the elasticity,
+    sorted by the size of the elasticity, and warnings about piecewise boundaries. If
+    the parameter can't be differentiated, there are no inputs and "error" says why.
+    """
+    from . import FUNCTIONS as NAMES, MATH_CONSTANTS, minus
+
+    environment = {NAMES[name]: function for name, function in FUNCTIONS.items() if name in NAMES} | MATH_CONSTANTS | {"minus": minus}
+    IDs = [model.lookup(ID) for ID in IDs] if IDs else [ID for ID, parameter in model.parameters.items() if parameter.performance]
+    # The base units and nominal value of each input, by name
+    inputs = {}
+    # (value, warnings) of each parameter evaluated so far, by id()
+    memo = {}
+
+    def evaluate(scope, chain, parameter, expression, args, warnings, trail):
+        # An equation of a parameter in scope, a (sub)model whose submodel symbols from
+        # the top model are chain.
+        values = {}
+        for arg in sorted(dict.fromkeys(args), key=len, reverse=True):
+            if "." in arg:
+                ID, symbol = arg.split(".", 1)
+                submodel = scope._retrieve_model(scope.submodels[symbol]["path"])
+                name = "_" + arg.replace(".", "__")
+                expression = re.sub(r"(?<!\w)" + re.escape(arg), name, expression)
+                values[name] = value(submodel, [symbol] + chain, submodel.parameters[ID], warnings, trail)
+            elif arg in scope.parameters:
+                values[arg] = value(scope, chain, scope.parameters[arg], warnings, trail)
+        try:
+            return eval(expression, dict(environment), values)
+        except NotDifferentiable:
+            raise
+        except Exception as e:
+            raise NotDifferentiable(f"{parameter.id} can't be differentiated ({type(e).__name__}: {e}).")
+
+    def part(scope, chain, parameter, equation, warnings, trail):
+        # A branch of a piecewise parameter or one side of a min/max equation
+        if equation.independent:
+            return Dual((equation.min + equation.max) / 2) if isinstance(equation.min, (int, float)) else equation.min
+        return evaluate(scope, chain, parameter, equation.equation, equation.args, warnings, trail)
+
+    def value(scope, chain, parameter, warnings, trail):
+        name = ".".join([parameter.id] + chain)
+        if id(parameter) in memo:
+            result, found = memo[id(parameter)]
+            warnings.extend(warning for warning in found if warning not in warnings)
+            return result
+        if id(parameter) in trail:
+            raise NotDifferentiable(f"{name} depends on itself.")
+        trail = trail | {id(parameter)}
+        own = []
+
+        if parameter.independent:
+            if isinstance(parameter.min, (int, float)) and not isinstance(parameter.min, bool):
+                inputs[name] = (parameter.units, (parameter.min + parameter.max) / 2)
+                result = Dual(inputs[name][1], {name: 1.0})
+            else:
+                result = parameter.min
+        elif parameter.callable:
+            raise NotDifferentiable(f"{name} is a {OPAQUE['callable']} ({parameter.equation.__name__}), which can't be differentiated.")
+        elif parameter.pointer:
+            result = evaluate(scope, chain, parameter, parameter.equation, [parameter.equation], own, trail)
+        elif parameter.piecewise:
+            result, boundary = None, False
+            for equation, condition in parameter.equation:
+                del boundaries[:]
+                true = part(scope, chain, parameter, condition, own, trail)
+                if boundaries and not boundary:
+                    boundary = True
+                    own.append(f"at the boundary between branches of {name} ({condition.equation}), so the derivatives are one-sided.")
+                if true:
+                    result = part(scope, chain, parameter, equation, own, trail)
+                    break
+            if result is None:
+                raise NotDifferentiable(f"No branch of {name} applies at the nominal values.")
+        elif parameter.minmax_equation:
+            low, high = parameter.equation
+            result = _midpoint(part(scope, chain, parameter, low, own, trail), part(scope, chain, parameter, high, own, trail))
+        else:
+            result = evaluate(scope, chain, parameter, parameter.equation, parameter.args, own, trail)
+
+        memo[id(parameter)] = (result, own)
+        warnings.extend(warning for warning in own if warning not in warnings)
+        return result
+
+    report = []
+    for ID in IDs:
+        parameter = model.parameters[ID]
+        entry = {"id": ID, "value": None, "units": _units(parameter.units), "inputs": [], "warnings": [], "error": None}
+        try:
+            result = lift(value(model, [], parameter, entry["warnings"], frozenset()))
+        except NotDifferentiable as e:
+            entry["error"] = str(e)
+            report.append(entry)
+            continue
+
+        entry["value"] = result.value
+        for name, derivative in result.derivatives.items():
+            source, nominal = inputs[name]
+            quotient = {unit: parameter.units.get(unit, 0) - source.get(unit, 0) for unit in set(parameter.units) | set(source)}
+            elasticity = derivative * nominal / result.value if result.value else None
+            entry["inputs"].append({"input": name, "derivative": derivative, "units": _units({u: p for u, p in quotient.items() if p}), "elasticity": elasticity})
+        entry["inputs"].sort(key=lambda i: -abs(i["elasticity"]) if i["elasticity"] is not None else 0)
+        report.append(entry)
+    return report
+
+def print_sensitivities(model, IDs=None, sigfigs=4):
+    """
+    Print the sensitivities of parameters (see sensitivities()) as a table.
+    """
+    report = sensitivities(model, IDs)
+    if not report:
+        print("No performance parameters. Give the parameters to find the sensitivities of.")
+        return report
+
+    table = BeautifulTable()
+    table.columns.header = ["Parameter", "Input", "Derivative", "Elasticity"]
+    for entry in report:
+        for sensitivity in entry["inputs"]:
+            derivative = f"{sensitivity['derivative']:.{sigfigs}g}" + (f" {sensitivity['units']}" if sensitivity["units"] else "")
+            elasticity = f"{sensitivity['elasticity']:.{sigfigs}g}" if sensitivity["elasticity"] is not None else "-"
+            table.append_row([entry["id"], sensitivity["input"], derivative, elasticity])
+    if any(entry["inputs"] for entry in report):
+        print(table)
+
+    for entry in report:
+        if entry["error"]:
+            print(f"{bcolors.error('ERROR')} No sensitivities for {entry['id']}: {entry['error']}")
+        elif not entry["inputs"]:
+            print(f"{entry['id']} doesn't depend on any inputs.")
+        for warning in entry["warnings"]:
+            print(f"{bcolors.YELLOW}{entry['id']}: {warning}{bcolors.ENDC}")
+    return report
+
+def run(inp, IDs=None):
+    """
+    Load a model and print the sensitivities of its parameters.
+    """
+    from . import Model
+
+    directory, model_file = os.path.split(inp if inp.endswith(".on") else inp + ".on")
+    with working_directory(directory or "."):
+        return print_sensitivities(Model(model_file), IDs)
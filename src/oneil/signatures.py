@@ -0,0 +1,85 @@
+"""
+Unit signatures for imported Python functions, so the unit checker can check the
+parameters that call them instead of treating the functions as opaque.
+
+A Python module declares the units of its functions in a `ONEIL_UNITS` dict beside
+them, from the function's name to its signature: the units of each argument, in order,
+and of what it returns.
+
+    def temperature(D, t):
+        ...
+
+    ONEIL_UNITS = {
+        "temperature": "m, s -> K",
+        "emissivity": "*, 1 -> 1",
+    }
+
+Units are written as in models, with `1` for unitless and `*` for an argument that can
+have any units (e.g. a discrete option). When a model loads, each parameter that calls
+a function with a signature is checked: it must pass as many arguments as the signature
+has, in the units it gives, and be declared in the units the function returns. Functions
+without a signature are still unchecked until they run.
+"""
+
+import sys
+
+from . import units as un
+
+ATTRIBUTE = "ONEIL_UNITS"
+
+ANY = "*"
+
+def _units(text):
+    text = text.strip()
+    if text == ANY:
+        return None
+    if not text:
+        raise ValueError("Units are missing. Use 1 for unitless and * for any units.")
+    try:
+        return un.parse(text)[0]
+    except Exception:
+        raise ValueError(f"\"{text}\" aren't units Oneil knows.")
+
+def parse(text):
+    """
+    A signature ("m, s -> K") as the units of its arguments and of its result, where
+    None is any units. Raises ValueError for an invalid signature.
+    """
+    if not isinstance(text, str) or text.count("->") != 1:
+        raise ValueError("Signatures must be of the form \"<units>, ... -> <units>\".")
+    arguments, result = text.split("->")
+    return [_units(argument) for argument in arguments.split(",")] if arguments.strip() else [], _units(result)
+
+def declared(function):
+    """
+    The signature declared for a function in the ONEIL_UNITS of its module, as text, or
+    None if it has none.
+    """
+    module = sys.modules.get(getattr(function, "__module__", None) or "")
+    declarations = getattr(module, ATTRIBUTE, None)
+    if not isinstance(declarations, dict):
+        return None
+    return declarations.get(getattr(function, "__name__", None))
+
+def check(function, arguments, result, lookup):
+    """
+    The problems with a call of a function (with the IDs of its arguments) from a
+    parameter in `result` units, against the function's signature. `lookup` gives the
+    declared units of an ID, or None if they aren't known. Returns None if the function
+    has no signature. Raises ValueError if its signature is invalid.
+    """
+    text = declared(function)
+    if text is None:
+        return None
+    expected, returns = parse(text)
+
+    problems = []
+    if len(arguments) != len(expected):
+        problems.append(f"{function.__name__}() takes {len(expected)} argument(s), but is given {len(arguments)}.")
+    for ID, units in zip(arguments, expected):
+        found = lookup(ID)
+        if units is not None and found is not None and found != units:
+            problems.append(f"{ID} is in {un.hr_units(found) or 'unitless'}, but {function.__name__}() takes {un.hr_units(units) or 'unitless'}.")
+    if returns is not None and result != returns:
+        problems.append(f"{function.__name__}() returns {un.hr_units(returns) or 'unitless'}, but the parameter is in {un.hr_units(result) or 'unitless'}.")
+    return problems
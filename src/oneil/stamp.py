@@ -0,0 +1,130 @@
+"""
+Reproducibility stamps in exported results, and `oneil verify`, which checks that the
+workspace still matches one (for audit trails of delivered analyses).
+
+A stamp records how the results were made: the Oneil version, when, the model and the
+designs and overrides applied to it, the values of its external inputs (see
+oneil.externs), the evaluation flags, and the SHA-256 hash of every file the model
+loaded (see oneil.deps). JSON exports have it as "reproducibility":
+
+    "reproducibility": {
+      "oneil": "0.15.0",
+      "timestamp": "2026-10-14T19:20:00+00:00",
+      "model": "craft.on",
+      "designs": ["heavy.on"],
+      "overrides": ["m=4 :kg"],
+      "externs": {"launch_mass": [2300, 2500]},
+      "flags": {"cfg": ["flight"], "limits": {"time": null, "depth": 250},
+                "infinity": {"policy": "allow", "bound": 1e+30},
+                "evaluation": {"order": "strict", "damping": 1.0, "tolerance": 1e-09, "max_iterations": 100},
+                "deterministic": false, "nominal": false,
+                "parser": {"unicode_identifiers": true, "legacy_syntax": true, "recovery": "none"}},
+      "inputs": {"craft.on": "<sha256>", "thermal.on": "<sha256>", ...}
+    }
+
+Paths are relative to the directory of the exported file, so the results can be
+verified from anywhere. Reports have the same stamp as JSON in a LaTeX comment.
+"""
+
+import datetime
+import json
+import os
+
+from . import bcolors
+from . import console
+from . import deps
+from . import deterministic
+from . import externs
+from . import infinity
+from . import limits
+from . import messages
+from . import ordering
+from . import syntax
+from .pack import file_hash
+
+# The comment that holds the stamp in a LaTeX report
+REPORT_PREFIX = "% oneil-reproducibility: "
+
+def stamp(model, directory=None):
+    """
+    The reproducibility stamp of a model's current results, with paths relative to
+    directory (the working directory by default).
+    """
+    from . import active_cfg, nominal
+
+    directory = directory or os.getcwd()
+    name = lambda path: os.path.relpath(os.path.abspath(path), directory)
+    files, _ = deps.dependencies(model.model_filename, model.design_files)
+    return {
+        "oneil": console.__version__,
+        "timestamp": datetime.datetime.now(datetime.timezone.utc).isoformat(timespec="seconds"),
+        "model": name(model.model_filename),
+        "designs": [name(design) for design in model.design_files],
+        "overrides": list(model.overrides),
+        "externs": {parameter.extern: externs.lookup(parameter.extern) for _, parameter in model._annotated_parameters(None) if parameter.extern is not None},
+        "flags": {"cfg": sorted(active_cfg), "limits": dict(limits.limits), "infinity": dict(infinity.settings), "evaluation": dict(ordering.settings), "deterministic": deterministic.enabled, "nominal": nominal, "parser": dict(syntax.settings)},
+        "inputs": {name(entry["file"]): file_hash(entry["file"]) for entry in files},
+    }
+
+def read(path):
+    """
+    The stamp in an exported JSON file or LaTeX report, or None if it has none.
+    """
+    with open(path, "r") as f:
+        text = f.read()
+    try:
+        return json.loads(text).get("reproducibility")
+    except (ValueError, AttributeError):
+        pass
+    for line in text.splitlines():
+        if line.startswith(REPORT_PREFIX):
+            return json.loads(line[len(REPORT_PREFIX):])
+    return None
+
+def check(stamped, directory):
+    """
+    Compare the input hashes of a stamp with the files in directory. Returns the
+    changed and the missing files.
+    """
+    changed, missing = [], []
+    for file, digest in stamped["inputs"].items():
+        path = os.path.join(directory, file)
+        if not os.path.exists(path):
+            missing.append(file)
+        elif file_hash(path) != digest:
+            changed.append(file)
+    return changed, missing
+
+def verify(path):
+    """
+    Print whether the workspace matches the stamp in an exported file (as JSON with
+    `--error-format json`). Returns whether it does; if not, the exit code is the test
+    failure code.
+    """
+    from . import VerifyError
+
+    try:
+        stamped = read(path)
+    except (OSError, ValueError) as e:
+        raise VerifyError(path, f"Can't read {path}: {e}")
+    if not stamped:
+        raise VerifyError(path, "No reproducibility stamp found. Was it exported by an older version of Oneil?")
+
+    changed, missing = check(stamped, os.path.dirname(path) or ".")
+    matches = not changed and not missing
+    if not matches:
+        console.record_exit(console.EXIT_TEST_FAILURE)
+
+    if console.error_format == "json":
+        print(json.dumps({"matches": matches, "changed": changed, "missing": missing, "stamp": stamped}))
+        return matches
+
+    for file in changed:
+        print(f"{bcolors.error('CHANGED')} {messages.text('verify-changed', '{file} changed since {timestamp}.', file=file, timestamp=stamped['timestamp'])}")
+    for file in missing:
+        print(f"{bcolors.error('MISSING')} {messages.text('verify-missing', '{file} is missing.', file=file)}")
+    if stamped["oneil"] != console.__version__:
+        print(bcolors.YELLOW + messages.text("verify-version", "The results were made with Oneil {stamped}, and this is Oneil {current}.", stamped=stamped["oneil"], current=console.__version__) + bcolors.ENDC)
+    if matches:
+        print(bcolors.OKGREEN + messages.text("verify-match", "All {count} input(s) match the stamp from {timestamp}.", count=len(stamped["inputs"]), timestamp=stamped["timestamp"]) + bcolors.ENDC)
+    return matches
@@ -0,0 +1,171 @@
+"""
+Complexity statistics for the models in a workspace (`oneil stats`).
+
+Nothing is sent anywhere: the report is computed locally from the parsed models.
+"""
+
+import ast
+import contextlib
+import os
+
+from beautifultable import BeautifulTable
+
+from . import bcolors
+
+# Default thresholds above which a model is flagged as a refactoring candidate.
+THRESHOLDS = {
+    "parameters": 100,
+    "nesting": 8,
+    "fan_in": 15,
+    "fan_out": 10,
+    "external": 40,
+}
+
+@contextlib.contextmanager
+def working_directory(path):
+    """
+    Models resolve `use` and `import` relative to the working directory, so load each
+    model from its own directory.
+    """
+    previous = os.getcwd()
+    os.chdir(path or ".")
+    try:
+        yield
+    finally:
+        os.chdir(previous)
+
+def workspace_files(paths):
+    """
+    Expand a list of files and directories into the .on files they contain.
+    """
+    files = []
+    for path in paths or ["."]:
+        if os.path.isdir(path):
+            for root, dirs, names in os.walk(path):
+                dirs[:] = sorted(d for d in dirs if not d.startswith(".") and d != "__oncache__")
+                files.extend(os.path.join(root, name) for name in sorted(names) if name.endswith(".on"))
+        else:
+            files.append(path if path.endswith(".on") else path + ".on")
+
+    return files
+
+def _ast_depth(node):
+    children = [child for child in ast.iter_child_nodes(node) if isinstance(child, ast.expr)]
+    return 1 + max((_ast_depth(child) for child in children), default=0)
+
+def expression_depth(equation):
+    """
+    Nesting depth of an equation (a string expression, a Python function, or the
+    sub-parameters of piecewise and min/max equations).
+    """
+    if equation is None:
+        return 0
+    if callable(equation):
+        return 1
+    if isinstance(equation, (int, float)):
+        return 1
+    if isinstance(equation, str):
+        try:
+            return _ast_depth(ast.parse(equation, mode="eval").body)
+        except SyntaxError:
+            depth = max_depth = 0
+            for character in equation:
+                depth += {"(": 1, ")": -1}.get(character, 0)
+                max_depth = max(max_depth, depth)
+            return max_depth + 1
+    if isinstance(equation, (list, tuple)):
+        return max((expression_depth(part) for part in equation), default=0)
+    # Sub-parameters of piecewise and min/max equations
+    return expression_depth(getattr(equation, "equation", None))
+
+def model_uses(model):
+    """
+    The files of the models a model uses, with `use` or, from the submodels of another
+    model, with `from ... use`.
+    """
+    return {model._retrieve_model(entry["path"]).model_filename for entry in model.submodels.values()}
+
+def model_stats(model):
+    """
+    The complexity metrics of a model. Its fan-out is how many models it uses, and its
+    fan-in how many models in the workspace use it, which collect() counts (it's 0 here).
+    """
+    parameters = model.parameters
+    external = sum(1 for p in parameters.values() for arg in set(p.args) if "." in arg)
+    external += sum(1 for p in parameters.values() if p.callable)
+
+    return {
+        "parameters": len(parameters),
+        "tests": len(model.tests),
+        "submodels": len(model.submodels),
+        "nesting": max((expression_depth(p.equation) for p in parameters.values()), default=0),
+        "fan_in": 0,
+        "fan_out": len(model_uses(model)),
+        "external": external,
+    }
+
+def collect(paths):
+    """
+    Load each model in the workspace (parse only, no evaluation) and compute its stats.
+    Returns a list of (file, stats or None, error or None).
+    """
+    from . import Model, OneilError
+
+    results = []
+    # The files of the models each model uses, for fan-in
+    uses = {}
+    for file in workspace_files(paths):
+        directory, name = os.path.split(file)
+        try:
+            with working_directory(directory):
+                model = Model(name)
+            results.append((file, model_stats(model), None))
+            uses[os.path.abspath(file)] = model_uses(model)
+        except OneilError as e:
+            results.append((file, None, e))
+
+    for file, stats, _ in results:
+        if stats:
+            stats["fan_in"] = sum(os.path.abspath(file) in used for user, used in uses.items() if user != os.path.abspath(file))
+
+    return results
+
+def report(paths, thresholds={}):
+    thresholds = THRESHOLDS | thresholds
+    results = collect(paths)
+
+    table = BeautifulTable()
+    columns = ["parameters", "tests", "submodels", "nesting", "fan_in", "fan_out", "external"]
+    table.columns.header = ["Model"] + [c.replace("_", "-") for c in columns]
+
+    flagged = []
+    for file, stats, error in results:
+        if error:
+            table.append_row([file] + ["-"] * len(columns))
+            continue
+
+        row = [file]
+        for column in columns:
+            value = stats[column]
+            if column in thresholds and value > thresholds[column]:
+                flagged.append(f"{file}: {column.replace('_', '-')} {value} > {thresholds[column]}")
+                row.append(bcolors.YELLOW + str(value) + bcolors.ENDC)
+            else:
+                row.append(str(value))
+        table.append_row(row)
+
+    if results:
+        print(table)
+    else:
+        print("No models found.")
+
+    for file, _, error in results:
+        if error:
+            print(f"{bcolors.error('ERROR')} Could not load {file}: {error}")
+
+    if flagged:
+        print(bcolors.YELLOW + f"\n{len(flagged)} threshold(s) exceeded (consider splitting these models):" + bcolors.ENDC)
+        for flag in flagged:
+            print("  - " + flag)
+
+    return results, flagged
@@ -0,0 +1,128 @@
+"""
+Structural keys, hashes, and equality for equations and parameters.
+
+Two equations are structurally equal if they parse to the same expression, regardless of
+whitespace, redundant parentheses, number formatting (`2` and `2.0`), or where they are
+defined. Keys are plain tuples, so they can be used in sets and dictionaries; hashes are
+stable across processes, so they can be stored (e.g. in caches or diffs).
+
+Node IDs (see node_id()) identify declarations rather than definitions, for editor
+tooling that keeps caches across edits.
+"""
+
+import ast
+import hashlib
+
+def _canonical(node):
+    if isinstance(node, ast.Constant):
+        value = node.value
+        if isinstance(value, (int, float)) and not isinstance(value, bool):
+            value = float(value)
+        return ("Constant", repr(value))
+    if isinstance(node, ast.AST):
+        fields = [f for f in node._fields if f not in ("ctx", "kind", "type_comment")]
+        return (type(node).__name__,) + tuple(_canonical(getattr(node, f)) for f in fields)
+    if isinstance(node, list):
+        return tuple(_canonical(n) for n in node)
+    return repr(node)
+
+def expression_key(expression):
+    """
+    A key for an equation string or Python function that ignores formatting.
+    """
+    if expression is None:
+        return None
+    if callable(expression):
+        return ("Python", getattr(expression, "__module__", None), getattr(expression, "__qualname__", repr(expression)))
+    if not isinstance(expression, str):
+        return ("Value", repr(expression))
+    try:
+        return _canonical(ast.parse(expression.strip(), mode="eval").body)
+    except SyntaxError:
+        return ("Text", "".join(expression.split()))
+
+def _units_key(units):
+    return tuple(sorted((units or {}).items()))
+
+def parameter_key(parameter):
+    """
+    A key for the definition of a parameter: its equation (or value), units, and
+    pointer, piecewise, min/max, and fallback structure. The ID, name, notes, and
+    location are ignored.
+    """
+    if parameter is None:
+        return None
+    units = _units_key(parameter.units)
+    if parameter.independent:
+        if parameter.isdiscrete:
+            return ("Discrete", repr(parameter.min), tuple(parameter.options or ()))
+        return ("Value", repr(float(parameter.min)), repr(float(parameter.max)), units)
+    if parameter.piecewise:
+        pieces = tuple((parameter_key(piece), expression_key(condition)) for piece, condition in parameter.equation)
+        return ("Piecewise", pieces, units)
+    if parameter.minmax_equation:
+        return ("MinMax", parameter_key(parameter.equation[0]), parameter_key(parameter.equation[1]), units)
+    if parameter.pointer:
+        return ("Pointer", parameter.equation, units)
+    if parameter.callable:
+        return ("Python", expression_key(parameter.equation), tuple(parameter.args), units, parameter_key(parameter.fallback_param))
+    return ("Expression", expression_key(parameter.equation), units)
+
+def _key(item):
+    from . import Parameter
+
+    return parameter_key(item) if isinstance(item, Parameter) else expression_key(item)
+
+def structural_hash(item):
+    """
+    A stable hex digest of an equation or parameter's structural key.
+    """
+    return hashlib.sha256(repr(_key(item)).encode("utf-8")).hexdigest()
+
+def _text(text):
+    return "".join(str(text).split())
+
+def node_id(item, model):
+    """
+    A stable ID for the declaration of a parameter or test in a model: a digest of the
+    model's name and the declaration's text (its line, piecewise branches, and notes,
+    ignoring whitespace), not its position. Reparsing a file gives the same IDs to the
+    declarations that didn't change, even if an edit above them moved them, so results
+    cached by ID stay valid. Tests with the same text in one model have the same ID.
+    """
+    from . import Parameter
+
+    if isinstance(item, Parameter):
+        line = item.line.values() if isinstance(item.line, dict) else [item.line]
+        key = ("Parameter", model, item.id, bool(item.performance), tuple(_text(l) for l in line), tuple(_text(l) for l in item.piece_lines), tuple(_text(note) for note in item.notes))
+    else:
+        key = ("Test", model, _text(item.line), tuple(_text(note) for note in item.notes))
+    return hashlib.sha256(repr(key).encode("utf-8")).hexdigest()[:16]
+
+def structurally_equal(a, b):
+    """
+    Whether two equations or two parameters have the same structure.
+    """
+    return _key(a) == _key(b)
+
+def duplicate_parameters(model):
+    """
+    Find parameters with the same ID and definition in a model and its submodels (e.g.
+    the same constant defined in several submodels). Returns lists of `ID.model` names
+    (just `ID` for the model itself), for each duplicated definition.
+    """
+    groups = {}
+    seen = set()
+
+    def visit(submodel, label):
+        if id(submodel) in seen:
+            return
+        seen.add(id(submodel))
+        for ID, parameter in submodel.parameters.items():
+            groups.setdefault((ID, parameter_key(parameter)), []).append(f"{ID}.{label}" if label else ID)
+        for entry in submodel.submodels.values():
+            if 'model' in entry:
+                visit(entry['model'], entry['model'].name)
+
+    visit(model, "")
+    return [names for names in groups.values() if len(names) > 1]
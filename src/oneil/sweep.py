@@ -0,0 +1,303 @@
+"""
+Parameter sweeps (`oneil sweep`, `sweep` in the interpreter): evaluate a model over a
+grid of values of one or more of its inputs and collect the values of its outputs, as a
+table or CSV.
+
+Each swept input is given by a spec: `ID=start..stop/step` for evenly spaced numbers
+(stop included if the steps reach it), or `ID=a,b,c` for a list of values, like the
+options of a discrete parameter. Numbers are in the input's display units, or in the
+units after a colon (`m=2..6/0.5:kg`). Inputs of submodels are `ID.submodel`. With
+several specs, every combination is evaluated, the first spec varying slowest.
+
+For each point, only the parameters downstream of the swept inputs (those that use them,
+directly or through other parameters, in their model or in the models that use it) are
+reset and calculated again; the rest keep their values. The model's tests are run at
+each point, and a point that fails to evaluate is kept with its error.
+"""
+
+import csv
+import io
+import itertools
+import math
+import os
+import re
+
+from beautifultable import BeautifulTable
+
+from . import bcolors
+
+# The most points a sweep evaluates
+MAX_POINTS = 100000
+
+NUMBER = r"[-+]?(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?"
+RANGE = re.compile(r"^(" + NUMBER + r")\s*\.\.\s*(" + NUMBER + r")\s*/\s*(" + NUMBER + r")$")
+
+def parse_spec(spec):
+    """
+    Parse a sweep spec (`ID=start..stop/step` or `ID=a,b,c`, with optional `:units`) into
+    the input's ID, its values (numbers, or strings for options), and the units (None for
+    the input's display units). Raises SweepError for malformed specs.
+    """
+    from . import SweepError
+
+    if "=" not in spec or not spec.split("=")[0].strip():
+        raise SweepError(spec, "Sweeps must be of the form \"<ID>=<start>..<stop>/<step>\" or \"<ID>=<value>,<value>,...\".")
+    ID, text = [part.strip() for part in spec.split("=", 1)]
+    text, units = (part.strip() for part in text.split(":", 1)) if ":" in text else (text, None)
+
+    match = RANGE.match(text)
+    if match:
+        start, stop, step = (float(group) for group in match.groups())
+        if step <= 0:
+            raise SweepError(spec, "The step of a sweep must be greater than zero.")
+        if stop < start:
+            raise SweepError(spec, "A sweep's stop must be at least its start.")
+        count = math.floor((stop - start) / step + 1e-9) + 1
+        if count > MAX_POINTS:
+            raise SweepError(spec, f"The sweep has {count} points, more than {MAX_POINTS}.")
+        return ID, [start + i * step for i in range(count)], units or None
+
+    values = [value.strip() for value in text.split(",")]
+    if not all(values):
+        raise SweepError(spec, "Sweeps must be of the form \"<ID>=<start>..<stop>/<step>\" or \"<ID>=<value>,<value>,...\".")
+    return ID, [float(value) if re.fullmatch(NUMBER, value) else value for value in values], units or None
+
+def _scope(model, ID):
+    # The model a parameter ID (or `ID.submodel`) is in, with the ID there, or None
+    from . import OneilError
+
+    if "." not in ID:
+        return (model, ID) if ID in model.parameters else None
+    parameter_ID, symbol = ID.split(".", 1)
+    if symbol not in model.submodels:
+        return None
+    try:
+        submodel = model._retrieve_model(model.submodels[symbol]["path"])
+    except OneilError:
+        return None
+    return (submodel, parameter_ID) if parameter_ID in submodel.parameters else None
+
+def _values(spec, scope, ID, values, units):
+    # The values of a swept input as (min, max) in base units (or options)
+    from . import OneilError, SweepError, parse_override
+
+    parameter = scope.parameters[ID]
+    discrete = parameter.isdiscrete or isinstance(parameter.min, str)
+    units = units or (None if discrete else parameter.hr_units)
+    converted = []
+    for value in values:
+        text = f"{ID}={value!r}" if isinstance(value, float) else f"{ID}={value}"
+        try:
+            parsed = parse_override(text + (f" :{units}" if units else ""))
+        except OneilError as e:
+            raise SweepError(spec, e.message())
+        if not discrete and not parsed.isdiscrete and parsed.units != parameter.units:
+            raise SweepError(spec, f"The values are in {units or 'no units'}, but {ID} is in {parameter.hr_units or 'no units'}.")
+        converted.append((parsed.min, parsed.max))
+    return converted
+
+def _models(model, trail=None):
+    # The model and the submodels it uses
+    from . import OneilError
+
+    trail = {} if trail is None else trail
+    trail[id(model)] = model
+    for entry in model.submodels.values():
+        try:
+            submodel = entry.get("model") or model._retrieve_model(entry["path"])
+        except OneilError:
+            continue
+        if id(submodel) not in trail:
+            _models(submodel, trail)
+    return list(trail.values())
+
+def _args(parameter):
+    # The IDs a parameter uses, including in its branches, min/max equations, and fallback
+    args = list(parameter.args)
+    if parameter.piecewise:
+        args += [arg for piece in parameter.equation for part in piece for arg in _args(part)]
+    elif parameter.minmax_equation:
+        args += [arg for extreme in parameter.equation for arg in _args(extreme)]
+    if parameter.fallback_param is not None:
+        args += _args(parameter.fallback_param)
+    return args
+
+def downstream(model, inputs):
+    """
+    The parameters that depend on the inputs (a list of (model, ID)), directly or through
+    other parameters, in the model and its submodels: a dict of each model's id() to
+    the set of its parameter IDs, including the inputs.
+    """
+    models = _models(model)
+    affected = {id(scope): set() for scope in models}
+    for scope, ID in inputs:
+        affected.setdefault(id(scope), set()).add(ID)
+
+    def uses(scope, arg):
+        found = _scope(scope, arg)
+        return found is not None and found[1] in affected.get(id(found[0]), ())
+
+    changed = True
+    while changed:
+        changed = False
+        for scope in models:
+            for ID, parameter in scope.parameters.items():
+                if ID not in affected[id(scope)] and any(uses(scope, arg) for arg in _args(parameter)):
+                    affected[id(scope)].add(ID)
+                    changed = True
+    return affected
+
+def _display(parameter, units, values=None):
+    # A parameter's value (or the values) as (min, max) in the units, or (None, None)
+    from . import units as un
+
+    low, high = values or (parameter.min, parameter.max)
+    if low is None or high is None:
+        return None, None
+    if parameter.isdiscrete or isinstance(low, str):
+        return low, high
+    return un.hr_values((low, high), parameter.units, units)[0]
+
+def _recalculate(model, models, affected):
+    for scope in models:
+        scope._reset(affected[id(scope)])
+    model._calculate_models_recursively(quiet=True)
+
+def sweep(model, specs, outputs=None):
+    """
+    Evaluate a model (already built) at every combination of the values of the sweep
+    specs. The outputs are parameter IDs (or `ID.submodel`), by default the model's
+    performance parameters, or if it has none, its parameters that depend on the inputs.
+
+    Returns the inputs and outputs (each with its ID, name, and display units) and a row
+    for each point with the inputs' and outputs' values in display units (an output's
+    (min, max), or (None, None) if the point failed), the number of failed tests, and
+    the error, if the point failed. The model is recalculated with its own values
+    afterwards. Raises SweepError for specs or outputs that aren't in the model.
+    """
+    from . import OneilError, SweepError
+
+    inputs = []
+    for spec in specs:
+        ID, values, units = parse_spec(spec)
+        found = _scope(model, ID)
+        if found is None:
+            raise SweepError(spec, f"Parameter \"{ID}\" not found in model {model.name}.")
+        scope, local_ID = found
+        if not scope.parameters[local_ID].independent:
+            raise SweepError(spec, f"{ID} is calculated from an equation, so it can't be swept. Only independent parameters can.")
+        inputs.append({"id": ID, "scope": scope, "parameter": scope.parameters[local_ID], "values": _values(spec, scope, local_ID, values, units)})
+    if math.prod(len(entry["values"]) for entry in inputs) > MAX_POINTS:
+        raise SweepError(" ".join(specs), f"The sweep has more than {MAX_POINTS} points.")
+
+    models = _models(model)
+    affected = downstream(model, [(entry["scope"], entry["parameter"].id) for entry in inputs])
+    if outputs:
+        found = [(ID, _scope(model, ID)) for ID in outputs]
+        missing = [ID for ID, scope in found if scope is None]
+        if missing:
+            raise SweepError(" ".join(specs), f"Parameter(s) not found in model {model.name}: {', '.join(missing)}.")
+        outputs = [(ID, scope.parameters[local_ID]) for ID, (scope, local_ID) in found]
+    else:
+        swept = [entry["id"] for entry in inputs]
+        outputs = [(ID, parameter) for ID, parameter in model.parameters.items() if parameter.performance and ID not in swept]
+        outputs = outputs or [(ID, parameter) for ID, parameter in model.parameters.items() if ID in affected[id(model)] and ID not in swept]
+
+    columns = {"inputs": [], "outputs": []}
+    for entry in inputs:
+        result = entry["parameter"].result()
+        columns["inputs"].append({"id": entry["id"], "name": result["name"], "units": result["units"]})
+    for ID, parameter in outputs:
+        result = parameter.result()
+        columns["outputs"].append({"id": ID, "name": result["name"], "units": result["units"]})
+
+    original = [(entry["parameter"].min, entry["parameter"].max) for entry in inputs]
+    rows = []
+    try:
+        for point in itertools.product(*(entry["values"] for entry in inputs)):
+            row = {"inputs": [_display(entry["parameter"], column["units"], values)[0] for entry, column, values in zip(inputs, columns["inputs"], point)]}
+            try:
+                # Writing checks the values against the inputs' limits.
+                for entry, (low, high) in zip(inputs, point):
+                    entry["parameter"].write(low if low == high else (low, high))
+                _recalculate(model, models, affected)
+                row["outputs"] = [_display(parameter, column["units"]) for (_, parameter), column in zip(outputs, columns["outputs"])]
+                row["failed"] = model.test(verbose=False)[0]
+                row["error"] = None
+            except OneilError as e:
+                row.update({"outputs": [(None, None)] * len(outputs), "failed": None, "error": e.message()})
+            rows.append(row)
+    finally:
+        for entry, (low, high) in zip(inputs, original):
+            entry["parameter"].min, entry["parameter"].max = low, high
+        _recalculate(model, models, affected)
+        model.test(verbose=False)
+
+    return columns | {"rows": rows}
+
+def _header(column, suffix=""):
+    return f"{column['id']}{suffix}" + (f" ({column['units']})" if column["units"] else "")
+
+def _intervals(report):
+    # Whether each output has an interval value (min != max) at some point
+    return [any(row["outputs"][i][0] != row["outputs"][i][1] for row in report["rows"]) for i in range(len(report["outputs"]))]
+
+def to_csv(report):
+    """
+    The rows of a sweep as CSV: a column for each input and output (a min and a max
+    column for outputs with interval values), the number of failed tests, and the error
+    of points that failed. Values are in display units, which are in the headers.
+    """
+    intervals = _intervals(report)
+    header = [_header(column) for column in report["inputs"]]
+    for column, interval in zip(report["outputs"], intervals):
+        header += [_header(column, " min"), _header(column, " max")] if interval else [_header(column)]
+    output = io.StringIO()
+    writer = csv.writer(output, lineterminator="\n")
+    writer.writerow(header + ["failed tests", "error"])
+    for row in report["rows"]:
+        values = list(row["inputs"])
+        for (low, high), interval in zip(row["outputs"], intervals):
+            values += [low, high] if interval else [low]
+        writer.writerow(["" if value is None else value for value in values] + ["" if row["failed"] is None else row["failed"], row["error"] or ""])
+    return output.getvalue()
+
+def _format(value, sigfigs):
+    if value is None:
+        return "-"
+    if isinstance(value, float):
+        return f"{value:.{sigfigs}g}"
+    return str(value)
+
+def print_sweep(report, sigfigs=4):
+    """
+    Print the rows of a sweep as a table, with intervals as `min|max`.
+    """
+    table = BeautifulTable()
+    table.columns.header = [_header(column) for column in report["inputs"] + report["outputs"]] + ["Failed tests"]
+    for row in report["rows"]:
+        outputs = [_format(low, sigfigs) if low == high else f"{_format(low, sigfigs)}|{_format(high, sigfigs)}" for low, high in row["outputs"]]
+        table.append_row([_format(value, sigfigs) for value in row["inputs"]] + outputs + ["-" if row["failed"] is None else row["failed"]])
+    print(table)
+    for row in report["rows"]:
+        if row["error"]:
+            point = ", ".join(f"{column['id']}={_format(value, sigfigs)}" for column, value in zip(report["inputs"], row["inputs"]))
+            print(f"{bcolors.YELLOW}At {point}: {row['error']}{bcolors.ENDC}")
+
+def run(inp, designs, specs, outputs=None, output_file=None):
+    """
+    Load a model (with the designs), sweep it, and write the rows as CSV to output_file,
+    or print them if it's None or "-".
+    """
+    from . import loader
+
+    model = loader(inp, list(designs), capture_errors=False, quiet=True)
+    report = sweep(model, specs, outputs)
+    text = to_csv(report)
+    if output_file and output_file != "-":
+        with open(output_file, "w", newline="") as f:
+            f.write(text)
+        print(f"Wrote {len(report['rows'])} point(s) to {os.path.relpath(output_file)}.")
+    else:
+        print(text, end="")
+    return report
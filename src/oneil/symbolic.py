@@ -0,0 +1,274 @@
+"""
+Closed-form expressions of parameters (`oneil symbolic`), for papers and for checking
+derivations by hand: a parameter's equation with the equations of the parameters it
+depends on substituted in, down to the chosen inputs, and simplified. The model isn't
+evaluated.
+
+By default the expression is in terms of the independent parameters it reaches. With
+inputs given, only they stay symbols, and the other independent parameters are replaced
+by their values. Like Oneil's calculations, the expression is in base units (including
+the values substituted in), so the base units of the result and each input are listed.
+Parameters of submodels are named with their submodel symbols (`w.d`, or `x.e.d` for
+parameter x of submodel e of submodel d). Breakout functions and piecewise and min/max
+equations can't be substituted and stay symbols.
+"""
+
+import ast
+import math
+import os
+import re
+
+from . import units as un
+from .stats import working_directory
+
+# Parameters that stay symbols because they can't be substituted, by reason
+OPAQUE = {"callable": "breakout function", "piecewise": "piecewise equation", "minmax_equation": "min/max equation"}
+CIRCULAR = "circular dependency"
+
+def _number(value):
+    if isinstance(value, float) and value.is_integer() and abs(value) < 1e15:
+        value = int(value)
+    return ast.UnaryOp(ast.USub(), ast.Constant(-value)) if value < 0 else ast.Constant(value)
+
+def _value(node):
+    if isinstance(node, ast.Constant) and isinstance(node.value, (int, float)) and not isinstance(node.value, bool):
+        return node.value
+    if isinstance(node, ast.UnaryOp) and isinstance(node.op, ast.USub) and _value(node.operand) is not None:
+        return -_value(node.operand)
+    return None
+
+def _units(units):
+    # Base units for display, e.g. "kg m/s^2"
+    return un._build_compound_unit_str({unit: int(power) if float(power).is_integer() else power for unit, power in units.items()})
+
+def _coefficient(term):
+    # A term of a sum as its coefficient and the rest, e.g. (2, x) for 2*x
+    if isinstance(term, ast.BinOp) and isinstance(term.op, ast.Mult) and _value(term.left) is not None:
+        return _value(term.left), term.right
+    return 1, term
+
+def _summands(node, sign=1):
+    # The terms of a sum with their signs, e.g. [(1, a), (-1, b), (1, c)] for a - b + c
+    if isinstance(node, ast.BinOp) and isinstance(node.op, (ast.Add, ast.Sub)):
+        return _summands(node.left, sign) + _summands(node.right, sign if isinstance(node.op, ast.Add) else -sign)
+    if isinstance(node, ast.UnaryOp) and isinstance(node.op, ast.USub) and _value(node) is None:
+        return _summands(node.operand, -sign)
+    return [(sign, node)]
+
+def _factors(node):
+    # The factors of a product, e.g. [a, b, c] for a*b*c
+    if isinstance(node, ast.BinOp) and isinstance(node.op, ast.Mult):
+        return _factors(node.left) + _factors(node.right)
+    return [node]
+
+def simplify(node):
+    """
+    Simplify an expression (as an AST): fold constants, collect the constants of sums
+    and products, and drop identities (x*1, x + 0, x**1, ...).
+    """
+    if isinstance(node, ast.UnaryOp):
+        operand = simplify(node.operand)
+        if isinstance(node.op, ast.UAdd):
+            return operand
+        if isinstance(node.op, ast.USub):
+            if _value(operand) is not None:
+                return _number(-_value(operand))
+            if isinstance(operand, ast.UnaryOp) and isinstance(operand.op, ast.USub):
+                return operand.operand
+        return ast.UnaryOp(node.op, operand)
+
+    if isinstance(node, ast.Call):
+        return ast.Call(node.func, [simplify(arg) for arg in node.args], node.keywords)
+
+    if not isinstance(node, ast.BinOp):
+        return node
+
+    if isinstance(node.op, (ast.Add, ast.Sub)):
+        # Collect the constants and like terms of sums (2*x + 3 - x + 1 is x + 4).
+        constant, coefficients = 0, {}
+        for sign, term in _summands(node):
+            for inner_sign, inner in _summands(simplify(term), sign):
+                if _value(inner) is not None:
+                    constant += inner_sign * _value(inner)
+                    continue
+                coefficient, rest = _coefficient(inner)
+                key = ast.dump(rest)
+                coefficients[key] = (coefficients.get(key, (0, rest))[0] + inner_sign * coefficient, rest)
+        result = None
+        for coefficient, rest in coefficients.values():
+            if coefficient == 0:
+                continue
+            term = rest if abs(coefficient) == 1 else ast.BinOp(_number(abs(coefficient)), ast.Mult(), rest)
+            if result is None:
+                result = term if coefficient > 0 else ast.UnaryOp(ast.USub(), term)
+            else:
+                result = ast.BinOp(result, ast.Add() if coefficient > 0 else ast.Sub(), term)
+        if result is None:
+            return _number(constant)
+        return ast.BinOp(result, ast.Add() if constant > 0 else ast.Sub(), _number(abs(constant))) if constant else result
+
+    if isinstance(node.op, ast.Mult):
+        # Collect the constants of products (2*x*3 is 6*x).
+        factors = [inner for factor in _factors(node) for inner in _factors(simplify(factor))]
+        constant = math.prod(_value(factor) for factor in factors if _value(factor) is not None)
+        symbols = [factor for factor in factors if _value(factor) is None]
+        if constant == 0 or not symbols:
+            return _number(constant)
+        result = symbols[0]
+        for factor in symbols[1:]:
+            result = ast.BinOp(result, ast.Mult(), factor)
+        return result if constant == 1 else ast.BinOp(_number(constant), ast.Mult(), result)
+
+    left, right = simplify(node.left), simplify(node.right)
+    a, b = _value(left), _value(right)
+    if a is not None and b is not None and isinstance(node.op, (ast.Sub, ast.Div, ast.Pow)):
+        try:
+            return _number({ast.Sub: lambda: a - b, ast.Div: lambda: a / b, ast.Pow: lambda: a ** b}[type(node.op)]())
+        except (ZeroDivisionError, OverflowError, TypeError):
+            pass
+    if isinstance(node.op, ast.Sub):
+        if b == 0:
+            return left
+        if a == 0:
+            return simplify(ast.UnaryOp(ast.USub(), right))
+    if isinstance(node.op, ast.Div) and b == 1:
+        return left
+    if isinstance(node.op, ast.Pow):
+        if b == 1:
+            return left
+        if b == 0:
+            return _number(1)
+    return ast.BinOp(left, node.op, right)
+
+def unparse(node):
+    """
+    An expression (as an AST) in Oneil syntax.
+    """
+    from . import FUNCTIONS
+
+    text = ast.unparse(node)
+    for name, function in FUNCTIONS.items():
+        text = text.replace(function + "(", name + "(")
+    return re.sub(r"\s*\|\s*minus\s*\|\s*", " -- ", text)
+
+def closed_form(model, ID, inputs=()):
+    """
+    The closed-form expression of a parameter (by ID or label) in terms of the given
+    inputs (IDs or labels, or `ID.submodel`), or of the independent parameters it
+    reaches if none are given.
+
+    Returns the ID, the expression and its LaTeX (None if it can't be converted), the
+    base units of the result and of each symbol in the expression, and the parameters
+    that stayed symbols because they can't be substituted, with why.
+    """
+    from . import IDError, MATH_CONSTANTS
+
+    ID = model.lookup(ID)
+    target = model.parameters[ID]
+    reason = next((why for attribute, why in OPAQUE.items() if getattr(target, attribute)), None)
+    if reason:
+        raise IDError(model, ID, f"{ID} is a {reason}, which has no closed form.")
+    if target.independent:
+        raise IDError(model, ID, f"{ID} is independent, so its closed form is its value.")
+
+    names = []
+    for name in inputs:
+        if "." in name:
+            model.retrieve_parameter_from_submodel(name)
+            names.append(name)
+        else:
+            names.append(model.lookup(name))
+
+    symbols = {}
+    opaque = {}
+
+    def symbol(name, parameter):
+        symbols[name] = _units(parameter.units)
+        return ast.Name(name)
+
+    def expand(scope, chain, name, trail):
+        # A parameter's expression, where name is its ID in scope, a (sub)model whose
+        # submodel symbols from the top model are chain.
+        if "." in name:
+            parameter_ID, submodel_symbol = name.split(".")
+            scope, chain = scope._retrieve_model(scope.submodels[submodel_symbol]["path"]), [submodel_symbol] + chain
+        else:
+            parameter_ID = name
+        parameter = scope.parameters[parameter_ID]
+        qualified = ".".join([parameter_ID] + chain)
+
+        if qualified in names:
+            return symbol(qualified, parameter)
+        if parameter.independent:
+            if names and parameter.min == parameter.max and isinstance(parameter.min, (int, float)) and not isinstance(parameter.min, bool):
+                return _number(parameter.min)
+            return symbol(qualified, parameter)
+        reason = next((why for attribute, why in OPAQUE.items() if getattr(parameter, attribute)), None)
+        if reason or not isinstance(parameter.equation, str) or qualified in trail:
+            opaque[qualified] = reason or CIRCULAR
+            return symbol(qualified, parameter)
+        if parameter.pointer:
+            return expand(scope, chain, parameter.equation, trail + [qualified])
+
+        return Substitute(scope, chain, trail + [qualified]).visit(ast.parse(parameter.equation, mode="eval").body)
+
+    class Substitute(ast.NodeTransformer):
+        def __init__(self, scope, chain, trail):
+            self.scope, self.chain, self.trail = scope, chain, trail
+
+        def visit_Call(self, node):
+            # Function names aren't parameters.
+            node.args = [self.visit(arg) for arg in node.args]
+            return node
+
+        def visit_Attribute(self, node):
+            if isinstance(node.value, ast.Name) and node.attr in self.scope.submodels:
+                return expand(self.scope, self.chain, f"{node.value.id}.{node.attr}", self.trail)
+            return node
+
+        def visit_Name(self, node):
+            if node.id in self.scope.parameters and node.id not in MATH_CONSTANTS:
+                return expand(self.scope, self.chain, node.id, self.trail)
+            return node
+
+    expression = simplify(Substitute(model, [], [ID]).visit(ast.parse(target.equation, mode="eval").body))
+    text = unparse(expression)
+    try:
+        latex = model._param2latex(ID) + " = " + model._param2latex(ast.unparse(expression))
+    except Exception:  # noqa: BLE001 -- LaTeX is optional, e.g. for extremal subtraction
+        latex = None
+
+    return {
+        "id": ID,
+        "expression": text,
+        "latex": latex,
+        "units": _units(target.units),
+        "symbols": {name: symbols[name] for name in sorted(symbols) if name in re.findall(r"[\w.]+", text)},
+        "opaque": opaque,
+    }
+
+def print_closed_form(model, ID, inputs=(), latex=False):
+    """
+    Print the closed-form expression of a parameter (see closed_form()).
+    """
+    form = closed_form(model, ID, inputs)
+    units = f" :{form['units']}" if form["units"] else ""
+    print(f"{form['id']} = {form['expression']}{units}")
+    for name, units in form["symbols"].items():
+        print(f"    {name}: {units or 'unitless'}")
+    for name, reason in form["opaque"].items():
+        print(f"    {name} ({reason}) wasn't substituted.")
+    if latex:
+        print(form["latex"] if form["latex"] is not None else "No LaTeX for this expression.")
+    return form
+
+def run(inp, ID, inputs=(), latex=False):
+    """
+    Load a model (without evaluating it) and print the closed-form expression of one
+    of its parameters.
+    """
+    from . import Model
+
+    directory, model_file = os.path.split(inp if inp.endswith(".on") else inp + ".on")
+    with working_directory(directory or "."):
+        return print_closed_form(Model(model_file), ID, inputs, latex)
@@ -0,0 +1,108 @@
+"""
+Parser settings, so every tool (evaluation, lint, tokens, the editor server) parses a
+project's models the same way.
+
+The settings come from the `parser:` lines of a project's manifest (see
+oneil.read_manifest), as `name=value` pairs:
+
+    parser: unicode_identifiers=false legacy_syntax=false recovery=declaration
+
+    unicode_identifiers  whether IDs and submodel symbols can have non-ASCII letters
+                         (`Δv`), or only ASCII letters, digits, and underscores
+    legacy_syntax        whether aliases left over from renames (`ID := target`) are
+                         accepted, or are a SyntaxError
+    recovery             "none" to stop at the first SyntaxError in a file (the
+                         default), or "declaration" to skip the declaration with the
+                         error and report the rest of the file's SyntaxErrors with it
+
+Settings that aren't given keep their defaults. to_manifest() writes settings as a
+`parser:` line that from_manifest() reads back as the same settings.
+"""
+
+import re
+
+RECOVERY = ["none", "declaration"]
+
+DEFAULT_SETTINGS = {"unicode_identifiers": True, "legacy_syntax": True, "recovery": "none"}
+
+# The current settings
+settings = dict(DEFAULT_SETTINGS)
+
+PREFIX = "parser:"
+
+def reset():
+    settings.clear()
+    settings.update(DEFAULT_SETTINGS)
+
+def _value(text):
+    if text in ("true", "false"):
+        return text == "true"
+    return text
+
+def _text(value):
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    return str(value)
+
+def apply(config, errors):
+    """
+    Apply parser settings, adding a message to errors for each invalid one.
+    """
+    for key, value in config.items():
+        if key not in DEFAULT_SETTINGS:
+            errors.append(f"Unknown parser setting \"{key}\". Settings are: {', '.join(DEFAULT_SETTINGS)}.")
+        elif key == "recovery" and value not in RECOVERY:
+            errors.append(f"parser.recovery must be one of: {', '.join(RECOVERY)}.")
+        elif key != "recovery" and not isinstance(value, bool):
+            errors.append(f"parser.{key} must be true or false.")
+        else:
+            settings[key] = value
+
+def from_manifest(line, errors):
+    """
+    The settings of a `parser:` line, adding a message to errors for each pair that
+    isn't `name=value`.
+    """
+    config = {}
+    for pair in re.split(r"[\s,]+", line[len(PREFIX):].strip()):
+        if not pair:
+            continue
+        key, equals, value = pair.partition("=")
+        if not equals or not key or not value:
+            errors.append(f"Parser settings must be name=value pairs, not \"{pair}\".")
+            continue
+        config[key] = _value(value)
+    return config
+
+def to_manifest(values=None):
+    """
+    The `parser:` line for the settings (the current ones by default).
+    """
+    values = settings if values is None else values
+    return PREFIX + " " + " ".join(f"{key}={_text(values[key])}" for key in DEFAULT_SETTINGS if key in values)
+
+def load(manifest, lines):
+    """
+    Apply the settings of a manifest's `parser:` lines (the later ones take precedence).
+    Raises ConfigError for invalid settings after applying the valid ones.
+    """
+    from . import ConfigError
+
+    errors = []
+    for line in lines:
+        if line.startswith(PREFIX):
+            apply(from_manifest(line, errors), errors)
+    if errors:
+        error = ConfigError(manifest, "Some settings were ignored.")
+        for message in errors:
+            error = error.with_note(message)
+        raise error
+
+def check_id(ID, filename, line_no, line):
+    """
+    Raise a SyntaxError for an ID or submodel symbol the settings don't allow.
+    """
+    from . import SyntaxError
+
+    if not settings["unicode_identifiers"] and not ID.isascii():
+        raise SyntaxError(filename, line_no, line, f"\"{ID}\" has non-ASCII characters, which this project's parser settings don't allow (unicode_identifiers=false).")
@@ -0,0 +1,287 @@
+"""
+The tokens of a model file, for syntax highlighters and other tools that need to agree
+with the parser (`oneil tokens`).
+
+Lines are classified as `parse_file` classifies them (see oneil.refactor.kind()), and
+equations are split with the same operators, functions, and constants. Each token has
+a kind, its text, its 1-based line, and its 0-based start and end columns in characters
+(see oneil.positions to convert them). Whitespace isn't a token, and every other
+character of the file is in exactly one token. The parser settings of the project's
+manifest (`oneil tokens --manifest`, see oneil.syntax) apply to tokens too.
+
+The kinds are:
+
+    comment      `# ...` lines
+    attribute    `#[cfg(...)]` guards and `#feature(...)` pragmas
+    annotation   the `@name` of an annotation line (its arguments are `text`)
+    note         indented notes and the model's header
+    keyword      use, from, import, include, section, unit, test, as, if, and, or, not
+    label        a parameter's label, or a section's name
+    definition   the ID a parameter, alias, or design line defines, or a declared unit
+    identifier   a parameter ID in an expression
+    submodel     the symbol after the "." of a submodel parameter (`F.e`), or after `as`
+    module       the model, path, or Python module in use, from, and import lines
+    function     a builtin or Python function that's called
+    constant     pi, e, or inf
+    number       a number
+    string       a quoted string (a discrete value, a test name, or an included file)
+    operator     an operator in an expression, or a `$`, `*`, or `**` prefix
+    punctuation  : = := => ( ) [ ] { } , | ? .
+    units        the units after an equation or a unit declaration's number
+    text         an annotation's arguments
+    error        anything the parser wouldn't accept there
+"""
+
+import collections
+import re
+
+from . import features
+from . import syntax
+from .refactor import DECLARATIONS, kind
+
+Token = collections.namedtuple("Token", ["kind", "text", "line", "start", "end"])
+
+KINDS = ["comment", "attribute", "annotation", "note", "keyword", "label", "definition", "identifier", "submodel", "module", "function", "constant", "number", "string", "operator", "punctuation", "units", "text", "error"]
+
+WORD_OPERATORS = ["if", "and", "or", "not"]
+
+# The kinds of tokens that are IDs or submodel symbols, which the parser settings can
+# limit to ASCII (see oneil.syntax)
+NAMES = ["definition", "identifier", "submodel"]
+
+EXPRESSION = re.compile(r"""
+    (?P<string>'[^']*'|"[^"]*")
+  | (?P<number>(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?)
+  | (?P<name>[^\W\d]\w*)
+  | (?P<operator>\*\*|//|--|==|!=|<=|>=|[-+*/%^<>!])
+  | (?P<punctuation>[()\[\]{},|?=.])
+  | (?P<space>\s+)
+  | (?P<error>.)
+""", re.VERBOSE)
+
+def _expression(text, line_no, offset):
+    """
+    The tokens of an equation, a test expression, or a limit.
+    """
+    from . import FUNCTIONS, MATH_CONSTANTS
+
+    found = [match for match in EXPRESSION.finditer(text) if match.lastgroup != "space"]
+    for i, match in enumerate(found):
+        token_kind, word = match.lastgroup, match.group()
+        if token_kind == "name":
+            after = text[match.end():].lstrip()
+            if i > 0 and found[i - 1].group() == ".":
+                token_kind = "submodel"
+            elif word in WORD_OPERATORS:
+                token_kind = "keyword"
+            elif after.startswith("("):
+                token_kind = "function"
+            elif word in MATH_CONSTANTS and word not in FUNCTIONS:
+                token_kind = "constant"
+            else:
+                token_kind = "identifier"
+        yield Token(token_kind, word, line_no, offset + match.start(), offset + match.end())
+
+def _token(token_kind, line, start, end, line_no):
+    """
+    A token for line[start:end] without its surrounding whitespace, or None if that's
+    all it is.
+    """
+    text = line[start:end]
+    stripped = text.strip()
+    if not stripped:
+        return None
+    start += len(text) - len(text.lstrip())
+    return Token(token_kind, stripped, line_no, start, start + len(stripped))
+
+def _tokens(spans, line, line_no):
+    for token_kind, start, end in spans:
+        token = _token(token_kind, line, start, end, line_no)
+        if token:
+            yield token
+
+def _prefix(line, line_no):
+    """
+    The `$`, `*`, and `**` prefixes of a line, and where the rest of it starts.
+    """
+    found = []
+    match = re.match(r"\s*(\$)?\s*(\*{1,2})?\s*", line)
+    for group in (1, 2):
+        if match.group(group):
+            found.append(Token("operator", match.group(group), line_no, match.start(group), match.end(group)))
+    return found, match.end()
+
+def _units(line, colon, line_no):
+    yield Token("punctuation", ":", line_no, colon, colon + 1)
+    token = _token("units", line, colon + 1, len(line), line_no)
+    if token:
+        yield token
+
+def _equation(line, start, line_no):
+    """
+    The tokens of `= equation :units` (or `=> ID`) from `start` on.
+    """
+    equals = line.index("=", start)
+    width = 2 if line[equals:equals + 2] == "=>" else 1
+    yield Token("punctuation", line[equals:equals + width], line_no, equals, equals + width)
+    colon = line.find(":", equals)
+    end = colon if colon != -1 else len(line)
+    yield from _expression(line[equals + width:end], line_no, equals + width)
+    if colon != -1:
+        yield from _units(line, colon, line_no)
+
+def _parameter(line, line_no):
+    found, start = _prefix(line, line_no)
+    yield from found
+    colon = line.index(":", start)
+    preamble = line[start:colon]
+    bracket = re.search(r"[(\[]", preamble)
+    label_end = start + bracket.start() if bracket else colon
+    token = _token("label", line, start, label_end, line_no)
+    if token:
+        yield token
+    if bracket:
+        # Limits `(min, max)` are expressions; discrete options `[a, b]` are names.
+        yield from _expression(line[label_end:colon], line_no, label_end)
+    yield Token("punctuation", ":", line_no, colon, colon + 1)
+    equals = line.index("=", colon)
+    yield from _tokens([("definition", colon + 1, equals)], line, line_no)
+    yield from _equation(line, equals, line_no)
+
+def _design(line, line_no):
+    found, start = _prefix(line, line_no)
+    yield from found
+    equals = line.index("=", start)
+    target = line[start:equals].strip()
+    offset = start + line[start:equals].index(target)
+    ID, _, symbol = target.partition(".")
+    yield Token("definition", ID, line_no, offset, offset + len(ID))
+    if symbol:
+        yield Token("punctuation", ".", line_no, offset + len(ID), offset + len(ID) + 1)
+        yield Token("submodel", symbol, line_no, offset + len(ID) + 1, offset + len(target))
+    yield from _equation(line, equals, line_no)
+
+def _alias(line, line_no):
+    assign = line.index(":=")
+    colon = line.rfind(":", 0, assign)
+    if colon != -1:
+        yield from _tokens([("label", 0, colon)], line, line_no)
+        yield Token("punctuation", ":", line_no, colon, colon + 1)
+    yield from _tokens([("definition", colon + 1, assign)], line, line_no)
+    yield Token("punctuation", ":=", line_no, assign, assign + 2)
+    yield from _expression(line[assign + 2:], line_no, assign + 2)
+
+def _test(line, line_no):
+    from . import test_colon
+
+    found, start = _prefix(line, line_no)
+    yield from found
+    keyword = line.index("test", start)
+    yield Token("keyword", "test", line_no, keyword, keyword + 4)
+    colon = test_colon(line)
+    # The name and the inputs (`"name" {A_lim}`)
+    yield from _expression(line[keyword + 4:colon], line_no, keyword + 4)
+    yield Token("punctuation", ":", line_no, colon, colon + 1)
+    yield from _expression(line[colon + 1:], line_no, colon + 1)
+
+def _declaration(line, line_no):
+    """
+    use, from, import, include, section, and unit lines.
+    """
+    keyword = line.split()[0]
+    yield Token("keyword", keyword, line_no, 0, len(keyword))
+    if keyword == "unit":
+        # `unit furlong = 201.168 m`
+        equals = line.index("=")
+        number = equals + 1 + re.match(r"\s*\S*", line[equals + 1:]).end()
+        yield from _tokens([("definition", len(keyword), equals)], line, line_no)
+        yield Token("punctuation", "=", line_no, equals, equals + 1)
+        yield from _tokens([("number", equals + 1, number), ("units", number, len(line))], line, line_no)
+        return
+    if keyword == "section":
+        yield from _tokens([("label", len(keyword), len(line))], line, line_no)
+        return
+    if keyword == "include":
+        yield from _expression(line[len(keyword):], line_no, len(keyword))
+        return
+    previous = keyword
+    for match in re.finditer(r"\(([^)]*)\)|[^\s(]+", line[len(keyword):]):
+        start, end = len(keyword) + match.start(), len(keyword) + match.end()
+        word = match.group()
+        if word.startswith("("):
+            # Test inputs passed to the submodel (`use model(A_lim=A_max) as th`)
+            yield from _expression(word, line_no, start)
+        elif word in ("use", "as"):
+            yield Token("keyword", word, line_no, start, end)
+        else:
+            yield Token("submodel" if previous == "as" else "module", word, line_no, start, end)
+        previous = word
+
+def _annotation(line, line_no):
+    name = re.match(r"@\w*", line)
+    yield Token("annotation", name.group(), line_no, 0, name.end())
+    yield from _tokens([("text", name.end(), len(line))], line, line_no)
+
+def _line(line, line_no, statement):
+    """
+    The tokens of one line, given the kind of statement it follows (for indented lines).
+    """
+    stripped = line.strip()
+    if not stripped:
+        return
+    if line[0] in (" ", "\t"):
+        if statement == "parameter" and stripped.startswith("{"):
+            # A branch of a piecewise equation
+            yield from _expression(line, line_no, 0)
+        else:
+            yield from _tokens([("note", 0, len(line))], line, line_no)
+        return
+
+    line_kind = kind(line)
+    if line_kind == "comment":
+        yield from _tokens([("attribute" if stripped.startswith("#[cfg") or features.is_pragma(line) else "comment", 0, len(line))], line, line_no)
+    elif line_kind == "annotation":
+        yield from _annotation(line, line_no)
+    elif line_kind in DECLARATIONS:
+        yield from _declaration(line, line_no)
+    elif line_kind == "test":
+        yield from _test(line, line_no)
+    elif line_kind == "alias" and syntax.settings["legacy_syntax"]:
+        yield from _alias(line, line_no)
+    elif line_kind == "design":
+        yield from _design(line, line_no)
+    elif line_kind == "parameter":
+        yield from _parameter(line, line_no)
+    else:
+        yield from _tokens([("error", 0, len(line))], line, line_no)
+
+def tokenize(text):
+    """
+    Yield the tokens of a model's text, line by line.
+    """
+    statement = "header"
+    # Experimental operators are operators in models that enable them (see oneil.features).
+    experimental = features.OPERATORS if "unicode_operators" in features.enabled(text) else {}
+    for i, line in enumerate(text.splitlines()):
+        if line.strip() and line[0] not in (" ", "\t"):
+            statement = kind(line)
+        try:
+            found = list(_line(line, i + 1, statement))
+        except ValueError:
+            # A line missing a part its kind needs (e.g. a test without a ":")
+            found = list(_tokens([("error", 0, len(line))], line, i + 1))
+        found = [token._replace(kind="operator") if token.kind == "error" and token.text in experimental else token for token in found]
+        if not syntax.settings["unicode_identifiers"]:
+            found = [token._replace(kind="error") if token.kind in NAMES and not token.text.isascii() else token for token in found]
+        yield from found
+
+def run(file):
+    """
+    Print the tokens of a model file as JSON lines.
+    """
+    import json
+
+    with open(file, "r", encoding="utf-8") as f:
+        text = f.read()
+    for token in tokenize(text):
+        print(json.dumps(token._asdict()))
@@ -0,0 +1,91 @@
+"""
+Traces of test conditions, so a failing test shows which operand was out of range
+instead of only that it failed. A trace is the condition as a tree of its
+sub-expressions, each annotated with its value:
+
+    m_max.c1 + m_max.c2 <= m_alloc  →  false
+    ├── m_max.c1 + m_max.c2  →  11.2 kg
+    │   ├── m_max.c1  →  6 kg
+    │   └── m_max.c2  →  5.2 kg
+    └── m_alloc  →  10 kg
+
+The trace level (`--trace <level>`, or `trace` in the `[display]` section of the config
+file) is one of LEVELS: `off`, `failures` to trace the tests that fail, or `all` to
+trace every test that runs.
+"""
+
+import ast
+import re
+
+LEVELS = ["off", "failures", "all"]
+
+# The level given with `--trace`, which takes precedence over the config file's
+override = None
+
+def current():
+    from . import config
+
+    return override or config.display["trace"]
+
+def traced(passed):
+    """
+    Whether a test that passed (or failed) is traced at the current level.
+    """
+    return current() == "all" or (current() == "failures" and not passed)
+
+def _children(node):
+    if isinstance(node, ast.Compare):
+        children = [node.left, *node.comparators]
+    elif isinstance(node, ast.BoolOp):
+        children = node.values
+    elif isinstance(node, ast.BinOp):
+        children = [node.left, node.right]
+    elif isinstance(node, ast.UnaryOp):
+        children = [node.operand]
+    elif isinstance(node, ast.Call):
+        children = node.args
+    elif isinstance(node, ast.IfExp):
+        children = [node.test, node.body, node.orelse]
+    else:
+        children = []
+    # Numbers and strings are shown as written, so they aren't worth a line of their own
+    return [child for child in children if not isinstance(child, ast.Constant)]
+
+def _value(node, globals, scope):
+    from . import OneilError
+
+    try:
+        value = eval(compile(ast.Expression(body=node), "<test>", "eval"), globals, scope)
+    except OneilError as e:
+        return f"error: {e.message}"
+    except Exception as e:
+        return f"error: {e}"
+    if isinstance(value, bool) or type(value).__name__ == "bool_":
+        return "true" if value else "false"
+    return str(value).strip()
+
+def _label(source, node, names):
+    text = ast.get_source_segment(source, node)
+    for old, new in names.items():
+        text = re.sub(r"(?<![\w.])" + re.escape(old) + r"(?![\w.])", new, text)
+    return text.replace("**", "^")
+
+def trace(expression, globals, scope, names={}):
+    """
+    The lines of the trace of a test's expression (as it's run), evaluated with the
+    same globals and scope as the test. `names` maps names in the expression back to
+    how they're written in the model (prefixed submodel IDs, renamed functions).
+    """
+    root = ast.parse(expression.strip(), mode="eval").body
+    source = expression.strip()
+    lines = []
+
+    def walk(node, prefix, child_prefix):
+        lines.append(f"{prefix}{_label(source, node, names)}  →  {_value(node, globals, scope)}")
+        children = _children(node)
+        for i, child in enumerate(children):
+            last = i == len(children) - 1
+            walk(child, child_prefix + ("└── " if last else "├── "), child_prefix + ("    " if last else "│   "))
+
+    walk(root, "", "")
+    return lines
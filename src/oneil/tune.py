@@ -0,0 +1,473 @@
+"""
+Interactive tuning protocol (`oneil tune`), for GUI front ends like sliders that update
+a design live: a small JSON-RPC 2.0 protocol over stdin and stdout, separate from any
+editor protocol.
+
+Each message is one line of JSON. Requests and their responses:
+
+    {"jsonrpc": "2.0", "id": 1, "method": "initialize"}
+        -> {"protocol": 1, "model": "craft", "inputs": [{"id": "m", "value": {...}, ...}]}
+    {"jsonrpc": "2.0", "id": 2, "method": "setInput", "params": {"id": "m", "value": "4|5 :kg"}}
+        -> {"changes": {...}}   (see oneil.watch.diff())
+    {"jsonrpc": "2.0", "id": 3, "method": "getOutputs", "params": {"ids": ["F_n", "F.e"]}}
+        -> {"outputs": {"F_n": {...}, "F.e": {...}}}
+    {"jsonrpc": "2.0", "id": 4, "method": "subscribe", "params": {"id": "F_n"}}
+        -> {"id": "F_n", "value": {...}}
+    {"jsonrpc": "2.0", "id": 5, "method": "unsubscribe", "params": {"id": "F_n"}}
+    {"jsonrpc": "2.0", "id": 6, "method": "oneil/modelTree"}
+        -> {"model": "craft", "file": "craft.on", "parameters": [...], "submodels": [...]}
+    {"jsonrpc": "2.0", "id": 7, "method": "oneil/evaluate", "params": {"reload": true}}
+        -> {"results": {...}}   (see Model.results())
+    {"jsonrpc": "2.0", "id": 8, "method": "oneil/didChange", "params": {"changes": [...]}}
+        -> {"declarations": [{"line": 4, "end_line": 6, "tokens": [...]}]}
+    {"jsonrpc": "2.0", "id": 9, "method": "oneil/hover", "params": {"line": 3, "character": 15}}
+        -> {"contents": {"kind": "markdown", "value": "..."}, "range": {...}}
+    {"jsonrpc": "2.0", "id": 10, "method": "oneil/speculate", "params": {"line": 3}}
+        -> {"id": "a", "line": 4, "value": "2.5 m/s^2", "error": null}
+    {"jsonrpc": "2.0", "id": 11, "method": "textDocument/formatting", "params": {"options": {...}}}
+        -> [{"range": {...}, "newText": "..."}]
+    {"jsonrpc": "2.0", "id": 12, "method": "oneil/builtins", "params": {"kind": "function"}}
+        -> {"builtins": [{"name": "sqrt", "kind": "function", "signature": "sqrt(x)", "doc": "..."}]}
+    {"jsonrpc": "2.0", "id": 13, "method": "oneil/checkUnits"}
+        -> {"diagnostics": [{"kind": "UnitEvaluationError", "line": 4, ...}], "checked": ["a"]}
+    {"jsonrpc": "2.0", "id": 14, "method": "shutdown"}
+
+After an input changes, each subscribed parameter whose value changed is sent as a
+notification: {"jsonrpc": "2.0", "method": "parameterChanged", "params": {"id": ...,
+"value": {...}}}. Values are `Parameter.result()` dicts, in display units.
+
+Inputs are set like `--set`: a value is a string ("4|5 :kg"), or a number or [min, max]
+in the parameter's display units. Setting an input re-evaluates the model with it. If
+that fails, the error is returned and the model is restored to its previous inputs.
+Anything evaluation prints (warnings, summaries) goes to stderr.
+
+The `oneil/` methods are for editors: the submodel hierarchy for a tree view, and the
+evaluation results with the lines they come from for a values panel. Their columns are
+counted in characters unless `initialize` is given the `positionEncodings` the editor
+supports, in which case the first one Oneil supports is used (see oneil.positions) and
+returned as `positionEncoding`. Parameters and tests also have a `node`, an ID that
+stays the same across reloads while their declaration doesn't change (see
+oneil.structure.node_id()), for keeping what the editor shows for them between edits.
+
+`oneil/didChange` takes edits to the model file as LSP content changes, without saving
+it. Only the declarations they touch are reparsed (see oneil.incremental), and they're
+returned with their lines (1-based, `end_line` inclusive) and tokens for highlighting
+and syntax errors. The edited text is what `oneil/evaluate` with `"reload": true` loads.
+While a parameter's equation is edited, `oneil/speculate` evaluates the equation on a
+line of the edited text with the last values of the parameters it uses, without
+reloading or changing the model, so the editor can show the would-be value inline.
+oneil/didChange returns the same for each parameter it reparsed as `speculative`. Piecewise,
+Python, and min/max equations aren't evaluated speculatively.
+
+`oneil/hover` describes the parameter at a 0-based line and column of the model file in
+Markdown, like LSP's hover: its label, units, value, and notes (null if there's no
+parameter there). `textDocument/formatting` is LSP's: the edits that format the file as
+edited (see oneil.fmt), as one edit of the whole text, or none if it's formatted. The
+editor applies them and sends them back with `oneil/didChange`.
+
+`oneil/checkUnits` checks the units of the model file as edited without evaluating it
+(see oneil.check), e.g. when the editor saves it. The units of each equation and test
+are kept between checks, so only the ones whose expression, or the units of the IDs they
+use, changed are worked out again (`checked`).
+
+`oneil/builtins` lists the builtin functions, constants, and units models can use,
+including those registered by plugins (see oneil.builtin), or only those of a `kind`,
+for completions and signature help.
+"""
+
+import contextlib
+import json
+import os
+import sys
+
+from . import builtin
+from . import check
+from . import console
+from . import explanations
+from . import fmt
+from . import incremental
+from . import positions
+from . import structure
+from . import watch
+from .tokens import tokenize
+
+# Bumped when a method or message changes incompatibly
+PROTOCOL_VERSION = 1
+
+PARSE_ERROR = -32700
+INVALID_REQUEST = -32600
+METHOD_NOT_FOUND = -32601
+INVALID_PARAMS = -32602
+# Errors from Oneil itself (a bad value, a limit, a unit mismatch), with the error's
+# kind, code, message, and notes as data
+MODEL_ERROR = 1
+
+class ProtocolError(Exception):
+    def __init__(self, code, message, data=None):
+        super().__init__(message)
+        self.code = code
+        self.data = data
+
+def _number(value):
+    return isinstance(value, (int, float)) and not isinstance(value, bool)
+
+class Session:
+    """
+    A model being tuned: its inputs set so far and the parameters subscribed to. Use
+    handle() to answer a message.
+    """
+    def __init__(self, inp, designs=()):
+        self.inp = inp
+        self.designs = list(designs)
+        self.overrides = {}
+        self.subscriptions = []
+        self.shutdown = False
+        self.position_encoding = "utf-32"
+        # The model file as edited with oneil/didChange
+        self.document = None
+        # The units worked out by oneil/checkUnits
+        self.unit_cache = check.UnitCache()
+        self.model = self._load()
+
+    def _load(self):
+        from . import loader
+
+        with contextlib.redirect_stdout(sys.stderr):
+            return loader(self.inp, self.designs, capture_errors=False, quiet=True, overrides=list(self.overrides.values()))
+
+    def _parameter(self, ID):
+        from . import OneilError
+
+        if not isinstance(ID, str):
+            raise ProtocolError(INVALID_PARAMS, "A parameter ID must be a string.")
+        try:
+            parameter = self.model.retrieve_parameter_from_submodel(ID)[0] if "." in ID else self.model.parameters.get(ID)
+        except OneilError:
+            parameter = None
+        if parameter is None:
+            raise ProtocolError(INVALID_PARAMS, f"Parameter \"{ID}\" not found in model {self.model.name}.")
+        return parameter
+
+    def _value(self, ID):
+        return self._parameter(ID).result()
+
+    def inputs(self):
+        """
+        The independent parameters of the model and its submodels (as `ID.symbol`), which
+        can be set, with their values and limits.
+        """
+        found = []
+        scopes = [("", self.model)] + [("." + symbol, entry["model"]) for symbol, entry in self.model.submodels.items() if "model" in entry]
+        for suffix, scope in scopes:
+            for ID, parameter in scope.parameters.items():
+                if parameter.independent:
+                    found.append({"id": ID + suffix, "value": parameter.result()} | parameter.limits())
+        return found
+
+    def model_tree(self, model=None, trail=None):
+        """
+        The submodel hierarchy of the model: for each model, its name, file, design,
+        parameters (with where they're declared, see Parameter.source(), and their node
+        IDs), number of tests, and submodels by symbol.
+        """
+        model = model or self.model
+        trail = set() if trail is None else trail
+        trail.add(id(model))
+        filename = os.path.relpath(model.model_filename)
+        parameters = [{"id": ID, "name": parameter.name, "section": parameter.section or None, "performance": bool(parameter.performance), "source": parameter.source(filename, self.position_encoding), "node": structure.node_id(parameter, model.name)} for ID, parameter in model.parameters.items()]
+        submodels = []
+        for symbol, entry in model.submodels.items():
+            if "model" in entry and id(entry["model"]) not in trail:
+                submodels.append({"symbol": symbol} | self.model_tree(entry["model"], trail))
+        return {"model": model.name, "file": filename, "design": model.design, "parameters": parameters, "tests": len(model.tests), "submodels": submodels}
+
+    def _override(self, ID, value):
+        units = self._parameter(ID).hr_units
+        if isinstance(value, str):
+            return f"{ID}={value}"
+        if _number(value):
+            text = repr(value)
+        elif isinstance(value, list) and len(value) == 2 and all(_number(v) for v in value):
+            text = f"{value[0]!r}|{value[1]!r}"
+        else:
+            raise ProtocolError(INVALID_PARAMS, "A value must be a string, a number, or [min, max].")
+        return f"{ID}={text} :{units}" if units else f"{ID}={text}"
+
+    def set_input(self, ID, value):
+        """
+        Set an input and re-evaluate the model. Returns the changes in its results and the
+        notifications for subscribed parameters that changed.
+        """
+        from . import OneilError
+
+        override = self._override(ID, value)
+        previous = self.model.results()
+        before = {subscribed: self._value(subscribed) for subscribed in self.subscriptions}
+        try:
+            with contextlib.redirect_stdout(sys.stderr):
+                self.model.apply_overrides([override], quiet=True)
+        except OneilError:
+            # The failed value may have been partly written, so start again from the
+            # inputs that worked.
+            self.model = self._load()
+            raise
+        self.overrides[ID] = override
+        self.model.overrides = list(self.overrides.values())
+
+        notifications = [{"jsonrpc": "2.0", "method": "parameterChanged", "params": {"id": subscribed, "value": self._value(subscribed)}} for subscribed in self.subscriptions if self._value(subscribed) != before[subscribed]]
+        return {"changes": watch.diff(previous, self.model.results())}, notifications
+
+    def did_change(self, changes):
+        """
+        Apply edits to the model file's text, reparsing the declarations they touch.
+        Returns those declarations.
+        """
+        from . import source_overrides
+
+        if not isinstance(changes, list) or not all(isinstance(change, dict) and isinstance(change.get("text"), str) for change in changes):
+            raise ProtocolError(INVALID_PARAMS, "\"changes\" must be a list of changes with a \"text\".")
+        path = self.model.model_filename
+        if self.document is None:
+            if path in source_overrides:
+                text = source_overrides[path]
+            else:
+                with open(path, "r", encoding="utf-8") as f:
+                    text = f.read()
+            self.document = incremental.Document(text, self.position_encoding)
+        try:
+            changed = self.document.change(changes)
+        except (KeyError, TypeError) as e:
+            raise ProtocolError(INVALID_PARAMS, f"Invalid change: {e}")
+        source_overrides[path] = self.document.text
+        speculative = [self.speculate(declaration.start) for declaration in changed]
+        return {
+            "declarations": [{"line": declaration.start + 1, "end_line": declaration.end, "tokens": [token._asdict() for token in declaration.tokens]} for declaration in changed],
+            "speculative": [result for result in speculative if result is not None],
+        }
+
+    def speculate(self, line):
+        """
+        Evaluate the parameter declared on a 0-based line of the edited text with the
+        current values of the parameters it uses, without changing the model. Returns its
+        ID, 1-based line, and value (or error), or None if the line isn't a parameter whose
+        equation can be evaluated on its own.
+        """
+        from . import OneilError, features, parse_parameter, refactor
+        from . import units as un
+
+        if not isinstance(line, int):
+            raise ProtocolError(INVALID_PARAMS, "speculate takes a \"line\".")
+        lines = self._text().splitlines()
+        if not 0 <= line < len(lines) or refactor.kind(lines[line]) != "parameter" or lines[line].lstrip("$ \t").startswith("*"):
+            return None
+        piecewise = line + 1 < len(lines) and lines[line + 1].strip().startswith("{")
+        try:
+            parameter, _ = parse_parameter(features.operators(lines[line], self.model.features), line + 1, os.path.basename(self.model.model_filename), [])
+        except (OneilError, ValueError):
+            # Not a complete declaration yet
+            return None
+        if piecewise or parameter.piecewise or parameter.callable or parameter.minmax_equation or parameter.pointer:
+            return None
+
+        speculative = {"id": parameter.id, "line": line + 1, "value": None, "error": None}
+        try:
+            if parameter.independent:
+                speculative["value"] = parameter.human_readable()
+                return speculative
+            with contextlib.redirect_stdout(sys.stderr):
+                result = self.model.eval(parameter.equation)
+        except OneilError as e:
+            speculative["error"] = e.message()
+            return speculative
+
+        if not hasattr(result, "units"):
+            speculative["value"] = str(result)
+        elif not result.isdiscrete and result.units != parameter.units:
+            speculative["error"] = f"Calculated units ({un.hr_units(result.units)}) do not match the required units: ({un.hr_units(parameter.units)})."
+        elif result.isdiscrete or isinstance(result.min, str):
+            speculative["value"] = result.human_readable()
+        else:
+            speculative["value"] = un.hr_vals_and_units((result.min, result.max), result.units, parameter.hr_units or None, 4)
+        return speculative
+
+    def check_units(self):
+        """
+        The unit errors in the model file as edited, as JSON errors, and the parameters
+        and tests whose units were worked out again for them (see check.UnitCache).
+        """
+        from . import Model
+
+        with contextlib.redirect_stdout(sys.stderr):
+            model = Model(self.model.model_filename)
+        errors = self.unit_cache.unit_errors(model)
+        return {"diagnostics": [console.error_json(error) for error in errors], "checked": [name for _, name in self.unit_cache.checked]}
+
+    def _text(self):
+        if self.document is not None:
+            return self.document.text
+        with open(self.model.model_filename, "r", encoding="utf-8") as f:
+            return f.read()
+
+    def hover(self, line, character):
+        """
+        The label, units, value, and notes of the parameter at a 0-based line and column
+        (in the position encoding) of the model file, in Markdown, or None.
+        """
+        if not isinstance(line, int) or not isinstance(character, int):
+            raise ProtocolError(INVALID_PARAMS, "hover takes a \"line\" and a \"character\".")
+        text = self._text()
+        lines = text.splitlines()
+        if not 0 <= line < len(lines):
+            return None
+        index = positions.index_of(lines[line], character, self.position_encoding)
+        found = self.document.tokens() if self.document is not None else list(tokenize(text))
+        found = [token for token in found if token.line == line + 1]
+        at = next((i for i, token in enumerate(found) if token.start <= index < token.end), None)
+        if at is None or found[at].kind not in ("identifier", "definition", "submodel"):
+            return None
+
+        # `F.e` is one parameter: the ID before the "." and the submodel symbol after it.
+        first, last = at, at
+        if found[at].kind == "submodel" and at >= 2 and found[at - 1].text == ".":
+            first = at - 2
+        elif at + 2 < len(found) and found[at + 1].text == "." and found[at + 2].kind == "submodel":
+            last = at + 2
+        ID = "".join(token.text for token in found[first:last + 1])
+        try:
+            parameter = self._parameter(ID)
+        except ProtocolError:
+            return None
+
+        contents = [f"**{parameter.name}** (`{ID}`)"]
+        if parameter.hr_units:
+            contents.append(f"Units: `{parameter.hr_units}`")
+        value = parameter.human_readable() if parameter.min is not None else "not calculated"
+        contents.append(f"Value: `{value}`")
+        notes = "\n".join(note.strip() for note in parameter.notes).strip()
+        if notes:
+            contents.append(notes)
+        range_ = {
+            "start": {"line": line, "character": positions.column_of(lines[line], found[first].start, self.position_encoding)},
+            "end": {"line": line, "character": positions.column_of(lines[line], found[last].end, self.position_encoding)},
+        }
+        return {"contents": {"kind": "markdown", "value": "\n\n".join(contents)}, "range": range_}
+
+    def formatting(self):
+        """
+        LSP `TextEdit`s that format the model file as edited: one replacing the whole
+        text, or none if it's already formatted.
+        """
+        text = self._text()
+        formatted = fmt.format_text(text)
+        if formatted == text:
+            return []
+        line, character = positions.line_and_column(text, len(text), self.position_encoding)
+        end = {"line": line - 1, "character": character}
+        return [{"range": {"start": {"line": 0, "character": 0}, "end": end}, "newText": formatted}]
+
+    def _call(self, method, params):
+        if method == "initialize":
+            encodings = params.get("positionEncodings", [self.position_encoding])
+            if not isinstance(encodings, list):
+                raise ProtocolError(INVALID_PARAMS, "\"positionEncodings\" must be a list of encodings.")
+            self.position_encoding = next((encoding for encoding in encodings if encoding in positions.ENCODINGS), "utf-32")
+            return {"protocol": PROTOCOL_VERSION, "model": self.model.name, "inputs": self.inputs(), "positionEncoding": self.position_encoding}, []
+        if method == "setInput":
+            if "id" not in params or "value" not in params:
+                raise ProtocolError(INVALID_PARAMS, "setInput takes an \"id\" and a \"value\".")
+            return self.set_input(params["id"], params["value"])
+        if method == "getOutputs":
+            IDs = params.get("ids", list(self.model.parameters))
+            if not isinstance(IDs, list):
+                raise ProtocolError(INVALID_PARAMS, "\"ids\" must be a list of parameter IDs.")
+            return {"outputs": {ID: self._value(ID) for ID in IDs}}, []
+        if method == "subscribe":
+            value = self._value(params.get("id"))
+            if params["id"] not in self.subscriptions:
+                self.subscriptions.append(params["id"])
+            return {"id": params["id"], "value": value}, []
+        if method == "unsubscribe":
+            if params.get("id") in self.subscriptions:
+                self.subscriptions.remove(params["id"])
+            return None, []
+        if method == "oneil/modelTree":
+            return self.model_tree(), []
+        if method == "oneil/evaluate":
+            if params.get("reload"):
+                # Pick up edits to the model's files, keeping the inputs set so far.
+                self.model = self._load()
+            return {"results": self.model.results(source=True, encoding=self.position_encoding)}, []
+        if method == "oneil/didChange":
+            return self.did_change(params.get("changes")), []
+        if method == "oneil/hover":
+            return self.hover(params.get("line"), params.get("character")), []
+        if method == "oneil/speculate":
+            return self.speculate(params.get("line")), []
+        if method == "oneil/checkUnits":
+            return self.check_units(), []
+        if method == "oneil/builtins":
+            kind = params.get("kind")
+            if kind is not None and kind not in builtin.KINDS:
+                raise ProtocolError(INVALID_PARAMS, f"\"kind\" must be one of {', '.join(builtin.KINDS)}.")
+            return {"builtins": builtin.default().describe(kind)}, []
+        if method == "textDocument/formatting":
+            return self.formatting(), []
+        if method == "shutdown":
+            self.shutdown = True
+            return None, []
+        raise ProtocolError(METHOD_NOT_FOUND, f"Unknown method \"{method}\".")
+
+    def handle(self, line):
+        """
+        Answer one line of the protocol. Returns the messages to send: the response (unless
+        the request was a notification, without an "id") and any notifications.
+        """
+        from . import OneilError
+
+        try:
+            request = json.loads(line)
+        except json.JSONDecodeError as e:
+            return [_error(None, PARSE_ERROR, f"Invalid JSON: {e}")]
+        if not isinstance(request, dict) or not isinstance(request.get("method"), str):
+            return [_error(request.get("id") if isinstance(request, dict) else None, INVALID_REQUEST, "A request must be an object with a \"method\".")]
+
+        ID = request.get("id")
+        params = request.get("params", {})
+        try:
+            if not isinstance(params, dict):
+                raise ProtocolError(INVALID_PARAMS, "\"params\" must be an object.")
+            result, notifications = self._call(request["method"], params)
+        except ProtocolError as e:
+            return [_error(ID, e.code, str(e), e.data)] if "id" in request else []
+        except OneilError as e:
+            data = {"kind": e.kind(), "code": e.code(), "message": e.message(), "notes": e.notes()}
+            # As in LSP diagnostics, a link to the explanation of the code
+            if e.code():
+                data["codeDescription"] = {"href": explanations.url(e.code())}
+            return [_error(ID, MODEL_ERROR, e.message(), data)] if "id" in request else []
+
+        messages = [{"jsonrpc": "2.0", "id": ID, "result": result}] if "id" in request else []
+        return messages + notifications
+
+def _error(ID, code, message, data=None):
+    error = {"code": code, "message": message}
+    if data is not None:
+        error["data"] = data
+    return {"jsonrpc": "2.0", "id": ID, "error": error}
+
+def run(inp, designs=(), stdin=None, stdout=None):
+    """
+    Load a model and answer the protocol on stdin and stdout until "shutdown" or the end
+    of the input.
+    """
+    stdin = stdin or sys.stdin
+    stdout = stdout or sys.stdout
+    session = Session(inp, designs)
+    for line in stdin:
+        if not line.strip():
+            continue
+        for message in session.handle(line):
+            stdout.write(json.dumps(message) + "\n")
+            stdout.flush()
+        if session.shutdown:
+            break
@@ -1,3 +1,6 @@
+import contextlib
+import math
+import os
 import re
 import numpy as np
 
@@ -215,7 +218,105 @@ DIMENSIONLESS_UNITS = {
 if any(u for v in DIMENSIONLESS_UNITS.values() for u in v[0]):
     raise ValueError("Units in DIMENSIONLESS_UNITS should be {}.")
 
-LINEAR_UNITS = STANDARD_UNITS | alt(STANDARD_UNITS) | DIMENSIONLESS_UNITS | alt(DIMENSIONLESS_UNITS)
+AFFINE_DETAILS = """
+Affine units are absolute temperatures on scales that don't start at absolute zero, so
+converting them to K adds an offset as well as multiplying: K = value * multiplier + offset.
+They can't be combined with other units (W/°C), since the offset wouldn't mean anything;
+use K or a temperature difference there. A parameter in an affine unit is an absolute
+temperature, and the difference of two absolute temperatures is a temperature difference.
+"""
+AFFINE_UNITS = {
+    "°C": ({"K": 1}, 1, {"alt": [("degC", "degC"), ("Celsius", "Celsius")], "offset": 273.15}),
+    "°F": ({"K": 1}, 5 / 9, {"alt": [("degF", "degF"), ("Fahrenheit", "Fahrenheit")], "offset": 459.67 * 5 / 9}),
+}
+
+if invalid_units(AFFINE_UNITS):
+    raise ValueError("Invalid unit in AFFINE_UNITS: " + invalid_units(AFFINE_UNITS))
+
+DIFFERENCE_DETAILS = "Temperature differences, which convert to K by multiplying alone, like other linear units."
+DIFFERENCE_UNITS = {
+    "ΔK": ({"K": 1}, 1, {"alt": [("delta-K", "delta-K")]}),
+    "Δ°C": ({"K": 1}, 1, {"alt": [("delta-degC", "delta-degC")]}),
+    "Δ°F": ({"K": 1}, 5 / 9, {"alt": [("delta-degF", "delta-degF")]}),
+}
+
+if invalid_units(DIFFERENCE_UNITS):
+    raise ValueError("Invalid unit in DIFFERENCE_UNITS: " + invalid_units(DIFFERENCE_UNITS))
+
+LINEAR_UNITS = STANDARD_UNITS | alt(STANDARD_UNITS) | DIMENSIONLESS_UNITS | alt(DIMENSIONLESS_UNITS) | DIFFERENCE_UNITS | alt(DIFFERENCE_UNITS)
+
+DECLARED_DETAILS = """
+Units declared in models (`unit furlong = 201.168 m`), as a number of other units. A
+model can use the units it declares, after their declarations, and its designs can use
+them too. Values keep their display units, so they're displayed in them anywhere.
+"""
+# Declared units, by symbol, with the files that declare them in their details. They're
+# also added to LINEAR_UNITS and SYMBOLS, so they parse and display like other units.
+DECLARED_UNITS = {}
+
+# The declared units that can be used in the file being parsed (see scope()), or None
+# outside of parsing, where any of them can be
+_scope = None
+
+class ScopeError(ValueError):
+    pass
+
+# Map each natural language variation from alt() to the symbol of its unit (`sec` to
+# `s`). Variations that are symbols themselves are left out.
+def canonical_names(UNITS):
+    names = {}
+    for k, v in UNITS.items():
+        for name in alt({k: v}):
+            if name not in UNITS and name not in BASE_UNITS:
+                names.setdefault(name, k)
+    return names
+
+CANONICAL_NAMES = canonical_names(STANDARD_UNITS | DIMENSIONLESS_UNITS | AFFINE_UNITS | DIFFERENCE_UNITS)
+
+# The names of units without prefixes in lowercase (`newton` and `watt-hours`), since
+# people rarely type them with the capitalization above. Names that are ambiguous in
+# lowercase are left out.
+LOWERCASE_NAMES = {}
+for name, symbol in canonical_names(SI_UNITS | BASE_UNITS | LEGACY_UNITS | DIMENSIONLESS_UNITS).items():
+    if name.lower() in LOWERCASE_NAMES and LOWERCASE_NAMES[name.lower()] != symbol:
+        LOWERCASE_NAMES[name.lower()] = None
+    else:
+        LOWERCASE_NAMES[name.lower()] = symbol
+SYMBOLS = STANDARD_UNITS | DIMENSIONLESS_UNITS | BASE_UNITS | AFFINE_UNITS | DIFFERENCE_UNITS
+LOWERCASE_NAMES = {name: symbol for name, symbol in LOWERCASE_NAMES.items() if symbol is not None and name not in SYMBOLS}
+
+def _canonical_word(word, divided):
+    if word in CANONICAL_NAMES:
+        return CANONICAL_NAMES[word]
+    if word in SYMBOLS:
+        return word
+    if word.lower() in LOWERCASE_NAMES:
+        return LOWERCASE_NAMES[word.lower()]
+    for prefix, (_, _, name) in SI_PREFIXES.items():
+        # A prefix's name with a unit's name (`kilowatts`)
+        if name and word.lower().startswith(name) and LOWERCASE_NAMES.get(word.lower()[len(name):], "") and prefix + LOWERCASE_NAMES[word.lower()[len(name):]] in SI_MULTIPLES:
+            return prefix + LOWERCASE_NAMES[word.lower()[len(name):]]
+    if "-" in word:
+        # A product of names (`newton-meters`), which divides if it follows a "/"
+        parts = [_canonical_word(part, False) for part in word.split("-")]
+        if all(part in SYMBOLS for part in parts):
+            return ("/" if divided else "*").join(parts)
+    return word
+
+def canonical(unit_str):
+    """
+    A unit string with each natural language name replaced by its symbol (`meters/sec^2`
+    is `m/s^2`, `newton-meters` is `N*m`), so it parses to the same units.
+    """
+    if unit_str in CANONICAL_NAMES:
+        return CANONICAL_NAMES[unit_str]
+    if unit_str in SYMBOLS:
+        return unit_str
+    if unit_str.lower() in LOWERCASE_NAMES:
+        return LOWERCASE_NAMES[unit_str.lower()]
+    if unit_str.startswith("dB"):
+        return "dB" + canonical(unit_str[2:]) if unit_str[2:] else unit_str
+    return re.sub("[A-Za-z$%'\"°Δ]+(?:-[A-Za-z]+)*", lambda m: _canonical_word(m.group(), unit_str[:m.start()].endswith("/")), unit_str)
 
 def print_all():
     print("\n\nThe following units are supported by Oneil.")
@@ -232,8 +333,16 @@ def print_all():
     print("-"*30 + "\nLegacy Units\n" + "-"*30 + f"\n{LEGACY_DETAILS}\n" + "-"*30)
     for k, v in (LEGACY_UNITS | DIMENSIONLESS_UNITS).items():
         print(f"   - {k}, aka {v[2]['alt']}")
+    print("-"*30 + "\nTEMPERATURE UNITS\n" + "-"*30 + f"\n{AFFINE_DETAILS}\n{DIFFERENCE_DETAILS}\n" + "-"*30)
+    for k, v in (AFFINE_UNITS | DIFFERENCE_UNITS).items():
+        print(f"   - {k}, aka {v[2]['alt']}")
     print("-"*30 + "\nNONLINEAR UNITS\n" + "-"*30)
     print("Any linear unit (simple or compound, e.g. W/Hz) can be prepended by dB to produce a nonlinear logarithmic unit.")
+    print("Powers in dB relative to a milliwatt are dBmW (dBm is dB relative to a meter). Values in dB add and subtract in dB (see logarithmic()).")
+    if DECLARED_UNITS:
+        print("-"*30 + "\nDECLARED UNITS\n" + "-"*30 + f"\n{DECLARED_DETAILS}\n" + "-"*30)
+        for k, v in DECLARED_UNITS.items():
+            print(f"   - {k}, {v[1]:g} {hr_units(v[0]) or '(unitless)'}, declared in {', '.join(v[2]['declared'])}")
 
 
 #################################################
@@ -241,9 +350,17 @@ def print_all():
 #################################################
 
 def parse(unit_str):
+    unit_str = canonical(unit_str)
+    if _scope is not None:
+        for word in re.findall("[A-Za-z$%'\"°Δ]+", unit_str):
+            if word in DECLARED_UNITS and word not in _scope:
+                raise ScopeError(f"{word} is declared in {', '.join(DECLARED_UNITS[word][2]['declared'])}, not in this model. Declare it here to use it (`unit {word} = ...`).")
     if unit_str in BASE_UNITS:
         units = {unit_str: 1}
         unit_fx = lambda x: x
+    elif unit_str in AFFINE_UNITS:
+        units, multiplier, details = AFFINE_UNITS[unit_str]
+        unit_fx = lambda x: x * multiplier + details["offset"]
     elif unit_str in LINEAR_UNITS:
         units = LINEAR_UNITS[unit_str][0]
         multiplier = LINEAR_UNITS[unit_str][1]
@@ -286,12 +403,14 @@ def _validate_compound_unit_format(unit_str):
 
 
 def _parse_compound_units(unit_str):
+    unit_str = canonical(unit_str)
+
     # Validate unit string format
     _validate_compound_unit_format(unit_str)
     
     # Parse the unit string based on operators /, *, ^
     unit_list = [
-        x for x in re.findall("[A-Za-z$%'\"°]+", unit_str) if x not in UNIT_OPERATORS
+        x for x in re.findall("[A-Za-z$%'\"°Δ]+", unit_str) if x not in UNIT_OPERATORS
     ]
 
     value_list = [
@@ -299,7 +418,7 @@ def _parse_compound_units(unit_str):
     ]
 
     # Find the indices of the above matches
-    unit_indices = [m.span() for m in re.finditer("[A-Za-z$%'\"°]+", unit_str)]
+    unit_indices = [m.span() for m in re.finditer("[A-Za-z$%'\"°Δ]+", unit_str)]
 
     # Find indices of all numeric values
     value_indices = [m.span() for m in re.finditer("[0-9.]+", unit_str)]
@@ -351,6 +470,8 @@ def _parse_compound_units(unit_str):
                     for key, value in LINEAR_UNITS[unit][0].items():
                         units[key] -= value
                     multiplier /= LINEAR_UNITS[unit][1]
+        elif unit in AFFINE_UNITS:
+            raise ValueError(f"{unit} is an absolute temperature, which can't be combined with other units. Use K or Δ{unit}.")
         else:
             raise ValueError("Invalid unit: " + unit)
 
@@ -361,18 +482,107 @@ def _parse_compound_units(unit_str):
 
 
 
+def temperature(unit_str):
+    """
+    Whether units given as a string are an absolute temperature ("absolute", for affine
+    units like °C) or a temperature difference ("difference", like Δ°C), or None if they
+    aren't either or can't be told (K can be both).
+    """
+    unit_str = canonical(unit_str.strip()) if unit_str else ""
+    if unit_str in AFFINE_UNITS:
+        return "absolute"
+    if unit_str in DIFFERENCE_UNITS:
+        return "difference"
+    return None
+
+def logarithmic(unit_str):
+    """
+    Whether units given as a string are logarithmic (dB, dBW, dBmW/Hz). Values in them are
+    kept linear, but adding or subtracting two of them is done in dB: it multiplies or
+    divides their linear values.
+    """
+    return bool(unit_str) and canonical(unit_str.strip()).startswith("dB")
+
+@contextlib.contextmanager
+def scope(symbols=()):
+    """
+    Parse a file with the declared units it can use: the given ones and those it
+    declares (see declare()).
+    """
+    global _scope
+    outer, _scope = _scope, set(symbols)
+    try:
+        yield _scope
+    finally:
+        _scope = outer
+
+def declare(symbol, definition, file):
+    """
+    Declare a unit (`furlong`) as a number of other units (`201.168 m`) in a file, so
+    the file can use it. Declaring a unit again with the same definition (in another
+    model) is allowed. Raises ValueError for an invalid declaration.
+    """
+    if not re.fullmatch("[A-Za-z]+", symbol):
+        raise ValueError(f"\"{symbol}\" isn't a valid unit symbol. Use letters only.")
+    if symbol not in DECLARED_UNITS and (symbol in SYMBOLS or symbol in LINEAR_UNITS or canonical(symbol) != symbol or symbol.startswith("dB")):
+        raise ValueError(f"{symbol} is already a unit. Give the new unit another symbol.")
+    number, _, unit_str = definition.strip().partition(" ")
+    try:
+        number = float(number)
+    except ValueError:
+        raise ValueError(f"Units must be declared as a number of other units (`unit {symbol} = 201.168 m`).")
+    unit_str = unit_str.strip().lstrip(":").strip()
+    if unit_str and (temperature(unit_str) == "absolute" or logarithmic(unit_str)):
+        raise ValueError(f"Units can't be declared in {unit_str}, since it isn't linear.")
+    units, unit_fx = parse(unit_str) if unit_str else ({}, lambda x: x)
+    multiplier = unit_fx(number)
+    if not multiplier > 0:
+        raise ValueError(f"{symbol} must be a positive number of units.")
+
+    file = os.path.basename(file)
+    # A file that's the only one declaring a unit can change it (when it's reloaded).
+    if symbol in DECLARED_UNITS and DECLARED_UNITS[symbol][2]["declared"] != [file]:
+        declared_units, declared_multiplier, details = DECLARED_UNITS[symbol]
+        if declared_units != units or not math.isclose(declared_multiplier, multiplier, rel_tol=1e-12):
+            raise ValueError(f"{symbol} is already declared differently in {', '.join(details['declared'])}.")
+        if file not in details["declared"]:
+            details["declared"].append(file)
+    else:
+        DECLARED_UNITS[symbol] = SYMBOLS[symbol] = LINEAR_UNITS[symbol] = (units, multiplier, {"alt": [], "declared": [file]})
+    if _scope is not None:
+        _scope.add(symbol)
+
+
 #################################################
 # UNIT DISPLAY
 #################################################
 
+# Set from the user config (see config.py): "g" for general or "e" for scientific
+# notation, and "derived" or "base" units when no units are requested.
+NUMBER_FORMAT = "g"
+UNIT_STYLE = "derived"
+
 def _round(num, n=3):
     formatstr = "%." + str(n) + "g"
     return float(formatstr % num)
 
-def hr_vals_and_units(vals, units, pref=None, sigfigs=3):
+def _format(num, n=3):
+    if NUMBER_FORMAT == "e":
+        formatstr = "%." + str(max(n - 1, 0)) + "e"
+        return formatstr % num
+    return str(_round(num, n))
+
+def hr_vals_and_units(vals, units, pref=None, sigfigs=3, interval=False):
     hrvals, hrunits = _hr_parts(vals, units, pref)
 
-    hrstr = str(_round(hrvals[0], sigfigs))
+    # As an interval literal ("[2.1, 2.4] V") in one unit, so it can be written back in a
+    # model as it's shown
+    if interval and len(hrvals) > 1:
+        if hrunits[0] != hrunits[1]:
+            hrvals, hrunits = _hr_parts(vals, units, hrunits[0])
+        return "[" + _format(hrvals[0], sigfigs) + ", " + _format(hrvals[1], sigfigs) + "] " + hrunits[0]
+
+    hrstr = _format(hrvals[0], sigfigs)
 
     if len(hrvals) > 1 and hrvals[0] != hrvals[1]:
         if hrunits[0] != hrunits[1]:
@@ -380,18 +590,33 @@ def hr_vals_and_units(vals, units, pref=None, sigfigs=3):
                 " "
                 + hrunits[0]
                 + " | "
-                + str(_round(hrvals[1], sigfigs))
+                + _format(hrvals[1], sigfigs)
                 + " "
                 + hrunits[1]
             )
         else:
-            hrstr += "|" + str(_round(hrvals[1], sigfigs)) + " " + hrunits[0]
+            hrstr += "|" + _format(hrvals[1], sigfigs) + " " + hrunits[0]
     else:
         hrstr += " " + hrunits[0]
 
     return hrstr
 
 
+def hr_values(vals, units, pref=None):
+    """
+    Convert a (min, max) pair of base-unit values to the preferred units (or the derived
+    units closest to the max), using the same units for both. Returns the values and
+    the unit string.
+    """
+    try:
+        _, unit = _find_derived_unit(units, vals[1], pref) if pref or UNIT_STYLE != "base" else (None, None)
+        if unit:
+            return tuple(float(_find_derived_unit(units, val, unit)[0]) for val in vals), unit
+    except Exception:
+        pass
+    return tuple(float(val) for val in vals), _build_compound_unit_str(units).strip()
+
+
 def hr_units(units, vals=[0, 0]):
     pref=None
     _, hrunits = _hr_parts(vals, units, pref)
@@ -407,7 +632,10 @@ def _hr_parts(vals, units, pref=None):
     hrunit = ""
     hrval = ""
     for val in vals:
-        hrval, hrunit = _find_derived_unit(units, val, pref)
+        if pref or UNIT_STYLE != "base":
+            hrval, hrunit = _find_derived_unit(units, val, pref)
+        else:
+            hrunit = None
 
         if not hrunit:
             # Just build a raw unit string
@@ -422,13 +650,19 @@ def _find_derived_unit(base_units, value, pref=None):
 
     # If a unit was specified by the user, use it.
     if pref:
-        if pref in LINEAR_UNITS:
+        if canonical(pref) in AFFINE_UNITS:
+            units, multiplier, details = AFFINE_UNITS[canonical(pref)]
+            if units == base_units:
+                return (value - details["offset"]) / multiplier, pref
+            else:
+                raise ValueError("Requested units do not match parameter units.")
+        elif pref in LINEAR_UNITS:
             if LINEAR_UNITS[pref][0] == base_units:
                 return value / LINEAR_UNITS[pref][1], pref
             else:
                 raise ValueError("Requested units do not match parameter units.")
         elif pref.startswith("dB"):
-            inner_units, inner_multiplier = _parse_linear_unit(pref[2:])
+            inner_units, inner_multiplier = _parse_linear_unit(canonical(pref)[2:])
             if inner_units == base_units:
                 # Use errstate to allow log10(0) to return -inf (mathematically correct for dB)
                 with np.errstate(divide='ignore'):
@@ -0,0 +1,182 @@
+"""
+Watch mode (`oneil watch`): re-evaluate a model whenever one of its files changes and
+print only what changed.
+
+The changes are computed by `diff()` from two `Model.results()` dicts, so other
+frontends (editors, dashboards) can use it to show what an edit did.
+"""
+
+import json
+import os
+import time
+
+from . import bcolors
+from . import console
+from . import messages
+
+def _relative_change(old, new):
+    if old["units"] != new["units"]:
+        return float("inf")
+    if not all(isinstance(result[bound], (int, float)) for result in (old, new) for bound in ("min", "max")):
+        return 0.0 if (old["min"], old["max"]) == (new["min"], new["max"]) else float("inf")
+    change = max(abs(new["min"] - old["min"]), abs(new["max"] - old["max"]))
+    scale = max(abs(old["min"]), abs(old["max"]))
+    if not change:
+        return 0.0
+    return change / scale if scale else float("inf")
+
+def _status(passed):
+    return {True: "pass", False: "fail", None: "skipped"}[passed]
+
+def diff(old, new, threshold=0.0, path=()):
+    """
+    The changes between two `Model.results()` dicts: parameters whose values changed by
+    more than `threshold` (relative to the old value, e.g. 0.01 for 1%) or whose units
+    changed, parameters that were added or removed, and tests that changed status.
+
+    Returns {"parameters": [...], "added": [...], "removed": [...], "tests": [...]},
+    where each change has the `model` path (the submodel symbols from the top-level
+    model, e.g. "thermal.radiator") and the old and new results.
+    """
+    changes = {"parameters": [], "added": [], "removed": [], "tests": []}
+    model = ".".join(path) or new["model"]
+
+    old_parameters, new_parameters = old["parameters"], new["parameters"]
+    for ID, result in new_parameters.items():
+        if ID not in old_parameters:
+            changes["added"].append({"model": model, "id": ID, "new": result})
+            continue
+        change = _relative_change(old_parameters[ID], result)
+        if change > threshold:
+            changes["parameters"].append({"model": model, "id": ID, "old": old_parameters[ID], "new": result, "change": change})
+    for ID, result in old_parameters.items():
+        if ID not in new_parameters:
+            changes["removed"].append({"model": model, "id": ID, "old": result})
+
+    # Tests are matched by name, or by expression if they don't have one.
+    old_tests = {test.get("name") or test["expression"]: test for test in old["tests"].get("results", [])}
+    for test in new["tests"].get("results", []):
+        previous = old_tests.get(test.get("name") or test["expression"])
+        if previous and previous["passed"] != test["passed"]:
+            changes["tests"].append({"model": model, "name": test.get("name"), "expression": test["expression"], "line": test["line"], "old": _status(previous["passed"]), "new": _status(test["passed"])})
+
+    for symbol, submodel in new["submodels"].items():
+        if symbol in old["submodels"]:
+            for kind, items in diff(old["submodels"][symbol], submodel, threshold, path + (symbol,)).items():
+                changes[kind].extend(items)
+
+    return changes
+
+def _value(result):
+    if result["min"] == result["max"]:
+        value = f"{result['min']:.4g}" if isinstance(result["min"], float) else str(result["min"])
+    else:
+        value = f"{result['min']:.4g}|{result['max']:.4g}" if isinstance(result["min"], float) else f"{result['min']}|{result['max']}"
+    return value + (" " + result["units"] if result["units"] else "")
+
+def print_diff(changes):
+    """
+    Print the changes from `diff()`, or "No changes." if there are none.
+    """
+    if console.error_format == "json":
+        print(json.dumps(changes))
+        return
+
+    if not any(changes.values()):
+        print(messages.text("watch-no-changes", "No changes."))
+        return
+
+    for change in changes["parameters"]:
+        print(f"{change['id']} ({change['model']}): {_value(change['old'])} -> {bcolors.YELLOW}{_value(change['new'])}{bcolors.ENDC}")
+    for change in changes["added"]:
+        print(f"{bcolors.OKGREEN}+ {change['id']} ({change['model']}): {_value(change['new'])}{bcolors.ENDC}")
+    for change in changes["removed"]:
+        print(f"{bcolors.FAIL}- {change['id']} ({change['model']}){bcolors.ENDC}")
+    for change in changes["tests"]:
+        color = bcolors.OKGREEN if change["new"] == "pass" else bcolors.FAIL
+        label = f"\"{change['name']}\"" if change.get("name") else change["expression"]
+        print(f"Test ({change['model']}, line {change['line']}): {label}: {change['old']} -> {color}{change['new']}{bcolors.ENDC}")
+
+def files(model, designs=[]):
+    """
+    The files a loaded model depends on: its file, its submodels' files, the fragments
+    they include, and the design files.
+    """
+    from . import read_model_lines, OneilError
+
+    found = {os.path.abspath(_on_file(design)) for design in designs}
+    models = [model]
+    seen = set()
+    while models:
+        current = models.pop()
+        if current.model_filename in seen:
+            continue
+        seen.add(current.model_filename)
+        found.add(current.model_filename)
+        try:
+            found.update(os.path.abspath(line_no.fragment) for _, line_no in read_model_lines(current.model_filename) if getattr(line_no, "fragment", None))
+        except (OSError, OneilError):
+            pass
+        models.extend(entry["model"] for entry in current.submodels.values() if "model" in entry)
+    return found
+
+def _on_file(name):
+    return name if name.endswith(".on") else name + ".on"
+
+def _mtimes(paths):
+    mtimes = {}
+    for path in paths:
+        try:
+            mtimes[path] = os.path.getmtime(path)
+        except OSError:
+            mtimes[path] = None
+    return mtimes
+
+def parse_threshold(threshold):
+    """
+    A relative change threshold from "0.01" or "1%".
+    """
+    if threshold.endswith("%"):
+        return float(threshold[:-1]) / 100
+    return float(threshold)
+
+def run(inp, designs=[], overrides=[], threshold=0.0, interval=0.5):
+    """
+    Load the model, then reload it whenever one of its files changes and print the
+    changes in its results. Runs until interrupted.
+    """
+    from . import loader, _used_models, OneilError
+
+    try:
+        model = loader(inp, designs, capture_errors=False, overrides=overrides)
+        previous = model.results()
+        watched = files(model, designs)
+    except OneilError as e:
+        console.print_error(e)
+        previous = None
+        watched = {os.path.abspath(_on_file(path)) for path in [inp] + designs}
+        watched |= _used_models(watched)
+    mtimes = _mtimes(watched)
+    if not console.quiet:
+        print(messages.text("watch-start", "Watching {count} file(s) for changes. Press Ctrl+C to stop.", count=len(mtimes)))
+
+    while True:
+        time.sleep(interval)
+        if _mtimes(mtimes) == mtimes:
+            continue
+        mtimes = _mtimes(mtimes)
+        if not console.quiet and console.error_format != "json":
+            print(f"{bcolors.ITALIC}{messages.text('watch-reload', 'Change detected, reloading {model}...', model=inp)}{bcolors.ENDC}")
+
+        try:
+            # Print the whole summary until the model has loaded once, then only changes.
+            model = loader(inp, designs, capture_errors=False, quiet=previous is not None, overrides=overrides)
+        except OneilError as e:
+            console.print_error(e)
+            continue
+
+        results = model.results()
+        if previous is not None:
+            print_diff(diff(previous, results, threshold))
+        previous = results
+        mtimes = _mtimes(files(model, designs))
@@ -0,0 +1,123 @@
+"""
+New workspaces (`oneil init`), so a team starts a model library with the layout the rest
+of Oneil expects:
+
+    models/         the models, starting with an example entry model
+    models/models.txt
+                    the manifest: the models to evaluate, parser settings, glossary,
+                    and templates (see oneil.read_manifest)
+    models/tests/   fixtures for `oneil test --manifest models/models.txt`, which are
+                    loaded beside the manifest (see oneil.fixtures)
+    templates/      the team's templates for `oneil new` (see oneil.scaffold)
+    .editorconfig   indentation for notes, which editors otherwise mangle
+    .gitignore      Oneil's caches
+    README.md       how to work in the workspace, with editor setup hints
+
+Files that already exist are kept, so running it again only adds what's missing.
+"""
+
+import os
+
+from . import syntax
+from .function_cache import CACHE_DIR
+
+MANIFEST = os.path.join("models", "models.txt")
+
+def _files(name):
+    """
+    The files of a new workspace, by path, with their text.
+    """
+    from . import scaffold
+
+    example = scaffold.render(scaffold.TEMPLATES["component"][1], "component", "example")
+    return {
+        MANIFEST: f"""\
+# The models of {name}, one per line, relative to this file.
+# `oneil eval --manifest {MANIFEST}` evaluates them and
+# `oneil test --manifest {MANIFEST}` runs their tests and the fixtures in tests/.
+{syntax.to_manifest(syntax.DEFAULT_SETTINGS)}
+template: component ../templates/component.on
+glossary: {name}
+
+example.on
+""",
+        os.path.join("models", "example.on"): example,
+        os.path.join("models", "tests", "example.on"): """\
+    Regression fixture for the example model.
+
+use example as ex
+
+m.ex = 2 :kg
+
+Expected mass with margin: m_gold = 2.4 :kg
+
+@tolerance(0.1%)
+test "golden mass with margin": m_max.ex == m_gold
+""",
+        os.path.join("templates", "component.on"): scaffold.TEMPLATES["component"][1],
+        ".editorconfig": """\
+root = true
+
+[*.{on,oni}]
+indent_style = space
+indent_size = 4
+trim_trailing_whitespace = true
+insert_final_newline = true
+""",
+        ".gitignore": f"""\
+{CACHE_DIR}/
+*.onpack
+__pycache__/
+""",
+        "README.md": f"""\
+# {name}
+
+Oneil models. The manifest, `{MANIFEST}`, lists the models to evaluate, and
+`models/tests/` has regression fixtures.
+
+    oneil eval --manifest {MANIFEST}     # evaluate every model
+    oneil test --manifest {MANIFEST}     # run the tests and the fixtures
+    oneil lint --manifest {MANIFEST}     # check the models for common mistakes
+    oneil new --manifest {MANIFEST} -o models/<name>.on component <name>
+                                          # start a model from a template
+
+## Editors
+
+Notes are indented four spaces (see `.editorconfig`). Oneil's repository has syntax
+highlighting for vim (`vim/`) and a tree-sitter grammar (`tree-sitter/`). Other editors
+can highlight models with `oneil tokens --manifest {MANIFEST} <model>` and evaluate
+them live with `oneil tune --manifest {MANIFEST} <model>`.
+""",
+    }
+
+def init(directory=".", name=None):
+    """
+    Create a workspace in a directory (made if it doesn't exist), named after the
+    directory by default. Returns the paths it created and those it kept because they
+    already existed.
+    """
+    name = name or os.path.basename(os.path.abspath(directory))
+    created, kept = [], []
+    for path, text in _files(name).items():
+        full = os.path.join(directory, path)
+        if os.path.exists(full):
+            kept.append(path)
+            continue
+        os.makedirs(os.path.dirname(full) or ".", exist_ok=True)
+        with open(full, "w") as f:
+            f.write(text)
+        created.append(path)
+    return created, kept
+
+def run(directory="."):
+    """
+    Create a workspace and print what was created and kept.
+    """
+    from . import messages
+
+    created, kept = init(directory)
+    for path in created:
+        print(f"  + {path}")
+    for path in kept:
+        print(f"  = {path} (kept)")
+    print(messages.text("init-done", "Created {count} file(s) in {directory}.", count=len(created), directory=os.path.abspath(directory)))
@@ -0,0 +1,13 @@
+"""pytest configuration: restores the global state that each test set, and removes the
+workspaces it made (see helpers.py)."""
+
+import pytest
+
+import helpers
+
+
+@pytest.fixture(autouse=True)
+def _isolated():
+    with helpers.isolated():
+        yield
+    helpers.cleanup()
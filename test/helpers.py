@@ -0,0 +1,121 @@
+"""Shared helpers for the tests: temporary workspaces of model files, models loaded from
+them, and the oneil CLI run with the global state it sets restored afterwards.
+
+The workspaces are removed by cleanup() (after every test under pytest, see
+conftest.py), or when the process exits.
+"""
+
+import contextlib
+import copy
+import io
+import os
+import sys
+import tempfile
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import Model, console, corners, crash, deps, deterministic, diagnostics, externs, infinity, limits, memory, ordering, sandbox, syntax, traces  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+
+# The global state that flags, manifests, and evaluations set, by module
+STATE = [
+    (console, ["quiet", "deny_warnings", "error_format", "max_errors", "max_errors_flag", "errors_printed", "errors_suppressed", "exit_code"]),
+    (limits, ["limits", "overrides"]),
+    (diagnostics, ["policy", "overrides"]),
+    (infinity, ["settings", "overrides"]),
+    (ordering, ["settings", "overrides"]),
+    (syntax, ["settings"]),
+    (externs, ["values"]),
+    (traces, ["override"]),
+    (corners, ["enabled"]),
+    (deterministic, ["enabled"]),
+    (sandbox, ["no_python", "no_fs_outside_root"]),
+    (deps, ["depfile", "depfile_target"]),
+    (crash, ["command_line", "current_model"]),
+    (memory, ["enabled"]),
+    (oneil, ["active_cfg", "nominal", "MATH_CONSTANTS"]),
+]
+
+# The workspaces made since the last cleanup()
+_workspaces = []
+
+
+def write(directory, files):
+    """
+    Write files (text by path relative to the directory, or None for an empty directory)
+    into a directory, making the directories in their paths.
+    """
+    for name, text in files.items():
+        path = os.path.join(directory, name)
+        if text is None:
+            os.makedirs(path, exist_ok=True)
+            continue
+        os.makedirs(os.path.dirname(path), exist_ok=True)
+        with open(path, "w", encoding="utf-8") as f:
+            f.write(text)
+
+
+def workspace(files=None):
+    """
+    A new temporary directory with files in it (see write()). Returns its path.
+    """
+    directory = tempfile.TemporaryDirectory()
+    _workspaces.append(directory)
+    write(directory.name, files or {})
+    return directory.name
+
+
+def load(files, name, build=True, **options):
+    """
+    A model loaded from files in a new workspace, and built (quietly) unless build is
+    False. Returns the model and the workspace.
+    """
+    directory = workspace(files)
+    with working_directory(directory):
+        model = Model(name, **options)
+        if build:
+            model.build(quiet=True)
+    return model, directory
+
+
+@contextlib.contextmanager
+def isolated():
+    """
+    Restore the global state (see STATE) when the block exits. Dicts and sets are
+    restored in place, since modules import them by name.
+    """
+    saved = [(module, name, copy.copy(getattr(module, name))) for module, names in STATE for name in names]
+    try:
+        yield
+    finally:
+        for module, name, value in saved:
+            current = getattr(module, name)
+            if isinstance(current, (dict, set)):
+                current.clear()
+                current.update(value)
+            else:
+                setattr(module, name, value)
+
+
+def cli(args, directory=None):
+    """
+    Run the oneil CLI with args (in a directory), with its output captured and the global
+    state restored afterwards. Returns the output and the exit code.
+    """
+    output = io.StringIO()
+    code = console.EXIT_SUCCESS
+    with isolated(), working_directory(directory), contextlib.redirect_stdout(output):
+        try:
+            oneil.main(args)
+        except SystemExit as e:
+            code = e.code
+    return output.getvalue(), code
+
+
+def cleanup():
+    """
+    Remove the workspaces made since the last cleanup.
+    """
+    while _workspaces:
+        _workspaces.pop().cleanup()
@@ -0,0 +1,135 @@
+#!/usr/bin/env python3
+"""Tests for oneil eval: evaluating many models in one process, with their submodels
+shared, and the summary table.
+
+Run directly:
+
+    python test/test_batch_eval.py
+
+Or:
+
+    pytest test/test_batch_eval.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, evaluate_all  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """\
+Thrust: F = 20 :kN
+"""
+
+CRAFT = """\
+use engine as e
+Mass: m = 500 :kg
+$ Acceleration: a = F.e/m :m/s^2
+Minimum acceleration: a_min = 10 :m/s^2
+test: a > a_min
+"""
+
+PROBE = """\
+use engine as e
+Mass: m = 4000 :kg
+$ Acceleration: a = F.e/m :m/s^2
+Minimum acceleration: a_min = 10 :m/s^2
+test: a > a_min
+"""
+
+FILES = {"models/engine.on": ENGINE, "models/craft.on": CRAFT, "models/probe.on": PROBE, "targets.txt": "# The entry models\nmodels/craft.on\nmodels/engine.on\n"}
+
+
+def _evaluate(paths, **options):
+    directory = helpers.workspace(FILES)
+    output = io.StringIO()
+    with helpers.isolated(), contextlib.redirect_stdout(output):
+        results = evaluate_all([os.path.join(directory, path) for path in paths], **options)
+        exit_code = console.exit_code
+    return {os.path.basename(file): (summary, error) for file, summary, _, error in results}, output.getvalue(), exit_code
+
+
+def test_entries():
+    # Only the models that no other model uses are evaluated in a directory
+    results, output, _ = _evaluate(["models"])
+    assert sorted(results) == ["craft.on", "probe.on"], results
+    assert "Evaluated 2 model(s) sharing 1 submodel(s): 1 passed, 1 failed." in output, output
+
+
+def test_all_models():
+    results, _, _ = _evaluate(["models"], entries_only=False)
+    assert sorted(results) == ["craft.on", "engine.on", "probe.on"], results
+    assert results["engine.on"][0]["parameters"] == 1 and results["craft.on"][0]["parameters"] == 3, results
+
+
+def test_named():
+    # Models named on the command line are evaluated even if another one uses them
+    results, _, _ = _evaluate(["models/engine.on", "models/craft.on"])
+    assert sorted(results) == ["craft.on", "engine.on"], results
+
+
+def test_summary():
+    results, output, exit_code = _evaluate(["models"])
+    assert results["craft.on"][0]["failed"] == 0 and results["probe.on"][0]["failed"] == 1, results
+    assert exit_code == console.EXIT_TEST_FAILURE
+    for header in ("Model", "Parameters", "Tests", "Result", "Time (s)"):
+        assert header in output, output
+
+
+def test_errors():
+    directory = helpers.workspace({"craft.on": CRAFT})
+    with helpers.isolated(), contextlib.redirect_stdout(io.StringIO()):
+        [(_, summary, _, error)] = evaluate_all([directory])
+        exit_code = console.exit_code
+    assert summary is None and error.kind() == "ModelLoadingError", error
+    assert exit_code == console.EXIT_RESOLUTION_ERROR
+
+
+def test_manifest():
+    directory = helpers.workspace(FILES)
+    output, code = helpers.cli(["eval", "--manifest", "targets.txt"], directory)
+    assert code == console.EXIT_SUCCESS, output
+    assert "Evaluated 2 model(s)" in output and "2 passed, 0 failed." in output, output
+
+
+ALL_TESTS = [
+    test_entries,
+    test_all_models,
+    test_named,
+    test_summary,
+    test_errors,
+    test_manifest,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
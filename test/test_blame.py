@@ -0,0 +1,117 @@
+#!/usr/bin/env python3
+"""Tests for oneil.blame, the git history of a parameter's definition (`oneil blame`).
+
+Run directly:
+
+    python test/test_blame.py
+
+Or:
+
+    pytest test/test_blame.py
+"""
+
+import os
+import subprocess
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import blame, Model  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+MODEL = """Mode [low, high]: D = low
+Mass: m = 3 :kg
+Rate: r = {2*m if D == 'low' :kg
+          {3*m if D == 'high'
+"""
+
+
+def _git(directory, *args):
+    subprocess.run(["git", "-c", "user.name=Ada", "-c", "user.email=ada@example.com", *args], cwd=directory, check=True, capture_output=True)
+
+
+def _repository():
+    directory = helpers.workspace({"craft.on": MODEL})
+    _git(directory, "init", "-q")
+    path = os.path.join(directory, "craft.on")
+    _git(directory, "add", "craft.on")
+    _git(directory, "commit", "-q", "-m", "Add craft")
+    with open(path, "w") as f:
+        f.write(MODEL.replace("3*m", "4*m"))
+    _git(directory, "commit", "-q", "-a", "-m", "Faster when high")
+    return directory
+
+
+def test_span_includes_piecewise_lines():
+    directory = _repository()
+    with working_directory(directory):
+        model = Model("craft.on")
+        assert blame.span(model.parameters["r"], "craft.on") == ("craft.on", 3, 4)
+        assert blame.span(model.parameters["m"], "craft.on") == ("craft.on", 2, 2)
+
+
+def test_blame_and_history():
+    directory = _repository()
+    path = os.path.join(directory, "craft.on")
+    lines = blame.blame(path, 3, 4)
+    assert [(entry["line"], entry["summary"], entry["author"]) for entry in lines] == [(3, "Add craft", "Ada"), (4, "Faster when high", "Ada")]
+
+    changes = blame.history(path, 3, 4)
+    assert [change["summary"] for change in changes] == ["Faster when high", "Add craft"]
+    assert changes[0]["removed"] == ["          {3*m if D == 'high'"]
+    assert changes[0]["added"] == ["          {4*m if D == 'high'"]
+
+
+def test_uncommitted_changes():
+    directory = _repository()
+    path = os.path.join(directory, "craft.on")
+    with open(path, "w") as f:
+        f.write(MODEL.replace("m = 3", "m = 5"))
+    assert blame.blame(path, 2, 2)[0]["commit"] is None
+
+
+def test_not_a_repository():
+    path = os.path.join(helpers.workspace({"craft.on": MODEL}), "craft.on")
+    try:
+        blame.blame(path, 1, 1)
+        assert False, "expected an OSError"
+    except OSError:
+        pass
+
+
+ALL_TESTS = [
+    test_span_includes_piecewise_lines,
+    test_blame_and_history,
+    test_uncommitted_changes,
+    test_not_a_repository,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,148 @@
+#!/usr/bin/env python3
+"""Tests for oneil.builtin, the builder and introspection API for builtin functions,
+constants, and units (`builtins`, `oneil/builtins`, and plugins).
+
+Run directly:
+
+    python test/test_builtin.py
+
+Or:
+
+    pytest test/test_builtin.py
+"""
+
+import contextlib
+import io
+import json
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import builtin, tune  # noqa: E402
+import helpers  # noqa: E402
+
+
+def _double(x):
+    return x * 2
+
+
+def test_builder():
+    builtins = (builtin.Builtins.builder()
+        .with_function("double", _double, ["x"], "Twice x.")
+        .with_constant("g_std", 9.80665, "m/s^2", "Standard gravity.")
+        .with_unit("fathom", {"m": 1}, 1.8288, "A nautical fathom."))
+    assert builtins.names() == ["double", "fathom", "g_std"]
+    assert builtins.names("unit") == ["fathom"]
+    assert builtins.signature("double") == "double(x)"
+    assert builtins.signature("g_std") == "g_std :m/s^2"
+    assert builtins.lookup("fathom").value == ({"m": 1}, 1.8288)
+    assert builtins.lookup("double", "constant") is None and builtins.signature("nope") is None
+    assert builtins.describe("function") == [{"name": "double", "kind": "function", "signature": "double(x)", "doc": "Twice x."}]
+
+    for build in [
+        lambda: builtins.with_function("double", _double),
+        lambda: builtins.with_function("not a name", _double),
+        lambda: builtins.with_function("three", 3),
+        lambda: builtins.with_unit("furlong", {"yards": 1}, 220),
+    ]:
+        try:
+            build()
+        except ValueError:
+            continue
+        raise AssertionError("Expected a ValueError")
+
+
+def test_default():
+    builtins = builtin.default()
+    assert builtins.signature("min") == "min(a, b?)"
+    assert builtins.signature("sqrt") == "sqrt(x)"
+    assert "pi" in builtins.names("constant") and "kg" in builtins.names("unit")
+    assert all(builtins.lookup(name).doc for name in builtins.names("function"))
+
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        builtin.print_builtins(builtins)
+    assert "sqrt(x)" in output.getvalue() and "The square root of x." in output.getvalue()
+    assert "Units" not in output.getvalue()
+
+
+def test_register():
+    builtin.register(builtin.Builtins.builder()
+        .with_function("halve", lambda x: x / 2, ["x"], "Half of x.")
+        .with_constant("g_plug", 10, "m/s^2", "Round gravity.")
+        .with_unit("span", {"m": 1}, 0.2286, "A hand span."))
+    builtins = builtin.default()
+    assert builtins.signature("halve") == "halve(x)" and builtins.lookup("halve").doc == "Half of x."
+    assert builtins.signature("g_plug") == "g_plug :m/s^2"
+    assert builtins.lookup("span", "unit").doc == "A hand span."
+
+    model, _ = helpers.load({"craft.on": "Mass: m = 4 :kg\nWeight: W = m*g_plug :N\nHalf: h = halve(m) :kg\nReach: r = 10 :span\n"}, "craft.on")
+    assert model.parameters["W"].min == 40
+    assert model.parameters["h"].min == 2
+    assert abs(model.parameters["r"].min - 2.286) < 1e-9
+
+    try:
+        builtin.register(builtin.Builtins.builder().with_function("halve", _double))
+    except ValueError:
+        return
+    raise AssertionError("Expected a ValueError for a function that's already a builtin")
+
+
+def test_constants_after_loading():
+    # Evaluating constant expressions doesn't add Python's builtins to the constants
+    model, _ = helpers.load({"craft.on": "Angle: a = pi/4 :rad\nRange: r = 1|2*e :m\n"}, "craft.on")
+    assert abs(model.parameters["a"].min - math.pi / 4) < 1e-12
+    names = builtin.default().names("constant")
+    assert "__builtins__" not in names and not any(name.startswith("__") for name in names), names
+    assert {"pi", "e", "inf"} <= set(names), names
+
+
+def test_tune():
+    path = os.path.join(helpers.workspace({"craft.on": "Mass: m = 4 :kg\n"}), "craft.on")
+    requests = io.StringIO(json.dumps({"jsonrpc": "2.0", "id": 1, "method": "oneil/builtins", "params": {"kind": "function"}}) + "\n"
+        + json.dumps({"jsonrpc": "2.0", "id": 2, "method": "oneil/builtins", "params": {"kind": "keyword"}}) + "\n")
+    responses = io.StringIO()
+    tune.run(path, stdin=requests, stdout=responses)
+    functions, unknown = [json.loads(line) for line in responses.getvalue().splitlines()]
+    assert {"name": "sqrt", "kind": "function", "signature": "sqrt(x)", "doc": "The square root of x."} in functions["result"]["builtins"]
+    assert all(entry["kind"] == "function" for entry in functions["result"]["builtins"])
+    assert unknown["error"]["code"] == tune.INVALID_PARAMS
+
+
+ALL_TESTS = [
+    test_builder,
+    test_default,
+    test_register,
+    test_constants_after_loading,
+    test_tune,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,152 @@
+#!/usr/bin/env python3
+"""Tests for #[cfg(...)] guards: model variants toggled with --cfg flags, for declarations
+and whole sections.
+
+Run directly:
+
+    python test/test_cfg.py
+
+Or:
+
+    pytest test/test_cfg.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import OneilError, console  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """\
+#[cfg(flight)]
+Battery mass: m_b = 5 :kg
+
+#[cfg(not(flight))]
+Battery mass: m_b = 2 :kg
+    Bench supply stand-in.
+
+Heater threshold: m_h = 3 :kg
+
+#[cfg(any(flight, qual))]
+Heater power: P_h = {10 if m_b > m_h :W
+                    {4 if m_b <= m_h
+
+Structure mass: m_s = 20 :kg
+$ Total mass: m = m_s + m_b :kg
+
+#[cfg(all(flight, qual))]
+section Qualification
+
+Vibration margin: M_v = 3
+
+test: M_v > 2
+"""
+
+
+def _load(*flags):
+    with helpers.isolated():
+        oneil.active_cfg.update(flags)
+        model, _ = helpers.load({"craft.on": CRAFT}, "craft.on")
+    return model
+
+
+def test_predicates():
+    with helpers.isolated():
+        oneil.active_cfg.update({"flight"})
+        assert oneil.cfg_enabled("flight")
+        assert not oneil.cfg_enabled("qual")
+        assert oneil.cfg_enabled("not(qual)")
+        assert oneil.cfg_enabled("any(qual, flight)")
+        assert not oneil.cfg_enabled("all(qual, flight)")
+        assert oneil.cfg_enabled("all(flight, not(any(qual, ground)))")
+        for predicate, message in (("not(a, b)", "not() takes exactly one predicate."), ("flight-model", "Invalid cfg predicate \"flight-model\".")):
+            try:
+                oneil.cfg_enabled(predicate)
+            except ValueError as e:
+                assert str(e) == message, e
+            else:
+                assert False, f"Expected a ValueError for {predicate}"
+
+
+def test_default_variant():
+    model = _load()
+    assert model.parameters["m_b"].min == 2
+    assert any("Bench supply stand-in." in note for note in model.parameters["m_b"].notes), model.parameters["m_b"].notes
+    assert model.parameters["m"].min == 22
+    assert "P_h" not in model.parameters and "M_v" not in model.parameters
+    assert not model.tests
+
+
+def test_flight_variant():
+    model = _load("flight")
+    assert model.parameters["m_b"].min == 5
+    assert model.parameters["P_h"].min == 10
+    assert model.parameters["m"].min == 25
+    assert "M_v" not in model.parameters
+
+
+def test_section():
+    model = _load("flight", "qual")
+    assert model.parameters["M_v"].min == 3
+    assert len(model.tests) == 1 and model.tests[0].passed
+
+
+def test_invalid_guard():
+    try:
+        helpers.load({"craft.on": "#[cfg(flight-model)]\nMass: m = 2 :kg\n"}, "craft.on")
+    except OneilError as e:
+        assert e.kind() == "SyntaxError", e
+        assert "Guards must be of the form" in e.message(), e.message()
+    else:
+        assert False, "Expected a SyntaxError"
+
+
+def test_cli():
+    directory = helpers.workspace({"craft.on": CRAFT})
+    output, code = helpers.cli(["--cfg", "flight", "--cfg", "qual", "craft.on", "m", "quit"], directory)
+    assert code == console.EXIT_SUCCESS, output
+    assert "Config: flight, qual" in output, output
+    assert "m: 25" in output, output
+    assert oneil.active_cfg == set()
+
+
+ALL_TESTS = [
+    test_predicates,
+    test_default_variant,
+    test_flight_variant,
+    test_section,
+    test_invalid_guard,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
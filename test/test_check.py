@@ -0,0 +1,207 @@
+#!/usr/bin/env python3
+"""Tests for oneil.check: static validation of models without evaluating them.
+
+Run directly:
+
+    python test/test_check.py
+
+Or:
+
+    pytest test/test_check.py
+"""
+
+import contextlib
+import io
+import os
+import subprocess
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, check, console  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """\
+Mass: m = 3 :kg
+Acceleration: a = 2 :m/s^2
+Thrust: F = m*a :N
+Impulse: J = F*t_b :N*s
+Burn time: t_b = 30 :s
+Zero: z = m -- m :kg
+Ratio: r = F/(z*a) :1
+test: F > 0
+"""
+
+BROKEN = """\
+Mass: m = 3 :kg
+Acceleration: a = 2 :m/s^2
+Thrust: F = m*a*a :N
+Length: L = m + a :m
+Burn time: t_b = m - 1 :s
+test: F > m
+"""
+
+CRAFT = """\
+use engine as e
+Total thrust: F_t = 2*F.e :N
+"""
+
+
+def _git(directory, *args):
+    subprocess.run(["git", "-c", "user.name=Ada", "-c", "user.email=ada@example.com", *args], cwd=directory, check=True, capture_output=True)
+
+
+def _count(results):
+    return sum(len(result.unit_errors) + bool(result.load_error) for result in results)
+
+
+def _run(files, paths=None):
+    directory = helpers.workspace(files)
+    console.exit_code = console.EXIT_SUCCESS
+    output = io.StringIO()
+    try:
+        with working_directory(directory), contextlib.redirect_stdout(output):
+            results = check.run(paths or ["."])
+        return results, console.exit_code, output.getvalue()
+    finally:
+        console.exit_code = console.EXIT_SUCCESS
+
+
+def test_clean():
+    # r divides by zero when it's evaluated, but the check doesn't evaluate it
+    results, exit_code, output = _run({"engine.on": ENGINE, "craft.on": CRAFT})
+    assert _count(results) == 0 and exit_code == console.EXIT_SUCCESS, output
+    assert "No errors in 2 file(s)." in output
+
+
+def test_every_unit_error():
+    results, exit_code, output = _run({"engine.on": BROKEN})
+    assert _count(results) == 4, output
+    assert exit_code == console.EXIT_UNIT_ERROR
+    assert "F is declared in N, but its equation is in" in output
+    assert "Cannot add kg and" in output
+    assert "Cannot subtract kg and" not in output, "number literals can be added to anything"
+    assert "t_b is declared in s, but its equation is in kg" in output
+    assert "Cannot compare N to kg." in output and "In test F > m" in output
+
+
+def test_submodels():
+    # The submodel's errors are under its own file, once
+    results, _, output = _run({"engine.on": BROKEN, "craft.on": CRAFT})
+    assert [(result.file, len(result.unit_errors)) for result in results] == [("craft.on", 0), ("engine.on", 4)], results
+    assert "Broken files:\n    engine.on: 4 unit error(s)\n" in output and "1 of 2 file(s) broken." in output, output
+    results, _, _ = _run({"engine.on": BROKEN, "craft.on": CRAFT}, ["craft.on"])
+    assert [(result.file, len(result.unit_errors)) for result in results] == [("craft.on", 0), ("engine.on", 4)], results
+
+
+def test_load_errors():
+    results, exit_code, output = _run({"engine.on": ENGINE, "bad.on": "Mass: m = 3 :kg\nForce: F = m*g :N\n", "lib": None})
+    assert [result.file for result in results if result.load_error] == ["bad.on"], results
+    assert exit_code == console.EXIT_RESOLUTION_ERROR, output
+    assert "While checking bad.on" in output
+    assert "bad.on: doesn't load" in output
+
+
+def test_unused_files():
+    # Files under subdirectories are checked even if nothing uses them
+    results, _, output = _run({"engine.on": ENGINE, "lib": None, os.path.join("lib", "pump.on"): BROKEN})
+    assert [result.file for result in results] == ["engine.on", os.path.join("lib", "pump.on")], results
+    assert len(results[1].unit_errors) == 4
+
+
+def test_since():
+    directory = helpers.workspace({"engine.on": ENGINE, "craft.on": CRAFT, "pump.on": "Flow: Q = 2 :m^3/s\n", "tank.on": "Volume: V = 3 :m^3\n"})
+    _git(directory, "init", "-q")
+    _git(directory, "add", ".")
+    _git(directory, "commit", "-q", "-m", "Add models")
+
+    with working_directory(directory):
+        assert check.validate(["."], since="HEAD") == []
+        with open("engine.on", "w") as f:
+            f.write(BROKEN)
+        with open("valve.on", "w") as f:
+            f.write("Area: A = 1 :m^2\n")
+        # The changed model, the model that uses it, and the new file
+        assert [result.file for result in check.validate(["."], since="HEAD")] == ["craft.on", "engine.on", "valve.on"]
+        _git(directory, "add", ".")
+        _git(directory, "commit", "-q", "-m", "Break the engine")
+        os.remove("pump.on")
+        assert [result.file for result in check.validate(["."], since="HEAD~1")] == ["craft.on", "engine.on", "valve.on"]
+
+    console.exit_code = console.EXIT_SUCCESS
+    output = io.StringIO()
+    try:
+        with working_directory(directory), contextlib.redirect_stdout(output):
+            assert check.run(["."], since="no-such-ref") == []
+        assert console.exit_code == console.EXIT_USAGE and "Can't find the changes since no-such-ref" in output.getvalue()
+    finally:
+        console.exit_code = console.EXIT_SUCCESS
+
+
+def test_unit_errors():
+    directory = helpers.workspace({"engine.on": BROKEN})
+    with working_directory(directory):
+        errors = check.unit_errors(Model("engine.on"))
+    assert [error.kind() for error in errors] == ["UnitEvaluationError"] * 4
+
+
+def test_unit_cache():
+    directory = helpers.workspace({"engine.on": ENGINE, "craft.on": CRAFT})
+    cache = check.UnitCache()
+    with working_directory(directory):
+        assert cache.unit_errors(Model("craft.on")) == []
+        assert cache.checked == [(os.path.join(directory, "craft.on"), "F_t")], cache.checked
+        assert cache.unit_errors(Model("craft.on")) == [] and cache.checked == []
+        # A change in the units of a submodel's parameter checks the equations using it
+        helpers.write(directory, {"engine.on": ENGINE.replace("F = m*a :N", "F = m*a*a :N*m/s^2")})
+        errors = cache.unit_errors(Model("craft.on"))
+        assert cache.checked == [(os.path.join(directory, "craft.on"), "F_t")], cache.checked
+        assert [error.kind() for error in errors] == ["UnitEvaluationError"], errors
+    # The cache gives the same errors as checking from scratch
+    with working_directory(helpers.workspace({"engine.on": BROKEN})):
+        model = Model("engine.on")
+        cache = check.UnitCache()
+        assert [e.message() for e in cache.unit_errors(model)] == [e.message() for e in check.unit_errors(model)]
+        assert [e.message() for e in cache.unit_errors(model)] == [e.message() for e in check.unit_errors(model)]
+
+
+ALL_TESTS = [
+    test_clean,
+    test_every_unit_error,
+    test_submodels,
+    test_load_errors,
+    test_unused_files,
+    test_since,
+    test_unit_errors,
+    test_unit_cache,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,96 @@
+#!/usr/bin/env python3
+"""Tests for CircularDependencyError, raised for models that use each other in a cycle.
+
+Run directly:
+
+    python test/test_circular.py
+
+Or:
+
+    pytest test/test_circular.py
+"""
+
+import os
+import sys
+import tempfile
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, CircularDependencyError, Model  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+
+
+def _load(models, name):
+    with tempfile.TemporaryDirectory() as directory:
+        for file, text in models.items():
+            with open(os.path.join(directory, file), "w") as f:
+                f.write(text)
+        with working_directory(directory):
+            try:
+                return Model(name)
+            except CircularDependencyError as e:
+                return e
+
+
+def test_cycle():
+    error = _load({
+        "a.on": "use b as b\nX: x = 1\n",
+        "b.on": "Y: y = 2\n\nuse c as c\n",
+        "c.on": "use a as a\nZ: z = 3\n",
+    }, "a.on")
+    assert isinstance(error, CircularDependencyError)
+    assert error.message().endswith("a.on -> b.on -> c.on -> a.on")
+    assert [(r["file"], r["line"]) for r in error.related()] == [("a.on", 1), ("b.on", 3), ("c.on", 1)]
+    assert error.notes()[1] == "in b.on (line 3): use c as c"
+    assert console.exit_code_for(error) == console.EXIT_RESOLUTION_ERROR
+
+
+def test_self_use():
+    error = _load({"a.on": "X: x = 1\nuse a as a\n"}, "a.on")
+    assert isinstance(error, CircularDependencyError)
+    assert error.related() == [{"file": "a.on", "line": 2, "message": "use a as a"}]
+
+
+def test_shared_submodel_is_not_a_cycle():
+    model = _load({
+        "a.on": "use b as b\nuse c as c\nX: x = y.b + z.c\n",
+        "b.on": "use d as d\nY: y = 2*w.d\n",
+        "c.on": "use d as d\nZ: z = 3*w.d\n",
+        "d.on": "W: w = 1\n",
+    }, "a.on")
+    assert isinstance(model, Model)
+
+
+ALL_TESTS = [
+    test_cycle,
+    test_self_use,
+    test_shared_submodel_is_not_a_cycle,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,167 @@
+#!/usr/bin/env python3
+"""Tests for oneil.completion: model files, the IDs of a model file, the candidates for
+the words after `oneil`, completing IDs in the interpreter, and `oneil __complete`.
+
+Run directly:
+
+    python test/test_completion.py
+
+Or:
+
+    pytest test/test_completion.py
+"""
+
+import builtins
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import completion  # noqa: E402
+import helpers  # noqa: E402
+
+
+POWER = """\
+Solar flux: S = 1361 :W/m^2
+Panel area: A = 2 :m^2
+"""
+
+CRAFT = """\
+use power as pw
+
+Efficiency: eta = 0.3
+$ Power: P = S.pw*A.pw*eta :W
+Power margin: P_m = 10 :W
+"""
+
+FILES = {
+    "power.on": POWER,
+    "craft.on": CRAFT,
+    "hot.on": "Efficiency: eta = 0.35\n",
+    "broken.on": "Broken: b = = 2\n",
+    "designs": None,
+}
+
+
+def _complete(words):
+    directory = helpers.workspace(FILES)
+    with helpers.isolated(), helpers.working_directory(directory):
+        return completion.candidates(words)
+
+
+def test_model_files():
+    directory = helpers.workspace(FILES)
+    with helpers.working_directory(directory):
+        assert completion.model_files("c") == ["craft.on"]
+        assert completion.model_files("d") == ["designs" + os.sep]
+        # Design chains complete the model after the "@"
+        assert completion.model_files("hot@cr") == ["hot@craft.on"]
+        assert completion.model_files("nothing") == []
+
+
+def test_file_ids():
+    directory = helpers.workspace(FILES)
+    with helpers.isolated(), helpers.working_directory(directory):
+        assert completion.file_ids("craft.on") == ["eta", "P", "P_m", "S.pw", "A.pw"]
+        # The extension is optional, and designs complete the IDs of their model
+        assert completion.file_ids("craft") == completion.file_ids("hot@craft.on") == completion.file_ids("craft.on")
+        assert completion.file_ids("missing.on") == []
+        # Models that can't be loaded have no IDs, rather than an error
+        output = io.StringIO()
+        with contextlib.redirect_stdout(output):
+            assert completion.file_ids("broken.on") == []
+        assert output.getvalue() == "", output.getvalue()
+
+
+def test_candidates():
+    first = _complete([""])
+    assert "eval" in first and "--set" in first and "craft.on" in first, first
+    assert _complete(["ev"]) == ["eval"]
+    assert _complete(["completions", ""]) == completion.SHELLS
+    assert _complete(["eval", "--output", ""]) == ["table", "json"]
+    assert _complete(["eval", "--ma"]) == ["--manifest"]
+    assert "unit-name" in _complete(["-W", ""])
+    # Subcommands that take IDs complete them from the model file
+    assert _complete(["blame", "craft.on", "P"]) == ["P", "P_m"]
+    assert _complete(["sensitivity", "craft.on", ""]) == ["eta", "P", "P_m", "S.pw", "A.pw"]
+    assert _complete(["sweep", "craft.on", "--vary", "e"]) == ["eta="]
+    assert _complete(["craft.on", "--set", "P_"]) == ["P_m="]
+    # After a model, interpreter commands and IDs
+    after = _complete(["craft.on", "s"])
+    assert "summarize" in after and "S.pw" not in after, after
+    assert _complete(["craft.on", "S"]) == ["S.pw"]
+
+
+def test_interpreter():
+    # The IDs are only listed again when the model changes, not at every prompt
+    directory = helpers.workspace(FILES)
+    completed = []
+    commands = iter(["tree", "units", "reload", "tree"])
+
+    def prompt(text):
+        try:
+            return next(commands)
+        except StopIteration:
+            raise EOFError
+
+    complete_repl, original_input = completion.complete_repl, builtins.input
+    completion.complete_repl = completed.append
+    builtins.input = prompt
+    try:
+        with helpers.isolated(), helpers.working_directory(directory), contextlib.redirect_stdout(io.StringIO()):
+            model = oneil.loader("craft", [], capture_errors=False, partial=True)
+            try:
+                oneil.interpreter(model)
+            except EOFError:
+                pass
+    finally:
+        completion.complete_repl, builtins.input = complete_repl, original_input
+    assert len(completed) == 2 and completed[0] is model and completed[1] is not model, completed
+
+
+def test_cli():
+    directory = helpers.workspace(FILES)
+    output, code = helpers.cli(["__complete", "blame", "craft.on", "P"], directory)
+    assert code == 0 and output == "P\nP_m\n", output
+    output, _ = helpers.cli(["completions", "bash"], directory)
+    assert "oneil __complete" in output, output
+
+
+ALL_TESTS = [
+    test_model_files,
+    test_file_ids,
+    test_candidates,
+    test_interpreter,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
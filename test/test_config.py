@@ -0,0 +1,201 @@
+#!/usr/bin/env python3
+"""Tests for oneil.config: display settings, colors, and extra builtin constants from the
+user config file, and reloading it when it changes.
+
+Run directly:
+
+    python test/test_config.py
+
+Or:
+
+    pytest test/test_config.py
+"""
+
+import contextlib
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import MATH_CONSTANTS, OneilError, bcolors, config  # noqa: E402
+from oneil import units as un  # noqa: E402
+import helpers  # noqa: E402
+
+
+VALID = """\
+[display]
+sigfigs = 6
+notation = "scientific"
+units = "base"
+
+[colors]
+error = "magenta"
+warning = "cyan"
+
+[constants]
+g_0 = "9.80665 :m/s^2"
+c_light = 299792458
+"""
+
+
+@contextlib.contextmanager
+def _config(text=None):
+    """
+    Point ONEIL_CONFIG at a config file with the text (or a missing one if it's None) in a
+    new workspace, and yield its path and the constants it's loaded into. The defaults
+    are restored afterwards.
+    """
+    filename = os.path.join(helpers.workspace({"config.toml": text} if text is not None else {}), "config.toml")
+    previous = os.environ.get("ONEIL_CONFIG")
+    constants = dict(MATH_CONSTANTS)
+    os.environ["ONEIL_CONFIG"] = filename
+    try:
+        with helpers.isolated():
+            try:
+                yield filename, constants
+            finally:
+                os.environ["ONEIL_CONFIG"] = filename + ".missing"
+                config.load(constants)
+    finally:
+        if previous is None:
+            del os.environ["ONEIL_CONFIG"]
+        else:
+            os.environ["ONEIL_CONFIG"] = previous
+
+
+def _errors(constants):
+    try:
+        config.load(constants)
+    except OneilError as e:
+        assert e.kind() == "ConfigError", e
+        assert e.message() == "Some settings were ignored.", e.message()
+        return e.notes()
+    return []
+
+
+def test_path():
+    with _config("") as (filename, _):
+        assert config.path() == filename
+
+
+def test_valid():
+    with _config(VALID) as (_, constants):
+        assert _errors(constants) == []
+        assert (config.display["sigfigs"], config.display["notation"], config.display["units"]) == (6, "scientific", "base")
+        assert (un.NUMBER_FORMAT, un.UNIT_STYLE) == ("e", "base")
+        assert (bcolors.FAIL, bcolors.YELLOW) == (config.COLORS["magenta"], config.COLORS["cyan"])
+        assert constants["g_0"].min == 9.80665 and constants["g_0"].units == {"m": 1, "s": -2}
+        assert constants["c_light"] == 299792458
+
+
+def test_colors_disabled():
+    with _config("[colors]\nenabled = false\n") as (_, constants):
+        assert _errors(constants) == []
+        assert bcolors.FAIL == bcolors.ENDC == ""
+        assert set(bcolors.LEVEL_COLORS) == {""}
+
+
+def test_unknown():
+    with _config("[display]\nsigfigs = 6\nwidth = 80\n\n[colors]\ninfo = \"blue\"\nerror = \"teal\"\n\n[plugins]\nx = 1\n") as (_, constants):
+        errors = _errors(constants)
+        assert "Unknown section \"[plugins]\"." in errors, errors
+        assert "Unknown display setting \"width\"." in errors, errors
+        assert "Unknown color \"info\". Colors are: error, warning, pass, model, design." in errors, errors
+        assert "colors.error must be one of: red, green, yellow, blue, magenta, cyan, orange." in errors, errors
+        # The valid settings are still applied
+        assert config.display["sigfigs"] == 6
+
+
+def test_invalid_values():
+    with _config("[display]\nsigfigs = 0\nnotation = \"fixed\"\n\n[constants]\nflag = true\nrate = \"2*x :m\"\n") as (_, constants):
+        errors = _errors(constants)
+        assert "display.sigfigs must be a positive integer." in errors, errors
+        assert "display.notation must be one of: general, scientific." in errors, errors
+        assert "Constant \"flag\" must be a number or a \"<value> :<unit>\" string." in errors, errors
+        assert "Constant \"rate\" must be a value, not an equation." in errors, errors
+        assert config.display == config.DEFAULT_DISPLAY
+        assert "flag" not in constants and "rate" not in constants
+
+
+def test_shadowed_builtin():
+    with _config("[constants]\npi = 3\n") as (_, constants):
+        assert _errors(constants) == ["Constant \"pi\" is already a builtin constant."]
+        assert constants["pi"] == MATH_CONSTANTS["pi"]
+
+
+def test_reload():
+    with _config(VALID) as (filename, constants):
+        _errors(constants)
+        assert not config.changed()
+        helpers.write(os.path.dirname(filename), {"config.toml": "[display]\nsigfigs = 3\n\n[constants]\ng_0 = \"9.8 :m/s^2\"\n"})
+        stat = os.stat(filename)
+        os.utime(filename, (stat.st_atime, stat.st_mtime + 1))
+        assert config.changed()
+        assert _errors(constants) == []
+        assert not config.changed()
+        assert (config.display["sigfigs"], config.display["notation"]) == (3, "general")
+        assert constants["g_0"].min == 9.8 and "c_light" not in constants
+        assert bcolors.FAIL == config._default_colors["FAIL"]
+
+
+def test_removed():
+    with _config(VALID) as (filename, constants):
+        _errors(constants)
+        os.remove(filename)
+        assert config.changed()
+        assert _errors(constants) == []
+        assert config.display == config.DEFAULT_DISPLAY
+        assert (un.NUMBER_FORMAT, un.UNIT_STYLE) == ("g", "derived")
+        assert constants == MATH_CONSTANTS
+
+
+def test_broken():
+    with _config("[display\n") as (filename, constants):
+        try:
+            config.load(constants)
+        except OneilError as e:
+            assert e.kind() == "ConfigError", e
+            assert e.filename == filename
+        else:
+            assert False, "Expected a ConfigError"
+
+
+ALL_TESTS = [
+    test_path,
+    test_valid,
+    test_colors_disabled,
+    test_unknown,
+    test_invalid_values,
+    test_shadowed_builtin,
+    test_reload,
+    test_removed,
+    test_broken,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,136 @@
+#!/usr/bin/env python3
+"""Tests for oneil.consistency, the checks between the resolved model and the evaluator
+(`oneil dev verify`).
+
+Run directly:
+
+    python test/test_consistency.py
+
+Or:
+
+    pytest test/test_consistency.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import ConsistencyError, Model, consistency  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = "Max thrust: F_max = 5 :N\ntest {F_min}: F_max >= F_min\n"
+
+CRAFT = """use engine(F_min=F_req) as e
+Mass: m = 10|12 :kg
+Required thrust: F_req = 2 :N
+Mode [high, low]: mode = high
+Gravity: g = {9.8*m/2**2 if mode == 'high' :kg
+             {0*m if mode == 'low'
+Thrust: F = min(F_max.e, 4) :N
+Reference: R => m
+test "light": m < 100*m/m and not (m > 1000*m/m)
+"""
+
+
+def _load():
+    directory = helpers.workspace({"engine.on": ENGINE, "craft.on": CRAFT})
+    with working_directory(directory):
+        return Model("craft.on"), directory
+
+
+def test_consistent():
+    model, _ = _load()
+    assert consistency.problems(model) == [], consistency.problems(model)
+    consistency.verify(model)
+
+
+def test_unresolved():
+    model, _ = _load()
+    model.parameters["F"].args.append("F_min.e")
+    model.parameters["g"].equation[1][0].args.append("ghost")
+    model.tests[0].args.append("x.nowhere")
+    messages = [(problem["line"], problem["message"]) for problem in consistency.problems(model)]
+    assert messages == [
+        (5, "Branch 2 of Gravity (g) uses ghost, which doesn't resolve to a parameter."),
+        (7, "Thrust (F) uses F_min.e, which doesn't resolve to a parameter."),
+        (9, 'Test "light" uses x.nowhere, which doesn\'t resolve to a parameter or test input.'),
+    ], messages
+
+
+def test_functions_and_kinds():
+    model, _ = _load()
+    model.parameters["F"].equation = "par_nope(F_max.e)"
+    model.parameters["R"].equation = "M"
+    model.submodels["e"]["inputs"]["F_min"] = "F_gone"
+    messages = [problem["message"] for problem in consistency.problems(model)]
+    assert "Thrust (F) calls par_nope(), which isn't a function the evaluator knows." in messages, messages
+    assert "Reference (R) points to M, which doesn't resolve to a parameter." in messages, messages
+    assert "The test input F_min=F_gone for e doesn't resolve to a parameter." in messages, messages
+
+    try:
+        consistency.verify(model)
+    except ConsistencyError as e:
+        assert e.code() == "E0802" and len(e.notes()) == 3, e.notes()
+        assert "in craft.on (line 7): Thrust (F) calls par_nope()" in e.notes()[0], e.notes()
+        return
+    raise AssertionError("Expected a ConsistencyError")
+
+
+def test_run():
+    _, directory = _load()
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        found = consistency.run(os.path.join(directory, "craft.on"))
+    assert found == [] and "consistent" in output.getvalue()
+
+    previous = os.environ.get("ONEIL_DEBUG")
+    os.environ["ONEIL_DEBUG"] = "1"
+    try:
+        assert consistency.enabled()
+        _load()
+    finally:
+        if previous is None:
+            del os.environ["ONEIL_DEBUG"]
+        else:
+            os.environ["ONEIL_DEBUG"] = previous
+
+
+ALL_TESTS = [
+    test_consistent,
+    test_unresolved,
+    test_functions_and_kinds,
+    test_run,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,126 @@
+#!/usr/bin/env python3
+"""Tests for oneil.corners, which ends of their inputs' ranges give parameters' bounds.
+
+Run directly:
+
+    python test/test_corners.py
+
+Or:
+
+    pytest test/test_corners.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, corners  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+SUBMODEL = """\
+Payload mass: m_p = 1|2 :kg
+"""
+
+MODEL = """\
+use payload as p
+
+Dry mass: m_dry = 10|12 :kg
+Propellant mass: m_prop = 3|5 :kg
+Vented mass: m_vent = 0.5|1 :kg
+Fixed mass: m_fix = 2 :kg
+Wet mass: m_wet = m_dry + m_prop :kg
+Total mass: m_total = m_wet - m_vent + m_p.p + m_fix :kg
+Margin: m_margin = m_total - m_dry :kg
+"""
+
+
+def _load(enabled=True):
+    directory = helpers.workspace({"payload.on": SUBMODEL, "craft.on": MODEL})
+    corners.enabled = enabled
+    try:
+        with working_directory(directory):
+            model = Model("craft.on")
+            model.build(quiet=True)
+            return model
+    finally:
+        corners.enabled = False
+
+
+def test_corners():
+    model = _load()
+    assert model.parameters["m_wet"].corners == {"min": {"m_dry": "min", "m_prop": "min"}, "max": {"m_dry": "max", "m_prop": "max"}}
+    total = model.parameters["m_total"].corners
+    assert total["min"] == {"m_wet": "min", "m_vent": "max", "m_p.p": "min"}, total
+    assert total["max"] == {"m_wet": "max", "m_vent": "min", "m_p.p": "max"}, total
+    assert model.parameters["m_dry"].corners is None
+
+
+def test_stackup():
+    model = _load()
+    assert corners.stackup(model, "m_total", "min") == {"m_dry": "min", "m_prop": "min", "m_vent": "max", "m_p.p": "min"}
+    # m_margin needs m_dry at its min through m_total, and at its max itself
+    assert corners.stackup(model, "m_margin", "min")["m_dry"] == "both"
+
+
+def test_explain():
+    model = _load()
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        model.explain("m_total")
+    assert "Min from: m_wet at its min, m_vent at its max, m_p.p at its min" in output.getvalue(), output.getvalue()
+    assert "Stackup: m_dry at its min, m_prop at its min, m_vent at its max, m_p.p at its min" in output.getvalue(), output.getvalue()
+
+
+def test_results():
+    model = _load()
+    results = model.results()
+    assert results["parameters"]["m_wet"]["corners"]["max"] == {"m_dry": "max", "m_prop": "max"}
+    assert "corners" not in results["parameters"]["m_dry"]
+
+
+def test_off_by_default():
+    model = _load(enabled=False)
+    assert all(parameter.corners is None for parameter in model.parameters.values())
+    assert "corners" not in model.results()["parameters"]["m_wet"]
+
+
+ALL_TESTS = [
+    test_corners,
+    test_stackup,
+    test_explain,
+    test_results,
+    test_off_by_default,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
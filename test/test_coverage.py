@@ -0,0 +1,104 @@
+#!/usr/bin/env python3
+"""Tests for oneil.coverage, which parameters and branches a model's tests exercise.
+
+Run directly:
+
+    python test/test_coverage.py
+
+Or:
+
+    pytest test/test_coverage.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import coverage, loader  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """Thrust: F = 10 :N
+Drag: D = 2 :N
+Spare: S = 1 :N
+"""
+
+CRAFT = """use engine as e
+
+Height: h = 5 :km
+Switch height: h_s = 10 :km
+Altitude gain: g = {2*h if h < h_s :km
+    {h + h_s if h >= h_s
+Mass(1, 100): m = 4 :kg
+Net force: F_n = F.e - D.e :N
+
+test "gain": g > h
+test "net": F_n < F.e
+"""
+
+
+def _model():
+    directory = helpers.workspace({"engine.on": ENGINE, "craft.on": CRAFT})
+    with working_directory(directory):
+        return loader("craft", [], capture_errors=False, quiet=True), directory
+
+
+def test_parameters_and_branches():
+    model, directory = _model()
+    report = {os.path.basename(entry["file"]): entry for entry in coverage.coverage(model)}
+    assert set(report) == {"craft.on", "engine.on"}, report
+
+    craft = {p["id"]: p["covered"] for p in report["craft.on"]["parameters"]}
+    assert craft == {"h": True, "h_s": True, "g": True, "m": False, "F_n": True}, craft
+    engine = {p["id"]: p["covered"] for p in report["engine.on"]["parameters"]}
+    assert engine == {"F": True, "D": True, "S": False}, engine
+
+    assert [(b["branch"], b["covered"]) for b in report["craft.on"]["branches"]] == [(0, True), (1, False)]
+    assert [p["id"] for p in report["craft.on"]["unchecked_limits"]] == ["m"]
+    assert report["engine.on"]["unchecked_limits"] == [], "default limits aren't reported"
+
+
+def test_html_report():
+    model, directory = _model()
+    with working_directory(directory):
+        page = coverage.html_report(model)
+    assert '<span class="uncovered"><span class="line-number">7</span>Mass(1, 100)' in page, page
+    assert "limits never checked" in page
+    assert '<span class="uncovered"><span class="line-number">6</span>' in page, "the second branch isn't exercised"
+    assert '<span class="covered"><span class="line-number">5</span>' in page
+
+
+ALL_TESTS = [
+    test_parameters_and_branches,
+    test_html_report,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,149 @@
+#!/usr/bin/env python3
+"""Tests for oneil.crash: crash reports for bugs in Oneil, written instead of a raw
+traceback, and the interpreter session that keeps going after one.
+
+Run directly:
+
+    python test/test_crash.py
+
+Or:
+
+    pytest test/test_crash.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, console, crash  # noqa: E402
+import helpers  # noqa: E402
+
+
+@contextlib.contextmanager
+def _crash_directory():
+    directory = helpers.workspace()
+    previous = os.environ.get("ONEIL_CRASH_DIR")
+    os.environ["ONEIL_CRASH_DIR"] = directory
+    try:
+        yield directory
+    finally:
+        if previous is None:
+            del os.environ["ONEIL_CRASH_DIR"]
+        else:
+            os.environ["ONEIL_CRASH_DIR"] = previous
+
+
+def _error():
+    try:
+        {}["missing"]
+    except KeyError as e:
+        return e
+
+
+def _report(error_format="human"):
+    errors = io.StringIO()
+    with _crash_directory() as directory, helpers.isolated(), contextlib.redirect_stderr(errors):
+        console.error_format = error_format
+        crash.command_line = ["craft.on", "--quiet"]
+        crash.current_model = "/models/craft.on"
+        path = crash.report(_error(), "summarize")
+        exit_code = console.exit_code
+    return path, directory, errors.getvalue(), exit_code
+
+
+def test_bundle():
+    with helpers.isolated():
+        crash.command_line = ["craft.on", "--quiet"]
+        crash.current_model = "/models/craft.on"
+        text = crash.bundle(_error(), "summarize")
+    lines = text.splitlines()
+    assert lines[0] == "Oneil crash report"
+    assert f"Version: {console.__version__}" in lines
+    assert "Command: oneil craft.on --quiet" in lines
+    assert "Model: /models/craft.on" in lines
+    assert "Interpreter command: summarize" in lines
+    assert "Traceback (most recent call last):" in text and "KeyError: 'missing'" in text, text
+
+
+def test_report():
+    path, directory, errors, exit_code = _report()
+    assert os.path.dirname(path) == directory and os.path.basename(path).startswith("oneil-crash-"), path
+    with open(path) as f:
+        assert "KeyError: 'missing'" in f.read()
+    assert "InternalError[E0801]" in errors, errors
+    assert "Oneil crashed (KeyError: 'missing'). This is a bug in Oneil, not in your model." in errors, errors
+    assert f"A crash report was written to {path}" in errors, errors
+    assert "Traceback" not in errors, errors
+    assert exit_code == console.EXIT_INTERNAL_ERROR
+
+
+def test_report_json():
+    path, _, errors, _ = _report("json")
+    report = json.loads(errors)
+    assert report["kind"] == "InternalError" and report["exit_code"] == console.EXIT_INTERNAL_ERROR, report
+    assert report["notes"] == [f"A crash report was written to {path}"], report
+
+
+def test_session_continues():
+    directory = helpers.workspace({"craft.on": "Mass: m = 2 :kg\n"})
+    summarize = Model.summarize
+
+    def broken(self, *args, **kwargs):
+        raise RuntimeError("broken summary")
+
+    errors = io.StringIO()
+    Model.summarize = broken
+    try:
+        with _crash_directory() as reports, contextlib.redirect_stderr(errors):
+            output, code = helpers.cli(["--quiet", "craft.on", "summarize", "m", "quit"], directory)
+            written = os.listdir(reports)
+    finally:
+        Model.summarize = summarize
+    assert code == console.EXIT_INTERNAL_ERROR, output
+    assert "RuntimeError: broken summary" in errors.getvalue(), errors.getvalue()
+    # The next command still runs
+    assert "m: 2" in output, output
+    assert len(written) == 1, written
+    with open(os.path.join(reports, written[0])) as f:
+        report = f.read()
+    assert "Interpreter command: summarize" in report and f"Model: {os.path.join(directory, 'craft.on')}" in report, report
+
+
+ALL_TESTS = [
+    test_bundle,
+    test_report,
+    test_report_json,
+    test_session_continues,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
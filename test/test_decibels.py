@@ -0,0 +1,184 @@
+#!/usr/bin/env python3
+"""Tests for values in dB in models: sums and differences in dB, the linear math that
+models did before them, linear() and decibels(), and mixing dB with linear values.
+
+Run directly:
+
+    python test/test_decibels.py
+
+Or:
+
+    pytest test/test_decibels.py
+"""
+
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import ParameterError, PiecewiseUnitError, UnitEvaluationError  # noqa: E402
+import helpers  # noqa: E402
+
+
+MODEL = """\
+Transmit power: P_tx = 10 :dBW
+Antenna gain: G_tx = 30 :dB
+Path loss: L_p = 180|190 :dB
+Received power: P_rx = P_tx + G_tx - L_p :dBW
+Noise power: P_n = -100 :dBmW
+Signal power: P_s = -90 :dBmW
+Signal-to-noise ratio: S_N = P_s - P_n :dB
+Linear signal-to-noise ratio: S_L = P_s/P_n :dB
+Total power: P_sum = linear(P_s) + linear(P_n) :dBmW
+Loss as a gain: G_L = -L_p :dB
+
+test: S_N > G_tx - L_p
+"""
+
+
+def _close(values, expected):
+    return all(math.isclose(value, e, rel_tol=1e-9) for value, e in zip(values, expected))
+
+
+def _range(model, ID):
+    result = model.parameters[ID].result()
+    return result["min"], result["max"]
+
+
+def _load(text):
+    return helpers.load({"link.on": text}, "link.on")[0]
+
+
+def _expect_error(text, error=UnitEvaluationError):
+    try:
+        _load(text)
+    except error as e:
+        return e
+    raise AssertionError(f"Expected a {error.__name__}")
+
+
+def test_sums_in_db():
+    model = _load(MODEL)
+    assert _close(_range(model, "P_rx"), (-150, -140)), _range(model, "P_rx")
+    assert _close(_range(model, "S_N"), (10, 10)), _range(model, "S_N")
+    assert _close(_range(model, "S_L"), (10, 10)), _range(model, "S_L")
+    assert _close(_range(model, "G_L"), (-190, -180)), _range(model, "G_L")
+    assert model.parameters["P_rx"].logarithmic and not model.parameters["S_L"].result()["units"] == "W"
+    assert model.test() == (0, 1)
+
+
+def test_linear_math():
+    # Products and quotients of values in dB, and numbers added to them, are linear math,
+    # as they were before dB arithmetic.
+    model = _load("""\
+Gain: G = 10 :dB
+Signal power: P_s = -90 :dBmW
+Noise power: P_n = -100 :dBmW
+Ratio: S_L = P_s/P_n :dB
+Amplified power: P_a = P_s*G :dBmW
+Doubled gain: G_2 = 2*G :dB
+Gain plus one: G_1 = G + 1 :dB
+Twelve minus gain: G_m = 12 - G
+""")
+    assert _close(_range(model, "S_L"), (10, 10)), _range(model, "S_L")
+    assert _close(_range(model, "P_a"), (-80, -80)), _range(model, "P_a")
+    assert _close(_range(model, "G_2"), (10 * math.log10(20),) * 2), _range(model, "G_2")
+    assert _close(_range(model, "G_1"), (10 * math.log10(11),) * 2), _range(model, "G_1")
+    assert _close(_range(model, "G_m"), (2, 2)), _range(model, "G_m")
+
+
+def test_conversions():
+    model = _load(MODEL + """\
+Gain ratio: R = linear(G_tx)
+Twenty: n_20 = 20
+Gain of 20 dB: R_20 = linear(n_20)
+Ratio in dB: S_dB = decibels(S_L)
+Hundred: n_100 = 100
+Ratio of 100 in dB: D_100 = decibels(n_100)
+""")
+    assert _close(_range(model, "R"), (1000, 1000)), _range(model, "R")
+    assert _close(_range(model, "R_20"), (100, 100)), _range(model, "R_20")
+    assert _close(_range(model, "S_dB"), (10, 10)), _range(model, "S_dB")
+    assert _close(_range(model, "D_100"), (20, 20)), _range(model, "D_100")
+    assert not model.parameters["R"].logarithmic
+    assert _close(_range(model, "P_sum"), (10 * math.log10(1e-9 + 1e-10),) * 2), _range(model, "P_sum")
+    error = _expect_error(MODEL + "Length: l = 3 :m\nLinear length: L_l = linear(l) :m\n")
+    assert "linear() must be in dB or a unitless number of dB" in error.message(), error.message()
+    error = _expect_error(MODEL + "Power in dB: P_dB = decibels(P_tx)\n")
+    assert "decibels() must be unitless" in error.message(), error.message()
+    _expect_error(MODEL + "Zero: z = 0\nZero in dB: z_dB = decibels(z)\n", ParameterError)
+
+
+def test_dbm():
+    model = _load(MODEL)
+    assert math.isclose(model.parameters["P_n"].min, 1e-13, rel_tol=1e-9), model.parameters["P_n"].min
+    # dBm is dB relative to a meter
+    model = _load("Distance: d = 30 :dBm\n")
+    assert model.parameters["d"].units == {"m": 1} and math.isclose(model.parameters["d"].min, 1000, rel_tol=1e-9), model.parameters["d"].min
+
+
+def test_mixing_linear():
+    error = _expect_error(MODEL.replace(":dBW\nAntenna gain: G_tx = 30 :dB", ":dBW\nAntenna gain: G_tx = 1000"))
+    assert "in dB, and G_tx, which is linear" in error.message(), error.message()
+
+
+def test_adding_powers():
+    error = _expect_error(MODEL.replace("linear(P_s) + linear(P_n)", "P_s + P_n"))
+    assert "both in dB with units" in error.message(), error.message()
+
+
+def test_piecewise():
+    model = _load(MODEL + """\
+Mode [clear, rain]: mode = clear
+Rain loss: L_r = 3 :dB
+Faded power: P_f = {P_tx + G_tx - L_p if mode == 'clear' :dBW
+                   {P_rx - L_r if mode == 'rain'
+""")
+    assert _close(_range(model, "P_f"), (-150, -140)), _range(model, "P_f")
+    error = _expect_error(MODEL + """\
+Mode [clear, rain]: mode = clear
+Rain loss: L_r = 2
+Faded power: P_f = {P_tx + G_tx - L_p if mode == 'clear' :dBW
+                   {P_rx - L_r if mode == 'rain'
+""", PiecewiseUnitError)
+    assert "in dB and a linear value" in error.notes_[0], error.notes_
+
+
+ALL_TESTS = [
+    test_sums_in_db,
+    test_linear_math,
+    test_conversions,
+    test_dbm,
+    test_mixing_linear,
+    test_adding_powers,
+    test_piecewise,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
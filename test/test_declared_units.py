@@ -0,0 +1,152 @@
+#!/usr/bin/env python3
+"""Tests for units declared in models (`unit furlong = 201.168 m`).
+
+Run directly:
+
+    python test/test_declared_units.py
+
+Or:
+
+    pytest test/test_declared_units.py
+"""
+
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, SyntaxError, UnitParseError  # noqa: E402
+from oneil import units as un  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+from oneil.tokens import tokenize  # noqa: E402
+import helpers  # noqa: E402
+
+
+# Declared units are global, so each test declares its own.
+MODEL = """\
+unit furlong = 201.168 m
+unit fortnight = 14 day
+
+Track length: L = 10 :furlong
+Race time: t = 2 :fortnight
+Average speed: v = L/t :furlong/fortnight
+Average speed in SI: v_si = L/t :m/s
+"""
+
+
+def _load(files, model="race.on", design=None):
+    directory = helpers.workspace(files)
+    with working_directory(directory):
+        model = Model(model)
+        if design:
+            model.overwrite([design], quiet=True)
+        model.build(quiet=True)
+    return model
+
+
+def test_declare():
+    model = _load({"race.on": MODEL})
+    # Values are kept in base units and displayed in their own.
+    assert math.isclose(model.parameters["L"].min, 2011.68)
+    assert str(model.parameters["L"]).strip() == "10.0 furlong"
+    assert math.isclose(model.parameters["v"].min, 2011.68 / (28 * 86400))
+    assert math.isclose(model.parameters["v_si"].min, model.parameters["v"].min)
+    assert "furlong" in un.DECLARED_UNITS and "race.on" in un.DECLARED_UNITS["furlong"][2]["declared"]
+
+
+def test_design():
+    model = _load({"race.on": MODEL, "long.on": "L = 12 :furlong\n"}, design="long.on")
+    assert math.isclose(model.parameters["v"].min, 12 * 201.168 / (28 * 86400))
+
+
+def test_other_model():
+    files = {"race.on": MODEL, "stable.on": "use race as r\n\nStall length: l_s = 2 :furlong\n"}
+    try:
+        _load(files, "stable.on")
+    except UnitParseError as e:
+        assert "declared in race.on" in e.notes()[0]
+    else:
+        raise AssertionError("expected a UnitParseError")
+
+    files["stable.on"] = "use race as r\nunit furlong = 201.168 m\n\nStall length: l_s = 2 :furlong\n"
+    assert math.isclose(_load(files, "stable.on").parameters["l_s"].min, 2 * 201.168)
+
+
+def test_invalid():
+    for line, message in [
+        ("unit m = 2 ft", "already a unit"),
+        ("unit meter = 1 m", "already a unit"),
+        ("unit league = far", "number of other units"),
+        ("unit loudness = 3 dBm", "isn't linear"),
+        ("unit nothing = 0 m", "positive"),
+    ]:
+        try:
+            _load({"bad.on": f"{line}\n\nLength: l = 1 :m\n"}, "bad.on")
+        except SyntaxError as e:
+            assert message in e.message(), (line, e.message())
+        else:
+            raise AssertionError(f"expected a SyntaxError for {line}")
+
+
+def test_redeclared():
+    _load({"cubit.on": "unit cubit = 0.4572 m\n\nLength: l = 1 :cubit\n"}, "cubit.on")
+    try:
+        _load({"other.on": "unit cubit = 0.5 m\n\nLength: l = 1 :cubit\n"}, "other.on")
+    except SyntaxError as e:
+        assert "declared differently in cubit.on" in e.message()
+    else:
+        raise AssertionError("expected a SyntaxError")
+    # The only file that declares a unit can change it.
+    model = _load({"cubit.on": "unit cubit = 0.5 m\n\nLength: l = 1 :cubit\n"}, "cubit.on")
+    assert str(model.parameters["l"]).strip() == "1.0 cubit" and math.isclose(model.parameters["l"].min, 0.5)
+
+
+def test_label():
+    # A parameter whose label starts with "unit" isn't a declaration.
+    model = _load({"cost.on": "unit cost: c = 3\n"}, "cost.on")
+    assert model.parameters["c"].min == 3
+
+
+def test_tokens():
+    found = [(token.kind, token.text) for token in tokenize("unit stone = 14 lb\n")]
+    assert found == [("keyword", "unit"), ("definition", "stone"), ("punctuation", "="), ("number", "14"), ("units", "lb")]
+
+
+ALL_TESTS = [
+    test_declare,
+    test_design,
+    test_other_model,
+    test_invalid,
+    test_redeclared,
+    test_label,
+    test_tokens,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
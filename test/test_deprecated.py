@@ -0,0 +1,154 @@
+#!/usr/bin/env python3
+"""Tests for @deprecated: warnings for references to deprecated parameters and models, and
+the deprecated items still in use listed in the summary.
+
+Run directly:
+
+    python test/test_deprecated.py
+
+Or:
+
+    pytest test/test_deprecated.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import OneilError, console  # noqa: E402
+import helpers  # noqa: E402
+
+
+LIB = """\
+@deprecated("use structure_v2 instead")
+
+@deprecated("use m_total instead")
+Dry mass: m_dry = 300 :kg
+
+@deprecated
+Old margin: M_old = 2
+
+Total mass: m_total = 320 :kg
+"""
+
+CRAFT = """\
+use lib as l
+$ Launch mass: m_launch = 1.1*m_dry.l :kg
+$ Total: m = 1.0*m_total.l :kg
+Margin: M = 2*M_old.l
+test: M > 1
+"""
+
+FILES = {"lib.on": LIB, "craft.on": CRAFT}
+
+
+def _load(files=FILES):
+    output = io.StringIO()
+    with helpers.isolated(), contextlib.redirect_stdout(output):
+        model, _ = helpers.load(files, "craft.on")
+        exit_code = console.exit_code
+    return model, output.getvalue(), exit_code
+
+
+def test_parse():
+    assert oneil.parse_annotation("@deprecated") == ("deprecated", ())
+    assert oneil.parse_annotation("@deprecated(\"use x\")") == ("deprecated", ("use x",))
+    assert oneil.deprecation_message({"deprecated": ("use x",)}) == "use x"
+    assert oneil.deprecation_message({"deprecated": ()}) == ""
+    assert oneil.deprecation_message({}) is None
+    for line in ("@deprecated(\"a\", \"b\")", "@deprecated(2)"):
+        try:
+            oneil.parse_annotation(line)
+        except ValueError as e:
+            assert str(e) == "\"@deprecated\" takes at most one message string.", e
+        else:
+            assert False, f"Expected a ValueError for {line}"
+
+
+def test_uses():
+    model, _, _ = _load()
+    uses = [(use["item"], use["user"], use["message"]) for use in model.deprecations()]
+    assert uses == [
+        ("Model lib", "craft (as l)", "use structure_v2 instead"),
+        ("m_dry.l", "m_launch in craft", "use m_total instead"),
+        ("M_old.l", "M in craft", ""),
+    ], uses
+    # The deprecated items still work
+    assert model.parameters["m_launch"].min == 330
+
+
+def test_warnings():
+    _, output, exit_code = _load()
+    assert "DeprecationWarning" in output, output
+    assert "m_dry.l is deprecated: use m_total instead" in output, output
+    assert "M_old.l is deprecated." in output, output
+    assert "Used by m_launch in craft" in output, output
+    # Warnings don't change the exit code
+    assert exit_code == console.EXIT_SUCCESS
+
+
+def test_not_deprecated():
+    model, output, _ = _load({"lib.on": LIB.replace("@deprecated(\"use structure_v2 instead\")\n\n", ""), "craft.on": "use lib as l\n$ Total: m = 1.0*m_total.l :kg\n"})
+    assert model.deprecations() == []
+    assert "DeprecationWarning" not in output, output
+
+
+def test_summary():
+    model, _, _ = _load()
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        model.summarize()
+    assert "Deprecated items in use:" in output.getvalue(), output.getvalue()
+    assert "  m_dry.l (used by m_launch in craft): use m_total instead" in output.getvalue(), output.getvalue()
+
+
+def test_misplaced():
+    try:
+        helpers.load({"craft.on": "@deprecated\nuse lib as l\n", "lib.on": LIB}, "craft.on")
+    except OneilError as e:
+        assert e.kind() == "SyntaxError", e
+        assert e.message().startswith("Annotations must be followed by a parameter"), e.message()
+    else:
+        assert False, "Expected a SyntaxError"
+
+
+ALL_TESTS = [
+    test_parse,
+    test_uses,
+    test_warnings,
+    test_not_deprecated,
+    test_summary,
+    test_misplaced,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
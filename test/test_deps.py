@@ -0,0 +1,111 @@
+#!/usr/bin/env python3
+"""Tests for oneil.deps.dependencies, the files a model would load.
+
+Run directly:
+
+    python test/test_deps.py
+
+Or:
+
+    pytest test/test_deps.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import deps  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+FILES = {
+    "craft.on": 'use thermal as th\nimport helpers\ninclude "parts/common.on"\n\nMass: m = 3 :kg\n',
+    "thermal.on": "use radiator as rad\n\nTemperature: T = 300 :K\n",
+    "radiator.on": "Area: A = 2 :m^2\n",
+    "parts/common.on": "use radiator as rad2\n\nLength: L = 2 :m\n",
+    "helpers.py": "import numpy\nfrom util import f\n",
+    "util.py": "def f():\n    return 1\n",
+    "heavy.on": "m = 4 :kg\n",
+}
+
+
+def _workspace(files=FILES):
+    return helpers.workspace(files)
+
+
+def test_files_and_chains():
+    with working_directory(_workspace()):
+        files, missing = deps.dependencies("craft")
+    chains = {entry["file"]: (entry["kind"], entry["chain"]) for entry in files}
+    assert missing == []
+    assert chains["craft.on"] == ("model", ["craft.on"])
+    # Uses in fragments are reached through the fragment.
+    assert chains["radiator.on"] == ("model", ["craft.on", os.path.join("parts", "common.on"), "radiator.on"])
+    assert chains[os.path.join("parts", "common.on")] == ("fragment", ["craft.on", os.path.join("parts", "common.on")])
+    assert chains["util.py"] == ("python", ["craft.on", "helpers.py", "util.py"])
+    # Installed modules like numpy aren't inputs of the model.
+    assert len(files) == 6
+
+
+def test_designs():
+    with working_directory(_workspace()):
+        files, _ = deps.dependencies("craft.on", ["heavy"])
+    assert {"file": "heavy.on", "kind": "design", "chain": ["heavy.on"]} in files
+
+
+def test_missing():
+    with working_directory(_workspace(FILES | {"thermal.on": "use pump as p\n"})):
+        _, missing = deps.dependencies("craft")
+    assert missing == [{"file": "pump.on", "kind": "model", "chain": ["craft.on", "thermal.on", "pump.on"]}]
+
+
+def test_eval_directory():
+    directory = _workspace()
+    with working_directory(os.path.dirname(directory)):
+        files, missing = deps.dependencies("craft.on", directory=directory)
+    assert missing == []
+    assert os.path.join(os.path.basename(directory), "thermal.on") in [entry["file"] for entry in files]
+
+
+def test_depfile_text():
+    text = deps.depfile_text("out/report.pdf", ["craft.on", "my parts/common.on"])
+    assert text == "out/report.pdf: \\\n  craft.on \\\n  my\\ parts/common.on\n"
+
+
+ALL_TESTS = [
+    test_files_and_chains,
+    test_designs,
+    test_missing,
+    test_eval_directory,
+    test_depfile_text,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,118 @@
+#!/usr/bin/env python3
+"""Tests for oneil.deterministic, the evaluation profile for baselines that compare
+across platforms.
+
+Run directly:
+
+    python test/test_deterministic.py
+
+Or:
+
+    pytest test/test_deterministic.py
+"""
+
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, OneilError, deterministic  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """Angle: a = 0.3|0.7
+Sine: s = sin(a)
+Log(-inf, 0): l = log(a)
+Root: r = a**0.37
+Mode [high, low]: m = high
+Switch: w = {s*2 if m == 'high'
+    {l*z if m == 'low'
+Zed: z = 2
+"""
+
+
+def _build(text, enabled):
+    directory = helpers.workspace({"craft.on": text})
+    deterministic.enabled = enabled
+    try:
+        with working_directory(directory):
+            model = Model("craft.on")
+            model.build(quiet=True)
+        return model
+    finally:
+        deterministic.enabled = False
+
+
+def test_rounded():
+    assert deterministic.rounded(math.pi) == math.pi, "values aren't rounded outside deterministic mode"
+    deterministic.enabled = True
+    try:
+        assert deterministic.rounded(math.pi) == 3.14159265359
+        assert deterministic.rounded(-1.234567890123456e-30) == -1.23456789012e-30
+        assert deterministic.rounded(math.inf) == math.inf
+        assert deterministic.rounded(7) == 7
+    finally:
+        deterministic.enabled = False
+
+
+def test_libm_results():
+    model = _build(CRAFT, True)
+    assert model.parameters["s"].max == float(f"{math.sin(0.7):.11e}")
+    assert model.parameters["l"].min == float(f"{math.log(0.3):.11e}")
+    assert model.parameters["r"].min == float(f"{0.3 ** 0.37:.11e}")
+    assert type(model.parameters["s"].min) is float, "values are Python floats, not NumPy's"
+
+    default = _build(CRAFT, False)
+    assert math.isclose(default.parameters["s"].max, model.parameters["s"].max, rel_tol=1e-11)
+
+
+def test_domain_errors():
+    try:
+        _build("Zero: z = 0\nLog: l = log(z)\n", True)
+    except OneilError as e:
+        assert e.parameter.id == "l", e
+    else:
+        raise AssertionError("expected log(0) to fail, as it does with NumPy")
+
+
+def test_argument_order():
+    model = _build(CRAFT, True)
+    assert model.parameters["w"].args == ["s", "m", "l", "z"], model.parameters["w"].args
+
+
+ALL_TESTS = [
+    test_rounded,
+    test_libm_results,
+    test_domain_errors,
+    test_argument_order,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,191 @@
+#!/usr/bin/env python3
+"""Tests for oneil.diagnostics: allowing, warning about, and denying each category of
+warning from the manifest and the -A, -W, and -D flags.
+
+Run directly:
+
+    python test/test_diagnostics.py
+
+Or:
+
+    pytest test/test_diagnostics.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import console, diagnostics, lint  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+def _shadowed():
+    return oneil.ShadowedBuiltinWarning("model.on", 1, "pi has the name of a builtin constant.")
+
+
+def _note():
+    return oneil.LintWarning("model.on", 1, "spelling", "typo", severity="note")
+
+
+def _printed(warning, flags=(), manifest=None, deny_warnings=False):
+    diagnostics.reset()
+    console.exit_code = console.EXIT_SUCCESS
+    console.deny_warnings = deny_warnings
+    output = io.StringIO()
+    try:
+        errors = []
+        assert diagnostics.extract_flags(list(flags), errors) == [], errors
+        assert errors == [], errors
+        if manifest is not None:
+            diagnostics.load("project.txt", manifest.splitlines())
+        with contextlib.redirect_stdout(output):
+            console.print_warning(warning)
+        return output.getvalue(), console.exit_code
+    finally:
+        diagnostics.reset()
+        console.deny_warnings = False
+        console.exit_code = console.EXIT_SUCCESS
+
+
+def test_category():
+    assert diagnostics.category(_shadowed()) == "shadowed-builtin"
+    assert diagnostics.category(_note()) == "spelling"
+    assert diagnostics.category(oneil.ConfigError("config.toml", "bad")) is None
+    assert set(lint.LINTS) <= set(diagnostics.categories())
+
+
+def test_default():
+    output, exit_code = _printed(_shadowed())
+    assert "ShadowedBuiltinWarning" in output, output
+    assert exit_code == console.EXIT_SUCCESS
+
+
+def test_allow():
+    output, exit_code = _printed(_shadowed(), ["-A", "shadowed-builtin"])
+    assert output == "", output
+    assert exit_code == console.EXIT_SUCCESS
+
+
+def test_deny():
+    output, exit_code = _printed(_shadowed(), ["-Dshadowed-builtin"])
+    assert "The diagnostics policy denies shadowed-builtin." in output, output
+    assert exit_code == console.EXIT_WARNINGS
+
+
+def test_later_flag_wins():
+    _, exit_code = _printed(_shadowed(), ["-D", "shadowed-builtin", "-W", "shadowed-builtin"])
+    assert exit_code == console.EXIT_SUCCESS
+
+
+def test_group():
+    _, exit_code = _printed(_shadowed(), ["-D", "warnings"])
+    assert exit_code == console.EXIT_WARNINGS
+    # The group doesn't cover notes, and a category set itself takes precedence
+    _, exit_code = _printed(_note(), ["-D", "warnings"])
+    assert exit_code == console.EXIT_SUCCESS
+    output, exit_code = _printed(_shadowed(), ["-D", "warnings", "-A", "shadowed-builtin"])
+    assert output == "" and exit_code == console.EXIT_SUCCESS
+
+
+def test_deny_notes():
+    _, exit_code = _printed(_note(), ["-D", "spelling"])
+    assert exit_code == console.EXIT_WARNINGS
+
+
+def test_manifest():
+    _, exit_code = _printed(_shadowed(), manifest="model.on\ndiagnostics: warnings=deny spelling=allow\n")
+    assert exit_code == console.EXIT_WARNINGS
+    output, _ = _printed(_note(), manifest="diagnostics: warnings=deny spelling=allow\n")
+    assert output == "", output
+
+
+def test_flags_override_manifest():
+    _, exit_code = _printed(_shadowed(), ["-W", "warnings"], manifest="diagnostics: warnings=deny\n")
+    assert exit_code == console.EXIT_SUCCESS
+    # --deny-warnings doesn't override a policy that allows warnings
+    output, exit_code = _printed(_shadowed(), manifest="diagnostics: warnings=allow\n", deny_warnings=True)
+    assert output == "" and exit_code == console.EXIT_SUCCESS
+
+
+def test_invalid():
+    errors = []
+    diagnostics.extract_flags(["-D", "typo", "-W"], errors)
+    assert errors[0].startswith("Unknown diagnostic category \"typo\""), errors
+    assert errors[1] == "Missing category for -W.", errors
+    try:
+        diagnostics.load("project.txt", ["diagnostics: alias=forbid spelling"])
+    except oneil.ConfigError as e:
+        assert "The level of alias must be one of: allow, warn, deny." in e.notes(), e.notes()
+        assert "Diagnostics must be category=level pairs, not \"spelling\"." in e.notes(), e.notes()
+    else:
+        assert False, "Expected a ConfigError"
+    finally:
+        diagnostics.reset()
+
+
+def test_read_manifest():
+    directory = helpers.workspace({"project.txt": "model.on\ndiagnostics: alias=deny\n"})
+    with working_directory(directory):
+        assert oneil.read_manifest("project.txt") == ["model.on"]
+
+
+def test_lint_count():
+    directory = helpers.workspace({"model.on": "Mass: m = 2 :kg\nOld mass: m_old := m\nWeight: W = m_old*9.81 :N\n"})
+    diagnostics.reset()
+    errors = []
+    diagnostics.extract_flags(["-A", "alias"], errors)
+    try:
+        with working_directory(directory), contextlib.redirect_stdout(io.StringIO()) as output:
+            count = lint.run(["."])
+    finally:
+        diagnostics.reset()
+    assert count == 0, output.getvalue()
+
+
+ALL_TESTS = [
+    test_category,
+    test_default,
+    test_allow,
+    test_deny,
+    test_later_flag_wins,
+    test_group,
+    test_deny_notes,
+    test_manifest,
+    test_flags_override_manifest,
+    test_invalid,
+    test_read_manifest,
+    test_lint_count,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
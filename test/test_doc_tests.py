@@ -0,0 +1,103 @@
+#!/usr/bin/env python3
+"""Tests for tests in ```oneil-test blocks of notes.
+
+Run directly:
+
+    python test/test_doc_tests.py
+
+Or:
+
+    pytest test/test_doc_tests.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, SyntaxError  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """    A craft, where thrust beats the minimum:
+    ```oneil-test
+    test: F > F_min
+    ```
+
+Force: F = 10 :N
+Minimum force: F_min = 5 :N
+Mass: m = 2|4 :kg
+Minimum acceleration: a_min = 2 :m/s^2
+Acceleration: a = F/m :m/s^2
+    The acceleration, e.g.
+    ```oneil-test
+    test "fast enough": a > a_min
+
+    test: a < a_min
+    ```
+    More notes.
+
+test: m > m*0.5
+"""
+
+
+def _model(text):
+    directory = helpers.workspace({"craft.on": text})
+    with working_directory(directory):
+        return Model("craft.on")
+
+
+def test_tests_in_notes_run_with_the_model():
+    model = _model(CRAFT)
+    # The block stays in the notes.
+    assert any("```oneil-test" in note for note in model.parameters["a"].notes), model.parameters["a"].notes
+    model.build(quiet=True)
+    assert (model.fail_count, model.test_count) == (1, 4), (model.fail_count, model.test_count)
+    docs = [(test.doc, test.label(), test.passed) for test in model.tests if test.doc]
+    assert docs == [("craft", "F > F_min", True), ("a", '"fast enough"', True), ("a", "a < a_min", False)], docs
+    assert model.tests[-1].line_no == 15, model.tests[-1].line_no
+
+
+def test_unclosed_block():
+    text = CRAFT.replace("    ```\n    More notes.\n", "")
+    try:
+        _model(text)
+    except SyntaxError as e:
+        assert "Unclosed" in e.message(), e.message()
+    else:
+        raise AssertionError("expected a SyntaxError for the unclosed block")
+
+
+ALL_TESTS = [
+    test_tests_in_notes_run_with_the_model,
+    test_unclosed_block,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
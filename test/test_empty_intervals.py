@@ -0,0 +1,95 @@
+#!/usr/bin/env python3
+"""Tests for Model.empty_report, tracing empty intervals (bounds that aren't numbers)
+back to the operation that produced them.
+
+Run directly:
+
+    python test/test_empty_intervals.py
+
+Or:
+
+    pytest test/test_empty_intervals.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import empty_reason, is_empty  # noqa: E402
+import helpers  # noqa: E402
+
+
+BEAM = """Upper: u = 1|inf :m
+Lower: l = 1|inf :m
+Width: w = 3|4 :m
+"""
+
+CRAFT = """use beam as b
+
+Gap: g = u.b -- l.b :m
+Area: A = g*w.b :m^2
+Span: s = 2*w.b :m
+"""
+
+
+def _model():
+    return helpers.load({"beam.on": BEAM, "craft.on": CRAFT}, "craft.on")[0]
+
+
+def test_reasons():
+    assert empty_reason("(u) -- (l)") == "subtracting infinite bounds of the same sign (inf - inf)"
+    assert empty_reason("(a) * (b)") == "multiplying a zero bound by an infinite one (0 * inf)"
+    assert empty_reason("(1.0)/(b)").startswith("dividing")
+    assert empty_reason("sqrt(x)") == "a result that isn't a number (NaN)"
+
+
+def test_empty_provenance():
+    model = _model()
+    assert is_empty(model.parameters["g"]) and is_empty(model.parameters["A"])
+    assert not is_empty(model.parameters["s"])
+
+    assert len(model.empty_intervals) == 1, model.empty_intervals
+    entry = model.empty_intervals[0]
+    assert (entry["id"], entry["model"]) == ("g", "craft"), entry
+    assert entry["expression"] == "(u) -- (l)", entry
+    assert entry["reason"].startswith("subtracting"), entry
+    assert entry["affected"] == ["g", "A"], entry
+
+    warning = entry["warning"]
+    assert warning.kind() == "EmptyIntervalWarning" and warning.line_no == 3
+    assert warning.notes() == ["Also empty because of it: A"], warning.notes()
+
+
+ALL_TESTS = [
+    test_reasons,
+    test_empty_provenance,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,97 @@
+#!/usr/bin/env python3
+"""Tests for grouping errors from many models by their root cause.
+
+Run directly:
+
+    python test/test_error_groups.py
+
+Or:
+
+    pytest test/test_error_groups.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+import tempfile
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, evaluate_all, SyntaxError  # noqa: E402
+
+MODELS = {
+    "broken.on": "X: x = (1 +\n",
+    "a.on": "use broken as b\nY: y = 2\n",
+    "c.on": "use broken as b\nZ: z = 3\n",
+    "other.on": "W: w = 1 +* 2\n",
+    "fine.on": "V: v = 1\n",
+}
+
+
+def test_group_errors():
+    broken = SyntaxError("broken", 1, "x = (", "Invalid syntax.")
+    other = SyntaxError("other", 1, "w = 1 +* 2", "Invalid syntax.")
+    failures = [
+        ("a.on", broken),
+        ("other.on", other),
+        ("broken.on", SyntaxError("broken", 1, "x = (", "Invalid syntax.")),
+        ("c.on", broken),
+    ]
+    groups = console.group_errors(failures)
+    assert [files for _, files in groups] == [["broken.on", "a.on", "c.on"], ["other.on"]]
+
+
+def test_evaluate_all_groups_errors():
+    previous = console.exit_code
+    with tempfile.TemporaryDirectory() as directory:
+        for name, text in MODELS.items():
+            with open(os.path.join(directory, name), "w") as f:
+                f.write(text)
+        output = io.StringIO()
+        try:
+            with contextlib.redirect_stdout(output):
+                results = evaluate_all([directory], entries_only=False)
+        finally:
+            console.exit_code = previous
+
+    output = output.getvalue()
+    assert len([r for r in results if r[3]]) == 4
+    # One error for the broken submodel and one for the other broken model
+    assert output.count("SyntaxError[") == 2
+    assert "Also caused 2 other model(s) to fail" in output
+    assert output.index("broken (line 1)") < output.index("other (line 1)")
+
+
+ALL_TESTS = [
+    test_group_errors,
+    test_evaluate_all_groups_errors,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,132 @@
+#!/usr/bin/env python3
+"""Tests for where errors are in JSON diagnostics (--error-format json): their file,
+line, columns, and severity.
+
+Run directly:
+
+    python test/test_error_locations.py
+
+Or:
+
+    pytest test/test_error_locations.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import DeprecationWarning, DesignError, LintWarning, Model, OneilError, SyntaxError, check, console  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+def _directory(files):
+    directory = helpers.workspace(files)
+    return directory
+
+
+def _load_error(text):
+    with working_directory(_directory({"craft.on": text})):
+        try:
+            Model("craft.on").build(quiet=True)
+        except OneilError as e:
+            return console.error_json(e)
+    raise AssertionError("expected an error")
+
+
+def test_syntax_error():
+    error = _load_error("Mass: m = 3 :kg\nΔv budget: dv = 2 +* :m/s\n")
+    assert error["severity"] == "error"
+    assert (error["file"], error["line"], error["start_column"], error["end_column"]) == ("craft.on", 2, 1, 26), error
+
+
+def test_parameter_error():
+    error = _load_error("Mass: m = 3 :kg\nAcceleration: a = 2 :m/s^2\nΔv thrust: F_Δ = m*a*a :N\n")
+    assert error["kind"] == "UnitEvaluationError"
+    # The columns of the parameter's ID, in characters
+    assert (error["file"], error["line"], error["start_column"], error["end_column"]) == ("craft.on", 3, 12, 15), error
+
+
+def test_encodings():
+    directory = _directory({"craft.on": "Mass: m = 3 :kg\nΔv thrust: F_Δ = m*m :N\n"})
+    with working_directory(directory):
+        errors = check.unit_errors(Model("craft.on"))
+        assert console.location(errors[0])["start_column"] == 12
+        assert console.location(errors[0], "utf-8")["start_column"] == 13
+        assert console.location(errors[0], "utf-8")["end_column"] == 17
+
+
+def test_unknown_parts():
+    error = console.error_json(DesignError(["design.on"]))
+    assert (error["file"], error["line"], error["start_column"], error["end_column"]) == (None, None, None, None)
+    error = _load_error("Mass: m = 3 :kg\nForce: F = m*g :N\n")
+    assert error["kind"] == "IDError" and error["file"] == "craft.on" and error["line"] is None, error
+
+
+def test_warnings():
+    directory = _directory({"craft.on": "Mass: m = 300000000 :kg\n"})
+    previous = console.error_format
+    console.error_format = "json"
+    stderr = io.StringIO()
+    try:
+        with working_directory(directory), contextlib.redirect_stderr(stderr):
+            console.print_warning(DeprecationWarning("craft.on", 1, "m is deprecated."))
+            console.print_warning(LintWarning("craft.on", 1, "spelling", "Typo.", severity="note"))
+    finally:
+        console.error_format = previous
+    warning, note = [json.loads(line) for line in stderr.getvalue().splitlines()]
+    assert warning["severity"] == "warning" and (warning["file"], warning["line"], warning["start_column"], warning["end_column"]) == ("craft.on", 1, 1, 24), warning
+    assert note["severity"] == "note"
+
+
+def test_human_format_unchanged():
+    output = io.StringIO()
+    previous = console.exit_code
+    try:
+        with contextlib.redirect_stdout(output):
+            console.print_error(SyntaxError("craft.on", 2, "x = (", "Invalid syntax."))
+    finally:
+        console.exit_code = previous
+    assert "Invalid syntax." in output.getvalue() and "start_column" not in output.getvalue()
+
+
+ALL_TESTS = [
+    test_syntax_error,
+    test_parameter_error,
+    test_encodings,
+    test_unknown_parts,
+    test_warnings,
+    test_human_format_unchanged,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
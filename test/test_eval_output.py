@@ -0,0 +1,97 @@
+#!/usr/bin/env python3
+"""Tests for `oneil eval --output json`, the results of many models for scripts and CI.
+
+Run directly:
+
+    python test/test_eval_output.py
+
+Or:
+
+    pytest test/test_eval_output.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+import tempfile
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, evaluate_all  # noqa: E402
+
+MODELS = {
+    "craft.on": "Mass(0, 10): m = 2|3 :kg\n$ Payload(0, 100): P = 4*m :kg\n\ntest \"light\": P < 8*m\n",
+    "broken.on": "X: x = (1 +\n",
+}
+
+
+def _evaluate():
+    previous = console.exit_code
+    with tempfile.TemporaryDirectory() as directory:
+        for name, text in MODELS.items():
+            with open(os.path.join(directory, name), "w") as f:
+                f.write(text)
+        output = io.StringIO()
+        try:
+            with contextlib.redirect_stdout(output):
+                evaluate_all([directory], entries_only=False, output="json")
+            exit_code = console.exit_code
+        finally:
+            console.exit_code = previous
+    return json.loads(output.getvalue()), exit_code
+
+
+def test_results():
+    document, _ = _evaluate()
+    craft = next(model for model in document["models"] if model["file"].endswith("craft.on"))
+    assert craft["passed"] is True
+    parameters = craft["results"]["parameters"]
+    assert (parameters["m"]["min"], parameters["m"]["max"], parameters["m"]["units"]) == (2, 3, "kg")
+    assert parameters["m"]["limits"] == {"min": 0, "max": 10}
+    assert parameters["m"]["within_limits"] is True
+    assert craft["results"]["tests"]["results"][0]["name"] == "light"
+    assert [entry["id"] for entry in craft["performance"]] == ["P"]
+
+
+def test_errors():
+    document, exit_code = _evaluate()
+    broken = next(model for model in document["models"] if model["file"].endswith("broken.on"))
+    assert broken["passed"] is False and "results" not in broken
+    assert broken["error"]["kind"] == "SyntaxError"
+    assert exit_code == console.EXIT_CODES["SyntaxError"]
+
+
+ALL_TESTS = [
+    test_results,
+    test_errors,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,119 @@
+#!/usr/bin/env python3
+"""Tests for `oneil eval --set <model>.<ID>=<value>`, overrides when evaluating many models.
+
+Run directly:
+
+    python test/test_eval_overrides.py
+
+Or:
+
+    pytest test/test_eval_overrides.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import OverrideError, console, evaluate_all, model_overrides  # noqa: E402
+import helpers  # noqa: E402
+
+MODELS = {
+    "craft.on": "Mass(0, 10): m = 2 :kg\nPayload: P = 4*m :kg\n",
+    "mission.on": "use craft as c\nTotal: T = 2*m.c :kg\n",
+}
+
+# Two entry models that share one submodel, one sorted before it and one after
+SHARED = {
+    "apollo.on": "use engine as e\nThrust: T = 2*F.e :N\n",
+    "engine.on": "Thrust: F = 20 :N\n",
+    "zephyr.on": "use engine as e\nThrust: T = 3*F.e :N\n",
+}
+
+
+def _evaluate(overrides, stream=False, models=MODELS):
+    previous = console.exit_code
+    directory = helpers.workspace(models)
+    try:
+        output = io.StringIO()
+        with contextlib.redirect_stdout(output):
+            evaluate_all([directory], stream=stream, output="json", overrides=overrides)
+    finally:
+        console.exit_code = previous
+    return {os.path.basename(model["file"]): model for model in json.loads(output.getvalue())["models"]}
+
+
+def test_model_overrides():
+    files = ["models/craft.on", "mission.on"]
+    assert model_overrides(["craft.m=5:kg", "craft.A.th=2:m^2"], files) == {"models/craft.on": ["m=5:kg", "A.th=2:m^2"]}
+    for override in ["m=5:kg", "rover.m=5:kg"]:
+        try:
+            model_overrides([override], files)
+        except OverrideError as e:
+            assert e.override == override
+            continue
+        raise AssertionError(f"Expected an OverrideError for {override}")
+
+
+def test_override_applied():
+    results = _evaluate(["craft.m=5:kg"])
+    assert "craft.on" in results, "overridden models are evaluated even if they're used"
+    assert results["craft.on"]["results"]["parameters"]["P"]["min"] == 20
+    assert results["mission.on"]["results"]["parameters"]["T"]["min"] == 10, "models that use the overridden model see the override"
+    assert "craft.on" not in _evaluate([]), "craft is only used by mission"
+
+
+def test_override_validated():
+    for override, kind in [("craft.m=20:kg", "LimitError"), ("craft.m=5:m", "UnitEvaluationError"), ("craft.x=1", "OverrideError")]:
+        error = _evaluate([override])["craft.on"]["error"]
+        assert error["kind"] == kind, (override, error)
+    results = _evaluate(["craft.m=3:kg"], stream=True)
+    assert results["craft.on"]["results"]["parameters"]["P"]["min"] == 12
+    assert results["mission.on"]["results"]["parameters"]["T"]["min"] == 4, "streamed models don't share the override"
+
+
+def test_shared_submodel():
+    # Every override is applied before any model is evaluated, so both models see it
+    results = _evaluate(["engine.F=100:N"], models=SHARED)
+    thrusts = {name: results[name]["results"]["parameters"]["T"]["min"] for name in ("apollo.on", "zephyr.on")}
+    assert thrusts == {"apollo.on": 200, "zephyr.on": 300}, thrusts
+    assert results["engine.on"]["results"]["parameters"]["F"]["min"] == 100
+
+
+ALL_TESTS = [
+    test_model_overrides,
+    test_override_applied,
+    test_override_validated,
+    test_shared_submodel,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,115 @@
+#!/usr/bin/env python3
+"""Tests for the CLI's exit codes by failure type, and the --quiet and --error-format
+flags.
+
+Run directly:
+
+    python test/test_exit_codes.py
+
+Or:
+
+    pytest test/test_exit_codes.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console  # noqa: E402
+import helpers  # noqa: E402
+
+
+FILES = {
+    "good.on": "Mass: m = 2 :kg\nGravity: g = 9.81 :m/s^2\n$ Weight: W = g*m :N\ntest: W > 0 :N\n",
+    "syntax.on": "Mass: m = 2 :kg\nWeight W = 9.81*m :N\n",
+    "missing.on": "use nowhere as n\nMass: m = 2 :kg\n",
+    "units.on": "Mass: m = 2 :kg\nLength: l = 3 :m\nSum: s = m + l :kg\n",
+    "failing.on": "Mass: m = 2 :kg\nMinimum mass: m_min = 5 :kg\ntest: m > m_min\n",
+    "limit.on": "Mass (0, 1): m = 2 :kg\n",
+}
+
+
+def _run(*args):
+    errors = io.StringIO()
+    with contextlib.redirect_stderr(errors):
+        output, code = helpers.cli(list(args), helpers.workspace(FILES))
+    return output, errors.getvalue(), code
+
+
+def test_by_failure():
+    for name, expected in (
+        ("good.on", console.EXIT_SUCCESS),
+        ("syntax.on", console.EXIT_PARSE_ERROR),
+        ("missing.on", console.EXIT_RESOLUTION_ERROR),
+        ("units.on", console.EXIT_UNIT_ERROR),
+        ("failing.on", console.EXIT_TEST_FAILURE),
+        ("limit.on", console.EXIT_LIMIT_VIOLATION),
+    ):
+        output, _, code = _run("--quiet", name, "test", "quit")
+        assert code == expected, (name, code, output)
+
+
+def test_first_failure():
+    # The exit code is that of the first failure
+    with helpers.isolated():
+        console.record_exit(console.EXIT_UNIT_ERROR)
+        console.record_exit(console.EXIT_TEST_FAILURE)
+        assert console.exit_code == console.EXIT_UNIT_ERROR
+
+
+def test_quiet():
+    output, _, _ = _run("good.on", "W", "quit")
+    assert "Oneil " in output and "Loading model good.on..." in output, output
+    output, _, code = _run("--quiet", "good.on", "W", "quit")
+    assert code == console.EXIT_SUCCESS
+    assert "Oneil " not in output and "Loading model" not in output, output
+    assert "W: 19.62 N" in output, output
+
+
+def test_error_format_json():
+    output, errors, code = _run("--quiet", "--error-format", "json", "units.on", "quit")
+    assert code == console.EXIT_UNIT_ERROR
+    assert "UnitEvaluationError" not in output, output
+    error = json.loads(errors.strip().splitlines()[0])
+    assert error["kind"] == "UnitEvaluationError", error
+    assert error["exit_code"] == console.EXIT_UNIT_ERROR and error["severity"] == "error", error
+
+
+ALL_TESTS = [
+    test_by_failure,
+    test_first_failure,
+    test_quiet,
+    test_error_format_json,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,92 @@
+#!/usr/bin/env python3
+"""Tests for oneil.explanations, the long-form explanations of error codes (`oneil
+explain-error`).
+
+Run directly:
+
+    python test/test_explanations.py
+
+Or:
+
+    pytest test/test_explanations.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import ModelNotFoundError, console, explanations, messages  # noqa: E402
+
+
+def test_every_code_explained():
+    codes = set(messages.CODES.values())
+    assert set(explanations.explanations()) == codes, set(explanations.explanations()) ^ codes
+    for code in codes:
+        entry = explanations.explain(code)
+        for field in explanations.FIELDS:
+            assert entry[field], (code, field)
+        assert messages.CODES[entry["kind"]] == code
+
+
+def test_lookup():
+    assert explanations.explain("e0104")["code"] == "E0104"
+    assert explanations.explain("IDError")["code"] == "E0104"
+    assert explanations.explain("E9999") is None
+    assert explanations.explain("nonsense") is None
+    assert explanations.url("W0002") == explanations.DOCS_URL + "#w0002"
+
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        assert explanations.print_explanation("E0104")
+        assert not explanations.print_explanation("E9999")
+    assert "ID error" in output.getvalue() and "E9999" in output.getvalue()
+
+
+def test_docs_in_sync():
+    path = os.path.join(os.path.dirname(__file__), "..", "docs", "errors.md")
+    with open(path) as f:
+        assert f.read() == explanations.markdown() + "\n", "regenerate docs/errors.md with `oneil explain-error --markdown`"
+
+
+def test_json_links():
+    error = ModelNotFoundError("craft.on")
+    assert console.error_json(error)["code_description"] == {"href": explanations.url("E0103")}
+
+
+ALL_TESTS = [
+    test_every_code_explained,
+    test_lookup,
+    test_docs_in_sync,
+    test_json_links,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,125 @@
+#!/usr/bin/env python3
+"""Tests for exporting results to JSON: the results of a model and its submodels, and the
+optional source locations of their parameters.
+
+Run directly:
+
+    python test/test_export_json.py
+
+Or:
+
+    pytest test/test_export_json.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """\
+include "lib/constants.oni"
+Thrust: F = 20 :kN
+"""
+
+CRAFT = """\
+use engine as e
+
+Mass (0, 1000): m = 400|500 :kg
+$ Acceleration: a = F.e/m :m/s^2
+test "fast enough": a > g_E.e
+"""
+
+FILES = {"engine.on": ENGINE, "craft.on": CRAFT, "lib/constants.oni": "Gravity: g_E = 9.81 :m/s^2\n"}
+
+
+def test_results():
+    model, _ = helpers.load(FILES, "craft.on")
+    results = model.results()
+    assert (results["model"], results["design"]) == ("craft", "default")
+    m = results["parameters"]["m"]
+    assert (m["name"], m["min"], m["max"], m["units"]) == ("Mass", 400, 500, "kg"), m
+    assert m["limits"] == {"min": 0, "max": 1000}, m
+    assert (results["parameters"]["a"]["min"], results["parameters"]["a"]["max"]) == (40, 50)
+    assert results["tests"] == {"passed": 1, "total": 1, "results": [{"name": "fast enough", "expression": "a > g_E.e", "line": 5, "passed": True}]}, results["tests"]
+    assert results["submodels"]["e"]["parameters"]["F"]["units"] == "kN"
+    assert "source" not in m and "source" not in results
+
+
+def test_source():
+    model, directory = helpers.load(FILES, "craft.on")
+    with working_directory(directory):
+        results = model.results(source=True)
+    assert results["source"] == {"file": "craft.on"}
+    assert results["parameters"]["m"]["source"] == {"file": "craft.on", "line": 3, "start_column": 17, "end_column": 18}, results["parameters"]["m"]["source"]
+    assert results["parameters"]["a"]["source"] == {"file": "craft.on", "line": 4, "start_column": 17, "end_column": 18}, results["parameters"]["a"]["source"]
+    engine = results["submodels"]["e"]
+    assert engine["parameters"]["F"]["source"]["file"] == "engine.on"
+    # Parameters from a fragment are in the fragment
+    assert engine["parameters"]["g_E"]["source"] == {"file": os.path.join("lib", "constants.oni"), "line": 1, "start_column": 10, "end_column": 13}, engine["parameters"]["g_E"]["source"]
+
+
+def test_export_file():
+    model, directory = helpers.load(FILES, "craft.on")
+    with working_directory(directory), contextlib.redirect_stdout(io.StringIO()) as output:
+        model.export_json()
+        model.export_json("with-source.json", source=True)
+    assert "Exported results to craft.json." in output.getvalue(), output.getvalue()
+    with open(os.path.join(directory, "craft.json")) as f:
+        exported = json.load(f)
+    assert exported["parameters"]["a"]["min"] == 40 and "source" not in exported["parameters"]["a"]
+    assert "reproducibility" in exported
+    with open(os.path.join(directory, "with-source.json")) as f:
+        assert json.load(f)["parameters"]["a"]["source"]["line"] == 4
+
+
+def test_cli():
+    directory = helpers.workspace(FILES)
+    output, code = helpers.cli(["--quiet", "craft.on", "export json - source", "quit"], directory)
+    assert code == console.EXIT_SUCCESS, output
+    exported = json.loads(output[output.index("{"):output.rindex("}") + 1])
+    assert exported["model"] == "craft"
+    assert exported["parameters"]["m"]["source"]["line"] == 3
+
+
+ALL_TESTS = [
+    test_results,
+    test_source,
+    test_export_file,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,115 @@
+#!/usr/bin/env python3
+"""Tests for the nesting and length limits on expressions, including a small fuzz test
+that deeply nested or very long generated expressions fail with a Oneil error rather
+than crashing the parser.
+
+Run directly:
+
+    python test/test_expression_limits.py
+
+Or:
+
+    pytest test/test_expression_limits.py
+"""
+
+import os
+import random
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import parse_file, OneilError, MAX_EXPRESSION_NESTING, MAX_EXPRESSION_OPERATORS  # noqa: E402
+from oneil import SyntaxError as OneilSyntaxError  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+def _parse(text):
+    with working_directory(helpers.workspace({"model.on": text})):
+        return parse_file("model.on")
+
+
+def _syntax_error(text):
+    try:
+        _parse(text)
+    except OneilSyntaxError as e:
+        return e
+    return None
+
+
+def test_nesting():
+    n = MAX_EXPRESSION_NESTING
+    assert _parse("X: x = " + "(" * n + "1" + ")" * n + "\n")
+    error = _syntax_error("X: x = " + "(" * (n + 1) + "1" + ")" * (n + 1) + "\n")
+    assert error and error.line_no == 1 and "nested" in error.message()
+
+
+def test_operators():
+    n = MAX_EXPRESSION_OPERATORS
+    assert _parse("X: x = 1" + "+1" * n + "\n")
+    error = _syntax_error("X: x = 1" + "+1" * (n + 1) + "\n")
+    assert error and "operators" in error.message()
+
+
+def test_tests_and_piecewise():
+    deep = "(" * 300 + "x" + ")" * 300
+    assert _syntax_error(f"X: x = 1\n\ntest: {deep} > 0\n")
+    assert _syntax_error(f"X: x = {{1 if x > 0\n    {{{deep} if x <= 0\n")
+
+
+def test_notes_are_not_limited():
+    assert _parse("X: x = 1\n    See " + "{" * 300 + "\\LaTeX" + "}" * 300 + "\n")
+
+
+def test_fuzz():
+    rng = random.Random(3221)
+    atoms = ["1", "2.5", "x", "pi", "(x)", "min(x, 2)"]
+    operators = ["+", "-", "*", "/", "**", "<", "=="]
+    for _ in range(60):
+        expression = rng.choice(atoms)
+        for _ in range(rng.randrange(0, 3)):
+            terms = [rng.choice(atoms) for _ in range(rng.randrange(1, 1500))]
+            expression = expression + rng.choice(operators) + rng.choice(operators).join(terms)
+        depth = rng.randrange(0, 400)
+        expression = "(" * depth + expression + ")" * rng.choice([depth, depth - 1, depth + 1])
+        try:
+            _parse(f"X: x = 2\nY: y = {expression}\n")
+        except OneilError:
+            pass
+
+
+ALL_TESTS = [
+    test_nesting,
+    test_operators,
+    test_tests_and_piecewise,
+    test_notes_are_not_limited,
+    test_fuzz,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,192 @@
+#!/usr/bin/env python3
+"""Tests for oneil.externs: parameters bound to external inputs from --input-json and the
+environment.
+
+Run directly:
+
+    python test/test_externs.py
+
+Or:
+
+    pytest test/test_externs.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, OneilError, console, externs, stamp  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """\
+Launch mass: m_launch = extern("launch_mass") :kg
+Dry mass (0, 5000): m_dry = extern("dry_mass") :kg
+Mode [low, high]: mode = extern("mode")
+Thrust: F = 20 :kN
+$ Acceleration: a = F/m_launch :m/s^2
+"""
+
+
+@contextlib.contextmanager
+def _inputs(values=None, environment=None):
+    previous = {name: os.environ.get(name) for name in (environment or {})}
+    with helpers.isolated():
+        externs.values.clear()
+        externs.values.update(values or {})
+        os.environ.update(environment or {})
+        try:
+            yield
+        finally:
+            for name, value in previous.items():
+                if value is None:
+                    del os.environ[name]
+                else:
+                    os.environ[name] = value
+
+
+def _load(values=None, environment=None, partial=False):
+    directory = helpers.workspace({"craft.on": CRAFT})
+    with _inputs(values, environment), working_directory(directory), contextlib.redirect_stdout(io.StringIO()):
+        model = Model("craft.on", partial=partial)
+        model.build(quiet=True)
+    return model
+
+
+def test_name():
+    assert externs.name("extern(\"launch_mass\")") == "launch_mass"
+    assert externs.name(" extern( 'm' ) ") == "m"
+    assert externs.name("extern(m)") is None
+    assert externs.name("2*extern(\"m\")") is None
+
+
+def test_bound():
+    model = _load({"launch_mass": [2000, 2500], "dry_mass": 1200, "mode": "high"})
+    assert (model.parameters["m_launch"].min, model.parameters["m_launch"].max) == (2000, 2500)
+    assert model.parameters["m_dry"].min == 1200
+    assert model.parameters["mode"].min == "high"
+    assert (model.parameters["a"].min, model.parameters["a"].max) == (8, 10)
+
+
+def test_environment():
+    model = _load({"launch_mass": 2000, "mode": "low"}, {"ONEIL_DRY_MASS": "1.2 Mg", "ONEIL_LAUNCH_MASS": "9"})
+    assert model.parameters["m_dry"].min == 1200
+    # The JSON file takes precedence
+    assert model.parameters["m_launch"].min == 2000
+
+
+def test_missing():
+    try:
+        _load({"dry_mass": 1200})
+    except OneilError as e:
+        assert e.kind() == "ExternError", e
+        assert e.message() == "No value was given for m_launch (extern \"launch_mass\"), mode (extern \"mode\").", e.message()
+        assert "ONEIL_LAUNCH_MASS, ONEIL_MODE" in e.notes()[0], e.notes()
+        assert console.exit_code_for(e) == console.EXIT_RESOLUTION_ERROR
+    else:
+        assert False, "Expected an ExternError"
+
+
+def test_invalid():
+    for value, expected in (("1200 s", "Must be a value in units that match kg, not 1200 s."), (True, "Must be a number, a [min, max] pair, or a string, not true.")):
+        try:
+            _load({"launch_mass": 2000, "dry_mass": value, "mode": "low"})
+        except OneilError as e:
+            assert e.kind() == "ExternError", e
+            assert e.message() == f"Invalid value for m_dry (extern \"dry_mass\"): {expected}", e.message()
+        else:
+            assert False, f"Expected an ExternError for {value}"
+
+
+def test_limits():
+    try:
+        _load({"launch_mass": 2000, "dry_mass": 9000, "mode": "low"})
+    except OneilError as e:
+        assert e.kind() == "LimitError", e
+        assert "In extern \"dry_mass\"" in e.notes(), e.notes()
+    else:
+        assert False, "Expected a LimitError"
+
+
+def test_partial():
+    model = _load({"dry_mass": 1200}, partial=True)
+    assert set(model.failed()) == {"m_launch", "mode", "a"}, model.failed()
+    assert [error.kind() for error in model.errors()] == ["ExternError"]
+    assert model.parameters["m_dry"].min == 1200
+
+
+def test_load_json():
+    directory = helpers.workspace({"inputs.json": "{\"launch_mass\": 2000}", "list.json": "[1, 2]", "broken.json": "{"})
+    with _inputs(), working_directory(directory):
+        externs.load_json("inputs.json")
+        assert externs.lookup("launch_mass") == 2000
+        for name, message in (("list.json", "must be a JSON object"), ("broken.json", "aren't valid JSON"), ("missing.json", "Can't read")):
+            try:
+                externs.load_json(name)
+            except ValueError as e:
+                assert message in str(e), e
+            else:
+                assert False, f"Expected a ValueError for {name}"
+
+
+def test_stamp():
+    model = _load({"launch_mass": [2000, 2500], "dry_mass": 1200, "mode": "high"})
+    with _inputs({"launch_mass": [2000, 2500], "dry_mass": 1200, "mode": "high"}):
+        stamped = stamp.stamp(model, os.path.dirname(model.model_filename))
+    assert stamped["externs"] == {"launch_mass": [2000, 2500], "dry_mass": 1200, "mode": "high"}, stamped["externs"]
+
+
+def test_cli():
+    directory = helpers.workspace({"craft.on": CRAFT, "inputs.json": json.dumps({"launch_mass": 2000, "dry_mass": 1200, "mode": "low"})})
+    output, exit_code = helpers.cli(["--quiet", "--input-json", "inputs.json", "craft.on", "a", "quit"], directory)
+    assert exit_code == console.EXIT_SUCCESS, output
+    assert "a: 10.0 m/s^2" in output, output
+    # The inputs, --quiet, and the exit code don't outlast the CLI
+    assert externs.values == {} and not console.quiet and console.exit_code == console.EXIT_SUCCESS
+
+
+ALL_TESTS = [
+    test_name,
+    test_bound,
+    test_environment,
+    test_missing,
+    test_invalid,
+    test_limits,
+    test_partial,
+    test_load_json,
+    test_stamp,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
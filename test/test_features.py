@@ -0,0 +1,145 @@
+#!/usr/bin/env python3
+"""Tests for oneil.features, experimental language features enabled by `#feature(...)`
+pragmas.
+
+Run directly:
+
+    python test/test_features.py
+
+Or:
+
+    pytest test/test_features.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import SyntaxError, console, features, tokens  # noqa: E402
+import helpers  # noqa: E402
+
+
+AREA = """#feature(unicode_operators)
+# Comments can come first.
+
+Width: w = 2 :m
+Height: h = 3 :m
+Area: A = w × h − w·w/2 :m^2
+Ratio: r = {w ÷ h if w < h
+           {h ÷ w if w >= h
+
+test "big enough": A ÷ w > h − w
+"""
+
+
+def _load(files, name="area.on"):
+    return helpers.load(files, name)[0]
+
+
+def _syntax_error(files, name="area.on"):
+    try:
+        _load(files, name)
+    except SyntaxError as e:
+        return e
+    raise AssertionError("Expected a SyntaxError")
+
+
+def test_pragma():
+    assert features.pragma("#feature(unicode_operators)") == ["unicode_operators"]
+    assert features.pragma("#feature( unicode_operators , unicode_operators )") == ["unicode_operators", "unicode_operators"]
+    for line in ["#feature(arrays)", "#feature()", "#features(unicode_operators)"]:
+        try:
+            features.pragma(line)
+        except ValueError:
+            continue
+        raise AssertionError(f"Expected a ValueError for {line}")
+    assert features.enabled(AREA) == {"unicode_operators"}
+
+
+def test_unicode_operators():
+    model = _load({"area.on": AREA})
+    assert model.features == {"unicode_operators"}
+    assert model.parameters["A"].min == 4, model.parameters["A"].min
+    assert abs(model.parameters["r"].min - 2 / 3) < 1e-9
+    assert model.test() == (0, 1)
+
+    error = _syntax_error({"area.on": AREA.replace("#feature(unicode_operators)\n", "")})
+    assert error.line_no == 5 and "#feature(unicode_operators)" in error.message(), error
+
+
+def test_labels_and_notes():
+    model = _load({"area.on": "Width × height: A = 6 :m^2\n    The × in notes is just text.\n"})
+    assert model.features == set() and model.parameters["A"].name == "Width × height"
+
+
+def test_placement():
+    error = _syntax_error({"area.on": "Width: w = 2 :m\n#feature(unicode_operators)\n"})
+    assert error.line_no == 2 and "before" in error.message(), error
+    error = _syntax_error({"area.on": "#feature(arrays)\nWidth: w = 2 :m\n"})
+    assert error.line_no == 1 and "arrays" in error.message(), error
+    error = _syntax_error({"area.on": "include \"common.oni\"\nWidth: w = 2 :m\n", "common.oni": "#feature(unicode_operators)\n"})
+    assert "model's own file" in error.message(), error
+
+
+def test_submodel_warning():
+    files = {"area.on": AREA, "craft.on": "use area as a\nDouble: D = 2*A.a :m^2\n"}
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        model = _load(files, "craft.on")
+    assert model.parameters["D"].min == 8 and model.features == set()
+    assert "FeatureWarning" in output.getvalue() and "unicode_operators" in output.getvalue(), output.getvalue()
+    assert console.exit_code == 0
+
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        _load(dict(files, **{"craft.on": "#feature(unicode_operators)\nuse area as a\nDouble: D = 2*A.a :m^2\n"}), "craft.on")
+    assert "FeatureWarning" not in output.getvalue(), output.getvalue()
+
+
+def test_tokens():
+    found = list(tokens.tokenize(AREA))
+    assert found[0].kind == "attribute"
+    assert [token.kind for token in found if token.text in features.OPERATORS] == ["operator"] * 7
+    found = list(tokens.tokenize(AREA.replace("#feature(unicode_operators)\n", "")))
+    assert all(token.kind == "error" for token in found if token.text in features.OPERATORS)
+
+
+ALL_TESTS = [
+    test_pragma,
+    test_unicode_operators,
+    test_labels_and_notes,
+    test_placement,
+    test_submodel_warning,
+    test_tokens,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
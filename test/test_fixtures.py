@@ -0,0 +1,109 @@
+#!/usr/bin/env python3
+"""Tests for oneil.fixtures, regression suites in the tests/ directory beside a manifest.
+
+Run directly:
+
+    python test/test_fixtures.py
+
+Or:
+
+    pytest test/test_fixtures.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import fixtures, parse_annotation  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """Force: F = 10 :N
+Mass: m = 4 :kg
+Acceleration: a = F/m :m/s^2
+"""
+
+HEAVY = """use craft as c
+
+m.c = 12 :kg
+
+Expected acceleration: a_gold = 0.8333 :m/s^2
+
+@tolerance(0.1%)
+test "golden acceleration": a.c == a_gold
+test "exact": a.c == a_gold
+"""
+
+
+def _library():
+    return helpers.workspace({"craft.on": CRAFT, "models.txt": "craft.on\n", os.path.join("tests", "heavy.on"): HEAVY})
+
+
+def test_tolerance_annotation():
+    assert parse_annotation("@tolerance(0.1%)\n") == ("tolerance", (0.001,))
+    assert parse_annotation("@tolerance(0.02)\n") == ("tolerance", (0.02,))
+    for line in ("@tolerance\n", "@tolerance(-1%)\n", "@tolerance(x)\n"):
+        try:
+            parse_annotation(line)
+        except ValueError as e:
+            assert "@tolerance" in str(e), e
+        else:
+            raise AssertionError(f"expected a ValueError for {line!r}")
+
+
+def test_fixtures_set_inputs_and_check_golden_values():
+    directory = _library()
+    manifest = os.path.join(directory, "models.txt")
+    assert fixtures.discover(manifest) == [os.path.join(directory, "tests", "heavy.on")]
+
+    model = fixtures.load(fixtures.discover(manifest)[0], directory)
+    passed = {test.name: test.passed for test in model.tests}
+    assert passed == {"golden acceleration": True, "exact": False}, passed
+    assert oneil.tolerance is None, "the tolerance only applies while its test runs"
+
+    results = fixtures.run(manifests=[manifest])
+    assert [(os.path.basename(file), p, t) for file, p, t in results] == [("craft.on", 0, 0), ("heavy.on", 1, 2)], results
+
+
+def test_eval_skips_fixtures():
+    directory = _library()
+    assert fixtures.models([directory]) == [os.path.join(directory, "craft.on")]
+    fixture = os.path.join(directory, "tests", "heavy.on")
+    assert fixtures.models([fixture]) == [fixture], "named fixtures aren't skipped"
+
+
+ALL_TESTS = [
+    test_tolerance_annotation,
+    test_fixtures_set_inputs_and_check_golden_values,
+    test_eval_skips_fixtures,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
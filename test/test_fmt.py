@@ -0,0 +1,140 @@
+#!/usr/bin/env python3
+"""Tests for oneil.fmt, the formatter for model files (`oneil fmt`).
+
+Run directly:
+
+    python test/test_fmt.py
+
+Or:
+
+    pytest test/test_fmt.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, console, fmt  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+MODEL = """    A craft.
+Mass (0,100): m=3   :kg
+Length:l = 5*  m/m + -3*m/m
+Mode [low,high]: D = low
+
+
+Rate: r = {2*m -- m if D=='low' :kg
+ {m*(1+ -2) if D=='high'
+    A note.
+
+    More.
+section Outputs
+test "light" : m<  5*m
+Alias: M:=m
+"""
+
+FORMATTED = """    A craft.
+Mass (0, 100): m    = 3 :kg
+Length: l           = 5*m/m + -3*m/m
+Mode [low, high]: D = low
+
+Rate: r = {2*m -- m if D == 'low' :kg
+          {m*(1 + -2) if D == 'high'
+    A note.
+
+    More.
+
+section Outputs
+test "light": m < 5*m
+Alias: M := m
+"""
+
+
+def test_format_text():
+    assert fmt.format_text(MODEL) == FORMATTED, fmt.format_text(MODEL)
+    assert fmt.format_text(FORMATTED) == FORMATTED, "formatting is idempotent"
+
+
+def test_spacing():
+    assert fmt.format_text("Speed: v = max(l,l)/ 3 ? l * 1 :m\n") == "Speed: v = max(l, l)/3 ? l*1 :m\n"
+    assert fmt.format_text("Thrust: F = F_max.e*-1 - - 2 :N\n") == "Thrust: F = F_max.e*-1 - -2 :N\n"
+    assert fmt.format_text("Range: r = -2|- 1\n") == "Range: r = -2|-1\n"
+    assert fmt.format_text("test: m>1and not(m<2)\n") == "test: m > 1 and not (m < 2)\n"
+
+
+def test_unparsed_lines_kept():
+    text = "Mass: m = 3 :kg\nnot a declaration  \ntest: m >\n"
+    assert fmt.format_text(text) == "Mass: m = 3 :kg\nnot a declaration\ntest: m >\n"
+
+
+def test_same_values():
+    directory = helpers.workspace({"craft.on": MODEL, "formatted.on": fmt.format_text(MODEL)})
+    with working_directory(directory):
+        models = [Model(name) for name in ("craft.on", "formatted.on")]
+        for model in models:
+            model.build(quiet=True)
+    values = [{ID: (parameter.min, parameter.max) for ID, parameter in model.parameters.items()} for model in models]
+    assert values[0] == values[1], values
+
+
+def test_check():
+    directory = helpers.workspace({"craft.on": MODEL, "clean.on": FORMATTED})
+    console.exit_code = console.EXIT_SUCCESS
+    try:
+        output = io.StringIO()
+        with contextlib.redirect_stdout(output):
+            changed = fmt.run([directory], check=True)
+        assert changed == [os.path.join(directory, "craft.on")], changed
+        assert "+Mass (0, 100): m    = 3 :kg" in output.getvalue(), output.getvalue()
+        assert console.exit_code == console.EXIT_USAGE
+        with open(os.path.join(directory, "craft.on")) as f:
+            assert f.read() == MODEL, "--check doesn't change files"
+
+        with contextlib.redirect_stdout(io.StringIO()):
+            fmt.run([directory])
+        with open(os.path.join(directory, "craft.on")) as f:
+            assert f.read() == FORMATTED
+    finally:
+        console.exit_code = console.EXIT_SUCCESS
+
+
+ALL_TESTS = [
+    test_format_text,
+    test_spacing,
+    test_unparsed_lines_kept,
+    test_same_values,
+    test_check,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,100 @@
+#!/usr/bin/env python3
+"""Tests for oneil.grammar, which generates the tree-sitter grammar from the parser.
+
+Run directly:
+
+    python test/test_grammar.py
+
+Or:
+
+    pytest test/test_grammar.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import BOOLEAN_OPERATORS, BUILTIN_CONSTANTS, BUILTIN_FUNCTIONS, FUNCTIONS, MATH_CONSTANTS, grammar  # noqa: E402
+from oneil.refactor import DECLARATIONS  # noqa: E402
+import helpers  # noqa: E402
+
+
+CHECKED_IN = os.path.join(os.path.dirname(__file__), "..", "tree-sitter")
+
+
+def test_up_to_date():
+    for path, text in grammar.generate().items():
+        with open(os.path.join(CHECKED_IN, path)) as f:
+            assert f.read() == text, f"tree-sitter/{path} is out of date: run `oneil grammar tree-sitter`"
+
+
+def test_parser_tables():
+    generated = grammar.generate()
+    rules = generated["grammar.js"]
+    highlights = generated[os.path.join("queries", "highlights.scm")]
+    for keyword in DECLARATIONS:
+        assert f'seq("{keyword}", /[ \\t]/)' in rules, keyword
+        assert f'"{keyword}"' in highlights, keyword
+    for operator in BOOLEAN_OPERATORS:
+        assert f'"{operator}"' in rules, operator
+    for constant in BUILTIN_CONSTANTS:
+        assert f'"{constant}"' in rules, constant
+    for function in BUILTIN_FUNCTIONS:
+        assert f'"{function}"' in highlights, function
+
+
+def test_fixed_builtins():
+    # Loading models, config constants, and plugins don't change the grammar
+    generated = grammar.generate()
+    helpers.load({"craft.on": "Angle: a = pi/4 :rad\n"}, "craft.on")
+    with helpers.isolated():
+        MATH_CONSTANTS["g_config"] = 9.81
+        FUNCTIONS["halve"] = "plugin_halve"
+        try:
+            assert grammar.generate() == generated
+        finally:
+            del FUNCTIONS["halve"]
+
+
+def test_run():
+    directory = helpers.workspace()
+    grammar.run(directory)
+    assert os.path.exists(os.path.join(directory, "grammar.js"))
+    assert os.path.exists(os.path.join(directory, "queries", "folds.scm"))
+
+
+ALL_TESTS = [
+    test_up_to_date,
+    test_parser_tables,
+    test_fixed_builtins,
+    test_run,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,141 @@
+#!/usr/bin/env python3
+"""Tests for `include "<fragment>"`: splicing the declarations of fragment files into a
+model, include cycles, and errors that point into the fragment.
+
+Run directly:
+
+    python test/test_include.py
+
+Or:
+
+    pytest test/test_include.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import OneilError  # noqa: E402
+import helpers  # noqa: E402
+
+
+CONSTANTS = """\
+Gravity: g_E = 9.81 :m/s^2
+include "units.oni"
+"""
+
+CRAFT = """\
+include "lib/constants.oni"
+
+Mass: m = 2 :kg
+$ Weight: W = m*g_E :N
+Weight in pounds: W_lb = W/lbf
+"""
+
+FILES = {
+    "lib/constants.oni": CONSTANTS,
+    "lib/units.oni": "Pound force: lbf = 4.448 :N",
+    "craft.on": CRAFT,
+}
+
+
+def _error(files, name="craft.on"):
+    try:
+        helpers.load(files, name)
+    except OneilError as e:
+        return e
+    assert False, "Expected an error"
+
+
+def test_spliced():
+    model, _ = helpers.load(FILES, "craft.on")
+    assert model.parameters["W"].min == 19.62
+    assert abs(model.parameters["W_lb"].min - 19.62/4.448) < 1e-9
+    # The fragment's parameters belong to the model, without a submodel symbol
+    assert set(model.parameters) == {"g_E", "lbf", "m", "W", "W_lb"}, set(model.parameters)
+    assert not model.submodels
+
+
+def test_lines():
+    model, _ = helpers.load(FILES, "craft.on")
+    assert str(model.parameters["g_E"].line_no) == "1 of lib/constants.oni", str(model.parameters["g_E"].line_no)
+    assert str(model.parameters["lbf"].line_no) == "1 of lib/units.oni", str(model.parameters["lbf"].line_no)
+    assert model.parameters["m"].line_no == 3
+
+
+def test_error_in_fragment():
+    e = _error(FILES | {"lib/units.oni": "Pound force: lbf = 4.448 :N\nBroken lbf2 = 2\n"})
+    assert e.kind() == "SyntaxError", e
+    assert "line 2 of lib/units.oni" in e.context(), e.context()
+
+
+def test_missing():
+    e = _error({"craft.on": "include \"nowhere.oni\"\nMass: m = 2 :kg\n"})
+    assert e.kind() == "ModelLoadingError", e
+    assert e.message() == "Included file \"nowhere.oni\" does not exist.", e.message()
+
+
+def test_malformed():
+    e = _error({"craft.on": "include common.oni\nMass: m = 2 :kg\n"})
+    assert e.kind() == "SyntaxError", e
+
+
+def test_cycle():
+    e = _error(FILES | {"lib/units.oni": "Pound force: lbf = 4.448 :N\ninclude \"constants.oni\"\n"})
+    assert e.kind() == "ModelLoadingError", e
+    assert e.message().startswith("Include cycle: "), e.message()
+    cycle = [os.path.basename(path) for path in e.message()[len("Include cycle: "):].split(" -> ")]
+    assert cycle == ["constants.oni", "units.oni", "constants.oni"], cycle
+
+
+def test_self_include():
+    e = _error({"craft.on": "include \"craft.on\"\nMass: m = 2 :kg\n"})
+    assert e.kind() == "ModelLoadingError" and e.message().startswith("Include cycle: "), e
+
+
+def test_included_twice():
+    # The same fragment in two branches isn't a cycle
+    files = {"a.oni": "include \"c.oni\"\n", "b.oni": "include \"c.oni\"\n", "c.oni": "Gravity: g_E = 9.81 :m/s^2\n", "craft.on": "include \"a.oni\"\ninclude \"b.oni\"\nMass: m = 2 :kg\n"}
+    model, _ = helpers.load(files, "craft.on")
+    assert model.parameters["g_E"].min == 9.81
+
+
+ALL_TESTS = [
+    test_spliced,
+    test_lines,
+    test_error_in_fragment,
+    test_missing,
+    test_malformed,
+    test_cycle,
+    test_self_include,
+    test_included_twice,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
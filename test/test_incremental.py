@@ -0,0 +1,142 @@
+#!/usr/bin/env python3
+"""Tests for oneil.incremental, which reparses only the declarations an edit touches.
+
+Run directly:
+
+    python test/test_incremental.py
+
+Or:
+
+    pytest test/test_incremental.py
+"""
+
+import json
+import os
+import random
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import incremental, source_overrides, tokens, tune  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """    A craft for testing edits.
+
+use engine as e
+
+section Sizing
+Mass(1, 100): m = 4 :kg
+    The dry mass.
+Gravity: g = {9.8*m if m > 2 :kg*m/s^2
+             {0 if m <= 2
+Thrust: F = min(F_max.e, 4) :N
+
+test "light": m < 5*m
+"""
+
+
+def _change(line, character, end_line, end_character, text):
+    return {"range": {"start": {"line": line, "character": character}, "end": {"line": end_line, "character": end_character}}, "text": text}
+
+
+def test_only_touched_declarations():
+    document = incremental.Document(CRAFT)
+    changed = document.change([_change(5, 18, 5, 19, "5")])
+    assert [(d.start, d.end) for d in changed] == [(4, 5), (5, 7)], "the edited declaration and the one before it"
+    assert ("number", "5") in [(t.kind, t.text) for t in changed[1].tokens]
+    assert document.tokens() == list(tokens.tokenize(document.text))
+
+
+def test_lines_shift():
+    document = incremental.Document(CRAFT)
+    changed = document.change([_change(2, 0, 2, 0, "Drag: D = 2 :N\n\n")])
+    assert document.text.startswith("    A craft for testing edits.\n\nDrag: D = 2 :N\n\nuse")
+    assert all(d.start <= 4 for d in changed), changed
+    assert document.tokens() == list(tokens.tokenize(document.text))
+
+
+def test_indenting_joins_declarations():
+    document = incremental.Document(CRAFT)
+    document.change([_change(9, 0, 9, 0, "    ")])
+    assert [t.kind for t in document.tokens() if t.line == 10] == ["note"], "an indented line is a note"
+    assert document.tokens() == list(tokens.tokenize(document.text))
+
+
+def test_random_edits():
+    rng = random.Random(3253)
+    pieces = ["\n", "    ", "x", "Drag: D = 2 :N", "\n    {1 if m > 1", "\n\n", "test: m > 1\n", "#", "section S\n", ""]
+    for _ in range(100):
+        document = incremental.Document(CRAFT)
+        for _ in range(6):
+            lines = document.text.split("\n")
+            line = rng.randrange(len(lines))
+            end_line = rng.randrange(line, min(len(lines), line + 3))
+            character = rng.randrange(len(lines[line]) + 1)
+            end_character = rng.randrange(len(lines[end_line]) + 1) if end_line > line else rng.randrange(character, len(lines[line]) + 1)
+            document.change([_change(line, character, end_line, end_character, rng.choice(pieces))])
+            assert document.tokens() == list(tokens.tokenize(document.text)), document.text
+
+
+def test_encoding():
+    document = incremental.Document("Δv 𝛼: dv = 3\n", "utf-16")
+    document.change([_change(0, 12, 0, 13, "4")])
+    assert document.text == "Δv 𝛼: dv = 4\n"
+
+
+def test_tune_did_change():
+    directory = helpers.workspace({"craft.on": "Mass: m = 4 :kg\nWeight: w = 2*m :kg\n"})
+    with working_directory(directory):
+        path = os.path.abspath("craft.on")
+        try:
+            session = tune.Session("craft")
+            request = {"jsonrpc": "2.0", "id": 1, "method": "oneil/didChange", "params": {"changes": [_change(0, 10, 0, 11, "5")]}}
+            [response] = session.handle(json.dumps(request))
+            [declaration] = [d for d in response["result"]["declarations"] if d["line"] == 1]
+            assert {"kind": "number", "text": "5", "line": 1, "start": 10, "end": 11} in declaration["tokens"]
+            with open("craft.on") as f:
+                assert f.read().startswith("Mass: m = 4"), "the file isn't written"
+            request = {"jsonrpc": "2.0", "id": 2, "method": "oneil/evaluate", "params": {"reload": True}}
+            [response] = session.handle(json.dumps(request))
+            assert response["result"]["results"]["parameters"]["w"]["min"] == 10
+        finally:
+            source_overrides.pop(path, None)
+
+
+ALL_TESTS = [
+    test_only_touched_declarations,
+    test_lines_shift,
+    test_indenting_joins_declarations,
+    test_random_edits,
+    test_encoding,
+    test_tune_did_change,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
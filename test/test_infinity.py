@@ -0,0 +1,142 @@
+#!/usr/bin/env python3
+"""Tests for oneil.infinity, the policy for infinite values produced from finite inputs.
+
+Run directly:
+
+    python test/test_infinity.py
+
+Or:
+
+    pytest test/test_infinity.py
+"""
+
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, ParameterError, infinity  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+SENSOR = """Gap: g = 0|2 :m
+Length: L = 4 :m
+"""
+
+CRAFT = """use sensor as s
+
+Ratio: r = 4/g.s :1/m
+Double ratio: d = 2*r :1/m
+Open: o = 1|inf :m
+Sum: u = o + L.s :m
+Literal: i = inf*L.s :m
+
+test "ratio": r*L.s > 1
+"""
+
+
+def _model(policy, **settings):
+    directory = helpers.workspace({"sensor.on": SENSOR, "craft.on": CRAFT})
+    infinity.reset()
+    infinity.settings.update({"policy": policy}, **settings)
+    try:
+        with working_directory(directory):
+            model = Model("craft.on")
+            model.build(quiet=True)
+        return model
+    finally:
+        infinity.reset()
+
+
+def test_allow():
+    model = _model("allow")
+    assert model.parameters["r"].max == math.inf
+    assert model.unbounded == [], model.unbounded
+
+
+def test_warn():
+    model = _model("warn")
+    assert model.parameters["r"].max == math.inf
+    assert len(model.unbounded) == 1, model.unbounded
+    entry = model.unbounded[0]
+    assert entry["id"] == "r" and entry["expression"] == "(4)/(g)", entry
+    assert entry["reason"] == "dividing by a range that reaches zero"
+    assert entry["affected"] == ["r", "d"], "parameters infinite because of an input with 1|inf aren't reported"
+    assert entry["tests"] == ['"ratio"']
+    assert entry["warning"].line_no == 3
+    assert any("Also infinite because of it: d" in note for note in entry["warning"].notes())
+
+
+def test_saturate():
+    model = _model("saturate", bound=1e6)
+    assert model.parameters["r"].max == 1e6 and model.parameters["d"].max == 2e6
+    assert model.parameters["u"].max == math.inf, "infinite inputs aren't saturated"
+    assert model.parameters["i"].max == math.inf, "neither is inf in an equation"
+    assert model.unbounded[0]["saturated"] == 1e6 and model.unbounded[0]["affected"] == ["r"]
+
+
+def test_fail():
+    try:
+        _model("fail")
+    except ParameterError as e:
+        assert e.parameter.id == "r", e.parameter.id
+        assert "dividing by a range that reaches zero" in e.message()
+    else:
+        raise AssertionError("expected a ParameterError")
+
+
+def test_settings():
+    errors = []
+    infinity.apply({"policy": "loud", "bound": -1, "bounds": {"m/s": 3e8, "blah": 1}}, errors)
+    try:
+        assert len(errors) == 3, errors
+        assert infinity.bound({"m": 1, "s": -1}) == 3e8
+        assert infinity.bound({"m": 1}) == infinity.DEFAULT_SETTINGS["bound"]
+    finally:
+        infinity.reset()
+
+    errors = []
+    infinity.apply({"bounds": {"km": 2}}, errors)
+    try:
+        assert errors == [] and infinity.bound({"m": 1}) == 2000
+    finally:
+        infinity.reset()
+
+
+ALL_TESTS = [
+    test_allow,
+    test_warn,
+    test_saturate,
+    test_fail,
+    test_settings,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,116 @@
+#!/usr/bin/env python3
+"""Tests for the lerp() and map_range() builtins, which interpolate between design points.
+
+Run directly:
+
+    python test/test_interpolation.py
+
+Or:
+
+    pytest test/test_interpolation.py
+"""
+
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import PiecewiseUnitError, UnitEvaluationError, sensitivity  # noqa: E402
+import helpers  # noqa: E402
+
+
+TANK = """\
+Small tank volume: V_s = 1 :m^3
+Large tank volume: V_l = 5 :m^3
+Small tank mass: m_s = 10 :kg
+Large tank mass: m_l = 30|32 :kg
+Size fraction: f = 0.25|0.5
+Tank volume: V = 2|3 :m^3
+$ Lerped mass: m = lerp(m_s, m_l, f) :kg
+By hand: m_h = m_s + (m_l - m_s)*f :kg
+$ Mapped mass: m_v = map_range(V, V_s, V_l, m_s, m_l) :kg
+Extrapolated mass: m_x = lerp(m_s, m_l, 2) :kg
+"""
+
+
+def _model(text):
+    return helpers.load({"tank.on": text}, "tank.on")[0]
+
+
+def _error(text):
+    try:
+        _model(text)
+    except (UnitEvaluationError, PiecewiseUnitError) as e:
+        return e
+    raise AssertionError("expected a unit error")
+
+
+def test_lerp():
+    model = _model(TANK)
+    assert (model.parameters["m"].min, model.parameters["m"].max) == (15, 21)
+    assert (model.parameters["m_h"].min, model.parameters["m_h"].max) == (15, 21)
+    assert (model.parameters["m_x"].min, model.parameters["m_x"].max) == (50, 54)
+
+
+def test_map_range():
+    model = _model(TANK)
+    assert math.isclose(model.parameters["m_v"].min, 15) and math.isclose(model.parameters["m_v"].max, 21)
+
+
+def test_units():
+    assert "kg and m^3" in _error(TANK.replace("lerp(m_s, m_l, f)", "lerp(m_s, V, f)")).message()
+    assert "fraction of lerp() must be unitless" in _error(TANK.replace("lerp(m_s, m_l, f)", "lerp(m_s, m_l, V)")).message()
+    assert "map_range()" in _error(TANK.replace("map_range(V, V_s", "map_range(m_s, V_s")).message()
+
+
+def test_piecewise_units():
+    text = TANK + "Mode [low, high]: M = low\nPicked: p = {m_s*1 if M == 'low' :kg\n    {lerp(m_s, V_l, f) if M == 'high'\n"
+    error = _error(text)
+    assert isinstance(error, PiecewiseUnitError) and "Cannot interpolate between" in error.notes_[0]
+
+
+def test_sensitivity():
+    report = {entry["id"]: entry for entry in sensitivity.sensitivities(_model(TANK))}
+    derivatives = {i["input"]: i["derivative"] for i in report["m"]["inputs"]}
+    # At f = 0.375 and m_l = 31 kg
+    assert math.isclose(derivatives["m_l"], 0.375) and math.isclose(derivatives["f"], 21)
+    derivatives = {i["input"]: i["derivative"] for i in report["m_v"]["inputs"]}
+    assert math.isclose(derivatives["V"], 21 / 4)
+
+
+ALL_TESTS = [
+    test_lerp,
+    test_map_range,
+    test_units,
+    test_piecewise_units,
+    test_sensitivity,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
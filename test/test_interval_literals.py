@@ -0,0 +1,138 @@
+#!/usr/bin/env python3
+"""Tests for interval literals (`x = [2.1, 2.4] :V`).
+
+Run directly:
+
+    python test/test_interval_literals.py
+
+Or:
+
+    pytest test/test_interval_literals.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import LimitError, Model, SyntaxError, interval_literal, parse_file  # noqa: E402
+from oneil import units as un  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+BUS = """\
+Bus voltage (0, 5): V_b = [2.1, 2.4] :V
+Bus current: I_b = 2|3 :A
+Bus power: P_b = V_b*I_b :W
+Phase (0, 2*pi): phi = [0, pi/2] :rad
+"""
+
+
+def _model(text, design=None):
+    directory = helpers.workspace({"bus.on": text, "design.on": design or ""})
+    with working_directory(directory):
+        model = Model("bus.on")
+        model.build(quiet=True)
+        if design:
+            model.overwrite(["design.on"], quiet=True)
+    return model
+
+
+def _error(text):
+    try:
+        _model(text)
+    except (SyntaxError, LimitError) as e:
+        return e
+    raise AssertionError("expected an error")
+
+
+def test_parse():
+    assert interval_literal("[2.1, 2.4]") == ("2.1", "2.4")
+    assert interval_literal(" [0,pi/2] ") == ("0", "pi/2")
+    assert interval_literal("2.1|2.4") is None
+    assert interval_literal("[a, b, c]") is None
+    with working_directory(helpers.workspace({"bus.on": BUS})):
+        parameters = parse_file("bus.on")[1]
+    assert parameters["V_b"].independent and parameters["V_b"].interval
+    assert (parameters["V_b"].min, parameters["V_b"].max) == (2.1, 2.4)
+    assert not parameters["I_b"].interval
+
+
+def test_calculation():
+    model = _model(BUS)
+    assert (model.parameters["P_b"].min, model.parameters["P_b"].max) == (2.1 * 2, 2.4 * 3)
+
+
+def test_display():
+    model = _model(BUS)
+    assert str(model.parameters["V_b"]) == "[2.1, 2.4] V", str(model.parameters["V_b"])
+    assert str(model.parameters["phi"]) == "[0.0, 1.571] rad", str(model.parameters["phi"])
+    assert str(model.parameters["I_b"]) == "2.0|3.0 A"
+    # Shown in one unit, so it can be written back
+    assert un.hr_vals_and_units((900, 1200), {"m": 1}, None, 4, interval=True) == "[0.9, 1.2] km"
+    # A shown interval parses back to the same value
+    shown = str(model.parameters["V_b"])
+    value, units = shown.rsplit(" ", 1)
+    again = _model(f"Bus voltage: V_b = {value} :{units}\n")
+    assert (again.parameters["V_b"].min, again.parameters["V_b"].max) == (2.1, 2.4)
+
+
+def test_design():
+    model = _model(BUS, "V_b = [1, 2] :V\n")
+    assert str(model.parameters["V_b"]) == "[1.0, 2.0] V"
+    assert (model.parameters["P_b"].min, model.parameters["P_b"].max) == (2, 6)
+
+
+def test_errors():
+    assert "min greater than its max" in _error("Voltage: V = [2.4, 2.1] :V\n").message()
+    assert "\"V_x | 3\"" in _error("Voltage: V = [V_x, 3] :V\n").message()
+    error = _error(BUS.replace("[2.1, 2.4]", "[4, 6]"))
+    assert isinstance(error, LimitError) and "[4.0, 6.0] V is out of bounds" in error.message(), error.message()
+    # A bound of zero is checked too
+    assert isinstance(_error(BUS.replace("[2.1, 2.4]", "[0, 6]")), LimitError)
+
+
+def test_zero_values():
+    # Other values of zero are checked as before interval literals
+    model = _model("Offset (1, 5): x = 0 :V\nRange (-2, -1): r = 0|3 :V\n")
+    assert (model.parameters["x"].min, model.parameters["r"].max) == (0, 3)
+    assert isinstance(_error("Offset (1, 5): x = [0, 2] :V\n"), LimitError)
+
+
+ALL_TESTS = [
+    test_parse,
+    test_calculation,
+    test_display,
+    test_design,
+    test_errors,
+    test_zero_values,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,129 @@
+#!/usr/bin/env python3
+"""Tests for parameter labels: the label index, lookup by ID or label, `explain` with a
+label, and the duplicate-label lint.
+
+Run directly:
+
+    python test/test_labels.py
+
+Or:
+
+    pytest test/test_labels.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import OneilError, console, lint  # noqa: E402
+import helpers  # noqa: E402
+
+
+CYLINDER = """\
+Day length: t_day = 6 :s
+Rotation rate: omega = 2*pi/t_day :rad/s
+    The rate that gives one rotation per day.
+Radius: r = 250 :m
+$ Artificial gravity: g_a = omega^2*r :m/s^2
+Mass: m_1 = 2 :kg
+mass: m_2 = 3 :kg
+test: g_a > omega^2*r/2
+"""
+
+
+def _model(text=CYLINDER, build=True):
+    return helpers.load({"cylinder.on": text}, "cylinder.on", build=build)[0]
+
+
+def test_labels():
+    model = _model(build=False)
+    labels = model.labels()
+    assert labels["rotation rate"] == ["omega"]
+    assert labels["mass"] == ["m_1", "m_2"]
+    assert "Rotation rate" not in labels
+
+
+def test_lookup():
+    model = _model(build=False)
+    assert model.lookup("omega") == "omega"
+    assert model.lookup("Rotation Rate") == "omega"
+    assert model.lookup("  artificial gravity ") == "g_a"
+    for name, message in (("Mass", "More than one parameter is labeled \"Mass\" (m_1, m_2). Use an ID instead."), ("spin", "Could not find a parameter with this ID or label in the model.")):
+        try:
+            model.lookup(name)
+        except OneilError as e:
+            assert e.kind() == "IDError", e
+            assert e.message() == message, e.message()
+        else:
+            assert False, f"Expected an IDError for {name}"
+
+
+def test_explain():
+    model = _model()
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        model.explain("rotation rate")
+    lines = output.getvalue().splitlines()
+    assert lines[0] == "Rotation rate (omega), line 2 of cylinder", lines
+    assert "    Depends on: pi, t_day" in lines, lines
+    assert "    Used by: g_a, test g_a > omega**2*r/2" in lines, lines
+    # The notes of a calculated parameter are kept
+    assert lines[-1] == "    The rate that gives one rotation per day.", lines
+
+
+def test_duplicate_label_lint():
+    warnings = [warning for warning in lint.lint_model(_model(build=False)) if warning.lint == "duplicate-label"]
+    assert len(warnings) == 1, warnings
+    assert warnings[0].line_no == 7
+    assert warnings[0].message_ == "m_2 has the same label as m_1 (\"Mass\"). Give it a distinct label.", warnings[0].message_
+    assert warnings[0].notes() == ["m_1 is defined on line 6"], warnings[0].notes()
+    distinct = _model(CYLINDER.replace("mass: m_2", "Second mass: m_2"), build=False)
+    assert not [warning for warning in lint.lint_model(distinct) if warning.lint == "duplicate-label"]
+
+
+def test_cli():
+    directory = helpers.workspace({"cylinder.on": CYLINDER})
+    output, code = helpers.cli(["--quiet", "cylinder.on", "explain Artificial gravity", "explain mass", "quit"], directory)
+    assert "Artificial gravity (g_a), line 5 of cylinder" in output, output
+    assert "More than one parameter is labeled \"mass\" (m_1, m_2)." in output, output
+    assert code == console.EXIT_RESOLUTION_ERROR, output
+
+
+ALL_TESTS = [
+    test_labels,
+    test_lookup,
+    test_explain,
+    test_duplicate_label_lint,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
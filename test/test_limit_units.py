@@ -0,0 +1,104 @@
+#!/usr/bin/env python3
+"""Tests for units written on parameter limits and discrete options.
+
+Run directly:
+
+    python test/test_limit_units.py
+
+Or:
+
+    pytest test/test_limit_units.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import LimitUnitError, UnitParseError, parse_file, split_limit_units  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+def _parameters(text):
+    with working_directory(helpers.workspace({"model.on": text})):
+        return parse_file("model.on")[1]
+
+
+def _error(text):
+    try:
+        _parameters(text)
+    except (LimitUnitError, UnitParseError) as e:
+        return e
+    return None
+
+
+def test_split():
+    assert split_limit_units("5 km") == ("5", "km")
+    assert split_limit_units("5km") == ("5", "km")
+    assert split_limit_units("-pi/2 rad") == ("-pi/2", "rad")
+    assert split_limit_units("2 m/s^2") == ("2", "m/s^2")
+    assert split_limit_units("5e3") == ("5e3", None)
+    assert split_limit_units("2*pi") == ("2*pi", None)
+    assert split_limit_units("- pi / 2") == ("- pi / 2", None)
+    assert split_limit_units("inf") == ("inf", None)
+
+
+def test_limits_converted():
+    parameters = _parameters("Length (0, 5 km): l = 2 :m\nWidth (0 m, 5000m): w = 2 :km\nAngle (-pi/2, pi/2): a = 0.1\n")
+    assert parameters["l"].options == (0, 5000), parameters["l"].options
+    assert parameters["w"].options == (0, 5000), "limits without units are in the parameter's units"
+    assert parameters["a"].options[1] > 1.57
+
+
+def test_mismatched_limit():
+    error = _error("Length (0, 5 s): l = 2 :m\n")
+    assert isinstance(error, LimitUnitError), error
+    assert error.code() == "E0203" and error.line_no == 1
+    assert error.limit == "5 s" and error.limit_units == {"s": 1} and error.units == {"m": 1}
+    assert isinstance(_error("Ratio (0, 1 m): r = 0.5\n"), LimitUnitError), "unitless parameters can't have limits with units"
+    assert isinstance(_error("Length (0, 5 furlongs): l = 2 :m\n"), UnitParseError)
+
+
+def test_options():
+    parameters = _parameters("Mode [Earth orbit, far]: D = Earthorbit\n")
+    assert parameters["D"].options == ["Earthorbit", "far"], "options without units are unchanged"
+    error = _error("Size [1 s, 2 s]: n = 2 :m\n")
+    assert isinstance(error, LimitUnitError) and error.limit == "1 s", error
+    assert isinstance(_error("Size [1 km, 2 km]: n = 2 :m\n"), LimitUnitError), "options are in the parameter's own units"
+
+
+ALL_TESTS = [
+    test_split,
+    test_limits_converted,
+    test_mismatched_limit,
+    test_options,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,135 @@
+#!/usr/bin/env python3
+"""Tests for oneil.limits, the time and depth limits on evaluation.
+
+Run directly:
+
+    python test/test_limits.py
+
+Or:
+
+    pytest test/test_limits.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+import time
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import limits, EvaluationLimitError  # noqa: E402
+import helpers  # noqa: E402
+
+MODEL = "Ratio: x = 2\nA: a = 2*(x + x*(x + 1))\n"
+
+
+def _raises(function):
+    try:
+        function()
+    except EvaluationLimitError as e:
+        return e
+    return None
+
+
+def test_depth():
+    try:
+        limits.overrides["depth"] = 3
+        limits.reset()
+        limits.check(None, ["a", "b", "c"])
+        error = _raises(lambda: limits.check(None, ["a", "b", "c", "d"]))
+        assert error and error.limit == "depth" and "--max-depth" in error.notes()[0]
+    finally:
+        limits.overrides.clear()
+        limits.reset()
+
+
+def test_time():
+    try:
+        limits.overrides["time"] = 0.05
+        limits.reset()
+        with limits.evaluation():
+            limits.check(None, [])
+            error = _raises(lambda: time.sleep(1))
+        assert error and error.limit == "time"
+        # The deadline only applies during an evaluation.
+        limits.check(None, [])
+    finally:
+        limits.overrides.clear()
+        limits.reset()
+
+
+def test_config_settings():
+    errors = []
+    limits.apply({"time": 10, "depth": 0, "samples": 5}, errors)
+    try:
+        assert limits.limits["time"] == 10
+        assert limits.limits["depth"] == limits.DEFAULT_LIMITS["depth"]
+        assert len(errors) == 2
+    finally:
+        limits.reset()
+
+
+def test_flags_take_precedence():
+    try:
+        limits.overrides["depth"] = 7
+        limits.reset()
+        limits.apply({"depth": 100}, [])
+        assert limits.limits["depth"] == 7
+    finally:
+        limits.overrides.clear()
+        limits.reset()
+
+
+def test_max_depth_flag():
+    directory = helpers.workspace({"craft.on": MODEL})
+    with helpers.isolated(), contextlib.redirect_stdout(io.StringIO()):
+        oneil.main(["--max-depth", "7", "completions", "bash"])
+        assert limits.limits["depth"] == 7, limits.limits
+    # `oneil stats` has its own --max-nesting threshold, so --max-depth is the limit for it too
+    with helpers.isolated():
+        output = io.StringIO()
+        with contextlib.redirect_stdout(output):
+            oneil.main(["--max-depth", "3", "stats", "--max-nesting", "1", directory])
+        assert "craft.on: nesting 5 > 1" in output.getvalue(), output.getvalue()
+        assert limits.overrides["depth"] == 3, limits.overrides
+    output, _ = helpers.cli(["stats", "--max-nesting", "5", directory])
+    assert "threshold(s) exceeded" not in output, output
+
+
+ALL_TESTS = [
+    test_depth,
+    test_time,
+    test_config_settings,
+    test_flags_take_precedence,
+    test_max_depth_flag,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,142 @@
+#!/usr/bin/env python3
+"""Tests for oneil.lint: the typos found by the spelling lint, and the magnitude lint
+with its fixes.
+
+Run directly:
+
+    python test/test_lint.py
+
+Or:
+
+    pytest test/test_lint.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, lint  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+KNOWN = {"solar", "panel", "area", "temperature", "mission", "deploy", "array", "heat"}
+
+
+def test_typos():
+    found = lint.misspellings("Solar pannel aera", KNOWN)
+    assert found == [("pannel", "panel"), ("aera", "area")]
+
+
+def test_swapped_letters():
+    assert lint.misspellings("Radiator tempreature", KNOWN) == [("tempreature", "temperature")]
+
+
+def test_endings():
+    assert lint.misspellings("Deployed arrays, deploying panels", KNOWN) == []
+
+
+def test_unknown_words():
+    # Names and jargon that aren't close to a known word aren't typos.
+    assert lint.misspellings("Careweather RTG xyzzy", KNOWN) == []
+
+
+def test_ignored_text():
+    assert lint.misspellings(r"$\pannel{x}$ and `pannel` and \pannel", KNOWN) == []
+
+
+def test_bundled_dictionary():
+    assert {"temperature", "radiator", "world", "convention"} <= lint.dictionary()
+
+
+RADIO = """Frequency: f = 2400000000 :Hz
+Pulse(0, 0.001): t = 0.000002|0.000005 :s
+Range(1, inf): r = 36000000 :m
+Power: P = 20 :W
+Mass: M = 3000000 :kg
+Drift(0, pi): d = 0.0000004 :m
+"""
+
+
+def _radio():
+    directory = helpers.workspace({"radio.on": RADIO})
+    return directory
+
+
+def test_magnitude():
+    with working_directory(_radio()):
+        warnings = {w.line_no: w for w in lint.magnitudes(Model("radio.on"))}
+    assert set(warnings) == {1, 2, 3, 6}, "values between 1e-3 and 1e6, and units that don't take prefixes, are left alone"
+    assert warnings[1].message() == "f is 2400000000 :Hz. Write it as 2.4 :GHz. [magnitude]", warnings[1].message()
+    assert warnings[1].fix == "Frequency: f = 2.4 :GHz"
+    assert warnings[2].fix == "Pulse(0, 1000): t = 2|5 :us", "limits are rescaled with the value"
+    assert warnings[3].fix == "Range(0.001, inf): r = 36000 :km", "prefixes are limited to the ones the unit takes"
+    assert warnings[6].fix is None, "limits that aren't numbers aren't fixed"
+
+
+def test_fix():
+    directory = _radio()
+    with working_directory(directory):
+        before = Model("radio.on")
+        before.build(quiet=True)
+    lint.run([os.path.join(directory, "radio.on")], fix=True)
+    with working_directory(directory):
+        after = Model("radio.on")
+        after.build(quiet=True)
+        assert [w.line_no for w in lint.magnitudes(after)] == [6]
+    for ID in ("f", "t", "r"):
+        assert abs(after.parameters[ID].max - before.parameters[ID].max) <= 1e-12 * before.parameters[ID].max, ID
+    assert after.parameters["f"].hr_units == "GHz"
+
+
+def test_unit_names():
+    directory = helpers.workspace({"arm.on": "Torque: tau = 12 :newton-meters\nPeriod: T = 4  :  seconds\nMass: m = 3 :kg\n"})
+    with working_directory(directory):
+        warnings = list(lint.unit_names(Model("arm.on")))
+    assert [w.message() for w in warnings] == [
+        'The units of tau are written as "newton-meters". Write them as "N*m". [unit-name]',
+        'The units of T are written as "seconds". Write them as "s". [unit-name]',
+    ]
+    assert [w.fix for w in warnings] == ["Torque: tau = 12 :N*m", "Period: T = 4  :  s"]
+
+
+ALL_TESTS = [
+    test_typos,
+    test_swapped_letters,
+    test_endings,
+    test_unknown_words,
+    test_ignored_text,
+    test_bundled_dictionary,
+    test_magnitude,
+    test_fix,
+    test_unit_names,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,117 @@
+#!/usr/bin/env python3
+"""Tests for the cap on printed errors (--max-errors and display.max_errors).
+
+Run directly:
+
+    python test/test_max_errors.py
+
+Or:
+
+    pytest test/test_max_errors.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import config, console, SyntaxError  # noqa: E402
+
+
+def _print_errors(count):
+    stdout, stderr = io.StringIO(), io.StringIO()
+    with contextlib.redirect_stdout(stdout), contextlib.redirect_stderr(stderr):
+        for i in range(count):
+            console.print_error(SyntaxError("m.on", i + 1, "x = (", "Invalid syntax."))
+        console.summarize_errors()
+    return stdout.getvalue(), stderr.getvalue()
+
+
+@contextlib.contextmanager
+def _settings(max_errors, error_format="human"):
+    previous = (console.max_errors, console.error_format, console.exit_code)
+    console.max_errors, console.error_format = max_errors, error_format
+    try:
+        yield
+    finally:
+        console.max_errors, console.error_format, console.exit_code = previous
+
+
+def test_summarizes_extra_errors():
+    with _settings(3):
+        stdout, _ = _print_errors(10)
+        assert stdout.count("SyntaxError") == 3
+        assert "...and 7 more error(s)" in stdout
+        assert console.exit_code == console.EXIT_PARSE_ERROR
+        # Counting starts again after each summary.
+        stdout, _ = _print_errors(2)
+        assert stdout.count("SyntaxError") == 2 and "more error" not in stdout
+
+
+def test_no_limit():
+    with _settings(0):
+        stdout, _ = _print_errors(50)
+        assert stdout.count("SyntaxError") == 50 and "more error" not in stdout
+
+
+def test_json_summary():
+    with _settings(1, "json"):
+        _, stderr = _print_errors(4)
+        lines = [json.loads(line) for line in stderr.splitlines()]
+        assert [line["kind"] for line in lines] == ["SyntaxError", "ErrorSummary"]
+        assert lines[1]["suppressed"] == 3
+
+
+def test_config_and_flag():
+    try:
+        config._apply_display({"max_errors": 5}, [])
+        assert console.max_errors == 5
+        errors = []
+        config._apply_display({"max_errors": -1}, errors)
+        assert len(errors) == 1
+        console.max_errors_flag = 2
+        config._apply_display({"max_errors": 5}, [])
+        assert console.max_errors == 2
+    finally:
+        console.max_errors_flag = None
+        config.display.update(config.DEFAULT_DISPLAY)
+        config._apply_max_errors()
+
+
+ALL_TESTS = [
+    test_summarizes_extra_errors,
+    test_no_limit,
+    test_json_summary,
+    test_config_and_flag,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,165 @@
+#!/usr/bin/env python3
+"""Tests for oneil.memory (peak memory per phase with --profile-memory) and the streaming
+mode of oneil eval, which evaluates one model at a time.
+
+Run directly:
+
+    python test/test_memory.py
+
+Or:
+
+    pytest test/test_memory.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+import tracemalloc
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, evaluate_all, memory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """\
+Thrust: F = 20 :kN
+"""
+
+CRAFT = """\
+use engine as e
+Mass: m = 500 :kg
+$ Acceleration: a = F.e/m :m/s^2
+Minimum acceleration: a_min = 10 :m/s^2
+test: a > a_min
+"""
+
+PROBE = CRAFT.replace("500", "4000")
+
+FILES = {"engine.on": ENGINE, "craft.on": CRAFT, "probe.on": PROBE}
+
+
+@contextlib.contextmanager
+def _profiling():
+    tracing = tracemalloc.is_tracing()
+    with helpers.isolated():
+        memory.enable()
+        try:
+            yield
+        finally:
+            memory.phases.clear()
+            if not tracing:
+                tracemalloc.stop()
+
+
+def _evaluate(directory, **options):
+    output = io.StringIO()
+    with helpers.isolated(), contextlib.redirect_stdout(output):
+        results = evaluate_all([directory], **options)
+    return {os.path.basename(file): (summary, error) for file, summary, _, error in results}, output.getvalue()
+
+
+def test_disabled():
+    with helpers.isolated():
+        memory.enabled = False
+        with memory.phase("calculate", "craft"):
+            pass
+    assert memory.phases == []
+
+
+def test_phases():
+    with _profiling():
+        with memory.phase("calculate", "craft"):
+            data = [0] * 100000
+            # Nested phases count toward the outermost one
+            with memory.phase("calculate", "engine"):
+                pass
+        del data
+        assert [(name, model) for name, model, _, _ in memory.phases] == [("calculate", "craft")], memory.phases
+        _, _, peak, retained = memory.phases[0]
+        assert peak >= 800000 and retained < peak, (peak, retained)
+
+        output = io.StringIO()
+        with contextlib.redirect_stdout(output):
+            memory.report()
+        assert "Peak (MB)" in output.getvalue() and "Peak memory: " in output.getvalue(), output.getvalue()
+        assert memory.phases == []
+
+
+def test_evaluation_phases():
+    directory = helpers.workspace(FILES)
+    with _profiling():
+        _, output = _evaluate(directory, stream=True)
+    for phase in ("parse and resolve", "calculate", "test"):
+        assert phase in output, output
+
+
+def test_stream():
+    directory = helpers.workspace(FILES)
+    shared, _ = _evaluate(directory)
+    streamed, output = _evaluate(directory, stream=True)
+    # The same entry models, with the same results
+    assert sorted(streamed) == sorted(shared) == ["craft.on", "probe.on"], streamed
+    for name in streamed:
+        assert streamed[name][0]["performance"][0]["min"] == shared[name][0]["performance"][0]["min"]
+    assert "Evaluated 2 model(s) one at a time: 1 passed, 1 failed." in output, output
+
+
+def test_stream_overrides():
+    # Streamed models aren't shared, so an override only applies to the model it names
+    directory = helpers.workspace(FILES)
+    results, _ = _evaluate(directory, stream=True, overrides=["engine.F=40:kN"])
+    assert sorted(results) == ["craft.on", "engine.on", "probe.on"], results
+    assert results["craft.on"][0]["performance"][0]["min"] == 40
+
+
+def test_cli():
+    directory = helpers.workspace(FILES)
+    tracing = tracemalloc.is_tracing()
+    try:
+        output, code = helpers.cli(["eval", "--stream", "--profile-memory", "."], directory)
+    finally:
+        if not tracing:
+            tracemalloc.stop()
+    assert code == console.EXIT_TEST_FAILURE, output
+    assert "one at a time" in output and "Peak memory: " in output, output
+    assert not memory.enabled
+
+
+ALL_TESTS = [
+    test_disabled,
+    test_phases,
+    test_evaluation_phases,
+    test_stream,
+    test_stream_overrides,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
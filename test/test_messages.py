@@ -0,0 +1,91 @@
+#!/usr/bin/env python3
+"""Tests for oneil.messages, the error codes and message catalogs.
+
+Run directly:
+
+    python test/test_messages.py
+
+Or:
+
+    pytest test/test_messages.py
+"""
+
+import glob
+import os
+import re
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import messages  # noqa: E402
+
+SRC = os.path.join(os.path.dirname(__file__), "..", "src", "oneil")
+
+
+def test_codes_are_unique():
+    codes = list(messages.CODES.values())
+    assert len(codes) == len(set(codes))
+    assert all(re.fullmatch(r"[EW]\d{4}", code) for code in codes)
+
+
+def test_english_catalog_has_every_message():
+    used = set()
+    for path in glob.glob(os.path.join(SRC, "*.py")):
+        with open(path) as f:
+            used |= set(re.findall(r"messages\.text\(\s*[\"']([\w-]+)[\"']", f.read()))
+    assert used
+    missing = used - set(messages.catalog("en")["messages"])
+    assert not missing, missing
+
+
+def test_catalog_codes_exist():
+    for ID in messages.catalog("en")["codes"]:
+        assert ID.split("-")[0] in messages.CODES.values(), ID
+
+
+def test_fallbacks():
+    previous = messages.language
+    try:
+        messages.language = "xx"
+        assert messages.text("context-line", "unused", file="a.on", line=3) == "in a.on (line 3)"
+        assert messages.text("not-in-any-catalog", "Found {count}", count=2) == "Found 2"
+        assert messages.error_text("ModelNotFoundError", "unused", file="a.on") == "Model a.on not found."
+        assert messages.error_text("SyntaxError", "Invalid syntax.") == "Invalid syntax."
+    finally:
+        messages.language = previous
+
+
+ALL_TESTS = [
+    test_codes_are_unique,
+    test_english_catalog_has_every_message,
+    test_catalog_codes_exist,
+    test_fallbacks,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
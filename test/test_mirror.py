@@ -0,0 +1,98 @@
+#!/usr/bin/env python3
+"""Tests for `@mirror` annotations and Model.mirror_report, drift between Python
+constants and the parameters that mirror them.
+
+Run directly:
+
+    python test/test_mirror.py
+
+Or:
+
+    pytest test/test_mirror.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import parse_annotation  # noqa: E402
+import helpers  # noqa: E402
+
+
+CONSTANTS = """K_COPPER = 401.0
+K_ALUMINUM = 237.0
+SPAN = (2.0, 3.0)
+"""
+
+MODEL = """import mirror_constants
+
+@mirror(mirror_constants.py::K_COPPER)
+Copper conductivity: k_cu = 401 :W/m/K
+
+@mirror(mirror_constants.py::K_ALUMINUM)
+Aluminum conductivity: k_al = 205 :W/m/K
+
+@mirror(mirror_constants.py::SPAN)
+Span: s = 2|3 :m
+
+@mirror(mirror_constants.py::MISSING)
+Gap: g = 1 :mm
+"""
+
+
+def test_mirror_annotation():
+    assert parse_annotation("@mirror(thermal.py::K_COPPER)\n") == ("mirror", ("thermal.py", "K_COPPER"))
+    assert parse_annotation("@mirror(lib/thermal.py::K)\n") == ("mirror", ("lib/thermal.py", "K"))
+    for line in ("@mirror\n", "@mirror(thermal::K)\n", "@mirror(thermal.py)\n"):
+        try:
+            parse_annotation(line)
+        except ValueError as e:
+            assert "@mirror" in str(e), e
+        else:
+            raise AssertionError(f"expected a ValueError for {line!r}")
+
+
+def test_mirror_drift():
+    model, _ = helpers.load({"mirror_constants.py": CONSTANTS, "thermal.on": MODEL}, "thermal.on")
+
+    drift = {entry["id"]: entry for entry in model.mirror_drift}
+    assert set(drift) == {"k_al", "g"}, drift
+    assert drift["k_al"]["value"] == 237.0
+    assert drift["k_al"]["warning"].kind() == "MirrorWarning"
+    assert "205" in drift["k_al"]["warning"].message() and "237" in drift["k_al"]["warning"].message()
+    assert drift["g"]["value"] is None and "doesn't define MISSING" in drift["g"]["warning"].message()
+
+
+ALL_TESTS = [
+    test_mirror_annotation,
+    test_mirror_drift,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
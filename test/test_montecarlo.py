@@ -0,0 +1,140 @@
+#!/usr/bin/env python3
+"""Tests for oneil.montecarlo, Monte Carlo sampling of a model's inputs (`montecarlo`,
+`oneil eval --samples`).
+
+Run directly:
+
+    python test/test_montecarlo.py
+
+Or:
+
+    pytest test/test_montecarlo.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, evaluate_all, montecarlo, parse_annotation  # noqa: E402
+import helpers  # noqa: E402
+
+
+MODEL = """@distribution(normal)
+Thrust (0, 200): F = 90|110 :N
+Mass (1, 10): m = 2|6 :kg
+Floor: F_min = 100 :N
+$ Acceleration: a = F/m :m/s^2
+
+test "thrust": F > F_min
+"""
+
+
+def _model():
+    return helpers.load({"craft.on": MODEL}, "craft.on")[0]
+
+
+def _parameter(report, ID):
+    return next(entry for entry in report["parameters"] if entry["id"] == ID)
+
+
+def test_annotation():
+    assert parse_annotation("@distribution(normal, 2)") == ("distribution", ("normal", 2))
+    assert parse_annotation("@distribution(triangular)") == ("distribution", ("triangular",))
+    for line in ["@distribution", "@distribution(lognormal)", "@distribution(uniform, 2)", "@distribution(normal, 0)", "@distribution(triangular, 2)"]:
+        try:
+            parse_annotation(line)
+        except ValueError:
+            continue
+        raise AssertionError(f"Expected a ValueError for {line}")
+
+
+def test_percentile():
+    assert montecarlo.percentile([1, 2, 3, 4, 5], 50) == 3
+    assert montecarlo.percentile([0, 10], 5) == 0.5
+    assert montecarlo.percentile([7], 95) == 7
+
+
+def test_simulate():
+    model = _model()
+    report = montecarlo.simulate(model, samples=500, seed=1)
+    assert report["samples"] == 500 and report["failed"] == 0 and report["seed"] == 1
+    assert report["inputs"] == [{"input": "F", "distribution": "normal"}, {"input": "m", "distribution": "uniform"}], report["inputs"]
+
+    thrust = _parameter(report, "F")
+    assert abs(thrust["mean"] - 100) < 1 and abs(thrust["std"] - 10 / 3) < 0.5, thrust
+    acceleration = _parameter(report, "a")
+    assert (acceleration["min"], acceleration["max"]) == (15, 55) and acceleration["performance"]
+    percentiles = [acceleration["percentiles"][str(q)] for q in montecarlo.PERCENTILES]
+    assert 15 <= percentiles[0] < percentiles[1] < percentiles[2] <= 55, percentiles
+    assert _parameter(report, "F_min")["std"] == 0
+
+    probability = report["tests"][0]["probability"]
+    assert report["tests"][0]["name"] == "thrust" and 0.4 < probability < 0.6, report["tests"]
+    assert (model.parameters["F"].min, model.parameters["F"].max) == (90, 110), "the model's own values are restored"
+    assert model.parameters["a"].max == 55
+
+
+def test_seed():
+    model = _model()
+    first, second = (montecarlo.simulate(model, samples=50, seed=3) for _ in range(2))
+    assert _parameter(first, "a") == _parameter(second, "a")
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        montecarlo.print_simulation(first)
+    assert "Acceleration (a)" in output.getvalue() and "Thrust (F)" not in output.getvalue(), output.getvalue()
+    assert "Test thrust (craft) passed in" in output.getvalue()
+
+
+def test_eval_samples():
+    directory = helpers.workspace({"craft.on": MODEL})
+    previous = console.exit_code
+    output = io.StringIO()
+    try:
+        with contextlib.redirect_stdout(output):
+            evaluate_all([directory], output="json", samples=20, seed=0)
+    finally:
+        console.exit_code = previous
+    craft = json.loads(output.getvalue())["models"][0]
+    assert craft["monte_carlo"]["samples"] == 20
+    assert _parameter(craft["monte_carlo"], "a")["mean"] is not None
+
+
+ALL_TESTS = [
+    test_annotation,
+    test_percentile,
+    test_simulate,
+    test_seed,
+    test_eval_samples,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
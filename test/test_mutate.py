@@ -0,0 +1,92 @@
+#!/usr/bin/env python3
+"""Tests for oneil.mutate, mutation testing of a model's tests.
+
+Run directly:
+
+    python test/test_mutate.py
+
+Or:
+
+    pytest test/test_mutate.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import mutate  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """Force: F = 10 :N
+Mass: m = 4 :kg
+Acceleration: a = 2*F/m :m/s^2
+Mode [low, high]: D = low
+Level: L = {1 if D == 'low'
+    {2 if D == 'high'
+Margin: M = a*2 + a :m/s^2
+
+Expected acceleration: a_gold = 5 :m/s^2
+
+test "acceleration": a == a_gold
+"""
+
+
+def test_mutations():
+    lines = CRAFT.splitlines(keepends=True)
+    found = [(line, original, replacement) for line, _, original, replacement in mutate.mutations(lines)]
+    assert (3, "/", "*") in found, found
+    assert (7, "*", "/") in found and (7, "2", "2.2") in found and (7, "+", "-") in found, found
+    assert all(line not in (1, 2, 4, 9) for line, _, _ in found), "inputs and discrete values aren't mutated"
+    assert (5, "1", "1.1") in found and (5, "==", "!=") in found, found
+    assert not any("low" in original for _, original, _ in found), "strings aren't mutated"
+
+
+def test_mutants_killed_and_survived():
+    directory = helpers.workspace({"craft.on": CRAFT})
+    with working_directory(directory):
+        mutants = mutate.mutate("craft")
+        with open("craft.on") as f:
+            assert f.read() == CRAFT, "the model file isn't modified"
+
+    results = {(m["line"], m["original"], m["replacement"]): m for m in mutants}
+    assert results[(3, "2", "2.2")]["result"] == "killed", results
+    assert results[(3, "2", "2.2")]["tests"] == ['"acceleration"']
+    assert results[(3, "/", "*")] == {"line": 3, "column": 22, "original": "/", "replacement": "*", "result": "error", "error": "UnitEvaluationError"}
+    assert results[(7, "+", "-")]["result"] == "survived", "no test checks the margin"
+
+
+ALL_TESTS = [
+    test_mutations,
+    test_mutants_killed_and_survived,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
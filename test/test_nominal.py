@@ -0,0 +1,101 @@
+#!/usr/bin/env python3
+"""Tests for nominal-only evaluation (`--nominal`).
+
+Run directly:
+
+    python test/test_nominal.py
+
+Or:
+
+    pytest test/test_nominal.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import Model, stamp  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+MODEL = """Side: x = 1|3 :m
+Count: n = 4
+Area: A = x^2 :m^2
+Bound: B = 1*n | 2*n
+Reach: R = x*n :m
+"""
+
+
+def _model(nominal):
+    directory = helpers.workspace({"square.on": MODEL})
+    previous, oneil.nominal = oneil.nominal, nominal
+    try:
+        with working_directory(directory):
+            model = Model("square.on")
+            model.build(quiet=True)
+            model.apply_overrides(["n=2|6"], quiet=True)
+            return model, stamp.stamp(model)
+    finally:
+        oneil.nominal = previous
+
+
+def _value(model, ID):
+    return (model.parameters[ID].min, model.parameters[ID].max)
+
+
+def test_ranges_are_collapsed():
+    model, _ = _model(True)
+    assert _value(model, "x") == (2, 2)
+    assert _value(model, "A") == (4, 4), _value(model, "A")
+    assert _value(model, "B") == (6, 6), _value(model, "B")
+
+
+def test_overrides_are_collapsed():
+    model, _ = _model(True)
+    assert _value(model, "n") == (4, 4)
+    assert _value(model, "R") == (8, 8)
+
+
+def test_full_evaluation_is_unchanged():
+    model, stamped = _model(False)
+    assert _value(model, "A") == (1, 9) and _value(model, "R") == (2, 18), (_value(model, "A"), _value(model, "R"))
+    assert stamped["flags"]["nominal"] is False
+    assert _model(True)[1]["flags"]["nominal"] is True
+
+
+ALL_TESTS = [
+    test_ranges_are_collapsed,
+    test_overrides_are_collapsed,
+    test_full_evaluation_is_unchanged,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,138 @@
+#!/usr/bin/env python3
+"""Tests for oneil.ordering, the evaluation order and iterated cycles.
+
+Run directly:
+
+    python test/test_ordering.py
+
+Or:
+
+    pytest test/test_ordering.py
+"""
+
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, ParameterError, ordering  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+SIZING = """\
+Payload mass: m_payload = 100 :kg
+
+@iterate(1000)
+Total mass: m_total = m_payload + m_structure :kg
+Structure mass: m_structure = 0.25*m_total :kg
+Margin: M = m_total/m_payload
+"""
+
+
+def _model(text, order="fixpoint", **settings):
+    directory = helpers.workspace({"sizing.on": text})
+    ordering.reset()
+    ordering.settings.update({"order": order}, **settings)
+    try:
+        with working_directory(directory):
+            model = Model("sizing.on")
+            model.build(quiet=True)
+        return model
+    finally:
+        ordering.reset()
+
+
+def test_converges():
+    model = _model(SIZING)
+    assert math.isclose(model.parameters["m_total"].min, 400 / 3, rel_tol=1e-8)
+    assert math.isclose(model.parameters["m_structure"].min, 100 / 3, rel_tol=1e-8)
+    assert math.isclose(model.parameters["M"].min, 4 / 3, rel_tol=1e-8)
+    assert model.parameters["m_total"].iterations > 1
+    assert model.results()["parameters"]["m_total"]["iterations"] == model.parameters["m_total"].iterations
+
+
+def test_damping():
+    undamped = _model(SIZING).parameters["m_total"].iterations
+    damped = _model(SIZING, damping=0.5).parameters["m_total"].iterations
+    assert damped > undamped
+
+
+def test_diverges():
+    try:
+        _model(SIZING.replace("0.25*m_total", "2*m_total"), max_iterations=20)
+    except ParameterError as e:
+        assert "didn't converge in 20 iterations" in e.message()
+    else:
+        raise AssertionError("expected a ParameterError")
+
+
+def test_strict():
+    try:
+        _model(SIZING, order="strict")
+    except ParameterError as e:
+        assert "Circular dependency" in e.message()
+        assert "--order fixpoint" in e.notes()[-1]
+    else:
+        raise AssertionError("expected a ParameterError")
+
+
+def test_undeclared():
+    try:
+        _model(SIZING.replace("@iterate(1000)\n", ""))
+    except ParameterError as e:
+        assert "@iterate(<initial guess>)" in e.notes()[-1]
+    else:
+        raise AssertionError("expected a ParameterError")
+
+
+def test_no_cycle():
+    model = _model("@iterate(5)\nLength: l = 2*w :m\nWidth: w = 3 :m\n")
+    assert model.parameters["l"].min == 6 and model.parameters["l"].iterations is None
+
+
+def test_settings():
+    errors = []
+    ordering.apply({"order": "loose", "damping": 0, "tolerance": 2, "max_iterations": 1.5, "speed": 1}, errors)
+    assert len(errors) == 5, errors
+    ordering.reset()
+
+
+ALL_TESTS = [
+    test_converges,
+    test_damping,
+    test_diverges,
+    test_strict,
+    test_undeclared,
+    test_no_cycle,
+    test_settings,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,139 @@
+#!/usr/bin/env python3
+"""Tests for --set: command-line overrides of the parameters of a model and its submodels.
+
+Run directly:
+
+    python test/test_overrides.py
+
+Or:
+
+    pytest test/test_overrides.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import OneilError, console  # noqa: E402
+import helpers  # noqa: E402
+
+
+NOZZLE = """\
+Throat area: A_t = 0.01 :m^2
+"""
+
+ENGINE = """\
+use nozzle as n
+Chamber pressure: p_c = 2 :MPa
+$ Thrust: F = 1.5*p_c*A_t.n :kN
+"""
+
+CRAFT = """\
+use engine as e
+Mass (0, 1000): m = 500 :kg
+$ Acceleration: a = F.e/m :m/s^2
+"""
+
+FILES = {"nozzle.on": NOZZLE, "engine.on": ENGINE, "craft.on": CRAFT}
+
+
+def _overridden(*overrides):
+    model, _ = helpers.load(FILES, "craft.on")
+    with contextlib.redirect_stdout(io.StringIO()):
+        model.apply_overrides(list(overrides), quiet=True)
+    return model
+
+
+def _error(*overrides):
+    try:
+        _overridden(*overrides)
+    except OneilError as e:
+        return e
+    assert False, f"Expected an error for {overrides}"
+
+
+def test_top_level():
+    model = _overridden("m=250kg")
+    assert model.parameters["m"].min == 250
+    assert model.parameters["a"].min == 120
+
+
+def test_submodel():
+    model = _overridden("p_c.e=4:MPa")
+    assert model.parameters["a"].min == 120
+
+
+def test_nested():
+    # A parameter of a submodel of a submodel, with the symbols from the innermost out
+    model = _overridden("A_t.n.e=0.02:m^2")
+    assert model.parameters["a"].min == 120
+
+
+def test_unknown():
+    for override, message in (
+        ("q=1", "Parameter \"q\" not found in model craft."),
+        ("q.e=1", "Parameter \"q\" not found in model engine."),
+        ("m.x=1:kg", "Submodel \"x\" not found in model craft."),
+        ("A_t.e.n=1:m^2", "Submodel \"n\" not found in model craft."),
+        ("A_t.x.e=1:m^2", "Submodel \"x\" not found in model engine."),
+    ):
+        e = _error(override)
+        assert e.kind() == "OverrideError", e
+        assert e.message() == message, e.message()
+
+
+def test_validated():
+    assert _error("m=2000kg").kind() == "LimitError"
+    assert _error("p_c.e=4:kg").kind() == "UnitEvaluationError"
+
+
+def test_cli():
+    directory = helpers.workspace(FILES)
+    output, exit_code = helpers.cli(["craft.on", "--set", "A_t.n.e=0.02m^2", "--set", "m=250kg", "a", "quit"], directory)
+    assert exit_code == console.EXIT_SUCCESS, output
+    assert "a: 240" in output, output
+
+    output, exit_code = helpers.cli(["craft.on", "--set", "m.x.e=1kg", "quit"], directory)
+    assert exit_code == console.EXIT_RESOLUTION_ERROR, output
+    assert "Submodel \"x\" not found in model engine." in output, output
+
+
+ALL_TESTS = [
+    test_top_level,
+    test_submodel,
+    test_nested,
+    test_unknown,
+    test_validated,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
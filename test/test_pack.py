@@ -0,0 +1,140 @@
+#!/usr/bin/env python3
+"""Tests for oneil.pack, the self-contained archives of a model (`oneil pack`).
+
+Run directly:
+
+    python test/test_pack.py
+
+Or:
+
+    pytest test/test_pack.py
+"""
+
+import json
+import os
+import sys
+import zipfile
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import pack, Model, PackError  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+FILES = {
+    "models/craft.on": "import helper\nuse sub as s\nX: x = f(y.s)\n",
+    "models/sub.on": "Y: y = 3\n",
+    "models/helper.py": "def f(x):\n    return 2 * x\n",
+    "designs/flight.on": "y.s = 5\n",
+}
+
+
+def _workspace():
+    return helpers.workspace(FILES)
+
+
+def _pack():
+    directory = _workspace()
+    output = os.path.join(directory, "craft.onpack")
+    with working_directory(os.path.join(directory, "models")):
+        manifest = pack.pack("craft", ["../designs/flight"], output)
+    return output, manifest
+
+
+def _rewrite(path, changes):
+    # Copy the archive with some of its entries replaced
+    with zipfile.ZipFile(path) as archive:
+        entries = {name: archive.read(name) for name in archive.namelist()}
+    entries.update(changes)
+    copy = path.replace(".onpack", "-changed.onpack")
+    with zipfile.ZipFile(copy, "w") as archive:
+        for name, data in entries.items():
+            archive.writestr(name, data)
+    return copy
+
+
+def test_round_trip():
+    output, manifest = _pack()
+    assert manifest["entry"] == "models/craft.on"
+    assert manifest["designs"] == ["designs/flight.on"]
+    assert set(manifest["files"]) == set(FILES)
+    assert manifest["files"]["models/helper.py"]["kind"] == "python"
+
+    entry, designs = pack.unpack(output)
+    for name in FILES:
+        assert os.path.exists(os.path.join(os.path.dirname(entry), "..", name))
+    with working_directory(os.path.dirname(entry)):
+        model = Model(os.path.basename(entry))
+        model.build(quiet=True)
+    assert model.parameters["x"].min == 6
+    assert pack.resolve(["a.on", output]) == ["a.on", entry]
+
+
+def test_modified_archive():
+    output, _ = _pack()
+    changed = _rewrite(output, {"models/sub.on": b"Y: y = 4\n"})
+    try:
+        pack.unpack(changed)
+        assert False, "expected a PackError"
+    except PackError as e:
+        assert "models/sub.on" in e.message()
+
+
+def test_paths_outside_the_archive():
+    output, manifest = _pack()
+    manifest["files"]["../outside.on"] = {"kind": "model", "sha256": ""}
+    changed = _rewrite(output, {pack.MANIFEST: json.dumps(manifest), "../outside.on": b"X: x = 1\n"})
+    try:
+        pack.unpack(changed)
+        assert False, "expected a PackError"
+    except PackError as e:
+        assert "Invalid path" in e.message()
+
+
+def test_missing_files():
+    directory = _workspace()
+    os.remove(os.path.join(directory, "models", "sub.on"))
+    with working_directory(os.path.join(directory, "models")):
+        try:
+            pack.pack("craft", [], "craft.onpack")
+            assert False, "expected a PackError"
+        except PackError as e:
+            assert "sub.on" in e.message()
+        assert not os.path.exists("craft.onpack")
+
+
+ALL_TESTS = [
+    test_round_trip,
+    test_modified_archive,
+    test_paths_outside_the_archive,
+    test_missing_files,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
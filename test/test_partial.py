@@ -0,0 +1,163 @@
+#!/usr/bin/env python3
+"""Tests for partial evaluation: loading and evaluating a model around the errors in it,
+and reporting them together.
+
+Run directly:
+
+    python test/test_partial.py
+
+Or:
+
+    pytest test/test_partial.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, OneilError, console  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+BROKEN = """\
+Mass: m = 3 :kg
+Thrust: F = 20 :N
+Burn time: t_b = 30 :s
+$ Impulse: J = F*t_b + m :N*s
+$ Specific impulse: I = J/m :N*s/kg
+$ Undefined: U = q*2 :kg
+$ Ratio: r = m/(m - m)
+$ Good: G = F*t_b :N*s
+test: G > 0
+test: I > 0
+"""
+
+ENGINE = """\
+Mass: m = 3 :kg
+Ratio: r = m/(m - m)
+Thrust: F = 20 :N
+"""
+
+CRAFT = """\
+use engine as e
+Twice the ratio: R = 2*r.e
+Twice the thrust: T = 2*F.e :N
+"""
+
+
+def _build(files, name, partial=True):
+    directory = helpers.workspace(files)
+    output = io.StringIO()
+    console.exit_code = console.EXIT_SUCCESS
+    try:
+        with working_directory(directory), contextlib.redirect_stdout(output):
+            model = Model(name, partial=partial)
+            model.build(quiet=True)
+        return model, output.getvalue(), console.exit_code
+    finally:
+        console.exit_code = console.EXIT_SUCCESS
+
+
+def test_partial():
+    model, _, _ = _build({"broken.on": BROKEN}, "broken.on")
+    assert [error.kind() for error in model.errors()] == ["IDError", "UnitEvaluationError", "ImportedFunctionError"], model.errors()
+    assert set(model.failed()) == {"J", "I", "U", "r"}, model.failed()
+    assert model.failed()["I"] is model.failed()["J"], "I is left out because of J's error"
+    assert model.parameters["G"].min == 600
+    assert [test.passed for test in model.tests] == [True, None]
+
+
+def test_reported_together():
+    _, output, exit_code = _build({"broken.on": BROKEN}, "broken.on")
+    for kind in ("IDError", "UnitEvaluationError", "ImportedFunctionError"):
+        assert kind in output, output
+    assert exit_code == console.EXIT_RESOLUTION_ERROR, exit_code
+
+
+def test_strict_by_default():
+    directory = helpers.workspace({"broken.on": BROKEN})
+    with working_directory(directory), contextlib.redirect_stdout(io.StringIO()):
+        try:
+            Model("broken.on")
+        except OneilError as e:
+            assert e.kind() == "IDError", e.kind()
+        else:
+            assert False, "Expected an IDError"
+
+
+def test_submodel_failure():
+    model, _, _ = _build({"engine.on": ENGINE, "craft.on": CRAFT}, "craft.on")
+    assert set(model.failed()) == {"R"}, model.failed()
+    assert model.parameters["T"].min == 40
+    assert [error.kind() for error in model.errors()] == ["ImportedFunctionError"], model.errors()
+
+
+def test_evaluated_again():
+    # Errors from loading stay, and those from evaluating are found again
+    model, _, _ = _build({"broken.on": BROKEN}, "broken.on")
+    with contextlib.redirect_stdout(io.StringIO()):
+        model._reset()
+        assert set(model.failed()) == {"J", "I", "U"}, model.failed()
+        model.build(quiet=True)
+    console.exit_code = console.EXIT_SUCCESS
+    assert len(model.errors()) == 3, model.errors()
+    assert set(model.failed()) == {"J", "I", "U", "r"}, model.failed()
+
+
+def test_summary():
+    model, _, _ = _build({"broken.on": BROKEN}, "broken.on")
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        model.summarize()
+    assert "Not calculated (see the errors above):" in output.getvalue(), output.getvalue()
+    assert "  I: UnitEvaluationError (from J)" in output.getvalue(), output.getvalue()
+
+
+def test_cli():
+    output, exit_code = helpers.cli(["--quiet", "broken.on", "G", "quit"], helpers.workspace({"broken.on": BROKEN}))
+    assert exit_code == console.EXIT_RESOLUTION_ERROR, exit_code
+    assert not console.quiet and console.exit_code == console.EXIT_SUCCESS
+    assert "600" in output, output
+
+
+ALL_TESTS = [
+    test_partial,
+    test_reported_together,
+    test_strict_by_default,
+    test_submodel_failure,
+    test_evaluated_again,
+    test_summary,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
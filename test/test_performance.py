@@ -0,0 +1,127 @@
+#!/usr/bin/env python3
+"""Tests for the results of performance parameters (`$`): their limits, margins, and tests,
+from model.performance_parameters() and oneil eval --performance.
+
+Run directly:
+
+    python test/test_performance.py
+
+Or:
+
+    pytest test/test_performance.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console  # noqa: E402
+import helpers  # noqa: E402
+
+
+THERMAL = """\
+$ Radiator area (0, 10): A = 2 :m^2
+Mode [cold, hot]: mode = hot
+$ Heater mode [off, on]: heater = off
+"""
+
+CRAFT = """\
+use thermal as th
+
+Width: w = 2|4 :m
+Height: h = 3 :m
+$ Volume (0, 100): V = w*w*h :m^3
+$ Reach: R = 2*w :m
+Maximum reach: R_max = 7 :m
+Maximum volume: V_max = 50 :m^3
+
+test "small enough": V < V_max
+test "within reach": R < R_max
+"""
+
+FILES = {"thermal.on": THERMAL, "craft.on": CRAFT}
+
+
+def _performance():
+    model, _ = helpers.load(FILES, "craft.on")
+    return {(entry["model"], entry["id"]): entry for entry in model.performance_parameters()}
+
+
+def test_listed():
+    performance = _performance()
+    assert list(performance) == [("craft", "V"), ("craft", "R"), ("th", "A"), ("th", "heater")], list(performance)
+    V = performance[("craft", "V")]
+    assert (V["name"], V["min"], V["max"], V["units"]) == ("Volume", 12, 48, "m^3"), V
+
+
+def test_margins():
+    performance = _performance()
+    V = performance[("craft", "V")]
+    assert V["limits"] == {"min": 0, "max": 100}, V["limits"]
+    assert V["margins"] == {"lower": 12, "upper": 52}, V["margins"]
+    assert V["within_limits"] is True
+    # Infinite limits (the default upper limit) have no margin
+    R = performance[("craft", "R")]
+    assert (R["limits"], R["margins"]) == ({"min": 0, "max": None}, {"lower": 4, "upper": None}), R
+
+
+def test_discrete():
+    heater = _performance()[("th", "heater")]
+    assert heater["limits"] == ["off", "on"] and heater["margins"] is None and heater["within_limits"] is True, heater
+
+
+def test_tests():
+    performance = _performance()
+    assert [test["name"] for test in performance[("craft", "V")]["tests"]] == ["small enough"]
+    assert performance[("craft", "V")]["passed"] is True
+    # A parameter fails if a test that uses it fails
+    R = performance[("craft", "R")]
+    assert [(test["name"], test["passed"]) for test in R["tests"]] == [("within reach", False)], R["tests"]
+    assert R["passed"] is False
+    assert performance[("th", "A")]["tests"] == [] and performance[("th", "A")]["passed"] is True
+
+
+def test_cli():
+    directory = helpers.workspace(FILES)
+    output, code = helpers.cli(["eval", "--performance", "craft.on"], directory)
+    assert code == console.EXIT_TEST_FAILURE, output
+    for row in ("craft.on | Volume (V) | 12|48 m^3 | [0, 100] | 12 m^3 | PASS", "craft.on (th) | Radiator area (A) | 2 m^2 | [0, 10] | 2 m^2 | PASS", "craft.on | Reach (R) | 4|8 m | [0, inf] | 4 m | FAIL"):
+        assert row in output.replace("\x1b[92m", "").replace("\x1b[91m", "").replace("\x1b[0m", ""), output
+
+
+ALL_TESTS = [
+    test_listed,
+    test_margins,
+    test_discrete,
+    test_tests,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
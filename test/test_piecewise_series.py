@@ -0,0 +1,113 @@
+#!/usr/bin/env python3
+"""Tests for Model.piecewise_series, sampling piecewise parameters for plotting.
+
+Run directly:
+
+    python test/test_piecewise_series.py
+
+Or:
+
+    pytest test/test_piecewise_series.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import IDError  # noqa: E402
+import helpers  # noqa: E402
+
+
+MODEL = """Height: h = 5 :km
+Switch height: h_s = 10 :km
+Low height: h_l = 3 :km
+Altitude gain: g = {2*h if h < h_s :km
+    {h + h_s if h >= h_s
+Step: s = {1*h if h < h_l :km
+    {h + h_l if h >= h_l
+Mode [low, high]: D = low
+Level: L = {1 if D == 'low'
+    {2 if D == 'high'
+"""
+
+
+def _model():
+    return helpers.load({"craft.on": MODEL}, "craft.on")[0]
+
+
+def test_continuous_switch():
+    series = _model().piecewise_series("g", points=20)
+    assert series["input"] == "h" and series["units"] == {"x": "km", "y": "km"}, series
+    assert [branch["condition"] for branch in series["branches"]] == ["h<h_s", "h>=h_s"], series["branches"]
+    assert len(series["samples"]) == 20
+    assert {sample["branch"] for sample in series["samples"]} == {0, 1}
+    assert len(series["switches"]) == 1, series["switches"]
+    switch = series["switches"][0]
+    assert (switch["from"], switch["to"]) == (0, 1)
+    assert abs(switch["x"] - 10) < 1e-6, switch
+    assert abs(switch["jump"]) < 1e-6, switch
+
+
+def test_discontinuous_switch():
+    series = _model().piecewise_series("s", domain=(1000, 5000), points=5)
+    assert [sample["x"] for sample in series["samples"]] == [1, 2, 3, 4, 5], series["samples"]
+    assert [sample["branch"] for sample in series["samples"]] == [0, 0, 1, 1, 1]
+    assert [sample["y"] for sample in series["samples"]][:2] == [1, 2]
+    switch = series["switches"][0]
+    assert abs(switch["x"] - 3) < 1e-6 and abs(switch["jump"] - 3) < 1e-6, switch
+
+
+def test_discrete_input():
+    series = _model().piecewise_series("L")
+    assert series["input"] == "D"
+    assert [(sample["x"], sample["y"], sample["branch"]) for sample in series["samples"]] == [("low", 1, 0), ("high", 2, 1)], series["samples"]
+    assert series["switches"] == [{"x": "high", "from": 0, "to": 1, "jump": None}]
+
+
+def test_restores_the_input():
+    model = _model()
+    model.piecewise_series("g")
+    assert model.parameters["h"].min == 5000 and model.parameters["g"].min == 10000, (model.parameters["h"].min, model.parameters["g"].min)
+    try:
+        model.piecewise_series("h")
+    except IDError as e:
+        assert "isn't a piecewise parameter" in str(e.message())
+    else:
+        raise AssertionError("expected an IDError for a parameter that isn't piecewise")
+
+
+ALL_TESTS = [
+    test_continuous_switch,
+    test_discontinuous_switch,
+    test_discrete_input,
+    test_restores_the_input,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
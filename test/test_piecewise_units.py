@@ -0,0 +1,110 @@
+#!/usr/bin/env python3
+"""Tests for the unit check of every branch of piecewise equations.
+
+Run directly:
+
+    python test/test_piecewise_units.py
+
+Or:
+
+    pytest test/test_piecewise_units.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, PiecewiseUnitError, static_units  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+MODEL = """Mass: m = 3 :kg
+Length: l = 2 :m
+Mode [low, high]: D = low
+Weight: w = {m*9.8 if D == 'low' :kg
+    {sqrt(l*l)*m/l if D == 'high'
+    {l*1 if l > 5*l
+"""
+
+
+def _load(text):
+    directory = helpers.workspace({"craft.on": text})
+    with working_directory(directory):
+        return Model("craft.on")
+
+
+def test_consistent_branches():
+    model = _load(MODEL.replace("    {l*1 if l > 5*l\n", ""))
+    model.build(quiet=True)
+    assert model.parameters["w"].min == 3 * 9.8
+
+
+def test_untaken_branch():
+    # The third branch never applies with these values, but its units are still checked.
+    try:
+        _load(MODEL)
+    except PiecewiseUnitError as e:
+        assert [related["line"] for related in e.related()] == [6], e.related()
+        assert "{l*1 if l > 5*l" in e.notes()[0], e.notes()
+        return
+    raise AssertionError("Expected a PiecewiseUnitError")
+
+
+def test_condition_units():
+    try:
+        _load(MODEL.replace("{l*1 if l > 5*l", "{m*1 if l > m"))
+    except PiecewiseUnitError as e:
+        assert [related["line"] for related in e.related()] == [6], e.related()
+        assert "Cannot compare" in e.related()[0]["message"], e.related()
+        return
+    raise AssertionError("Expected a PiecewiseUnitError")
+
+
+def test_static_units():
+    declared = {"m": {"kg": 1}, "l": {"m": 1}, "v.e": {"m": 1, "s": -1}}.get
+    assert static_units("par_sqrt(l*l)*m/l", declared) == {"kg": 1}
+    assert static_units("l**2|minus|l*l", declared) == {"m": 2}
+    assert static_units("v.e*m", declared) == {"m": 1, "s": -1, "kg": 1}
+    assert static_units("par_min(l,2)+0", declared) == {"m": 1}
+    assert static_units("par_sin(pi)", declared) == {}
+    # Unknown parameters and Python functions aren't checked.
+    assert static_units("x*m", declared) is None
+    assert static_units("f(m)", declared) is None
+
+
+ALL_TESTS = [
+    test_consistent_branches,
+    test_untaken_branch,
+    test_condition_units,
+    test_static_units,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
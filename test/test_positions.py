@@ -0,0 +1,114 @@
+#!/usr/bin/env python3
+"""Tests for oneil.positions, which converts columns between the encodings tools count
+them in.
+
+Run directly:
+
+    python test/test_positions.py
+
+Or:
+
+    pytest test/test_positions.py
+"""
+
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, positions, tune  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+# "Δ" is 2 bytes in UTF-8 and one UTF-16 unit; "𝛼" is 4 bytes and two UTF-16 units.
+LINE = "Δv 𝛼: dv = 3"
+
+
+def test_columns():
+    i = LINE.index("dv")
+    assert positions.column_of(LINE, i) == 6
+    assert positions.column_of(LINE, i, "utf-16") == 7
+    assert positions.column_of(LINE, i, "utf-8") == 10
+    for encoding in positions.ENCODINGS:
+        assert positions.index_of(LINE, positions.column_of(LINE, i, encoding), encoding) == i, encoding
+    assert positions.convert(LINE, 10, "utf-8", "utf-16") == 7
+
+
+def test_inside_a_character():
+    # The second UTF-16 unit of "𝛼" and the second byte of "Δ" are those characters.
+    assert positions.index_of(LINE, 4, "utf-16") == 3
+    assert positions.index_of(LINE, 1, "utf-8") == 0
+    assert positions.index_of(LINE, 100, "utf-8") == len(LINE)
+
+
+def test_offsets():
+    text = "Mass: m = 2\r\n" + LINE + "\n"
+    i = text.index("dv")
+    assert positions.line_and_column(text, i, "utf-16") == (2, 7)
+    assert positions.offset(text, 2, 7, "utf-16") == i
+    try:
+        positions.column_of(LINE, 1, "latin-1")
+    except ValueError as e:
+        assert "utf-16" in str(e)
+    else:
+        raise AssertionError("expected an unknown encoding to fail")
+
+
+def _directory():
+    return helpers.workspace({"probe.on": "Mass: m = 2 :kg\n$ " + LINE + "\n"})
+
+
+def test_source():
+    with working_directory(_directory()):
+        model = Model("probe.on")
+        assert model.parameters["dv"].source("probe.on") == {"file": "probe.on", "line": 2, "start_column": 9, "end_column": 11}, "the `$` prefix counts"
+        assert model.parameters["dv"].source("probe.on", "utf-8")["start_column"] == 13
+
+
+def test_tune_encoding():
+    with working_directory(_directory()):
+        session = tune.Session("probe")
+        request = {"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"positionEncodings": ["utf-7", "utf-16"]}}
+        [response] = session.handle(json.dumps(request))
+        assert response["result"]["positionEncoding"] == "utf-16"
+        [response] = session.handle(json.dumps({"jsonrpc": "2.0", "id": 2, "method": "oneil/modelTree"}))
+        assert response["result"]["parameters"][1]["source"]["start_column"] == 10
+
+
+ALL_TESTS = [
+    test_columns,
+    test_inside_a_character,
+    test_offsets,
+    test_source,
+    test_tune_encoding,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
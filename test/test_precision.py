@@ -0,0 +1,108 @@
+#!/usr/bin/env python3
+"""Tests for `@precision` budgets and Model.precision_report.
+
+Run directly:
+
+    python test/test_precision.py
+
+Or:
+
+    pytest test/test_precision.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import parse_annotation, relative_uncertainty  # noqa: E402
+import helpers  # noqa: E402
+
+
+WHEEL = """@precision(1%)
+Radius: r = 0.29|0.31 :m
+"""
+
+CRAFT = """use wheel as w
+
+Mass: m = 3.9|4.1 :kg
+Force: F = 9|11 :N
+Gain: k = 2
+@precision(1%)
+Acceleration: a = k*F/m :m/s^2
+@precision(0.4)
+Thrust: T = 2*F :N
+"""
+
+
+def _model():
+    return helpers.load({"wheel.on": WHEEL, "craft.on": CRAFT}, "craft.on")[0]
+
+
+def test_parse_budget():
+    assert parse_annotation("@precision(1%)") == ("precision", (0.01,))
+    assert parse_annotation("@precision(2.5 %)") == ("precision", (0.025,))
+    assert parse_annotation("@precision(0.02)") == ("precision", (0.02,))
+    for line in ("@precision", "@precision(0)", "@precision(a)", "@precision(1%, 2%)"):
+        try:
+            parse_annotation(line)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"expected a ValueError for {line}")
+
+
+def test_report_names_dominant_inputs():
+    model = _model()
+    report = {(entry["id"], entry["model"]): entry for entry in model.over_budget}
+    assert set(report) == {("a", "craft"), ("r", "wheel")}, report.keys()
+
+    a = report[("a", "craft")]
+    assert abs(a["uncertainty"] - 0.125) < 1e-3 and a["budget"] == 0.01, a
+    assert [c["input"] for c in a["contributors"]] == ["F", "m"], a["contributors"]
+    assert abs(a["contributors"][0]["share"] - 0.8) < 0.01, a["contributors"]
+    assert "Mostly from F (80%), m (20%)" in a["warning"].notes_, a["warning"].notes_
+    assert report[("r", "wheel")]["contributors"] == []
+
+
+def test_report_restores_values():
+    model = _model()
+    model.precision_report()
+    assert (model.parameters["F"].min, model.parameters["F"].max) == (9, 11)
+    assert abs(relative_uncertainty(model.parameters["a"]) - 0.125) < 1e-3
+    assert model.parameters["a"].line_no["model line"] == 7, model.parameters["a"].line_no
+
+
+ALL_TESTS = [
+    test_parse_budget,
+    test_report_names_dominant_inputs,
+    test_report_restores_values,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
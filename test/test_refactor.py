@@ -0,0 +1,314 @@
+#!/usr/bin/env python3
+"""Tests for oneil.refactor, which rewrites model files in place.
+
+Run directly:
+
+    python test/test_refactor.py
+
+Or:
+
+    pytest test/test_refactor.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import refactor, OneilError, RefactorError  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """use engine as e
+
+Mass: m = 10 :kg
+
+section Thermal
+# Thermal control
+Heat load: Q = 20 :W
+    Fraction of power dissipated as heat.
+Radiator flux: q_r = 200 :W/m^2
+Radiator area: A = Q/q_r :m^2
+Drag: D = F.e*0.5 :N
+
+section Summary
+Areal density: rho = m/A :kg/m^2
+"""
+
+ENGINE = "Thrust: F = 5 :N\n"
+
+MISSION = """use craft as c
+
+Total area: A_t = A.c*2 :m^2
+Craft mass: M = m.c*2 :kg
+"""
+
+DESIGN = "q_r = 300 :W/m^2\nm = 12 :kg\n"
+
+
+CRAFT_WITH_THERMAL = """use engine as e
+use thermal(A_lim=A_max) as th
+
+Mass: m = 10 :kg
+Max area: A_max = 4 :m^2
+
+section Summary
+Areal density: rho = m/A.th :kg/m^2
+"""
+
+THERMAL = """use engine as eng
+
+    Thermal subsystem.
+
+section Thermal
+Heat load: Q = 20 :W
+Radiator flux: q_r = 200 :W/m^2
+Radiator area: A = Q/q_r :m^2
+Mass: m = 2 :kg
+Drag: D = F.eng*0.5 :N
+
+test {A_lim}: A < A_lim
+"""
+
+MISSION_WITH_THERMAL = """use craft as c
+from craft use thermal as t
+
+Total area: A_t = A.t*2 :m^2
+Thermal mass: M_t = m.t*1 :kg
+"""
+
+
+def _workspace(files):
+    directory = helpers.workspace(files)
+    return directory
+
+
+def _read(directory, name):
+    with open(os.path.join(directory, name)) as f:
+        return f.read()
+
+
+# ---------------------------------------------------------------------------
+# Reference rewriting
+# ---------------------------------------------------------------------------
+
+def test_rewrite_expression_skips_accessors_strings_and_functions():
+    renames = {"A": "A.t", "mode": "mode.t"}
+    assert refactor.rewrite_expression(" A*A.s + AB + f(A)", renames) == " A.t*A.s + AB + f(A.t)"
+    assert refactor.rewrite_expression(" 1 if mode == 'mode'", renames) == " 1 if mode.t == 'mode'"
+    assert refactor.rewrite_expression(" A(x)", renames) == " A(x)"
+
+
+def test_rewrite_block_leaves_names_and_units():
+    block = ["Area A: A = A*2 :A\n", "    A note about A.\n"]
+    assert refactor.rewrite_block(block, {"A": "A.t"}) == ["Area A: A = A.t*2 :A\n", "    A note about A.\n"]
+
+
+def test_rewrite_block_renames_design_ids():
+    assert refactor.rewrite_block(["q_r = q_r*2 :W\n"], {"q_r": "q_r.t"}) == ["q_r.t = q_r.t*2 :W\n"]
+
+
+def test_rewrite_block_renames_tests_in_notes():
+    block = ["Area: A = A*2 :m^2\n", "    For A:\n", "    ```oneil-test\n", "    test \"A is big\": A > A*1\n", "    ```\n", "    A again.\n"]
+    rewritten = refactor.rewrite_block(block, {"A": "A.t"})
+    assert rewritten[3] == "    test \"A is big\": A.t > A.t*1\n", rewritten
+    assert rewritten[1] == "    For A:\n" and rewritten[5] == "    A again.\n", rewritten
+
+
+def test_aliases_are_rewritten_and_renamed():
+    assert refactor.kind("Old mass: m_old := m\n") == "alias"
+    assert refactor.block_id(["m_old := m\n"]) == "m_old"
+    assert refactor.rewrite_block(["m_old := m\n"], {"m": "m.t"}) == ["m_old := m.t\n"]
+    assert refactor.rename_definition(["Old mass: m_old := m\n"], {"m_old": "m_old_t"}) == ["Old mass: m_old_t := m\n"]
+
+
+# ---------------------------------------------------------------------------
+# extract-section
+# ---------------------------------------------------------------------------
+
+def test_extract_section():
+    directory = _workspace({"craft.on": CRAFT, "engine.on": ENGINE, "mission.on": MISSION, "hot.on": DESIGN})
+    refactor.extract_section(os.path.join(directory, "craft.on"), "Thermal", "thermal.on")
+
+    thermal = _read(directory, "thermal.on")
+    assert thermal.startswith("use engine as e\n\nsection Thermal\n# Thermal control\n"), thermal
+    assert "    Fraction of power dissipated as heat.\n" in thermal
+    assert "Drag: D = F.e*0.5 :N" in thermal
+
+    craft = _read(directory, "craft.on")
+    assert "use thermal as thermal\n" in craft
+    assert "Areal density: rho = m/A.thermal :kg/m^2" in craft
+    assert "section Thermal" not in craft
+
+    mission = _read(directory, "mission.on")
+    assert "from craft use thermal as thermal\n" in mission
+    assert "A_t = A.thermal*2" in mission
+    assert "M = m.c*2" in mission
+
+    assert _read(directory, "hot.on") == "q_r.thermal = 300 :W/m^2\nm = 12 :kg\n"
+
+
+def test_extract_section_with_outside_dependency():
+    directory = _workspace({"craft.on": CRAFT.replace("Q = 20 :W", "Q = m*1 :W"), "engine.on": ENGINE})
+    try:
+        refactor.extract_section(os.path.join(directory, "craft.on"), "Thermal", "thermal.on")
+    except RefactorError as e:
+        assert "m is used by Q" in e.notes()
+    else:
+        raise AssertionError("expected a RefactorError")
+    assert not os.path.exists(os.path.join(directory, "thermal.on"))
+
+
+def test_extract_included_section():
+    # The section's parameters are in the model, but its header is in a fragment.
+    fragment = "section Thermal\nHeat load: Q = 20 :W\n"
+    craft = "include \"thermal.oni\"\n\nMass: m = 10 :kg\n"
+    directory = _workspace({"craft.on": craft, "thermal.oni": fragment})
+    try:
+        refactor.extract_section(os.path.join(directory, "craft.on"), "Thermal", "thermal.on")
+    except RefactorError as e:
+        assert "may come from an included file" in e.message(), e.message()
+    else:
+        raise AssertionError("expected a RefactorError")
+    assert _read(directory, "craft.on") == craft
+
+
+# ---------------------------------------------------------------------------
+# inline
+# ---------------------------------------------------------------------------
+
+def test_inline_submodel():
+    directory = _workspace({
+        "craft.on": CRAFT_WITH_THERMAL,
+        "thermal.on": THERMAL,
+        "engine.on": ENGINE,
+        "mission.on": MISSION_WITH_THERMAL,
+        "hot.on": "q_r.th = 300 :W/m^2\n",
+    })
+    refactor.inline_submodel(os.path.join(directory, "craft.on"), "th")
+
+    craft = _read(directory, "craft.on")
+    assert "use thermal" not in craft
+    assert "use engine as eng" not in craft
+    assert "Areal density: rho = m/A :kg/m^2" in craft
+    assert "section Thermal\n" in craft
+    assert "# Thermal subsystem.\n" in craft
+    assert "Mass: m_th = 2 :kg" in craft
+    assert "Drag: D = F.e*0.5 :N" in craft
+    assert "test : A < A_max" in craft
+
+    mission = _read(directory, "mission.on")
+    assert "from craft use thermal" not in mission
+    assert "A_t = A.c*2" in mission
+    assert "M_t = m_th.c*1" in mission
+
+    assert _read(directory, "hot.on") == "q_r = 300 :W/m^2\n"
+
+    # The submodel is left in place for other models that use it.
+    assert _read(directory, "thermal.on") == THERMAL
+
+
+def test_inline_missing_submodel():
+    directory = _workspace({"craft.on": CRAFT, "engine.on": ENGINE})
+    try:
+        refactor.inline_submodel(os.path.join(directory, "craft.on"), "th")
+    except RefactorError as e:
+        assert "no submodel" in e.message()
+    else:
+        raise AssertionError("expected a RefactorError")
+
+
+def test_refactoring_reverts_on_failure():
+    directory = _workspace({"craft.on": CRAFT, "engine.on": ENGINE})
+    refactoring = refactor.Refactoring(directory)
+    refactoring.write("thermal.on", ["Heat load: Q = 20 :W\n"], "created")
+    refactoring.write("craft.on", ["Broken line\n"], "broken")
+    try:
+        refactoring.verify(["thermal.on", "craft.on"])
+    except OneilError as e:
+        assert "all of the changes were reverted" in e.notes()[-1]
+    else:
+        raise AssertionError("expected the broken model to fail to load")
+    assert _read(directory, "craft.on") == CRAFT
+    assert not os.path.exists(os.path.join(directory, "thermal.on"))
+
+
+def test_refactoring_reverts_changed_results():
+    directory = _workspace({"craft.on": CRAFT, "engine.on": ENGINE, "mission.on": MISSION})
+    refactoring = refactor.Refactoring(directory)
+    refactoring.write("thermal.on", ["Heat load: Q = 20 :W\n"], "created")
+    refactoring.write("craft.on", [line.replace("m = 10", "m = 11") for line in CRAFT.splitlines(True)], "changed the mass")
+    refactoring.write("mission.on", MISSION.splitlines(True), "unchanged")
+    try:
+        refactoring.verify(["thermal.on", "craft.on", "mission.on"])
+    except RefactorError as e:
+        assert "changed the results of craft.on" in e.message(), e.message()
+        assert e.notes() == ["m was 10.0 kg and is now 11.0 kg", "rho was 100.0 kg/m^2 and is now 110.0 kg/m^2"], e.notes()
+    else:
+        raise AssertionError("expected the changed results to be found")
+    assert _read(directory, "craft.on") == CRAFT
+    assert not os.path.exists(os.path.join(directory, "thermal.on"))
+
+
+UNIT_NAMES = """Mass: m = 10 :kilogram
+    Dry mass, in kilograms.
+Period(0, 100): T = 90 :sec
+Speed: v = 2*pi*6700000/T :meters/second
+Rate: r = {1/T if m > 5 :Hertz
+          {2/T if m <= 5
+"""
+
+UNIT_NAMES_DESIGN = "T = 95 :seconds\n"
+
+
+def test_canonical_units():
+    directory = _workspace({"craft.on": UNIT_NAMES, "slow.on": UNIT_NAMES_DESIGN, "engine.on": ENGINE})
+    refactoring = refactor.canonical_units([directory])
+    assert _read(directory, "craft.on") == UNIT_NAMES.replace(":kilogram", ":kg").replace(":sec", ":s").replace(":meters/second", ":m/s").replace(":Hertz", ":Hz")
+    assert _read(directory, "slow.on") == "T = 95 :s\n"
+    assert len(refactoring.changes) == 2, "models that already use symbols aren't touched"
+
+
+ALL_TESTS = [
+    test_rewrite_expression_skips_accessors_strings_and_functions,
+    test_rewrite_block_leaves_names_and_units,
+    test_rewrite_block_renames_design_ids,
+    test_rewrite_block_renames_tests_in_notes,
+    test_aliases_are_rewritten_and_renamed,
+    test_extract_section,
+    test_extract_section_with_outside_dependency,
+    test_extract_included_section,
+    test_inline_submodel,
+    test_inline_missing_submodel,
+    test_refactoring_reverts_on_failure,
+    test_refactoring_reverts_changed_results,
+    test_canonical_units,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,180 @@
+#!/usr/bin/env python3
+"""Tests for oneil.render: snippets that underline and label each place an error involves.
+
+Run directly:
+
+    python test/test_render.py
+
+Or:
+
+    pytest test/test_render.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import re
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import Model, check, console, render  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """\
+Mass: m = 3 :kg
+Thrust: F = 20 :N
+Burn time: t_b = 30 :s
+Impulse: J = F*t_b + m :N*s
+Ratio: r = sqrt(m)^2 -- (t_b) :kg
+"""
+
+TANK = """\
+Tank mass: m_t = 2 :kg
+"""
+
+CRAFT = """\
+use tank as t
+Burn time: t_b = 30 :s
+Total: T = m_t.t + t_b :kg
+"""
+
+
+def _plain(text):
+    return re.sub(r"\x1b\[[0-9;]*m", "", text)
+
+
+def _errors(files, name):
+    directory = helpers.workspace(files)
+    with working_directory(directory), contextlib.redirect_stdout(io.StringIO()):
+        model = Model(name)
+        errors = check.unit_errors(model)
+        return errors, [error.labels() for error in errors], directory
+
+
+def _spans(labels):
+    return [(label["line"], label["start_column"], label["end_column"], label["message"], label["primary"]) for label in labels]
+
+
+def test_written_pattern():
+    pattern = oneil._written_pattern("par_sqrt(m)**2-par_log(x)")
+    assert re.fullmatch(pattern, "sqrt(m)^2 -- ln(x)")
+    assert re.fullmatch(pattern, "sqrt( m ) ** 2 - log(x)")
+    assert not re.fullmatch(pattern, "sqrt(n)^2 - ln(x)")
+
+
+def test_sum_labels():
+    _, labels, _ = _errors({"engine.on": ENGINE}, "engine.on")
+    assert _spans(labels[0]) == [
+        (1, 14, 16, "m is declared in kg", False),
+        (4, 14, 19, "in (kg m)/s", False),
+        (4, 20, 21, "added here", True),
+        (4, 22, 23, "in kg", False),
+    ], labels[0]
+
+
+def test_operator_without_brackets():
+    # The operands are found as written, with renamed functions, ^, and --, and the
+    # operator's span leaves out the brackets around an operand
+    _, labels, _ = _errors({"engine.on": ENGINE}, "engine.on")
+    line = "Ratio: r = sqrt(m)^2 -- (t_b) :kg"
+    primary = [label for label in labels[1] if label["primary"]][0]
+    assert line[primary["start_column"] - 1:primary["end_column"] - 1] == "--", primary
+    operands = [line[label["start_column"] - 1:label["end_column"] - 1] for label in labels[1] if label["line"] == 5 and not label["primary"]]
+    assert operands == ["sqrt(m)^2", "t_b"], operands
+
+
+def test_submodel_declaration():
+    _, labels, _ = _errors({"tank.on": TANK, "craft.on": CRAFT}, "craft.on")
+    files = [label["file"] for label in labels[0]]
+    assert files[0] == "craft.on" and "tank.on" in files, labels[0]
+    declared = [label for label in labels[0] if label["file"] == "tank.on"][0]
+    assert (declared["line"], declared["message"]) == (1, "m_t.t is declared in kg"), declared
+
+
+def test_snippet():
+    errors, labels, directory = _errors({"engine.on": ENGINE}, "engine.on")
+    with working_directory(directory):
+        lines = [_plain(line) for line in render.snippet(labels[0])]
+    assert lines == [
+        " --> engine.on",
+        "  |",
+        "1 | Mass: m = 3 :kg",
+        "  |              -- m is declared in kg",
+        "4 | Impulse: J = F*t_b + m :N*s",
+        "  |              ----- in (kg m)/s",
+        "  |                    ^ added here",
+        "  |                      - in kg",
+    ], lines
+
+
+def test_print_error():
+    errors, _, directory = _errors({"engine.on": ENGINE}, "engine.on")
+    output = io.StringIO()
+    try:
+        with working_directory(directory), contextlib.redirect_stdout(output):
+            console.print_error(errors[0])
+    finally:
+        console.exit_code = console.EXIT_SUCCESS
+    lines = _plain(output.getvalue()).splitlines()
+    assert lines[0].endswith("Cannot add (kg m)/s and kg."), lines
+    assert lines[1] == " --> engine.on", lines
+
+
+def test_json_labels():
+    errors, _, directory = _errors({"engine.on": ENGINE}, "engine.on")
+    with working_directory(directory):
+        output = json.loads(json.dumps(console.error_json(errors[0])))
+    assert [label["message"] for label in output["labels"]] == ["m is declared in kg", "in (kg m)/s", "added here", "in kg"], output
+
+
+def test_no_labels():
+    # Errors without an operation (like those from evaluation) print as before
+    error = oneil.UnitEvaluationError("Cannot add kg and s.", [])
+    assert error.labels() == []
+    assert "labels" not in console.error_json(error)
+    assert render.snippet([]) == []
+
+
+ALL_TESTS = [
+    test_written_pattern,
+    test_sum_labels,
+    test_operator_without_brackets,
+    test_submodel_declaration,
+    test_snippet,
+    test_print_error,
+    test_json_labels,
+    test_no_labels,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,128 @@
+#!/usr/bin/env python3
+"""Tests for oneil.sandbox, the security review mode for untrusted models.
+
+Run directly:
+
+    python test/test_sandbox.py
+
+Or:
+
+    pytest test/test_sandbox.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import ImportedFunctionError, Model, ModelLoadingError, sandbox  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+FUNCS = """import pathlib
+pathlib.Path("ran.txt").write_text("imported")
+G = 9.8
+
+def double(x):
+    return 2 * x
+"""
+
+CRAFT = """import sandbox_funcs
+
+Input: x = 10
+Doubled: y = double(x) ? x * 2
+Tripled: t = double(x)
+Plain: z = 3*x
+"""
+
+
+def _directory():
+    directory = helpers.workspace({"sandbox_funcs.py": FUNCS, "craft.on": CRAFT})
+    return directory
+
+
+def _build(name):
+    model = Model(name)
+    model.build(quiet=True)
+    return model
+
+
+def test_no_python():
+    directory = _directory()
+    sandbox.no_python = True
+    try:
+        with working_directory(directory):
+            model = Model("craft.on")
+            assert model.parameters["t"].callable, "the model parses with a stand-in for double()"
+            try:
+                model.build(quiet=True)
+            except ImportedFunctionError as e:
+                assert e.parameter.id == "t", e.parameter.id
+                assert "double() would run Python from sandbox_funcs.py" in e.message(), e.message()
+            else:
+                raise AssertionError("expected an ImportedFunctionError")
+            assert model.parameters["y"].min == 20, "parameters with a fallback use it"
+            assert not os.path.exists("ran.txt"), "the module wasn't imported"
+    finally:
+        sandbox.no_python = False
+
+
+def test_no_fs_outside_root():
+    outside = _directory()
+    with open(os.path.join(outside, "secret.on"), "w") as f:
+        f.write("Secret: s = 1\n")
+    workspace = helpers.workspace({"reach.on": f"include \"{os.path.join(outside, 'secret.on')}\"\n"})
+    os.symlink(os.path.join(outside, "secret.on"), os.path.join(workspace, "linked.on"))
+    with open(os.path.join(workspace, "craft.on"), "w") as f:
+        f.write("use linked as l\n\nLocal: q = 2\n")
+
+    sandbox.no_fs_outside_root = True
+    try:
+        with working_directory(workspace):
+            for name, line in (("reach.on", 1), ("craft.on", 1)):
+                try:
+                    _build(name)
+                except ModelLoadingError as e:
+                    assert e.line_no == line and "outside the workspace root" in e.message(), e.message()
+                else:
+                    raise AssertionError(f"{name} reached outside the workspace root")
+    finally:
+        sandbox.no_fs_outside_root = False
+
+    with working_directory(workspace):
+        assert _build("reach.on").parameters["s"].min == 1, "without the flag, the include works"
+
+
+ALL_TESTS = [
+    test_no_python,
+    test_no_fs_outside_root,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,120 @@
+#!/usr/bin/env python3
+"""Tests for oneil.scaffold, making new models from templates (`oneil new`).
+
+Run directly:
+
+    python test/test_scaffold.py
+
+Or:
+
+    pytest test/test_scaffold.py
+"""
+
+import contextlib
+import datetime
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import ConfigError, Model, ScaffoldError, read_manifest, scaffold  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+def _expect(error, action):
+    try:
+        action()
+    except error as e:
+        return e
+    raise AssertionError(f"Expected a {error.__name__}")
+
+
+def test_render():
+    text = scaffold.render("{{title}} ({{ name }}) from {{template}} on {{date}}", "component", "thermal_radiator", datetime.date(2026, 10, 15))
+    assert text == "Thermal radiator (thermal_radiator) from component on 2026-10-15", text
+    error = _expect(ScaffoldError, lambda: scaffold.render("{{author}}", "component", "x"))
+    assert "{{author}}" in error.message()
+
+
+def test_builtin_templates():
+    with working_directory(helpers.workspace()):
+        for template in ("component", "system"):
+            path = scaffold.new(template, f"my_{template}")
+            assert path == f"my_{template}.on"
+            model = Model(path)
+            model.build(quiet=True)
+            assert model.annotations["tags"] == (template,), model.annotations
+            assert "My " + template in model.note, model.note
+            assert model.test() == (0, 1)
+        assert scaffold.new("design", "heavy", "heavy.on") == "heavy.on"
+        model = Model("my_component.on")
+        model.overwrite(["heavy.on"], quiet=True)
+        assert model.parameters["m"].min == 1 and model.design_files
+
+        _expect(ScaffoldError, lambda: scaffold.new("component", "my_component"))
+        _expect(ScaffoldError, lambda: scaffold.new("component", "thermal-radiator"))
+        _expect(ScaffoldError, lambda: scaffold.new("widget", "thermal_radiator"))
+
+
+def test_manifest_templates():
+    directory = helpers.workspace({
+        os.path.join("templates", "radio.on"): "    {{title}} radio.\n\nFrequency: f = 400 :MHz\n",
+        "targets.txt": "template: radio templates/radio.on\ntemplate: component templates/radio.on\ncraft.on\n",
+    })
+    manifest = os.path.join(directory, "targets.txt")
+    assert read_manifest(manifest) == [os.path.join(directory, "craft.on")]
+
+    with working_directory(directory):
+        assert scaffold.new("radio", "uhf", manifests=[manifest]) == "uhf.on"
+        with open("uhf.on") as f:
+            assert f.read() == "    Uhf radio.\n\nFrequency: f = 400 :MHz\n"
+        assert scaffold.templates([manifest])["component"][0] == "From templates/radio.on."
+
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        scaffold.print_templates([manifest])
+    assert [line.split()[0] for line in output.getvalue().splitlines()] == ["component", "design", "radio", "system"]
+
+    with open(manifest, "a") as f:
+        f.write("template: broken\n")
+    _expect(ConfigError, lambda: scaffold.templates([manifest]))
+    with open(manifest, "w") as f:
+        f.write("template: gone templates/gone.on\n")
+    _expect(ScaffoldError, lambda: scaffold.templates([manifest]))
+
+
+ALL_TESTS = [
+    test_render,
+    test_builtin_templates,
+    test_manifest_templates,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,143 @@
+#!/usr/bin/env python3
+"""Tests for oneil.search: hits in parameter IDs, labels, and notes, test names, and model
+notes, the submodel references of parameters found by ID, and `oneil search`.
+
+Run directly:
+
+    python test/test_search.py
+
+Or:
+
+    pytest test/test_search.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, search  # noqa: E402
+import helpers  # noqa: E402
+
+
+POWER = """\
+    Uses the solar flux at 1 AU.
+
+section Input
+
+Solar flux: S = 1361 :W/m^2
+    Mean solar flux at Earth's distance.
+Panel area: A = 2 :m^2
+Minimum flux: S_min = 1000 :W/m^2
+
+test "flux above minimum": S > S_min
+"""
+
+CRAFT = """\
+use power as pw
+
+Efficiency: eta = 0.3
+$ Power: P = S.pw*A.pw*eta :W
+Minimum power: P_min = 100 :W
+
+test: S.pw*A.pw*eta > P_min
+"""
+
+FILES = {"power.on": POWER, "craft.on": CRAFT}
+
+
+def _search(query, files=FILES):
+    directory = helpers.workspace(files)
+    with helpers.isolated():
+        hits, errors = search.search(query, [directory])
+    return [(os.path.basename(hit["file"]), hit["line"], hit["section"], hit["kind"], hit["text"]) for hit in hits], errors
+
+
+def test_labels_and_notes():
+    hits, errors = _search("SOLAR FLUX")
+    assert errors == []
+    assert hits == [
+        ("power.on", 1, None, "model note", "Uses the solar flux at 1 AU."),
+        ("power.on", 5, "Input", "label", "Solar flux (S)"),
+        ("power.on", 6, "Input", "note", "S: Mean solar flux at Earth's distance."),
+    ], hits
+
+
+def test_test_names():
+    hits, _ = _search("above minimum")
+    assert hits == [("power.on", 10, "Input", "test", "test \"flux above minimum\"")], hits
+
+
+def test_references():
+    hits, _ = _search("S_min")
+    assert hits == [("power.on", 8, "Input", "id", "S_min (Minimum flux)")], hits
+    # Parameters found by ID list their uses in other models through a submodel
+    hits, _ = _search("A")
+    references = [hit for hit in hits if hit[3] == "reference"]
+    assert references == [
+        ("craft.on", 4, None, "reference", "P uses A.pw"),
+        ("craft.on", 7, None, "reference", "test S.pw*A.pw*eta > P_min uses A.pw"),
+    ], references
+
+
+def test_errors():
+    hits, errors = _search("flux", FILES | {"broken.on": "Broken: b = = 2\n"})
+    assert len(errors) == 1 and errors[0].kind() == "SyntaxError", errors
+    assert "While searching" in errors[0].notes()[-1], errors[0].notes()
+    # The other models are still searched
+    assert ("power.on", 5, "Input", "label", "Solar flux (S)") in hits, hits
+
+
+def test_cli():
+    directory = helpers.workspace(FILES)
+    output, code = helpers.cli(["search", "solar flux"], directory)
+    assert code == 0, output
+    lines = [line.replace("\x1b[94m", "").replace("\x1b[0m", "") for line in output.splitlines()]
+    assert lines == [
+        "./power.on:1 model note: Uses the solar flux at 1 AU.",
+        "./power.on:5 [Input] label: Solar flux (S)",
+        "./power.on:6 [Input] note: S: Mean solar flux at Earth's distance.",
+    ], lines
+
+    output, _ = helpers.cli(["search", "thermal", "craft.on"], directory)
+    assert output == "No matches for \"thermal\".\n", output
+
+    output, code = helpers.cli(["search"], directory)
+    assert "Usage: oneil search" in output and code == console.EXIT_USAGE, output
+
+
+ALL_TESTS = [
+    test_labels_and_notes,
+    test_test_names,
+    test_references,
+    test_errors,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
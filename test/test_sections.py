@@ -0,0 +1,129 @@
+#!/usr/bin/env python3
+"""Tests for per-section results: parameters and tests grouped by their section in
+declaration order, in `all sections`, `summarize sections`, and exported results.
+
+Run directly:
+
+    python test/test_sections.py
+
+Or:
+
+    pytest test/test_sections.py
+"""
+
+import contextlib
+import io
+import os
+import re
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console  # noqa: E402
+import helpers  # noqa: E402
+
+
+CYLINDER = """\
+Mass: m = 1000 :kg
+
+section Geometry
+
+Radius: r = 250 :m
+$ Diameter: D = 2*r :m
+
+section Gravity
+
+Rotation rate: omega = 1 :rad/s
+$ Artificial gravity: g_a = omega^2*r :m/s^2
+Minimum gravity: g_min = 9 :m/s^2
+
+test: g_a > g_min
+
+section Budget
+
+Maximum radius: r_max = 200 :m
+
+test: r < r_max
+"""
+
+
+def _output(function, *args, **kwargs):
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        function(*args, **kwargs)
+    return re.sub(r"\x1b\[[0-9;]*m", "", output.getvalue())
+
+
+def test_sections():
+    model, _ = helpers.load({"cylinder.on": CYLINDER}, "cylinder.on")
+    assert model.sections() == {"": ["m"], "Geometry": ["r", "D"], "Gravity": ["omega", "g_a", "g_min"], "Budget": ["r_max"]}, model.sections()
+    assert [test.section for test in model.tests] == ["Gravity", "Budget"]
+
+
+def test_all():
+    model, _ = helpers.load({"cylinder.on": CYLINDER}, "cylinder.on")
+    lines = [line for line in _output(model.all, sections=True).splitlines() if line.strip()]
+    headers = [line for line in lines if not line.startswith(" ") and ":" not in line or line.startswith(("Gravity (", "Budget ("))]
+    assert headers == ["(no section)", "Geometry", "Gravity (tests: 1/1 PASS)", "Budget (tests: 0/1 FAIL)"], lines
+    # Parameters are in declaration order under their section
+    order = [line.split(":")[0].strip() for line in lines if line not in headers]
+    assert order == ["m", "r", "D", "omega", "g_a", "g_min", "r_max"], lines
+
+
+def test_summarize():
+    model, _ = helpers.load({"cylinder.on": CYLINDER}, "cylinder.on")
+    output = _output(model.summarize, sections=True)
+    # Only the performance parameters, and the sections with them or with tests
+    assert "(no section)" not in output, output
+    assert output.index("Geometry") < output.index("D: ") < output.index("Gravity (tests: 1/1 PASS)") < output.index("g_a: ") < output.index("Budget (tests: 0/1 FAIL)"), output
+    assert "omega: " not in output and "r_max: " not in output, output
+
+
+def test_exported():
+    model, _ = helpers.load({"cylinder.on": CYLINDER}, "cylinder.on")
+    parameters = model.results()["parameters"]
+    assert (parameters["m"]["section"], parameters["D"]["section"], parameters["r_max"]["section"]) == (None, "Geometry", "Budget")
+
+
+def test_cli():
+    directory = helpers.workspace({"cylinder.on": CYLINDER})
+    output, code = helpers.cli(["--quiet", "cylinder.on", "all sections", "quit"], directory)
+    assert code == console.EXIT_TEST_FAILURE, output
+    assert "Gravity" in output and "tests: 1/1" in output, output
+
+
+ALL_TESTS = [
+    test_sections,
+    test_all,
+    test_summarize,
+    test_exported,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,131 @@
+#!/usr/bin/env python3
+"""Tests for oneil.sensitivity, exact derivatives of parameters from dual numbers.
+
+Run directly:
+
+    python test/test_sensitivity.py
+
+Or:
+
+    pytest test/test_sensitivity.py
+"""
+
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import sensitivity, Model  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+STEP = """Height: h = 3 :km
+Switch height: h_s = 3 :km
+Step: g = {2*h if h < h_s :km
+    {h + h_s if h >= h_s
+"""
+
+CRAFT = """import funcs
+use step as w
+
+Force: F = 10 :N
+Mass: m = 2|4 :kg
+Gain: k = 1.5
+Radius: r = 2 :m
+Angle: t = 0.5
+
+$ Acceleration: a = k*F/m :m/s^2
+$ Area: A = pi*r^2*sin(t) :m^2
+Stepped: S = 1*g.w :km
+Doubled: y = double(F) :N
+Load: W = y*1 :N
+"""
+
+FUNCS = """def double(x):
+    return 2 * x
+"""
+
+
+def _model():
+    directory = helpers.workspace({"step.on": STEP, "craft.on": CRAFT, "funcs.py": FUNCS})
+    with working_directory(directory):
+        return Model("craft.on")
+
+
+def _derivatives(entry):
+    return {i["input"]: i["derivative"] for i in entry["inputs"]}
+
+
+def test_dual_numbers():
+    x = sensitivity.Dual(2.0, {"x": 1.0})
+    y = sensitivity.Dual(3.0, {"y": 1.0})
+    z = x * y / (x + 1) - 2 ** x + x ** 3
+    assert math.isclose(z.value, 2 - 4 + 8)
+    assert math.isclose(z.derivatives["x"], 3 / 9 - 4 * math.log(2) + 12)
+    assert math.isclose(z.derivatives["y"], 2 / 3)
+    s = sensitivity.FUNCTIONS["sqrt"](x)
+    assert math.isclose(s.derivatives["x"], 0.5 / math.sqrt(2))
+
+
+def test_performance_parameters_by_default():
+    report = sensitivity.sensitivities(_model())
+    assert [entry["id"] for entry in report] == ["a", "A"], report
+    a, A = report
+    # a = k*F/m at the midpoint of m (3 kg)
+    assert math.isclose(a["value"], 5)
+    assert all(math.isclose(d, e) for d, e in zip(sorted(_derivatives(a).values()), sorted([-5 / 3, 0.5, 10 / 3]))), a
+    assert {i["input"]: i["units"] for i in a["inputs"]}["F"] == "1/kg"
+    assert all(math.isclose(abs(i["elasticity"]), 1) for i in a["inputs"]), a
+    assert math.isclose(_derivatives(A)["r"], 2 * math.pi * 2 * math.sin(0.5))
+    assert math.isclose(_derivatives(A)["t"], math.pi * 4 * math.cos(0.5))
+    assert [i["input"] for i in A["inputs"]] == ["r", "t"], "inputs should be sorted by elasticity"
+
+
+def test_piecewise_boundary_warns():
+    [entry] = sensitivity.sensitivities(_model(), ["stepped"])
+    assert _derivatives(entry) == {"h.w": 1, "h_s.w": 1}, entry
+    assert len(entry["warnings"]) == 1 and "g.w" in entry["warnings"][0], entry["warnings"]
+
+
+def test_breakout_functions_have_no_sensitivities():
+    report = sensitivity.sensitivities(_model(), ["y", "W"])
+    for entry in report:
+        assert entry["inputs"] == [] and "breakout function" in entry["error"], entry
+
+
+ALL_TESTS = [
+    test_dual_numbers,
+    test_performance_parameters_by_default,
+    test_piecewise_boundary_warns,
+    test_breakout_functions_have_no_sensitivities,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
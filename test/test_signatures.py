@@ -0,0 +1,151 @@
+#!/usr/bin/env python3
+"""Tests for oneil.signatures, the unit signatures of imported Python functions.
+
+Run directly:
+
+    python test/test_signatures.py
+
+Or:
+
+    pytest test/test_signatures.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import FunctionUnitError, Model, signatures  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+from oneil.units import hr_units  # noqa: E402
+import helpers  # noqa: E402
+
+
+FUNCTIONS = """\
+def temperature(D, t):
+    return 300
+
+def emissivity(finish, A):
+    return 0.8
+
+ONEIL_UNITS = {
+    "temperature": "m, s -> K",
+    "emissivity": "*, 1 -> 1",
+}
+"""
+
+MODEL = """\
+import {module}
+
+Diameter: D = 2 :km
+Duration: t = 3 :min
+Finish [matte, gloss]: f = matte
+Area ratio: A = 0.5
+Temperature: T = temperature(D, t) :K
+Emissivity: e = emissivity(f, A)
+"""
+
+
+def _load(text, functions=FUNCTIONS):
+    # Each model imports its own module, since modules stay loaded between tests.
+    module = f"signature_functions_{_load.count}"
+    _load.count += 1
+    with working_directory(helpers.workspace({module + ".py": functions, "craft.on": text.replace("{module}", module)})):
+        return Model("craft.on")
+_load.count = 0
+
+
+def _expect_error(text, functions=FUNCTIONS):
+    try:
+        _load(text, functions)
+    except FunctionUnitError as e:
+        return e
+    raise AssertionError("Expected a FunctionUnitError")
+
+
+def test_parse():
+    assert signatures.parse("m, s -> K") == ([{"m": 1}, {"s": 1}], {"K": 1})
+    assert signatures.parse("* , 1 -> 1") == ([None, {}], {})
+    assert signatures.parse(" -> kg") == ([], {"kg": 1})
+    for invalid in ("m, s", "m -> K -> s", "m, -> K", "furlongs -> K", 3):
+        try:
+            signatures.parse(invalid)
+        except ValueError:
+            continue
+        raise AssertionError(f"Expected {invalid!r} to be invalid")
+
+
+def test_matching_calls():
+    model = _load(MODEL)
+    model.build(quiet=True)
+    assert model.parameters["T"].min == 300
+    assert model.parameters["e"].min == 0.8
+
+
+def test_argument_units():
+    error = _expect_error(MODEL.replace("Duration: t = 3 :min", "Duration: t = 3 :kg"))
+    assert error.parameter.id == "T"
+    assert error.notes() == [f"t is in {hr_units({'kg': 1})}, but temperature() takes {hr_units({'s': 1})}."], error.notes()
+    assert error.code() == "E0205"
+
+
+def test_result_units():
+    error = _expect_error(MODEL.replace("temperature(D, t) :K", "temperature(D, t) :s"))
+    assert error.notes() == [f"temperature() returns K, but the parameter is in {hr_units({'s': 1})}."], error.notes()
+
+
+def test_argument_count():
+    error = _expect_error(MODEL.replace("temperature(D, t)", "temperature(D)"))
+    assert "takes 2 argument(s), but is given 1" in error.notes()[0], error.notes()
+
+
+def test_invalid_signature():
+    error = _expect_error(MODEL, FUNCTIONS.replace("m, s -> K", "m, s"))
+    assert error.invalid and error.signature == "m, s"
+    assert "invalid" in error.message()
+
+
+def test_without_signature():
+    # Functions without a signature are unchecked until they run.
+    model = _load(MODEL.replace("Duration: t = 3 :min", "Duration: t = 3 :kg"), FUNCTIONS.split("ONEIL_UNITS")[0])
+    model.build(quiet=True)
+    assert model.parameters["T"].min == 300
+
+
+ALL_TESTS = [
+    test_parse,
+    test_matching_calls,
+    test_argument_units,
+    test_result_units,
+    test_argument_count,
+    test_invalid_signature,
+    test_without_signature,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,132 @@
+#!/usr/bin/env python3
+"""Tests for oneil.stamp, the reproducibility stamps in exports and `oneil verify`.
+
+Run directly:
+
+    python test/test_stamp.py
+
+Or:
+
+    pytest test/test_stamp.py
+"""
+
+import contextlib
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, stamp, Model, VerifyError  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+FILES = {
+    "craft.on": "use sub as s\nX: x = 2*y.s\n",
+    "sub.on": "Y: y = 3\n",
+    "heavy.on": "y.s = 5\n",
+}
+
+
+def _export():
+    # Export the results of craft with the heavy design to out/craft.json
+    directory = helpers.workspace(FILES)
+    os.mkdir(os.path.join(directory, "out"))
+    with working_directory(directory), contextlib.redirect_stdout(io.StringIO()):
+        model = Model("craft.on")
+        model.build(quiet=True)
+        model.overwrite(["heavy.on"])
+        model.export_json(os.path.join("out", "craft.json"))
+    return directory, os.path.join(directory, "out", "craft.json")
+
+
+def _verify(path):
+    previous = console.exit_code
+    console.exit_code = console.EXIT_SUCCESS
+    try:
+        with contextlib.redirect_stdout(io.StringIO()) as output:
+            matches = stamp.verify(path)
+        return matches, console.exit_code, output.getvalue()
+    finally:
+        console.exit_code = previous
+
+
+def test_stamp():
+    _, path = _export()
+    with open(path) as f:
+        stamped = json.load(f)["reproducibility"]
+    assert stamped["model"] == os.path.join("..", "craft.on")
+    assert stamped["designs"] == [os.path.join("..", "heavy.on")]
+    assert set(stamped["inputs"]) == {os.path.join("..", name) for name in FILES}
+    assert stamped["oneil"] == console.__version__
+
+
+def test_verify():
+    directory, path = _export()
+    matches, code, _ = _verify(path)
+    assert matches and code == console.EXIT_SUCCESS
+
+    with open(os.path.join(directory, "sub.on"), "a") as f:
+        f.write("Z: z = 1\n")
+    os.remove(os.path.join(directory, "heavy.on"))
+    matches, code, output = _verify(path)
+    assert not matches and code == console.EXIT_TEST_FAILURE
+    assert "sub.on changed" in output and "heavy.on is missing" in output
+
+
+def test_report_stamp():
+    directory, path = _export()
+    with open(path) as f:
+        stamped = json.load(f)["reproducibility"]
+    report = os.path.join(directory, "out", "export.tex")
+    with open(report, "w") as f:
+        f.write(stamp.REPORT_PREFIX + json.dumps(stamped) + "\n\\begin{document}\n")
+    assert stamp.read(report) == stamped
+    assert _verify(report)[0]
+
+
+def test_no_stamp():
+    directory, _ = _export()
+    try:
+        stamp.verify(os.path.join(directory, "craft.on"))
+        assert False, "expected a VerifyError"
+    except VerifyError:
+        pass
+
+
+ALL_TESTS = [
+    test_stamp,
+    test_verify,
+    test_report_stamp,
+    test_no_stamp,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,156 @@
+#!/usr/bin/env python3
+"""Tests for oneil stats: complexity metrics for the models in a workspace, and the
+thresholds that flag them.
+
+Run directly:
+
+    python test/test_stats.py
+
+Or:
+
+    pytest test/test_stats.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import console, stats  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """\
+Thrust: F = 20 :kN
+Chamber pressure: p_c = 2 :MPa
+"""
+
+TANK = """\
+Propellant mass: m_p = 300 :kg
+"""
+
+CRAFT = """\
+use engine as e
+use tank as t
+Mass: m = 500 :kg
+Gravity: g = 9.81 :m/s^2
+$ Acceleration: a = F.e/m :m/s^2
+$ Thrust to weight: T_W = F.e/(m*g)
+$ Margin: M = (a - g)/(g + (a - 2*g))
+test: T_W > 1
+"""
+
+PROBE = """\
+use engine as e
+Probe thrust: F_p = F.e/2 :kN
+"""
+
+# Uses craft, and engine from craft's submodels
+MISSION = """\
+use craft as c
+from craft use engine as ce
+Mission mass: m_m = 2*m.c :kg
+"""
+
+FILES = {
+    "engine.on": ENGINE,
+    "tank.on": TANK,
+    "craft.on": CRAFT,
+    "probe.on": PROBE,
+    "mission.on": MISSION,
+    "broken.on": "Mass: m = 2 :kg\nWeight W = m :kg\n",
+}
+
+
+def test_expression_depth():
+    assert stats.expression_depth(None) == 0
+    assert stats.expression_depth(3) == 1
+    assert stats.expression_depth("x") == 1
+    assert stats.expression_depth("x + y") == 2
+    assert stats.expression_depth("(x + y)*z") == 3
+    assert stats.expression_depth(["x", "x*(y + z)"]) == 3
+
+
+def test_model_stats():
+    model, _ = helpers.load(FILES, "craft.on", build=False)
+    assert stats.model_stats(model) == {
+        "parameters": 5,
+        "tests": 1,
+        "submodels": 2,
+        "nesting": 5,
+        "fan_in": 0,
+        "fan_out": 2,
+        "external": 2,
+    }, stats.model_stats(model)
+
+
+def test_collect():
+    directory = helpers.workspace(FILES)
+    with contextlib.redirect_stdout(io.StringIO()):
+        results = stats.collect([directory])
+    by_name = {os.path.basename(file): (result, error) for file, result, error in results}
+    assert sorted(by_name) == ["broken.on", "craft.on", "engine.on", "mission.on", "probe.on", "tank.on"], by_name
+    assert by_name["engine.on"][0]["parameters"] == 2
+    # Fan-in and fan-out count the models that use each model, and that it uses
+    fans = {name: (result["fan_in"], result["fan_out"]) for name, (result, _) in by_name.items() if result}
+    assert fans == {"engine.on": (3, 0), "tank.on": (1, 0), "craft.on": (1, 2), "probe.on": (0, 1), "mission.on": (0, 2)}, fans
+    assert by_name["broken.on"][0] is None and by_name["broken.on"][1].kind() == "SyntaxError"
+
+
+def test_thresholds():
+    directory = helpers.workspace(FILES)
+    with contextlib.redirect_stdout(io.StringIO()):
+        _, flagged = stats.report([directory])
+        assert flagged == []
+        _, flagged = stats.report([directory], {"parameters": 4, "fan_in": 2})
+    assert sorted(flag.replace(directory + os.sep, "") for flag in flagged) == ["craft.on: parameters 5 > 4", "engine.on: fan-in 3 > 2"], flagged
+
+
+def test_cli():
+    directory = helpers.workspace(FILES)
+    output, code = helpers.cli(["stats", "craft.on", "engine.on", "--max-parameters", "4", "--max-fan-out", "1"], directory)
+    assert code == console.EXIT_SUCCESS, output
+    assert "2 threshold(s) exceeded" in output, output
+    assert "craft.on: parameters 5 > 4" in output and "craft.on: fan-out 2 > 1" in output, output
+    output, code = helpers.cli(["stats", "--max-nesting", "deep"], directory)
+    assert code == console.EXIT_USAGE, output
+    assert "--max-nesting takes an integer" in output, output
+
+
+ALL_TESTS = [
+    test_expression_depth,
+    test_model_stats,
+    test_collect,
+    test_thresholds,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
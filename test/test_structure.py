@@ -0,0 +1,120 @@
+#!/usr/bin/env python3
+"""Tests for oneil.structure, the structural keys and hashes of equations and parameters.
+
+Run directly:
+
+    python test/test_structure.py
+
+Or:
+
+    pytest test/test_structure.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, Parameter  # noqa: E402
+from oneil import structure  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+def test_formatting_is_ignored():
+    assert structure.structurally_equal("a*b + 2", "(a * b)+2.0")
+    assert structure.structural_hash("a*b + 2") == structure.structural_hash(" (a*b) + 2 ")
+
+
+def test_structure_is_compared():
+    assert not structure.structurally_equal("a*b", "b*a")
+    assert not structure.structurally_equal("a + 2", "a + 3")
+    assert not structure.structurally_equal("f(a)", "g(a)")
+
+
+def test_hash_is_stable():
+    # The digest must not depend on Python's per-process hash seed.
+    assert structure.structural_hash("a + 1") == structure.structural_hash("a+1")
+    assert len(structure.structural_hash("a + 1")) == 64
+
+
+def test_parameters_ignore_id_and_location():
+    a = Parameter("x*2", {"m": 1}, "a", line_no=3, name="First")
+    b = Parameter("x * 2", {"m": 1}, "b", line_no=10, name="Second")
+    assert structure.structurally_equal(a, b)
+
+
+def test_parameters_compare_units():
+    a = Parameter("x*2", {"m": 1}, "a")
+    b = Parameter("x*2", {"s": 1}, "b")
+    assert not structure.structurally_equal(a, b)
+
+
+def test_independent_values():
+    a = Parameter(2, {"m": 1}, "a")
+    b = Parameter(2.0, {"m": 1}, "b")
+    c = Parameter((1, 2), {"m": 1}, "c")
+    assert structure.structurally_equal(a, b)
+    assert not structure.structurally_equal(a, c)
+
+
+def _nodes(text):
+    directory = helpers.workspace({"craft.on": text})
+    with working_directory(directory):
+        model = Model("craft.on")
+    nodes = {ID: structure.node_id(parameter, model.name) for ID, parameter in model.parameters.items()}
+    nodes["test"] = structure.node_id(model.tests[0], model.name)
+    return nodes
+
+
+def test_node_ids():
+    text = "Mass: m = 2\nArea: A = {m*2 if m > 1\n    {m*1 if m <= 1\n    The area.\n\ntest: A > 0\n"
+    before = _nodes(text)
+    assert len(set(before.values())) == 3
+    # Moving the declarations and reformatting them keeps their IDs.
+    after = _nodes("Width: w = 3\n\n" + text.replace("m*2", "m * 2"))
+    assert all(after[key] == node for key, node in before.items())
+    # Changing a declaration changes only its ID.
+    after = _nodes(text.replace("The area.", "The area of the panel."))
+    assert after["A"] != before["A"] and after["m"] == before["m"] and after["test"] == before["test"]
+    after = _nodes(text.replace("{m*1 if", "{m*3 if"))
+    assert after["A"] != before["A"]
+
+
+ALL_TESTS = [
+    test_formatting_is_ignored,
+    test_structure_is_compared,
+    test_hash_is_stable,
+    test_parameters_ignore_id_and_location,
+    test_parameters_compare_units,
+    test_independent_values,
+    test_node_ids,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,102 @@
+#!/usr/bin/env python3
+"""Tests for the unit check of test inputs passed to submodels in use declarations.
+
+Run directly:
+
+    python test/test_submodel_input_units.py
+
+Or:
+
+    pytest test/test_submodel_input_units.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, SubmodelInputUnitError, parse_test_input  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """Thrust: F = 2 :kN
+Mass: m = 100 :kg
+test {F_min}: F >= F_min
+"""
+
+
+def _load(use, parent="Count: n = 1\n"):
+    directory = helpers.workspace({"engine.on": ENGINE, "craft.on": use + "\n" + parent})
+    with working_directory(directory):
+        return Model("craft.on")
+
+
+def _error(use, parent="Count: n = 1\n"):
+    try:
+        _load(use, parent)
+    except SubmodelInputUnitError as e:
+        return e
+    return None
+
+
+def test_parse_test_input():
+    assert parse_test_input("F", "F_max", "craft.on", 1, "") is None
+    assert parse_test_input("F", "F.e", "craft.on", 1, "") is None
+    parameter = parse_test_input("F", "5 :kN", "craft.on", 1, "")
+    assert parameter.min == 5000 and parameter.units == {"kg": 1, "m": 1, "s": -2}
+
+
+def test_matching_inputs():
+    assert _error("use engine(F=5 :kN, F_min=1 :N) as e") is None
+    assert _error("use engine(F=T) as e", "Force: T = 3 :N\n") is None
+
+
+def test_mismatched_value():
+    error = _error("use engine(F=5 :kg) as e")
+    assert isinstance(error, SubmodelInputUnitError), error
+    assert error.code() == "E0204" and error.line_no == 1, (error.code(), error.line_no)
+    assert error.arg == "F" and error.value == "5 :kg" and error.units == {"kg": 1}
+    assert "use engine(F=5 :kg) as e" in error.notes()[0]
+    assert isinstance(_error("use engine (F=5) as e"), SubmodelInputUnitError), "a value without units is unitless"
+
+
+def test_mismatched_parameter():
+    error = _error("use engine(m=T) as e", "Force: T = 3 :N\n")
+    assert isinstance(error, SubmodelInputUnitError) and error.expected == {"kg": 1}, error
+
+
+ALL_TESTS = [
+    test_parse_test_input,
+    test_matching_inputs,
+    test_mismatched_value,
+    test_mismatched_parameter,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
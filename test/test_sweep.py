@@ -0,0 +1,167 @@
+#!/usr/bin/env python3
+"""Tests for oneil.sweep, sweeping a model's inputs over ranges (`oneil sweep`, `sweep`).
+
+Run directly:
+
+    python test/test_sweep.py
+
+Or:
+
+    pytest test/test_sweep.py
+"""
+
+import contextlib
+import csv
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import SweepError, sweep  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = "Max thrust: F_max = 10 :N\n"
+
+CRAFT = """use engine as e
+Mass (0.5, 100): m = 2 :kg
+Drag: D = 1 :N
+Mode [fast, slow]: mode = fast
+$ Acceleration: a = (F_max.e - D)/m :m/s^2
+Reserve: r = {2*D if mode == 'fast' :N
+             {D*1 if mode == 'slow'
+Unrelated: u = D*3 :N
+Floor: a_min = 3 :m/s^2
+
+test "quick": a > a_min
+"""
+
+
+def _load():
+    model, directory = helpers.load({"engine.on": ENGINE, "craft.on": CRAFT}, "craft.on")
+    return model, directory
+
+
+def test_parse_spec():
+    assert sweep.parse_spec("m=1..2/0.5") == ("m", [1.0, 1.5, 2.0], None)
+    assert sweep.parse_spec("m = 1..2.2/0.5 :g") == ("m", [1.0, 1.5, 2.0], "g")
+    assert sweep.parse_spec("mode=fast,slow") == ("mode", ["fast", "slow"], None)
+    assert sweep.parse_spec("F_max.e=1,2") == ("F_max.e", [1.0, 2.0], None)
+    for spec in ["m", "=1..2/1", "m=1..2/0", "m=3..2/1", "m=1,,2", "m=0..1/0.000001"]:
+        try:
+            sweep.parse_spec(spec)
+        except SweepError:
+            continue
+        raise AssertionError(f"Expected a SweepError for {spec}")
+
+
+def test_downstream():
+    model, _ = _load()
+    engine = model.submodels["e"]["model"]
+    affected = sweep.downstream(model, [(model, "m")])
+    assert affected[id(model)] == {"m", "a"}, affected
+    affected = sweep.downstream(model, [(engine, "F_max")])
+    assert affected[id(engine)] == {"F_max"} and affected[id(model)] == {"a"}, affected
+    affected = sweep.downstream(model, [(model, "mode")])
+    assert affected[id(model)] == {"mode", "r"}, affected
+
+
+def test_sweep():
+    model, _ = _load()
+    calculated = []
+    calculate = model._calculate_parameters_recursively
+
+    def spy(parameters, trail=[]):
+        calculated.extend(ID for ID, parameter in parameters.items() if parameter.min is None)
+        return calculate(parameters, trail)
+
+    model._calculate_parameters_recursively = spy
+    report = sweep.sweep(model, ["m=1..4/1", "F_max.e=11,21"])
+    del model._calculate_parameters_recursively
+    assert [column["id"] for column in report["inputs"]] == ["m", "F_max.e"]
+    assert report["outputs"] == [{"id": "a", "name": "Acceleration", "units": "m/s^2"}], report["outputs"]
+    assert [row["inputs"] for row in report["rows"]] == [[m, F] for m in (1, 2, 3, 4) for F in (11, 21)]
+    assert [row["outputs"][0][0] for row in report["rows"]] == [10, 20, 5, 10, 10 / 3, 20 / 3, 2.5, 5]
+    assert [row["failed"] for row in report["rows"]] == [0, 0, 0, 0, 0, 0, 1, 0], "a = 2.5 m/s^2 fails the test"
+    assert "u" not in calculated and "r" not in calculated and "a" in calculated, calculated
+
+    assert model.parameters["m"].min == 2 and model.parameters["a"].min == 4.5, "the model's own values are restored"
+    assert model.submodels["e"]["model"].parameters["F_max"].min == 10
+
+
+def test_units_and_options():
+    model, _ = _load()
+    report = sweep.sweep(model, ["m=1000..2000/1000:g", "mode=fast,slow"], ["r", "a"])
+    assert [row["inputs"] for row in report["rows"]] == [[1, "fast"], [1, "slow"], [2, "fast"], [2, "slow"]], report["rows"]
+    assert [row["outputs"][0][0] for row in report["rows"]] == [2, 1, 2, 1]
+    assert [row["failed"] for row in report["rows"]] == [0, 0, 0, 0]
+
+    for specs, outputs in [(["m=1..2/1:s"], None), (["a=1..2/1"], None), (["x=1,2"], None), (["m=1,2"], ["nope"])]:
+        try:
+            sweep.sweep(model, specs, outputs)
+        except SweepError:
+            continue
+        raise AssertionError(f"Expected a SweepError for {specs} {outputs}")
+
+
+def test_failed_point():
+    model, _ = _load()
+    report = sweep.sweep(model, ["m=0.1,1"])
+    assert report["rows"][0]["error"] and report["rows"][0]["outputs"] == [(None, None)], report["rows"][0]
+    assert report["rows"][1]["error"] is None and report["rows"][1]["outputs"] == [(9, 9)]
+    assert model.parameters["a"].min == 4.5
+
+
+def test_csv():
+    model, directory = _load()
+    rows = list(csv.reader(io.StringIO(sweep.to_csv(sweep.sweep(model, ["m=1,2"], ["a", "D"])))))
+    assert rows[0] == ["m (kg)", "a (m/s^2)", "D (N)", "failed tests", "error"], rows[0]
+    assert rows[1] == ["1.0", "9.0", "1.0", "0", ""], rows[1]
+
+    path = os.path.join(directory, "sweep.csv")
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output), working_directory(directory):
+        sweep.run("craft", [], ["m=1..2/1"], output_file=path)
+    with open(path) as f:
+        assert f.read().splitlines() == ["m (kg),a (m/s^2),failed tests,error", "1.0,9.0,0,", "2.0,4.5,0,"]
+    assert "Wrote 2 point(s)" in output.getvalue()
+
+
+ALL_TESTS = [
+    test_parse_spec,
+    test_downstream,
+    test_sweep,
+    test_units_and_options,
+    test_failed_point,
+    test_csv,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,125 @@
+#!/usr/bin/env python3
+"""Tests for oneil.symbolic, closed-form expressions of parameters (`oneil symbolic`).
+
+Run directly:
+
+    python test/test_symbolic.py
+
+Or:
+
+    pytest test/test_symbolic.py
+"""
+
+import ast
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import symbolic, Model, IDError  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+WHEEL = """Radius: r = 0.3 :m
+Diameter: d = 2*r :m
+"""
+
+CRAFT = """use wheel as w
+
+Mass: m = 4 :kg
+Force: F = 10 :N
+Margin: k = 1.5
+Acceleration: a = F/m :m/s^2
+Scaled acceleration: b = k*a*1 :m/s^2
+Reach: R = 3*d.w + 2*d.w :m
+Mode [low, high]: D = low
+Level: L = {1*m if D == 'low' :kg
+    {2*m if D == 'high'
+Load: W = L*a :N
+"""
+
+
+def _model():
+    directory = helpers.workspace({"wheel.on": WHEEL, "craft.on": CRAFT})
+    with working_directory(directory):
+        return Model("craft.on")
+
+
+def _simplified(expression):
+    return symbolic.unparse(symbolic.simplify(ast.parse(expression, mode="eval").body))
+
+
+def test_simplify():
+    assert _simplified("2*x*3 + x*1 + 0*y") == "7 * x"
+    assert _simplified("x - (y - x) + 2 - 2") == "2 * x - y"
+    assert _simplified("x - x") == "0"
+    assert _simplified("(x**1)/1 + 2**3") == "x + 8"
+    assert _simplified("par_sqrt(4*1) | minus | b") == "sqrt(4) -- b"
+
+
+def test_independent_parameters_stay_symbols():
+    form = symbolic.closed_form(_model(), "scaled acceleration")
+    assert form["id"] == "b" and form["expression"] == "k * (F / m)", form
+    assert form["units"] == "m/s^2"
+    assert form["symbols"] == {"F": "(kg m)/s^2", "k": "", "m": "kg"}, form["symbols"]
+    assert form["latex"].startswith("b = "), form["latex"]
+
+
+def test_inputs_replace_other_parameters_with_values():
+    model = _model()
+    assert symbolic.closed_form(model, "b", ["F"])["expression"] == "1.5 * (F / 4)"
+    assert symbolic.closed_form(model, "b", ["a"])["expression"] == "1.5 * a"
+    form = symbolic.closed_form(model, "R")
+    assert form["expression"] == "10 * r.w" and form["symbols"] == {"r.w": "m"}, form
+    assert symbolic.closed_form(model, "R", ["d.w"])["expression"] == "5 * d.w"
+
+
+def test_piecewise_parameters_stay_symbols():
+    model = _model()
+    form = symbolic.closed_form(model, "W")
+    assert form["expression"] == "L * (F / m)", form
+    assert form["opaque"] == {"L": "piecewise equation"}, form["opaque"]
+    for ID in ("L", "m"):
+        try:
+            symbolic.closed_form(model, ID)
+        except IDError:
+            pass
+        else:
+            raise AssertionError(f"expected an IDError for {ID}")
+
+
+ALL_TESTS = [
+    test_simplify,
+    test_independent_parameters_stay_symbols,
+    test_inputs_replace_other_parameters_with_values,
+    test_piecewise_parameters_stay_symbols,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
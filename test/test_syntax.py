@@ -0,0 +1,149 @@
+#!/usr/bin/env python3
+"""Tests for oneil.syntax, the parser settings in `parser:` lines of manifests.
+
+Run directly:
+
+    python test/test_syntax.py
+
+Or:
+
+    pytest test/test_syntax.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import ConfigError, Model, SyntaxError, _manifest_lines, read_manifest, syntax, tokens  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+CRAFT = """Mass: m = 2 :kg
+Δ mass: Δm = 1 :kg
+Old mass: m_old := m
+"""
+
+
+def _load(text):
+    with working_directory(helpers.workspace({"craft.on": text})):
+        model = Model("craft.on")
+        model.build(quiet=True)
+    return model
+
+
+def _syntax_error(text):
+    try:
+        _load(text)
+    except SyntaxError as e:
+        return e
+    raise AssertionError("Expected a SyntaxError")
+
+
+def test_round_trip():
+    syntax.reset()
+    values = {"unicode_identifiers": False, "legacy_syntax": True, "recovery": "declaration"}
+    line = syntax.to_manifest(values)
+    assert line == "parser: unicode_identifiers=false legacy_syntax=true recovery=declaration", line
+    errors = []
+    assert syntax.from_manifest(line, errors) == values and not errors
+    assert syntax.from_manifest(syntax.to_manifest(), errors) == syntax.DEFAULT_SETTINGS and not errors
+
+
+def test_load():
+    syntax.reset()
+    directory = helpers.workspace({"targets.txt": "parser: recovery=declaration  # keep going\nparser: legacy_syntax=false, colour=blue unicode_identifiers=maybe oops\ncraft.on\n"})
+    manifest = os.path.join(directory, "targets.txt")
+    try:
+        syntax.load(manifest, _manifest_lines(manifest))
+    except ConfigError as e:
+        assert len(e.notes_) == 3, e.notes_
+    else:
+        raise AssertionError("Expected a ConfigError")
+    assert syntax.settings == {"unicode_identifiers": True, "legacy_syntax": False, "recovery": "declaration"}, syntax.settings
+    assert read_manifest(manifest) == [os.path.join(directory, "craft.on")]
+    syntax.reset()
+
+
+def test_defaults():
+    syntax.reset()
+    model = _load(CRAFT)
+    assert model.parameters["Δm"].min == 1 and model.parameters["m_old"].alias == "m"
+
+
+def test_unicode_identifiers():
+    syntax.reset()
+    syntax.settings["unicode_identifiers"] = False
+    try:
+        error = _syntax_error(CRAFT)
+        assert error.line_no == 2 and "Δm" in error.message(), error
+        kinds = {(token.line, token.text): token.kind for token in tokens.tokenize(CRAFT)}
+        assert kinds[2, "Δm"] == "error" and kinds[1, "m"] == "definition" and kinds[2, "Δ mass"] == "label", kinds
+    finally:
+        syntax.reset()
+
+
+def test_legacy_syntax():
+    syntax.reset()
+    syntax.settings["legacy_syntax"] = False
+    try:
+        error = _syntax_error(CRAFT)
+        assert error.line_no == 3 and "legacy_syntax" in error.message(), error
+        assert all(token.kind == "error" for token in tokens.tokenize(CRAFT) if token.line == 3)
+    finally:
+        syntax.reset()
+
+
+def test_recovery():
+    syntax.reset()
+    text = "Mass: m = 2 :kg\nuse engine\n    notes of the bad line\nDrag: D = 1 :N\nimport\nthing\n"
+    error = _syntax_error(text)
+    assert error.line_no == 2 and not getattr(error, "notes_", None)
+
+    syntax.settings["recovery"] = "declaration"
+    try:
+        error = _syntax_error(text)
+        assert error.line_no == 2, error
+        assert [note.split(": ")[0] for note in error.notes_] == ["in craft.on (line 5)", "in craft.on (line 6)"], error.notes_
+        assert _load("Mass: m = 2 :kg\n").parameters["m"].min == 2
+    finally:
+        syntax.reset()
+
+
+ALL_TESTS = [
+    test_round_trip,
+    test_load,
+    test_defaults,
+    test_unicode_identifiers,
+    test_legacy_syntax,
+    test_recovery,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
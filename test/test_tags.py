@@ -0,0 +1,157 @@
+#!/usr/bin/env python3
+"""Tests for `@tags` annotations: parsing tags, model.tagged_parameters(), `all tag=...`,
+tags in exported results, and oneil eval --tag.
+
+Run directly:
+
+    python test/test_tags.py
+
+Or:
+
+    pytest test/test_tags.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import OneilError  # noqa: E402
+import helpers  # noqa: E402
+
+
+PANEL = """\
+@tags(mass)
+Panel mass: m_p = 4 :kg
+
+@tags(power)
+Panel power: P_p = 100 :W
+"""
+
+CRAFT = """\
+use panel as panel
+
+@tags(mass, structures)
+Frame mass: m_f = 12 :kg
+
+@tags(mass)
+@tags(budget-total)
+$ Total mass: m = m_f + 2*m_p.panel :kg
+
+Length: L = 2 :m
+"""
+
+FILES = {"panel.on": PANEL, "craft.on": CRAFT}
+
+
+def _output(function, *args, **kwargs):
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        function(*args, **kwargs)
+    return output.getvalue()
+
+
+def test_parse():
+    assert oneil.parse_annotation("@tags(mass, structures)") == ("tags", ("mass", "structures"))
+    assert oneil.parse_annotation("@tags(budget-total, \"cots\")") == ("tags", ("budget-total", "cots"))
+    assert oneil.annotated_tags({"tags": ("mass", "budget-total")}) == ["mass", "budget-total"]
+    assert oneil.annotated_tags({}) == []
+    for line in ("@tags", "@tags()", "@tags(dry mass)", "@tags(mass,)"):
+        try:
+            oneil.parse_annotation(line)
+        except ValueError as e:
+            assert str(e) == "\"@tags\" takes one or more tags (e.g. \"@tags(mass, structures)\").", e
+        else:
+            assert False, f"Expected a ValueError for {line}"
+
+
+def test_annotations():
+    model, _ = helpers.load(FILES, "craft.on", build=False)
+    # Several @tags lines add up
+    assert oneil.annotated_tags(model.parameters["m"].annotations) == ["mass", "budget-total"]
+    assert oneil.annotated_tags(model.parameters["L"].annotations) == []
+
+
+def test_tagged_parameters():
+    model, _ = helpers.load(FILES, "craft.on")
+    mass = [(entry["model"], entry["id"], entry["min"]) for entry in model.tagged_parameters("mass")]
+    assert mass == [("craft", "m_f", 12), ("craft", "m", 20), ("panel", "m_p", 4)], mass
+    tagged = [(entry["model"], entry["id"]) for entry in model.tagged_parameters()]
+    assert tagged == [("craft", "m_f"), ("craft", "m"), ("panel", "m_p"), ("panel", "P_p")], tagged
+    assert model.tagged_parameters("thermal") == []
+
+
+def test_results():
+    model, _ = helpers.load(FILES, "craft.on")
+    parameters = model.results()["parameters"]
+    assert parameters["m_f"]["tags"] == ["mass", "structures"]
+    assert parameters["L"]["tags"] == []
+
+
+def test_all():
+    model, _ = helpers.load(FILES, "craft.on")
+    output = _output(model.all, tag="structures")
+    assert "m_f" in output and "m:" not in output and "L" not in output, output
+    assert _output(model.all, tag="thermal") == "No parameters tagged thermal.\n"
+
+
+def test_invalid():
+    try:
+        helpers.load({"craft.on": "@tags(dry mass)\nMass: m = 1 :kg\n"}, "craft.on", build=False)
+    except OneilError as e:
+        assert "\"@tags\" takes one or more tags" in e.message(), e.message()
+    else:
+        assert False, "Expected an error for an invalid tag"
+
+
+def test_cli():
+    directory = helpers.workspace(FILES)
+    output, _ = helpers.cli(["eval", "--tag", "mass", "--tag", "power", "craft.on"], directory)
+    for row in ("craft.on | Frame mass (m_f) | 12 kg | mass, structures", "craft.on (panel) | Panel mass (m_p) | 4 kg | mass", "craft.on (panel) | Panel power (P_p) | 100 W | power"):
+        assert row in output, output
+    assert "Length (L)" not in output, output
+
+    output, _ = helpers.cli(["eval", "--tag", "thermal", "craft.on"], directory)
+    assert "No parameters tagged thermal found." in output, output
+
+
+ALL_TESTS = [
+    test_parse,
+    test_annotations,
+    test_tagged_parameters,
+    test_results,
+    test_all,
+    test_invalid,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,123 @@
+#!/usr/bin/env python3
+"""Tests for absolute temperatures and temperature differences in models (°C, °F, and
+Δ°C, Δ°F, ΔK).
+
+Run directly:
+
+    python test/test_temperatures.py
+
+Or:
+
+    pytest test/test_temperatures.py
+"""
+
+import math
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import UnitEvaluationError  # noqa: E402
+import helpers  # noqa: E402
+
+
+MODEL = """\
+Cabin temperature (-40 °F, 120 °F): T_c = 70 :°F
+Outside temperature: T_o = 20|30 :°F
+Temperature rise: dT = T_c - T_o :Δ°F
+Temperature rise in Celsius: dT_C = T_c - T_o :Δ°C
+Heater outlet temperature: T_h = T_c + dT :°F
+Heater outlet in Kelvin: T_K = T_c + dT :K
+
+test: T_h > T_c
+"""
+
+
+def _close(values, expected):
+    return all(math.isclose(value, e, rel_tol=1e-9) for value, e in zip(values, expected))
+
+
+def _range(model, ID):
+    result = model.parameters[ID].result()
+    return result["min"], result["max"]
+
+
+def _load(text):
+    return helpers.load({"cabin.on": text}, "cabin.on")[0]
+
+
+def _expect_unit_error(text):
+    try:
+        _load(text)
+    except UnitEvaluationError as e:
+        return e
+    raise AssertionError("Expected a UnitEvaluationError")
+
+
+def test_differences():
+    model = _load(MODEL)
+    assert math.isclose(model.parameters["T_c"].min, 294.26111111, rel_tol=1e-9)
+    assert _close(_range(model, "dT"), (40, 50)), _range(model, "dT")
+    assert _close(_range(model, "dT_C"), (40 * 5 / 9, 50 * 5 / 9)), _range(model, "dT_C")
+    assert _close(_range(model, "T_h"), (110, 120)), _range(model, "T_h")
+    assert model.parameters["dT"].temperature == "difference" and model.parameters["T_h"].temperature == "absolute"
+    assert model.parameters["T_K"].temperature is None
+    assert model.test() == (0, 1)
+
+
+def test_limits():
+    model = _load(MODEL)
+    limits = model.parameters["T_c"].limits()["limits"]
+    assert _close((limits["min"], limits["max"]), (-40, 120)), limits
+
+
+def test_difference_as_absolute():
+    error = _expect_unit_error(MODEL.replace("dT = T_c - T_o :Δ°F", "dT = T_c - T_o :°F"))
+    assert "temperature difference" in error.message() and "Δ°F" in error.message(), error.message()
+
+
+def test_absolute_as_difference():
+    error = _expect_unit_error(MODEL.replace("T_h = T_c + dT :°F", "T_h = T_c + dT :Δ°F"))
+    assert "absolute temperature" in error.message(), error.message()
+
+
+def test_adding_absolutes():
+    error = _expect_unit_error(MODEL.replace("T_h = T_c + dT :°F", "T_h = T_c + T_o :°F"))
+    assert "Cannot add two absolute temperatures" in error.message(), error.message()
+
+
+ALL_TESTS = [
+    test_differences,
+    test_limits,
+    test_difference_as_absolute,
+    test_absolute_as_difference,
+    test_adding_absolutes,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,139 @@
+#!/usr/bin/env python3
+"""Tests for named tests (`test "name": ...`): parsing the name, and the name in test
+output, results, lints, refactorings, and syntax errors.
+
+Run directly:
+
+    python test/test_test_names.py
+
+Or:
+
+    pytest test/test_test_names.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import OneilError, console, lint, refactor  # noqa: E402
+import helpers  # noqa: E402
+
+
+CYLINDER = """\
+Rotation rate: omega = 0.2 :rad/s
+Radius: r = 250 :m
+$ Artificial gravity: g_a = omega^2*r :m/s^2
+Earth gravity: g_E = 9.81 :m/s^2
+
+test "gravity: Earth-like": g_E*0.9 <= g_a <= g_E*1.1
+test "gravity margin" {delta_g}: g_E - delta_g <= g_a
+test: g_a > 0*g_E
+"""
+
+
+def _model(text=CYLINDER, build=True):
+    return helpers.load({"cylinder.on": text}, "cylinder.on", build=build)[0]
+
+
+def test_parse():
+    tests = _model(build=False).tests
+    assert [test.name for test in tests] == ["gravity: Earth-like", "gravity margin", None]
+    # The colon in the name doesn't end the preamble
+    assert tests[0].expression == "g_E*0.9 <= g_a <= g_E*1.1", tests[0].expression
+    assert (tests[1].refs, tests[1].expression) == (["delta_g"], "g_E - delta_g <= g_a")
+    assert oneil.test_colon('test "a: b" {x}: x > 1') == 15
+
+
+def test_label():
+    tests = _model(build=False).tests
+    assert [test.label() for test in tests] == ['"gravity: Earth-like"', '"gravity margin"', "g_a > 0*g_E"]
+
+
+def test_output():
+    model = _model(build=False)
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        model.build()
+        model.test(verbose=True)
+    text = output.getvalue()
+    assert 'Test (cylinder): "gravity: Earth-like": ' in text, text
+    # Tests with inputs are skipped without them, and named in the message
+    assert 'Test (cylinder): "gravity margin" (' in text and "skipped" in text, text
+
+
+def test_results():
+    results = _model().results()["tests"]["results"]
+    assert [(result["name"], result["passed"]) for result in results] == [("gravity: Earth-like", True), ("gravity margin", None), (None, True)], results
+
+
+def test_lint():
+    users = lint._users(_model(build=False), "g_E")
+    assert users == ['test "gravity: Earth-like"', 'test "gravity margin"', "test g_a > 0*g_E"], users
+
+
+def test_rename():
+    # Only the expression is rewritten, not the name
+    block = ['test "omega: fast enough": omega > 0.1\n']
+    assert refactor.rewrite_block(block, {"omega": "omega.c"}) == ['test "omega: fast enough": omega.c > 0.1\n']
+
+
+def test_malformed():
+    for line in ('test "unclosed: g_a > 0', 'test {delta_g} "late name": g_a > 0'):
+        try:
+            _model(CYLINDER + line + "\n", build=False)
+        except OneilError as e:
+            assert e.kind() == "SyntaxError", e
+            assert e.message().startswith("Tests must be of the form \"test \"<name>\""), e.message()
+        else:
+            assert False, f"Expected a SyntaxError for {line}"
+
+
+def test_cli():
+    directory = helpers.workspace({"cylinder.on": CYLINDER.replace("0.2", "0.1")})
+    output, code = helpers.cli(["--quiet", "cylinder.on", "test", "quit"], directory)
+    assert code == console.EXIT_TEST_FAILURE, output
+    assert "gravity: Earth-like" in output, output
+
+
+ALL_TESTS = [
+    test_parse,
+    test_label,
+    test_output,
+    test_results,
+    test_lint,
+    test_rename,
+    test_malformed,
+    test_cli,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,120 @@
+#!/usr/bin/env python3
+"""Tests for oneil.tokens, the token stream for external highlighters.
+
+Run directly:
+
+    python test/test_tokens.py
+
+Or:
+
+    pytest test/test_tokens.py
+"""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, tokens  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = "Max thrust: F_max = 5 :N\n"
+
+CRAFT = """#[cfg(flight)]
+use engine as e
+from engine use engine as e2
+
+    A craft for testing tokens.
+
+section Thermal
+# Sizing
+$ Mass (0, 100): m = 10|12 :kg
+    A note.
+Mode [high, low]: mode = high
+Gravity: g = {9.8*m/2**2 if mode == 'high' :kg
+             {0 if mode == 'low'
+Thrust: F = min(F_max.e, 4) :N
+Mass alias: M := m
+test "light" {m_lim}: m < m_lim and not m > 100
+"""
+
+
+def _kinds(text):
+    return [(t.kind, t.text) for t in tokens.tokenize(text)]
+
+
+def test_expressions():
+    assert _kinds("Thrust: F = min(F_max.e, pi) :N") == [
+        ("label", "Thrust"), ("punctuation", ":"), ("definition", "F"), ("punctuation", "="),
+        ("function", "min"), ("punctuation", "("), ("identifier", "F_max"), ("punctuation", "."), ("submodel", "e"),
+        ("punctuation", ","), ("constant", "pi"), ("punctuation", ")"), ("punctuation", ":"), ("units", "N"),
+    ]
+
+
+def test_lines():
+    found = _kinds(CRAFT)
+    assert found[:4] == [("attribute", "#[cfg(flight)]"), ("keyword", "use"), ("module", "engine"), ("keyword", "as")]
+    for expected in [("note", "A craft for testing tokens."), ("label", "Thermal"), ("comment", "# Sizing"), ("operator", "$"),
+                     ("string", "'high'"), ("keyword", "if"), ("punctuation", ":="), ("string", '"light"'), ("keyword", "not")]:
+        assert expected in found, expected
+
+
+def test_columns_cover_the_text():
+    lines = CRAFT.splitlines()
+    for token in tokens.tokenize(CRAFT):
+        assert token.kind in tokens.KINDS, token
+        assert lines[token.line - 1][token.start:token.end] == token.text, token
+    covered = sum(1 for t in tokens.tokenize(CRAFT) for c in t.text if not c.isspace())
+    assert covered == sum(1 for c in CRAFT if not c.isspace()), "every other character is in one token"
+
+
+def test_definitions_match_the_parser():
+    directory = helpers.workspace({"engine.on": ENGINE, "craft.on": CRAFT.replace("#[cfg(flight)]\n", "")})
+    with working_directory(directory):
+        model = Model("craft.on")
+    definitions = [t.text for t in tokens.tokenize(CRAFT) if t.kind == "definition"]
+    assert definitions == [ID for ID, p in model.parameters.items() if p.model == "craft"], definitions
+
+
+def test_errors():
+    assert _kinds("??? what") == [("error", "??? what")]
+    assert ("error", "@") in _kinds("Ratio: r = 2 @ 3")
+
+
+ALL_TESTS = [
+    test_expressions,
+    test_lines,
+    test_columns_cover_the_text,
+    test_definitions_match_the_parser,
+    test_errors,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
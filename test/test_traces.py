@@ -0,0 +1,146 @@
+#!/usr/bin/env python3
+"""Tests for oneil.traces, the annotated trees of test conditions.
+
+Run directly:
+
+    python test/test_traces.py
+
+Or:
+
+    pytest test/test_traces.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, config, traces  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+COMPONENT = """\
+Mass: m_max = 6 :kg
+"""
+
+MODEL = """\
+use component as c
+
+Mass allocation: m_alloc = 10 :kg
+Angle: th = 2 :rad
+Expected allocation: m_gold = 10.001 :kg
+
+test "mass within allocation": m_max.c + m_alloc - m_alloc*0.5 <= m_alloc
+test "angle": sin(th) > 0.5
+@tolerance(0.1%)
+test "golden allocation": m_alloc == m_gold
+"""
+
+
+def _test(text, level):
+    directory = helpers.workspace({"component.on": COMPONENT, "craft.on": text})
+    output = io.StringIO()
+    traces.override = level
+    try:
+        with working_directory(directory), contextlib.redirect_stdout(output):
+            Model("craft.on").test()
+    finally:
+        traces.override = None
+    return output.getvalue()
+
+
+def test_tree():
+    lines = traces.trace("a + b*2 > 3", {}, {"a": 1, "b": 0.5})
+    assert lines == [
+        "a + b*2 > 3  →  false",
+        "└── a + b*2  →  2.0",
+        "    ├── a  →  1",
+        "    └── b*2  →  1.0",
+        "        └── b  →  0.5",
+    ], lines
+
+
+def test_names():
+    lines = traces.trace("c_m + par_sqrt(x**2) > 0", {"par_sqrt": lambda x: x ** 0.5}, {"c_m": 1, "x": 3}, {"c_m": "m.c", "par_sqrt": "sqrt"})
+    assert lines[0] == "m.c + sqrt(x^2) > 0  →  true", lines
+    assert lines[2] == "    ├── m.c  →  1", lines
+
+
+def test_errors():
+    lines = traces.trace("a/b > 1", {}, {"a": 1, "b": 0})
+    assert lines[0].startswith("a/b > 1  →  error:"), lines
+
+
+def test_failures():
+    output = _test(MODEL, "failures")
+    assert "m_max.c + m_alloc - m_alloc*0.5 <= m_alloc  →  false" in output, output
+    assert "├── m_max.c  →  6.0 kg" in output, output
+    assert "sin(th) > 0.5  →" not in output, output
+
+
+def test_all():
+    output = _test(MODEL, "all")
+    assert "sin(th) > 0.5  →  true" in output, output
+    assert "└── th  →  2.0 rad" in output, output
+    assert "m_alloc == m_gold  →  true" in output, output
+
+
+def test_off():
+    output = _test(MODEL, "off")
+    assert "→" not in output, output
+
+
+def test_config():
+    try:
+        config._apply_display({"trace": "failures"}, [])
+        assert traces.current() == "failures"
+        traces.override = "all"
+        assert traces.current() == "all"
+        errors = []
+        config._apply_display({"trace": "sometimes"}, errors)
+        assert len(errors) == 1
+    finally:
+        traces.override = None
+        config.display.update(config.DEFAULT_DISPLAY)
+
+
+ALL_TESTS = [
+    test_tree,
+    test_names,
+    test_errors,
+    test_failures,
+    test_all,
+    test_off,
+    test_config,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,249 @@
+#!/usr/bin/env python3
+"""Tests for oneil.tune, the JSON-RPC protocol for tuning a model's inputs live.
+
+Run directly:
+
+    python test/test_tune.py
+
+Or:
+
+    pytest test/test_tune.py
+"""
+
+import io
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import source_overrides, tune  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+ENGINE = """Thrust: F = 10 :N
+"""
+
+CRAFT = """use engine as e
+
+Mass(1, 100): m = 4 :kg
+Acceleration: a = F.e/m :m/s^2
+Drag: D = 2 :N
+
+test "fast": a*m > D
+"""
+
+
+def _directory():
+    directory = helpers.workspace({"engine.on": ENGINE, "craft.on": CRAFT})
+    return directory
+
+
+def _request(session, ID, method, **params):
+    return session.handle(json.dumps({"jsonrpc": "2.0", "id": ID, "method": method, "params": params}))
+
+
+def test_session():
+    with working_directory(_directory()):
+        session = tune.Session("craft")
+        [response] = _request(session, 1, "initialize")
+        result = response["result"]
+        assert result["protocol"] == tune.PROTOCOL_VERSION and result["model"] == "craft"
+        inputs = {entry["id"]: entry for entry in result["inputs"]}
+        assert set(inputs) == {"m", "D", "F.e"}, inputs
+        assert inputs["m"]["limits"] == {"min": 1, "max": 100}, inputs["m"]
+
+        [response] = _request(session, 2, "subscribe", id="a")
+        assert response["result"]["value"]["min"] == 2.5
+
+        messages = _request(session, 3, "setInput", id="m", value=[2, 5])
+        assert messages[0]["id"] == 3
+        changes = messages[0]["result"]["changes"]
+        assert [(change["id"], change["new"]["min"]) for change in changes["parameters"]] == [("m", 2), ("a", 2)], changes
+        assert messages[1] == {"jsonrpc": "2.0", "method": "parameterChanged", "params": {"id": "a", "value": session.model.parameters["a"].result()}}
+        assert session.model.parameters["a"].max == 5
+
+        # Values that don't change aren't notified.
+        messages = _request(session, 4, "setInput", id="D", value="3 :N")
+        assert len(messages) == 1 and messages[0]["result"]["changes"]["parameters"][0]["id"] == "D"
+
+        [response] = _request(session, 5, "getOutputs", ids=["a", "F.e"])
+        assert response["result"]["outputs"]["F.e"]["min"] == 10
+        assert session.model.overrides == ["m=2|5 :kg", "D=3 :N"], session.model.overrides
+
+
+def test_errors():
+    with working_directory(_directory()):
+        session = tune.Session("craft")
+        _request(session, 1, "setInput", id="m", value=8)
+
+        [response] = _request(session, 2, "setInput", id="m", value=500)
+        assert response["error"]["code"] == tune.MODEL_ERROR
+        assert response["error"]["data"]["kind"] == "LimitError", response
+        assert response["error"]["data"]["codeDescription"]["href"].endswith("errors.md#e0302"), response
+        assert session.model.parameters["m"].min == 8, "the model keeps the inputs that worked"
+
+        [response] = _request(session, 3, "setInput", id="x", value=1)
+        assert response["error"]["code"] == tune.INVALID_PARAMS
+        [response] = _request(session, 4, "launch")
+        assert response["error"]["code"] == tune.METHOD_NOT_FOUND
+        [response] = session.handle("{not json")
+        assert response["error"]["code"] == tune.PARSE_ERROR and response["id"] is None
+        assert session.handle(json.dumps({"jsonrpc": "2.0", "method": "launch"})) == [], "notifications get no response"
+
+
+def test_editor_requests():
+    with working_directory(_directory()):
+        session = tune.Session("craft")
+        [response] = _request(session, 1, "oneil/modelTree")
+        tree = response["result"]
+        assert (tree["model"], tree["file"], tree["tests"]) == ("craft", "craft.on", 1), tree
+        assert [p["id"] for p in tree["parameters"]] == ["m", "a", "D"]
+        assert tree["parameters"][1]["source"] == {"file": "craft.on", "line": 4, "start_column": 15, "end_column": 16}, tree["parameters"][1]
+        [engine] = tree["submodels"]
+        assert (engine["symbol"], engine["file"], engine["submodels"]) == ("e", "engine.on", [])
+
+        _request(session, 2, "setInput", id="m", value=5)
+        with open("craft.on", "a") as f:
+            f.write("Jerk: j = 2*a :m/s^2\n")
+        [response] = _request(session, 3, "oneil/evaluate")
+        assert "j" not in response["result"]["results"]["parameters"]
+        [response] = _request(session, 4, "oneil/evaluate", reload=True)
+        results = response["result"]["results"]
+        assert results["parameters"]["j"]["min"] == 4, "reloading keeps the inputs set so far"
+        assert results["parameters"]["j"]["source"]["line"] == 8
+
+
+def test_hover():
+    with working_directory(_directory()):
+        session = tune.Session("craft")
+        [response] = _request(session, 1, "oneil/hover", line=3, character=22)
+        hover = response["result"]
+        assert hover["contents"]["value"].startswith("**Mass** (`m`)\n\nUnits: `kg`\n\nValue: `4"), hover
+        assert hover["range"] == {"start": {"line": 3, "character": 22}, "end": {"line": 3, "character": 23}}
+        [response] = _request(session, 2, "oneil/hover", line=3, character=20)
+        assert response["result"]["contents"]["value"].startswith("**Thrust** (`F.e`)"), "submodel parameters are one ID"
+        assert response["result"]["range"]["start"]["character"] == 18
+        [response] = _request(session, 3, "oneil/hover", line=3, character=2)
+        assert response["result"] is None, "labels aren't parameters"
+        [response] = _request(session, 4, "oneil/hover", line=40, character=0)
+        assert response["result"] is None
+
+
+def test_speculate():
+    with working_directory(_directory()):
+        session = tune.Session("craft")
+        change = {"range": {"start": {"line": 3, "character": 18}, "end": {"line": 3, "character": 18}}, "text": "2*"}
+        try:
+            [response] = _request(session, 1, "oneil/didChange", changes=[change])
+            [speculative] = [result for result in response["result"]["speculative"] if result["id"] == "a"]
+            assert speculative == {"id": "a", "line": 4, "value": "5.0 m/s^2", "error": None}, speculative
+            with open("craft.on") as f:
+                assert "2*F.e" not in f.read()
+            assert session.model.parameters["a"].min == 2.5, "the model isn't changed"
+
+            change = {"range": {"start": {"line": 3, "character": 18}, "end": {"line": 3, "character": 25}}, "text": "F.e*1"}
+            [response] = _request(session, 2, "oneil/didChange", changes=[change])
+            assert "do not match" in response["result"]["speculative"][-1]["error"]
+            [response] = _request(session, 3, "oneil/speculate", line=0)
+            assert response["result"] is None, "only parameters are evaluated"
+        finally:
+            source_overrides.clear()
+
+
+def test_formatting():
+    with working_directory(_directory()):
+        session = tune.Session("craft")
+        try:
+            [response] = _request(session, 1, "textDocument/formatting", options={"tabSize": 4, "insertSpaces": True})
+            [edit] = response["result"]
+            assert edit["range"] == {"start": {"line": 0, "character": 0}, "end": {"line": 7, "character": 0}}, edit
+            assert "Mass (1, 100): m = 4 :kg" in edit["newText"], edit["newText"]
+            with open("craft.on") as f:
+                assert f.read() == CRAFT, "formatting doesn't write the file"
+
+            _request(session, 2, "oneil/didChange", changes=[{"text": edit["newText"]}])
+            [response] = _request(session, 3, "textDocument/formatting")
+            assert response["result"] == [], "the edited text is formatted"
+        finally:
+            source_overrides.clear()
+
+
+def test_check_units():
+    with working_directory(_directory()):
+        session = tune.Session("craft")
+        try:
+            [response] = _request(session, 1, "oneil/checkUnits")
+            assert response["result"] == {"diagnostics": [], "checked": ["a", 'test "fast"']}, response
+            [response] = _request(session, 2, "oneil/checkUnits")
+            assert response["result"]["checked"] == [], "nothing changed"
+
+            _request(session, 3, "oneil/didChange", changes=[{"range": {"start": {"line": 7, "character": 0}, "end": {"line": 7, "character": 0}}, "text": "Jerk: j = 2*a :m/s^2\n"}])
+            [response] = _request(session, 4, "oneil/checkUnits")
+            assert response["result"]["checked"] == ["j"], response
+
+            # Changing m's units checks what uses m again, but not j, which uses a
+            _request(session, 5, "oneil/didChange", changes=[{"range": {"start": {"line": 2, "character": 21}, "end": {"line": 2, "character": 23}}, "text": "s"}])
+            [response] = _request(session, 6, "oneil/checkUnits")
+            result = response["result"]
+            assert result["checked"] == ["a", 'test "fast"'], result
+            assert [(error["kind"], error["line"]) for error in result["diagnostics"]] == [("UnitEvaluationError", 4), ("UnitEvaluationError", None)], result["diagnostics"]
+            with open("craft.on") as f:
+                assert f.read() == CRAFT, "checking doesn't write the file"
+        finally:
+            source_overrides.clear()
+
+
+def test_run():
+    with working_directory(_directory()):
+        stdin = io.StringIO("\n".join(json.dumps(message) for message in [
+            {"jsonrpc": "2.0", "id": 1, "method": "getOutputs", "params": {"ids": ["a"]}},
+            {"jsonrpc": "2.0", "id": 2, "method": "shutdown"},
+            {"jsonrpc": "2.0", "id": 3, "method": "getOutputs"},
+        ]) + "\n")
+        stdout = io.StringIO()
+        tune.run("craft", stdin=stdin, stdout=stdout)
+    lines = [json.loads(line) for line in stdout.getvalue().splitlines()]
+    assert [line["id"] for line in lines] == [1, 2], "nothing is answered after shutdown"
+    assert lines[0]["result"]["outputs"]["a"]["units"] == "m/s^2", lines[0]
+
+
+ALL_TESTS = [
+    test_session,
+    test_errors,
+    test_editor_requests,
+    test_hover,
+    test_speculate,
+    test_formatting,
+    test_check_units,
+    test_run,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
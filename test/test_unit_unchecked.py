@@ -0,0 +1,150 @@
+#!/usr/bin/env python3
+"""Tests for @unit_unchecked: turning off unit checking for one parameter, with the reason
+recorded in the summary, LaTeX exports, and lint notes.
+
+Run directly:
+
+    python test/test_unit_unchecked.py
+
+Or:
+
+    pytest test/test_unit_unchecked.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import Model, check, lint  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+PLATE = """\
+Temperature difference: dT = 30 :K
+Plate height: D = 0.5 :m
+
+@unit_unchecked("empirical fit to laminar free convection in air, in SI units")
+$ Convection coefficient: h = 1.32*(dT/D)^0.25 :W/m^2/K
+
+@unit_unchecked("fit")
+Piecewise fit: p = {dT + D if dT > 10 :W/m^2/K
+                   {2*D if dT <= 10
+
+@unit_unchecked("fit")
+Range fit: r = min(dT, D) | max(dT + D, D) :m
+Heat flux: q = h*dT :W/m^2
+test: q > 0 :W/m^2
+"""
+
+CRAFT = """\
+use plate as p
+Twice the coefficient: H = 2*h.p :W/m^2/K
+"""
+
+
+def _model(files, name, build=True):
+    directory = helpers.workspace(files)
+    with working_directory(directory), contextlib.redirect_stdout(io.StringIO()):
+        model = Model(name)
+        if build:
+            model.build(quiet=True)
+    return model, directory
+
+
+def test_parse():
+    assert oneil.parse_annotation("@unit_unchecked(\"mixed units\")") == ("unit_unchecked", ("mixed units",))
+    for line in ("@unit_unchecked", "@unit_unchecked(\"\")", "@unit_unchecked(1)", "@unit_unchecked(\"a\", \"b\")"):
+        try:
+            oneil.parse_annotation(line)
+        except ValueError as e:
+            assert "takes the reason the units aren't checked" in str(e), e
+        else:
+            assert False, f"Expected a ValueError for {line}"
+
+
+def test_evaluation():
+    model, _ = _model({"plate.on": PLATE}, "plate.on")
+    h = model.parameters["h"]
+    assert abs(h.min - 1.32 * 60 ** 0.25) < 1e-9, h.min
+    assert h.units == {"kg": 1, "s": -3, "K": -1}, h.units
+    assert model.parameters["p"].min == 30.5
+    assert (model.parameters["r"].min, model.parameters["r"].max) == (0.5, 30.5)
+    assert model.tests[0].passed
+
+
+def test_checked_without_annotation():
+    model, _ = _model({"plate.on": PLATE.replace("@unit_unchecked(\"empirical fit to laminar free convection in air, in SI units\")\n", "")}, "plate.on", build=False)
+    errors = check.unit_errors(model)
+    assert [error.context_.id for error in errors] == ["h"], errors
+
+
+def test_static_check():
+    model, _ = _model({"plate.on": PLATE}, "plate.on", build=False)
+    assert check.unit_errors(model) == []
+
+
+def test_users_checked():
+    # The parameter's users are still checked against its declared units
+    model, _ = _model({"plate.on": PLATE.replace("q = h*dT :W/m^2", "q = h*dT :W")}, "plate.on", build=False)
+    assert [error.context_.id for error in check.unit_errors(model)] == ["q"]
+
+
+def test_report():
+    model, _ = _model({"plate.on": PLATE, "craft.on": CRAFT}, "craft.on")
+    assert model.unchecked_units()[0] == {"id": "h", "model": "plate", "reason": "empirical fit to laminar free convection in air, in SI units"}
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        model.summarize()
+    assert "Units not checked:" in output.getvalue(), output.getvalue()
+    assert "  h in plate: empirical fit to laminar free convection in air, in SI units" in output.getvalue(), output.getvalue()
+
+
+def test_lint():
+    model, _ = _model({"plate.on": PLATE}, "plate.on", build=False)
+    notes = [warning for warning in lint.lint_model(model) if warning.lint == "unit-unchecked"]
+    assert [(note.line_no, note.severity) for note in notes] == [(5, "note"), (8, "note"), (12, "note")], notes
+    assert notes[0].message_ == "The units of h aren't checked: empirical fit to laminar free convection in air, in SI units.", notes[0].message_
+
+
+ALL_TESTS = [
+    test_parse,
+    test_evaluation,
+    test_checked_without_annotation,
+    test_static_check,
+    test_users_checked,
+    test_report,
+    test_lint,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
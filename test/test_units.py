@@ -164,6 +164,23 @@ def test_aliases():
     print("  ok")
 
 
+def test_long_names():
+    _section("Lowercase, prefixed, and hyphenated names (newton-meters, kilowatts)")
+    for name, symbol in [("seconds", "s"), ("newton", "N"), ("Kelvin", "K"), ("kilowatts", "kW"),
+                         ("millimeters", "mm"), ("kilowatt-hours", "kWh"), ("newton-meters", "N*m"),
+                         ("kg/newton-meters", "kg/N/m"), ("meters/sec^2", "m/s^2"), ("dBmilliwatts", "dBmW")]:
+        assert un.canonical(name) == symbol, f"{name}: {un.canonical(name)}"
+        units, fx = un.parse(name)
+        expected_units, expected_fx = un.parse(symbol)
+        assert units == expected_units, name
+        assert_close(fx(1.0), expected_fx(1.0), label=name)
+    # Symbols are never renamed, even when they match a name in lowercase (kn is a knot).
+    assert un.canonical("kN") == "kN"
+    assert un.canonical("Mm") == "Mm"
+    assert_raises(ValueError, un.parse, "widget-meters")
+    print("  ok")
+
+
 # ---------------------------------------------------------------------------
 # Compound units
 # ---------------------------------------------------------------------------
@@ -235,6 +252,17 @@ def test_db_dimensionless():
     print("  ok")
 
 
+def test_dbm():
+    _section("dBm is dB relative to a meter, dBmW to a milliwatt, and dB units are logarithmic")
+    assert un.canonical("dBm") == "dBm" and un.parse("dBm")[0] == {"m": 1}
+    assert un.canonical("dBmW/Hz") == "dBmW/Hz"
+    assert_close(un.parse("dBmW")[1](30.0), 1.0, label="30 dBmW")
+    assert_close(un._find_derived_unit({"kg": 1, "m": 2, "s": -3}, 1.0, "dBmW")[0], 30.0, label="1 W in dBmW")
+    assert un.logarithmic("dBmW") and un.logarithmic("dB") and un.logarithmic("dBW/Hz")
+    assert not un.logarithmic("W") and not un.logarithmic("") and not un.logarithmic(None)
+    print("  ok")
+
+
 # ---------------------------------------------------------------------------
 # dB on compound units (NEW behavior the fix introduces)
 # ---------------------------------------------------------------------------
@@ -354,6 +382,36 @@ def test_display_db_compound_zero_value():
     print("  ok")
 
 
+# ---------------------------------------------------------------------------
+# Temperatures: affine units and temperature differences
+# ---------------------------------------------------------------------------
+
+def test_affine_temperatures():
+    _section("°C and °F convert to K with an offset")
+    units, fx = un.parse("°C")
+    assert units == {"K": 1}
+    assert_close(fx(0), 273.15, label="0 °C")
+    assert_close(un.parse("degF")[1](32), 273.15, label="32 degF")
+    assert_close(un.parse("°F")[1](-40), un.parse("°C")[1](-40), label="-40 °F == -40 °C")
+    assert un.canonical("Celsius") == "°C" and un.canonical("degF") == "°F"
+    # Absolute temperatures can't be combined with other units.
+    assert_raises(ValueError, un.parse, "W/°C")
+    print("  ok")
+
+
+def test_temperature_differences():
+    _section("Δ°C, Δ°F, and ΔK convert by scaling alone, also in compound units")
+    assert_close(un.parse("Δ°F")[1](9), 5.0, label="9 Δ°F")
+    assert_close(un.parse("delta-degC")[1](1), 1.0, label="1 delta-degC")
+    units, fx = un.parse("W/Δ°F")
+    assert units == {"kg": 1, "m": 2, "s": -3, "K": -1}, units
+    assert_close(fx(1), 1.8, label="1 W/Δ°F")
+    assert un.temperature("°F") == "absolute" and un.temperature("degC") == "absolute"
+    assert un.temperature("Δ°C") == "difference" and un.temperature("delta-K") == "difference"
+    assert un.temperature("K") is None and un.temperature("") is None
+    print("  ok")
+
+
 # ---------------------------------------------------------------------------
 # Error handling
 # ---------------------------------------------------------------------------
@@ -436,6 +494,9 @@ def test_roundtrips():
     _roundtrip("dBW/Hz", 0.0)
     _roundtrip("dBW/Hz", -174.0)  # the canonical kTB noise floor reference
     _roundtrip("dBmW/Hz", -120.5)
+    _roundtrip("°C", -17.5)
+    _roundtrip("degF", 98.6)
+    _roundtrip("Δ°F", 12.0)
     print("  ok")
 
 
@@ -450,11 +511,15 @@ ALL_TESTS = [
     test_legacy_units,
     test_dimensionless_units,
     test_aliases,
+    test_long_names,
     test_compound_units_basic,
     test_db_simple,
     test_db_dimensionless,
+    test_dbm,
     test_db_compound_psd,
     test_db_compound_with_exponent,
+    test_affine_temperatures,
+    test_temperature_differences,
     test_display_simple_pref,
     test_display_db_simple,
     test_display_compound_pref,
@@ -0,0 +1,162 @@
+#!/usr/bin/env python3
+"""Tests for warning severities, the unused import and shadowed builtin warnings, and
+--deny-warnings.
+
+Run directly:
+
+    python test/test_warnings.py
+
+Or:
+
+    pytest test/test_warnings.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+import oneil  # noqa: E402
+from oneil import Model, console  # noqa: E402
+from oneil.errors import SEVERITIES  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+HEAT = """\
+def conduction(k, A, dT, L):
+    return k*A*dT/L
+
+def radiation(e, A, T):
+    return e*A*5.67e-8*T**4
+"""
+
+
+def _load(files, name="model.on"):
+    directory = helpers.workspace(files)
+    output = io.StringIO()
+    with working_directory(directory), contextlib.redirect_stdout(output):
+        model = Model(name)
+    return model, output.getvalue()
+
+
+def _printed(warning, deny=False):
+    console.exit_code = console.EXIT_SUCCESS
+    console.deny_warnings = deny
+    try:
+        with contextlib.redirect_stdout(io.StringIO()):
+            console.print_warning(warning)
+        return console.exit_code
+    finally:
+        console.deny_warnings = False
+        console.exit_code = console.EXIT_SUCCESS
+
+
+def test_severities():
+    assert oneil.SyntaxError("model.on", 1, "Mass m = 2", "bad").severity == "error"
+    assert oneil.FeatureWarning("model.on", 1, "experimental").severity == "warning"
+    assert oneil.LintWarning("model.on", 1, "spelling", "typo", severity="note").severity == "note"
+    assert set(SEVERITIES) == {"error", "warning", "note"}
+
+
+def test_unused_import():
+    model = (
+        "import heat_unused\n"
+        "Area: A = 2 :m^2\n"
+        "Temperature: T = 300 :K\n"
+    )
+    _, output = _load({"heat_unused.py": HEAT, "model.on": model})
+    assert "UnusedImportWarning[W0009]" in output, output
+    assert "heat_unused is imported, but no parameter calls its functions." in output, output
+
+
+def test_used_import():
+    model = (
+        "import heat_used\n"
+        "Conductivity: k = 400 :W/m/K\n"
+        "Area: A = 2 :m^2\n"
+        "Temperature difference: dT = 10 :K\n"
+        "Length: L = 1 :m\n"
+        "Heat flow: Q = conduction(k, A, dT, L) :W\n"
+    )
+    _, output = _load({"heat_used.py": HEAT, "model.on": model})
+    assert "UnusedImportWarning" not in output, output
+
+
+def test_shadowed_function():
+    _, output = _load({"model.on": "Minimum: min = 2 :m\nLength: L = 3 :m\n"})
+    assert "ShadowedBuiltinWarning[W0010]" in output, output
+    assert "min has the name of the builtin function min()" in output, output
+
+
+def test_shadowed_constant():
+    _, output = _load({"model.on": "Euler number: e = 3\nDouble: d = 2*e\n"})
+    assert "ShadowedBuiltinWarning[W0010]" in output, output
+    assert "equations that use e get the constant, not the parameter" in output, output
+    assert "Rename e" in output, output
+
+
+def test_no_warnings():
+    _, output = _load({"model.on": "Mass: m = 2 :kg\nWeight: W = m*9.81 :N\n"})
+    assert "Warning" not in output, output
+
+
+def test_deny_warnings():
+    warning = oneil.ShadowedBuiltinWarning("model.on", 1, "pi has the name of a builtin constant.")
+    assert _printed(warning) == console.EXIT_SUCCESS
+    assert _printed(warning, deny=True) == console.EXIT_WARNINGS
+
+
+def test_notes_not_denied():
+    note = oneil.LintWarning("model.on", 1, "spelling", "typo", severity="note")
+    assert _printed(note, deny=True) == console.EXIT_SUCCESS
+
+
+def test_deny_warnings_flag():
+    assert oneil.parse_output_flags(["--deny-warnings", "check"]) == ["check"]
+    assert console.deny_warnings
+    oneil.parse_output_flags([])
+    assert not console.deny_warnings
+
+
+ALL_TESTS = [
+    test_severities,
+    test_unused_import,
+    test_used_import,
+    test_shadowed_function,
+    test_shadowed_constant,
+    test_no_warnings,
+    test_deny_warnings,
+    test_notes_not_denied,
+    test_deny_warnings_flag,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,120 @@
+#!/usr/bin/env python3
+"""Tests for oneil.watch.diff, the changes between two sets of model results.
+
+Run directly:
+
+    python test/test_watch.py
+
+Or:
+
+    pytest test/test_watch.py
+"""
+
+import copy
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import watch  # noqa: E402
+
+
+def _results():
+    return {
+        "model": "craft",
+        "design": "default",
+        "parameters": {
+            "L": {"name": "Length", "min": 3.0, "max": 3.0, "units": "m"},
+            "W": {"name": "Width", "min": 2.0, "max": 4.0, "units": "m"},
+        },
+        "tests": {"passed": 1, "total": 1, "results": [{"expression": "L < W", "line": 4, "passed": True}]},
+        "submodels": {
+            "th": {
+                "model": "thermal",
+                "design": "default",
+                "parameters": {"T": {"name": "Temperature", "min": 300.0, "max": 300.0, "units": "K"}},
+                "tests": {"passed": 0, "total": 0, "results": []},
+                "submodels": {},
+            },
+        },
+    }
+
+
+def test_no_changes():
+    changes = watch.diff(_results(), _results())
+    assert not any(changes.values())
+
+
+def test_threshold():
+    new = _results()
+    new["parameters"]["L"]["max"] = 3.01
+    assert watch.diff(_results(), new, threshold=0.01)["parameters"] == []
+
+    changes = watch.diff(_results(), new)["parameters"]
+    assert [(c["model"], c["id"]) for c in changes] == [("craft", "L")]
+    assert changes[0]["old"]["max"] == 3.0 and changes[0]["new"]["max"] == 3.01
+
+
+def test_units_change():
+    new = _results()
+    new["parameters"]["L"]["units"] = "km"
+    assert [c["id"] for c in watch.diff(_results(), new, threshold=1.0)["parameters"]] == ["L"]
+
+
+def test_added_and_removed():
+    new = _results()
+    del new["parameters"]["W"]
+    new["submodels"]["th"]["parameters"]["Q"] = {"name": "Heat", "min": 1.0, "max": 1.0, "units": "W"}
+    changes = watch.diff(_results(), new)
+    assert [(c["model"], c["id"]) for c in changes["removed"]] == [("craft", "W")]
+    assert [(c["model"], c["id"]) for c in changes["added"]] == [("th", "Q")]
+
+
+def test_flipped_tests():
+    new = copy.deepcopy(_results())
+    new["tests"]["results"][0]["passed"] = False
+    changes = watch.diff(_results(), new)["tests"]
+    assert [(c["expression"], c["old"], c["new"]) for c in changes] == [("L < W", "pass", "fail")]
+
+
+def test_parse_threshold():
+    assert watch.parse_threshold("1%") == 0.01
+    assert watch.parse_threshold("0.05") == 0.05
+
+
+ALL_TESTS = [
+    test_no_changes,
+    test_threshold,
+    test_units_change,
+    test_added_and_removed,
+    test_flipped_tests,
+    test_parse_threshold,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,94 @@
+#!/usr/bin/env python3
+"""Tests for oneil.workspace, starting a workspace with `oneil init`.
+
+Run directly:
+
+    python test/test_workspace.py
+
+Or:
+
+    pytest test/test_workspace.py
+"""
+
+import contextlib
+import io
+import os
+import sys
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), "..", "src"))
+
+from oneil import Model, fixtures, read_manifest, scaffold, syntax, workspace  # noqa: E402
+from oneil.stats import working_directory  # noqa: E402
+import helpers  # noqa: E402
+
+
+def test_init():
+    directory = os.path.join(helpers.workspace(), "spacecraft")
+    created, kept = workspace.init(directory)
+    assert kept == [] and set(created) == set(workspace._files("spacecraft"))
+    assert os.path.isdir(os.path.join(directory, "models", "tests"))
+
+    manifest = os.path.join(directory, workspace.MANIFEST)
+    assert read_manifest(manifest) == [os.path.join(directory, "models", "example.on")]
+    with open(manifest) as f:
+        assert syntax.to_manifest(syntax.DEFAULT_SETTINGS) in f.read().splitlines()
+    assert scaffold.templates([manifest])["component"][0] == "From ../templates/component.on."
+
+    with working_directory(os.path.join(directory, "models")):
+        model = Model("example.on")
+        model.build(quiet=True)
+    assert model.test() == (0, 1)
+
+
+def test_fixture():
+    directory = helpers.workspace()
+    workspace.init(directory)
+    output = io.StringIO()
+    with contextlib.redirect_stdout(output):
+        fixtures.run(None, [], [os.path.join(directory, workspace.MANIFEST)])
+    assert "tests/example.on | 2/2" in output.getvalue(), output.getvalue()
+
+
+def test_keeps_files():
+    directory = helpers.workspace({"README.md": "Ours.\n"})
+    created, kept = workspace.init(directory, "craft")
+    assert kept == ["README.md"] and "README.md" not in created
+    with open(os.path.join(directory, "README.md")) as f:
+        assert f.read() == "Ours.\n"
+    created, kept = workspace.init(directory)
+    assert created == [] and len(kept) == len(workspace._files("craft"))
+
+
+ALL_TESTS = [
+    test_init,
+    test_fixture,
+    test_keeps_files,
+]
+
+
+def main():
+    failures = []
+    for t in ALL_TESTS:
+        try:
+            t()
+        except AssertionError as e:
+            failures.append((t.__name__, repr(e)))
+            print(f"  FAIL: {t.__name__}: {e}")
+        except Exception as e:  # noqa: BLE001
+            failures.append((t.__name__, repr(e)))
+            print(f"  ERROR: {t.__name__}: {type(e).__name__}: {e}")
+
+    print("\n" + "=" * 70)
+    if failures:
+        print(f"FAILED: {len(failures)} / {len(ALL_TESTS)}")
+        for name, msg in failures:
+            print(f"  - {name}: {msg}")
+        print("=" * 70)
+        return 1
+    print(f"PASSED: {len(ALL_TESTS)} / {len(ALL_TESTS)}")
+    print("=" * 70)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
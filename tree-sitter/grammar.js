@@ -0,0 +1,150 @@
+// Generated by `oneil grammar` from the Oneil parser. Don't edit it by hand: change the
+// parser and run `oneil grammar tree-sitter` to regenerate it.
+
+module.exports = grammar({
+  name: 'oneil',
+
+  extras: $ => [/[ \t]/],
+
+  word: $ => $.identifier,
+
+  conflicts: $ => [[$.parameter]],
+
+  rules: {
+    source_file: $ => seq(repeat($._item), optional($._statement)),
+
+    _item: $ => choice(seq($._statement, $._newline), $.notes, $._newline),
+
+    _newline: $ => /\r?\n/,
+
+    _statement: $ => choice(
+      $.comment,
+      $.attribute,
+      $.annotation,
+      $.use_declaration,
+      $.from_declaration,
+      $.import_declaration,
+      $.include_declaration,
+      $.section,
+      $.unit_declaration,
+      $.test,
+      $.alias,
+      $.design,
+      $.parameter,
+    ),
+
+    // Indented lines that aren't piecewise branches
+    notes: $ => prec.right(repeat1(seq($.note, $._newline))),
+    note: $ => token(seq(/[ \t]+/, /[^\s{#][^\n]*/)),
+
+    comment: $ => token(prec(1, seq('#', /[^\n]*/))),
+    attribute: $ => token(prec(2, seq('#[cfg', /[^\n]*/))),
+
+    annotation: $ => seq(field('name', alias(token(prec(1, /@\w*/)), $.annotation_name)), optional(field('arguments', alias(/[^\s][^\n]*/, $.text)))),
+
+    use_declaration: $ => seq(alias(token(prec(1, seq("use", /[ \t]/))), 'use'), field('model', $.module), optional($.submodel_inputs), 'as', field('symbol', $.submodel)),
+    from_declaration: $ => seq(alias(token(prec(1, seq("from", /[ \t]/))), 'from'), field('source', $.module), alias(token(prec(1, seq("use", /[ \t]/))), 'use'), field('model', $.module), optional($.submodel_inputs), 'as', field('symbol', $.submodel)),
+    import_declaration: $ => seq(alias(token(prec(1, seq("import", /[ \t]/))), 'import'), field('module', $.module)),
+    include_declaration: $ => seq(alias(token(prec(1, seq("include", /[ \t]/))), 'include'), field('path', $.string)),
+    section: $ => seq(alias(token(prec(1, seq("section", /[ \t]/))), 'section'), field('name', alias(/[^\s][^\n]*/, $.label))),
+    unit_declaration: $ => seq(alias(token(prec(1, seq("unit", /[ \t]/))), 'unit'), field('symbol', alias($.identifier, $.definition)), '=', field('value', $.number), optional(field('units', $.units))),
+
+    module: $ => /\w+(\.\w+)*/,
+    submodel: $ => /\w+/,
+    submodel_inputs: $ => seq('(', sep1(seq(field('input', $.identifier), '=', field('value', $._expression)), ','), ')'),
+
+    test: $ => seq(
+      optional($.trace),
+      alias(token(prec(1, "test")), 'test'),
+      optional(field('name', $.string)),
+      optional(seq('{', sep1(field('input', $.identifier), ','), '}')),
+      ':',
+      field('expression', $._expression),
+    ),
+
+    alias: $ => seq(
+      optional(seq(field('label', $.label), ':')),
+      field('id', alias($.label, $.definition)),
+      ':=',
+      field('target', $._expression),
+    ),
+
+    design: $ => seq(
+      optional($.trace),
+      field('id', alias($.label, $.definition)),
+      choice('=', '=>'),
+      field('value', $._value),
+      optional($._units),
+    ),
+
+    parameter: $ => seq(
+      optional($.performance),
+      optional($.trace),
+      field('label', $.label),
+      optional(choice($.limits, $.options)),
+      ':',
+      field('id', $.definition),
+      choice('=', '=>'),
+      field('value', $._value),
+      optional(seq('?', field('fallback', $._value))),
+      optional($._units),
+      repeat(seq($._newline, $.piecewise_branch)),
+    ),
+
+    performance: $ => '$',
+    trace: $ => choice('*', '**'),
+    label: $ => /[^\s#@:=$*(\[{][^:=\n(\[]*/,
+    definition: $ => $.identifier,
+    limits: $ => seq('(', field('min', $._expression), ',', field('max', $._expression), ')'),
+    options: $ => seq('[', sep1($.identifier, ','), ']'),
+
+    _units: $ => seq(':', field('units', $.units)),
+    units: $ => /[^\s][^\n]*/,
+
+    _value: $ => choice($.interval, $.interval_literal, $.piecewise_branch, $._expression),
+    interval: $ => seq(field('min', $._expression), '|', field('max', $._expression)),
+    interval_literal: $ => seq('[', field('min', $._expression), ',', field('max', $._expression), ']'),
+    piecewise_branch: $ => seq('{', field('value', $._expression), 'if', field('condition', $._expression)),
+
+    _expression: $ => choice(
+      $.number,
+      $.string,
+      $.constant,
+      $.identifier,
+      $.submodel_reference,
+      $.call,
+      $.parenthesized,
+      $.unary,
+      $.binary,
+    ),
+
+    parenthesized: $ => seq('(', $._expression, ')'),
+    call: $ => prec(16, seq(field('function', alias($.identifier, $.function)), '(', optional(sep1($._expression, ',')), ')')),
+    submodel_reference: $ => prec(16, seq(field('id', $.identifier), '.', field('symbol', alias($.identifier, $.submodel)))),
+
+    unary: $ => choice(
+      prec(5, seq(field('operator', "not"), field('operand', $._expression))),
+      prec(13, seq(field('operator', "-"), field('operand', $._expression))),
+    ),
+
+    binary: $ => choice(
+      ...["or"].map(op => prec.left(2, seq(field('left', $._expression), field('operator', op), field('right', $._expression)))),
+      ...["and"].map(op => prec.left(4, seq(field('left', $._expression), field('operator', op), field('right', $._expression)))),
+      ...["<", ">", "<=", ">=", "==", "!="].map(op => prec.left(6, seq(field('left', $._expression), field('operator', op), field('right', $._expression)))),
+      ...["--"].map(op => prec.left(8, seq(field('left', $._expression), field('operator', op), field('right', $._expression)))),
+      ...["+", "-"].map(op => prec.left(10, seq(field('left', $._expression), field('operator', op), field('right', $._expression)))),
+      ...["*", "/", "//", "%"].map(op => prec.left(12, seq(field('left', $._expression), field('operator', op), field('right', $._expression)))),
+      ...["**", "^"].map(op => prec.right(14, seq(field('left', $._expression), field('operator', op), field('right', $._expression)))),
+    ),
+
+    // Builtin functions: abs, acos, asin, atan, ceiling, cos, cosh, decibels, extent, floor, lerp, linear, ln, log, log10, log2, map_range, max, mid, min, mnmx, mxmn, range, sign, sin, sinh, sqrt, strip, tan, tanh
+    constant: $ => choice("e", "inf", "pi"),
+    number: $ => /(\d+\.?\d*|\.\d+)([eE][-+]?\d+)?/,
+    string: $ => choice(/'[^'\n]*'/, /"[^"\n]*"/),
+    identifier: $ => /[A-Za-z_]\w*/,
+  },
+});
+
+function sep1(rule, separator) {
+  return seq(rule, repeat(seq(separator, rule)));
+}
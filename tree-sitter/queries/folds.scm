@@ -0,0 +1,5 @@
+; Generated by `oneil grammar` from the Oneil parser. Don't edit it by hand: change the
+; parser and run `oneil grammar tree-sitter` to regenerate it.
+
+(notes) @fold
+(parameter) @fold
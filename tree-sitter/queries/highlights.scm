@@ -0,0 +1,28 @@
+; Generated by `oneil grammar` from the Oneil parser. Don't edit it by hand: change the
+; parser and run `oneil grammar tree-sitter` to regenerate it.
+
+(comment) @comment
+(attribute) @attribute
+(annotation_name) @attribute
+(text) @string.special
+(note) @comment.documentation
+
+["use" "from" "import" "include" "section" "unit" "test" "as" "if" "and" "or" "not"] @keyword
+
+(label) @string
+(definition (identifier) @variable)
+(identifier) @variable
+(submodel) @module
+(module) @module
+((function (identifier) @function.builtin)
+ (#any-of? @function.builtin "abs" "acos" "asin" "atan" "ceiling" "cos" "cosh" "decibels" "extent" "floor" "lerp" "linear" "ln" "log" "log10" "log2" "map_range" "max" "mid" "min" "mnmx" "mxmn" "range" "sign" "sin" "sinh" "sqrt" "strip" "tan" "tanh"))
+(function (identifier) @function)
+(constant) @constant.builtin
+(number) @number
+(string) @string
+(units) @type
+
+["<" ">" "<=" ">=" "==" "!=" "--" "+" "-" "*" "/" "//" "%" "**" "^"] @operator
+(performance) @operator
+(trace) @operator
+[":" "=" ":=" "=>" "(" ")" "[" "]" "{" "}" "," "|" "?" "."] @punctuation.delimiter
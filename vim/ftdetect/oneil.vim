@@ -1 +1 @@
-au BufRead,BufNewFile *.on set filetype=oneil
+au BufRead,BufNewFile *.on,*.oni set filetype=oneil
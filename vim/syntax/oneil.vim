@@ -48,7 +48,7 @@ syn match oneilUnit /\%(=.*:\)\@<=.*$/ contained skipwhite
 "  properly constructed parameter are missing or malformed, error will show
 "  through
 syn match oneilParameterKeys /[:=]/ contained
-syn region oneilParameter start=/\_^\v(\n)@!(#)@!(use)@!(import)@!(test)@!(section)@!(\s)@![^:=]*:/ end=/\_$\n/ contains=oneilParameterPreamble,oneilAssignment,oneilUnit,oneilID,oneilParameterKeys
+syn region oneilParameter start=/\_^\v(\n)@!(#)@!(\@)@!(use)@!(import)@!(test)@!(section)@!(\s)@![^:=]*:/ end=/\_$\n/ contains=oneilParameterPreamble,oneilAssignment,oneilUnit,oneilID,oneilParameterKeys
 
 "----------------------------------------------------------------/
 "  Design
@@ -62,7 +62,8 @@ syn region oneilDesignValue start=/\_^\v(\s\+)@!(\n)@!(#)@!(\s\+)@!(use)@!(impor
 "----------------------------------------------------------------/
 syn keyword oneilTestKeys test contained
 syn match oneilArgumentKeys /[{}]/ contained
-syn match oneilTestPreamble /\_^\*\{0,2}\s*\w[^\n]*:/me=e-1 contained contains=oneilTestKeys,oneilArgumentKeys,oneilBreakpoint
+syn match oneilTestName /"[^"]*"/ contained
+syn match oneilTestPreamble /\_^\*\{0,2}\s*\w[^\n]*:/me=e-1 contained contains=oneilTestKeys,oneilTestName,oneilArgumentKeys,oneilBreakpoint
 syn match oneilTestDelimiters /[:]/ contained
 syn region oneilTestExpression start=/:/ end=/\_$/ contained contains=oneilSubmodule
 syn region oneilTest start=/\_^\(\*\{1,2}\s*\)\{0,1}test/ end=/\_$/ contains=oneilTestPreamble,oneilTestDelimiters,oneilTestExpression
@@ -72,14 +73,16 @@ syn region oneilTest start=/\_^\(\*\{1,2}\s*\)\{0,1}test/ end=/\_$/ contains=one
 "  Includes
 "----------------------------------------------------------------/
 
-syn keyword oneilIncludeKeyword use as contained import
+syn keyword oneilIncludeKeyword use as contained import include
 syn match oneilModule /\w/ contained
 syn match pythonModule /\w/ contained
+syn match oneilFragment /".*"/ contained
 
 " Include Regions
 syn region oneilIncludeLine start=/\_^use/ end=/\_$/ transparent contains=oneilIncludeKeyword,oneilModule
 syn region oneilIncludeLine start=/\_^import/ end=/\_$/ transparent contains=oneilIncludeKeyword,pythonModule
 syn region oneilIncludeLine start=/\_^from/ end=/\_$/ transparent contains=oneilIncludeKeyword,oneilModule
+syn region oneilIncludeLine start=/\_^include/ end=/\_$/ transparent contains=oneilIncludeKeyword,oneilFragment
 
 
 "----------------------------------------------------------------/
@@ -98,6 +101,13 @@ set foldmethod=syntax
 syn keyword oneilSectionKeyword section contained
 syn region oneilSectionHeader start=/\_^section/ end=/\_$/ transparent contains=oneilSectionKeyword
 
+"----------------------------------------------------------------/
+"  Annotations
+"----------------------------------------------------------------/
+syn match oneilAnnotationName /\_^@\w\+/ contained
+syn match oneilAnnotationMessage /".*"/ contained
+syn region oneilAnnotation start=/\_^@/ end=/\_$/ contains=oneilAnnotationName,oneilAnnotationMessage
+
 "----------------------------------------------------------------/
 "  Comments
 "----------------------------------------------------------------/
@@ -115,6 +125,7 @@ hi def link startEndMarker		Special
 hi def link oneilIncludeKeyword		Keyword
 hi def link oneilModule			Function
 hi def link pythonModule		Function
+hi def link oneilFragment		String
 
 "  Assignments
 hi def link oneilPerformance		Operator	
@@ -146,6 +157,10 @@ hi def link oneilDesignValue		Error
 hi def link oneilNote			Comment
 hi def link oneilNoteTodo		Todo
 
+"  Annotations
+hi def link oneilAnnotationName		PreProc
+hi def link oneilAnnotationMessage	String
+
 "  Comments
 hi def link oneilComment		SpecialComment
 
@@ -157,5 +172,6 @@ hi def link oneilSectionHeader		SpecialComment
 hi def link oneilTestKeys		Keyword
 hi def link oneilArgumentKeys		Keyword
 hi def link oneilTestPreamble		Preproc
+hi def link oneilTestName		String
 hi def link oneilTestDelimiters		Delimiter
 hi def link oneilTest			Error